@@ -14,6 +14,7 @@ use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::SessionStatus;
 use codex_protocol::protocol::TurnAbortReason;
 use paste::paste;
 use serde::Deserialize;
@@ -114,6 +115,12 @@ client_request_definitions! {
         params: InterruptConversationParams,
         response: InterruptConversationResponse,
     },
+    /// The conversation's configuration and usage, as the TUI's `/status`
+    /// shows it. Answered once the conversation's listener sees the reply.
+    GetConversationStatus {
+        params: GetConversationStatusParams,
+        response: GetConversationStatusResponse,
+    },
     AddConversationListener {
         params: AddConversationListenerParams,
         response: AddConversationSubscriptionResponse,
@@ -568,6 +575,18 @@ pub struct InterruptConversationResponse {
     pub abort_reason: TurnAbortReason,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConversationStatusParams {
+    pub conversation_id: ConversationId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConversationStatusResponse {
+    pub status: SessionStatus,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct SendUserMessageResponse {}
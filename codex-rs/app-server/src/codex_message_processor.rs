@@ -18,6 +18,8 @@ use codex_app_server_protocol::ExecOneOffCommandParams;
 use codex_app_server_protocol::ExecOneOffCommandResponse;
 use codex_app_server_protocol::FuzzyFileSearchParams;
 use codex_app_server_protocol::FuzzyFileSearchResponse;
+use codex_app_server_protocol::GetConversationStatusParams;
+use codex_app_server_protocol::GetConversationStatusResponse;
 use codex_app_server_protocol::GetUserAgentResponse;
 use codex_app_server_protocol::GetUserSavedConfigResponse;
 use codex_app_server_protocol::GitDiffToRemoteResponse;
@@ -80,6 +82,7 @@ use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::InputItem as CoreInputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::UserQuestionRequestEvent;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
 use codex_login::run_login_server;
@@ -129,6 +132,8 @@ pub(crate) struct CodexMessageProcessor {
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: Arc<Mutex<HashMap<ConversationId, Vec<RequestId>>>>,
+    // Status requests per conversation, answered when SessionStatusResponse arrives.
+    pending_status_requests: Arc<Mutex<HashMap<ConversationId, Vec<RequestId>>>>,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
 }
 
@@ -149,6 +154,7 @@ impl CodexMessageProcessor {
             conversation_listeners: HashMap::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
+            pending_status_requests: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -182,6 +188,9 @@ impl CodexMessageProcessor {
             ClientRequest::InterruptConversation { request_id, params } => {
                 self.interrupt_conversation(request_id, params).await;
             }
+            ClientRequest::GetConversationStatus { request_id, params } => {
+                self.get_conversation_status(request_id, params).await;
+            }
             ClientRequest::AddConversationListener { request_id, params } => {
                 self.add_conversation_listener(request_id, params).await;
             }
@@ -707,7 +716,7 @@ impl CodexMessageProcessor {
         let cursor_ref = cursor_obj.as_ref();
 
         let page = match RolloutRecorder::list_conversations(
-            &self.config.codex_home,
+            &self.config.state_home.path,
             page_size,
             cursor_ref,
             INTERACTIVE_SESSION_SOURCES,
@@ -837,7 +846,11 @@ impl CodexMessageProcessor {
 
         // Verify that the rollout path is in the sessions directory or else
         // a malicious client could specify an arbitrary path.
-        let rollout_folder = self.config.codex_home.join(codex_core::SESSIONS_SUBDIR);
+        let rollout_folder = self
+            .config
+            .state_home
+            .path
+            .join(codex_core::SESSIONS_SUBDIR);
         let canonical_rollout_path = tokio::fs::canonicalize(&rollout_path).await;
         let canonical_rollout_path = if let Ok(path) = canonical_rollout_path
             && path.starts_with(&rollout_folder)
@@ -940,7 +953,8 @@ impl CodexMessageProcessor {
         let result: std::io::Result<()> = async {
             let archive_folder = self
                 .config
-                .codex_home
+                .state_home
+                .path
                 .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
             tokio::fs::create_dir_all(&archive_folder).await?;
             tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
@@ -1088,6 +1102,35 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
+    async fn get_conversation_status(
+        &mut self,
+        request_id: RequestId,
+        params: GetConversationStatusParams,
+    ) {
+        let GetConversationStatusParams { conversation_id } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        {
+            let mut map = self.pending_status_requests.lock().await;
+            map.entry(conversation_id).or_default().push(request_id);
+        }
+
+        // The conversation's listener replies once the status arrives.
+        let _ = conversation.submit(Op::GetSessionStatus).await;
+    }
+
     async fn add_conversation_listener(
         &mut self,
         request_id: RequestId,
@@ -1114,6 +1157,7 @@ impl CodexMessageProcessor {
             .insert(subscription_id, cancel_tx);
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
+        let pending_status_requests = self.pending_status_requests.clone();
         tokio::spawn(async move {
             loop {
                 tokio::select! {
@@ -1154,7 +1198,7 @@ impl CodexMessageProcessor {
                         })
                         .await;
 
-                        apply_bespoke_event_handling(event.clone(), conversation_id, conversation.clone(), outgoing_for_task.clone(), pending_interrupts.clone()).await;
+                        apply_bespoke_event_handling(event.clone(), conversation_id, conversation.clone(), outgoing_for_task.clone(), pending_interrupts.clone(), pending_status_requests.clone()).await;
                     }
                 }
             }
@@ -1255,6 +1299,7 @@ async fn apply_bespoke_event_handling(
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: Arc<Mutex<HashMap<ConversationId, Vec<RequestId>>>>,
+    pending_status_requests: Arc<Mutex<HashMap<ConversationId, Vec<RequestId>>>>,
 ) {
     let Event { id: event_id, msg } = event;
     match msg {
@@ -1301,6 +1346,19 @@ async fn apply_bespoke_event_handling(
                 on_exec_approval_response(event_id, rx, conversation).await;
             });
         }
+        // Clients cannot answer the agent's questions yet; let it carry on
+        // without an answer.
+        EventMsg::UserQuestionRequest(UserQuestionRequestEvent { call_id, .. }) => {
+            if let Err(err) = conversation
+                .submit(Op::UserQuestionAnswer {
+                    call_id,
+                    answer: None,
+                })
+                .await
+            {
+                error!("failed to dismiss question: {err}");
+            }
+        }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
             let pending = {
@@ -1316,6 +1374,16 @@ async fn apply_bespoke_event_handling(
                 }
             }
         }
+        EventMsg::SessionStatusResponse(status) => {
+            let pending = {
+                let mut map = pending_status_requests.lock().await;
+                map.remove(&conversation_id).unwrap_or_default()
+            };
+            let response = GetConversationStatusResponse { status };
+            for rid in pending {
+                outgoing.send_response(rid, response.clone()).await;
+            }
+        }
 
         _ => {}
     }
@@ -14,6 +14,7 @@ use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
+use codex_core::codex::EventBuffer;
 use codex_core::config::Config;
 use codex_core::default_client::USER_AGENT_SUFFIX;
 use codex_core::default_client::get_codex_user_agent;
@@ -36,10 +37,12 @@ impl MessageProcessor {
     ) -> Self {
         let outgoing = Arc::new(outgoing);
         let auth_manager = AuthManager::shared(config.codex_home.clone(), false);
-        let conversation_manager = Arc::new(ConversationManager::new(
-            auth_manager.clone(),
-            SessionSource::VSCode,
-        ));
+        // Clients start conversations before subscribing to them, and may
+        // never subscribe at all.
+        let conversation_manager = Arc::new(
+            ConversationManager::new(auth_manager.clone(), SessionSource::VSCode)
+                .with_event_buffer(EventBuffer::Unbounded),
+        );
         let codex_message_processor = CodexMessageProcessor::new(
             auth_manager,
             conversation_manager,
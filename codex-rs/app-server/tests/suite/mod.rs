@@ -9,5 +9,6 @@ mod list_resume;
 mod login;
 mod send_message;
 mod set_default_model;
+mod status;
 mod user_agent;
 mod user_info;
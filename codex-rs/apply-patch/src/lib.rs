@@ -96,6 +96,27 @@ pub struct ApplyPatchArgs {
     pub workdir: Option<String>,
 }
 
+impl ApplyPatchArgs {
+    /// Existing files the patch reads when it is verified: those it updates or
+    /// deletes, resolved against `cwd` (and the `cd` target, if any).
+    pub fn files_read(&self, cwd: &Path) -> Vec<PathBuf> {
+        let effective_cwd = resolve_workdir(self.workdir.as_deref(), cwd);
+        self.hunks
+            .iter()
+            .filter(|hunk| !matches!(hunk, Hunk::AddFile { .. }))
+            .map(|hunk| hunk.resolve_path(&effective_cwd))
+            .collect()
+    }
+}
+
+fn resolve_workdir(workdir: Option<&str>, cwd: &Path) -> PathBuf {
+    match workdir.map(Path::new) {
+        Some(dir) if dir.is_absolute() => dir.to_path_buf(),
+        Some(dir) => cwd.join(dir),
+        None => cwd.to_path_buf(),
+    }
+}
+
 pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
     match argv {
         // Direct invocation: apply_patch <patch>
@@ -214,6 +235,17 @@ impl ApplyPatchAction {
 /// cwd must be an absolute path so that we can resolve relative paths in the
 /// patch.
 pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApplyPatchVerified {
+    maybe_parse_apply_patch_verified_with(argv, cwd, &mut |path| std::fs::read_to_string(path))
+}
+
+/// [`maybe_parse_apply_patch_verified`] that reads the files the patch updates
+/// or deletes through `read_file`, for files that do not live on the local
+/// filesystem. See [`ApplyPatchArgs::files_read`].
+pub fn maybe_parse_apply_patch_verified_with(
+    argv: &[String],
+    cwd: &Path,
+    read_file: &mut dyn FnMut(&Path) -> std::io::Result<String>,
+) -> MaybeApplyPatchVerified {
     // Detect a raw patch body passed directly as the command or as the body of a bash -lc
     // script. In these cases, report an explicit error rather than applying the patch.
     match argv {
@@ -240,17 +272,7 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
             hunks,
             workdir,
         }) => {
-            let effective_cwd = workdir
-                .as_ref()
-                .map(|dir| {
-                    let path = Path::new(dir);
-                    if path.is_absolute() {
-                        path.to_path_buf()
-                    } else {
-                        cwd.join(path)
-                    }
-                })
-                .unwrap_or_else(|| cwd.to_path_buf());
+            let effective_cwd = resolve_workdir(workdir.as_deref(), cwd);
             let mut changes = HashMap::new();
             for hunk in hunks {
                 let path = hunk.resolve_path(&effective_cwd);
@@ -259,7 +281,7 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                         changes.insert(path, ApplyPatchFileChange::Add { content: contents });
                     }
                     Hunk::DeleteFile { .. } => {
-                        let content = match std::fs::read_to_string(&path) {
+                        let content = match read_file(&path) {
                             Ok(content) => content,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(
@@ -278,7 +300,19 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                         let ApplyPatchFileUpdate {
                             unified_diff,
                             content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
+                        } = match read_file(&path)
+                            .map_err(|err| {
+                                ApplyPatchError::IoError(IoError {
+                                    context: format!(
+                                        "Failed to read file to update {}",
+                                        path.display()
+                                    ),
+                                    source: err,
+                                })
+                            })
+                            .and_then(|original| {
+                                unified_diff_from_contents(original, &path, &chunks, 1)
+                            }) {
                             Ok(diff) => diff,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
@@ -647,7 +681,16 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    apply_chunks_to_contents(original_contents, path, chunks)
+}
 
+/// Apply `chunks` to `original_contents`, the current contents of the file at
+/// `path` (used in error messages).
+fn apply_chunks_to_contents(
+    original_contents: String,
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
 
     // Drop the trailing empty element that results from the final newline so
@@ -813,12 +856,41 @@ pub fn unified_diff_from_chunks_with_context(
         original_contents,
         new_contents,
     } = derive_new_contents_from_chunks(path, chunks)?;
-    let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
+    Ok(unified_diff_between(
+        &original_contents,
+        new_contents,
+        context,
+    ))
+}
+
+fn unified_diff_from_contents(
+    original_contents: String,
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+    context: usize,
+) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
+    let AppliedPatch {
+        original_contents,
+        new_contents,
+    } = apply_chunks_to_contents(original_contents, path, chunks)?;
+    Ok(unified_diff_between(
+        &original_contents,
+        new_contents,
+        context,
+    ))
+}
+
+fn unified_diff_between(
+    original_contents: &str,
+    new_contents: String,
+    context: usize,
+) -> ApplyPatchFileUpdate {
+    let text_diff = TextDiff::from_lines(original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
-    Ok(ApplyPatchFileUpdate {
+    ApplyPatchFileUpdate {
         unified_diff,
         content: new_contents,
-    })
+    }
 }
 
 /// Print the summary of changes in git-style format.
@@ -1603,6 +1675,56 @@ g
         );
     }
 
+    #[test]
+    fn test_apply_patch_verified_with_custom_reader() {
+        let cwd = Path::new("/srv/app");
+        let argv = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            r#"cd src && apply_patch <<'EOF'
+*** Begin Patch
+*** Add File: new.txt
++new
+*** Update File: main.txt
+@@
+-before
++after
+*** End Patch
+EOF"#
+                .to_string(),
+        ];
+        let MaybeApplyPatch::Body(args) = maybe_parse_apply_patch(&argv) else {
+            panic!("expected apply_patch body");
+        };
+        let main_path = PathBuf::from("/srv/app/src/main.txt");
+        assert_eq!(args.files_read(cwd), vec![main_path.clone()]);
+
+        let mut reads = Vec::new();
+        let result = maybe_parse_apply_patch_verified_with(&argv, cwd, &mut |path| {
+            reads.push(path.to_path_buf());
+            Ok("before\n".to_string())
+        });
+
+        assert_eq!(reads, vec![main_path.clone()]);
+        let MaybeApplyPatchVerified::Body(action) = result else {
+            panic!("expected verified patch, got {result:?}");
+        };
+        assert_eq!(
+            action.changes().get(&main_path),
+            Some(&ApplyPatchFileChange::Update {
+                unified_diff: "@@ -1 +1 @@\n-before\n+after\n".to_string(),
+                move_path: None,
+                new_content: "after\n".to_string(),
+            })
+        );
+        assert_eq!(
+            action.changes().get(Path::new("/srv/app/src/new.txt")),
+            Some(&ApplyPatchFileChange::Add {
+                content: "new\n".to_string()
+            })
+        );
+    }
+
     #[test]
     fn test_apply_patch_fails_on_write_error() {
         let dir = tempdir().unwrap();
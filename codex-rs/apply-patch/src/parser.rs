@@ -87,6 +87,17 @@ impl Hunk {
 
 use Hunk::*;
 
+/// Patches always separate path components with `/`. On Windows, switch to
+/// the native separator so resolved paths do not mix `\` and `/` and compare
+/// equal to paths reported by the rest of the system.
+fn patch_path(path: &str) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(path.replace('/', "\\"))
+    } else {
+        PathBuf::from(path)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct UpdateFileChunk {
     /// A single line of context used to narrow down the position of the chunk
@@ -260,7 +271,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
         }
         return Ok((
             AddFile {
-                path: PathBuf::from(path),
+                path: patch_path(path),
                 contents,
             },
             parsed_lines,
@@ -269,7 +280,7 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
         // Delete File
         return Ok((
             DeleteFile {
-                path: PathBuf::from(path),
+                path: patch_path(path),
             },
             1,
         ));
@@ -321,8 +332,8 @@ fn parse_one_hunk(lines: &[&str], line_number: usize) -> Result<(Hunk, usize), P
 
         return Ok((
             UpdateFile {
-                path: PathBuf::from(path),
-                move_path: move_path.map(PathBuf::from),
+                path: patch_path(path),
+                move_path: move_path.map(patch_path),
                 chunks,
             },
             parsed_lines,
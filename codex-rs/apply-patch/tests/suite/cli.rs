@@ -88,3 +88,26 @@ fn test_apply_patch_cli_stdin_add_and_update() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(windows)]
+#[test]
+fn test_apply_patch_cli_uses_native_separators_on_windows() -> anyhow::Result<()> {
+    let tmp = tempdir()?;
+    let add_patch = r#"*** Begin Patch
+*** Add File: nested/dir/file.txt
++hello
+*** End Patch"#;
+    Command::cargo_bin("apply_patch")
+        .expect("should find apply_patch binary")
+        .arg(add_patch)
+        .current_dir(tmp.path())
+        .assert()
+        .success()
+        .stdout("Success. Updated the following files:\nA nested\\dir\\file.txt\n");
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("nested").join("dir").join("file.txt"))?,
+        "hello\n"
+    );
+
+    Ok(())
+}
@@ -92,7 +92,7 @@ where
     // Regular invocation – create a Tokio runtime and execute the provided
     // async entry-point.
     let runtime = tokio::runtime::Runtime::new()?;
-    runtime.block_on(async move {
+    let result = runtime.block_on(async move {
         let codex_linux_sandbox_exe: Option<PathBuf> = if cfg!(target_os = "linux") {
             std::env::current_exe().ok()
         } else {
@@ -100,7 +100,12 @@ where
         };
 
         main_fn(codex_linux_sandbox_exe).await
-    })
+    });
+
+    // State kept in place of a read-only state home does not outlive the
+    // process.
+    codex_core::state_home::remove_fallback_dir();
+    result
 }
 
 const ILLEGAL_ENV_VAR_PREFIX: &str = "CODEX_";
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_core::provenance::PROVENANCE_FILE;
+use codex_core::provenance::ProvenanceLog;
+use codex_core::provenance::find_provenance_root;
+use codex_core::provenance::relative_key;
+
+/// Show which lines of a file were written by the agent, and in which
+/// session, from the `.codex-provenance.json` recorded with
+/// `record_provenance = true`.
+#[derive(Debug, clap::Parser)]
+pub struct BlameCli {
+    /// File to annotate.
+    pub file: PathBuf,
+}
+
+/// Characters of the session id to show, like an abbreviated commit hash.
+const SESSION_ID_LEN: usize = 8;
+
+impl BlameCli {
+    #[allow(clippy::print_stdout)]
+    pub fn run(self) -> Result<()> {
+        let cwd = std::env::current_dir().context("failed to resolve the current directory")?;
+        let file = cwd.join(&self.file);
+        let contents = std::fs::read_to_string(&file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        let Some(root) = file.parent().and_then(find_provenance_root) else {
+            bail!(
+                "no {PROVENANCE_FILE} found above {}; enable `record_provenance` to record one",
+                file.display()
+            );
+        };
+        let log = ProvenanceLog::load(&root)
+            .with_context(|| format!("failed to read {}", root.join(PROVENANCE_FILE).display()))?;
+        let Some(key) = relative_key(&root, &file) else {
+            bail!("{} is outside {}", file.display(), root.display());
+        };
+
+        let blame = log.blame(&key, &contents);
+        let model_width = blame
+            .iter()
+            .flatten()
+            .map(|record| record.model.len())
+            .max()
+            .unwrap_or(0);
+        let number_width = blame.len().to_string().len();
+        for (index, (line, record)) in contents.lines().zip(&blame).enumerate() {
+            let annotation = match record {
+                Some(record) => {
+                    let session: String = record.session_id.chars().take(SESSION_ID_LEN).collect();
+                    let date = record.timestamp.get(..10).unwrap_or(&record.timestamp);
+                    format!(
+                        "{session:<SESSION_ID_LEN$}  {:<model_width$}  {date:<10}",
+                        record.model
+                    )
+                }
+                None => " ".repeat(SESSION_ID_LEN + 2 + model_width + 2 + 10),
+            };
+            println!("{annotation} {:>number_width$}) {line}", index + 1);
+        }
+        Ok(())
+    }
+}
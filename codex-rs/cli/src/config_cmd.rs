@@ -0,0 +1,145 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use codex_core::config::ConfigScope;
+use codex_core::config::find_codex_home;
+use codex_core::config::get_config_value;
+use codex_core::config::load_config_as_toml_with_cli_overrides;
+use codex_core::config::set_config_value;
+
+/// Read or change single keys of `~/.codex/config.toml` from scripts.
+///
+/// Subcommands:
+/// - `get` — print the value of a key
+/// - `set` — set a key, keeping the rest of the file as it is
+/// - `show` — print a profile with everything it inherits filled in
+#[derive(Debug, clap::Parser)]
+pub struct ConfigCli {
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigSubcommand {
+    /// Print the value of a config key; exits with an error when it is not set.
+    Get(GetArgs),
+
+    /// Set a config key. Values that parse as TOML (`true`, `3`, `["a"]`) are
+    /// stored as such, anything else as a string.
+    Set(SetArgs),
+
+    /// Print a profile as it applies, with the settings it inherits through
+    /// `extends` filled in.
+    Show(ShowArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct ScopeArgs {
+    /// Read or write the key under `[profiles.<PROFILE>]`.
+    #[arg(long, value_name = "PROFILE", conflicts_with = "project")]
+    pub profile: Option<String>,
+
+    /// Read or write the key under `[projects."<PATH>"]`; defaults to the
+    /// current directory.
+    #[arg(long, value_name = "PATH")]
+    pub project: Option<Option<PathBuf>>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct GetArgs {
+    /// Dotted key, e.g. `model` or `tui.notifications`.
+    pub key: String,
+
+    #[command(flatten)]
+    pub scope: ScopeArgs,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct SetArgs {
+    /// Dotted key, e.g. `model` or `tui.notifications`.
+    pub key: String,
+
+    /// New value.
+    #[arg(allow_hyphen_values = true)]
+    pub value: String,
+
+    #[command(flatten)]
+    pub scope: ScopeArgs,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Profile to show; defaults to the `profile` selected in config.toml.
+    #[arg(long, value_name = "PROFILE")]
+    pub profile: Option<String>,
+}
+
+impl ConfigCli {
+    pub async fn run(self) -> Result<()> {
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+
+        match self.subcommand {
+            ConfigSubcommand::Get(GetArgs { key, scope }) => {
+                let project = scope.project_path()?;
+                match get_config_value(&codex_home, scope.resolve(project.as_deref()), &key)? {
+                    Some(value) => println!("{}", value.trim_end()),
+                    None => bail!("`{key}` is not set"),
+                }
+            }
+            ConfigSubcommand::Set(SetArgs { key, value, scope }) => {
+                let project = scope.project_path()?;
+                set_config_value(&codex_home, scope.resolve(project.as_deref()), &key, &value)
+                    .with_context(|| {
+                        format!(
+                            "failed to update {}",
+                            codex_home.join("config.toml").display()
+                        )
+                    })?;
+            }
+            ConfigSubcommand::Show(ShowArgs { profile }) => {
+                let config_toml = load_config_as_toml_with_cli_overrides(&codex_home, Vec::new())
+                    .await
+                    .context("failed to load config.toml")?;
+                let Some(name) = profile.or_else(|| config_toml.profile.clone()) else {
+                    bail!("no profile is selected in config.toml; pass --profile <PROFILE>");
+                };
+                let chain = config_toml.profile_chain(&name)?;
+                let resolved = config_toml.resolve_profile(&name)?;
+                match chain.split_first() {
+                    Some((_, [])) | None => println!("# profile {name}"),
+                    Some((_, bases)) => {
+                        println!("# profile {name} (extends {})", bases.join(" -> "));
+                    }
+                }
+                print!("{}", toml::to_string(&resolved)?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ScopeArgs {
+    /// The `--project` path resolved against the current directory, as
+    /// project tables are keyed by absolute paths.
+    fn project_path(&self) -> Result<Option<PathBuf>> {
+        let Some(project) = &self.project else {
+            return Ok(None);
+        };
+        let cwd = std::env::current_dir().context("failed to resolve the current directory")?;
+        Ok(Some(match project {
+            Some(path) => cwd.join(path),
+            None => cwd,
+        }))
+    }
+
+    fn resolve<'a>(&'a self, project: Option<&'a std::path::Path>) -> ConfigScope<'a> {
+        match (self.profile.as_deref(), project) {
+            (Some(profile), _) => ConfigScope::Profile(profile),
+            (None, Some(project)) => ConfigScope::Project(project),
+            (None, None) => ConfigScope::Global,
+        }
+    }
+}
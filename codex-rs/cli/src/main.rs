@@ -15,7 +15,14 @@ use codex_cli::login::run_login_with_device_code;
 use codex_cli::login::run_logout;
 use codex_cloud_tasks::Cli as CloudTasksCli;
 use codex_common::CliConfigOverrides;
+use codex_core::bump::BumpOptions;
+use codex_core::bump::Ecosystem;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
 use codex_exec::Cli as ExecCli;
+use codex_exec::audit::AuditCli;
+use codex_exec::changelog::ChangelogCli;
+use codex_exec::review::ReviewCli;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
 use codex_tui::Cli as TuiCli;
@@ -23,9 +30,15 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod blame_cmd;
+mod config_cmd;
 mod mcp_cmd;
+mod share_cmd;
 
+use crate::blame_cmd::BlameCli;
+use crate::config_cmd::ConfigCli;
 use crate::mcp_cmd::McpCli;
+use crate::share_cmd::ShareCli;
 
 /// Codex CLI
 ///
@@ -70,6 +83,12 @@ enum Subcommand {
     /// [experimental] Run the Codex MCP server (stdio transport).
     McpServer,
 
+    /// Read or set keys in config.toml, e.g. from setup scripts.
+    Config(ConfigCli),
+
+    /// Show which lines of a file the agent wrote, and in which session.
+    Blame(BlameCli),
+
     /// [experimental] Run the app server.
     AppServer,
 
@@ -77,9 +96,11 @@ enum Subcommand {
     Completion(CompletionCommand),
 
     /// Run commands within a Codex-provided sandbox.
-    #[clap(visible_alias = "debug")]
     Sandbox(SandboxArgs),
 
+    /// Debugging tools: the sandbox commands and the status line's data.
+    Debug(DebugArgs),
+
     /// Apply the latest diff produced by Codex agent as a `git apply` to your local working tree.
     #[clap(visible_alias = "a")]
     Apply(ApplyCommand),
@@ -87,6 +108,36 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Play back a recorded session in the TUI with its original timing.
+    Replay(ReplayCommand),
+
+    /// Serve a live, read-only view of a session over HTTP, e.g. `--bind 0.0.0.0 --port 8765`.
+    Share(ShareCli),
+
+    /// Walk the agent through the steps of a runbook file, pausing for confirmation between them.
+    Runbook(RunbookCommand),
+
+    /// Start a session that fixes the latest failed GitHub Actions run on the current branch.
+    FixCi,
+
+    /// Write release notes for a range of commits, e.g. `--from v1.2.0 --to HEAD`.
+    Changelog(ChangelogCli),
+
+    /// Run a security review of the current changes or given paths, e.g. `--rules owasp,secrets --format sarif`.
+    AuditCode(AuditCli),
+
+    /// Review the current changes, a branch or a commit without the TUI, e.g. `--base main --format sarif`.
+    Review(ReviewCli),
+
+    /// Start a session that resolves the merge conflicts in the current repository, one file at a time.
+    Resolve,
+
+    /// Start a session that plans an interactive rebase of the current branch and runs it once approved.
+    Rebase(RebaseCommand),
+
+    /// Start a session that upgrades outdated dependencies one at a time, committing each once the verify command passes.
+    Bump(BumpCommand),
+
     /// Internal: generate TypeScript protocol bindings.
     #[clap(hide = true)]
     GenerateTs(GenerateTsCommand),
@@ -121,6 +172,78 @@ struct ResumeCommand {
     config_overrides: TuiCli,
 }
 
+#[derive(Debug, Parser)]
+struct ReplayCommand {
+    /// Conversation/session id (UUID) of the recorded session to play back.
+    #[arg(value_name = "SESSION_ID")]
+    session_id: String,
+
+    /// Playback speed multiplier, e.g. `4x` or `0.5`.
+    #[arg(long = "speed", value_name = "SPEED", default_value = "1x", value_parser = parse_replay_speed)]
+    speed: f64,
+
+    /// Also write the replay to an asciicast v2 file, for `asciinema play` or the web player.
+    #[arg(long = "record", value_name = "FILE")]
+    record: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct RebaseCommand {
+    /// Branch or commit the current branch started from; defaults to its upstream, or the default branch.
+    #[arg(long, value_name = "REV")]
+    onto: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+struct BumpCommand {
+    /// Package managers to check, comma separated; defaults to all with a manifest in the current directory.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    ecosystem: Vec<EcosystemArg>,
+
+    /// Upgrade only these dependencies, comma separated.
+    #[arg(long, value_name = "NAME", value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// Command that must pass after each upgrade, e.g. `cargo test --all-features`.
+    #[arg(long, value_name = "COMMAND")]
+    verify: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum EcosystemArg {
+    Cargo,
+    Npm,
+    Pip,
+}
+
+impl From<EcosystemArg> for Ecosystem {
+    fn from(value: EcosystemArg) -> Self {
+        match value {
+            EcosystemArg::Cargo => Ecosystem::Cargo,
+            EcosystemArg::Npm => Ecosystem::Npm,
+            EcosystemArg::Pip => Ecosystem::Pip,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+struct RunbookCommand {
+    /// Path to the runbook YAML file.
+    #[arg(value_name = "FILE")]
+    path: PathBuf,
+}
+
+/// Parse a `--speed` value such as `4x`, `4` or `0.5x`.
+fn parse_replay_speed(value: &str) -> Result<f64, String> {
+    let number = value.strip_suffix(['x', 'X']).unwrap_or(value);
+    match number.trim().parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!(
+            "invalid speed `{value}`; expected a positive multiplier such as 2x or 0.5"
+        )),
+    }
+}
+
 #[derive(Debug, Parser)]
 struct SandboxArgs {
     #[command(subcommand)]
@@ -138,6 +261,28 @@ enum SandboxCommand {
     Linux(LandlockCommand),
 }
 
+#[derive(Debug, Parser)]
+struct DebugArgs {
+    #[command(subcommand)]
+    cmd: DebugCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum DebugCommand {
+    #[command(flatten)]
+    Sandbox(SandboxCommand),
+
+    /// Print the data the status line would start with as JSON, for bug
+    /// reports about how it renders.
+    Statusline(StatuslineCommand),
+}
+
+#[derive(Debug, Parser)]
+struct StatuslineCommand {
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
+}
+
 #[derive(Debug, Parser)]
 struct LoginCommand {
     #[clap(skip)]
@@ -224,6 +369,43 @@ fn format_exit_messages(exit_info: AppExitInfo, color_enabled: bool) -> Vec<Stri
     lines
 }
 
+async fn run_sandbox_command(
+    cmd: SandboxCommand,
+    root_config_overrides: CliConfigOverrides,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    match cmd {
+        SandboxCommand::Macos(mut seatbelt_cli) => {
+            prepend_config_flags(&mut seatbelt_cli.config_overrides, root_config_overrides);
+            codex_cli::debug_sandbox::run_command_under_seatbelt(
+                seatbelt_cli,
+                codex_linux_sandbox_exe,
+            )
+            .await
+        }
+        SandboxCommand::Linux(mut landlock_cli) => {
+            prepend_config_flags(&mut landlock_cli.config_overrides, root_config_overrides);
+            codex_cli::debug_sandbox::run_command_under_landlock(
+                landlock_cli,
+                codex_linux_sandbox_exe,
+            )
+            .await
+        }
+    }
+}
+
+async fn print_status_line(config_overrides: CliConfigOverrides) -> anyhow::Result<()> {
+    let config = Config::load_with_cli_overrides(
+        config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?,
+        ConfigOverrides::default(),
+    )
+    .await?;
+    println!("{}", codex_tui::dump_status_line(&config).await?);
+    Ok(())
+}
+
 fn print_exit_messages(exit_info: AppExitInfo) {
     let color_enabled = supports_color::on(Stream::Stdout).is_some();
     for line in format_exit_messages(exit_info, color_enabled) {
@@ -277,6 +459,15 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
             mcp_cli.run().await?;
         }
+        Some(Subcommand::Config(config_cli)) => {
+            config_cli.run().await?;
+        }
+        Some(Subcommand::Blame(blame_cli)) => {
+            blame_cli.run()?;
+        }
+        Some(Subcommand::Share(share_cli)) => {
+            share_cli.run().await?;
+        }
         Some(Subcommand::AppServer) => {
             codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
@@ -295,6 +486,96 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
             print_exit_messages(exit_info);
         }
+        Some(Subcommand::Replay(ReplayCommand {
+            session_id,
+            speed,
+            record,
+        })) => {
+            interactive.replay_session_id = Some(session_id);
+            interactive.replay_speed = Some(speed);
+            interactive.record_path = record;
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            print_exit_messages(exit_info);
+        }
+        Some(Subcommand::Runbook(RunbookCommand { path })) => {
+            interactive.runbook = Some(path);
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            print_exit_messages(exit_info);
+        }
+        Some(Subcommand::FixCi) => {
+            interactive.fix_ci = true;
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            print_exit_messages(exit_info);
+        }
+        Some(Subcommand::Changelog(mut changelog_cli)) => {
+            prepend_config_flags(
+                &mut changelog_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_exec::changelog::run_changelog(changelog_cli, codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::AuditCode(mut audit_cli)) => {
+            prepend_config_flags(
+                &mut audit_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_exec::audit::run_audit(audit_cli, codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Review(mut review_cli)) => {
+            prepend_config_flags(
+                &mut review_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            codex_exec::review::run_review(review_cli, codex_linux_sandbox_exe).await?;
+        }
+        Some(Subcommand::Resolve) => {
+            interactive.resolve = true;
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            print_exit_messages(exit_info);
+        }
+        Some(Subcommand::Rebase(RebaseCommand { onto })) => {
+            interactive.rebase = true;
+            interactive.rebase_onto = onto;
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            print_exit_messages(exit_info);
+        }
+        Some(Subcommand::Bump(BumpCommand {
+            ecosystem,
+            only,
+            verify,
+        })) => {
+            interactive.bump = Some(BumpOptions {
+                ecosystems: ecosystem.into_iter().map(Into::into).collect(),
+                only,
+                verify,
+            });
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            let exit_info = codex_tui::run_main(interactive, codex_linux_sandbox_exe).await?;
+            print_exit_messages(exit_info);
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
@@ -343,28 +624,29 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_cloud_tasks::run_main(cloud_cli, codex_linux_sandbox_exe).await?;
         }
-        Some(Subcommand::Sandbox(sandbox_args)) => match sandbox_args.cmd {
-            SandboxCommand::Macos(mut seatbelt_cli) => {
-                prepend_config_flags(
-                    &mut seatbelt_cli.config_overrides,
+        Some(Subcommand::Sandbox(sandbox_args)) => {
+            run_sandbox_command(
+                sandbox_args.cmd,
+                root_config_overrides.clone(),
+                codex_linux_sandbox_exe,
+            )
+            .await?;
+        }
+        Some(Subcommand::Debug(debug_args)) => match debug_args.cmd {
+            DebugCommand::Sandbox(sandbox_cmd) => {
+                run_sandbox_command(
+                    sandbox_cmd,
                     root_config_overrides.clone(),
-                );
-                codex_cli::debug_sandbox::run_command_under_seatbelt(
-                    seatbelt_cli,
                     codex_linux_sandbox_exe,
                 )
                 .await?;
             }
-            SandboxCommand::Linux(mut landlock_cli) => {
+            DebugCommand::Statusline(mut statusline_cli) => {
                 prepend_config_flags(
-                    &mut landlock_cli.config_overrides,
+                    &mut statusline_cli.config_overrides,
                     root_config_overrides.clone(),
                 );
-                codex_cli::debug_sandbox::run_command_under_landlock(
-                    landlock_cli,
-                    codex_linux_sandbox_exe,
-                )
-                .await?;
+                print_status_line(statusline_cli.config_overrides).await?;
             }
         },
         Some(Subcommand::Apply(mut apply_cli)) => {
@@ -650,4 +932,67 @@ mod tests {
         assert!(!interactive.resume_last);
         assert_eq!(interactive.resume_session_id, None);
     }
+
+    #[test]
+    fn replay_parses_speed_multipliers() {
+        let cli = MultitoolCli::try_parse_from(["codex", "replay", "sid", "--speed", "4x"])
+            .expect("parse");
+        assert_matches!(
+            cli.subcommand,
+            Some(Subcommand::Replay(ReplayCommand { ref session_id, speed, record: None }))
+                if session_id == "sid" && speed == 4.0
+        );
+
+        assert_eq!(parse_replay_speed("0.5"), Ok(0.5));
+        assert!(parse_replay_speed("0x").is_err());
+        assert!(parse_replay_speed("fast").is_err());
+    }
+
+    #[test]
+    fn replay_parses_record_path() {
+        let cli = MultitoolCli::try_parse_from(["codex", "replay", "sid", "--record", "demo.cast"])
+            .expect("parse");
+        assert_matches!(
+            cli.subcommand,
+            Some(Subcommand::Replay(ReplayCommand { record: Some(ref path), .. }))
+                if path == &PathBuf::from("demo.cast")
+        );
+    }
+
+    #[test]
+    fn debug_has_the_sandbox_commands_and_statusline() {
+        let cli = MultitoolCli::try_parse_from(["codex", "debug", "statusline"]).expect("parse");
+        assert_matches!(
+            cli.subcommand,
+            Some(Subcommand::Debug(DebugArgs {
+                cmd: DebugCommand::Statusline(_)
+            }))
+        );
+
+        let cli =
+            MultitoolCli::try_parse_from(["codex", "debug", "landlock", "true"]).expect("parse");
+        assert_matches!(
+            cli.subcommand,
+            Some(Subcommand::Debug(DebugArgs {
+                cmd: DebugCommand::Sandbox(SandboxCommand::Linux(_))
+            }))
+        );
+
+        assert!(MultitoolCli::try_parse_from(["codex", "sandbox", "statusline"]).is_err());
+    }
+
+    #[test]
+    fn share_parses_bind_and_port() {
+        let cli =
+            MultitoolCli::try_parse_from(["codex", "share", "--bind", "0.0.0.0", "--port", "9000"])
+                .expect("parse");
+        assert_matches!(
+            cli.subcommand,
+            Some(Subcommand::Share(ShareCli {
+                session_id: None,
+                bind,
+                port: 9000,
+            })) if bind == std::net::IpAddr::from([0, 0, 0, 0])
+        );
+    }
 }
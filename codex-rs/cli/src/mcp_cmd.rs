@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::path::Path;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -10,11 +12,12 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_global_mcp_servers;
-use codex_core::config::write_global_mcp_servers;
+use codex_core::config::update_global_mcp_servers;
 use codex_core::config_types::McpServerConfig;
 use codex_core::config_types::McpServerTransportConfig;
 use codex_core::mcp::auth::compute_auth_statuses;
 use codex_core::protocol::McpAuthStatus;
+use codex_core::state_home::is_writable;
 use codex_rmcp_client::delete_oauth_tokens;
 use codex_rmcp_client::perform_oauth_login;
 
@@ -199,9 +202,6 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
     validate_server_name(&name)?;
 
     let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
-    let mut servers = load_global_mcp_servers(&codex_home)
-        .await
-        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
 
     let transport = match transport_args {
         AddMcpTransportArgs {
@@ -241,12 +241,16 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
     };
 
-    servers.insert(name.clone(), new_entry);
-
-    write_global_mcp_servers(&codex_home, &servers)
-        .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
+    let ((), saved) = update_servers(&codex_home, |servers| {
+        servers.insert(name.clone(), new_entry);
+    })
+    .await?;
 
-    println!("Added global MCP server '{name}'.");
+    if saved {
+        println!("Added global MCP server '{name}'.");
+    } else {
+        println!("{}", not_saved_notice(&codex_home, &name));
+    }
 
     Ok(())
 }
@@ -259,18 +263,15 @@ async fn run_remove(config_overrides: &CliConfigOverrides, remove_args: RemoveAr
     validate_server_name(&name)?;
 
     let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
-    let mut servers = load_global_mcp_servers(&codex_home)
-        .await
-        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
-
-    let removed = servers.remove(&name).is_some();
-
-    if removed {
-        write_global_mcp_servers(&codex_home, &servers)
-            .with_context(|| format!("failed to write MCP servers to {}", codex_home.display()))?;
-    }
+    let (removed, saved) =
+        update_servers(&codex_home, |servers| servers.remove(&name).is_some()).await?;
 
-    if removed {
+    if removed && !saved {
+        println!(
+            "{} Disable it per run with `-c mcp_servers.{name}.enabled=false`.",
+            not_saved_notice(&codex_home, &name)
+        );
+    } else if removed {
         println!("Removed global MCP server '{name}'.");
     } else {
         println!("No MCP server named '{name}' found.");
@@ -279,6 +280,32 @@ async fn run_remove(config_overrides: &CliConfigOverrides, remove_args: RemoveAr
     Ok(())
 }
 
+/// Apply `update` to the global MCP servers and save them. When `CODEX_HOME`
+/// is read-only (a Nix-managed home, a locked-down CI runner) the change is
+/// applied to the registry in memory only and the returned flag is `false`.
+async fn update_servers<R>(
+    codex_home: &Path,
+    update: impl FnOnce(&mut BTreeMap<String, McpServerConfig>) -> R,
+) -> Result<(R, bool)> {
+    if is_writable(codex_home) {
+        let result = update_global_mcp_servers(codex_home, update)
+            .await
+            .with_context(|| format!("failed to update MCP servers in {}", codex_home.display()))?;
+        return Ok((result, true));
+    }
+    let mut servers = load_global_mcp_servers(codex_home)
+        .await
+        .with_context(|| format!("failed to load MCP servers from {}", codex_home.display()))?;
+    Ok((update(&mut servers), false))
+}
+
+fn not_saved_notice(codex_home: &Path, name: &str) -> String {
+    format!(
+        "{} is read-only, so the change to MCP server '{name}' was not saved.",
+        codex_home.display()
+    )
+}
+
 async fn run_login(config_overrides: &CliConfigOverrides, login_args: LoginArgs) -> Result<()> {
     let overrides = config_overrides.parse_overrides().map_err(|e| anyhow!(e))?;
     let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
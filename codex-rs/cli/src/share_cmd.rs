@@ -0,0 +1,375 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_core::INTERACTIVE_SESSION_SOURCES;
+use codex_core::RolloutRecorder;
+use codex_core::config::find_codex_home;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::state_home::preferred_dir;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::InputMessageKind;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use rand::Rng;
+use serde_json::json;
+use tiny_http::Header;
+use tiny_http::Method;
+use tiny_http::Request;
+use tiny_http::Response;
+use tiny_http::Server;
+
+/// Serve a live, read-only view of a session, for a teammate to follow an
+/// agent run during pairing. The page polls the session's rollout file, so
+/// it keeps up with the running TUI. The view lives under a random token in
+/// the URL, so only people given the link can open it.
+#[derive(Debug, clap::Parser)]
+pub struct ShareCli {
+    /// Session to share; defaults to the most recent interactive session.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: Option<String>,
+
+    /// Address to listen on; use 0.0.0.0 to share on the local network.
+    #[arg(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    pub bind: IpAddr,
+
+    /// Port to serve on.
+    #[arg(long, default_value_t = 8765)]
+    pub port: u16,
+}
+
+const PAGE: &str = include_str!("share_page.html");
+
+/// One entry of the shared transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    /// `user`, `agent`, `command` or `output`.
+    kind: &'static str,
+    text: String,
+}
+
+impl ShareCli {
+    #[allow(clippy::print_stdout)]
+    pub async fn run(self) -> Result<()> {
+        let codex_home = find_codex_home().context("failed to resolve CODEX_HOME")?;
+        let state_home = preferred_dir(&codex_home);
+        let rollout = match self.session_id.as_deref() {
+            Some(id) => find_conversation_path_by_id_str(&state_home, id)
+                .await?
+                .with_context(|| format!("no recorded session found with id {id}"))?,
+            None => RolloutRecorder::list_conversations(
+                &state_home,
+                1,
+                None,
+                INTERACTIVE_SESSION_SOURCES,
+            )
+            .await?
+            .items
+            .into_iter()
+            .next()
+            .map(|item| item.path)
+            .context("no recorded sessions to share")?,
+        };
+
+        let server = Server::http((self.bind, self.port))
+            .map_err(|err| anyhow!("failed to listen on {}:{}: {err}", self.bind, self.port))?;
+        let token = share_token();
+        let host = match self.bind {
+            IpAddr::V6(addr) => format!("[{addr}]"),
+            IpAddr::V4(addr) => addr.to_string(),
+        };
+        println!(
+            "Sharing {} read-only at http://{host}:{}/{token}/ (Ctrl-C to stop)",
+            rollout.display(),
+            self.port
+        );
+        tokio::task::spawn_blocking(move || {
+            let mut transcript = Transcript::default();
+            for request in server.incoming_requests() {
+                respond(request, &token, &rollout, &mut transcript);
+            }
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+/// 128 random bits, hex encoded, that a URL must start with to be served.
+fn share_token() -> String {
+    rand::rng()
+        .random::<[u8; 16]>()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn respond(request: Request, token: &str, rollout: &Path, transcript: &mut Transcript) {
+    if *request.method() != Method::Get {
+        let _ = request.respond(Response::from_string("read-only").with_status_code(405));
+        return;
+    }
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+    let path = path
+        .strip_prefix('/')
+        .and_then(|path| path.strip_prefix(token));
+    let response = match path {
+        Some("/") => with_content_type(Response::from_string(PAGE), "text/html; charset=utf-8"),
+        Some("/entries") => {
+            let from = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("from="))
+                .and_then(|from| from.parse().ok())
+                .unwrap_or(0);
+            // An unreadable rollout serves what was read before.
+            let _ = transcript.update(rollout);
+            let body = json!({
+                "next": transcript.parsed,
+                "entries": transcript
+                    .since(from)
+                    .map(|entry| json!({ "kind": entry.kind, "text": entry.text }))
+                    .collect::<Vec<_>>(),
+            });
+            with_content_type(Response::from_string(body.to_string()), "application/json")
+        }
+        _ => Response::from_string("not found").with_status_code(404),
+    };
+    let _ = request.respond(response);
+}
+
+fn with_content_type(
+    response: Response<std::io::Cursor<Vec<u8>>>,
+    content_type: &str,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    match Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes()) {
+        Ok(header) => response.with_header(header),
+        Err(()) => response,
+    }
+}
+
+/// User and agent messages, shell commands and their output from a rollout,
+/// in order. Each poll only parses what was appended to the rollout since the
+/// previous one, and viewers ask for the entries past the byte offset they
+/// have seen.
+#[derive(Debug, Default)]
+struct Transcript {
+    /// Bytes of the rollout parsed so far; always at the end of a line.
+    parsed: u64,
+    /// Entries with the offset just past the rollout line they came from.
+    entries: Vec<(u64, Entry)>,
+    /// Shell calls whose output has not been seen yet.
+    shell_calls: HashSet<String>,
+}
+
+impl Transcript {
+    fn update(&mut self, rollout: &Path) -> std::io::Result<()> {
+        let mut file = File::open(rollout)?;
+        if file.metadata()?.len() < self.parsed {
+            // Rewritten rather than appended to: start over.
+            *self = Self::default();
+        }
+        file.seek(SeekFrom::Start(self.parsed))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        self.push(&appended);
+        Ok(())
+    }
+
+    /// Parse the complete lines of `appended`, the bytes following
+    /// `parsed`. A final line without a newline is still being written and is
+    /// left for the next update; lines that do not parse are skipped.
+    fn push(&mut self, appended: &[u8]) {
+        let complete = appended
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |newline| newline + 1);
+        for line in appended[..complete].split_inclusive(|byte| *byte == b'\n') {
+            self.parsed += line.len() as u64;
+            let Ok(line) = serde_json::from_slice::<RolloutLine>(line) else {
+                continue;
+            };
+            if let Some(entry) = self.entry(line.item) {
+                self.entries.push((self.parsed, entry));
+            }
+        }
+    }
+
+    fn entry(&mut self, item: RolloutItem) -> Option<Entry> {
+        match item {
+            RolloutItem::EventMsg(EventMsg::UserMessage(event))
+                if matches!(event.kind, None | Some(InputMessageKind::Plain)) =>
+            {
+                Some(Entry {
+                    kind: "user",
+                    text: event.message,
+                })
+            }
+            RolloutItem::EventMsg(EventMsg::AgentMessage(event)) => Some(Entry {
+                kind: "agent",
+                text: event.message,
+            }),
+            RolloutItem::ResponseItem(item) => {
+                if let Some((call_id, command)) = shell_call(&item) {
+                    self.shell_calls.insert(call_id);
+                    Some(Entry {
+                        kind: "command",
+                        text: command,
+                    })
+                } else if let ResponseItem::FunctionCallOutput { call_id, output } = item
+                    && self.shell_calls.remove(&call_id)
+                {
+                    Some(Entry {
+                        kind: "output",
+                        text: shell_output(&output.content),
+                    })
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Entries from rollout lines past byte offset `from`.
+    fn since(&self, from: u64) -> impl Iterator<Item = &Entry> {
+        let start = self.entries.partition_point(|(end, _)| *end <= from);
+        self.entries[start..].iter().map(|(_, entry)| entry)
+    }
+}
+
+/// The call id and command line of a shell tool call.
+fn shell_call(item: &ResponseItem) -> Option<(String, String)> {
+    let (call_id, command) = match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } if name == "shell" => {
+            let params: ShellToolCallParams = serde_json::from_str(arguments).ok()?;
+            (call_id.clone(), params.command)
+        }
+        ResponseItem::LocalShellCall {
+            call_id: Some(call_id),
+            action: LocalShellAction::Exec(action),
+            ..
+        } => (call_id.clone(), action.command.clone()),
+        _ => return None,
+    };
+    let command = match command.as_slice() {
+        [shell, flag, script] if shell.ends_with("sh") && flag == "-lc" => script.clone(),
+        _ => command.join(" "),
+    };
+    Some((call_id, command))
+}
+
+/// Shell outputs are JSON with the combined output and exit metadata;
+/// anything else is shown as-is.
+fn shell_output(content: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(content)
+        .ok()
+        .and_then(|value| value.get("output")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| content.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn line(item: RolloutItem) -> String {
+        let line = RolloutLine {
+            timestamp: "2025-01-01T00:00:00.000Z".to_string(),
+            elapsed_ms: None,
+            item,
+        };
+        serde_json::to_string(&line).expect("rollout line serializes")
+    }
+
+    fn user(message: &str, kind: Option<InputMessageKind>) -> String {
+        line(RolloutItem::EventMsg(EventMsg::UserMessage(
+            UserMessageEvent {
+                message: message.to_string(),
+                kind,
+                images: None,
+            },
+        )))
+    }
+
+    #[test]
+    fn entries_follow_messages_and_shell_calls() {
+        let lines = [
+            user(
+                "<environment_context/>",
+                Some(InputMessageKind::EnvironmentContext),
+            ),
+            user("run the tests", None),
+            line(RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: json!({ "command": ["bash", "-lc", "cargo test"] }).to_string(),
+                call_id: "call-1".to_string(),
+            })),
+            line(RolloutItem::ResponseItem(
+                ResponseItem::FunctionCallOutput {
+                    call_id: "call-1".to_string(),
+                    output: FunctionCallOutputPayload {
+                        content: json!({ "output": "ok", "metadata": { "exit_code": 0 } })
+                            .to_string(),
+                        success: Some(true),
+                    },
+                },
+            )),
+            line(RolloutItem::EventMsg(EventMsg::AgentMessage(
+                AgentMessageEvent {
+                    message: "All tests pass.".to_string(),
+                },
+            ))),
+        ];
+        let partial = "{\"timestamp\":\"2025-01-01T00:00:01";
+
+        let entry = |kind, text: &str| Entry {
+            kind,
+            text: text.to_string(),
+        };
+        let mut transcript = Transcript::default();
+        // The command and its output arrive in separate polls.
+        let first = format!("{}\n", lines[..3].join("\n"));
+        transcript.push(first.as_bytes());
+        let seen = transcript.parsed;
+        assert_eq!(seen, first.len() as u64);
+        assert_eq!(
+            transcript.since(0).cloned().collect::<Vec<_>>(),
+            vec![
+                entry("user", "run the tests"),
+                entry("command", "cargo test")
+            ]
+        );
+
+        transcript.push(format!("{}\n{partial}", lines[3..].join("\n")).as_bytes());
+        assert_eq!(
+            transcript.since(seen).cloned().collect::<Vec<_>>(),
+            vec![entry("output", "ok"), entry("agent", "All tests pass.")]
+        );
+        assert_eq!(transcript.since(transcript.parsed).count(), 0);
+        // The partial line is left for the next poll.
+        assert_eq!(
+            transcript.parsed,
+            (first.len() + lines[3..].join("\n").len() + 1) as u64
+        );
+    }
+}
@@ -210,3 +210,31 @@ async fn add_cant_add_command_and_url() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(unix)]
+#[tokio::test]
+async fn add_to_read_only_home_is_kept_in_memory() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let codex_home = TempDir::new()?;
+    std::fs::set_permissions(codex_home.path(), std::fs::Permissions::from_mode(0o555))?;
+    if codex_core::state_home::is_writable(codex_home.path()) {
+        // Running as root: permissions are not enforced.
+        return Ok(());
+    }
+
+    let mut add_cmd = codex_command(codex_home.path())?;
+    add_cmd
+        .args(["mcp", "add", "docs", "--", "echo", "hello"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "is read-only, so the change to MCP server 'docs' was not saved.",
+        ));
+
+    std::fs::set_permissions(codex_home.path(), std::fs::Permissions::from_mode(0o755))?;
+    let servers = load_global_mcp_servers(codex_home.path()).await?;
+    assert!(servers.is_empty());
+
+    Ok(())
+}
@@ -1,10 +1,12 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::protocol::AskForApproval;
 use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use crate::write_quota::patch_write_bytes;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
 use std::collections::HashMap;
@@ -40,6 +42,45 @@ pub(crate) async fn apply_patch(
     call_id: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    // Going over the session's write quota needs the user's say-so, so an
+    // unattended loop stops here. Nobody answers approvals under `never`, so
+    // the patch is refused outright there.
+    let bytes = patch_write_bytes(&action);
+    let approved_over_quota = match sess.write_quota_overrun(bytes).await {
+        Some(reason) if turn_context.approval_policy == AskForApproval::Never => {
+            return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+                format!(
+                    "patch rejected: it would exceed the session's write quota (max_session_write_mb) and approval_policy is never. {reason}"
+                ),
+            )));
+        }
+        Some(reason) => {
+            let rx_approve = sess
+                .request_patch_approval(
+                    sub_id.to_owned(),
+                    call_id.to_owned(),
+                    &action,
+                    Some(reason.clone()),
+                    None,
+                )
+                .await;
+            match rx_approve.await.unwrap_or_default() {
+                ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
+                    sess.extend_write_quota(bytes).await;
+                    true
+                }
+                ReviewDecision::Denied | ReviewDecision::Abort => {
+                    return InternalApplyPatchInvocation::Output(Err(
+                        FunctionCallError::RespondToModel(format!(
+                            "patch rejected: it would exceed the session's write quota (max_session_write_mb). {reason}"
+                        )),
+                    ));
+                }
+            }
+        }
+        None => false,
+    };
+
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
@@ -53,6 +94,13 @@ pub(crate) async fn apply_patch(
             action,
             user_explicitly_approved_this_action: user_explicitly_approved,
         }),
+        // The user already approved this patch when allowing it over the quota.
+        SafetyCheck::AskUser if approved_over_quota => {
+            InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                action,
+                user_explicitly_approved_this_action: true,
+            })
+        }
         SafetyCheck::AskUser => {
             // Compute a readable summary of path changes to include in the
             // approval request so the user can make an informed decision.
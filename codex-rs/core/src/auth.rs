@@ -16,6 +16,7 @@ use std::sync::Mutex;
 use std::time::Duration;
 
 use codex_app_server_protocol::AuthMode;
+use codex_protocol::protocol::StatusAccount;
 
 use crate::token_data::PlanType;
 use crate::token_data::TokenData;
@@ -285,6 +286,21 @@ pub fn try_read_auth_json(auth_file: &Path) -> std::io::Result<AuthDotJson> {
     Ok(auth_dot_json)
 }
 
+/// The account in `codex_home`'s auth.json, as reported by
+/// `Op::GetSessionStatus`; `None` when not logged in.
+pub fn status_account(codex_home: &Path) -> Option<StatusAccount> {
+    let auth = try_read_auth_json(&get_auth_file(codex_home)).ok()?;
+    if let Some(tokens) = auth.tokens {
+        return Some(StatusAccount::ChatGpt {
+            plan: tokens.id_token.get_chatgpt_plan_type(),
+            email: tokens.id_token.email,
+        });
+    }
+    auth.openai_api_key
+        .filter(|key| !key.is_empty())
+        .map(|_| StatusAccount::ApiKey)
+}
+
 pub fn write_auth_json(auth_file: &Path, auth_dot_json: &AuthDotJson) -> std::io::Result<()> {
     if let Some(parent) = auth_file.parent() {
         std::fs::create_dir_all(parent)?;
@@ -0,0 +1,499 @@
+//! Context for `codex bump`: the outdated direct dependencies of the
+//! repository (Cargo, npm and pip), and the prompt that has the agent
+//! upgrade them one at a time, read their changelogs, run the verify
+//! command, fix what breaks and commit each upgrade as a checkpoint.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::git_info::collect_git_info;
+use crate::git_info::git_worktree_dirty;
+
+/// Package managers may refresh their index before answering.
+const OUTDATED_COMMAND_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Pip,
+}
+
+impl Ecosystem {
+    pub const ALL: [Ecosystem; 3] = [Ecosystem::Cargo, Ecosystem::Npm, Ecosystem::Pip];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Pip => "pip",
+        }
+    }
+
+    /// Whether `cwd` has a manifest of this ecosystem.
+    fn detect(self, cwd: &Path) -> bool {
+        match self {
+            Ecosystem::Cargo => cwd.join("Cargo.toml").is_file(),
+            Ecosystem::Npm => cwd.join("package.json").is_file(),
+            Ecosystem::Pip => {
+                cwd.join("pyproject.toml").is_file() || cwd.join("requirements.txt").is_file()
+            }
+        }
+    }
+
+    fn default_verify_command(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo test",
+            Ecosystem::Npm => "npm test",
+            Ecosystem::Pip => "python3 -m pytest",
+        }
+    }
+
+    /// How to apply an upgrade and where to look for release notes.
+    fn instructions(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => {
+                "cargo: raise the version requirement in the Cargo.toml that declares the crate when the target is \
+                 outside of it, then run `cargo update -p <name>`. Find the repository with \
+                 `curl -s https://crates.io/api/v1/crates/<name>`."
+            }
+            Ecosystem::Npm => {
+                "npm: run `npm install <name>@<target>` (or `--save-dev` for dev dependencies). Find the repository \
+                 with `npm view <name> repository.url`."
+            }
+            Ecosystem::Pip => {
+                "pip: update the pin in requirements.txt or pyproject.toml, then run `python3 -m pip install \
+                 <name>==<target>`. Find the project links with `curl -s https://pypi.org/pypi/<name>/json`."
+            }
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BumpError {
+    #[error("not inside a git repository")]
+    NotARepository,
+
+    #[error(
+        "the working tree has uncommitted changes; commit or stash them so each upgrade can be checkpointed"
+    )]
+    DirtyWorktree,
+
+    #[error("no Cargo.toml, package.json, pyproject.toml or requirements.txt in this directory")]
+    NoManifest,
+
+    #[error("all dependencies are up to date")]
+    NothingOutdated,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutdatedDependency {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub current: String,
+    /// Latest release, which may be a new major version.
+    pub target: String,
+}
+
+/// What `codex bump` should upgrade.
+#[derive(Debug, Clone, Default)]
+pub struct BumpOptions {
+    /// Ecosystems to look at; all detected ones when empty.
+    pub ecosystems: Vec<Ecosystem>,
+    /// Dependency names to upgrade; all outdated ones when empty.
+    pub only: Vec<String>,
+    /// Command that must pass after each upgrade; defaults to the test
+    /// command of each ecosystem.
+    pub verify: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BumpContext {
+    /// `HEAD` before the first upgrade.
+    pub head: String,
+    pub dependencies: Vec<OutdatedDependency>,
+    pub verify: String,
+    /// Ecosystems that could not be checked, with the reason.
+    pub skipped: Vec<String>,
+}
+
+/// Outdated direct dependencies of the repository at `cwd`, filtered by
+/// `options`.
+pub async fn bump_context(cwd: &Path, options: &BumpOptions) -> Result<BumpContext, BumpError> {
+    let head = collect_git_info(cwd)
+        .await
+        .and_then(|info| info.commit_hash)
+        .ok_or(BumpError::NotARepository)?;
+    if git_worktree_dirty(cwd).await.unwrap_or(false) {
+        return Err(BumpError::DirtyWorktree);
+    }
+    let ecosystems: Vec<Ecosystem> = Ecosystem::ALL
+        .into_iter()
+        .filter(|ecosystem| options.ecosystems.is_empty() || options.ecosystems.contains(ecosystem))
+        .filter(|ecosystem| ecosystem.detect(cwd))
+        .collect();
+    if ecosystems.is_empty() {
+        return Err(BumpError::NoManifest);
+    }
+
+    let mut dependencies = Vec::new();
+    let mut skipped = Vec::new();
+    for ecosystem in &ecosystems {
+        match outdated(*ecosystem, cwd).await {
+            Ok(outdated) => dependencies.extend(outdated),
+            Err(reason) => skipped.push(format!("{}: {reason}", ecosystem.name())),
+        }
+    }
+    dependencies
+        .retain(|dependency| options.only.is_empty() || options.only.contains(&dependency.name));
+    if dependencies.is_empty() {
+        return Err(BumpError::NothingOutdated);
+    }
+
+    let verify = options.verify.clone().unwrap_or_else(|| {
+        ecosystems
+            .iter()
+            .filter(|ecosystem| dependencies.iter().any(|d| d.ecosystem == **ecosystem))
+            .map(|ecosystem| ecosystem.default_verify_command())
+            .collect::<Vec<_>>()
+            .join(" && ")
+    });
+    Ok(BumpContext {
+        head,
+        dependencies,
+        verify,
+        skipped,
+    })
+}
+
+async fn outdated(ecosystem: Ecosystem, cwd: &Path) -> Result<Vec<OutdatedDependency>, String> {
+    match ecosystem {
+        Ecosystem::Cargo => {
+            let manifest = std::fs::read_to_string(cwd.join("Cargo.toml"))
+                .map_err(|err| format!("failed to read Cargo.toml: {err}"))?;
+            let direct = cargo_direct_dependencies(&manifest);
+            // `cargo update` reports on stderr.
+            let output = run(cwd, "cargo", &["update", "--dry-run", "--verbose"]).await?;
+            Ok(parse_cargo_update(&output.stderr, &direct))
+        }
+        Ecosystem::Npm => {
+            // Exits with 1 when something is outdated.
+            let output = run(cwd, "npm", &["outdated", "--json"]).await?;
+            parse_npm_outdated(&output.stdout)
+        }
+        Ecosystem::Pip => {
+            let output = run(
+                cwd,
+                "python3",
+                &[
+                    "-m",
+                    "pip",
+                    "list",
+                    "--outdated",
+                    "--not-required",
+                    "--format=json",
+                ],
+            )
+            .await?;
+            parse_pip_outdated(&output.stdout)
+        }
+    }
+}
+
+struct CommandOutput {
+    stdout: String,
+    stderr: String,
+}
+
+async fn run(cwd: &Path, program: &str, args: &[&str]) -> Result<CommandOutput, String> {
+    let output = timeout(
+        OUTDATED_COMMAND_TIMEOUT,
+        Command::new(program).args(args).current_dir(cwd).output(),
+    )
+    .await
+    .map_err(|_| format!("`{program} {}` timed out", args.join(" ")))?
+    .map_err(|err| format!("failed to run `{program}`: {err}"))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    if !output.status.success() && stdout.trim().is_empty() {
+        let reason = stderr.lines().last().unwrap_or_default().trim().to_string();
+        return Err(format!("`{program} {}` failed: {reason}", args.join(" ")));
+    }
+    Ok(CommandOutput { stdout, stderr })
+}
+
+/// Names in the `[dependencies]`-like tables of a Cargo manifest, including
+/// `[workspace.dependencies]` and target-specific tables.
+fn cargo_direct_dependencies(manifest: &str) -> BTreeSet<String> {
+    const TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+    let mut names = BTreeSet::new();
+    let Ok(manifest) = manifest.parse::<toml::Table>() else {
+        return names;
+    };
+    let mut collect = |table: &toml::Table| {
+        for key in TABLES {
+            if let Some(deps) = table.get(key).and_then(toml::Value::as_table) {
+                for (name, spec) in deps {
+                    // `foo = { package = "bar" }` renames the crate `bar`.
+                    let package = spec.get("package").and_then(toml::Value::as_str);
+                    names.insert(package.unwrap_or(name).to_string());
+                }
+            }
+        }
+    };
+    collect(&manifest);
+    if let Some(workspace) = manifest.get("workspace").and_then(toml::Value::as_table) {
+        collect(workspace);
+    }
+    if let Some(targets) = manifest.get("target").and_then(toml::Value::as_table) {
+        for target in targets.values().filter_map(toml::Value::as_table) {
+            collect(target);
+        }
+    }
+    names
+}
+
+/// `Updating foo v1.0.0 -> v1.0.3` and `Unchanged bar v0.8.5 (latest:
+/// v0.9.0)` lines of `cargo update --dry-run --verbose`, for crates in
+/// `direct`.
+fn parse_cargo_update(output: &str, direct: &BTreeSet<String>) -> Vec<OutdatedDependency> {
+    let mut outdated: Vec<OutdatedDependency> = Vec::new();
+    for line in output.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let [verb, name, current, rest @ ..] = words.as_slice() else {
+            continue;
+        };
+        if !matches!(*verb, "Updating" | "Unchanged") || !direct.contains(*name) {
+            continue;
+        }
+        let Some(current) = version(current) else {
+            continue;
+        };
+        let compatible = match rest {
+            ["->", next, ..] => version(next),
+            _ => None,
+        };
+        let latest = rest
+            .iter()
+            .position(|word| *word == "(latest:")
+            .and_then(|index| rest.get(index + 1))
+            .and_then(|word| version(word.trim_end_matches(')')));
+        let Some(target) = latest.or(compatible) else {
+            continue;
+        };
+        if !outdated.iter().any(|dependency| dependency.name == *name) {
+            outdated.push(OutdatedDependency {
+                ecosystem: Ecosystem::Cargo,
+                name: name.to_string(),
+                current: current.to_string(),
+                target: target.to_string(),
+            });
+        }
+    }
+    outdated
+}
+
+/// `v1.2.3` as `1.2.3`; `None` for words that are not versions.
+fn version(word: &str) -> Option<&str> {
+    let version = word.strip_prefix('v')?;
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(version)
+}
+
+fn parse_npm_outdated(output: &str) -> Result<Vec<OutdatedDependency>, String> {
+    #[derive(Deserialize)]
+    struct NpmOutdated {
+        current: Option<String>,
+        latest: String,
+    }
+
+    if output.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let packages: BTreeMap<String, NpmOutdated> = serde_json::from_str(output)
+        .map_err(|err| format!("unexpected `npm outdated` output: {err}"))?;
+    Ok(packages
+        .into_iter()
+        .filter_map(|(name, package)| {
+            // Not installed yet: nothing to upgrade from.
+            let current = package.current?;
+            (current != package.latest).then_some(OutdatedDependency {
+                ecosystem: Ecosystem::Npm,
+                name,
+                current,
+                target: package.latest,
+            })
+        })
+        .collect())
+}
+
+fn parse_pip_outdated(output: &str) -> Result<Vec<OutdatedDependency>, String> {
+    #[derive(Deserialize)]
+    struct PipOutdated {
+        name: String,
+        version: String,
+        latest_version: String,
+    }
+
+    let packages: Vec<PipOutdated> = serde_json::from_str(output)
+        .map_err(|err| format!("unexpected `pip list` output: {err}"))?;
+    Ok(packages
+        .into_iter()
+        .map(|package| OutdatedDependency {
+            ecosystem: Ecosystem::Pip,
+            name: package.name,
+            current: package.version,
+            target: package.latest_version,
+        })
+        .collect())
+}
+
+impl BumpContext {
+    /// Initial prompt asking the agent to upgrade the dependencies one at a
+    /// time with a commit after each.
+    pub fn prompt(&self) -> String {
+        let head = self.head.chars().take(10).collect::<String>();
+        let mut prompt = format!(
+            "Upgrade the {count} outdated dependencies below one at a time, in the order listed. The working tree was \
+             clean at HEAD {head} when we started. For each dependency:\n\n\
+             1. Read what changed between the current and the target version: the release notes or CHANGELOG in the \
+             package's repository, or its registry page. Say in a few bullets which breaking changes and deprecations \
+             affect this repository, with the files that use the affected APIs.\n\
+             2. Apply the upgrade with the package manager and adapt the code to the breaking changes.\n\
+             3. Run `{verify}`. When it fails, fix what broke and run it again. Give up on the dependency after three \
+             failed attempts, or sooner when the upgrade needs a decision from me.\n\
+             4. Checkpoint: when the verify command passes, commit only this upgrade with the message `Bump <name> from \
+             <current> to <target>`. If you gave up, put the attempt aside with `git stash push --include-untracked -m \
+             \"codex bump: <name>\"` so the tree is back at the last checkpoint, and move on.\n\n\
+             When all dependencies are done, list each one with its versions, whether it was upgraded or given up (and \
+             why), and its checkpoint commit or stash entry.\n\n",
+            count = self.dependencies.len(),
+            verify = self.verify,
+        );
+        let ecosystems: BTreeSet<Ecosystem> = self
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.ecosystem)
+            .collect();
+        for ecosystem in ecosystems {
+            prompt.push_str(ecosystem.instructions());
+            prompt.push('\n');
+        }
+        prompt.push_str("\n<dependencies>\n");
+        for dependency in &self.dependencies {
+            prompt.push_str(&format!(
+                "{} {}: {} -> {}\n",
+                dependency.ecosystem.name(),
+                dependency.name,
+                dependency.current,
+                dependency.target
+            ));
+        }
+        prompt.push_str("</dependencies>");
+        prompt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn dependency(
+        ecosystem: Ecosystem,
+        name: &str,
+        current: &str,
+        target: &str,
+    ) -> OutdatedDependency {
+        OutdatedDependency {
+            ecosystem,
+            name: name.to_string(),
+            current: current.to_string(),
+            target: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn cargo_update_lines_of_direct_dependencies_are_parsed() {
+        let manifest = r#"
+[workspace.dependencies]
+anyhow = "1"
+rand = "0.8"
+clap = { version = "4", features = ["derive"] }
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+"#;
+        let output = "    Updating crates.io index
+     Locking 3 packages to latest compatible versions
+    Updating anyhow v1.0.86 -> v1.0.89
+    Updating syn v2.0.70 -> v2.0.79
+    Updating libc v0.2.150 -> v0.2.159 (latest: v1.0.0-alpha.1)
+   Unchanged rand v0.8.5 (latest: v0.9.0)
+   Unchanged clap v4.5.20
+warning: not updating lockfile due to dry run
+";
+        let direct = cargo_direct_dependencies(manifest);
+
+        assert_eq!(
+            parse_cargo_update(output, &direct),
+            vec![
+                dependency(Ecosystem::Cargo, "anyhow", "1.0.86", "1.0.89"),
+                dependency(Ecosystem::Cargo, "libc", "0.2.150", "1.0.0-alpha.1"),
+                dependency(Ecosystem::Cargo, "rand", "0.8.5", "0.9.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn npm_and_pip_reports_are_parsed() {
+        let npm = r#"{
+  "react": { "current": "18.2.0", "wanted": "18.3.1", "latest": "19.0.0" },
+  "left-pad": { "wanted": "1.3.0", "latest": "1.3.0" }
+}"#;
+        let pip = r#"[{"name": "requests", "version": "2.31.0", "latest_version": "2.32.3", "latest_filetype": "wheel"}]"#;
+
+        assert_eq!(
+            parse_npm_outdated(npm),
+            Ok(vec![dependency(
+                Ecosystem::Npm,
+                "react",
+                "18.2.0",
+                "19.0.0"
+            )])
+        );
+        assert_eq!(
+            parse_pip_outdated(pip),
+            Ok(vec![dependency(
+                Ecosystem::Pip,
+                "requests",
+                "2.31.0",
+                "2.32.3"
+            )])
+        );
+    }
+
+    #[test]
+    fn prompt_lists_dependencies_and_verify_command() {
+        let context = BumpContext {
+            head: "1111111111aaaaaaaaaa".to_string(),
+            dependencies: vec![dependency(Ecosystem::Cargo, "rand", "0.8.5", "0.9.0")],
+            verify: "cargo test --all-features".to_string(),
+            skipped: Vec::new(),
+        };
+        let prompt = context.prompt();
+
+        assert!(prompt.contains("clean at HEAD 1111111111 when we started"));
+        assert!(prompt.contains("3. Run `cargo test --all-features`."));
+        assert!(prompt.contains("cargo update -p <name>"));
+        assert!(!prompt.contains("npm install"));
+        assert!(prompt.ends_with("<dependencies>\ncargo rand: 0.8.5 -> 0.9.0\n</dependencies>"));
+    }
+}
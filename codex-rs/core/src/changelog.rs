@@ -0,0 +1,365 @@
+//! Context for `codex changelog`: the commits of a range with the files they
+//! touch, split into chunks small enough to summarize one at a time, and the
+//! prompts that turn them into release notes.
+
+use std::path::Path;
+
+use tokio::process::Command;
+
+/// Size limit for the commit listing of one chunk.
+const MAX_CHUNK_BYTES: usize = 24 * 1024;
+/// Commits summarized together at most.
+const MAX_CHUNK_COMMITS: usize = 40;
+/// Files listed per commit; the rest are only counted.
+const MAX_FILES_PER_COMMIT: usize = 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ChangelogError {
+    #[error("failed to run git: {0}")]
+    Git(#[from] std::io::Error),
+
+    #[error("git log {range} failed: {stderr}")]
+    Log { range: String, stderr: String },
+
+    #[error("no commits in {0}")]
+    Empty(String),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChangelogFormat {
+    /// Sections per area of the codebase.
+    #[default]
+    Markdown,
+    /// <https://keepachangelog.com> sections (Added, Changed, Fixed, ...).
+    KeepAChangelog,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogCommit {
+    pub sha: String,
+    pub subject: String,
+    pub body: String,
+    /// Files changed, with lines added and removed (`None` for binary files).
+    pub files: Vec<(String, Option<(u64, u64)>)>,
+}
+
+impl ChangelogCommit {
+    /// Top-level areas of the codebase the commit touches, such as
+    /// `codex-rs/core` or `docs`.
+    pub fn areas(&self) -> Vec<String> {
+        let mut areas: Vec<String> = self.files.iter().map(|(path, _)| area_of(path)).collect();
+        areas.sort();
+        areas.dedup();
+        areas
+    }
+
+    /// The commit as listed in prompts: subject, body, areas and files.
+    pub(crate) fn render(&self) -> String {
+        let short_sha: String = self.sha.chars().take(10).collect();
+        let mut out = format!("commit {short_sha}: {}\n", self.subject);
+        let body = self.body.trim();
+        if !body.is_empty() {
+            for line in body.lines() {
+                out.push_str(&format!("    {line}\n"));
+            }
+        }
+        let areas = self.areas();
+        if !areas.is_empty() {
+            out.push_str(&format!("areas: {}\n", areas.join(", ")));
+        }
+        for (path, stat) in self.files.iter().take(MAX_FILES_PER_COMMIT) {
+            match stat {
+                Some((added, removed)) => {
+                    out.push_str(&format!("  {path} (+{added} -{removed})\n"))
+                }
+                None => out.push_str(&format!("  {path} (binary)\n")),
+            }
+        }
+        if self.files.len() > MAX_FILES_PER_COMMIT {
+            out.push_str(&format!(
+                "  ... {} more file(s)\n",
+                self.files.len() - MAX_FILES_PER_COMMIT
+            ));
+        }
+        out
+    }
+}
+
+/// A range of commits and what the notes for it should look like.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogRequest {
+    pub from: String,
+    pub to: String,
+    pub format: ChangelogFormat,
+    /// Commit date of `to`, `YYYY-MM-DD`.
+    pub date: Option<String>,
+}
+
+impl ChangelogRequest {
+    fn range(&self) -> String {
+        format!("{}..{}", self.from, self.to)
+    }
+
+    /// Prompt asking for notes on one chunk of the range, to be merged by
+    /// [`Self::merge_prompt`].
+    pub fn chunk_prompt(&self, chunk: &[ChangelogCommit], index: usize, total: usize) -> String {
+        let mut prompt = format!(
+            "You are helping write release notes for the commits in {range}. Below is part {part} of {total} of the commit \
+             list, with the files each commit changed. Write down the changes a user of this project would notice, one line \
+             per change, as `- <area>: <what changed> (<short sha>[, <short sha>...])`. Merge commits that belong to the same \
+             change, leave out refactors, tests and CI changes unless they matter to users, and run `git show <sha>` when a \
+             subject does not make the change clear. Reply with the list only.\n\n<commits>\n",
+            range = self.range(),
+            part = index + 1,
+        );
+        for commit in chunk {
+            prompt.push_str(&commit.render());
+        }
+        prompt.push_str("</commits>");
+        prompt
+    }
+
+    /// Prompt turning the notes on every chunk into the final release notes.
+    pub fn merge_prompt(&self, notes: &[String]) -> String {
+        let mut prompt = format!(
+            "Below are notes on the changes in {}, written in parts. Combine them into release notes. {}\n\n<notes>",
+            self.range(),
+            self.format_instructions()
+        );
+        for note in notes {
+            prompt.push('\n');
+            prompt.push_str(note.trim());
+            prompt.push('\n');
+        }
+        prompt.push_str("</notes>");
+        prompt
+    }
+
+    /// Prompt writing the release notes directly, for ranges that fit in a
+    /// single chunk.
+    pub fn single_prompt(&self, commits: &[ChangelogCommit]) -> String {
+        let mut prompt = format!(
+            "Write release notes for the commits in {}, listed below with the files each commit changed. Describe the \
+             changes a user of this project would notice, merge commits that belong to the same change, leave out \
+             refactors, tests and CI changes unless they matter to users, and run `git show <sha>` when a subject does \
+             not make the change clear. {}\n\n<commits>\n",
+            self.range(),
+            self.format_instructions()
+        );
+        for commit in commits {
+            prompt.push_str(&commit.render());
+        }
+        prompt.push_str("</commits>");
+        prompt
+    }
+
+    fn heading(&self) -> String {
+        let version = if self.to == "HEAD" {
+            "Unreleased"
+        } else {
+            self.to.as_str()
+        };
+        match (&self.format, &self.date) {
+            (ChangelogFormat::KeepAChangelog, Some(date)) if self.to != "HEAD" => {
+                format!("## [{version}] - {date}")
+            }
+            (ChangelogFormat::KeepAChangelog, _) => format!("## [{version}]"),
+            (ChangelogFormat::Markdown, _) => format!("## {version}"),
+        }
+    }
+
+    fn format_instructions(&self) -> String {
+        let heading = self.heading();
+        match self.format {
+            ChangelogFormat::Markdown => format!(
+                "Reply with Markdown only: start with the heading `{heading}`, then one `### <Area>` section per area of the \
+                 codebase, most important first, each with one bullet per change ending in the short commit sha(s) in \
+                 parentheses."
+            ),
+            ChangelogFormat::KeepAChangelog => format!(
+                "Reply with Markdown only, in the Keep a Changelog format: start with the heading `{heading}`, then the \
+                 sections `### Added`, `### Changed`, `### Deprecated`, `### Removed`, `### Fixed` and `### Security` in \
+                 that order, leaving out empty ones. Start each bullet with the area in bold, e.g. `- **tui**: ...`."
+            ),
+        }
+    }
+}
+
+/// Commits in `from..to`, oldest first, leaving out merges.
+pub async fn commits_in_range(
+    cwd: &Path,
+    from: &str,
+    to: &str,
+) -> Result<Vec<ChangelogCommit>, ChangelogError> {
+    let range = format!("{from}..{to}");
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--reverse",
+            "--no-merges",
+            "--numstat",
+            "--format=%x1e%H%x1f%s%x1f%b%x1f",
+            &range,
+            "--",
+        ])
+        .current_dir(cwd)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(ChangelogError::Log {
+            range,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    let commits = parse_log(&String::from_utf8_lossy(&output.stdout));
+    if commits.is_empty() {
+        return Err(ChangelogError::Empty(range));
+    }
+    Ok(commits)
+}
+
+/// Commit date of `rev`, `YYYY-MM-DD`.
+pub async fn commit_date(cwd: &Path, rev: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%cs", rev, "--"])
+        .current_dir(cwd)
+        .output()
+        .await
+        .ok()?;
+    let date = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    (output.status.success() && !date.is_empty()).then_some(date)
+}
+
+/// Consecutive runs of `commits` small enough to summarize together.
+pub fn chunk_commits(commits: &[ChangelogCommit]) -> Vec<&[ChangelogCommit]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut bytes = 0;
+    for (index, commit) in commits.iter().enumerate() {
+        let size = commit.render().len();
+        if index > start && (bytes + size > MAX_CHUNK_BYTES || index - start >= MAX_CHUNK_COMMITS) {
+            chunks.push(&commits[start..index]);
+            start = index;
+            bytes = 0;
+        }
+        bytes += size;
+    }
+    if start < commits.len() {
+        chunks.push(&commits[start..]);
+    }
+    chunks
+}
+
+fn parse_log(output: &str) -> Vec<ChangelogCommit> {
+    output
+        .split('\u{1e}')
+        .filter_map(|record| {
+            let mut fields = record.splitn(4, '\u{1f}');
+            let sha = fields.next()?.trim().to_string();
+            let subject = fields.next()?.trim().to_string();
+            let body = fields.next()?.trim().to_string();
+            let files = fields
+                .next()
+                .unwrap_or_default()
+                .lines()
+                .filter_map(parse_numstat)
+                .collect();
+            (!sha.is_empty()).then_some(ChangelogCommit {
+                sha,
+                subject,
+                body,
+                files,
+            })
+        })
+        .collect()
+}
+
+fn parse_numstat(line: &str) -> Option<(String, Option<(u64, u64)>)> {
+    let mut parts = line.splitn(3, '\t');
+    let added = parts.next()?;
+    let removed = parts.next()?;
+    let path = parts.next()?.to_string();
+    let stat = added.parse().ok().zip(removed.parse().ok());
+    Some((path, stat))
+}
+
+fn area_of(path: &str) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        [_file] => "(root)".to_string(),
+        [dir, _file] => (*dir).to_string(),
+        [first, second, ..] => format!("{first}/{second}"),
+        [] => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_log_with_numstat() {
+        let output = "\u{1e}aaaa\u{1f}Add resolve\u{1f}Body line\n\u{1f}\n\n3\t1\tcodex-rs/core/src/lib.rs\n-\t-\tdocs/logo.png\n\
+                      \u{1e}bbbb\u{1f}Fix typo\u{1f}\u{1f}\n\n1\t1\tREADME.md\n";
+        let commits = parse_log(output);
+
+        assert_eq!(
+            commits,
+            vec![
+                ChangelogCommit {
+                    sha: "aaaa".to_string(),
+                    subject: "Add resolve".to_string(),
+                    body: "Body line".to_string(),
+                    files: vec![
+                        ("codex-rs/core/src/lib.rs".to_string(), Some((3, 1))),
+                        ("docs/logo.png".to_string(), None),
+                    ],
+                },
+                ChangelogCommit {
+                    sha: "bbbb".to_string(),
+                    subject: "Fix typo".to_string(),
+                    body: String::new(),
+                    files: vec![("README.md".to_string(), Some((1, 1)))],
+                },
+            ]
+        );
+        assert_eq!(commits[0].areas(), vec!["codex-rs/core", "docs"]);
+        assert_eq!(commits[1].areas(), vec!["(root)"]);
+    }
+
+    #[test]
+    fn chunks_by_commit_count() {
+        let commits: Vec<ChangelogCommit> = (0..MAX_CHUNK_COMMITS + 5)
+            .map(|i| ChangelogCommit {
+                sha: format!("{i:040}"),
+                subject: format!("Change {i}"),
+                body: String::new(),
+                files: Vec::new(),
+            })
+            .collect();
+        let chunks = chunk_commits(&commits);
+
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.len()).collect::<Vec<_>>(),
+            vec![MAX_CHUNK_COMMITS, 5]
+        );
+    }
+
+    #[test]
+    fn keep_a_changelog_heading_uses_the_tag_date() {
+        let request = ChangelogRequest {
+            from: "v1.2.0".to_string(),
+            to: "v1.3.0".to_string(),
+            format: ChangelogFormat::KeepAChangelog,
+            date: Some("2026-10-01".to_string()),
+        };
+        assert_eq!(request.heading(), "## [v1.3.0] - 2026-10-01");
+
+        let unreleased = ChangelogRequest {
+            to: "HEAD".to_string(),
+            ..request
+        };
+        assert_eq!(unreleased.heading(), "## [Unreleased]");
+    }
+}
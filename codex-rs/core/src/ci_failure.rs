@@ -0,0 +1,302 @@
+//! Context for `codex fix-ci`: the latest failed GitHub Actions run on the
+//! current branch, with the logs of its failing steps.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::config_types::GitHubTracker;
+use crate::git_info::current_branch_name;
+use crate::issue_tracker::GitHubApi;
+use crate::issue_tracker::IssueFetchError;
+use crate::issue_tracker::default_project;
+use crate::truncate::truncate_middle;
+
+/// Failed jobs whose logs are included; later ones are only listed.
+const MAX_JOBS_WITH_LOGS: usize = 3;
+/// Size limit for the excerpt of each job log.
+const MAX_LOG_BYTES: usize = 12 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CiFailureError {
+    #[error(
+        "could not determine the GitHub repository; set `issue_trackers.github.repo` or add an `origin` remote"
+    )]
+    NoRepository,
+
+    #[error("not on a branch (detached HEAD?)")]
+    NoBranch,
+
+    #[error("no failed GitHub Actions run found for branch `{0}`")]
+    NoFailedRun(String),
+
+    #[error(transparent)]
+    Fetch(#[from] IssueFetchError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedJob {
+    pub name: String,
+    /// Name of the first failing step, when GitHub reports one.
+    pub step: Option<String>,
+    /// Excerpt of the job log around the failure, or why it is missing.
+    pub log: Result<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedRun {
+    pub repo: String,
+    pub branch: String,
+    pub workflow: String,
+    pub url: String,
+    pub head_sha: String,
+    pub jobs: Vec<FailedJob>,
+}
+
+impl FailedRun {
+    /// Initial prompt asking the agent to fix the failure.
+    pub fn to_prompt(&self) -> String {
+        let short_sha: String = self.head_sha.chars().take(7).collect();
+        let mut prompt = format!(
+            "CI is failing on branch `{}` of {}: the \"{}\" workflow failed for commit {short_sha} ({}). \
+             The logs of the failing steps are below. Find the cause, fix it, and run the relevant checks locally to confirm the fix.\n\n<ci_failure>",
+            self.branch, self.repo, self.workflow, self.url
+        );
+        for job in &self.jobs {
+            prompt.push_str(&format!("\nJob: {}\n", job.name));
+            if let Some(step) = &job.step {
+                prompt.push_str(&format!("Failing step: {step}\n"));
+            }
+            match &job.log {
+                Ok(log) => prompt.push_str(&format!("```\n{log}\n```\n")),
+                Err(reason) => prompt.push_str(&format!("(log unavailable: {reason})\n")),
+            }
+        }
+        prompt.push_str("</ci_failure>");
+        prompt
+    }
+}
+
+#[derive(Deserialize)]
+struct WorkflowRuns {
+    workflow_runs: Vec<WorkflowRun>,
+}
+
+#[derive(Deserialize)]
+struct WorkflowRun {
+    id: u64,
+    name: Option<String>,
+    html_url: String,
+    head_sha: String,
+}
+
+#[derive(Deserialize)]
+struct Jobs {
+    jobs: Vec<Job>,
+}
+
+#[derive(Deserialize)]
+struct Job {
+    id: u64,
+    name: String,
+    conclusion: Option<String>,
+    #[serde(default)]
+    steps: Vec<JobStep>,
+}
+
+#[derive(Deserialize)]
+struct JobStep {
+    name: String,
+    conclusion: Option<String>,
+}
+
+/// Fetch the most recent failed run for the branch checked out in `cwd`.
+pub async fn latest_failed_run(
+    config: &GitHubTracker,
+    cwd: &Path,
+) -> Result<FailedRun, CiFailureError> {
+    let repo = default_project(config.repo.as_deref(), cwd)
+        .await
+        .ok_or(CiFailureError::NoRepository)?;
+    let branch = current_branch_name(cwd)
+        .await
+        .ok_or(CiFailureError::NoBranch)?;
+    let api = GitHubApi::new(config);
+
+    let runs: WorkflowRuns = api
+        .get_json(&format!(
+            "/repos/{repo}/actions/runs?branch={}&status=failure&per_page=1",
+            urlencoding::encode(&branch)
+        ))
+        .await?;
+    let run = runs
+        .workflow_runs
+        .into_iter()
+        .next()
+        .ok_or_else(|| CiFailureError::NoFailedRun(branch.clone()))?;
+
+    let jobs: Jobs = api
+        .get_json(&format!(
+            "/repos/{repo}/actions/runs/{}/jobs?filter=latest&per_page=100",
+            run.id
+        ))
+        .await?;
+    let mut failed_jobs = Vec::new();
+    for (index, job) in jobs
+        .jobs
+        .into_iter()
+        .filter(|job| job.conclusion.as_deref() == Some("failure"))
+        .enumerate()
+    {
+        let step = job
+            .steps
+            .iter()
+            .find(|step| step.conclusion.as_deref() == Some("failure"))
+            .map(|step| step.name.clone());
+        let log = if index >= MAX_JOBS_WITH_LOGS {
+            Err("skipped, too many failed jobs".to_string())
+        } else if !api.has_token() {
+            Err("GitHub only serves job logs to authenticated requests; set a token (GITHUB_TOKEN by default)".to_string())
+        } else {
+            api.get_text(&format!("/repos/{repo}/actions/jobs/{}/logs", job.id))
+                .await
+                .map(|log| failing_step_excerpt(&log))
+                .map_err(|err| err.to_string())
+        };
+        failed_jobs.push(FailedJob {
+            name: job.name,
+            step,
+            log,
+        });
+    }
+
+    Ok(FailedRun {
+        repo,
+        branch,
+        workflow: run.name.unwrap_or_else(|| "CI".to_string()),
+        url: run.html_url,
+        head_sha: run.head_sha,
+        jobs: failed_jobs,
+    })
+}
+
+/// Extract the output of the failing step from a raw job log: from the last
+/// `##[group]Run` header before the first `##[error]` line through the last
+/// error, truncated in the middle. Logs without an error marker keep their
+/// last lines instead. Timestamps are stripped.
+fn failing_step_excerpt(log: &str) -> String {
+    let lines: Vec<&str> = log.lines().map(strip_timestamp).collect();
+    let excerpt = match (
+        lines.iter().position(|line| line.starts_with("##[error]")),
+        lines.iter().rposition(|line| line.starts_with("##[error]")),
+    ) {
+        (Some(first_error), Some(last_error)) => {
+            let start = lines[..first_error]
+                .iter()
+                .rposition(|line| line.starts_with("##[group]Run "))
+                .unwrap_or(0);
+            lines[start..=last_error].join("\n")
+        }
+        // No error marker: the end of the log is the most useful part.
+        _ => return log_tail(&lines),
+    };
+    truncate_middle(&excerpt, MAX_LOG_BYTES).0
+}
+
+/// The last lines of a log that fit in `MAX_LOG_BYTES`.
+fn log_tail(lines: &[&str]) -> String {
+    let mut start = lines.len();
+    let mut bytes = 0;
+    while let Some(line) = start.checked_sub(1).map(|idx| lines[idx])
+        && bytes + line.len() < MAX_LOG_BYTES
+    {
+        bytes += line.len() + 1;
+        start -= 1;
+    }
+    match (start, lines.last()) {
+        (0, _) => lines.join("\n"),
+        // The last line alone is over the limit.
+        (start, Some(last)) if start == lines.len() => truncate_middle(last, MAX_LOG_BYTES).0,
+        (start, _) => format!(
+            "…{start} earlier lines omitted…\n{}",
+            lines[start..].join("\n")
+        ),
+    }
+}
+
+/// Drop the `2024-05-01T12:00:00.1234567Z ` prefix GitHub puts on log lines.
+fn strip_timestamp(line: &str) -> &str {
+    let line = line.trim_start_matches('\u{feff}');
+    match line.split_once(' ') {
+        Some((stamp, rest))
+            if stamp.ends_with('Z')
+                && stamp.contains('T')
+                && stamp.starts_with(|c: char| c.is_ascii_digit()) =>
+        {
+            rest
+        }
+        _ => line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn excerpt_covers_failing_step() {
+        let log = "\u{feff}2024-05-01T12:00:00.0000000Z ##[group]Run actions/checkout@v4\n\
+                   2024-05-01T12:00:01.0000000Z ##[endgroup]\n\
+                   2024-05-01T12:00:02.0000000Z ##[group]Run cargo test\n\
+                   2024-05-01T12:00:03.0000000Z test foo ... FAILED\n\
+                   2024-05-01T12:00:04.0000000Z ##[error]Process completed with exit code 101.\n\
+                   2024-05-01T12:00:05.0000000Z Post job cleanup.";
+        assert_eq!(
+            failing_step_excerpt(log),
+            "##[group]Run cargo test\ntest foo ... FAILED\n##[error]Process completed with exit code 101."
+        );
+    }
+
+    #[test]
+    fn excerpt_without_error_marker_keeps_the_end() {
+        let lines: Vec<String> = (0..2000).map(|n| format!("line {n:04}")).collect();
+        let excerpt = failing_step_excerpt(&lines.join("\n"));
+        assert!(excerpt.len() <= MAX_LOG_BYTES + 64);
+        assert!(excerpt.starts_with("…"));
+        assert!(excerpt.ends_with("line 1998\nline 1999"));
+
+        assert_eq!(failing_step_excerpt("short\nlog"), "short\nlog");
+    }
+
+    #[test]
+    fn prompt_lists_failed_jobs() {
+        let run = FailedRun {
+            repo: "openai/codex".to_string(),
+            branch: "fix".to_string(),
+            workflow: "rust-ci".to_string(),
+            url: "https://github.com/openai/codex/actions/runs/1".to_string(),
+            head_sha: "0123456789abcdef".to_string(),
+            jobs: vec![
+                FailedJob {
+                    name: "test".to_string(),
+                    step: Some("cargo test".to_string()),
+                    log: Ok("test foo ... FAILED".to_string()),
+                },
+                FailedJob {
+                    name: "lint".to_string(),
+                    step: None,
+                    log: Err("HTTP 403".to_string()),
+                },
+            ],
+        };
+        let prompt = run.to_prompt();
+        assert!(prompt.starts_with(
+            "CI is failing on branch `fix` of openai/codex: the \"rust-ci\" workflow failed for commit 0123456 "
+        ));
+        assert!(prompt.ends_with(
+            "<ci_failure>\nJob: test\nFailing step: cargo test\n```\ntest foo ... FAILED\n```\n\
+             \nJob: lint\n(log unavailable: HTTP 403)\n</ci_failure>"
+        ));
+    }
+}
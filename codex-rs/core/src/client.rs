@@ -34,6 +34,7 @@ use crate::client_common::ResponsesApiRequest;
 use crate::client_common::create_reasoning_param_for_request;
 use crate::client_common::create_text_param_for_request;
 use crate::config::Config;
+use crate::config_types::IssueTrackers;
 use crate::default_client::create_client;
 use crate::error::CodexErr;
 use crate::error::Result;
@@ -119,6 +120,18 @@ impl ModelClient {
         })
     }
 
+    pub fn get_max_turn_duration(&self) -> Option<Duration> {
+        self.config.max_turn_duration
+    }
+
+    pub fn get_max_tool_calls_per_turn(&self) -> Option<u32> {
+        self.config.max_tool_calls_per_turn
+    }
+
+    pub fn get_issue_trackers(&self) -> &IssueTrackers {
+        &self.config.issue_trackers
+    }
+
     /// Dispatches to either the Responses or Chat implementation depending on
     /// the provider config.  Public callers always invoke `stream()` – the
     /// specialised helpers are private to avoid accidental misuse.
@@ -1,8 +1,11 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
+use std::time::Duration;
 
 use crate::AuthManager;
 use crate::client_common::REVIEW_PROMPT;
@@ -17,6 +20,7 @@ use codex_apply_patch::ApplyPatchAction;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::ConversationPathResponseEvent;
 use codex_protocol::protocol::ExitedReviewModeEvent;
+use codex_protocol::protocol::PinnedContextItem;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
@@ -42,6 +46,8 @@ use crate::apply_patch::convert_apply_patch_to_protocol;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::command_safety::deny_list::CommandDenyList;
 use crate::config::Config;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::conversation_history::ConversationHistory;
@@ -54,16 +60,21 @@ use crate::exec::StreamOutput;
 use crate::exec_command::ExecCommandParams;
 use crate::exec_command::ExecSessionManager;
 use crate::exec_command::WriteStdinParams;
+use crate::executor::CommandTarget;
 use crate::executor::Executor;
 use crate::executor::ExecutorConfig;
 use crate::executor::normalize_exec_result;
+use crate::external_edits::external_changes_message;
+use crate::issue_tracker::issue_context_for_input;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
-use crate::model_family::find_family_for_model;
-use crate::openai_model_info::get_model_info;
+use crate::model_capabilities::adapt_prompt_for_model;
+use crate::model_metadata::detect_model_limits;
 use crate::openai_tools::ToolsConfig;
 use crate::openai_tools::ToolsConfigParams;
+use crate::package_installs::PackageInstallLog;
 use crate::parse_command::parse_command;
+use crate::project_doc::discover_project_doc_paths;
 use crate::project_doc::get_user_instructions;
 use crate::protocol::AgentMessageDeltaEvent;
 use crate::protocol::AgentReasoningDeltaEvent;
@@ -73,13 +84,18 @@ use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
 use crate::protocol::ErrorEvent;
+use crate::protocol::ErrorKind;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::ExecCommandBeginEvent;
 use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::FileChange;
 use crate::protocol::InputItem;
+use crate::protocol::InputMessageKind;
 use crate::protocol::ListCustomPromptsResponseEvent;
+use crate::protocol::McpServerState;
+use crate::protocol::McpServerStatusEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
@@ -88,12 +104,19 @@ use crate::protocol::ReviewDecision;
 use crate::protocol::ReviewOutputEvent;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
+use crate::protocol::SessionStatus;
 use crate::protocol::StreamErrorEvent;
 use crate::protocol::Submission;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::TurnSummary;
+use crate::protocol::UserMessageEvent;
+use crate::protocol::UserQuestionKind;
+use crate::protocol::UserQuestionRequestEvent;
 use crate::protocol::WebSearchBeginEvent;
+use crate::provenance::record_patch;
+use crate::replay::Replay;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::shell;
@@ -105,12 +128,20 @@ use crate::tasks::ReviewTask;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::format_exec_output_str;
+use crate::tools::output_parsers::is_test_command;
 use crate::tools::parallel::ToolCallRuntime;
+use crate::turn_budget::TurnBudget;
+use crate::turn_budget::wrap_up_message;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::UserInstructions;
+use crate::user_note::UserNote;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
+use crate::verify_commands::VerifyCommand;
+use crate::verify_commands::detect_verify_commands;
+use crate::verify_commands::with_verify_instructions;
+use crate::write_quota::WriteQuota;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
@@ -143,19 +174,48 @@ pub struct CodexSpawnOk {
 
 pub(crate) const INITIAL_SUBMIT_ID: &str = "";
 pub(crate) const SUBMISSION_CHANNEL_CAPACITY: usize = 64;
+/// Events waiting for the client. Once full, sending an event waits for the
+/// client to catch up, except command output, which is coalesced instead.
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// How many events a conversation holds for its client.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventBuffer {
+    /// Up to [`EVENT_CHANNEL_CAPACITY`] events, for clients that read every
+    /// conversation they start.
+    #[default]
+    Bounded,
+    /// Any number of events, for clients that may leave a conversation
+    /// without a listener, which would otherwise stall its session.
+    Unbounded,
+}
+
+impl EventBuffer {
+    fn channel(self) -> (Sender<Event>, Receiver<Event>) {
+        match self {
+            EventBuffer::Bounded => async_channel::bounded(EVENT_CHANNEL_CAPACITY),
+            EventBuffer::Unbounded => async_channel::unbounded(),
+        }
+    }
+}
 
 impl Codex {
     /// Spawn a new [`Codex`] and initialize the session.
     pub async fn spawn(
-        config: Config,
+        mut config: Config,
         auth_manager: Arc<AuthManager>,
         conversation_history: InitialHistory,
         session_source: SessionSource,
+        event_buffer: EventBuffer,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
-        let (tx_event, rx_event) = async_channel::unbounded();
+        let (tx_event, rx_event) = event_buffer.channel();
 
-        let user_instructions = get_user_instructions(&config).await;
+        let verify_commands = detect_verify_commands(&config.cwd, &config.verify);
+        let user_instructions =
+            with_verify_instructions(get_user_instructions(&config).await, &verify_commands);
+
+        autodetect_model_limits(&mut config).await;
 
         let config = Arc::new(config);
 
@@ -170,6 +230,7 @@ impl Codex {
             sandbox_policy: config.sandbox_policy.clone(),
             notify: UserNotifier::new(config.notify.clone()),
             cwd: config.cwd.clone(),
+            verify_commands,
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -305,6 +366,9 @@ struct ConfigureSession {
     /// `ConfigureSession` operation so that the business-logic layer can
     /// operate deterministically.
     cwd: PathBuf,
+
+    /// Verify commands of the build systems found in `cwd`.
+    verify_commands: Vec<VerifyCommand>,
 }
 
 impl Session {
@@ -327,6 +391,7 @@ impl Session {
             sandbox_policy,
             notify,
             cwd,
+            verify_commands,
         } = configure_session;
         debug!("Configuring session: model={model}; provider={provider:?}");
         if !cwd.is_absolute() {
@@ -379,8 +444,15 @@ impl Session {
             anyhow::anyhow!("failed to initialize rollout recorder: {e:#}")
         })?;
         let rollout_path = rollout_recorder.rollout_path.clone();
+        let replay = config
+            .replay
+            .as_ref()
+            .map(Replay::open)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("failed to open replay log: {e:#}"))?;
         // Create the mutable state for the Session.
-        let state = SessionState::new();
+        let mut state = SessionState::new();
+        state.write_quota = WriteQuota::new(config.max_session_write_bytes);
 
         // Handle MCP manager result and record any startup failures.
         let (mcp_connection_manager, failed_clients) = match mcp_res {
@@ -390,7 +462,10 @@ impl Session {
                 error!("{message}");
                 post_session_configured_error_events.push(Event {
                     id: INITIAL_SUBMIT_ID.to_owned(),
-                    msg: EventMsg::Error(ErrorEvent { message }),
+                    msg: EventMsg::Error(ErrorEvent {
+                        message,
+                        kind: ErrorKind::Config,
+                    }),
                 });
                 (McpConnectionManager::default(), Default::default())
             }
@@ -403,10 +478,22 @@ impl Session {
                 error!("{message}");
                 post_session_configured_error_events.push(Event {
                     id: INITIAL_SUBMIT_ID.to_owned(),
-                    msg: EventMsg::Error(ErrorEvent { message }),
+                    msg: EventMsg::Error(ErrorEvent {
+                        message,
+                        kind: ErrorKind::Config,
+                    }),
                 });
             }
         }
+        state.mcp_servers = mcp_connection_manager.server_states(&config.mcp_servers);
+        if !state.mcp_servers.is_empty() {
+            post_session_configured_error_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::McpServerStatus(McpServerStatusEvent {
+                    servers: state.mcp_servers.clone(),
+                }),
+            });
+        }
 
         let otel_event_manager = OtelEventManager::new(
             conversation_id,
@@ -451,6 +538,10 @@ impl Session {
                 include_web_search_request: config.tools_web_search_request,
                 use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                 include_view_image_tool: config.include_view_image_tool,
+                include_system_info_tool: config.include_system_info_tool,
+                include_conversation_search_tool: config.include_conversation_search_tool,
+                include_todos_tool: config.include_todos_tool,
+                include_ask_user_tool: config.include_ask_user_tool,
                 experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
             }),
             user_instructions,
@@ -470,10 +561,23 @@ impl Session {
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            record_provenance: config.record_provenance,
+            structured_failures: config.structured_failures,
+            replay,
+            verify_commands,
             executor: Executor::new(ExecutorConfig::new(
                 turn_context.sandbox_policy.clone(),
                 turn_context.cwd.clone(),
                 config.codex_linux_sandbox_exe.clone(),
+                config.remote.clone(),
+                CommandTarget::from_config(&config),
+                CommandDenyList::new(&config.command_deny_list),
+                PackageInstallLog::new(
+                    config.package_installs,
+                    &config.state_home.path,
+                    conversation_id,
+                )
+                .map(Arc::new),
             )),
         };
 
@@ -489,6 +593,7 @@ impl Session {
         // Dispatch the SessionConfiguredEvent first and then report any errors.
         // If resuming, include converted initial messages in the payload so UIs can render them immediately.
         let initial_messages = initial_history.get_event_msgs();
+        let pinned_context = initial_history.get_pinned_context();
         sess.record_initial_history(&turn_context, initial_history)
             .await;
 
@@ -501,6 +606,7 @@ impl Session {
                 history_log_id,
                 history_entry_count,
                 initial_messages,
+                pinned_context,
                 rollout_path,
             }),
         })
@@ -544,6 +650,7 @@ impl Session {
                 if !reconstructed_history.is_empty() {
                     self.record_into_history(&reconstructed_history).await;
                 }
+                self.state.lock().await.pinned_context = conversation_history.get_pinned_context();
 
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
@@ -594,16 +701,50 @@ impl Session {
         }
 
         let event = Event {
-            id: event_id,
+            id: event_id.clone(),
             msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-                call_id,
+                call_id: call_id.clone(),
                 command,
                 cwd,
                 reason,
             }),
         };
         self.send_event(event).await;
-        rx_approve.await.unwrap_or_default()
+        let decision = rx_approve.await.unwrap_or_default();
+        if let Some(at) = self.active_turn.lock().await.as_mut() {
+            at.turn_state
+                .lock()
+                .await
+                .claim_command_edit(&event_id, &call_id);
+        }
+        decision
+    }
+
+    /// Approve the pending exec request of `sub_id` with the command the
+    /// user edited it into.
+    pub async fn approve_edited_command(&self, sub_id: &str, command: Vec<String>) {
+        if let Some(at) = self.active_turn.lock().await.as_mut() {
+            at.turn_state
+                .lock()
+                .await
+                .insert_pending_command_edit(sub_id.to_string(), command);
+        }
+        self.notify_approval(sub_id, ReviewDecision::Approved).await;
+    }
+
+    /// The command the user approved for `call_id` in place of the proposed
+    /// one, if they edited it.
+    pub(crate) async fn command_edit(&self, call_id: &str) -> Option<Vec<String>> {
+        let active = self.active_turn.lock().await;
+        let at = active.as_ref()?;
+        at.turn_state.lock().await.command_edit(call_id)
+    }
+
+    /// Like [`Self::command_edit`], forgetting the edit.
+    pub(crate) async fn take_command_edit(&self, call_id: &str) -> Option<Vec<String>> {
+        let active = self.active_turn.lock().await;
+        let at = active.as_ref()?;
+        at.turn_state.lock().await.take_command_edit(call_id)
     }
 
     pub async fn request_patch_approval(
@@ -665,6 +806,63 @@ impl Session {
         }
     }
 
+    /// Emit a question for the user and await the answer. Returns `None`
+    /// when the user dismissed it or the turn was aborted.
+    pub(crate) async fn ask_user(
+        &self,
+        sub_id: &str,
+        call_id: String,
+        question: String,
+        kind: UserQuestionKind,
+    ) -> Option<String> {
+        let (tx_answer, rx_answer) = oneshot::channel();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_question(call_id.clone(), tx_answer)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending question for call_id: {call_id}");
+        }
+
+        let event = Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::UserQuestionRequest(UserQuestionRequestEvent {
+                call_id,
+                question,
+                kind,
+            }),
+        };
+        self.send_event(event).await;
+        rx_answer.await.ok().flatten()
+    }
+
+    pub async fn notify_user_answer(&self, call_id: &str, answer: Option<String>) {
+        let entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.remove_pending_question(call_id)
+                }
+                None => None,
+            }
+        };
+        match entry {
+            Some(tx_answer) => {
+                tx_answer.send(answer).ok();
+            }
+            None => {
+                warn!("No pending question found for call_id: {call_id}");
+            }
+        }
+    }
+
     /// Records input items: always append to conversation history and
     /// persist these response items to rollout.
     async fn record_conversation_items(&self, items: &[ResponseItem]) {
@@ -799,6 +997,34 @@ impl Session {
         }
     }
 
+    /// Persist a note from the user to rollout as a `Note` UserMessage and,
+    /// with `include_in_context`, give it to the model. During a turn the
+    /// note waits with the other injected input for the next request, so it
+    /// never lands between a tool call and its output.
+    async fn add_note(&self, text: String, include_in_context: bool) {
+        if include_in_context {
+            let note = ResponseInputItem::from(UserNote::new(text.clone()));
+            let idle_note = match self.active_turn.lock().await.as_mut() {
+                Some(at) => {
+                    at.turn_state.lock().await.push_pending_input(note);
+                    None
+                }
+                None => Some(ResponseItem::from(note)),
+            };
+            if let Some(note) = idle_note {
+                self.record_conversation_items(&[note]).await;
+            }
+        }
+        self.persist_rollout_items(&[RolloutItem::EventMsg(EventMsg::UserMessage(
+            UserMessageEvent {
+                message: text,
+                kind: Some(InputMessageKind::Note),
+                images: None,
+            },
+        ))])
+        .await;
+    }
+
     /// Record a user input item to conversation history and also persist a
     /// corresponding UserMessage EventMsg to rollout.
     async fn record_input_and_rollout_usermsg(&self, response_input: &ResponseInputItem) {
@@ -884,7 +1110,8 @@ impl Session {
             exit_code,
             timed_out: _,
         } = output;
-        // Send full stdout/stderr to clients; do not truncate.
+        // Send stdout/stderr to clients as exec retained them; only the model sees
+        // the head+tail summary.
         let stdout = stdout.text.clone();
         let stderr = stderr.text.clone();
         let formatted_output = format_exec_output_str(output);
@@ -950,14 +1177,22 @@ impl Session {
         self.on_exec_command_begin(turn_diff_tracker.clone(), context.clone())
             .await;
 
-        let result = self
-            .services
-            .executor
-            .run(request, self, approval_policy, &context)
-            .await;
+        let result = match &self.services.replay {
+            Some(Replay::Replay(log)) => log.exec_result(&call_id, &request.mode),
+            _ => {
+                self.services
+                    .executor
+                    .run(request, self, approval_policy, &context)
+                    .await
+            }
+        };
+        if let Some(Replay::Record(recorder)) = &self.services.replay {
+            recorder.record_exec(&call_id, &result);
+        }
 
         let normalized = normalize_exec_result(&result);
         let borrowed = normalized.event_output();
+        let exit_code = borrowed.exit_code;
 
         self.on_exec_command_end(
             turn_diff_tracker,
@@ -970,9 +1205,33 @@ impl Session {
 
         drop(normalized);
 
+        if !is_apply_patch && is_test_command(&context.command_for_display) {
+            self.record_test_run(exit_code == 0).await;
+        }
+
         result
     }
 
+    /// Stream a response to `prompt`, or the next recorded one when
+    /// replaying a session.
+    pub(crate) async fn stream_model(
+        &self,
+        client: &ModelClient,
+        prompt: &Prompt,
+    ) -> CodexResult<ResponseStream> {
+        match &self.services.replay {
+            Some(Replay::Replay(log)) => log.next_response(),
+            Some(Replay::Record(recorder)) => match client.stream(prompt).await {
+                Ok(stream) => Ok(recorder.record_stream(stream)),
+                Err(e) => {
+                    recorder.record_request_error(&e);
+                    Err(e)
+                }
+            },
+            None => client.stream(prompt).await,
+        }
+    }
+
     /// Helper that emits a BackgroundEvent with the given message. This keeps
     /// the call‑sites terse so adding more diagnostics does not clutter the
     /// core agent logic.
@@ -986,11 +1245,31 @@ impl Session {
         self.send_event(event).await;
     }
 
-    async fn notify_stream_error(&self, sub_id: &str, message: impl Into<String>) {
+    /// Emit a background event about content dropped to fit the model's
+    /// capabilities, at most once per session.
+    async fn notify_capability_warning(&self, sub_id: &str, message: String) {
+        {
+            let mut state = self.state.lock().await;
+            if state.capability_warning_sent {
+                return;
+            }
+            state.capability_warning_sent = true;
+        }
+        self.notify_background_event(sub_id, message).await;
+    }
+
+    async fn notify_stream_error(
+        &self,
+        sub_id: &str,
+        message: impl Into<String>,
+        rate_limit_retry_after: Option<Duration>,
+    ) {
         let event = Event {
             id: sub_id.to_string(),
             msg: EventMsg::StreamError(StreamErrorEvent {
                 message: message.into(),
+                rate_limit_retry_after_ms: rate_limit_retry_after
+                    .map(|delay| u64::try_from(delay.as_millis()).unwrap_or(u64::MAX)),
             }),
         };
         self.send_event(event).await;
@@ -1030,6 +1309,29 @@ impl Session {
         }
     }
 
+    /// Count a test-suite run toward the current turn's summary.
+    pub(crate) async fn record_test_run(&self, passed: bool) {
+        let mut active = self.active_turn.lock().await;
+        if let Some(at) = active.as_mut() {
+            let mut ts = at.turn_state.lock().await;
+            ts.record_test_run(passed);
+        }
+    }
+
+    /// Fold the turn's file changes into its summary and return the summary
+    /// when the turn changed or tested anything.
+    pub(crate) async fn finish_turn_summary(
+        &self,
+        turn_diff_tracker: &SharedTurnDiffTracker,
+    ) -> Option<TurnSummary> {
+        let stats = turn_diff_tracker.lock().await.diff_stats();
+        let mut active = self.active_turn.lock().await;
+        let at = active.as_mut()?;
+        let mut ts = at.turn_state.lock().await;
+        ts.set_diff_stats(stats);
+        ts.summary()
+    }
+
     pub async fn call_tool(
         &self,
         server: &str,
@@ -1042,16 +1344,155 @@ impl Session {
             .await
     }
 
+    /// What `Op::GetSessionStatus` replies with.
+    async fn session_status(&self, turn_context: &TurnContext, config: &Config) -> SessionStatus {
+        let (token_usage, mcp_servers) = {
+            let state = self.state.lock().await;
+            (state.token_info.clone(), state.mcp_servers.clone())
+        };
+        let project_docs = discover_project_doc_paths(config).unwrap_or_else(|err| {
+            warn!("failed to list project docs: {err}");
+            Vec::new()
+        });
+        SessionStatus {
+            conversation_id: self.conversation_id,
+            model: turn_context.client.get_model(),
+            model_provider: turn_context.client.get_provider().name,
+            reasoning_effort: turn_context.client.get_reasoning_effort(),
+            cwd: turn_context.cwd.clone(),
+            account: crate::auth::status_account(&config.codex_home),
+            approval_policy: turn_context.approval_policy,
+            sandbox_policy: turn_context.sandbox_policy.clone(),
+            mcp_servers,
+            token_usage,
+            project_docs,
+        }
+    }
+
+    /// Called after a tool call to `server` failed: when the server has gone
+    /// away, it is marked as crashed and the UI is told.
+    pub(crate) async fn note_mcp_tool_call_failure(&self, sub_id: &str, server: &str) {
+        if !self.services.mcp_connection_manager.is_closed(server).await {
+            return;
+        }
+        let servers = {
+            let mut state = self.state.lock().await;
+            match state.mcp_servers.get_mut(server) {
+                Some(server_state) if *server_state == McpServerState::Connected => {
+                    *server_state = McpServerState::Crashed;
+                }
+                _ => return,
+            }
+            state.mcp_servers.clone()
+        };
+        warn!("MCP server `{server}` went away");
+        self.send_event(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::McpServerStatus(McpServerStatusEvent { servers }),
+        })
+        .await;
+    }
+
     pub(crate) fn parse_mcp_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
         self.services
             .mcp_connection_manager
             .parse_tool_name(tool_name)
     }
 
+    /// Why `command` may not run at all, when it falls under the user's
+    /// command deny-list.
+    pub(crate) fn command_deny_list_refusal(&self, command: &[String]) -> Option<String> {
+        self.services.executor.deny_list_refusal(command)
+    }
+
+    /// Remembers the files a successful shell `command` read, so later edits
+    /// made outside the session can be reported. Remote sessions read files
+    /// on another machine and are not tracked.
+    pub(crate) async fn note_files_read_by_command(&self, command: &[String], cwd: &Path) {
+        if self.services.executor.remote().is_some() {
+            return;
+        }
+        let mut state = self.state.lock().await;
+        state.read_files.record_command(command, cwd);
+    }
+
+    /// Records the lines an applied patch added in the repository's
+    /// provenance sidecar when `record_provenance` is on. Remote sessions
+    /// write files on another machine and are not recorded.
+    pub(crate) async fn record_provenance(
+        &self,
+        turn_context: &TurnContext,
+        changes: &HashMap<PathBuf, FileChange>,
+    ) {
+        if !self.services.record_provenance || self.services.executor.remote().is_some() {
+            return;
+        }
+        if let Err(err) = record_patch(
+            &turn_context.cwd,
+            changes,
+            &self.conversation_id.to_string(),
+            &turn_context.client.get_model(),
+        ) {
+            warn!("failed to record provenance: {err}");
+        }
+    }
+
+    /// Why applying a patch that writes `bytes` would go over the session's
+    /// write quota, if it would.
+    pub(crate) async fn write_quota_overrun(&self, bytes: u64) -> Option<String> {
+        self.state.lock().await.write_quota.overrun(bytes)
+    }
+
+    pub(crate) async fn extend_write_quota(&self, bytes: u64) {
+        self.state.lock().await.write_quota.extend(bytes);
+    }
+
+    pub(crate) async fn record_bytes_written(&self, bytes: u64) {
+        self.state.lock().await.write_quota.record(bytes);
+    }
+
+    pub(crate) async fn note_file_read(&self, path: &Path) {
+        let mut state = self.state.lock().await;
+        state.read_files.record_read(path);
+    }
+
+    /// A context item describing edits made outside the session to files
+    /// the agent read, if there were any since it last saw them.
+    async fn external_file_changes_item(&self, cwd: &Path) -> Option<ResponseItem> {
+        let changes = {
+            let mut state = self.state.lock().await;
+            state.read_files.take_external_changes()
+        };
+        (!changes.is_empty()).then(|| external_changes_message(&changes, cwd))
+    }
+
+    /// Takes the files the agent read as it left them at the end of a task.
+    async fn refresh_read_files(&self) {
+        let mut state = self.state.lock().await;
+        state.read_files.refresh();
+    }
+
+    /// Path of this session's rollout file, flushed so that reading it sees
+    /// every item recorded so far.
+    pub(crate) async fn flushed_rollout_path(&self) -> Option<PathBuf> {
+        let recorder = {
+            let guard = self.services.rollout.lock().await;
+            guard.clone()
+        }?;
+        if let Err(e) = recorder.flush().await {
+            warn!("failed to flush rollout recorder before reading it: {e}");
+        }
+        Some(recorder.get_rollout_path())
+    }
+
     pub(crate) async fn handle_exec_command_tool(
         &self,
         params: ExecCommandParams,
     ) -> Result<String, FunctionCallError> {
+        let command = [params.shell.clone(), "-c".to_string(), params.cmd.clone()];
+        if let Some(refusal) = self.command_deny_list_refusal(&command) {
+            return Err(FunctionCallError::RespondToModel(refusal));
+        }
         let result = self
             .services
             .session_manager
@@ -1149,14 +1590,7 @@ async fn submission_loop(
                 let prev = Arc::clone(&turn_context);
                 let provider = prev.client.get_provider();
 
-                // Effective model + family
-                let (effective_model, effective_family) = if let Some(ref m) = model {
-                    let fam =
-                        find_family_for_model(m).unwrap_or_else(|| config.model_family.clone());
-                    (m.clone(), fam)
-                } else {
-                    (prev.client.get_model(), prev.client.get_model_family())
-                };
+                let effective_model = model.clone().unwrap_or_else(|| prev.client.get_model());
 
                 // Effective reasoning settings
                 let effective_effort = effort.unwrap_or(prev.client.get_reasoning_effort());
@@ -1166,11 +1600,9 @@ async fn submission_loop(
 
                 // Build updated config for the client
                 let mut updated_config = (*config).clone();
-                updated_config.model = effective_model.clone();
-                updated_config.model_family = effective_family.clone();
-                if let Some(model_info) = get_model_info(&effective_family) {
-                    updated_config.model_context_window = Some(model_info.context_window);
-                }
+                updated_config.switch_model(&effective_model);
+                autodetect_model_limits(&mut updated_config).await;
+                let effective_family = updated_config.model_family.clone();
 
                 let otel_event_manager = prev.client.get_otel_event_manager().with_model(
                     updated_config.model.as_str(),
@@ -1200,6 +1632,10 @@ async fn submission_loop(
                     include_web_search_request: config.tools_web_search_request,
                     use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                     include_view_image_tool: config.include_view_image_tool,
+                    include_system_info_tool: config.include_system_info_tool,
+                    include_conversation_search_tool: config.include_conversation_search_tool,
+                    include_todos_tool: config.include_todos_tool,
+                    include_ask_user_tool: config.include_ask_user_tool,
                     experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
                 });
 
@@ -1263,17 +1699,11 @@ async fn submission_loop(
                     let provider = turn_context.client.get_provider();
                     let auth_manager = turn_context.client.get_auth_manager();
 
-                    // Derive a model family for the requested model; fall back to the session's.
-                    let model_family = find_family_for_model(&model)
-                        .unwrap_or_else(|| config.model_family.clone());
-
-                    // Create a per‑turn Config clone with the requested model/family.
+                    // Create a per‑turn Config clone with the requested model.
                     let mut per_turn_config = (*config).clone();
-                    per_turn_config.model = model.clone();
-                    per_turn_config.model_family = model_family.clone();
-                    if let Some(model_info) = get_model_info(&model_family) {
-                        per_turn_config.model_context_window = Some(model_info.context_window);
-                    }
+                    per_turn_config.switch_model(&model);
+                    autodetect_model_limits(&mut per_turn_config).await;
+                    let model_family = per_turn_config.model_family.clone();
 
                     let otel_event_manager =
                         turn_context.client.get_otel_event_manager().with_model(
@@ -1303,6 +1733,11 @@ async fn submission_loop(
                             use_streamable_shell_tool: config
                                 .use_experimental_streamable_shell_tool,
                             include_view_image_tool: config.include_view_image_tool,
+                            include_system_info_tool: config.include_system_info_tool,
+                            include_conversation_search_tool: config
+                                .include_conversation_search_tool,
+                            include_todos_tool: config.include_todos_tool,
+                            include_ask_user_tool: config.include_ask_user_tool,
                             experimental_unified_exec_tool: config
                                 .use_experimental_unified_exec_tool,
                         }),
@@ -1349,12 +1784,18 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other).await,
             },
+            Op::ExecApprovalWithEdit { id, command } => {
+                sess.approve_edited_command(&id, command).await;
+            }
             Op::PatchApproval { id, decision } => match decision {
                 ReviewDecision::Abort => {
                     sess.interrupt_task().await;
                 }
                 other => sess.notify_approval(&id, other).await,
             },
+            Op::UserQuestionAnswer { call_id, answer } => {
+                sess.notify_user_answer(&call_id, answer).await;
+            }
             Op::AddToHistory { text } => {
                 let id = sess.conversation_id;
                 let config = config.clone();
@@ -1420,6 +1861,14 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::GetSessionStatus => {
+                let status = sess.session_status(&turn_context, &config).await;
+                sess.send_event(Event {
+                    id: sub.id.clone(),
+                    msg: EventMsg::SessionStatusResponse(status),
+                })
+                .await;
+            }
             Op::ListCustomPrompts => {
                 let sub_id = sub.id.clone();
 
@@ -1438,6 +1887,19 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::UpdatePinnedContext { items } => {
+                sess.state.lock().await.pinned_context = items.clone();
+                sess.persist_rollout_items(&[RolloutItem::PinnedContext(PinnedContextItem {
+                    items,
+                })])
+                .await;
+            }
+            Op::AddNote {
+                text,
+                include_in_context,
+            } => {
+                sess.add_note(text, include_in_context).await;
+            }
             Op::Compact => {
                 // Attempt to inject input into current task
                 if let Err(items) = sess
@@ -1468,6 +1930,7 @@ async fn submission_loop(
                         id: sub.id.clone(),
                         msg: EventMsg::Error(ErrorEvent {
                             message: "Failed to shutdown rollout recorder".to_string(),
+                            kind: ErrorKind::Internal,
                         }),
                     };
                     sess.send_event(event).await;
@@ -1525,6 +1988,20 @@ async fn submission_loop(
     debug!("Agent loop exited");
 }
 
+/// Self-hosted models are usually missing from the built-in metadata; ask
+/// the provider for their limits so context usage is reported against the
+/// right window.
+async fn autodetect_model_limits(config: &mut Config) {
+    if config.model_metadata_autodetect
+        && config.model_context_window.is_none()
+        && let Some(limits) = detect_model_limits(&config.model_provider, &config.model).await
+    {
+        config.model_context_window = limits.context_window;
+        config.model_max_output_tokens =
+            config.model_max_output_tokens.or(limits.max_output_tokens);
+    }
+}
+
 /// Spawn a review thread using the given prompt.
 async fn spawn_review_thread(
     sess: Arc<Session>,
@@ -1533,16 +2010,24 @@ async fn spawn_review_thread(
     sub_id: String,
     review_request: ReviewRequest,
 ) {
-    let model = config.review_model.clone();
-    let review_model_family = find_family_for_model(&model)
-        .unwrap_or_else(|| parent_turn_context.client.get_model_family());
+    // Build per‑turn client with the review model.
+    let mut per_turn_config = (*config).clone();
+    per_turn_config.switch_model(&config.review_model);
+    autodetect_model_limits(&mut per_turn_config).await;
+    per_turn_config.model_reasoning_effort = Some(ReasoningEffortConfig::Low);
+    per_turn_config.model_reasoning_summary = ReasoningSummaryConfig::Detailed;
+
     let tools_config = ToolsConfig::new(&ToolsConfigParams {
-        model_family: &review_model_family,
+        model_family: &per_turn_config.model_family,
         include_plan_tool: false,
         include_apply_patch_tool: config.include_apply_patch_tool,
         include_web_search_request: false,
         use_streamable_shell_tool: false,
         include_view_image_tool: false,
+        include_system_info_tool: false,
+        include_conversation_search_tool: false,
+        include_todos_tool: false,
+        include_ask_user_tool: false,
         experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
     });
 
@@ -1550,17 +2035,6 @@ async fn spawn_review_thread(
     let review_prompt = review_request.prompt.clone();
     let provider = parent_turn_context.client.get_provider();
     let auth_manager = parent_turn_context.client.get_auth_manager();
-    let model_family = review_model_family.clone();
-
-    // Build per‑turn client with the requested model/family.
-    let mut per_turn_config = (*config).clone();
-    per_turn_config.model = model.clone();
-    per_turn_config.model_family = model_family.clone();
-    per_turn_config.model_reasoning_effort = Some(ReasoningEffortConfig::Low);
-    per_turn_config.model_reasoning_summary = ReasoningSummaryConfig::Detailed;
-    if let Some(model_info) = get_model_info(&model_family) {
-        per_turn_config.model_context_window = Some(model_info.context_window);
-    }
 
     let otel_event_manager = parent_turn_context
         .client
@@ -1646,6 +2120,12 @@ pub(crate) async fn run_task(
     };
     sess.send_event(event).await;
 
+    let issue_context = issue_context_for_input(
+        turn_context.client.get_issue_trackers(),
+        &input,
+        &turn_context.cwd,
+    )
+    .await;
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     // For review threads, keep an isolated in-memory history so the
     // model sees a fresh conversation without the parent session's history.
@@ -1660,12 +2140,34 @@ pub(crate) async fn run_task(
         sess.record_input_and_rollout_usermsg(&initial_input_for_turn)
             .await;
     }
+    if !is_review_mode && let Some(item) = sess.external_file_changes_item(&turn_context.cwd).await
+    {
+        sess.record_conversation_items(std::slice::from_ref(&item))
+            .await;
+    }
+    if let Some(issue_context) = issue_context {
+        for note in issue_context.notes {
+            sess.notify_background_event(&sub_id, note).await;
+        }
+        if let Some(message) = issue_context.item {
+            if is_review_mode {
+                review_thread_history.push(message);
+            } else {
+                sess.record_conversation_items(std::slice::from_ref(&message))
+                    .await;
+            }
+        }
+    }
 
     let mut last_agent_message: Option<String> = None;
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
     let mut auto_compact_recently_attempted = false;
+    let mut turn_budget = TurnBudget::new(
+        turn_context.client.get_max_turn_duration(),
+        turn_context.client.get_max_tool_calls_per_turn(),
+    );
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -1851,6 +2353,7 @@ pub(crate) async fn run_task(
                                 message: format!(
                                     "Conversation is still above the token limit after automatic summarization (limit {limit_str}, current {current_tokens}). Please start a new session or trim your input."
                                 ),
+                                kind: ErrorKind::Provider,
                             }),
                         };
                         sess.send_event(event).await;
@@ -1862,19 +2365,54 @@ pub(crate) async fn run_task(
                 }
 
                 auto_compact_recently_attempted = false;
+                turn_budget.record_tool_calls(responses.len());
 
                 if responses.is_empty() {
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
+                    let summary = sess.finish_turn_summary(&turn_diff_tracker).await;
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
                             turn_id: sub_id.clone(),
                             input_messages: turn_input_messages,
                             last_assistant_message: last_agent_message.clone(),
+                            summary: summary.as_ref().map(ToString::to_string),
                         });
                     break;
                 }
+
+                if turn_budget.wrap_up_requested() {
+                    // The model ignored the wrap-up request. Every tool call so far
+                    // has its output recorded, so stopping here stays resumable.
+                    last_agent_message = get_last_assistant_message_from_turn(
+                        &items_to_record_in_conversation_history,
+                    );
+                    sess.finish_turn_summary(&turn_diff_tracker).await;
+                    sess.notify_background_event(
+                        &sub_id,
+                        "Stopped the task: the model kept calling tools after being asked to wrap up. Send a message to continue.",
+                    )
+                    .await;
+                    break;
+                }
+                if let Some(limit) = turn_budget.take_exceeded() {
+                    sess.notify_background_event(
+                        &sub_id,
+                        format!(
+                            "Reached {}; asking the model to summarize its progress and stop.",
+                            limit.describe()
+                        ),
+                    )
+                    .await;
+                    let message = wrap_up_message(limit);
+                    if is_review_mode {
+                        review_thread_history.push(message);
+                    } else {
+                        sess.record_conversation_items(std::slice::from_ref(&message))
+                            .await;
+                    }
+                }
                 continue;
             }
             Err(e) => {
@@ -1883,6 +2421,7 @@ pub(crate) async fn run_task(
                     id: sub_id.clone(),
                     msg: EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
+                        kind: e.kind(),
                     }),
                 };
                 sess.send_event(event).await;
@@ -1906,6 +2445,8 @@ pub(crate) async fn run_task(
             last_agent_message.as_deref().map(parse_review_output_event),
         )
         .await;
+    } else {
+        sess.refresh_read_files().await;
     }
 
     last_agent_message
@@ -1941,7 +2482,7 @@ async fn run_turn(
     turn_context: Arc<TurnContext>,
     turn_diff_tracker: SharedTurnDiffTracker,
     sub_id: String,
-    input: Vec<ResponseItem>,
+    mut input: Vec<ResponseItem>,
 ) -> CodexResult<TurnRunResult> {
     let mcp_tools = sess.services.mcp_connection_manager.list_all_tools();
     let router = Arc::new(ToolRouter::from_config(
@@ -1949,14 +2490,18 @@ async fn run_turn(
         Some(mcp_tools),
     ));
 
-    let model_supports_parallel = turn_context
-        .client
-        .get_model_family()
-        .supports_parallel_tool_calls;
-    let parallel_tool_calls = model_supports_parallel;
+    let model_family = turn_context.client.get_model_family();
+    let mut tools = router.specs();
+    let adaptation = adapt_prompt_for_model(&model_family, &mut input, &mut tools);
+    if let Some(message) = adaptation.warning_message(&turn_context.client.get_model()) {
+        warn!("{message}");
+        sess.notify_capability_warning(&sub_id, message).await;
+    }
+
+    let parallel_tool_calls = model_family.supports_parallel_tool_calls;
     let prompt = Prompt {
         input,
-        tools: router.specs(),
+        tools,
         parallel_tool_calls,
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: turn_context.final_output_json_schema.clone(),
@@ -1999,6 +2544,7 @@ async fn run_turn(
                         CodexErr::Stream(_, Some(delay)) => delay,
                         _ => backoff(retries),
                     };
+                    let rate_limit_retry_after = e.is_rate_limit().then_some(delay);
                     warn!(
                         "stream disconnected - retrying turn ({retries}/{max_retries} in {delay:?})...",
                     );
@@ -2009,6 +2555,7 @@ async fn run_turn(
                     sess.notify_stream_error(
                         &sub_id,
                         format!("Re-connecting... {retries}/{max_retries}"),
+                        rate_limit_retry_after,
                     )
                     .await;
 
@@ -2109,7 +2656,7 @@ async fn try_run_turn(
         summary: turn_context.client.get_reasoning_summary(),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
-    let mut stream = turn_context.client.clone().stream(&prompt).await?;
+    let mut stream = sess.stream_model(&turn_context.client, &prompt).await?;
 
     let tool_runtime = ToolCallRuntime::new(
         Arc::clone(&router),
@@ -2444,6 +2991,8 @@ use crate::tools::context::ApplyPatchCommandContext;
 use crate::tools::context::ExecCommandContext;
 #[cfg(test)]
 pub(crate) use tests::make_session_and_context;
+#[cfg(test)]
+pub(crate) use tests::make_session_and_context_with_rx;
 
 #[cfg(test)]
 mod tests {
@@ -2520,6 +3069,26 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn record_initial_history_restores_the_last_pinned_context() {
+        let (session, turn_context) = make_session_and_context();
+        let (mut rollout_items, _) = sample_rollout(&session, &turn_context);
+        rollout_items.push(RolloutItem::PinnedContext(PinnedContextItem {
+            items: vec!["first".to_string(), "second".to_string()],
+        }));
+        rollout_items.push(RolloutItem::PinnedContext(PinnedContextItem {
+            items: vec!["second".to_string()],
+        }));
+
+        tokio_test::block_on(
+            session.record_initial_history(&turn_context, InitialHistory::Forked(rollout_items)),
+        );
+
+        let pinned =
+            tokio_test::block_on(async { session.state.lock().await.pinned_context.clone() });
+        assert_eq!(vec!["second".to_string()], pinned);
+    }
+
     #[test]
     fn prefers_structured_content_when_present() {
         let ctr = CallToolResult {
@@ -2753,6 +3322,10 @@ mod tests {
             include_web_search_request: config.tools_web_search_request,
             use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
             include_view_image_tool: config.include_view_image_tool,
+            include_system_info_tool: config.include_system_info_tool,
+            include_conversation_search_tool: config.include_conversation_search_tool,
+            include_todos_tool: config.include_todos_tool,
+            include_ask_user_tool: config.include_ask_user_tool,
             experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
         });
         let turn_context = TurnContext {
@@ -2775,10 +3348,18 @@ mod tests {
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            record_provenance: config.record_provenance,
+            structured_failures: config.structured_failures,
+            replay: None,
+            verify_commands: Vec::new(),
             executor: Executor::new(ExecutorConfig::new(
                 turn_context.sandbox_policy.clone(),
                 turn_context.cwd.clone(),
                 None,
+                None,
+                None,
+                CommandDenyList::default(),
+                None,
             )),
         };
         let session = Session {
@@ -2794,7 +3375,7 @@ mod tests {
 
     // Like make_session_and_context, but returns Arc<Session> and the event receiver
     // so tests can assert on emitted events.
-    fn make_session_and_context_with_rx() -> (
+    pub(crate) fn make_session_and_context_with_rx() -> (
         Arc<Session>,
         Arc<TurnContext>,
         async_channel::Receiver<Event>,
@@ -2826,6 +3407,10 @@ mod tests {
             include_web_search_request: config.tools_web_search_request,
             use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
             include_view_image_tool: config.include_view_image_tool,
+            include_system_info_tool: config.include_system_info_tool,
+            include_conversation_search_tool: config.include_conversation_search_tool,
+            include_todos_tool: config.include_todos_tool,
+            include_ask_user_tool: config.include_ask_user_tool,
             experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
         });
         let turn_context = Arc::new(TurnContext {
@@ -2848,10 +3433,18 @@ mod tests {
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            record_provenance: config.record_provenance,
+            structured_failures: config.structured_failures,
+            replay: None,
+            verify_commands: Vec::new(),
             executor: Executor::new(ExecutorConfig::new(
                 config.sandbox_policy.clone(),
                 config.cwd.clone(),
                 None,
+                None,
+                None,
+                CommandDenyList::default(),
+                None,
             )),
         };
         let session = Arc::new(Session {
@@ -2893,6 +3486,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn unbounded_events_do_not_wait_for_a_listener() {
+        let event = || Event {
+            id: "sub".to_string(),
+            msg: EventMsg::ShutdownComplete,
+        };
+
+        let (tx_event, _rx_event) = EventBuffer::Unbounded.channel();
+        let sends = async {
+            for _ in 0..=EVENT_CHANNEL_CAPACITY {
+                tx_event.send(event()).await.expect("send");
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), sends)
+            .await
+            .expect("sending without a listener should not block");
+
+        let (tx_event, _rx_event) = EventBuffer::Bounded.channel();
+        for _ in 0..EVENT_CHANNEL_CAPACITY {
+            tx_event.try_send(event()).expect("send");
+        }
+        assert!(tx_event.try_send(event()).is_err());
+    }
+
     #[tokio::test]
     async fn abort_regular_task_emits_turn_aborted_only() {
         let (sess, tc, rx) = make_session_and_context_with_rx();
@@ -27,6 +27,7 @@ use futures::prelude::*;
 
 pub const SUMMARIZATION_PROMPT: &str = include_str!("../../templates/compact/prompt.md");
 const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
+const PINNED_CONTEXT_PREFIX: &str = "The user pinned the following excerpts from earlier in this conversation. They were kept verbatim when the history was summarized; treat them as authoritative.";
 
 #[derive(Template)]
 #[template(path = "compact/history_bridge.md", escape = "none")]
@@ -125,6 +126,7 @@ async fn run_compact_task_inner(
                     id: sub_id.clone(),
                     msg: EventMsg::Error(ErrorEvent {
                         message: e.to_string(),
+                        kind: e.kind(),
                     }),
                 };
                 sess.send_event(event).await;
@@ -137,6 +139,7 @@ async fn run_compact_task_inner(
                     sess.notify_stream_error(
                         &sub_id,
                         format!("Re-connecting... {retries}/{max_retries}"),
+                        e.is_rate_limit().then_some(delay),
                     )
                     .await;
                     tokio::time::sleep(delay).await;
@@ -146,6 +149,7 @@ async fn run_compact_task_inner(
                         id: sub_id.clone(),
                         msg: EventMsg::Error(ErrorEvent {
                             message: e.to_string(),
+                            kind: e.kind(),
                         }),
                     };
                     sess.send_event(event).await;
@@ -159,7 +163,9 @@ async fn run_compact_task_inner(
     let summary_text = get_last_assistant_message_from_turn(&history_snapshot).unwrap_or_default();
     let user_messages = collect_user_messages(&history_snapshot);
     let initial_context = sess.build_initial_context(turn_context.as_ref());
-    let new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    let mut new_history = build_compacted_history(initial_context, &user_messages, &summary_text);
+    let pinned_context = sess.state.lock().await.pinned_context.clone();
+    new_history.extend(build_pinned_context_message(&pinned_context));
     sess.replace_history(new_history).await;
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
@@ -252,13 +258,33 @@ pub(crate) fn build_compacted_history(
     history
 }
 
+/// Wrap the user's pinned excerpts in a message that survives compaction
+/// verbatim. Returns `None` when nothing is pinned.
+pub(crate) fn build_pinned_context_message(pinned: &[String]) -> Option<ResponseItem> {
+    if pinned.is_empty() {
+        return None;
+    }
+    let excerpts = pinned
+        .iter()
+        .map(|item| format!("<pinned>\n{}\n</pinned>", item.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!("{PINNED_CONTEXT_PREFIX}\n\n{excerpts}"),
+        }],
+    })
+}
+
 async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
     sub_id: &str,
     prompt: &Prompt,
 ) -> CodexResult<()> {
-    let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let mut stream = sess.stream_model(&turn_context.client, prompt).await?;
     loop {
         let maybe_event = stream.next().await;
         let Some(event) = maybe_event else {
@@ -414,4 +440,28 @@ mod tests {
             "bridge should include the provided summary text"
         );
     }
+
+    #[test]
+    fn pinned_context_message_wraps_each_excerpt() {
+        assert_eq!(build_pinned_context_message(&[]), None);
+
+        let message = build_pinned_context_message(&[
+            "Use SQLite for the cache.".to_string(),
+            "  Plan: 1. migrate 2. backfill  ".to_string(),
+        ])
+        .expect("pinned message");
+        let text = match &message {
+            ResponseItem::Message { role, content, .. } if role == "user" => {
+                content_items_to_text(content).unwrap_or_default()
+            }
+            other => panic!("unexpected item: {other:?}"),
+        };
+
+        assert_eq!(
+            text,
+            format!(
+                "{PINNED_CONTEXT_PREFIX}\n\n<pinned>\nUse SQLite for the cache.\n</pinned>\n\n<pinned>\nPlan: 1. migrate 2. backfill\n</pinned>"
+            )
+        );
+    }
 }
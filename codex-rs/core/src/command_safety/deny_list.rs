@@ -0,0 +1,153 @@
+//! Commands the user never wants the agent to run, whatever the approval
+//! policy: `git push --force`, `kubectl delete ns`, `terraform apply`.
+//!
+//! A rule is a program followed by words that must all appear after it, in
+//! order but not necessarily adjacent, so `git push --force` also catches
+//! `git push origin main --force` and `sudo /usr/bin/git push --force`.
+//! Shell scripts passed with `-c`/`-lc` are split into words and checked as
+//! a whole, which errs on the side of refusing.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandDenyList {
+    rules: Vec<DenyRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DenyRule {
+    pattern: String,
+    words: Vec<String>,
+}
+
+impl CommandDenyList {
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let rules = patterns
+            .into_iter()
+            .filter_map(|pattern| {
+                let pattern = pattern.as_ref().trim();
+                let words = shlex::split(pattern)
+                    .unwrap_or_else(|| pattern.split_whitespace().map(str::to_string).collect());
+                (!words.is_empty()).then(|| DenyRule {
+                    pattern: pattern.to_string(),
+                    words,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// The first rule `command` falls under, as the user wrote it.
+    pub fn matching(&self, command: &[String]) -> Option<&str> {
+        if self.rules.is_empty() {
+            return None;
+        }
+        let words = command_words(command);
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(&words))
+            .map(|rule| rule.pattern.as_str())
+    }
+}
+
+impl DenyRule {
+    fn matches(&self, words: &[String]) -> bool {
+        let Some((program, rest)) = self.words.split_first() else {
+            return false;
+        };
+        words.iter().enumerate().any(|(index, word)| {
+            program_name(word) == program.as_str() && is_subsequence(rest, &words[index + 1..])
+        })
+    }
+}
+
+/// `command` as a flat list of words, with the script of `sh -c <script>`
+/// style invocations split into its words.
+fn command_words(command: &[String]) -> Vec<String> {
+    let script = match command {
+        [shell, flag, script] if is_shell(shell) && matches!(flag.as_str(), "-c" | "-lc") => {
+            Some(script)
+        }
+        _ => None,
+    };
+    let Some(script) = script else {
+        return command.to_vec();
+    };
+    let words = shlex::split(script)
+        .unwrap_or_else(|| script.split_whitespace().map(str::to_string).collect());
+    words
+        .iter()
+        .flat_map(|word| word.split([';', '&', '|', '(', ')', '`']))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_shell(program: &str) -> bool {
+    matches!(
+        program_name(program),
+        "bash" | "sh" | "zsh" | "dash" | "fish" | "ksh"
+    )
+}
+
+fn program_name(word: &str) -> &str {
+    Path::new(word)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(word)
+}
+
+fn is_subsequence(needle: &[String], haystack: &[String]) -> bool {
+    let mut haystack = haystack.iter();
+    needle
+        .iter()
+        .all(|word| haystack.any(|candidate| candidate == word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    #[test]
+    fn matches_rules_in_argv_and_scripts() {
+        let deny_list =
+            CommandDenyList::new(["git push --force", "kubectl delete ns", "terraform apply"]);
+
+        assert_eq!(
+            deny_list.matching(&vec_str(&["git", "push", "origin", "main", "--force"])),
+            Some("git push --force")
+        );
+        assert_eq!(
+            deny_list.matching(&vec_str(&["sudo", "/usr/bin/git", "push", "--force"])),
+            Some("git push --force")
+        );
+        assert_eq!(
+            deny_list.matching(&vec_str(&[
+                "bash",
+                "-lc",
+                "cd infra&&terraform apply -auto-approve"
+            ])),
+            Some("terraform apply")
+        );
+        assert_eq!(
+            deny_list.matching(&vec_str(&["kubectl", "-n", "x", "delete", "ns", "staging"])),
+            Some("kubectl delete ns")
+        );
+
+        assert_eq!(deny_list.matching(&vec_str(&["git", "push"])), None);
+        assert_eq!(deny_list.matching(&vec_str(&["terraform", "plan"])), None);
+        assert_eq!(
+            deny_list.matching(&vec_str(&["bash", "-lc", "echo terraform"])),
+            None
+        );
+    }
+}
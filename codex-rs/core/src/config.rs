@@ -1,22 +1,39 @@
+use crate::config_edit::edit_config;
+use crate::config_edit::edit_config_async;
 use crate::config_loader::LoadedConfigLayers;
 pub use crate::config_loader::load_config_as_toml;
 use crate::config_loader::load_config_layers_with_overrides;
 use crate::config_loader::merge_toml_values;
 use crate::config_profile::ConfigProfile;
 use crate::config_types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config_types::EnvironmentVariablePattern;
 use crate::config_types::History;
+use crate::config_types::HistoryPersistence;
+use crate::config_types::IssueTrackers;
+use crate::config_types::KubernetesExec;
 use crate::config_types::McpServerConfig;
 use crate::config_types::McpServerTransportConfig;
+use crate::config_types::ModelOverride;
+use crate::config_types::ModelPricing;
 use crate::config_types::Notifications;
 use crate::config_types::OtelConfig;
 use crate::config_types::OtelConfigToml;
 use crate::config_types::OtelExporterKind;
+use crate::config_types::OutputFilters;
+use crate::config_types::PackageInstalls;
+use crate::config_types::ProjectEnvToml;
 use crate::config_types::ReasoningSummaryFormat;
+use crate::config_types::RemoteWorkspace;
 use crate::config_types::SandboxWorkspaceWrite;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
+use crate::config_types::StatusLineConfig;
+use crate::config_types::StatusLineToml;
 use crate::config_types::Tui;
 use crate::config_types::UriBasedFileOpener;
+use crate::config_types::VerifyCommands;
+use crate::devcontainer::Devcontainer;
+use crate::devcontainer::find_devcontainer;
 use crate::git_info::resolve_root_git_project_for_trust;
 use crate::model_family::ModelFamily;
 use crate::model_family::derive_default_model_family;
@@ -24,8 +41,13 @@ use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::built_in_model_providers;
 use crate::openai_model_info::get_model_info;
+use crate::project_env::ProjectEnv;
+use crate::project_env::find_project_env;
+use crate::project_env::load_project_env;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::replay::ReplayMode;
+use crate::state_home::StateHome;
 use anyhow::Context;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
@@ -41,8 +63,8 @@ use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use tempfile::NamedTempFile;
 use toml::Value as TomlValue;
 use toml_edit::Array as TomlArray;
 use toml_edit::DocumentMut;
@@ -63,6 +85,9 @@ pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 
 pub(crate) const CONFIG_TOML_FILE: &str = "config.toml";
 
+/// Default for `tui.idle_after_secs`.
+const DEFAULT_TUI_IDLE_AFTER_SECS: u64 = 120;
+
 /// Application configuration loaded from disk and merged with overrides.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Config {
@@ -83,6 +108,30 @@ pub struct Config {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Per-model token limit overrides keyed by model slug. Consulted again
+    /// whenever the model changes mid-session.
+    pub model_overrides: HashMap<String, ModelOverride>,
+
+    /// Per-model prices keyed by model slug, consulted before the built-in
+    /// price list when estimating costs.
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// When `true`, query the provider's `/models` endpoint at startup to
+    /// discover the context window of models Codex has no metadata for.
+    pub model_metadata_autodetect: bool,
+
+    /// Wall-clock budget for a single task. Once exceeded, the model is asked
+    /// to summarize its progress and stop instead of being cut off.
+    pub max_turn_duration: Option<Duration>,
+
+    /// Maximum number of tool calls in a single task before the model is
+    /// asked to wrap up.
+    pub max_tool_calls_per_turn: Option<u32>,
+
+    /// Bytes the agent may write to files with `apply_patch` in one session
+    /// before the user is asked to allow more.
+    pub max_session_write_bytes: Option<u64>,
+
     /// Key into the model_providers map that specifies which provider to use.
     pub model_provider_id: String,
 
@@ -137,6 +186,32 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
+    /// Show free disk, memory and CPU load in the TUI status line.
+    pub tui_system_resources: bool,
+
+    /// Time without input, while no task runs, after which the TUI stops
+    /// scheduling animation frames; `None` keeps animating.
+    pub tui_idle_after: Option<Duration>,
+
+    /// Time without input after which the TUI hides the transcript behind a
+    /// lock screen; `None` never locks.
+    pub tui_lock_after: Option<Duration>,
+
+    /// Capture the mouse in the TUI, for clickable status line segments.
+    pub tui_mouse: bool,
+
+    /// Keep notes added with `/note` out of the model's context.
+    pub tui_private_notes: bool,
+
+    /// Record and replay keyboard macros in the transcript overlay.
+    pub tui_macros: bool,
+
+    /// Commands the TUI pipes diffs and JSON tool results through.
+    pub tui_output_filters: OutputFilters,
+
+    /// Layout and custom segments of the TUI status line.
+    pub statusline: StatusLineConfig,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -167,9 +242,66 @@ pub struct Config {
     /// overridden by the `CODEX_HOME` environment variable).
     pub codex_home: PathBuf,
 
+    /// Where sessions, logs and message history are written. Falls back to a
+    /// temporary directory when `CODEX_STATE_HOME`/`CODEX_HOME` is read-only.
+    pub state_home: StateHome,
+
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Issue trackers that `@gh#123`-style references are fetched from.
+    pub issue_trackers: IssueTrackers,
+
+    /// When set, shell commands and `apply_patch` run on this host over SSH
+    /// and `cwd` is the remote working directory.
+    pub remote: Option<RemoteWorkspace>,
+
+    /// Devcontainer configuration found for `cwd`, if any.
+    pub devcontainer: Option<Devcontainer>,
+
+    /// Whether shell commands run inside `devcontainer`: the choice recorded
+    /// for the project, or `None` when the user has not been asked.
+    pub use_devcontainer: Option<bool>,
+
+    /// When set, shell commands run in this pod via `kubectl exec`, with the
+    /// context pinned when the configuration was loaded.
+    pub kubernetes_exec: Option<KubernetesExec>,
+
+    /// Commands that are refused before they reach the sandbox, whatever the
+    /// approval policy: the global `command_deny_list` followed by the
+    /// project's.
+    pub command_deny_list: Vec<String>,
+
+    /// Recording of, and approval for, packages the agent installs.
+    pub package_installs: PackageInstalls,
+
+    /// Verify commands suggested for the detected build systems.
+    pub verify: VerifyCommands,
+
+    /// When true, lines the agent writes are recorded in the repository's
+    /// `.codex-provenance.json` for `codex blame`.
+    pub record_provenance: bool,
+
+    /// Record this session to, or replay it from, a replay log. Set by
+    /// callers such as `codex exec --record-replay`; never read from
+    /// `config.toml`.
+    pub replay: Option<ReplayMode>,
+
+    /// Variable name patterns that may be loaded from the project's
+    /// `.env`/`.envrc`; empty when the feature is off.
+    pub project_env_allow: Vec<EnvironmentVariablePattern>,
+
+    /// `.env`/`.envrc` found for `cwd` while `project_env_allow` is set.
+    pub project_env: Option<ProjectEnv>,
+
+    /// Whether the user trusts the current contents of `project_env`, or
+    /// `None` when they have not been asked since the files last changed.
+    pub project_env_trusted: Option<bool>,
+
+    /// Names of the variables loaded from `project_env` into
+    /// `shell_environment_policy`.
+    pub project_env_vars: Vec<String>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -218,6 +350,25 @@ pub struct Config {
     /// Include the `view_image` tool that lets the agent attach a local image path to context.
     pub include_view_image_tool: bool,
 
+    /// Include the `system_info` tool reporting CPU load, memory, disk space
+    /// of the workspace volume and GPUs.
+    pub include_system_info_tool: bool,
+
+    /// Include the `conversation_search` tool that searches this session's
+    /// recorded history, including parts lost to compaction.
+    pub include_conversation_search_tool: bool,
+
+    /// Include the `todos` tool that lists TODO, FIXME and HACK comments in
+    /// the workspace.
+    pub include_todos_tool: bool,
+
+    /// Include the `ask_user` tool that asks the user a structured question.
+    pub include_ask_user_tool: bool,
+
+    /// Attach a structured `failures` list, parsed from the output, to failed
+    /// runs of well-known build and test commands.
+    pub structured_failures: bool,
+
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
@@ -342,87 +493,174 @@ fn ensure_no_inline_bearer_tokens(value: &TomlValue) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Replace the `[mcp_servers]` table of `CODEX_HOME/config.toml` with
+/// `servers`. Prefer [`update_global_mcp_servers`] for adding or removing
+/// individual servers, so concurrent changes are not lost.
 pub fn write_global_mcp_servers(
     codex_home: &Path,
     servers: &BTreeMap<String, McpServerConfig>,
 ) -> std::io::Result<()> {
-    let config_path = codex_home.join(CONFIG_TOML_FILE);
-    let mut doc = match std::fs::read_to_string(&config_path) {
-        Ok(contents) => contents
-            .parse::<DocumentMut>()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
-        Err(e) => return Err(e),
+    edit_config(codex_home, |doc| {
+        set_mcp_servers_in_document(doc, servers);
+        Ok(())
+    })
+    .map_err(into_io_error)
+}
+
+/// Apply `update` to the MCP servers in `CODEX_HOME/config.toml` and write
+/// the result back. The servers are read under the config lock, so changes
+/// made by other Codex processes in the meantime are merged rather than
+/// overwritten. Returns what `update` returned.
+pub async fn update_global_mcp_servers<R>(
+    codex_home: &Path,
+    update: impl FnOnce(&mut BTreeMap<String, McpServerConfig>) -> R,
+) -> std::io::Result<R> {
+    edit_config_async(codex_home, |doc| {
+        let mut servers = mcp_servers_from_document(doc)?;
+        let result = update(&mut servers);
+        set_mcp_servers_in_document(doc, &servers);
+        Ok(result)
+    })
+    .await
+    .map_err(into_io_error)
+}
+
+/// Keep the `ErrorKind` of I/O errors that went through [`edit_config`].
+fn into_io_error(err: anyhow::Error) -> std::io::Error {
+    err.downcast().unwrap_or_else(std::io::Error::other)
+}
+
+fn mcp_servers_from_document(
+    doc: &DocumentMut,
+) -> std::io::Result<BTreeMap<String, McpServerConfig>> {
+    let root_value: TomlValue = toml::from_str(&doc.to_string())
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+    let Some(servers_value) = root_value.get("mcp_servers") else {
+        return Ok(BTreeMap::new());
     };
 
-    doc.as_table_mut().remove("mcp_servers");
+    ensure_no_inline_bearer_tokens(servers_value)?;
 
-    if !servers.is_empty() {
+    servers_value
+        .clone()
+        .try_into()
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Make the `[mcp_servers]` table of `doc` describe exactly `servers`.
+/// Existing entries are updated in place so comments on them survive.
+fn set_mcp_servers_in_document(doc: &mut DocumentMut, servers: &BTreeMap<String, McpServerConfig>) {
+    if servers.is_empty() {
+        doc.as_table_mut().remove("mcp_servers");
+        return;
+    }
+
+    if !doc.get("mcp_servers").is_some_and(TomlItem::is_table) {
         let mut table = TomlTable::new();
         table.set_implicit(true);
         doc["mcp_servers"] = TomlItem::Table(table);
+    }
+    let Some(servers_table) = doc["mcp_servers"].as_table_mut() else {
+        return;
+    };
+    servers_table.retain(|name, _| servers.contains_key(name));
 
-        for (name, config) in servers {
+    for (name, config) in servers {
+        if !servers_table.get(name).is_some_and(TomlItem::is_table) {
             let mut entry = TomlTable::new();
             entry.set_implicit(false);
-            match &config.transport {
-                McpServerTransportConfig::Stdio { command, args, env } => {
-                    entry["command"] = toml_edit::value(command.clone());
-
-                    if !args.is_empty() {
-                        let mut args_array = TomlArray::new();
-                        for arg in args {
-                            args_array.push(arg.clone());
-                        }
-                        entry["args"] = TomlItem::Value(args_array.into());
-                    }
+            servers_table.insert(name, TomlItem::Table(entry));
+        }
+        let Some(entry) = servers_table.get_mut(name).and_then(TomlItem::as_table_mut) else {
+            continue;
+        };
 
-                    if let Some(env) = env
-                        && !env.is_empty()
-                    {
-                        let mut env_table = TomlTable::new();
-                        env_table.set_implicit(false);
-                        let mut pairs: Vec<_> = env.iter().collect();
-                        pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
-                        for (key, value) in pairs {
-                            env_table.insert(key, toml_edit::value(value.clone()));
+        match &config.transport {
+            McpServerTransportConfig::Stdio { command, args, env } => {
+                set_toml_value(entry, "command", Some(command.as_str().into()));
+                let args_value = (!args.is_empty()).then(|| {
+                    let mut args_array = TomlArray::new();
+                    for arg in args {
+                        args_array.push(arg.clone());
+                    }
+                    toml_edit::Value::from(args_array)
+                });
+                set_toml_value(entry, "args", args_value);
+
+                match env {
+                    Some(env) if !env.is_empty() => {
+                        if !entry.get("env").is_some_and(TomlItem::is_table) {
+                            let mut env_table = TomlTable::new();
+                            env_table.set_implicit(false);
+                            entry.insert("env", TomlItem::Table(env_table));
+                        }
+                        if let Some(env_table) =
+                            entry.get_mut("env").and_then(TomlItem::as_table_mut)
+                        {
+                            env_table.retain(|key, _| env.contains_key(key));
+                            let mut pairs: Vec<_> = env.iter().collect();
+                            pairs.sort_by(|(a, _), (b, _)| a.cmp(b));
+                            for (key, value) in pairs {
+                                set_toml_value(env_table, key, Some(value.as_str().into()));
+                            }
                         }
-                        entry["env"] = TomlItem::Table(env_table);
                     }
-                }
-                McpServerTransportConfig::StreamableHttp {
-                    url,
-                    bearer_token_env_var,
-                } => {
-                    entry["url"] = toml_edit::value(url.clone());
-                    if let Some(env_var) = bearer_token_env_var {
-                        entry["bearer_token_env_var"] = toml_edit::value(env_var.clone());
+                    _ => {
+                        entry.remove("env");
                     }
                 }
+                entry.remove("url");
+                entry.remove("bearer_token_env_var");
             }
-
-            if !config.enabled {
-                entry["enabled"] = toml_edit::value(false);
-            }
-
-            if let Some(timeout) = config.startup_timeout_sec {
-                entry["startup_timeout_sec"] = toml_edit::value(timeout.as_secs_f64());
-            }
-
-            if let Some(timeout) = config.tool_timeout_sec {
-                entry["tool_timeout_sec"] = toml_edit::value(timeout.as_secs_f64());
+            McpServerTransportConfig::StreamableHttp {
+                url,
+                bearer_token_env_var,
+            } => {
+                set_toml_value(entry, "url", Some(url.as_str().into()));
+                set_toml_value(
+                    entry,
+                    "bearer_token_env_var",
+                    bearer_token_env_var.as_deref().map(Into::into),
+                );
+                entry.remove("command");
+                entry.remove("args");
+                entry.remove("env");
             }
-
-            doc["mcp_servers"][name.as_str()] = TomlItem::Table(entry);
         }
-    }
 
-    std::fs::create_dir_all(codex_home)?;
-    let tmp_file = NamedTempFile::new_in(codex_home)?;
-    std::fs::write(tmp_file.path(), doc.to_string())?;
-    tmp_file.persist(config_path).map_err(|err| err.error)?;
+        set_toml_value(entry, "enabled", (!config.enabled).then(|| false.into()));
+        set_toml_value(
+            entry,
+            "startup_timeout_sec",
+            config
+                .startup_timeout_sec
+                .map(|timeout| timeout.as_secs_f64().into()),
+        );
+        // Superseded by `startup_timeout_sec`, which is always written.
+        entry.remove("startup_timeout_ms");
+        set_toml_value(
+            entry,
+            "tool_timeout_sec",
+            config
+                .tool_timeout_sec
+                .map(|timeout| timeout.as_secs_f64().into()),
+        );
+    }
+}
 
-    Ok(())
+/// Set `key` in `table` to `value`, or remove it when `value` is `None`. An
+/// existing value keeps its decoration, such as a trailing comment.
+fn set_toml_value(table: &mut TomlTable, key: &str, value: Option<toml_edit::Value>) {
+    let Some(mut value) = value else {
+        table.remove(key);
+        return;
+    };
+    if let Some(existing) = table.get_mut(key).and_then(TomlItem::as_value_mut) {
+        *value.decor_mut() = existing.decor().clone();
+        *existing = value;
+    } else {
+        table.insert(key, TomlItem::Value(value));
+    }
 }
 
 fn set_project_trusted_inner(doc: &mut DocumentMut, project_path: &Path) -> anyhow::Result<()> {
@@ -436,6 +674,16 @@ fn set_project_trusted_inner(doc: &mut DocumentMut, project_path: &Path) -> anyh
     //
     // [projects]
     // "/path/to/project" = { trust_level = "trusted" }
+    let proj_tbl = project_table_mut(doc, project_path)?;
+    proj_tbl["trust_level"] = toml_edit::value("trusted");
+    Ok(())
+}
+
+/// The explicit `[projects."<project_path>"]` table, created if needed.
+fn project_table_mut<'a>(
+    doc: &'a mut DocumentMut,
+    project_path: &Path,
+) -> anyhow::Result<&'a mut TomlTable> {
     let project_key = project_path.to_string_lossy().to_string();
 
     // Ensure top-level `projects` exists as a non-inline, explicit table. If it
@@ -486,34 +734,44 @@ fn set_project_trusted_inner(doc: &mut DocumentMut, project_path: &Path) -> anyh
         return Err(anyhow::anyhow!("project table missing for {project_key}"));
     };
     proj_tbl.set_implicit(false);
-    proj_tbl["trust_level"] = toml_edit::value("trusted");
-    Ok(())
+    Ok(proj_tbl)
 }
 
 /// Patch `CODEX_HOME/config.toml` project state.
 /// Use with caution.
 pub fn set_project_trusted(codex_home: &Path, project_path: &Path) -> anyhow::Result<()> {
-    let config_path = codex_home.join(CONFIG_TOML_FILE);
-    // Parse existing config if present; otherwise start a new document.
-    let mut doc = match std::fs::read_to_string(config_path.clone()) {
-        Ok(s) => s.parse::<DocumentMut>()?,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
-        Err(e) => return Err(e.into()),
-    };
-
-    set_project_trusted_inner(&mut doc, project_path)?;
-
-    // ensure codex_home exists
-    std::fs::create_dir_all(codex_home)?;
-
-    // create a tmp_file
-    let tmp_file = NamedTempFile::new_in(codex_home)?;
-    std::fs::write(tmp_file.path(), doc.to_string())?;
+    edit_config(codex_home, |doc| {
+        set_project_trusted_inner(doc, project_path)
+    })
+}
 
-    // atomically move the tmp file into config.toml
-    tmp_file.persist(config_path)?;
+/// Record whether shell commands for the project at `project_path` run in
+/// its devcontainer.
+pub fn set_project_devcontainer(
+    codex_home: &Path,
+    project_path: &Path,
+    enabled: bool,
+) -> anyhow::Result<()> {
+    edit_config(codex_home, |doc| {
+        project_table_mut(doc, project_path)?["devcontainer"] = toml_edit::value(enabled);
+        Ok(())
+    })
+}
 
-    Ok(())
+/// Record whether the user trusts the environment files of `project_env`
+/// with their current contents.
+pub fn set_project_env_trust(
+    codex_home: &Path,
+    project_env: &ProjectEnv,
+    trusted: bool,
+) -> anyhow::Result<()> {
+    edit_config(codex_home, |doc| {
+        let mut trust = toml_edit::InlineTable::new();
+        trust.insert("digest", project_env.digest.as_str().into());
+        trust.insert("trusted", trusted.into());
+        project_table_mut(doc, &project_env.root)?["env_trust"] = toml_edit::value(trust);
+        Ok(())
+    })
 }
 
 /// Persist the acknowledgement flag for the Windows onboarding screen.
@@ -521,22 +779,10 @@ pub fn set_windows_wsl_setup_acknowledged(
     codex_home: &Path,
     acknowledged: bool,
 ) -> anyhow::Result<()> {
-    let config_path = codex_home.join(CONFIG_TOML_FILE);
-    let mut doc = match std::fs::read_to_string(config_path.clone()) {
-        Ok(s) => s.parse::<DocumentMut>()?,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
-        Err(e) => return Err(e.into()),
-    };
-
-    doc["windows_wsl_setup_acknowledged"] = toml_edit::value(acknowledged);
-
-    std::fs::create_dir_all(codex_home)?;
-
-    let tmp_file = NamedTempFile::new_in(codex_home)?;
-    std::fs::write(tmp_file.path(), doc.to_string())?;
-    tmp_file.persist(config_path)?;
-
-    Ok(())
+    edit_config(codex_home, |doc| {
+        doc["windows_wsl_setup_acknowledged"] = toml_edit::value(acknowledged);
+        Ok(())
+    })
 }
 
 fn ensure_profile_table<'a>(
@@ -596,58 +842,148 @@ pub async fn persist_model_selection(
     model: &str,
     effort: Option<ReasoningEffort>,
 ) -> anyhow::Result<()> {
-    let config_path = codex_home.join(CONFIG_TOML_FILE);
-    let serialized = match tokio::fs::read_to_string(&config_path).await {
-        Ok(contents) => contents,
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
-        Err(err) => return Err(err.into()),
-    };
-
-    let mut doc = if serialized.is_empty() {
-        DocumentMut::new()
-    } else {
-        serialized.parse::<DocumentMut>()?
-    };
-
-    if let Some(profile_name) = active_profile {
-        let profile_table = ensure_profile_table(&mut doc, profile_name)?;
-        profile_table["model"] = toml_edit::value(model);
-        match effort {
-            Some(effort) => {
-                profile_table["model_reasoning_effort"] = toml_edit::value(effort.to_string());
+    edit_config_async(codex_home, |doc| {
+        if let Some(profile_name) = active_profile {
+            let profile_table = ensure_profile_table(doc, profile_name)?;
+            profile_table["model"] = toml_edit::value(model);
+            match effort {
+                Some(effort) => {
+                    profile_table["model_reasoning_effort"] = toml_edit::value(effort.to_string());
+                }
+                None => {
+                    profile_table.remove("model_reasoning_effort");
+                }
             }
-            None => {
-                profile_table.remove("model_reasoning_effort");
+        } else {
+            let table = doc.as_table_mut();
+            table["model"] = toml_edit::value(model);
+            match effort {
+                Some(effort) => {
+                    table["model_reasoning_effort"] = toml_edit::value(effort.to_string());
+                }
+                None => {
+                    table.remove("model_reasoning_effort");
+                }
             }
         }
-    } else {
-        let table = doc.as_table_mut();
-        table["model"] = toml_edit::value(model);
-        match effort {
-            Some(effort) => {
-                table["model_reasoning_effort"] = toml_edit::value(effort.to_string());
-            }
-            None => {
-                table.remove("model_reasoning_effort");
+        Ok(())
+    })
+    .await
+}
+
+/// Which table of `config.toml` `codex config get/set` reads and writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigScope<'a> {
+    /// Top-level keys.
+    Global,
+    /// `[profiles.<name>]`.
+    Profile(&'a str),
+    /// `[projects."<path>"]`.
+    Project(&'a Path),
+}
+
+/// Set the dotted `key` in `scope` to `raw_value`, parsed as a TOML value
+/// when it is one (`true`, `3`, `["a", "b"]`) and taken as a string
+/// otherwise. The change is rejected, leaving the file untouched, when the
+/// resulting config would no longer load.
+pub fn set_config_value(
+    codex_home: &Path,
+    scope: ConfigScope<'_>,
+    key: &str,
+    raw_value: &str,
+) -> anyhow::Result<()> {
+    let segments = config_key_segments(key)?;
+    let value = parse_config_value(raw_value);
+    edit_config(codex_home, |doc| {
+        let mut table = match scope {
+            ConfigScope::Global => doc.as_table_mut(),
+            ConfigScope::Profile(name) => ensure_profile_table(doc, name)?,
+            ConfigScope::Project(path) => project_table_mut(doc, path)?,
+        };
+        let (last, parents) = segments
+            .split_last()
+            .context("config key must not be empty")?;
+        for segment in parents {
+            if !table.contains_key(segment) {
+                let mut child = TomlTable::new();
+                child.set_implicit(true);
+                table.insert(segment, TomlItem::Table(child));
             }
+            table = table[*segment]
+                .as_table_mut()
+                .with_context(|| format!("`{segment}` in `{key}` is not a table"))?;
+        }
+        table[*last] = toml_edit::value(value);
+
+        toml::from_str::<ConfigToml>(&doc.to_string())
+            .with_context(|| format!("invalid value for `{key}`"))?;
+        Ok(())
+    })
+}
+
+/// The value of the dotted `key` in `scope`, rendered as TOML, or `None`
+/// when it is not set.
+pub fn get_config_value(
+    codex_home: &Path,
+    scope: ConfigScope<'_>,
+    key: &str,
+) -> anyhow::Result<Option<String>> {
+    let segments = config_key_segments(key)?;
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let contents = match std::fs::read_to_string(&config_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let root: TomlValue = toml::from_str(&contents)
+        .with_context(|| format!("failed to parse {}", config_path.display()))?;
+
+    let mut current = Some(&root);
+    match scope {
+        ConfigScope::Global => {}
+        ConfigScope::Profile(name) => {
+            current = current
+                .and_then(|value| value.get("profiles"))
+                .and_then(|profiles| profiles.get(name));
+        }
+        ConfigScope::Project(path) => {
+            current = current
+                .and_then(|value| value.get("projects"))
+                .and_then(|projects| projects.get(&*path.to_string_lossy()));
         }
     }
+    for segment in &segments {
+        current = current.and_then(|value| value.get(segment));
+    }
 
-    // TODO(jif) refactor the home creation
-    tokio::fs::create_dir_all(codex_home)
-        .await
-        .with_context(|| {
-            format!(
-                "failed to create Codex home directory at {}",
-                codex_home.display()
-            )
-        })?;
+    Ok(current.map(|value| match value {
+        TomlValue::String(s) => s.clone(),
+        TomlValue::Table(table) => toml::to_string(table).unwrap_or_default(),
+        other => other.to_string(),
+    }))
+}
 
-    tokio::fs::write(&config_path, doc.to_string())
-        .await
-        .with_context(|| format!("failed to persist config.toml at {}", config_path.display()))?;
+fn config_key_segments(key: &str) -> anyhow::Result<Vec<&str>> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|segment| segment.trim().is_empty()) {
+        anyhow::bail!("invalid config key `{key}`");
+    }
+    Ok(segments)
+}
 
-    Ok(())
+/// `raw` as a TOML value, or as a plain string when it does not parse as
+/// one (so `codex config set model o3` needs no quoting).
+fn parse_config_value(raw: &str) -> toml_edit::Value {
+    format!("value = {raw}")
+        .parse::<DocumentMut>()
+        .ok()
+        .filter(|doc| doc.len() == 1)
+        .and_then(|doc| doc.get("value").and_then(TomlItem::as_value).cloned())
+        .map(|mut value| {
+            value.decor_mut().clear();
+            value
+        })
+        .unwrap_or_else(|| raw.into())
 }
 
 /// Apply a single dotted-path override onto a TOML value.
@@ -713,6 +1049,28 @@ pub struct ConfigToml {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Per-model token limit overrides keyed by model slug.
+    #[serde(default)]
+    pub model_overrides: HashMap<String, ModelOverride>,
+
+    /// Per-model prices in USD per million tokens, keyed by model slug.
+    #[serde(default)]
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// Query the provider's `/models` endpoint for token limits of models
+    /// without built-in metadata. Defaults to `true`.
+    pub model_metadata_autodetect: Option<bool>,
+
+    /// Seconds a single task may run before the model is asked to wrap up.
+    pub max_turn_duration_sec: Option<u64>,
+
+    /// Tool calls a single task may make before the model is asked to wrap up.
+    pub max_tool_calls_per_turn: Option<u32>,
+
+    /// Megabytes the agent may write to files in one session before asking
+    /// the user to allow more.
+    pub max_session_write_mb: Option<u64>,
+
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
@@ -765,6 +1123,38 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Issue trackers that `@gh#123`-style references are fetched from.
+    #[serde(default)]
+    pub issue_trackers: Option<IssueTrackers>,
+
+    /// Run shell commands and `apply_patch` on a remote host over SSH.
+    #[serde(default)]
+    pub remote: Option<RemoteWorkspace>,
+
+    /// Run shell commands in a Kubernetes pod via `kubectl exec`.
+    #[serde(default)]
+    pub kubernetes_exec: Option<KubernetesExec>,
+
+    /// Load allowed variables from the project's `.env`/`.envrc`.
+    #[serde(default)]
+    pub project_env: Option<ProjectEnvToml>,
+
+    /// Commands the agent may never run, e.g. `"git push --force"`.
+    #[serde(default)]
+    pub command_deny_list: Option<Vec<String>>,
+
+    /// Recording of, and approval for, packages the agent installs.
+    #[serde(default)]
+    pub package_installs: Option<PackageInstalls>,
+
+    /// Commands suggested to check changes, by build system.
+    #[serde(default)]
+    pub verify: Option<VerifyCommands>,
+
+    /// Record the session, model and time of the lines the agent writes in
+    /// `.codex-provenance.json`. Defaults to `false`.
+    pub record_provenance: Option<bool>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -772,6 +1162,9 @@ pub struct ConfigToml {
     /// Collection of settings that are specific to the TUI.
     pub tui: Option<Tui>,
 
+    /// Layout and custom segments of the TUI status line.
+    pub statusline: Option<StatusLineToml>,
+
     /// When set to `true`, `AgentReasoning` events will be hidden from the
     /// UI/output. Defaults to `false`.
     pub hide_agent_reasoning: Option<bool>,
@@ -845,6 +1238,22 @@ impl From<ConfigToml> for UserSavedConfig {
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ProjectConfig {
     pub trust_level: Option<String>,
+    /// Whether to run shell commands in the project's devcontainer.
+    pub devcontainer: Option<bool>,
+    /// The user's decision about the project's `.env`/`.envrc`.
+    pub env_trust: Option<ProjectEnvTrust>,
+    /// Commands the agent may never run in this project, on top of the
+    /// global `command_deny_list`.
+    pub command_deny_list: Option<Vec<String>>,
+}
+
+/// Whether the user trusts a project's environment files, as they were when
+/// the decision was made.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProjectEnvTrust {
+    /// [`ProjectEnv::digest`] of the files the decision applies to.
+    pub digest: String,
+    pub trusted: bool,
 }
 
 #[derive(Deserialize, Debug, Clone, Default, PartialEq)]
@@ -855,6 +1264,30 @@ pub struct ToolsToml {
     /// Enable the `view_image` tool that lets the agent attach local images.
     #[serde(default)]
     pub view_image: Option<bool>,
+
+    /// Enable the `system_info` tool that reports the machine's free
+    /// resources.
+    #[serde(default)]
+    pub system_info: Option<bool>,
+
+    /// Enable the `conversation_search` tool that searches the session's
+    /// own history.
+    #[serde(default)]
+    pub conversation_search: Option<bool>,
+
+    /// Enable the `todos` tool that lists the workspace's TODO comments.
+    #[serde(default)]
+    pub todos: Option<bool>,
+
+    /// Enable the `ask_user` tool that asks the user a question and waits
+    /// for the answer.
+    #[serde(default)]
+    pub ask_user: Option<bool>,
+
+    /// Parse the output of failed cargo, pytest, eslint and tsc runs into a
+    /// structured `failures` list for the model.
+    #[serde(default)]
+    pub structured_failures: Option<bool>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -881,7 +1314,10 @@ impl ConfigToml {
                     exclude_tmpdir_env_var,
                     exclude_slash_tmp,
                 }) => SandboxPolicy::WorkspaceWrite {
-                    writable_roots: writable_roots.clone(),
+                    writable_roots: writable_roots
+                        .iter()
+                        .map(|root| dunce::simplified(root).to_path_buf())
+                        .collect(),
                     network_access: *network_access,
                     exclude_tmpdir_env_var: *exclude_tmpdir_env_var,
                     exclude_slash_tmp: *exclude_slash_tmp,
@@ -918,6 +1354,46 @@ impl ConfigToml {
         false
     }
 
+    /// The recorded decision about `project_env`, if it still matches the
+    /// files' contents.
+    fn project_env_trust(&self, project_env: &ProjectEnv) -> Option<bool> {
+        let trust = self
+            .projects
+            .as_ref()?
+            .get(project_env.root.to_string_lossy().as_ref())?
+            .env_trust
+            .as_ref()?;
+        (trust.digest == project_env.digest).then_some(trust.trusted)
+    }
+
+    /// The deny-list entries of the project at `resolved_cwd` or, for
+    /// worktrees, of the root git project.
+    fn project_command_deny_list(&self, resolved_cwd: &Path) -> Vec<String> {
+        let Some(projects) = self.projects.as_ref() else {
+            return Vec::new();
+        };
+        let mut paths = vec![resolved_cwd.to_path_buf()];
+        if let Some(root_project) = resolve_root_git_project_for_trust(resolved_cwd)
+            && root_project != resolved_cwd
+        {
+            paths.push(root_project);
+        }
+        paths
+            .iter()
+            .filter_map(|path| projects.get(path.to_string_lossy().as_ref()))
+            .filter_map(|project| project.command_deny_list.clone())
+            .flatten()
+            .collect()
+    }
+
+    /// The recorded devcontainer choice for the project at `project_path`.
+    fn devcontainer_choice(&self, project_path: &Path) -> Option<bool> {
+        self.projects
+            .as_ref()?
+            .get(project_path.to_string_lossy().as_ref())?
+            .devcontainer
+    }
+
     pub fn get_config_profile(
         &self,
         override_profile: Option<String>,
@@ -925,18 +1401,53 @@ impl ConfigToml {
         let profile = override_profile.or_else(|| self.profile.clone());
 
         match profile {
-            Some(key) => {
-                if let Some(profile) = self.profiles.get(key.as_str()) {
-                    return Ok(profile.clone());
-                }
+            Some(key) => self.resolve_profile(&key),
+            None => Ok(ConfigProfile::default()),
+        }
+    }
 
-                Err(std::io::Error::new(
+    /// The profile `name` followed by the profiles it `extends`, nearest
+    /// first.
+    pub fn profile_chain(&self, name: &str) -> Result<Vec<&str>, std::io::Error> {
+        let mut chain: Vec<&str> = Vec::new();
+        let mut next = Some(name);
+        while let Some(key) = next {
+            let Some((key, profile)) = self.profiles.get_key_value(key) else {
+                return Err(std::io::Error::new(
                     std::io::ErrorKind::NotFound,
-                    format!("config profile `{key}` not found"),
-                ))
+                    match chain.last() {
+                        Some(child) => {
+                            format!("config profile `{key}` extended by `{child}` not found")
+                        }
+                        None => format!("config profile `{key}` not found"),
+                    },
+                ));
+            };
+            if chain.contains(&key.as_str()) {
+                chain.push(key);
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "config profiles extend each other in a cycle: {}",
+                        chain.join(" -> ")
+                    ),
+                ));
             }
-            None => Ok(ConfigProfile::default()),
+            chain.push(key);
+            next = profile.extends.as_deref();
         }
+        Ok(chain)
+    }
+
+    /// The profile `name` with the settings it inherits through `extends`
+    /// filled in.
+    pub fn resolve_profile(&self, name: &str) -> Result<ConfigProfile, std::io::Error> {
+        let chain = self.profile_chain(name)?;
+        Ok(chain
+            .iter()
+            .filter_map(|key| self.profiles.get(*key))
+            .cloned()
+            .fold(ConfigProfile::default(), ConfigProfile::inherit))
     }
 }
 
@@ -992,16 +1503,7 @@ impl Config {
             .or(cfg.profile.as_ref())
             .cloned();
         let config_profile = match active_profile_name.as_ref() {
-            Some(key) => cfg
-                .profiles
-                .get(key)
-                .ok_or_else(|| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::NotFound,
-                        format!("config profile `{key}` not found"),
-                    )
-                })?
-                .clone(),
+            Some(key) => cfg.resolve_profile(key)?,
             None => ConfigProfile::default(),
         };
 
@@ -1009,13 +1511,15 @@ impl Config {
 
         let mut model_providers = built_in_model_providers();
         // Merge user-defined providers into the built-in list.
-        for (key, provider) in cfg.model_providers.into_iter() {
-            model_providers.entry(key).or_insert(provider);
+        for (key, provider) in &cfg.model_providers {
+            model_providers
+                .entry(key.clone())
+                .or_insert_with(|| provider.clone());
         }
 
         let model_provider_id = model_provider
             .or(config_profile.model_provider)
-            .or(cfg.model_provider)
+            .or(cfg.model_provider.clone())
             .unwrap_or_else(|| "openai".to_string());
         let model_provider = model_providers
             .get(&model_provider_id)
@@ -1027,28 +1531,93 @@ impl Config {
             })?
             .clone();
 
-        let shell_environment_policy = cfg.shell_environment_policy.into();
-
         let resolved_cwd = {
             use std::env;
 
-            match cwd {
-                None => {
-                    tracing::info!("cwd not set, using current dir");
-                    env::current_dir()?
+            let cwd = if let Some(remote) = &cfg.remote {
+                // Commands run in the remote directory; the local one is unused.
+                // `has_root` rather than `is_absolute`: the remote path is a
+                // POSIX path even when this machine runs Windows.
+                if !remote.cwd.has_root() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                            "remote.cwd must be an absolute path, got {}",
+                            remote.cwd.display()
+                        ),
+                    ));
                 }
-                Some(p) if p.is_absolute() => p,
-                Some(p) => {
-                    // Resolve relative path against the current working directory.
-                    tracing::info!("cwd is relative, resolving against current dir");
-                    let mut current = env::current_dir()?;
-                    current.push(p);
-                    current
+                remote.cwd.clone()
+            } else {
+                match cwd {
+                    None => {
+                        tracing::info!("cwd not set, using current dir");
+                        env::current_dir()?
+                    }
+                    Some(p) if p.is_absolute() => p,
+                    Some(p) => {
+                        // Resolve relative path against the current working directory.
+                        tracing::info!("cwd is relative, resolving against current dir");
+                        let mut current = env::current_dir()?;
+                        current.push(p);
+                        current
+                    }
                 }
-            }
+            };
+            // Sandbox roots and displayed paths compare against the cwd, so
+            // drop any Windows verbatim (`\\?\`) prefix it was given with.
+            dunce::simplified(&cwd).to_path_buf()
+        };
+
+        // A devcontainer on this machine has nothing to do with a remote host.
+        let devcontainer = if cfg.remote.is_none() {
+            find_devcontainer(&resolved_cwd)
+        } else {
+            None
         };
+        let use_devcontainer = devcontainer
+            .as_ref()
+            .and_then(|devcontainer| cfg.devcontainer_choice(&devcontainer.workspace_folder));
 
-        let history = cfg.history.unwrap_or_default();
+        // Pin the context so switching `kubectl` contexts mid-session does not
+        // retarget the agent's commands.
+        let kubernetes_exec = cfg.kubernetes_exec.clone().map(|target| KubernetesExec {
+            context: target.context.or_else(crate::kubernetes::current_context),
+            ..target
+        });
+
+        let project_env_allow: Vec<EnvironmentVariablePattern> = cfg
+            .project_env
+            .as_ref()
+            .map(|project_env| {
+                project_env
+                    .allow
+                    .iter()
+                    .map(String::as_str)
+                    .map(EnvironmentVariablePattern::new_case_insensitive)
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Remote commands never see this machine's environment files.
+        let project_env = if project_env_allow.is_empty() || cfg.remote.is_some() {
+            None
+        } else {
+            find_project_env(&resolved_cwd)
+        };
+        let project_env_trusted = project_env
+            .as_ref()
+            .and_then(|project_env| cfg.project_env_trust(project_env));
+
+        let mut command_deny_list = cfg.command_deny_list.clone().unwrap_or_default();
+        command_deny_list.extend(cfg.project_command_deny_list(&resolved_cwd));
+
+        let shell_environment_policy = cfg.shell_environment_policy.into();
+
+        let state_home = StateHome::resolve(&codex_home);
+        let mut history = cfg.history.unwrap_or_default();
+        if state_home.read_only.is_some() {
+            history.persistence = HistoryPersistence::None;
+        }
 
         let tools_web_search_request = override_tools_web_search_request
             .or(cfg.tools.as_ref().and_then(|t| t.web_search))
@@ -1058,6 +1627,28 @@ impl Config {
             .or(cfg.tools.as_ref().and_then(|t| t.view_image))
             .unwrap_or(true);
 
+        let include_system_info_tool = cfg
+            .tools
+            .as_ref()
+            .and_then(|t| t.system_info)
+            .unwrap_or(false);
+
+        let include_conversation_search_tool = cfg
+            .tools
+            .as_ref()
+            .and_then(|t| t.conversation_search)
+            .unwrap_or(false);
+
+        let include_todos_tool = cfg.tools.as_ref().and_then(|t| t.todos).unwrap_or(false);
+
+        let include_ask_user_tool = cfg.tools.as_ref().and_then(|t| t.ask_user).unwrap_or(false);
+
+        let structured_failures = cfg
+            .tools
+            .as_ref()
+            .and_then(|t| t.structured_failures)
+            .unwrap_or(false);
+
         let model = model
             .or(config_profile.model)
             .or(cfg.model)
@@ -1072,21 +1663,30 @@ impl Config {
         if let Some(model_reasoning_summary_format) = cfg.model_reasoning_summary_format {
             model_family.reasoning_summary_format = model_reasoning_summary_format;
         }
+        let model_override = cfg.model_overrides.get(&model).copied().unwrap_or_default();
+        apply_model_capability_overrides(&model_override, &mut model_family);
 
         let openai_model_info = get_model_info(&model_family);
-        let model_context_window = cfg
-            .model_context_window
+        let model_context_window = model_override
+            .context_window
+            .or(cfg.model_context_window)
             .or_else(|| openai_model_info.as_ref().map(|info| info.context_window));
-        let model_max_output_tokens = cfg.model_max_output_tokens.or_else(|| {
-            openai_model_info
-                .as_ref()
-                .map(|info| info.max_output_tokens)
-        });
-        let model_auto_compact_token_limit = cfg.model_auto_compact_token_limit.or_else(|| {
-            openai_model_info
-                .as_ref()
-                .and_then(|info| info.auto_compact_token_limit)
-        });
+        let model_max_output_tokens = model_override
+            .max_output_tokens
+            .or(cfg.model_max_output_tokens)
+            .or_else(|| {
+                openai_model_info
+                    .as_ref()
+                    .map(|info| info.max_output_tokens)
+            });
+        let model_auto_compact_token_limit = model_override
+            .auto_compact_token_limit
+            .or(cfg.model_auto_compact_token_limit)
+            .or_else(|| {
+                openai_model_info
+                    .as_ref()
+                    .and_then(|info| info.auto_compact_token_limit)
+            });
 
         // Load base instructions override from a file if specified. If the
         // path is relative, resolve it against the effective cwd so the
@@ -1104,13 +1704,21 @@ impl Config {
             .or(cfg.review_model)
             .unwrap_or_else(default_review_model);
 
-        let config = Self {
+        let mut config = Self {
             model,
             review_model,
             model_family,
             model_context_window,
             model_max_output_tokens,
             model_auto_compact_token_limit,
+            model_overrides: cfg.model_overrides,
+            model_pricing: cfg.model_pricing,
+            model_metadata_autodetect: cfg.model_metadata_autodetect.unwrap_or(true),
+            max_turn_duration: cfg.max_turn_duration_sec.map(Duration::from_secs),
+            max_tool_calls_per_turn: cfg.max_tool_calls_per_turn,
+            max_session_write_bytes: cfg
+                .max_session_write_mb
+                .map(|mb| mb.saturating_mul(1024 * 1024)),
             model_provider_id,
             model_provider,
             cwd: resolved_cwd,
@@ -1143,7 +1751,22 @@ impl Config {
                 })
                 .collect(),
             codex_home,
+            state_home,
             history,
+            issue_trackers: cfg.issue_trackers.unwrap_or_default(),
+            remote: cfg.remote,
+            devcontainer,
+            use_devcontainer,
+            kubernetes_exec,
+            command_deny_list,
+            package_installs: cfg.package_installs.unwrap_or_default(),
+            verify: cfg.verify.unwrap_or_default(),
+            record_provenance: cfg.record_provenance.unwrap_or(false),
+            replay: None,
+            project_env_allow,
+            project_env,
+            project_env_trusted,
+            project_env_vars: Vec::new(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1177,6 +1800,11 @@ impl Config {
                 .unwrap_or(false),
             use_experimental_use_rmcp_client: cfg.experimental_use_rmcp_client.unwrap_or(false),
             include_view_image_tool,
+            include_system_info_tool,
+            include_conversation_search_tool,
+            include_todos_tool,
+            include_ask_user_tool,
+            structured_failures,
             active_profile: active_profile_name,
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
@@ -1185,6 +1813,30 @@ impl Config {
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
+            tui_system_resources: cfg.tui.as_ref().is_some_and(|t| t.system_resources),
+            tui_idle_after: match cfg.tui.as_ref().and_then(|t| t.idle_after_secs) {
+                Some(0) => None,
+                secs => Some(Duration::from_secs(
+                    secs.unwrap_or(DEFAULT_TUI_IDLE_AFTER_SECS),
+                )),
+            },
+            tui_lock_after: cfg
+                .tui
+                .as_ref()
+                .and_then(|t| t.lock_after_secs)
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs),
+            tui_mouse: cfg.tui.as_ref().is_some_and(|t| t.mouse),
+            tui_private_notes: cfg.tui.as_ref().is_some_and(|t| t.private_notes),
+            tui_macros: cfg.tui.as_ref().is_some_and(|t| t.macros),
+            tui_output_filters: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.output_filters.clone())
+                .unwrap_or_default(),
+            statusline: StatusLineToml::layered(config_profile.statusline, cfg.statusline.clone())
+                .map(Into::into)
+                .unwrap_or_default(),
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
@@ -1199,9 +1851,66 @@ impl Config {
                 }
             },
         };
+        config.apply_project_env();
         Ok(config)
     }
 
+    /// Add the allowed variables from the trusted `project_env` files to the
+    /// environment of shell tool calls. Variables set explicitly in
+    /// `shell_environment_policy.set` win.
+    pub fn apply_project_env(&mut self) {
+        let Some(project_env) = &self.project_env else {
+            return;
+        };
+        if self.project_env_trusted != Some(true) || !self.project_env_vars.is_empty() {
+            return;
+        }
+        let vars = load_project_env(project_env, &self.project_env_allow);
+        tracing::info!(
+            "loaded {:?} from {}",
+            vars.keys().collect::<Vec<_>>(),
+            project_env.root.display()
+        );
+        for (name, value) in vars {
+            self.shell_environment_policy
+                .r#set
+                .entry(name.clone())
+                .or_insert(value);
+            self.project_env_vars.push(name);
+        }
+    }
+
+    /// Switch to `model` mid-session and re-resolve its token limits and
+    /// capabilities. Per-model overrides win over the built-in metadata;
+    /// limits neither knows are left unset for
+    /// [`Self::model_metadata_autodetect`] to fill in. Switching to the model
+    /// already in use keeps everything as it is.
+    pub(crate) fn switch_model(&mut self, model: &str) {
+        if model == self.model {
+            return;
+        }
+        self.model = model.to_string();
+        self.model_family =
+            find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+        let model_override = self
+            .model_overrides
+            .get(&self.model)
+            .copied()
+            .unwrap_or_default();
+        apply_model_capability_overrides(&model_override, &mut self.model_family);
+        let model_info = get_model_info(&self.model_family);
+
+        self.model_context_window = model_override
+            .context_window
+            .or_else(|| model_info.as_ref().map(|info| info.context_window));
+        self.model_max_output_tokens = model_override
+            .max_output_tokens
+            .or_else(|| model_info.as_ref().map(|info| info.max_output_tokens));
+        self.model_auto_compact_token_limit = model_override
+            .auto_compact_token_limit
+            .or_else(|| model_info.and_then(|info| info.auto_compact_token_limit));
+    }
+
     fn load_instructions(codex_dir: Option<&Path>) -> Option<String> {
         let mut p = match codex_dir {
             Some(p) => p.to_path_buf(),
@@ -1260,105 +1969,489 @@ impl Config {
             Ok(Some(s))
         }
     }
-}
+}
+
+fn default_model() -> String {
+    OPENAI_DEFAULT_MODEL.to_string()
+}
+
+fn default_review_model() -> String {
+    OPENAI_DEFAULT_REVIEW_MODEL.to_string()
+}
+
+/// Returns the path to the Codex configuration directory, which can be
+/// specified by the `CODEX_HOME` environment variable. If not set, defaults to
+/// `~/.codex`.
+///
+/// - If `CODEX_HOME` is set, the value will be canonicalized and this
+///   function will Err if the path does not exist.
+/// - If `CODEX_HOME` is not set, this function does not verify that the
+///   directory exists.
+pub fn find_codex_home() -> std::io::Result<PathBuf> {
+    // Honor the `CODEX_HOME` environment variable when it is set to allow users
+    // (and tests) to override the default location.
+    if let Ok(val) = std::env::var("CODEX_HOME")
+        && !val.is_empty()
+    {
+        // `dunce` avoids the `\\?\` verbatim prefix `canonicalize` adds on
+        // Windows, which many tools and path comparisons do not handle.
+        return dunce::canonicalize(PathBuf::from(val));
+    }
+
+    let mut p = home_dir().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Could not find home directory",
+        )
+    })?;
+    p.push(".codex");
+    Ok(p)
+}
+
+fn apply_model_capability_overrides(
+    model_override: &ModelOverride,
+    model_family: &mut ModelFamily,
+) {
+    if let Some(supports_image_input) = model_override.supports_image_input {
+        model_family.supports_image_input = supports_image_input;
+    }
+    if let Some(supports_tools) = model_override.supports_tools {
+        model_family.supports_tools = supports_tools;
+    }
+    if let Some(supports_parallel_tool_calls) = model_override.supports_parallel_tool_calls {
+        model_family.supports_parallel_tool_calls = supports_parallel_tool_calls;
+    }
+    if let Some(supports_reasoning_summaries) = model_override.supports_reasoning_summaries {
+        model_family.supports_reasoning_summaries = supports_reasoning_summaries;
+    }
+}
+
+/// Returns the path to the folder where Codex logs are stored. Does not verify
+/// that the directory exists.
+pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
+    let mut p = cfg.state_home.path.clone();
+    p.push("log");
+    Ok(p)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config_types::HistoryPersistence;
+    use crate::config_types::Notifications;
+    use crate::config_types::StatusLineContextLevel;
+    use crate::config_types::StatusLineContextThresholds;
+    use crate::config_types::StatusLineQueuePreview;
+    use crate::config_types::StatusLineSpinner;
+    use crate::config_types::StatusLineStaleMode;
+    use crate::config_types::StatusLineStyle;
+    use crate::config_types::StatusLineThemeName;
+    use crate::config_types::StatusLineTimeFormat;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_toml_parsing() {
+        let history_with_persistence = r#"
+[history]
+persistence = "save-all"
+"#;
+        let history_with_persistence_cfg = toml::from_str::<ConfigToml>(history_with_persistence)
+            .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(History {
+                persistence: HistoryPersistence::SaveAll,
+                max_bytes: None,
+            }),
+            history_with_persistence_cfg.history
+        );
+
+        let history_no_persistence = r#"
+[history]
+persistence = "none"
+"#;
+
+        let history_no_persistence_cfg = toml::from_str::<ConfigToml>(history_no_persistence)
+            .expect("TOML deserialization should succeed");
+        assert_eq!(
+            Some(History {
+                persistence: HistoryPersistence::None,
+                max_bytes: None,
+            }),
+            history_no_persistence_cfg.history
+        );
+    }
+
+    #[test]
+    fn tui_config_missing_notifications_field_defaults_to_disabled() {
+        let cfg = r#"
+[tui]
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg)
+            .expect("TUI config without notifications should succeed");
+        let tui = parsed.tui.expect("config should include tui section");
+
+        assert_eq!(tui.notifications, Notifications::Enabled(false));
+    }
+
+    #[test]
+    fn tui_idle_after_defaults_and_zero_disables() -> std::io::Result<()> {
+        let load = |cfg: &str| -> std::io::Result<Config> {
+            let codex_home = TempDir::new()?;
+            Config::load_from_base_config_with_overrides(
+                toml::from_str::<ConfigToml>(cfg).expect("tui config should parse"),
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        assert_eq!(load("")?.tui_idle_after, Some(Duration::from_secs(120)));
+        assert_eq!(
+            load("[tui]\nidle_after_secs = 30\n")?.tui_idle_after,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(load("[tui]\nidle_after_secs = 0\n")?.tui_idle_after, None);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_lock_after_is_off_unless_configured() -> std::io::Result<()> {
+        let load = |cfg: &str| -> std::io::Result<Config> {
+            let codex_home = TempDir::new()?;
+            Config::load_from_base_config_with_overrides(
+                toml::from_str::<ConfigToml>(cfg).expect("tui config should parse"),
+                ConfigOverrides::default(),
+                codex_home.path().to_path_buf(),
+            )
+        };
+
+        assert_eq!(load("")?.tui_lock_after, None);
+        assert_eq!(
+            load("[tui]\nlock_after_secs = 300\n")?.tui_lock_after,
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(load("[tui]\nlock_after_secs = 0\n")?.tui_lock_after, None);
+        Ok(())
+    }
+
+    #[test]
+    fn tui_output_filters_are_parsed() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = r#"
+[tui.output_filters]
+diff = ["delta", "--paging=never"]
+json = ["jq", "-C", "."]
+"#;
+        let config = Config::load_from_base_config_with_overrides(
+            toml::from_str::<ConfigToml>(cfg).expect("tui config should parse"),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.tui_output_filters,
+            OutputFilters {
+                diff: Some(vec!["delta".to_string(), "--paging=never".to_string()]),
+                json: Some(vec!["jq".to_string(), "-C".to_string(), ".".to_string()]),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn statusline_format_is_parsed_into_a_layout() {
+        use crate::config_types::StatusLineItem;
+        use crate::config_types::StatusLineLayout;
+
+        let cfg = r#"
+[statusline]
+format = "{model} {git} {tokens} {cost} {battery} {weather} {context} | {cwd"
+right_format = "{hostname} {kubernetes}"
+
+[[statusline.segments]]
+name = "battery"
+command = "cat /sys/class/power_supply/BAT0/capacity"
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+        let statusline = StatusLineConfig::from(parsed.statusline.expect("statusline section"));
+
+        assert_eq!(
+            statusline.layout,
+            Some(StatusLineLayout {
+                left: vec![
+                    StatusLineItem::Model,
+                    StatusLineItem::Git,
+                    StatusLineItem::Tokens,
+                    StatusLineItem::Cost,
+                    StatusLineItem::Custom("battery".to_string()),
+                    StatusLineItem::Context,
+                ],
+                right: vec![StatusLineItem::Hostname, StatusLineItem::Kubernetes],
+            })
+        );
+        assert_eq!(statusline.custom_segments[0].interval_secs, 30);
+        assert_eq!(
+            statusline.warnings,
+            vec![
+                "statusline.format: unknown placeholder `{weather}`".to_string(),
+                "statusline.format: text outside placeholders is ignored: `|`".to_string(),
+                "statusline.format: unclosed placeholder `{cwd`".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn clock_and_battery_follow_the_layout() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
+
+        let builtin = parse("[statusline]\nshow_clock = true\n");
+        assert!(builtin.shows_clock());
+        assert!(!builtin.shows_battery());
+
+        let template = parse("[statusline]\nshow_clock = true\nformat = \"{model} {battery}\"\n");
+        assert!(!template.shows_clock());
+        assert!(template.shows_battery());
+    }
 
-fn default_model() -> String {
-    OPENAI_DEFAULT_MODEL.to_string()
-}
+    #[test]
+    fn statusline_lines_falls_back_to_one_with_a_warning() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
 
-fn default_review_model() -> String {
-    OPENAI_DEFAULT_REVIEW_MODEL.to_string()
-}
+        assert_eq!(StatusLineConfig::default().lines, 1);
+        let two = parse("[statusline]\nlines = 2\n");
+        assert_eq!(two.lines, 2);
+        assert!(two.warnings.is_empty());
 
-/// Returns the path to the Codex configuration directory, which can be
-/// specified by the `CODEX_HOME` environment variable. If not set, defaults to
-/// `~/.codex`.
-///
-/// - If `CODEX_HOME` is set, the value will be canonicalized and this
-///   function will Err if the path does not exist.
-/// - If `CODEX_HOME` is not set, this function does not verify that the
-///   directory exists.
-pub fn find_codex_home() -> std::io::Result<PathBuf> {
-    // Honor the `CODEX_HOME` environment variable when it is set to allow users
-    // (and tests) to override the default location.
-    if let Ok(val) = std::env::var("CODEX_HOME")
-        && !val.is_empty()
-    {
-        return PathBuf::from(val).canonicalize();
+        let three = parse("[statusline]\nlines = 3\n");
+        assert_eq!(three.lines, 1);
+        assert_eq!(
+            three.warnings,
+            vec!["statusline.lines: expected 1 or 2, got 3".to_string()]
+        );
     }
 
-    let mut p = home_dir().ok_or_else(|| {
-        std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Could not find home directory",
-        )
-    })?;
-    p.push(".codex");
-    Ok(p)
-}
+    #[test]
+    fn statusline_style_defaults_to_powerline() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
 
-/// Returns the path to the folder where Codex logs are stored. Does not verify
-/// that the directory exists.
-pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
-    let mut p = cfg.codex_home.clone();
-    p.push("log");
-    Ok(p)
-}
+        assert_eq!(
+            StatusLineConfig::default().style,
+            StatusLineStyle::Powerline
+        );
+        assert_eq!(
+            parse("[statusline]\nstyle = \"plain\"\n").style,
+            StatusLineStyle::Plain
+        );
+        assert!(toml::from_str::<ConfigToml>("[statusline]\nstyle = \"fancy\"\n").is_err());
+    }
 
-#[cfg(test)]
-mod tests {
-    use crate::config_types::HistoryPersistence;
-    use crate::config_types::Notifications;
+    #[test]
+    fn statusline_colors_override_the_theme() {
+        let cfg = r##"
+[statusline]
+theme = "solarized"
+
+[statusline.colors]
+model = "#A6E3A1"
+git_dirty = "orange"
+"##;
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+        let statusline = StatusLineConfig::from(parsed.statusline.expect("statusline section"));
+
+        assert_eq!(statusline.theme, StatusLineThemeName::Solarized);
+        assert_eq!(statusline.colors.model, Some([0xa6, 0xe3, 0xa1]));
+        assert_eq!(statusline.colors.git_dirty, None);
+        assert_eq!(
+            statusline.warnings,
+            vec![
+                "statusline.colors.git_dirty: expected a color like \"#a6e3a1\", got `orange`"
+                    .to_string()
+            ]
+        );
+    }
 
-    use super::*;
-    use pretty_assertions::assert_eq;
+    #[test]
+    fn statusline_context_thresholds_set_the_levels() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
 
-    use std::time::Duration;
-    use tempfile::TempDir;
+        let defaults = parse("[statusline]\n");
+        assert_eq!(
+            defaults.context_thresholds,
+            StatusLineContextThresholds::default()
+        );
+        assert!(!defaults.context_flash);
+
+        let custom = parse(
+            "[statusline]\ncontext_warn_at = 25\ncontext_critical_at = 10\ncontext_flash = true\n",
+        );
+        let thresholds = custom.context_thresholds;
+        assert_eq!(
+            (thresholds.warn, thresholds.high, thresholds.critical),
+            (25, 17, 10)
+        );
+        assert!(custom.context_flash);
+        assert_eq!(thresholds.level(30.0), StatusLineContextLevel::Ok);
+        assert_eq!(thresholds.level(25.0), StatusLineContextLevel::Warn);
+        assert_eq!(thresholds.level(12.0), StatusLineContextLevel::High);
+        assert_eq!(thresholds.level(10.0), StatusLineContextLevel::Critical);
+
+        let inverted = parse("[statusline]\ncontext_warn_at = 10\ncontext_critical_at = 30\n");
+        assert_eq!(
+            inverted.context_thresholds,
+            StatusLineContextThresholds::default()
+        );
+        assert_eq!(
+            inverted.warnings,
+            vec![
+                "statusline.context_critical_at: expected at most context_warn_at (10), got 30"
+                    .to_string()
+            ]
+        );
+    }
 
     #[test]
-    fn test_toml_parsing() {
-        let history_with_persistence = r#"
-[history]
-persistence = "save-all"
-"#;
-        let history_with_persistence_cfg = toml::from_str::<ConfigToml>(history_with_persistence)
-            .expect("TOML deserialization should succeed");
+    fn statusline_queue_preview_settings() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
+
         assert_eq!(
-            Some(History {
-                persistence: HistoryPersistence::SaveAll,
-                max_bytes: None,
-            }),
-            history_with_persistence_cfg.history
+            parse("[statusline]\n").queue_preview,
+            StatusLineQueuePreview::default()
         );
 
-        let history_no_persistence = r#"
-[history]
-persistence = "none"
-"#;
+        let custom = parse(
+            "[statusline]\nqueue_preview_messages = 3\nqueue_preview_width = 20\nqueue_count_only = true\nqueue_edit_hint = false\n",
+        );
+        assert_eq!(
+            custom.queue_preview,
+            StatusLineQueuePreview {
+                messages: 3,
+                width: 20,
+                count_only: true,
+                edit_hint: false,
+            }
+        );
 
-        let history_no_persistence_cfg = toml::from_str::<ConfigToml>(history_no_persistence)
-            .expect("TOML deserialization should succeed");
+        let invalid = parse("[statusline]\nqueue_preview_messages = 0\nqueue_preview_width = 2\n");
+        assert_eq!(invalid.queue_preview, StatusLineQueuePreview::default());
         assert_eq!(
-            Some(History {
-                persistence: HistoryPersistence::None,
-                max_bytes: None,
-            }),
-            history_no_persistence_cfg.history
+            invalid.warnings,
+            vec![
+                "statusline.queue_preview_messages: expected at least 1, got 0; use queue_count_only to hide the previews"
+                    .to_string(),
+                "statusline.queue_preview_width: expected at least 4, got 2".to_string(),
+            ]
         );
     }
 
     #[test]
-    fn tui_config_missing_notifications_field_defaults_to_disabled() {
+    fn statusline_time_format_defaults_to_units() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
+
+        assert_eq!(
+            parse("[statusline]\n").time_format,
+            StatusLineTimeFormat::Units
+        );
+        assert_eq!(
+            parse("[statusline]\ntime_format = \"clock\"\n").time_format,
+            StatusLineTimeFormat::Clock
+        );
+        assert_eq!(
+            parse("[statusline]\ntime_format = \"hms\"\n").time_format,
+            StatusLineTimeFormat::Hms
+        );
+        assert!(!parse("[statusline]\n").show_wall_time);
+        assert!(parse("[statusline]\nshow_wall_time = true\n").show_wall_time);
+        assert!(toml::from_str::<ConfigToml>("[statusline]\ntime_format = \"hh:mm\"\n").is_err());
+    }
+
+    #[test]
+    fn statusline_spinner_defaults_to_blink_at_its_own_speed() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
+
+        let default = parse("[statusline]\n");
+        assert_eq!(default.spinner, StatusLineSpinner::Blink);
+        assert_eq!(default.spinner_interval, None);
+
+        let braille = parse("[statusline]\nspinner = \"braille\"\nspinner_interval_ms = 120\n");
+        assert_eq!(braille.spinner, StatusLineSpinner::Braille);
+        assert_eq!(braille.spinner_interval, Some(Duration::from_millis(120)));
+
+        let zero = parse("[statusline]\nspinner_interval_ms = 0\n");
+        assert_eq!(zero.spinner_interval, None);
+        assert_eq!(
+            zero.warnings,
+            vec!["statusline.spinner_interval_ms: expected a positive number, got 0".to_string()]
+        );
+    }
+
+    #[test]
+    fn statusline_stale_segments_are_kept_unless_configured() {
+        let parse = |cfg: &str| {
+            let parsed = toml::from_str::<ConfigToml>(cfg).expect("statusline config should parse");
+            StatusLineConfig::from(parsed.statusline.expect("statusline section"))
+        };
+
+        let default = parse("[statusline]\n");
+        assert_eq!(default.stale_after, None);
+        assert_eq!(default.stale, StatusLineStaleMode::Dim);
+
+        let hide = parse("[statusline]\nstale_after_secs = 600\nstale = \"hide\"\n");
+        assert_eq!(hide.stale_after, Some(Duration::from_secs(600)));
+        assert_eq!(hide.stale, StatusLineStaleMode::Hide);
+    }
+
+    #[test]
+    fn model_pricing_is_read_per_model() {
         let cfg = r#"
-[tui]
+[model_pricing.gpt-5]
+input_per_million = 1.0
+output_per_million = 8.0
+
+[model_pricing."my-finetune"]
+input_per_million = 3.0
+cached_input_per_million = 1.5
+output_per_million = 12.0
 "#;
 
-        let parsed = toml::from_str::<ConfigToml>(cfg)
-            .expect("TUI config without notifications should succeed");
-        let tui = parsed.tui.expect("config should include tui section");
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("model_pricing should parse");
 
-        assert_eq!(tui.notifications, Notifications::Enabled(false));
+        assert_eq!(
+            parsed.model_pricing.get("gpt-5"),
+            Some(&ModelPricing {
+                input_per_million: 1.0,
+                cached_input_per_million: None,
+                output_per_million: 8.0,
+            })
+        );
+        assert_eq!(
+            parsed.model_pricing["my-finetune"].cached_input_per_million,
+            Some(1.5)
+        );
     }
 
     #[test]
@@ -1436,6 +2529,51 @@ exclude_slash_tmp = true
         Ok(())
     }
 
+    #[test]
+    fn model_overrides_win_over_global_settings() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg = toml::from_str::<ConfigToml>(
+            r#"
+model = "qwen3-coder"
+model_context_window = 32000
+model_max_output_tokens = 4096
+
+[model_overrides."qwen3-coder"]
+context_window = 65536
+supports_image_input = false
+
+[model_overrides."gpt-5"]
+context_window = 100000
+"#,
+        )
+        .expect("TOML deserialization should succeed");
+
+        let mut config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.model_context_window, Some(65_536));
+        assert_eq!(config.model_max_output_tokens, Some(4_096));
+        assert!(!config.model_family.supports_image_input);
+
+        config.switch_model("gpt-5");
+
+        assert_eq!(config.model_context_window, Some(100_000));
+        assert_eq!(config.model_max_output_tokens, Some(128_000));
+        assert!(config.model_family.supports_image_input);
+
+        // Nothing knows this model: its limits are left for autodetection
+        // rather than carried over from the previous model.
+        config.switch_model("my-finetune");
+        assert_eq!(config.model_context_window, None);
+        assert_eq!(config.model_max_output_tokens, None);
+        assert_eq!(config.model_auto_compact_token_limit, None);
+
+        Ok(())
+    }
+
     #[test]
     fn config_honors_explicit_file_oauth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1943,6 +3081,80 @@ model = "gpt-5-codex"
         Ok(())
     }
 
+    #[test]
+    fn set_config_value_writes_scoped_keys() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let project = Path::new("/work/app");
+
+        set_config_value(codex_home.path(), ConfigScope::Global, "model", "o3")?;
+        set_config_value(
+            codex_home.path(),
+            ConfigScope::Profile("dev"),
+            "model_reasoning_effort",
+            "high",
+        )?;
+        set_config_value(
+            codex_home.path(),
+            ConfigScope::Global,
+            "tui.notifications",
+            "true",
+        )?;
+        set_config_value(
+            codex_home.path(),
+            ConfigScope::Project(project),
+            "devcontainer",
+            "true",
+        )?;
+
+        let serialized = std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE))?;
+        let parsed: ConfigToml = toml::from_str(&serialized)?;
+        assert_eq!(parsed.model.as_deref(), Some("o3"));
+        assert_eq!(
+            parsed
+                .profiles
+                .get("dev")
+                .and_then(|profile| profile.model_reasoning_effort),
+            Some(ReasoningEffort::High),
+        );
+        assert_eq!(
+            get_config_value(codex_home.path(), ConfigScope::Global, "tui.notifications")?,
+            Some("true".to_string())
+        );
+        assert_eq!(
+            get_config_value(
+                codex_home.path(),
+                ConfigScope::Project(project),
+                "devcontainer"
+            )?,
+            Some("true".to_string())
+        );
+        assert_eq!(
+            get_config_value(codex_home.path(), ConfigScope::Global, "model")?,
+            Some("o3".to_string())
+        );
+        assert_eq!(
+            get_config_value(codex_home.path(), ConfigScope::Global, "sandbox_mode")?,
+            None
+        );
+
+        // A value the config cannot load is rejected without touching the file.
+        assert!(
+            set_config_value(
+                codex_home.path(),
+                ConfigScope::Global,
+                "approval_policy",
+                "sometimes"
+            )
+            .is_err()
+        );
+        assert_eq!(
+            std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE))?,
+            serialized
+        );
+
+        Ok(())
+    }
+
     struct PrecedenceTestFixture {
         cwd: TempDir,
         codex_home: TempDir,
@@ -2089,6 +3301,12 @@ model_verbosity = "high"
                 model_context_window: Some(200_000),
                 model_max_output_tokens: Some(100_000),
                 model_auto_compact_token_limit: None,
+                model_overrides: HashMap::new(),
+                model_pricing: HashMap::new(),
+                model_metadata_autodetect: true,
+                max_turn_duration: None,
+                max_tool_calls_per_turn: None,
+                max_session_write_bytes: None,
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: AskForApproval::Never,
@@ -2103,7 +3321,25 @@ model_verbosity = "high"
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 codex_home: fixture.codex_home(),
+                state_home: StateHome {
+                    path: fixture.codex_home(),
+                    read_only: None,
+                },
                 history: History::default(),
+                issue_trackers: IssueTrackers::default(),
+                remote: None,
+                devcontainer: None,
+                use_devcontainer: None,
+                kubernetes_exec: None,
+                command_deny_list: Vec::new(),
+                package_installs: PackageInstalls::default(),
+                verify: VerifyCommands::default(),
+                record_provenance: false,
+                replay: None,
+                project_env_allow: Vec::new(),
+                project_env: None,
+                project_env_trusted: None,
+                project_env_vars: Vec::new(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -2120,10 +3356,23 @@ model_verbosity = "high"
                 use_experimental_unified_exec_tool: false,
                 use_experimental_use_rmcp_client: false,
                 include_view_image_tool: true,
+                include_system_info_tool: false,
+                include_conversation_search_tool: false,
+                include_todos_tool: false,
+                include_ask_user_tool: false,
+                structured_failures: false,
                 active_profile: Some("o3".to_string()),
                 windows_wsl_setup_acknowledged: false,
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_system_resources: false,
+                tui_idle_after: Some(Duration::from_secs(DEFAULT_TUI_IDLE_AFTER_SECS)),
+                tui_lock_after: None,
+                tui_mouse: false,
+                tui_private_notes: false,
+                tui_macros: false,
+                tui_output_filters: OutputFilters::default(),
+                statusline: StatusLineConfig::default(),
                 otel: OtelConfig::default(),
             },
             o3_profile_config
@@ -2152,6 +3401,12 @@ model_verbosity = "high"
             model_context_window: Some(16_385),
             model_max_output_tokens: Some(4_096),
             model_auto_compact_token_limit: None,
+            model_overrides: HashMap::new(),
+            model_pricing: HashMap::new(),
+            model_metadata_autodetect: true,
+            max_turn_duration: None,
+            max_tool_calls_per_turn: None,
+            max_session_write_bytes: None,
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: AskForApproval::UnlessTrusted,
@@ -2166,7 +3421,25 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
+            state_home: StateHome {
+                path: fixture.codex_home(),
+                read_only: None,
+            },
             history: History::default(),
+            issue_trackers: IssueTrackers::default(),
+            remote: None,
+            devcontainer: None,
+            use_devcontainer: None,
+            kubernetes_exec: None,
+            command_deny_list: Vec::new(),
+            package_installs: PackageInstalls::default(),
+            verify: VerifyCommands::default(),
+            record_provenance: false,
+            replay: None,
+            project_env_allow: Vec::new(),
+            project_env: None,
+            project_env_trusted: None,
+            project_env_vars: Vec::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2183,10 +3456,23 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
+            structured_failures: false,
             active_profile: Some("gpt3".to_string()),
             windows_wsl_setup_acknowledged: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_system_resources: false,
+            tui_idle_after: Some(Duration::from_secs(DEFAULT_TUI_IDLE_AFTER_SECS)),
+            tui_lock_after: None,
+            tui_mouse: false,
+            tui_private_notes: false,
+            tui_macros: false,
+            tui_output_filters: OutputFilters::default(),
+            statusline: StatusLineConfig::default(),
             otel: OtelConfig::default(),
         };
 
@@ -2230,6 +3516,12 @@ model_verbosity = "high"
             model_context_window: Some(200_000),
             model_max_output_tokens: Some(100_000),
             model_auto_compact_token_limit: None,
+            model_overrides: HashMap::new(),
+            model_pricing: HashMap::new(),
+            model_metadata_autodetect: true,
+            max_turn_duration: None,
+            max_tool_calls_per_turn: None,
+            max_session_write_bytes: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
@@ -2244,7 +3536,25 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
+            state_home: StateHome {
+                path: fixture.codex_home(),
+                read_only: None,
+            },
             history: History::default(),
+            issue_trackers: IssueTrackers::default(),
+            remote: None,
+            devcontainer: None,
+            use_devcontainer: None,
+            kubernetes_exec: None,
+            command_deny_list: Vec::new(),
+            package_installs: PackageInstalls::default(),
+            verify: VerifyCommands::default(),
+            record_provenance: false,
+            replay: None,
+            project_env_allow: Vec::new(),
+            project_env: None,
+            project_env_trusted: None,
+            project_env_vars: Vec::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2261,10 +3571,23 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
+            structured_failures: false,
             active_profile: Some("zdr".to_string()),
             windows_wsl_setup_acknowledged: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_system_resources: false,
+            tui_idle_after: Some(Duration::from_secs(DEFAULT_TUI_IDLE_AFTER_SECS)),
+            tui_lock_after: None,
+            tui_mouse: false,
+            tui_private_notes: false,
+            tui_macros: false,
+            tui_output_filters: OutputFilters::default(),
+            statusline: StatusLineConfig::default(),
             otel: OtelConfig::default(),
         };
 
@@ -2294,6 +3617,12 @@ model_verbosity = "high"
             model_context_window: Some(272_000),
             model_max_output_tokens: Some(128_000),
             model_auto_compact_token_limit: None,
+            model_overrides: HashMap::new(),
+            model_pricing: HashMap::new(),
+            model_metadata_autodetect: true,
+            max_turn_duration: None,
+            max_tool_calls_per_turn: None,
+            max_session_write_bytes: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
@@ -2308,7 +3637,25 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
+            state_home: StateHome {
+                path: fixture.codex_home(),
+                read_only: None,
+            },
             history: History::default(),
+            issue_trackers: IssueTrackers::default(),
+            remote: None,
+            devcontainer: None,
+            use_devcontainer: None,
+            kubernetes_exec: None,
+            command_deny_list: Vec::new(),
+            package_installs: PackageInstalls::default(),
+            verify: VerifyCommands::default(),
+            record_provenance: false,
+            replay: None,
+            project_env_allow: Vec::new(),
+            project_env: None,
+            project_env_trusted: None,
+            project_env_vars: Vec::new(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2325,10 +3672,23 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
+            structured_failures: false,
             active_profile: Some("gpt5".to_string()),
             windows_wsl_setup_acknowledged: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_system_resources: false,
+            tui_idle_after: Some(Duration::from_secs(DEFAULT_TUI_IDLE_AFTER_SECS)),
+            tui_lock_after: None,
+            tui_mouse: false,
+            tui_private_notes: false,
+            tui_macros: false,
+            tui_output_filters: OutputFilters::default(),
+            statusline: StatusLineConfig::default(),
             otel: OtelConfig::default(),
         };
 
@@ -2337,6 +3697,125 @@ model_verbosity = "high"
         Ok(())
     }
 
+    #[test]
+    fn profiles_inherit_from_the_profiles_they_extend() -> anyhow::Result<()> {
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[profiles.base]
+model = "gpt-5-codex"
+approval_policy = "on-request"
+model_reasoning_effort = "medium"
+
+[profiles.azure]
+extends = "base"
+model_provider = "azure"
+
+[profiles.deep]
+extends = "azure"
+model_reasoning_effort = "high"
+
+[profiles.loop-a]
+extends = "loop-b"
+
+[profiles.loop-b]
+extends = "loop-a"
+
+[profiles.orphan]
+extends = "missing"
+"#,
+        )?;
+
+        assert_eq!(cfg.profile_chain("deep")?, vec!["deep", "azure", "base"]);
+        assert_eq!(
+            cfg.resolve_profile("deep")?,
+            ConfigProfile {
+                model: Some("gpt-5-codex".to_string()),
+                model_provider: Some("azure".to_string()),
+                approval_policy: Some(AskForApproval::OnRequest),
+                model_reasoning_effort: Some(ReasoningEffort::High),
+                ..Default::default()
+            }
+        );
+
+        let cycle = cfg.resolve_profile("loop-a").expect_err("cycle");
+        assert_eq!(
+            cycle.to_string(),
+            "config profiles extend each other in a cycle: loop-a -> loop-b -> loop-a"
+        );
+        let missing = cfg.resolve_profile("orphan").expect_err("missing base");
+        assert_eq!(
+            missing.to_string(),
+            "config profile `missing` extended by `orphan` not found"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profiles_layer_their_statusline_over_the_top_level_one() -> anyhow::Result<()> {
+        use crate::config_types::StatusLineItem;
+        use crate::config_types::StatusLineLayout;
+        use crate::config_types::StatusLineThemeName;
+
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+profile = "personal"
+
+[statusline]
+format = "{model} {git}"
+show_clock = true
+
+[profiles.work]
+[profiles.work.statusline]
+right_format = "{k8s} {aws}"
+theme = "solarized"
+
+[profiles.oncall]
+extends = "work"
+[profiles.oncall.statusline]
+theme = "light"
+
+[profiles.personal]
+model = "gpt-5-codex"
+"#,
+        )?;
+        let statusline = |profile: &str| -> anyhow::Result<StatusLineConfig> {
+            let profile = cfg.resolve_profile(profile)?;
+            Ok(
+                StatusLineToml::layered(profile.statusline, cfg.statusline.clone())
+                    .map(Into::into)
+                    .unwrap_or_default(),
+            )
+        };
+
+        let personal = statusline("personal")?;
+        assert_eq!(
+            personal.layout,
+            Some(StatusLineLayout {
+                left: vec![StatusLineItem::Model, StatusLineItem::Git],
+                right: Vec::new(),
+            })
+        );
+        assert_eq!(personal.theme, StatusLineThemeName::Dark);
+
+        let work = statusline("work")?;
+        assert_eq!(
+            work.layout,
+            Some(StatusLineLayout {
+                left: vec![StatusLineItem::Model, StatusLineItem::Git],
+                right: vec![StatusLineItem::Kubernetes, StatusLineItem::Aws],
+            })
+        );
+        assert_eq!(work.theme, StatusLineThemeName::Solarized);
+        assert!(work.show_clock);
+
+        let oncall = statusline("oncall")?;
+        assert_eq!(oncall.layout, work.layout);
+        assert_eq!(oncall.theme, StatusLineThemeName::Light);
+
+        Ok(())
+    }
+
     #[test]
     fn test_set_project_trusted_writes_explicit_tables() -> anyhow::Result<()> {
         let project_dir = Path::new("/some/path");
@@ -2362,6 +3841,23 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn test_set_project_devcontainer_keeps_trust_level() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let project_dir = Path::new("/some/path");
+        set_project_trusted(codex_home.path(), project_dir)?;
+
+        set_project_devcontainer(codex_home.path(), project_dir, true)?;
+
+        let contents = std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE))?;
+        let cfg: ConfigToml = toml::from_str(&contents)?;
+        assert!(cfg.is_cwd_trusted(project_dir));
+        assert_eq!(cfg.devcontainer_choice(project_dir), Some(true));
+        assert_eq!(cfg.devcontainer_choice(Path::new("/other")), None);
+
+        Ok(())
+    }
+
     #[test]
     fn test_set_project_trusted_converts_inline_to_explicit() -> anyhow::Result<()> {
         let project_dir = Path::new("/some/path");
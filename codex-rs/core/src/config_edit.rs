@@ -1,5 +1,8 @@
 use crate::config::CONFIG_TOML_FILE;
+use crate::file_lock::UpdateLock;
+use anyhow::Context;
 use anyhow::Result;
+use std::io::Write;
 use std::path::Path;
 use tempfile::NamedTempFile;
 use toml_edit::DocumentMut;
@@ -7,6 +10,80 @@ use toml_edit::DocumentMut;
 pub const CONFIG_KEY_MODEL: &str = "model";
 pub const CONFIG_KEY_EFFORT: &str = "model_reasoning_effort";
 
+/// Copy of the previous `config.toml` kept by [`edit_config`].
+pub const CONFIG_BACKUP_FILE: &str = "config.toml.bak";
+
+/// Apply `edit` to `CODEX_HOME/config.toml` and save the result. All
+/// programmatic config changes go through here (or [`edit_config_async`]).
+///
+/// The file is edited as a `toml_edit` document, so comments and formatting
+/// outside the touched keys survive. The config lock is held from read to
+/// write so concurrent Codex processes do not lose each other's changes, the
+/// previous file is kept as `config.toml.bak`, and the new contents replace
+/// the file atomically. Nothing is written when `edit` leaves the document
+/// unchanged; a missing file is treated as empty.
+pub fn edit_config<T>(
+    codex_home: &Path,
+    edit: impl FnOnce(&mut DocumentMut) -> Result<T>,
+) -> Result<T> {
+    let _lock = UpdateLock::acquire(&codex_home.join(CONFIG_TOML_FILE))?;
+    apply_config_edit(codex_home, edit)
+}
+
+/// [`edit_config`] that waits for the config lock without blocking the
+/// async runtime.
+pub async fn edit_config_async<T>(
+    codex_home: &Path,
+    edit: impl FnOnce(&mut DocumentMut) -> Result<T>,
+) -> Result<T> {
+    let _lock = UpdateLock::acquire_async(&codex_home.join(CONFIG_TOML_FILE)).await?;
+    apply_config_edit(codex_home, edit)
+}
+
+fn apply_config_edit<T>(
+    codex_home: &Path,
+    edit: impl FnOnce(&mut DocumentMut) -> Result<T>,
+) -> Result<T> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let original = match std::fs::read_to_string(&config_path) {
+        Ok(contents) => Some(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let mut doc = match &original {
+        Some(contents) => contents
+            .parse::<DocumentMut>()
+            .with_context(|| format!("failed to parse {}", config_path.display()))?,
+        None => DocumentMut::new(),
+    };
+
+    let result = edit(&mut doc)?;
+
+    let updated = doc.to_string();
+    if updated == original.as_deref().unwrap_or_default() {
+        return Ok(result);
+    }
+
+    std::fs::create_dir_all(codex_home).with_context(|| {
+        format!(
+            "failed to create Codex home directory at {}",
+            codex_home.display()
+        )
+    })?;
+    if original.is_some() {
+        std::fs::copy(&config_path, codex_home.join(CONFIG_BACKUP_FILE))
+            .with_context(|| format!("failed to back up {}", config_path.display()))?;
+    }
+    let mut tmp_file = NamedTempFile::new_in(codex_home)?;
+    tmp_file.write_all(updated.as_bytes())?;
+    tmp_file.as_file().sync_all()?;
+    tmp_file
+        .persist(&config_path)
+        .with_context(|| format!("failed to persist {}", config_path.display()))?;
+
+    Ok(result)
+}
+
 #[derive(Copy, Clone)]
 enum NoneBehavior {
     Skip,
@@ -112,78 +189,48 @@ async fn persist_overrides_with_behavior(
         return Ok(());
     }
 
-    let config_path = codex_home.join(CONFIG_TOML_FILE);
-
-    let read_result = tokio::fs::read_to_string(&config_path).await;
-    let mut doc = match read_result {
-        Ok(contents) => contents.parse::<DocumentMut>()?,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-            if overrides
-                .iter()
-                .all(|(_, value)| value.is_none() && matches!(none_behavior, NoneBehavior::Remove))
-            {
-                return Ok(());
-            }
-
-            tokio::fs::create_dir_all(codex_home).await?;
-            DocumentMut::new()
-        }
-        Err(e) => return Err(e.into()),
-    };
-
-    let effective_profile = if let Some(p) = profile {
-        Some(p.to_owned())
-    } else {
-        doc.get("profile")
-            .and_then(|i| i.as_str())
-            .map(str::to_string)
-    };
-
-    let mut mutated = false;
-
-    for (segments, value) in overrides.iter().copied() {
-        let mut seg_buf: Vec<&str> = Vec::new();
-        let segments_to_apply: &[&str];
+    edit_config_async(codex_home, |doc| {
+        let effective_profile = if let Some(p) = profile {
+            Some(p.to_owned())
+        } else {
+            doc.get("profile")
+                .and_then(|i| i.as_str())
+                .map(str::to_string)
+        };
 
-        if let Some(ref name) = effective_profile {
-            if segments.first().copied() == Some("profiles") {
-                segments_to_apply = segments;
+        for (segments, value) in overrides.iter().copied() {
+            let mut seg_buf: Vec<&str> = Vec::new();
+            let segments_to_apply: &[&str];
+
+            if let Some(ref name) = effective_profile {
+                if segments.first().copied() == Some("profiles") {
+                    segments_to_apply = segments;
+                } else {
+                    seg_buf.reserve(2 + segments.len());
+                    seg_buf.push("profiles");
+                    seg_buf.push(name.as_str());
+                    seg_buf.extend_from_slice(segments);
+                    segments_to_apply = seg_buf.as_slice();
+                }
             } else {
-                seg_buf.reserve(2 + segments.len());
-                seg_buf.push("profiles");
-                seg_buf.push(name.as_str());
-                seg_buf.extend_from_slice(segments);
-                segments_to_apply = seg_buf.as_slice();
+                segments_to_apply = segments;
             }
-        } else {
-            segments_to_apply = segments;
-        }
 
-        match value {
-            Some(v) => {
-                let item_value = toml_edit::value(v);
-                apply_toml_edit_override_segments(&mut doc, segments_to_apply, item_value);
-                mutated = true;
-            }
-            None => {
-                if matches!(none_behavior, NoneBehavior::Remove)
-                    && remove_toml_edit_segments(&mut doc, segments_to_apply)
-                {
-                    mutated = true;
+            match value {
+                Some(v) => {
+                    let item_value = toml_edit::value(v);
+                    apply_toml_edit_override_segments(doc, segments_to_apply, item_value);
+                }
+                None => {
+                    if matches!(none_behavior, NoneBehavior::Remove) {
+                        remove_toml_edit_segments(doc, segments_to_apply);
+                    }
                 }
             }
         }
-    }
-
-    if !mutated {
-        return Ok(());
-    }
-
-    let tmp_file = NamedTempFile::new_in(codex_home)?;
-    tokio::fs::write(tmp_file.path(), doc.to_string()).await?;
-    tmp_file.persist(config_path)?;
-
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 fn remove_toml_edit_segments(doc: &mut DocumentMut, segments: &[&str]) -> bool {
@@ -740,6 +787,55 @@ model_reasoning_effort = "high"
         assert!(!codex_home.join(CONFIG_TOML_FILE).exists());
     }
 
+    /// Verifies edits keep unrelated comments and back up the previous file.
+    #[test]
+    fn edit_config_preserves_comments_and_writes_backup() {
+        let tmpdir = tempdir().expect("tmp");
+        let codex_home = tmpdir.path();
+        let original = r#"# my settings
+model = "o3"
+
+[projects."/tmp/a"]
+# keep trusted
+trust_level = "trusted"
+"#;
+        std::fs::write(codex_home.join(CONFIG_TOML_FILE), original).expect("seed write");
+
+        edit_config(codex_home, |doc| {
+            doc["model"] = toml_edit::value("gpt-5-codex");
+            Ok(())
+        })
+        .expect("edit");
+
+        let contents = std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read");
+        let expected = r#"# my settings
+model = "gpt-5-codex"
+
+[projects."/tmp/a"]
+# keep trusted
+trust_level = "trusted"
+"#;
+        assert_eq!(contents, expected);
+        let backup = std::fs::read_to_string(codex_home.join(CONFIG_BACKUP_FILE)).expect("backup");
+        assert_eq!(backup, original);
+    }
+
+    /// Verifies an edit that changes nothing leaves the file and backup alone.
+    #[test]
+    fn edit_config_skips_write_when_unchanged() {
+        let tmpdir = tempdir().expect("tmp");
+        let codex_home = tmpdir.path();
+        std::fs::write(codex_home.join(CONFIG_TOML_FILE), "model = \"o3\"\n").expect("seed");
+
+        edit_config(codex_home, |doc| {
+            doc["model"] = toml_edit::value("o3");
+            Ok(())
+        })
+        .expect("edit");
+
+        assert!(!codex_home.join(CONFIG_BACKUP_FILE).exists());
+    }
+
     // Test helper moved to bottom per review guidance.
     async fn read_config(codex_home: &Path) -> String {
         let p = codex_home.join(CONFIG_TOML_FILE);
@@ -1,6 +1,8 @@
 use serde::Deserialize;
+use serde::Serialize;
 use std::path::PathBuf;
 
+use crate::config_types::StatusLineToml;
 use crate::protocol::AskForApproval;
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
@@ -8,8 +10,11 @@ use codex_protocol::config_types::Verbosity;
 
 /// Collection of common configuration options that a user can define as a unit
 /// in `config.toml`.
-#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
 pub struct ConfigProfile {
+    /// Name of a profile whose settings apply wherever this one leaves
+    /// them unset.
+    pub extends: Option<String>,
     pub model: Option<String>,
     /// The key in the `model_providers` map identifying the
     /// [`ModelProviderInfo`] to use.
@@ -20,6 +25,32 @@ pub struct ConfigProfile {
     pub model_verbosity: Option<Verbosity>,
     pub chatgpt_base_url: Option<String>,
     pub experimental_instructions_file: Option<PathBuf>,
+    /// Status line settings layered over the top-level `[statusline]`.
+    #[serde(skip_serializing)]
+    pub statusline: Option<StatusLineToml>,
+}
+
+impl ConfigProfile {
+    /// This profile with unset settings filled in from `base`. The result
+    /// keeps no `extends`, as it is fully resolved.
+    pub fn inherit(self, base: ConfigProfile) -> ConfigProfile {
+        ConfigProfile {
+            extends: None,
+            model: self.model.or(base.model),
+            model_provider: self.model_provider.or(base.model_provider),
+            approval_policy: self.approval_policy.or(base.approval_policy),
+            model_reasoning_effort: self.model_reasoning_effort.or(base.model_reasoning_effort),
+            model_reasoning_summary: self
+                .model_reasoning_summary
+                .or(base.model_reasoning_summary),
+            model_verbosity: self.model_verbosity.or(base.model_verbosity),
+            chatgpt_base_url: self.chatgpt_base_url.or(base.chatgpt_base_url),
+            experimental_instructions_file: self
+                .experimental_instructions_file
+                .or(base.experimental_instructions_file),
+            statusline: StatusLineToml::layered(self.statusline, base.statusline),
+        }
+    }
 }
 
 impl From<ConfigProfile> for codex_app_server_protocol::Profile {
@@ -242,6 +242,127 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Where `@gh#123`, `@gl#45` and `@jira:ABC-42` references in user input are
+/// fetched from.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct IssueTrackers {
+    #[serde(default)]
+    pub github: GitHubTracker,
+    #[serde(default)]
+    pub gitlab: GitLabTracker,
+    #[serde(default)]
+    pub jira: JiraTracker,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct GitHubTracker {
+    /// `owner/name` used for references without an explicit repository.
+    /// Defaults to the `origin` remote of the working directory.
+    pub repo: Option<String>,
+    /// Defaults to `https://api.github.com`.
+    pub api_url: Option<String>,
+    /// Environment variable holding the API token. Defaults to `GITHUB_TOKEN`.
+    pub token_env: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct GitLabTracker {
+    /// `group/project` used for references without an explicit project.
+    /// Defaults to the `origin` remote of the working directory.
+    pub project: Option<String>,
+    /// Defaults to `https://gitlab.com`.
+    pub base_url: Option<String>,
+    /// Environment variable holding the API token. Defaults to `GITLAB_TOKEN`.
+    pub token_env: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct JiraTracker {
+    /// Site URL, e.g. `https://example.atlassian.net`. Required for Jira.
+    pub base_url: Option<String>,
+    /// Account email for Jira Cloud API tokens. Without it the token is sent
+    /// as a bearer token (Jira Data Center personal access tokens).
+    pub email: Option<String>,
+    /// Environment variable holding the API token. Defaults to `JIRA_API_TOKEN`.
+    pub token_env: Option<String>,
+}
+
+/// Host that the agent's shell commands and file edits run on over SSH,
+/// while the UI and model calls stay on this machine.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct RemoteWorkspace {
+    /// SSH destination, e.g. `deploy@staging.example.com` or a `Host` alias
+    /// from `~/.ssh/config`.
+    pub host: String,
+    /// Absolute path of the working directory on the remote host.
+    pub cwd: PathBuf,
+    /// Extra `ssh` arguments placed before the destination, e.g.
+    /// `["-p", "2222"]`.
+    #[serde(default)]
+    pub ssh_args: Vec<String>,
+}
+
+/// Pod whose container the agent's shell commands run in via `kubectl exec`,
+/// while `apply_patch` edits the local files.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct KubernetesExec {
+    pub pod: String,
+    /// Container in the pod; `kubectl`'s default container when unset.
+    pub container: Option<String>,
+    pub namespace: Option<String>,
+    /// kubeconfig context; defaults to the current context when the session
+    /// starts (the one shown in the status line).
+    pub context: Option<String>,
+    /// Directory in the container that corresponds to the session's working
+    /// directory; commands start in the container's working directory when
+    /// unset.
+    pub cwd: Option<PathBuf>,
+}
+
+/// Which variables from a project's `.env`/`.envrc` may be added to the
+/// environment of shell tool calls.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ProjectEnvToml {
+    /// Variable name patterns (`*` and `?` wildcards). Nothing is loaded
+    /// while this is empty.
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+/// What happens when the agent installs packages with `npm`, `yarn`,
+/// `pnpm`, `pip`, `uv` or `cargo add`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct PackageInstalls {
+    /// Record installed packages and their resolved versions in
+    /// `CODEX_HOME/package-installs/<session id>.json`.
+    pub record: bool,
+    /// Ask before commands that add packages not installed earlier in the
+    /// session, whatever the approval policy.
+    pub require_approval: bool,
+}
+
+impl Default for PackageInstalls {
+    fn default() -> Self {
+        Self {
+            record: true,
+            require_approval: false,
+        }
+    }
+}
+
+/// Commands that check a change, suggested to the agent for the build
+/// systems found in the working directory when the session starts.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct VerifyCommands {
+    /// Suggest the commands. Defaults to `false`.
+    pub enabled: bool,
+    /// Command by build system (`cargo`, `npm`, `pip`, `go`, `maven` or
+    /// `gradle`), replacing the built-in one; `""` suggests none.
+    pub commands: HashMap<String, String>,
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -320,6 +441,892 @@ pub struct Tui {
     /// Defaults to `false`.
     #[serde(default)]
     pub notifications: Notifications,
+
+    /// Show free disk space of the workspace volume, free memory and CPU load
+    /// in the status line. Defaults to `false`.
+    #[serde(default)]
+    pub system_resources: bool,
+
+    /// Stop animating, including the status line clock and countdowns, after
+    /// this many seconds without input while no task runs; the next key
+    /// press resumes. Defaults to 120; `0` never stops.
+    pub idle_after_secs: Option<u64>,
+
+    /// Lock the session after this many seconds without input: the
+    /// transcript is hidden until a key is pressed. Off by default.
+    pub lock_after_secs: Option<u64>,
+
+    /// Capture the mouse, so status line segments can be clicked. Defaults
+    /// to `false`; while on, most terminals need Shift held to select text.
+    #[serde(default)]
+    pub mouse: bool,
+
+    /// Keep `/note` notes out of the model's context; they are still saved
+    /// in the session. Defaults to `false`.
+    #[serde(default)]
+    pub private_notes: bool,
+
+    /// Keyboard macros in the transcript overlay: `q<letter>` records,
+    /// `@<letter>` replays. Defaults to `false`, as `q` then no longer quits.
+    #[serde(default)]
+    pub macros: bool,
+
+    /// Commands outputs are piped through before they are shown.
+    #[serde(default)]
+    pub output_filters: OutputFilters,
+}
+
+/// Commands the TUI pipes outputs through before showing them, under
+/// `[tui.output_filters]`. Each gets the output on stdin and prints what to
+/// show instead, ANSI colors included; when it fails or takes too long the
+/// output is shown unfiltered.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct OutputFilters {
+    /// Filter for the diff shown by `/diff`, e.g. `["delta", "--paging=never"]`.
+    pub diff: Option<Vec<String>>,
+
+    /// Filter for tool results that are JSON, e.g. `["jq", "-C", "."]`.
+    pub json: Option<Vec<String>>,
+}
+
+/// Status line settings, under `[statusline]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct StatusLineToml {
+    /// Segments to show and their order, e.g. `"{status} {model} {git} {cwd}"`.
+    pub format: Option<String>,
+
+    /// Segments pinned to the right edge, e.g. `"{hostname} {k8s} {aws}"`.
+    pub right_format: Option<String>,
+
+    /// Segments showing the output of a shell command.
+    #[serde(default)]
+    pub segments: Vec<CustomStatusSegment>,
+
+    /// Add the estimated cost to the built-in layout. Defaults to `false`;
+    /// templates use `{cost}` instead.
+    pub show_cost: Option<bool>,
+
+    /// Show the active Python virtualenv or conda environment. Defaults to
+    /// `true`.
+    pub show_python_env: Option<bool>,
+
+    /// Check whether the Docker daemon of the selected context answers, and
+    /// show it in red when it does not. Runs `docker version`, so defaults to
+    /// `false`.
+    pub docker_probe: Option<bool>,
+
+    /// Show the Azure CLI subscription. Defaults to `false`.
+    pub show_azure: Option<bool>,
+
+    /// When the hostname segment is shown: `"always"` (the default) or
+    /// `"ssh-only"` to show it only in sessions reached over SSH.
+    pub hostname: Option<StatusLineHostnameMode>,
+
+    /// Add the wall-clock time to the built-in layout. Defaults to `false`;
+    /// templates use `{clock}` instead.
+    pub show_clock: Option<bool>,
+
+    /// Add the laptop battery charge to the built-in layout. Defaults to
+    /// `false`; templates use `{battery}` instead.
+    pub show_battery: Option<bool>,
+
+    /// Add the short session id with a `codex resume` hint to the built-in
+    /// layout. Defaults to `false`; templates use `{session}` instead.
+    pub show_session: Option<bool>,
+
+    /// Show how many of the configured MCP servers are connected, red once
+    /// one of them crashed. Defaults to `true`; only shown when servers are
+    /// configured.
+    pub show_mcp: Option<bool>,
+
+    /// Add the sandbox policy and approval mode to the built-in layout.
+    /// Defaults to `false`; templates use `{sandbox}` instead.
+    pub show_sandbox: Option<bool>,
+
+    /// Show the proxy from `HTTPS_PROXY` and friends, and `offline` while
+    /// the sandbox blocks network access. Defaults to `false`; templates use
+    /// `{network}` instead.
+    pub show_network: Option<bool>,
+
+    /// Rows the status line takes: `1`, or `2` to give the run state, timer
+    /// and queued messages a row of their own above the rest. Defaults to `1`.
+    pub lines: Option<u16>,
+
+    /// `"powerline"` (the default) for colored segments joined by Nerd Font
+    /// separators, `"plain"` for colored text between `│` without Nerd Font
+    /// glyphs.
+    pub style: Option<StatusLineStyle>,
+
+    /// Color theme: `"dark"` (the default), `"light"` or `"solarized"`.
+    pub theme: Option<StatusLineThemeName>,
+
+    /// Colors overriding the theme's, as `"#rrggbb"`.
+    #[serde(default)]
+    pub colors: StatusLineColorsToml,
+
+    /// Percent of the context window left at which the context segment
+    /// turns to its warning color. Defaults to `40`.
+    pub context_warn_at: Option<u8>,
+
+    /// Percent of the context window left at which the context segment
+    /// turns to its critical color and the transcript says so, once.
+    /// Defaults to `8`.
+    pub context_critical_at: Option<u8>,
+
+    /// Flash the context segment for a few seconds when it crosses
+    /// `context_critical_at`. Defaults to `false`.
+    pub context_flash: Option<bool>,
+
+    /// Glyphs of the run spinner: `"blink"` (the default), `"dots"`,
+    /// `"braille"` or `"ascii"`. Terminals without Unicode get `"ascii"`.
+    pub spinner: Option<StatusLineSpinner>,
+
+    /// Milliseconds each spinner frame is shown. Defaults to a speed that
+    /// suits the glyph set.
+    pub spinner_interval_ms: Option<u64>,
+
+    /// How the run timer shows elapsed time: `"units"` (the default,
+    /// `1h 23m 45s`), `"clock"` (`83:45`), `"compact"` (`1h23m`) or `"hms"`
+    /// (`01:23:45`).
+    pub time_format: Option<StatusLineTimeFormat>,
+
+    /// Once a run has paused, e.g. waiting for approval, show its wall time
+    /// next to the running time. Defaults to `false`.
+    pub show_wall_time: Option<bool>,
+
+    /// Queued messages previewed next to the run state; the rest are
+    /// counted. Defaults to `1`.
+    pub queue_preview_messages: Option<usize>,
+
+    /// Columns each queued message preview may take before it is cut off
+    /// with `…`. Defaults to `32`.
+    pub queue_preview_width: Option<usize>,
+
+    /// Show only how many messages are queued (`+3 queued`) instead of
+    /// previewing them. Defaults to `false`.
+    pub queue_count_only: Option<bool>,
+
+    /// Show the key that moves the last queued message back into the
+    /// composer for editing. Defaults to `true`.
+    pub queue_edit_hint: Option<bool>,
+
+    /// Seconds after which the devspace, aws and k8s segments count as
+    /// stale when their probe has not refreshed them. Unset keeps them as
+    /// they are.
+    pub stale_after_secs: Option<u64>,
+
+    /// What happens to stale segments: `"dim"` (the default) or `"hide"`.
+    pub stale: Option<StatusLineStaleMode>,
+
+    /// Mirror the run state, run time and model into the terminal (or tmux
+    /// pane) title. Defaults to `false`.
+    pub terminal_title: Option<bool>,
+
+    /// Shell command that draws the status line: it gets the status line's
+    /// data as JSON on stdin and prints the rows to show, ANSI colors
+    /// allowed. Unset uses the built-in renderer.
+    pub command: Option<String>,
+}
+
+impl StatusLineToml {
+    /// These settings with unset ones filled in from `base`, e.g. a
+    /// profile's `[profiles.work.statusline]` over the top-level
+    /// `[statusline]`. Custom `segments` replace the base ones as a whole.
+    pub fn inherit(self, base: StatusLineToml) -> StatusLineToml {
+        StatusLineToml {
+            format: self.format.or(base.format),
+            right_format: self.right_format.or(base.right_format),
+            segments: if self.segments.is_empty() {
+                base.segments
+            } else {
+                self.segments
+            },
+            show_cost: self.show_cost.or(base.show_cost),
+            show_python_env: self.show_python_env.or(base.show_python_env),
+            docker_probe: self.docker_probe.or(base.docker_probe),
+            show_azure: self.show_azure.or(base.show_azure),
+            hostname: self.hostname.or(base.hostname),
+            show_clock: self.show_clock.or(base.show_clock),
+            show_battery: self.show_battery.or(base.show_battery),
+            show_session: self.show_session.or(base.show_session),
+            show_mcp: self.show_mcp.or(base.show_mcp),
+            show_sandbox: self.show_sandbox.or(base.show_sandbox),
+            show_network: self.show_network.or(base.show_network),
+            lines: self.lines.or(base.lines),
+            style: self.style.or(base.style),
+            theme: self.theme.or(base.theme),
+            colors: self.colors.inherit(base.colors),
+            context_warn_at: self.context_warn_at.or(base.context_warn_at),
+            context_critical_at: self.context_critical_at.or(base.context_critical_at),
+            context_flash: self.context_flash.or(base.context_flash),
+            spinner: self.spinner.or(base.spinner),
+            spinner_interval_ms: self.spinner_interval_ms.or(base.spinner_interval_ms),
+            time_format: self.time_format.or(base.time_format),
+            show_wall_time: self.show_wall_time.or(base.show_wall_time),
+            queue_preview_messages: self.queue_preview_messages.or(base.queue_preview_messages),
+            queue_preview_width: self.queue_preview_width.or(base.queue_preview_width),
+            queue_count_only: self.queue_count_only.or(base.queue_count_only),
+            queue_edit_hint: self.queue_edit_hint.or(base.queue_edit_hint),
+            stale_after_secs: self.stale_after_secs.or(base.stale_after_secs),
+            stale: self.stale.or(base.stale),
+            terminal_title: self.terminal_title.or(base.terminal_title),
+            command: self.command.or(base.command),
+        }
+    }
+
+    /// `profile` layered over `base` when both are set.
+    pub fn layered(
+        profile: Option<StatusLineToml>,
+        base: Option<StatusLineToml>,
+    ) -> Option<StatusLineToml> {
+        match (profile, base) {
+            (Some(profile), Some(base)) => Some(profile.inherit(base)),
+            (profile, base) => profile.or(base),
+        }
+    }
+}
+
+/// How status line segments are drawn.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusLineStyle {
+    /// Segments on truecolor backgrounds joined by Nerd Font separators.
+    /// Terminals without truecolor get [`StatusLineStyle::Plain`].
+    #[default]
+    Powerline,
+    /// Segment text in ANSI colors, separated by `│`, with Nerd Font
+    /// glyphs left out.
+    Plain,
+}
+
+/// Glyph sets of the status line run spinner.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusLineSpinner {
+    /// `•` blinking with `◦`.
+    #[default]
+    Blink,
+    /// A dot growing and shrinking, `· • ● •`.
+    Dots,
+    /// A gap circling a braille block, `⣾ ⣽ ⣻ ⢿ ⡿ ⣟ ⣯ ⣷`.
+    Braille,
+    /// `| / - \`, for terminals without Unicode.
+    Ascii,
+}
+
+/// How the status line run timer shows elapsed time.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusLineTimeFormat {
+    /// `45s`, `12m 05s`, `1h 23m 45s`.
+    #[default]
+    Units,
+    /// Minutes and seconds, `0:45`, `12:05`, `83:45`.
+    Clock,
+    /// The two largest units, `45s`, `12m05s`, `1h23m`.
+    Compact,
+    /// Hours, minutes and seconds, `00:00:45`, `00:12:05`, `01:23:45`.
+    Hms,
+}
+
+/// How the status line shows segments whose probe has gone stale.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusLineStaleMode {
+    /// Gray the segment out.
+    #[default]
+    Dim,
+    /// Leave the segment out.
+    Hide,
+}
+
+/// When the status line shows the hostname.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusLineHostnameMode {
+    /// Whenever the hostname is known.
+    #[default]
+    Always,
+    /// Only when `SSH_CONNECTION` or `SSH_TTY` says the session is remote.
+    SshOnly,
+}
+
+/// Built-in status line color themes.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusLineThemeName {
+    /// Pastel segments for dark terminal backgrounds.
+    #[default]
+    Dark,
+    /// Saturated segments for light terminal backgrounds.
+    Light,
+    /// The Solarized accent colors.
+    Solarized,
+}
+
+/// Status line colors to override, under `[statusline.colors]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct StatusLineColorsToml {
+    pub model: Option<String>,
+    pub cwd: Option<String>,
+    pub git_clean: Option<String>,
+    pub git_dirty: Option<String>,
+    pub git_conflict: Option<String>,
+    /// Context bar above `statusline.context_warn_at`.
+    pub context_ok: Option<String>,
+    /// Context bar from `statusline.context_warn_at` down.
+    pub context_warn: Option<String>,
+    /// Context bar between the warning and critical thresholds, from 20%
+    /// left or halfway between configured thresholds.
+    pub context_high: Option<String>,
+    /// Context bar from `statusline.context_critical_at` down.
+    pub context_critical: Option<String>,
+}
+
+impl StatusLineColorsToml {
+    fn inherit(self, base: StatusLineColorsToml) -> StatusLineColorsToml {
+        StatusLineColorsToml {
+            model: self.model.or(base.model),
+            cwd: self.cwd.or(base.cwd),
+            git_clean: self.git_clean.or(base.git_clean),
+            git_dirty: self.git_dirty.or(base.git_dirty),
+            git_conflict: self.git_conflict.or(base.git_conflict),
+            context_ok: self.context_ok.or(base.context_ok),
+            context_warn: self.context_warn.or(base.context_warn),
+            context_high: self.context_high.or(base.context_high),
+            context_critical: self.context_critical.or(base.context_critical),
+        }
+    }
+}
+
+/// [`StatusLineColorsToml`] parsed to RGB; `None` keeps the theme's color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatusLineColors {
+    pub model: Option<[u8; 3]>,
+    pub cwd: Option<[u8; 3]>,
+    pub git_clean: Option<[u8; 3]>,
+    pub git_dirty: Option<[u8; 3]>,
+    pub git_conflict: Option<[u8; 3]>,
+    pub context_ok: Option<[u8; 3]>,
+    pub context_warn: Option<[u8; 3]>,
+    pub context_high: Option<[u8; 3]>,
+    pub context_critical: Option<[u8; 3]>,
+}
+
+impl StatusLineColors {
+    /// Colors that are not `#rrggbb` are skipped with a warning.
+    fn parse(toml: &StatusLineColorsToml, warnings: &mut Vec<String>) -> Self {
+        let mut parse = |key: &str, value: Option<&str>| {
+            let value = value?;
+            let rgb = parse_hex_color(value);
+            if rgb.is_none() {
+                warnings.push(format!(
+                    "statusline.colors.{key}: expected a color like \"#a6e3a1\", got `{value}`"
+                ));
+            }
+            rgb
+        };
+        Self {
+            model: parse("model", toml.model.as_deref()),
+            cwd: parse("cwd", toml.cwd.as_deref()),
+            git_clean: parse("git_clean", toml.git_clean.as_deref()),
+            git_dirty: parse("git_dirty", toml.git_dirty.as_deref()),
+            git_conflict: parse("git_conflict", toml.git_conflict.as_deref()),
+            context_ok: parse("context_ok", toml.context_ok.as_deref()),
+            context_warn: parse("context_warn", toml.context_warn.as_deref()),
+            context_high: parse("context_high", toml.context_high.as_deref()),
+            context_critical: parse("context_critical", toml.context_critical.as_deref()),
+        }
+    }
+}
+
+/// Percent of the context window left at which the status line's context
+/// segment changes color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusLineContextThresholds {
+    pub warn: u8,
+    pub high: u8,
+    pub critical: u8,
+}
+
+impl Default for StatusLineContextThresholds {
+    fn default() -> Self {
+        Self {
+            warn: 40,
+            high: 20,
+            critical: 8,
+        }
+    }
+}
+
+/// How the status line previews messages queued while a run is going.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusLineQueuePreview {
+    /// Messages previewed; the rest are shown as a count.
+    pub messages: usize,
+    /// Columns of each preview before it is cut off.
+    pub width: usize,
+    /// Show `+3 queued` instead of previews.
+    pub count_only: bool,
+    pub edit_hint: bool,
+}
+
+impl Default for StatusLineQueuePreview {
+    fn default() -> Self {
+        Self {
+            messages: 1,
+            width: 32,
+            count_only: false,
+            edit_hint: true,
+        }
+    }
+}
+
+impl StatusLineQueuePreview {
+    /// Shortest preview that still shows a word before the `…`.
+    const MIN_WIDTH: usize = 4;
+
+    /// Zero messages or a width too narrow to show anything fall back to
+    /// the default with a warning.
+    fn parse(toml: &StatusLineToml, warnings: &mut Vec<String>) -> Self {
+        let default = Self::default();
+        let messages = match toml.queue_preview_messages {
+            Some(0) => {
+                warnings.push(
+                    "statusline.queue_preview_messages: expected at least 1, got 0; use queue_count_only to hide the previews"
+                        .to_string(),
+                );
+                default.messages
+            }
+            messages => messages.unwrap_or(default.messages),
+        };
+        let width = match toml.queue_preview_width {
+            Some(width) if width < Self::MIN_WIDTH => {
+                warnings.push(format!(
+                    "statusline.queue_preview_width: expected at least {}, got {width}",
+                    Self::MIN_WIDTH
+                ));
+                default.width
+            }
+            width => width.unwrap_or(default.width),
+        };
+        Self {
+            messages,
+            width,
+            count_only: toml.queue_count_only.unwrap_or(default.count_only),
+            edit_hint: toml.queue_edit_hint.unwrap_or(default.edit_hint),
+        }
+    }
+}
+
+/// How full the context window is, by [`StatusLineContextThresholds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StatusLineContextLevel {
+    Ok,
+    Warn,
+    High,
+    Critical,
+}
+
+impl StatusLineContextThresholds {
+    /// Thresholds out of range, or a critical threshold above the warning
+    /// one, are skipped with a warning. The high level sits halfway between
+    /// configured thresholds.
+    fn parse(warn: Option<u8>, critical: Option<u8>, warnings: &mut Vec<String>) -> Self {
+        let default = Self::default();
+        if warn.is_none() && critical.is_none() {
+            return default;
+        }
+        let mut check = |key: &str, value: Option<u8>| match value {
+            Some(percent) if percent > 100 => {
+                warnings.push(format!(
+                    "statusline.{key}: expected a percentage up to 100, got {percent}"
+                ));
+                None
+            }
+            value => value,
+        };
+        let warn = check("context_warn_at", warn).unwrap_or(default.warn);
+        let critical = check("context_critical_at", critical).unwrap_or(default.critical);
+        if critical > warn {
+            warnings.push(format!(
+                "statusline.context_critical_at: expected at most context_warn_at ({warn}), got {critical}"
+            ));
+            return default;
+        }
+        Self {
+            warn,
+            high: ((u16::from(warn) + u16::from(critical)) / 2) as u8,
+            critical,
+        }
+    }
+
+    /// The level with `percent_remaining` of the window left.
+    pub fn level(&self, percent_remaining: f64) -> StatusLineContextLevel {
+        if percent_remaining <= f64::from(self.critical) {
+            StatusLineContextLevel::Critical
+        } else if percent_remaining <= f64::from(self.high) {
+            StatusLineContextLevel::High
+        } else if percent_remaining <= f64::from(self.warn) {
+            StatusLineContextLevel::Warn
+        } else {
+            StatusLineContextLevel::Ok
+        }
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// A status line segment showing the trimmed output of `command`, re-run
+/// every `interval_secs`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CustomStatusSegment {
+    /// Placeholder name used in `statusline.format`.
+    pub name: String,
+    pub command: String,
+    #[serde(default = "default_status_segment_interval_secs")]
+    pub interval_secs: u64,
+}
+
+const fn default_status_segment_interval_secs() -> u64 {
+    30
+}
+
+/// A segment of the status line layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusLineItem {
+    /// Run state capsule, with the run timer and queued messages.
+    Status,
+    Cwd,
+    Model,
+    Tokens,
+    Git,
+    Context,
+    Hostname,
+    Aws,
+    /// `gcloud` project of the active configuration.
+    Gcp,
+    /// Azure CLI subscription.
+    Azure,
+    Kubernetes,
+    Devspace,
+    /// Active Python virtualenv or conda environment.
+    Python,
+    /// Node version pinned by the project in the working directory.
+    Node,
+    /// Selected Docker context.
+    Docker,
+    /// Selected Terraform workspace.
+    Terraform,
+    Resources,
+    /// Wall-clock time.
+    Clock,
+    /// Laptop battery charge.
+    Battery,
+    /// Estimated USD cost of the last turn and of the session.
+    Cost,
+    /// Short session id, to resume the session later.
+    Session,
+    /// Connected MCP servers out of the enabled ones.
+    Mcp,
+    /// Sandbox policy and approval mode.
+    Sandbox,
+    /// Proxy in use, and whether the sandbox blocks network access.
+    Network,
+    /// A `[[statusline.segments]]` entry, by name.
+    Custom(String),
+}
+
+impl StatusLineItem {
+    fn builtin(name: &str) -> Option<Self> {
+        Some(match name {
+            "status" => Self::Status,
+            "cwd" => Self::Cwd,
+            "model" => Self::Model,
+            "tokens" => Self::Tokens,
+            "git" => Self::Git,
+            "context" => Self::Context,
+            "hostname" => Self::Hostname,
+            "aws" => Self::Aws,
+            "gcp" | "gcloud" => Self::Gcp,
+            "azure" | "az" => Self::Azure,
+            "k8s" | "kubernetes" => Self::Kubernetes,
+            "devspace" => Self::Devspace,
+            "python" | "venv" => Self::Python,
+            "node" => Self::Node,
+            "docker" => Self::Docker,
+            "terraform" | "tf" => Self::Terraform,
+            "resources" => Self::Resources,
+            "clock" | "time" => Self::Clock,
+            "battery" => Self::Battery,
+            "cost" => Self::Cost,
+            "session" => Self::Session,
+            "mcp" => Self::Mcp,
+            "sandbox" | "approvals" => Self::Sandbox,
+            "network" | "proxy" => Self::Network,
+            _ => return None,
+        })
+    }
+}
+
+/// Status line segments from `statusline.format` (left-aligned) and
+/// `statusline.right_format` (right-aligned), in order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StatusLineLayout {
+    pub left: Vec<StatusLineItem>,
+    pub right: Vec<StatusLineItem>,
+}
+
+/// Resolved `[statusline]` settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusLineConfig {
+    /// `None` keeps the built-in layout.
+    pub layout: Option<StatusLineLayout>,
+    pub custom_segments: Vec<CustomStatusSegment>,
+    pub show_cost: bool,
+    pub show_python_env: bool,
+    pub docker_probe: bool,
+    pub show_azure: bool,
+    pub hostname: StatusLineHostnameMode,
+    pub show_clock: bool,
+    pub show_battery: bool,
+    pub show_session: bool,
+    pub show_mcp: bool,
+    pub show_sandbox: bool,
+    pub show_network: bool,
+    /// `1` or `2`.
+    pub lines: u16,
+    pub style: StatusLineStyle,
+    pub theme: StatusLineThemeName,
+    pub colors: StatusLineColors,
+    pub context_thresholds: StatusLineContextThresholds,
+    pub context_flash: bool,
+    pub spinner: StatusLineSpinner,
+    /// `None` uses the spinner's own speed.
+    pub spinner_interval: Option<Duration>,
+    pub time_format: StatusLineTimeFormat,
+    pub show_wall_time: bool,
+    pub queue_preview: StatusLineQueuePreview,
+    /// `None` never counts probed segments as stale.
+    pub stale_after: Option<Duration>,
+    pub stale: StatusLineStaleMode,
+    pub terminal_title: bool,
+    /// `statusline.command`; `None` uses the built-in renderer.
+    pub command: Option<String>,
+    /// Problems found in `statusline.format`, `statusline.lines`,
+    /// `statusline.colors` and the context thresholds, shown when the TUI
+    /// starts.
+    pub warnings: Vec<String>,
+}
+
+impl From<StatusLineToml> for StatusLineConfig {
+    fn from(toml: StatusLineToml) -> Self {
+        let mut warnings = Vec::new();
+        let layout = (toml.format.is_some() || toml.right_format.is_some()).then(|| {
+            let mut parse = |key: &str, format: Option<&str>| {
+                format.map_or_else(Vec::new, |format| {
+                    parse_statusline_format(key, format, &toml.segments, &mut warnings)
+                })
+            };
+            StatusLineLayout {
+                left: parse("statusline.format", toml.format.as_deref()),
+                right: parse("statusline.right_format", toml.right_format.as_deref()),
+            }
+        });
+        let lines = match toml.lines {
+            None | Some(1) => 1,
+            Some(2) => 2,
+            Some(lines) => {
+                warnings.push(format!("statusline.lines: expected 1 or 2, got {lines}"));
+                1
+            }
+        };
+        let colors = StatusLineColors::parse(&toml.colors, &mut warnings);
+        let context_thresholds = StatusLineContextThresholds::parse(
+            toml.context_warn_at,
+            toml.context_critical_at,
+            &mut warnings,
+        );
+        let spinner_interval = match toml.spinner_interval_ms {
+            Some(0) => {
+                warnings.push(
+                    "statusline.spinner_interval_ms: expected a positive number, got 0".to_string(),
+                );
+                None
+            }
+            interval_ms => interval_ms.map(Duration::from_millis),
+        };
+        let queue_preview = StatusLineQueuePreview::parse(&toml, &mut warnings);
+        Self {
+            layout,
+            custom_segments: toml.segments,
+            show_cost: toml.show_cost.unwrap_or(false),
+            show_python_env: toml.show_python_env.unwrap_or(true),
+            docker_probe: toml.docker_probe.unwrap_or(false),
+            show_azure: toml.show_azure.unwrap_or(false),
+            hostname: toml.hostname.unwrap_or_default(),
+            show_clock: toml.show_clock.unwrap_or(false),
+            show_battery: toml.show_battery.unwrap_or(false),
+            show_session: toml.show_session.unwrap_or(false),
+            show_mcp: toml.show_mcp.unwrap_or(true),
+            show_sandbox: toml.show_sandbox.unwrap_or(false),
+            show_network: toml.show_network.unwrap_or(false),
+            lines,
+            style: toml.style.unwrap_or_default(),
+            theme: toml.theme.unwrap_or_default(),
+            colors,
+            context_thresholds,
+            context_flash: toml.context_flash.unwrap_or(false),
+            spinner: toml.spinner.unwrap_or_default(),
+            spinner_interval,
+            time_format: toml.time_format.unwrap_or_default(),
+            show_wall_time: toml.show_wall_time.unwrap_or(false),
+            queue_preview,
+            stale_after: toml.stale_after_secs.map(Duration::from_secs),
+            stale: toml.stale.unwrap_or_default(),
+            terminal_title: toml.terminal_title.unwrap_or(false),
+            command: toml.command.filter(|command| !command.trim().is_empty()),
+            warnings,
+        }
+    }
+}
+
+impl Default for StatusLineConfig {
+    fn default() -> Self {
+        StatusLineToml::default().into()
+    }
+}
+
+impl StatusLineConfig {
+    /// Whether the status line shows the clock: the built-in layout when
+    /// `show_clock` is on, a template when it places `{clock}`.
+    pub fn shows_clock(&self) -> bool {
+        self.shows(&StatusLineItem::Clock, self.show_clock)
+    }
+
+    /// Like [`Self::shows_clock`], for the battery.
+    pub fn shows_battery(&self) -> bool {
+        self.shows(&StatusLineItem::Battery, self.show_battery)
+    }
+
+    /// Like [`Self::shows_clock`], for the session id.
+    pub fn shows_session(&self) -> bool {
+        self.shows(&StatusLineItem::Session, self.show_session)
+    }
+
+    /// Like [`Self::shows_clock`], for the MCP servers.
+    pub fn shows_mcp(&self) -> bool {
+        self.shows(&StatusLineItem::Mcp, self.show_mcp)
+    }
+
+    /// Like [`Self::shows_clock`], for the sandbox policy.
+    pub fn shows_sandbox(&self) -> bool {
+        self.shows(&StatusLineItem::Sandbox, self.show_sandbox)
+    }
+
+    /// Like [`Self::shows_clock`], for the proxy and offline indicator.
+    pub fn shows_network(&self) -> bool {
+        self.shows(&StatusLineItem::Network, self.show_network)
+    }
+
+    fn shows(&self, item: &StatusLineItem, builtin: bool) -> bool {
+        match &self.layout {
+            Some(layout) => layout.left.contains(item) || layout.right.contains(item),
+            None => builtin,
+        }
+    }
+}
+
+/// Placeholders of the `key` template `format` in order. Unknown
+/// placeholders and text outside of placeholders are skipped with a warning.
+fn parse_statusline_format(
+    key: &str,
+    format: &str,
+    custom_segments: &[CustomStatusSegment],
+    warnings: &mut Vec<String>,
+) -> Vec<StatusLineItem> {
+    let mut items = Vec::new();
+    let mut rest = format;
+    while !rest.is_empty() {
+        let Some(open) = rest.find('{') else {
+            warn_literal(key, rest, warnings);
+            break;
+        };
+        warn_literal(key, &rest[..open], warnings);
+        let Some(close) = rest[open..].find('}') else {
+            warnings.push(format!("{key}: unclosed placeholder `{}`", &rest[open..]));
+            break;
+        };
+        let name = rest[open + 1..open + close].trim();
+        // A `[[statusline.segments]]` entry keeps its name when a built-in
+        // segment of the same name is added later.
+        if custom_segments.iter().any(|segment| segment.name == name) {
+            items.push(StatusLineItem::Custom(name.to_string()));
+        } else if let Some(item) = StatusLineItem::builtin(name) {
+            items.push(item);
+        } else {
+            warnings.push(format!("{key}: unknown placeholder `{{{name}}}`"));
+        }
+        rest = &rest[open + close + 1..];
+    }
+    items
+}
+
+fn warn_literal(key: &str, text: &str, warnings: &mut Vec<String>) {
+    let text = text.trim();
+    if !text.is_empty() {
+        warnings.push(format!(
+            "{key}: text outside placeholders is ignored: `{text}`"
+        ));
+    }
+}
+
+/// Token limits and capabilities for a single model, keyed by model slug
+/// under `[model_overrides]`. Values set here win over the top-level
+/// `model_context_window` / `model_max_output_tokens` settings and over the
+/// built-in model metadata.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModelOverride {
+    /// Size of the context window for the model, in tokens.
+    pub context_window: Option<u64>,
+
+    /// Maximum number of output tokens.
+    pub max_output_tokens: Option<u64>,
+
+    /// Token usage threshold triggering auto-compaction of conversation history.
+    pub auto_compact_token_limit: Option<i64>,
+
+    /// Whether the model accepts image inputs.
+    pub supports_image_input: Option<bool>,
+
+    /// Whether the model accepts tool definitions.
+    pub supports_tools: Option<bool>,
+
+    /// Whether the model may issue several tool calls in one response.
+    pub supports_parallel_tool_calls: Option<bool>,
+
+    /// Whether the `reasoning` request field may be sent to the model.
+    pub supports_reasoning_summaries: Option<bool>,
+}
+
+/// Prices of a model in USD per million tokens, keyed by model slug under
+/// `[model_pricing]`. Used to estimate what a session costs; entries here
+/// win over the built-in price list.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+
+    /// Price of cached input tokens; defaults to the input price.
+    pub cached_input_per_million: Option<f64>,
+
+    pub output_per_million: f64,
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
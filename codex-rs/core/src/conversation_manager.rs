@@ -2,6 +2,7 @@ use crate::AuthManager;
 use crate::CodexAuth;
 use crate::codex::Codex;
 use crate::codex::CodexSpawnOk;
+use crate::codex::EventBuffer;
 use crate::codex::INITIAL_SUBMIT_ID;
 use crate::codex::compact::content_items_to_text;
 use crate::codex::compact::is_session_prefix_message;
@@ -37,6 +38,7 @@ pub struct ConversationManager {
     conversations: Arc<RwLock<HashMap<ConversationId, Arc<CodexConversation>>>>,
     auth_manager: Arc<AuthManager>,
     session_source: SessionSource,
+    event_buffer: EventBuffer,
 }
 
 impl ConversationManager {
@@ -45,9 +47,17 @@ impl ConversationManager {
             conversations: Arc::new(RwLock::new(HashMap::new())),
             auth_manager,
             session_source,
+            event_buffer: EventBuffer::default(),
         }
     }
 
+    /// Buffer the events of the conversations this manager starts as
+    /// `event_buffer` says.
+    pub fn with_event_buffer(mut self, event_buffer: EventBuffer) -> Self {
+        self.event_buffer = event_buffer;
+        self
+    }
+
     /// Construct with a dummy AuthManager containing the provided CodexAuth.
     /// Used for integration tests: should not be used by ordinary business logic.
     pub fn with_auth(auth: CodexAuth) -> Self {
@@ -75,6 +85,7 @@ impl ConversationManager {
             auth_manager,
             InitialHistory::New,
             self.session_source,
+            self.event_buffer,
         )
         .await?;
         self.finalize_spawn(codex, conversation_id).await
@@ -133,7 +144,14 @@ impl ConversationManager {
         let CodexSpawnOk {
             codex,
             conversation_id,
-        } = Codex::spawn(config, auth_manager, initial_history, self.session_source).await?;
+        } = Codex::spawn(
+            config,
+            auth_manager,
+            initial_history,
+            self.session_source,
+            self.event_buffer,
+        )
+        .await?;
         self.finalize_spawn(codex, conversation_id).await
     }
 
@@ -167,7 +185,14 @@ impl ConversationManager {
         let CodexSpawnOk {
             codex,
             conversation_id,
-        } = Codex::spawn(config, auth_manager, history, self.session_source).await?;
+        } = Codex::spawn(
+            config,
+            auth_manager,
+            history,
+            self.session_source,
+            self.event_buffer,
+        )
+        .await?;
 
         self.finalize_spawn(codex, conversation_id).await
     }
@@ -0,0 +1,281 @@
+//! Devcontainers: projects that describe their toolchain in
+//! `.devcontainer/devcontainer.json`. When the user opts in, the agent's
+//! shell commands run inside the project's (already running) container,
+//! while `apply_patch` keeps editing the files on the host, which the
+//! container sees through its workspace mount.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::exec::ExecParams;
+use crate::git_info::get_git_repo_root;
+use crate::remote::quote;
+
+/// Where devcontainer configurations live, relative to the project root.
+const CONFIG_CANDIDATES: [&str; 2] = [".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// Label the devcontainer tooling puts on a project's container.
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+
+/// A devcontainer configuration found for the working directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Devcontainer {
+    /// Host directory the configuration belongs to; mounted in the container.
+    pub workspace_folder: PathBuf,
+    /// The `devcontainer.json` that was found.
+    pub config_path: PathBuf,
+    /// Where `workspace_folder` is mounted inside the container.
+    pub container_workspace_folder: PathBuf,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct DevcontainerJson {
+    workspace_folder: Option<PathBuf>,
+}
+
+/// Look for a devcontainer configuration in `cwd` and its parents, up to the
+/// enclosing git repository root.
+pub fn find_devcontainer(cwd: &Path) -> Option<Devcontainer> {
+    let repo_root = get_git_repo_root(cwd);
+    for dir in cwd.ancestors() {
+        for candidate in CONFIG_CANDIDATES {
+            let config_path = dir.join(candidate);
+            if config_path.is_file() {
+                return Some(load(dir, config_path));
+            }
+        }
+        if repo_root.as_deref().is_none_or(|root| root == dir) {
+            break;
+        }
+    }
+    None
+}
+
+fn load(workspace_folder: &Path, config_path: PathBuf) -> Devcontainer {
+    let parsed = std::fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<DevcontainerJson>(&strip_jsonc(&text)).ok())
+        .unwrap_or_default();
+    // The devcontainer CLI mounts the project at /workspaces/<name> unless
+    // told otherwise.
+    let container_workspace_folder = parsed.workspace_folder.unwrap_or_else(|| {
+        let name = workspace_folder
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        PathBuf::from("/workspaces").join(name)
+    });
+    Devcontainer {
+        workspace_folder: workspace_folder.to_path_buf(),
+        config_path,
+        container_workspace_folder,
+    }
+}
+
+/// Remove the comments and trailing commas `devcontainer.json` allows so the
+/// rest parses as plain JSON.
+fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (']' | '}', _) => {
+                let trimmed = out.trim_end_matches(char::is_whitespace).len();
+                if out[..trimmed].ends_with(',') {
+                    out.remove(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl Devcontainer {
+    /// The directory inside the container that corresponds to `cwd` on the
+    /// host; the workspace root for directories outside the mount.
+    fn container_cwd(&self, cwd: &Path) -> PathBuf {
+        match cwd.strip_prefix(&self.workspace_folder) {
+            Ok(relative) => self.container_workspace_folder.join(relative),
+            Err(_) => self.container_workspace_folder.clone(),
+        }
+    }
+
+    /// Rewrite `params` so the command runs inside the container. Uses the
+    /// `devcontainer` CLI when it is installed and `docker exec` against the
+    /// project's running container otherwise. The local process gets this
+    /// process's environment; the command itself sees the container's.
+    pub(crate) async fn wrap_exec_params(&self, params: ExecParams) -> std::io::Result<ExecParams> {
+        let container_cwd = self.container_cwd(&params.cwd);
+        let command = if which::which("devcontainer").is_ok() {
+            let script = format!(
+                "cd {} && exec {}",
+                quote(&container_cwd.to_string_lossy()),
+                params
+                    .command
+                    .iter()
+                    .map(String::as_str)
+                    .map(quote)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+            vec![
+                "devcontainer".to_string(),
+                "exec".to_string(),
+                "--workspace-folder".to_string(),
+                self.workspace_folder.to_string_lossy().into_owned(),
+                "--config".to_string(),
+                self.config_path.to_string_lossy().into_owned(),
+                "sh".to_string(),
+                "-c".to_string(),
+                script,
+            ]
+        } else {
+            let container = self.running_container().await?;
+            let mut command = vec![
+                "docker".to_string(),
+                "exec".to_string(),
+                "-w".to_string(),
+                container_cwd.to_string_lossy().into_owned(),
+                container,
+            ];
+            command.extend(params.command.iter().cloned());
+            command
+        };
+        Ok(ExecParams {
+            command,
+            cwd: self.workspace_folder.clone(),
+            env: std::env::vars().collect(),
+            ..params
+        })
+    }
+
+    /// ID of the running container the devcontainer tooling started for this
+    /// project.
+    async fn running_container(&self) -> std::io::Result<String> {
+        let output = Command::new("docker")
+            .args(["ps", "--quiet", "--filter"])
+            .arg(format!(
+                "label={LOCAL_FOLDER_LABEL}={}",
+                self.workspace_folder.display()
+            ))
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(std::io::Error::other(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .map(str::to_string)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!(
+                        "no running devcontainer for {}; start it with `devcontainer up --workspace-folder {}`",
+                        self.workspace_folder.display(),
+                        self.workspace_folder.display()
+                    ),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        let text = r#"{
+  // The image to use.
+  "image": "mcr.microsoft.com/devcontainers/rust", /* pinned */
+  "workspaceFolder": "/src//app",
+  "features": { "a": 1, },
+}"#;
+        let value: serde_json::Value =
+            serde_json::from_str(&strip_jsonc(text)).expect("plain json");
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "image": "mcr.microsoft.com/devcontainers/rust",
+                "workspaceFolder": "/src//app",
+                "features": { "a": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn finds_config_in_parent_and_maps_cwd() {
+        let dir = TempDir::new().expect("tempdir");
+        let root = dir.path().join("project");
+        let nested = root.join("crates/core");
+        std::fs::create_dir_all(root.join(".git")).expect("git dir");
+        std::fs::create_dir_all(root.join(".devcontainer")).expect("devcontainer dir");
+        std::fs::create_dir_all(&nested).expect("nested dir");
+        std::fs::write(root.join(".devcontainer/devcontainer.json"), "{}").expect("write");
+
+        let devcontainer = find_devcontainer(&nested).expect("devcontainer found");
+
+        assert_eq!(devcontainer.workspace_folder, root);
+        assert_eq!(
+            devcontainer.container_workspace_folder,
+            PathBuf::from("/workspaces/project")
+        );
+        assert_eq!(
+            devcontainer.container_cwd(&nested),
+            PathBuf::from("/workspaces/project/crates/core")
+        );
+    }
+
+    #[test]
+    fn stops_at_repository_root() {
+        let dir = TempDir::new().expect("tempdir");
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join(".git")).expect("git dir");
+        std::fs::write(dir.path().join(".devcontainer.json"), "{}").expect("write");
+
+        assert_eq!(find_devcontainer(&root), None);
+    }
+}
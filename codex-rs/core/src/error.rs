@@ -3,6 +3,7 @@ use crate::token_data::KnownPlan;
 use crate::token_data::PlanType;
 use crate::truncate::truncate_middle;
 use codex_protocol::ConversationId;
+use codex_protocol::protocol::ErrorKind;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
@@ -305,6 +306,49 @@ impl CodexErr {
     pub fn downcast_ref<T: std::any::Any>(&self) -> Option<&T> {
         (self as &dyn std::any::Any).downcast_ref::<T>()
     }
+
+    /// Whether the request failed because of a rate limit: a
+    /// `rate_limit_exceeded` error with its retry delay, or HTTP 429 until
+    /// the request retries ran out.
+    pub fn is_rate_limit(&self) -> bool {
+        match self {
+            CodexErr::Stream(_, delay) => delay.is_some(),
+            CodexErr::RetryLimit(err) => err.status == StatusCode::TOO_MANY_REQUESTS,
+            _ => false,
+        }
+    }
+
+    /// What failed, for the [`ErrorKind`] on the error event.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            CodexErr::UnexpectedStatus(UnexpectedResponseError { status, .. })
+            | CodexErr::RetryLimit(RetryLimitReachedError { status, .. })
+                if is_auth_status(*status) =>
+            {
+                ErrorKind::Auth
+            }
+            CodexErr::Reqwest(err) if err.status().is_some_and(is_auth_status) => ErrorKind::Auth,
+            // A missing API key variable.
+            CodexErr::EnvVar(_) => ErrorKind::Auth,
+            CodexErr::Stream(..) | CodexErr::Reqwest(_) => ErrorKind::Network,
+            CodexErr::UnexpectedStatus(_)
+            | CodexErr::RetryLimit(_)
+            | CodexErr::UsageLimitReached(_)
+            | CodexErr::UsageNotIncluded
+            | CodexErr::InternalServerError
+            | CodexErr::ContextWindowExceeded => ErrorKind::Provider,
+            CodexErr::Sandbox(_) | CodexErr::LandlockSandboxExecutableNotProvided => {
+                ErrorKind::Sandbox
+            }
+            #[cfg(target_os = "linux")]
+            CodexErr::LandlockRuleset(_) | CodexErr::LandlockPathFd(_) => ErrorKind::Sandbox,
+            _ => ErrorKind::Internal,
+        }
+    }
+}
+
+fn is_auth_status(status: StatusCode) -> bool {
+    status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN
 }
 
 pub fn get_error_message_ui(e: &CodexErr) -> String {
@@ -362,6 +406,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn errors_are_classified_by_what_failed() {
+        let status = |status| {
+            CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                status,
+                body: String::new(),
+                request_id: None,
+            })
+        };
+        assert_eq!(status(StatusCode::UNAUTHORIZED).kind(), ErrorKind::Auth);
+        assert_eq!(status(StatusCode::BAD_REQUEST).kind(), ErrorKind::Provider);
+        assert_eq!(
+            CodexErr::EnvVar(EnvVarError {
+                var: "OPENAI_API_KEY".to_string(),
+                instructions: None,
+            })
+            .kind(),
+            ErrorKind::Auth
+        );
+        assert_eq!(
+            CodexErr::Stream("reset".to_string(), None).kind(),
+            ErrorKind::Network
+        );
+        assert_eq!(CodexErr::ContextWindowExceeded.kind(), ErrorKind::Provider);
+        assert_eq!(
+            CodexErr::Sandbox(SandboxErr::Signal(9)).kind(),
+            ErrorKind::Sandbox
+        );
+        assert_eq!(CodexErr::InternalAgentDied.kind(), ErrorKind::Internal);
+    }
+
     #[test]
     fn usage_limit_reached_error_formats_plus_plan() {
         let err = UsageLimitReachedError {
@@ -39,12 +39,31 @@ const EXEC_TIMEOUT_EXIT_CODE: i32 = 124; // conventional timeout exit code
 
 // I/O buffer sizing
 const READ_CHUNK_SIZE: usize = 8192; // bytes per read
-const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
 
 /// Limit the number of ExecCommandOutputDelta events emitted per exec call.
-/// Aggregation still collects full output; only the live event stream is capped.
+/// Aggregation still collects the output; only the live event stream is capped.
 pub(crate) const MAX_EXEC_OUTPUT_DELTAS_PER_CALL: usize = 10_000;
 
+/// Limit the bytes of live output sent per stream of an exec call; later
+/// output only reaches the aggregated output.
+const MAX_EXEC_OUTPUT_DELTA_BYTES_PER_CALL: usize = 4 * 1024 * 1024;
+
+/// Shortest time between two output deltas of one stream, so a command
+/// writing as fast as it can yields at most 30 updates a second; output in
+/// between is merged into the next delta.
+const OUTPUT_DELTA_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Most output kept per stream, and for the aggregated output: the first and
+/// last half of it, with a note of how much was dropped in between.
+const MAX_EXEC_OUTPUT_RETAINED_BYTES: usize = 8 * 1024 * 1024;
+
+/// Most output held back for the next delta; later output only reaches the
+/// aggregated output until that delta is sent.
+const MAX_PENDING_OUTPUT_DELTA_BYTES: usize = 256 * 1024;
+
+/// How often held-back output is offered again while the command is quiet.
+const PENDING_OUTPUT_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
 #[derive(Clone, Debug)]
 pub struct ExecParams {
     pub command: Vec<String>,
@@ -280,9 +299,45 @@ impl StreamOutput<Vec<u8>> {
     }
 }
 
-#[inline]
-fn append_all(dst: &mut Vec<u8>, src: &[u8]) {
-    dst.extend_from_slice(src);
+/// Output of a command, keeping the first and last
+/// `MAX_EXEC_OUTPUT_RETAINED_BYTES / 2` bytes of it.
+#[derive(Default)]
+struct RetainedOutput {
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    omitted: usize,
+}
+
+impl RetainedOutput {
+    const HALF: usize = MAX_EXEC_OUTPUT_RETAINED_BYTES / 2;
+
+    fn push(&mut self, chunk: &[u8]) {
+        let to_head = Self::HALF.saturating_sub(self.head.len()).min(chunk.len());
+        self.head.extend_from_slice(&chunk[..to_head]);
+        self.tail.extend_from_slice(&chunk[to_head..]);
+        // Trim in batches so each byte is moved at most once.
+        if self.tail.len() >= 2 * Self::HALF {
+            self.trim_tail();
+        }
+    }
+
+    fn trim_tail(&mut self) {
+        let excess = self.tail.len().saturating_sub(Self::HALF);
+        self.tail.drain(..excess);
+        self.omitted += excess;
+    }
+
+    fn into_bytes(mut self) -> Vec<u8> {
+        self.trim_tail();
+        let mut bytes = self.head;
+        if self.omitted > 0 {
+            bytes.extend_from_slice(
+                format!("\n[... {} bytes omitted ...]\n", self.omitted).as_bytes(),
+            );
+        }
+        bytes.extend_from_slice(&self.tail);
+        bytes
+    }
 }
 
 #[derive(Debug)]
@@ -348,6 +403,15 @@ async fn consume_truncated_output(
     })?;
 
     let (agg_tx, agg_rx) = async_channel::unbounded::<Vec<u8>>();
+    // Drain the aggregate as it is produced so a flood of output is trimmed
+    // instead of queued.
+    let aggregate_handle = tokio::spawn(async move {
+        let mut aggregate = RetainedOutput::default();
+        while let Ok(chunk) = agg_rx.recv().await {
+            aggregate.push(&chunk);
+        }
+        aggregate.into_bytes()
+    });
 
     let stdout_handle = tokio::spawn(read_capped(
         BufReader::new(stdout_reader),
@@ -388,12 +452,8 @@ async fn consume_truncated_output(
 
     drop(agg_tx);
 
-    let mut combined_buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
-    while let Ok(chunk) = agg_rx.recv().await {
-        append_all(&mut combined_buf, &chunk);
-    }
     let aggregated_output = StreamOutput {
-        text: combined_buf,
+        text: aggregate_handle.await?,
         truncated_after_lines: None,
     };
 
@@ -412,54 +472,157 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     is_stderr: bool,
     aggregate_tx: Option<Sender<Vec<u8>>>,
 ) -> io::Result<StreamOutput<Vec<u8>>> {
-    let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
+    let mut buf = RetainedOutput::default();
     let mut tmp = [0u8; READ_CHUNK_SIZE];
-    let mut emitted_deltas: usize = 0;
-
-    // No caps: append all bytes
+    let mut deltas = stream.map(|stream| OutputDeltas::new(stream, is_stderr));
 
     loop {
-        let n = reader.read(&mut tmp).await?;
+        let read = reader.read(&mut tmp);
+        let n = match deltas.as_ref().and_then(OutputDeltas::flush_deadline) {
+            Some(deadline) => match tokio::time::timeout_at(deadline, read).await {
+                Ok(n) => n?,
+                Err(_) => {
+                    if let Some(deltas) = deltas.as_mut() {
+                        deltas.try_flush();
+                    }
+                    continue;
+                }
+            },
+            None => read.await?,
+        };
         if n == 0 {
             break;
         }
 
-        if let Some(stream) = &stream
-            && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
-        {
-            let chunk = tmp[..n].to_vec();
-            let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
-                call_id: stream.call_id.clone(),
-                stream: if is_stderr {
-                    ExecOutputStream::Stderr
-                } else {
-                    ExecOutputStream::Stdout
-                },
-                chunk,
-            });
-            let event = Event {
-                id: stream.sub_id.clone(),
-                msg,
-            };
-            #[allow(clippy::let_unit_value)]
-            let _ = stream.tx_event.send(event).await;
-            emitted_deltas += 1;
+        if let Some(deltas) = deltas.as_mut() {
+            deltas.push(&tmp[..n]);
         }
 
         if let Some(tx) = &aggregate_tx {
             let _ = tx.send(tmp[..n].to_vec()).await;
         }
 
-        append_all(&mut buf, &tmp[..n]);
+        buf.push(&tmp[..n]);
         // Continue reading to EOF to avoid back-pressure
     }
 
+    if let Some(deltas) = deltas.as_mut() {
+        deltas.flush().await;
+    }
+
     Ok(StreamOutput {
-        text: buf,
+        text: buf.into_bytes(),
         truncated_after_lines: None,
     })
 }
 
+/// Live `ExecCommandOutputDelta` events for one stream of a command. Sending
+/// never waits on the consumer, so a command spewing output keeps running:
+/// chunks read within `OUTPUT_DELTA_INTERVAL` of the last delta, or while the
+/// event channel is full, are merged into one pending delta that is sent once
+/// the interval has passed and there is room.
+struct OutputDeltas {
+    stream: StdoutStream,
+    is_stderr: bool,
+    pending: Vec<u8>,
+    emitted: usize,
+    sent_bytes: usize,
+    /// When the pending delta may be sent.
+    next_send_at: tokio::time::Instant,
+}
+
+impl OutputDeltas {
+    fn new(stream: StdoutStream, is_stderr: bool) -> Self {
+        Self {
+            stream,
+            is_stderr,
+            pending: Vec::new(),
+            emitted: 0,
+            sent_bytes: 0,
+            next_send_at: tokio::time::Instant::now(),
+        }
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// When to stop waiting for more output and send what is pending.
+    fn flush_deadline(&self) -> Option<tokio::time::Instant> {
+        self.has_pending().then_some(self.next_send_at)
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        if self.emitted >= MAX_EXEC_OUTPUT_DELTAS_PER_CALL {
+            return;
+        }
+        let unsent = self.sent_bytes + self.pending.len();
+        let room = MAX_PENDING_OUTPUT_DELTA_BYTES
+            .saturating_sub(self.pending.len())
+            .min(MAX_EXEC_OUTPUT_DELTA_BYTES_PER_CALL.saturating_sub(unsent));
+        self.pending
+            .extend_from_slice(&chunk[..chunk.len().min(room)]);
+        if tokio::time::Instant::now() >= self.next_send_at {
+            self.try_flush();
+        }
+    }
+
+    fn try_flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let len = self.pending.len();
+        let now = tokio::time::Instant::now();
+        let event = self.take_event();
+        match self.stream.tx_event.try_send(event) {
+            Ok(()) => {
+                self.emitted += 1;
+                self.sent_bytes += len;
+                self.next_send_at = now + OUTPUT_DELTA_INTERVAL;
+            }
+            Err(async_channel::TrySendError::Full(event)) => {
+                self.restore(event);
+                self.next_send_at = now + PENDING_OUTPUT_RETRY_INTERVAL;
+            }
+            // Nobody is listening; the output is still aggregated.
+            Err(async_channel::TrySendError::Closed(_)) => {}
+        }
+    }
+
+    async fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let len = self.pending.len();
+        let event = self.take_event();
+        if self.stream.tx_event.send(event).await.is_ok() {
+            self.emitted += 1;
+            self.sent_bytes += len;
+        }
+    }
+
+    fn take_event(&mut self) -> Event {
+        Event {
+            id: self.stream.sub_id.clone(),
+            msg: EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+                call_id: self.stream.call_id.clone(),
+                stream: if self.is_stderr {
+                    ExecOutputStream::Stderr
+                } else {
+                    ExecOutputStream::Stdout
+                },
+                chunk: std::mem::take(&mut self.pending),
+            }),
+        }
+    }
+
+    fn restore(&mut self, event: Event) {
+        if let EventMsg::ExecCommandOutputDelta(delta) = event.msg {
+            self.pending = delta.chunk;
+        }
+    }
+}
+
 #[cfg(unix)]
 fn synthetic_exit_status(code: i32) -> ExitStatus {
     use std::os::unix::process::ExitStatusExt;
@@ -538,6 +701,88 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn output_is_coalesced_while_the_event_channel_is_full() {
+        let (tx_event, rx_event) = async_channel::bounded(1);
+        let stream = StdoutStream {
+            sub_id: "sub".to_string(),
+            call_id: "call".to_string(),
+            tx_event,
+        };
+        let mut deltas = OutputDeltas::new(stream, false);
+
+        deltas.push(b"one\n");
+        deltas.push(b"two\n");
+        deltas.push(b"three\n");
+        assert!(deltas.has_pending());
+
+        let chunk = |event: Event| match event.msg {
+            EventMsg::ExecCommandOutputDelta(delta) => delta.chunk,
+            other => panic!("unexpected event {other:?}"),
+        };
+        assert_eq!(chunk(rx_event.recv().await.expect("first delta")), b"one\n");
+        deltas.flush().await;
+        assert_eq!(
+            chunk(rx_event.recv().await.expect("coalesced delta")),
+            b"two\nthree\n"
+        );
+        assert!(!deltas.has_pending());
+    }
+
+    #[tokio::test]
+    async fn output_deltas_are_time_sliced_and_capped_per_call() {
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let stream = StdoutStream {
+            sub_id: "sub".to_string(),
+            call_id: "call".to_string(),
+            tx_event,
+        };
+        let mut deltas = OutputDeltas::new(stream, true);
+
+        deltas.push(b"first\n");
+        deltas.push(b"second\n");
+        deltas.push(b"third\n");
+        assert_eq!(rx_event.len(), 1);
+        assert!(deltas.flush_deadline().is_some());
+        deltas.flush().await;
+        assert_eq!(rx_event.len(), 2);
+
+        let chunk = vec![b'y'; READ_CHUNK_SIZE];
+        for _ in 0..2 * MAX_EXEC_OUTPUT_DELTA_BYTES_PER_CALL / READ_CHUNK_SIZE {
+            deltas.push(&chunk);
+            deltas.try_flush();
+            deltas.flush().await;
+        }
+        let mut sent = 0;
+        while let Ok(event) = rx_event.try_recv() {
+            let EventMsg::ExecCommandOutputDelta(delta) = event.msg else {
+                panic!("unexpected event {:?}", event.msg);
+            };
+            sent += delta.chunk.len();
+        }
+        assert_eq!(sent, MAX_EXEC_OUTPUT_DELTA_BYTES_PER_CALL);
+    }
+
+    #[test]
+    fn retained_output_keeps_head_and_tail() {
+        let mut small = RetainedOutput::default();
+        small.push(b"hello ");
+        small.push(b"world");
+        assert_eq!(small.into_bytes(), b"hello world");
+
+        let mut flood = RetainedOutput::default();
+        let half = RetainedOutput::HALF;
+        flood.push(&vec![b'a'; half]);
+        flood.push(&vec![b'b'; 3 * half]);
+        flood.push(b"end");
+        let bytes = flood.into_bytes();
+        let marker = format!("\n[... {} bytes omitted ...]\n", 2 * half + 3);
+        assert_eq!(bytes.len(), 2 * half + marker.len());
+        assert!(bytes[..half].iter().all(|byte| *byte == b'a'));
+        assert_eq!(&bytes[half..half + marker.len()], marker.as_bytes());
+        assert!(bytes.ends_with(b"bbend"));
+    }
+
     #[cfg(unix)]
     #[test]
     fn sandbox_detection_flags_sigsys_exit_code() {
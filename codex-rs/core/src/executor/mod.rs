@@ -2,12 +2,14 @@ mod backends;
 mod cache;
 mod runner;
 mod sandbox;
+mod target;
 
 pub(crate) use backends::ExecutionMode;
 pub(crate) use runner::ExecutionRequest;
 pub(crate) use runner::Executor;
 pub(crate) use runner::ExecutorConfig;
 pub(crate) use runner::normalize_exec_result;
+pub(crate) use target::CommandTarget;
 
 pub(crate) mod linkers {
     use crate::exec::ExecParams;
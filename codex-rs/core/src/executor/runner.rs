@@ -6,7 +6,10 @@ use std::time::Duration;
 use super::backends::ExecutionMode;
 use super::backends::backend_for_mode;
 use super::cache::ApprovalCache;
+use super::target::CommandTarget;
 use crate::codex::Session;
+use crate::command_safety::deny_list::CommandDenyList;
+use crate::config_types::RemoteWorkspace;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::error::get_error_message_ui;
@@ -17,8 +20,11 @@ use crate::exec::StdoutStream;
 use crate::exec::StreamOutput;
 use crate::exec::process_exec_tool_call;
 use crate::executor::errors::ExecError;
+use crate::executor::sandbox::SandboxDecision;
 use crate::executor::sandbox::select_sandbox;
 use crate::function_tool::FunctionCallError;
+use crate::package_installs::PackageInstallLog;
+use crate::package_installs::detect_package_install;
 use crate::protocol::AskForApproval;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxPolicy;
@@ -31,6 +37,16 @@ pub(crate) struct ExecutorConfig {
     pub(crate) sandbox_policy: SandboxPolicy,
     pub(crate) sandbox_cwd: PathBuf,
     codex_linux_sandbox_exe: Option<PathBuf>,
+    /// Host that commands and patches run on instead of this machine.
+    remote: Option<RemoteWorkspace>,
+    /// Where shell commands run instead of this machine; patches still apply
+    /// here.
+    command_target: Option<CommandTarget>,
+    /// Commands refused outright, before approval and sandboxing.
+    deny_list: CommandDenyList,
+    /// Packages installed this session; `None` when neither recorded nor
+    /// gated by approval.
+    package_installs: Option<Arc<PackageInstallLog>>,
 }
 
 impl ExecutorConfig {
@@ -38,13 +54,27 @@ impl ExecutorConfig {
         sandbox_policy: SandboxPolicy,
         sandbox_cwd: PathBuf,
         codex_linux_sandbox_exe: Option<PathBuf>,
+        remote: Option<RemoteWorkspace>,
+        command_target: Option<CommandTarget>,
+        deny_list: CommandDenyList,
+        package_installs: Option<Arc<PackageInstallLog>>,
     ) -> Self {
         Self {
             sandbox_policy,
             sandbox_cwd,
             codex_linux_sandbox_exe,
+            remote,
+            command_target,
+            deny_list,
+            package_installs,
         }
     }
+
+    /// Whether commands run somewhere the local sandbox cannot confine them,
+    /// so approvals that lean on it do not hold.
+    pub(crate) fn bypasses_local_sandbox(&self) -> bool {
+        self.remote.is_some() || self.command_target.is_some()
+    }
 }
 
 /// Coordinates sandbox selection, backend-specific preparation, and command
@@ -71,17 +101,124 @@ impl Executor {
         }
     }
 
+    /// The remote workspace commands run in, if any.
+    pub(crate) fn remote(&self) -> Option<RemoteWorkspace> {
+        self.config.read().ok().and_then(|cfg| cfg.remote.clone())
+    }
+
+    /// The refusal for `command` when it falls under the deny-list.
+    pub(crate) fn deny_list_refusal(&self, command: &[String]) -> Option<String> {
+        let cfg = self.config.read().ok()?;
+        cfg.deny_list
+            .matching(command)
+            .map(deny_list_refusal_message)
+    }
+
+    /// The command the user approved for `call_id` in place of the proposed
+    /// one, if they edited it. The deny-list applies to edits like any other
+    /// command.
+    async fn approved_edit(
+        &self,
+        session: &Session,
+        call_id: &str,
+    ) -> Result<Option<Vec<String>>, ExecError> {
+        let Some(command) = session.command_edit(call_id).await else {
+            return Ok(None);
+        };
+        match self.deny_list_refusal(&command) {
+            Some(refusal) => Err(ExecError::rejection(refusal)),
+            None => Ok(Some(command)),
+        }
+    }
+
+    fn package_installs(&self) -> Option<Arc<PackageInstallLog>> {
+        self.config
+            .read()
+            .ok()
+            .and_then(|cfg| cfg.package_installs.clone())
+    }
+
+    fn has_command_target(&self) -> bool {
+        self.config
+            .read()
+            .is_ok_and(|cfg| cfg.command_target.is_some())
+    }
+
     /// Runs a prepared execution request end-to-end: prepares parameters, decides on
     /// sandbox placement (prompting the user when necessary), launches the command,
     /// and lets the backend post-process the final output.
     pub(crate) async fn run(
+        &self,
+        request: ExecutionRequest,
+        session: &Session,
+        approval_policy: AskForApproval,
+        context: &ExecCommandContext,
+    ) -> Result<ExecToolCallOutput, ExecError> {
+        if !matches!(request.mode, ExecutionMode::Shell) {
+            return self
+                .run_request(request, session, approval_policy, context, &[])
+                .await;
+        }
+
+        // Denied commands are refused before anything else, so no approval
+        // policy or sandbox setting lets them through.
+        if let Some(refusal) = self.deny_list_refusal(&request.approval_command) {
+            return Err(ExecError::rejection(refusal));
+        }
+
+        let package_installs = self.package_installs();
+        let new_dependencies = package_installs
+            .as_ref()
+            .zip(detect_package_install(&request.approval_command))
+            .map(|(log, install)| log.new_dependencies(&install))
+            .unwrap_or_default();
+
+        let command = request.approval_command.clone();
+        let cwd = request.params.cwd.clone();
+        let result = self
+            .run_request(
+                request,
+                session,
+                approval_policy,
+                context,
+                &new_dependencies,
+            )
+            .await;
+        if let Some(log) = package_installs
+            && let Ok(output) = &result
+            && output.exit_code == 0
+        {
+            // Record what ran, which the user may have edited into (or out
+            // of) a package install.
+            let command = session
+                .command_edit(&context.call_id)
+                .await
+                .unwrap_or(command);
+            if let Some(install) = detect_package_install(&command) {
+                log.record(&install, &command, &cwd, &output.aggregated_output.text)
+                    .await;
+            }
+        }
+        result
+    }
+
+    /// [`Executor::run`] once the deny-list and package installs have been
+    /// looked at. Adding `new_dependencies` takes the user's approval in
+    /// place of the usual sandbox selection.
+    async fn run_request(
         &self,
         mut request: ExecutionRequest,
         session: &Session,
         approval_policy: AskForApproval,
         context: &ExecCommandContext,
+        new_dependencies: &[String],
     ) -> Result<ExecToolCallOutput, ExecError> {
-        if matches!(request.mode, ExecutionMode::Shell) {
+        // The local user shell says nothing about the remote host's or the
+        // container's.
+        if matches!(request.mode, ExecutionMode::Shell)
+            && self.remote().is_none()
+            && !self.has_command_target()
+        {
             request.params =
                 maybe_translate_shell_command(request.params, session, request.use_shell_profile);
         }
@@ -105,22 +242,58 @@ impl Executor {
             .clone();
 
         // Step 3: Decide sandbox placement, prompting for approval when needed.
-        let sandbox_decision = select_sandbox(
-            &request,
-            approval_policy,
-            self.approval_cache.snapshot(),
-            &config,
-            session,
-            &context.sub_id,
-            &context.call_id,
-            &context.otel_event_manager,
-        )
-        .await?;
+        let sandbox_decision = if new_dependencies.is_empty() {
+            select_sandbox(
+                &request,
+                approval_policy,
+                self.approval_cache.snapshot(),
+                &config,
+                session,
+                &context.sub_id,
+                &context.call_id,
+                &context.otel_event_manager,
+            )
+            .await?
+        } else {
+            self.approve_new_dependencies(
+                &request,
+                new_dependencies,
+                session,
+                approval_policy,
+                context,
+            )
+            .await?
+        };
+        // The user may have edited the command before approving it.
+        if matches!(request.mode, ExecutionMode::Shell)
+            && let Some(command) = self.approved_edit(session, &context.call_id).await?
+        {
+            request.params.command = command.clone();
+            request.approval_command = command;
+        }
         if sandbox_decision.record_session_approval {
             self.approval_cache.insert(request.approval_command.clone());
         }
 
-        // Step 4: Launch the command within the chosen sandbox.
+        // Step 4: Launch the command within the chosen sandbox. The local
+        // sandbox cannot confine commands on a remote host, so
+        // `select_sandbox` asked for approval of anything it would have
+        // confined.
+        if let Some(remote) = &config.remote {
+            return self
+                .run_remote(remote, &request, &config, stdout_stream)
+                .await;
+        }
+        // Likewise for commands run in a container or pod.
+        if let (Some(target), ExecutionMode::Shell) = (&config.command_target, &request.mode) {
+            let params = target
+                .wrap_exec_params(request.params.clone(), &config.sandbox_cwd)
+                .await
+                .map_err(CodexErr::from)?;
+            return Ok(self
+                .spawn(params, SandboxType::None, &config, stdout_stream)
+                .await?);
+        }
         let first_attempt = self
             .spawn(
                 request.params.clone(),
@@ -156,6 +329,48 @@ impl Executor {
         }
     }
 
+    /// Asks the user about a command that adds dependencies, whatever the
+    /// approval policy. Approval stands in for the regular prompt, so the
+    /// command then runs like any other the user approved. Nobody answers
+    /// approvals under `never`, so the command is refused outright there.
+    async fn approve_new_dependencies(
+        &self,
+        request: &ExecutionRequest,
+        new_dependencies: &[String],
+        session: &Session,
+        approval_policy: AskForApproval,
+        context: &ExecCommandContext,
+    ) -> Result<SandboxDecision, ExecError> {
+        if let Some(refusal) = new_dependencies_refusal(approval_policy, new_dependencies) {
+            return Err(ExecError::rejection(refusal));
+        }
+        let decision = session
+            .request_command_approval(
+                context.sub_id.to_string(),
+                context.call_id.to_string(),
+                request.approval_command.clone(),
+                request.params.cwd.clone(),
+                Some(format!(
+                    "adds new dependencies: {}",
+                    new_dependencies.join(", ")
+                )),
+            )
+            .await;
+        context.otel_event_manager.tool_decision(
+            &context.tool_name,
+            &context.call_id,
+            decision,
+            ToolDecisionSource::User,
+        );
+        match decision {
+            ReviewDecision::Approved => Ok(SandboxDecision::user_override(false)),
+            ReviewDecision::ApprovedForSession => Ok(SandboxDecision::user_override(true)),
+            ReviewDecision::Denied | ReviewDecision::Abort => {
+                Err(ExecError::rejection("dependency install rejected by user"))
+            }
+        }
+    }
+
     /// Fallback path invoked when a sandboxed run is denied so the user can
     /// approve rerunning without isolation.
     async fn retry_without_sandbox(
@@ -191,20 +406,21 @@ impl Executor {
         );
         match decision {
             ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
+                let mut params = request.params.clone();
+                let mut approval_command = request.approval_command.clone();
+                if let Some(command) = self.approved_edit(session, &context.call_id).await? {
+                    params.command = command.clone();
+                    approval_command = command;
+                }
                 if matches!(decision, ReviewDecision::ApprovedForSession) {
-                    self.approval_cache.insert(request.approval_command.clone());
+                    self.approval_cache.insert(approval_command);
                 }
                 session
                     .notify_background_event(&context.sub_id, "retrying command without sandbox")
                     .await;
 
                 let retry_output = self
-                    .spawn(
-                        request.params.clone(),
-                        SandboxType::None,
-                        config,
-                        stdout_stream,
-                    )
+                    .spawn(params, SandboxType::None, config, stdout_stream)
                     .await?;
 
                 Ok(retry_output)
@@ -215,6 +431,37 @@ impl Executor {
         }
     }
 
+    /// Runs the request on the remote workspace: shell commands through
+    /// `ssh`, patches by rewriting the affected files there.
+    async fn run_remote(
+        &self,
+        remote: &RemoteWorkspace,
+        request: &ExecutionRequest,
+        config: &ExecutorConfig,
+        stdout_stream: Option<StdoutStream>,
+    ) -> Result<ExecToolCallOutput, ExecError> {
+        match &request.mode {
+            ExecutionMode::Shell => {
+                let params = remote.wrap_exec_params(request.params.clone());
+                Ok(self
+                    .spawn(params, SandboxType::None, config, stdout_stream)
+                    .await?)
+            }
+            ExecutionMode::ApplyPatch(exec) => {
+                let output = remote
+                    .apply_patch(&exec.action, request.params.timeout_duration())
+                    .await;
+                if output.timed_out {
+                    return Err(CodexErr::Sandbox(SandboxErr::Timeout {
+                        output: Box::new(output),
+                    })
+                    .into());
+                }
+                Ok(output)
+            }
+        }
+    }
+
     async fn spawn(
         &self,
         params: ExecParams,
@@ -253,6 +500,30 @@ fn maybe_translate_shell_command(
     params
 }
 
+/// What the model is told when a command falls under the deny-list `rule`.
+fn deny_list_refusal_message(rule: &str) -> String {
+    format!(
+        "command refused: it matches `{rule}` in the user's command deny-list, which applies \
+         regardless of approval policy. Do not retry it or work around it with an equivalent \
+         command; if it is needed, ask the user to run it themselves."
+    )
+}
+
+/// The refusal for a command adding `new_dependencies` when approval cannot
+/// be asked for.
+fn new_dependencies_refusal(
+    approval_policy: AskForApproval,
+    new_dependencies: &[String],
+) -> Option<String> {
+    (approval_policy == AskForApproval::Never).then(|| {
+        format!(
+            "command rejected: it adds new dependencies ({}), which need the user's approval \
+             (package_installs.require_approval), and approval_policy is never.",
+            new_dependencies.join(", ")
+        )
+    })
+}
+
 fn sandbox_failure_message(error: SandboxErr) -> String {
     let codex_error = CodexErr::Sandbox(error);
     let friendly = get_error_message_ui(&codex_error);
@@ -324,10 +595,12 @@ pub(crate) fn normalize_exec_result(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codex::make_session_and_context;
     use crate::error::CodexErr;
     use crate::error::EnvVarError;
     use crate::error::SandboxErr;
     use crate::exec::StreamOutput;
+    use crate::state::ActiveTurn;
     use pretty_assertions::assert_eq;
 
     fn make_output(text: &str) -> ExecToolCallOutput {
@@ -363,6 +636,84 @@ mod tests {
         );
     }
 
+    #[test]
+    fn deny_list_refuses_before_approval() {
+        let executor = Executor::new(ExecutorConfig::new(
+            SandboxPolicy::DangerFullAccess,
+            PathBuf::from("/work"),
+            None,
+            None,
+            None,
+            CommandDenyList::new(["git push --force"]),
+            None,
+        ));
+        let command = ["git", "push", "-u", "origin", "--force"].map(String::from);
+
+        assert_eq!(
+            executor.deny_list_refusal(&command),
+            Some(deny_list_refusal_message("git push --force"))
+        );
+        assert_eq!(
+            executor.deny_list_refusal(&["git".to_string(), "push".to_string()]),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn deny_list_applies_to_edited_commands() {
+        let (session, _turn) = make_session_and_context();
+        let active_turn = ActiveTurn::default();
+        {
+            let mut state = active_turn.turn_state.lock().await;
+            state.insert_pending_command_edit(
+                "event".to_string(),
+                ["git", "push", "--force"].map(String::from).to_vec(),
+            );
+            state.claim_command_edit("event", "call");
+        }
+        *session.active_turn.lock().await = Some(active_turn);
+        let executor = Executor::new(ExecutorConfig::new(
+            SandboxPolicy::DangerFullAccess,
+            PathBuf::from("/work"),
+            None,
+            None,
+            None,
+            CommandDenyList::new(["git push --force"]),
+            None,
+        ));
+
+        match executor.approved_edit(&session, "call").await {
+            Err(ExecError::Function(FunctionCallError::RespondToModel(message))) => {
+                assert_eq!(message, deny_list_refusal_message("git push --force"));
+            }
+            other => panic!("expected the edit to be refused, got {other:?}"),
+        }
+        assert_eq!(
+            executor
+                .approved_edit(&session, "other-call")
+                .await
+                .expect("no edit"),
+            None
+        );
+    }
+
+    #[test]
+    fn new_dependencies_are_refused_when_approval_is_never_asked() {
+        let new_dependencies = ["left-pad".to_string(), "is-odd".to_string()];
+        assert_eq!(
+            new_dependencies_refusal(AskForApproval::Never, &new_dependencies),
+            Some(
+                "command rejected: it adds new dependencies (left-pad, is-odd), which need the \
+                 user's approval (package_installs.require_approval), and approval_policy is never."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            new_dependencies_refusal(AskForApproval::OnRequest, &new_dependencies),
+            None
+        );
+    }
+
     #[test]
     fn sandbox_failure_message_uses_denied_stderr() {
         let output = ExecToolCallOutput {
@@ -31,7 +31,7 @@ impl SandboxDecision {
         }
     }
 
-    fn user_override(record_session_approval: bool) -> Self {
+    pub(crate) fn user_override(record_session_approval: bool) -> Self {
         Self {
             initial_sandbox: SandboxType::None,
             escalate_on_failure: false,
@@ -100,13 +100,28 @@ async fn select_shell_sandbox(
         request.approval_command.clone()
     };
 
-    let safety = assess_command_safety(
+    let safety = match assess_command_safety(
         &command_for_safety,
         approval_policy,
         &config.sandbox_policy,
         &approved_snapshot,
         request.params.with_escalated_permissions.unwrap_or(false),
-    );
+    ) {
+        // Auto-approval that counts on the local sandbox does not cover a
+        // command that runs elsewhere.
+        SafetyCheck::AutoApprove { sandbox_type, .. }
+            if sandbox_type != SandboxType::None && config.bypasses_local_sandbox() =>
+        {
+            if approval_policy == AskForApproval::Never {
+                SafetyCheck::Reject {
+                    reason: "the local sandbox cannot confine commands that run elsewhere; rejected by user approval settings".to_string(),
+                }
+            } else {
+                SafetyCheck::AskUser
+            }
+        }
+        safety => safety,
+    };
 
     match safety {
         SafetyCheck::AutoApprove {
@@ -191,8 +206,14 @@ fn select_apply_patch_sandbox(
 mod tests {
     use super::*;
     use crate::codex::make_session_and_context;
+    use crate::codex::make_session_and_context_with_rx;
+    use crate::command_safety::deny_list::CommandDenyList;
+    use crate::config_types::KubernetesExec;
+    use crate::config_types::RemoteWorkspace;
     use crate::exec::ExecParams;
+    use crate::executor::CommandTarget;
     use crate::function_tool::FunctionCallError;
+    use crate::protocol::EventMsg;
     use crate::protocol::SandboxPolicy;
     use codex_apply_patch::ApplyPatchAction;
     use pretty_assertions::assert_eq;
@@ -207,7 +228,15 @@ mod tests {
             action,
             user_explicitly_approved_this_action: true,
         };
-        let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::ReadOnly,
+            std::env::temp_dir(),
+            None,
+            None,
+            None,
+            CommandDenyList::default(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 command: vec!["apply_patch".into()],
@@ -250,7 +279,15 @@ mod tests {
             action,
             user_explicitly_approved_this_action: false,
         };
-        let cfg = ExecutorConfig::new(SandboxPolicy::DangerFullAccess, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::DangerFullAccess,
+            std::env::temp_dir(),
+            None,
+            None,
+            None,
+            CommandDenyList::default(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 command: vec!["apply_patch".into()],
@@ -294,7 +331,15 @@ mod tests {
             action,
             user_explicitly_approved_this_action: false,
         };
-        let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::ReadOnly,
+            std::env::temp_dir(),
+            None,
+            None,
+            None,
+            CommandDenyList::default(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 command: vec!["apply_patch".into()],
@@ -333,7 +378,15 @@ mod tests {
     #[tokio::test]
     async fn select_shell_autoapprove_in_danger_mode() {
         let (session, ctx) = make_session_and_context();
-        let cfg = ExecutorConfig::new(SandboxPolicy::DangerFullAccess, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::DangerFullAccess,
+            std::env::temp_dir(),
+            None,
+            None,
+            None,
+            CommandDenyList::default(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 command: vec!["some-unknown".into()],
@@ -369,7 +422,15 @@ mod tests {
     #[tokio::test]
     async fn select_shell_escalates_on_failure_with_platform_sandbox() {
         let (session, ctx) = make_session_and_context();
-        let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::ReadOnly,
+            std::env::temp_dir(),
+            None,
+            None,
+            None,
+            CommandDenyList::default(),
+            None,
+        );
         let request = ExecutionRequest {
             params: ExecParams {
                 // Unknown command => untrusted but not flagged dangerous
@@ -402,4 +463,84 @@ mod tests {
         assert_ne!(decision.initial_sandbox, SandboxType::None);
         assert_eq!(decision.escalate_on_failure, true);
     }
+
+    /// The command of the approval request `select_sandbox` sends for an
+    /// unknown command under on-request, if it sends one.
+    async fn approval_requested_for_untrusted_command(
+        remote: Option<RemoteWorkspace>,
+        command_target: Option<CommandTarget>,
+    ) -> Option<Vec<String>> {
+        let (session, ctx, rx_event) = make_session_and_context_with_rx();
+        let cfg = ExecutorConfig::new(
+            SandboxPolicy::new_workspace_write_policy(),
+            std::env::temp_dir(),
+            None,
+            remote,
+            command_target,
+            CommandDenyList::default(),
+            None,
+        );
+        let request = ExecutionRequest {
+            params: ExecParams {
+                command: vec!["some-unknown".into()],
+                cwd: std::env::temp_dir(),
+                timeout_ms: None,
+                env: std::collections::HashMap::new(),
+                with_escalated_permissions: None,
+                justification: None,
+            },
+            approval_command: vec!["some-unknown".into()],
+            mode: ExecutionMode::Shell,
+            stdout_stream: None,
+            use_shell_profile: false,
+        };
+        let otel_event_manager = ctx.client.get_otel_event_manager();
+        let selection = select_sandbox(
+            &request,
+            AskForApproval::OnRequest,
+            Default::default(),
+            &cfg,
+            &session,
+            "sub",
+            "call",
+            &otel_event_manager,
+        );
+
+        // Nobody answers the request, so stop waiting once it is sent.
+        tokio::select! {
+            _ = selection => None,
+            event = rx_event.recv() => match event.ok()?.msg {
+                EventMsg::ExecApprovalRequest(request) => Some(request.command),
+                other => panic!("expected an approval request, got {other:?}"),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn select_shell_asks_before_running_untrusted_commands_remotely() {
+        let remote = RemoteWorkspace {
+            host: "deploy@staging".to_string(),
+            cwd: "/srv/app".into(),
+            ssh_args: Vec::new(),
+        };
+        assert_eq!(
+            approval_requested_for_untrusted_command(Some(remote), None).await,
+            Some(vec!["some-unknown".to_string()])
+        );
+    }
+
+    #[tokio::test]
+    async fn select_shell_asks_before_running_untrusted_commands_in_a_pod() {
+        let target = CommandTarget::Kubernetes(KubernetesExec {
+            pod: "api-0".to_string(),
+            container: None,
+            namespace: None,
+            context: None,
+            cwd: None,
+        });
+        assert_eq!(
+            approval_requested_for_untrusted_command(None, Some(target)).await,
+            Some(vec!["some-unknown".to_string()])
+        );
+    }
 }
@@ -0,0 +1,43 @@
+//! Places other than this machine where shell commands run, while
+//! `apply_patch` keeps editing the local files they can see.
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::config_types::KubernetesExec;
+use crate::devcontainer::Devcontainer;
+use crate::exec::ExecParams;
+
+#[derive(Clone, Debug)]
+pub(crate) enum CommandTarget {
+    Devcontainer(Devcontainer),
+    Kubernetes(KubernetesExec),
+}
+
+impl CommandTarget {
+    /// The target `config` selects. An explicitly configured pod wins over a
+    /// devcontainer the project opted into.
+    pub(crate) fn from_config(config: &Config) -> Option<Self> {
+        if let Some(kubernetes) = &config.kubernetes_exec {
+            return Some(Self::Kubernetes(kubernetes.clone()));
+        }
+        config
+            .devcontainer
+            .clone()
+            .filter(|_| config.use_devcontainer == Some(true))
+            .map(Self::Devcontainer)
+    }
+
+    /// Rewrite `params` so the command runs on this target. `session_cwd` is
+    /// the session's local working directory.
+    pub(crate) async fn wrap_exec_params(
+        &self,
+        params: ExecParams,
+        session_cwd: &Path,
+    ) -> std::io::Result<ExecParams> {
+        match self {
+            Self::Devcontainer(devcontainer) => devcontainer.wrap_exec_params(params).await,
+            Self::Kubernetes(kubernetes) => Ok(kubernetes.wrap_exec_params(params, session_cwd)),
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! Notices when files the agent has read are edited outside of it, e.g. by
+//! the user in their editor between turns, so the model does not patch
+//! against content it no longer has.
+//!
+//! Files read through `read_file` or plain shell readers (`cat`, `sed -n`,
+//! `head`, ...) are remembered with their content. Before each turn the
+//! remembered files are compared against disk and a diff of every change is
+//! added to the conversation. When a turn ends the snapshots are refreshed,
+//! so the agent's own edits are never reported back to it.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use similar::TextDiff;
+
+use crate::bash::parse_bash_lc_plain_commands;
+
+/// Files larger than this are not tracked.
+const MAX_TRACKED_FILE_BYTES: u64 = 256 * 1024;
+/// Diffs longer than this are replaced by a note to re-read the file.
+const MAX_DIFF_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Default)]
+pub(crate) struct ReadFileTracker {
+    /// Content of each file as the agent last saw it.
+    files: HashMap<PathBuf, String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum ExternalChange {
+    Modified { path: PathBuf, diff: String },
+    Deleted { path: PathBuf },
+}
+
+impl ReadFileTracker {
+    pub(crate) fn record_read(&mut self, path: &Path) {
+        if let Some(content) = read_tracked(path) {
+            self.files.insert(path.to_path_buf(), content);
+        }
+    }
+
+    /// Remembers the files a successful shell `command` run in `cwd` read.
+    pub(crate) fn record_command(&mut self, command: &[String], cwd: &Path) {
+        for path in read_paths(command) {
+            self.record_read(&cwd.join(path));
+        }
+    }
+
+    /// Changes made on disk since the files were last seen. Changed files
+    /// are remembered with their new content, deleted ones are forgotten.
+    pub(crate) fn take_external_changes(&mut self) -> Vec<ExternalChange> {
+        let mut changes = Vec::new();
+        let mut paths: Vec<PathBuf> = self.files.keys().cloned().collect();
+        paths.sort();
+        for path in paths {
+            let current = read_tracked(&path);
+            let Some(seen) = self.files.get_mut(&path) else {
+                continue;
+            };
+            match current {
+                Some(current) if current == *seen => {}
+                Some(current) => {
+                    let diff = TextDiff::from_lines(seen.as_str(), current.as_str())
+                        .unified_diff()
+                        .context_radius(3)
+                        .to_string();
+                    changes.push(ExternalChange::Modified {
+                        path: path.clone(),
+                        diff,
+                    });
+                    *seen = current;
+                }
+                None => {
+                    self.files.remove(&path);
+                    changes.push(ExternalChange::Deleted { path });
+                }
+            }
+        }
+        changes
+    }
+
+    /// Takes the current content of every tracked file as seen, so edits
+    /// the agent made itself are not reported as external.
+    pub(crate) fn refresh(&mut self) {
+        self.files.retain(|path, seen| match read_tracked(path) {
+            Some(current) => {
+                *seen = current;
+                true
+            }
+            None => false,
+        });
+    }
+}
+
+fn read_tracked(path: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_TRACKED_FILE_BYTES {
+        return None;
+    }
+    std::fs::read_to_string(path).ok()
+}
+
+/// Files read by plain reader commands in `command`, as written.
+fn read_paths(command: &[String]) -> Vec<String> {
+    let commands = parse_bash_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+    commands
+        .iter()
+        .flat_map(|words| reader_operands(words))
+        .collect()
+}
+
+fn reader_operands(words: &[String]) -> Vec<String> {
+    let Some((program, args)) = words.split_first() else {
+        return Vec::new();
+    };
+    match program.as_str() {
+        "cat" | "head" | "tail" | "nl" | "less" | "more" | "bat" => {
+            let mut operands = Vec::new();
+            let mut args = args.iter();
+            while let Some(arg) = args.next() {
+                if matches!(arg.as_str(), "-n" | "-c" | "-s" | "-w" | "-v" | "-b") {
+                    args.next();
+                } else if !arg.starts_with('-') {
+                    operands.push(arg.clone());
+                }
+            }
+            operands
+        }
+        "sed" => match args {
+            [flag, _script, files @ ..] if flag == "-n" => files
+                .iter()
+                .filter(|file| !file.starts_with('-'))
+                .cloned()
+                .collect(),
+            _ => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+/// The context item telling the model about `changes`.
+pub(crate) fn external_changes_message(changes: &[ExternalChange], cwd: &Path) -> ResponseItem {
+    let mut text = String::from(
+        "<external_file_changes>\nThese files you read earlier were changed outside of this \
+         session since you last saw them. Work from their current content.",
+    );
+    for change in changes {
+        match change {
+            ExternalChange::Modified { path, diff } => {
+                let path = display_path(path, cwd);
+                if diff.len() > MAX_DIFF_BYTES {
+                    text.push_str(&format!(
+                        "\n\nFile {path} changed externally; the diff is too large to show, read \
+                         the file again before editing it."
+                    ));
+                } else {
+                    text.push_str(&format!(
+                        "\n\nFile {path} changed externally:\n```diff\n{diff}```"
+                    ));
+                }
+            }
+            ExternalChange::Deleted { path } => {
+                let path = display_path(path, cwd);
+                text.push_str(&format!("\n\nFile {path} was deleted externally."));
+            }
+        }
+    }
+    text.push_str("\n</external_file_changes>");
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText { text }],
+    }
+}
+
+fn display_path(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    #[test]
+    fn finds_files_read_by_shell_commands() {
+        assert_eq!(read_paths(&vec_str(&["cat", "a.rs"])), vec_str(&["a.rs"]));
+        assert_eq!(
+            read_paths(&vec_str(&[
+                "bash",
+                "-lc",
+                "sed -n '1,80p' src/lib.rs && head -n 20 Cargo.toml"
+            ])),
+            vec_str(&["src/lib.rs", "Cargo.toml"])
+        );
+        assert!(read_paths(&vec_str(&["rm", "a.rs"])).is_empty());
+        assert!(read_paths(&vec_str(&["sed", "-i", "s/a/b/", "a.rs"])).is_empty());
+    }
+
+    #[test]
+    fn reports_external_edits_but_not_refreshed_ones() {
+        let dir = TempDir::new().expect("tempdir");
+        let cwd = dir.path();
+        std::fs::write(cwd.join("a.txt"), "one\ntwo\n").expect("write a");
+        std::fs::write(cwd.join("b.txt"), "b\n").expect("write b");
+
+        let mut tracker = ReadFileTracker::default();
+        tracker.record_command(&vec_str(&["cat", "a.txt"]), cwd);
+        tracker.record_read(&cwd.join("b.txt"));
+        assert_eq!(tracker.take_external_changes(), Vec::new());
+
+        // The agent's own edit, absorbed when its turn ends.
+        std::fs::write(cwd.join("b.txt"), "b2\n").expect("write b");
+        tracker.refresh();
+        assert_eq!(tracker.take_external_changes(), Vec::new());
+
+        std::fs::write(cwd.join("a.txt"), "one\nTWO\n").expect("write a");
+        std::fs::remove_file(cwd.join("b.txt")).expect("remove b");
+        let changes = tracker.take_external_changes();
+        assert_eq!(
+            changes,
+            vec![
+                ExternalChange::Modified {
+                    path: cwd.join("a.txt"),
+                    diff: "@@ -1,2 +1,2 @@\n one\n-two\n+TWO\n".to_string(),
+                },
+                ExternalChange::Deleted {
+                    path: cwd.join("b.txt"),
+                },
+            ]
+        );
+        let ResponseItem::Message { content, .. } = external_changes_message(&changes, cwd) else {
+            panic!("expected a message");
+        };
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one text item");
+        };
+        assert!(text.contains("File a.txt changed externally:\n```diff\n@@ -1,2 +1,2 @@"));
+        assert!(text.contains("File b.txt was deleted externally."));
+
+        // Changes are reported once.
+        assert_eq!(tracker.take_external_changes(), Vec::new());
+    }
+}
@@ -0,0 +1,184 @@
+//! Advisory locks that keep concurrent Codex processes from clobbering each
+//! other's state.
+//!
+//! Read-modify-write updates of shared files (`config.toml`, which also holds
+//! the MCP server registry) hold an exclusive lock on a sibling `.lock` file
+//! from the read to the write, so every update starts from the latest
+//! contents. The lock lives in its own file because those updates replace the
+//! target by renaming a temporary file over it.
+//!
+//! Session rollouts are owned by one process at a time through
+//! [`lock_session`], so two Codex instances cannot append to the same
+//! session.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::Weak;
+use std::time::Duration;
+
+use fs2::FileExt;
+
+/// How long to wait for another process to finish its update: 50 × 100 ms.
+const MAX_RETRIES: usize = 50;
+const RETRY_SLEEP: Duration = Duration::from_millis(100);
+
+/// Exclusive lock for updating a shared file; released when dropped.
+#[derive(Debug)]
+pub struct UpdateLock {
+    file: File,
+}
+
+impl UpdateLock {
+    /// Lock `path` for an update, waiting for other processes that hold the
+    /// lock. Blocks the current thread; see [`UpdateLock::acquire_async`].
+    pub fn acquire(path: &Path) -> std::io::Result<Self> {
+        let lock_path = lock_path(path);
+        let file = open_lock_file(&lock_path)?;
+        for _ in 0..MAX_RETRIES {
+            match FileExt::try_lock_exclusive(&file) {
+                Ok(()) => return Ok(Self { file }),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(RETRY_SLEEP);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::WouldBlock,
+            format!(
+                "timed out waiting for another Codex process to release {}",
+                lock_path.display()
+            ),
+        ))
+    }
+
+    /// [`UpdateLock::acquire`] without blocking the async runtime.
+    pub async fn acquire_async(path: &Path) -> std::io::Result<Self> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || Self::acquire(&path))
+            .await
+            .map_err(std::io::Error::other)?
+    }
+}
+
+impl Drop for UpdateLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Ownership of a session rollout by this process; see [`lock_session`].
+///
+/// The lock file is left in place when released: removing it would let a
+/// process that opened it just before the removal lock an unlinked file
+/// while another locks a freshly created one, both believing they own the
+/// session.
+#[derive(Debug)]
+pub(crate) struct SessionLock {
+    file: File,
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Session locks held by this process, so the same session can be reopened
+/// within the process (e.g. resumed while still loaded) but not by another.
+static SESSION_LOCKS: LazyLock<Mutex<HashMap<PathBuf, Weak<SessionLock>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Claim the rollout at `path` for this process without waiting. Fails with
+/// [`std::io::ErrorKind::WouldBlock`] when another process holds it. The
+/// claim is released when the last clone of the returned handle is dropped.
+pub(crate) fn lock_session(path: &Path) -> std::io::Result<Arc<SessionLock>> {
+    let mut locks = SESSION_LOCKS
+        .lock()
+        .map_err(|_| std::io::Error::other("session lock registry poisoned"))?;
+    locks.retain(|_, lock| lock.strong_count() > 0);
+    if let Some(lock) = locks.get(path).and_then(Weak::upgrade) {
+        return Ok(lock);
+    }
+
+    let lock_path = lock_path(path);
+    let file = open_lock_file(&lock_path)?;
+    FileExt::try_lock_exclusive(&file).map_err(|err| {
+        if err.kind() == std::io::ErrorKind::WouldBlock {
+            std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                format!(
+                    "session {} is open in another Codex process",
+                    path.display()
+                ),
+            )
+        } else {
+            err
+        }
+    })?;
+    let lock = Arc::new(SessionLock { file });
+    locks.insert(path.to_path_buf(), Arc::downgrade(&lock));
+    Ok(lock)
+}
+
+/// `config.toml` → `config.toml.lock`.
+fn lock_path(path: &Path) -> PathBuf {
+    let mut lock_path = OsString::from(path.as_os_str());
+    lock_path.push(".lock");
+    PathBuf::from(lock_path)
+}
+
+fn open_lock_file(lock_path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = lock_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(lock_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn lock_is_exclusive_until_dropped() {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("config.toml");
+        let lock = UpdateLock::acquire(&path).expect("first lock");
+
+        let other = File::open(dir.path().join("config.toml.lock")).expect("open lock file");
+        assert!(FileExt::try_lock_exclusive(&other).is_err());
+
+        drop(lock);
+        assert!(FileExt::try_lock_exclusive(&other).is_ok());
+    }
+
+    #[test]
+    fn session_lock_is_shared_within_process() {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("rollout.jsonl");
+        let first = lock_session(&path).expect("first claim");
+        let second = lock_session(&path).expect("second claim");
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let other = File::open(dir.path().join("rollout.jsonl.lock")).expect("open lock file");
+        assert!(FileExt::try_lock_exclusive(&other).is_err());
+
+        drop(first);
+        assert!(FileExt::try_lock_exclusive(&other).is_err());
+        drop(second);
+        assert!(FileExt::try_lock_exclusive(&other).is_ok());
+    }
+}
@@ -108,6 +108,16 @@ pub async fn collect_git_info(cwd: &Path) -> Option<GitInfo> {
     Some(git_info)
 }
 
+/// Whether the working tree at `cwd` has uncommitted changes (including
+/// untracked files). Returns `None` outside a repository or if git fails.
+pub async fn git_worktree_dirty(cwd: &Path) -> Option<bool> {
+    let output = run_git_command_with_timeout(&["status", "--porcelain"], cwd).await?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(!output.stdout.iter().all(u8::is_ascii_whitespace))
+}
+
 /// A minimal commit summary entry used for pickers (subject + timestamp + sha).
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CommitLogEntry {
@@ -585,6 +595,80 @@ pub async fn current_branch_name(cwd: &Path) -> Option<String> {
         .filter(|name| !name.is_empty())
 }
 
+/// Upstream of the current branch, or the local default branch when it has
+/// none.
+pub async fn upstream_or_default_branch(cwd: &Path) -> Option<String> {
+    if let Some(out) = run_git_command_with_timeout(
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ],
+        cwd,
+    )
+    .await
+        && out.status.success()
+        && let Ok(upstream) = String::from_utf8(out.stdout)
+        && !upstream.trim().is_empty()
+    {
+        return Some(upstream.trim().to_string());
+    }
+    get_default_branch_local(cwd).await
+}
+
+/// Best common ancestor of `HEAD` and `rev`.
+pub async fn merge_base_with_head(cwd: &Path, rev: &str) -> Option<String> {
+    let out = run_git_command_with_timeout(&["merge-base", "HEAD", rev], cwd).await?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8(out.stdout)
+        .ok()
+        .map(|sha| sha.trim().to_string())
+        .filter(|sha| !sha.is_empty())
+}
+
+/// Whether a `git rebase` is stopped midway in the repository containing
+/// `cwd`.
+pub async fn rebase_in_progress(cwd: &Path) -> bool {
+    for dir in ["rebase-merge", "rebase-apply"] {
+        if let Some(out) =
+            run_git_command_with_timeout(&["rev-parse", "--git-path", dir], cwd).await
+            && out.status.success()
+            && let Ok(path) = String::from_utf8(out.stdout)
+            && cwd.join(path.trim()).exists()
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Absolute paths of the files with unresolved merge conflicts in the
+/// repository containing `cwd`. Returns `None` outside a repository or if
+/// git fails.
+pub async fn unmerged_paths(cwd: &Path) -> Option<Vec<PathBuf>> {
+    let root = run_git_command_with_timeout(&["rev-parse", "--show-toplevel"], cwd).await?;
+    if !root.status.success() {
+        return None;
+    }
+    let root = PathBuf::from(String::from_utf8(root.stdout).ok()?.trim());
+    let out = run_git_command_with_timeout(&["diff", "--name-only", "--diff-filter=U", "-z"], cwd)
+        .await?;
+    if !out.status.success() {
+        return None;
+    }
+    let mut paths: Vec<PathBuf> = out
+        .stdout
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| root.join(String::from_utf8_lossy(path).as_ref()))
+        .collect();
+    paths.dedup();
+    Some(paths)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
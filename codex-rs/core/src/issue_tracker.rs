@@ -0,0 +1,931 @@
+//! Issue-tracker context for references in user input.
+//!
+//! `@gh#1234`, `@gl#56` and `@jira:ABC-42` (or `@gh:owner/repo#1234` and
+//! `@gl:group/project#56` for another repository) are resolved against the
+//! trackers configured under `[issue_trackers]`. The issue title, body and
+//! most recent comments are added to the conversation as an
+//! `<issue_context>` message so they no longer have to be pasted by hand.
+//! Images and attachment links are removed, and fetched issues are cached for
+//! a few minutes so repeated mentions do not hit the API again.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::InputItem;
+use futures::future::join_all;
+use regex_lite::Regex;
+use reqwest::RequestBuilder;
+use reqwest::Response;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::config_types::GitHubTracker;
+use crate::config_types::GitLabTracker;
+use crate::config_types::IssueTrackers;
+use crate::config_types::JiraTracker;
+use crate::default_client::create_client;
+use crate::git_info::collect_git_info;
+use crate::truncate::truncate_middle;
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITLAB_URL: &str = "https://gitlab.com";
+const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+const GITLAB_TOKEN_ENV: &str = "GITLAB_TOKEN";
+const JIRA_TOKEN_ENV: &str = "JIRA_API_TOKEN";
+
+/// Number of most recent comments included with an issue.
+const RECENT_COMMENTS: usize = 5;
+/// Page size used when listing GitHub issue comments.
+const GITHUB_COMMENTS_PER_PAGE: u64 = 100;
+/// Size limit for the issue body and for each comment.
+const MAX_TEXT_BYTES: usize = 8 * 1024;
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const ATTACHMENT_PLACEHOLDER: &str = "[attachment removed]";
+
+pub const ISSUE_CONTEXT_OPEN_TAG: &str = "<issue_context>";
+pub const ISSUE_CONTEXT_CLOSE_TAG: &str = "</issue_context>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IssueTrackerKind {
+    GitHub,
+    GitLab,
+    Jira,
+}
+
+impl IssueTrackerKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            IssueTrackerKind::GitHub => "GitHub",
+            IssueTrackerKind::GitLab => "GitLab",
+            IssueTrackerKind::Jira => "Jira",
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            IssueTrackerKind::GitHub => "gh",
+            IssueTrackerKind::GitLab => "gl",
+            IssueTrackerKind::Jira => "jira",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IssueReference {
+    pub tracker: IssueTrackerKind,
+    /// Repository or project path; `None` uses the configured default.
+    pub project: Option<String>,
+    /// Issue number, or the issue key for Jira.
+    pub id: String,
+}
+
+impl fmt::Display for IssueReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = self.tracker.prefix();
+        match (self.tracker, &self.project) {
+            (IssueTrackerKind::Jira, _) => write!(f, "@{prefix}:{}", self.id),
+            (_, Some(project)) => write!(f, "@{prefix}:{project}#{}", self.id),
+            (_, None) => write!(f, "@{prefix}#{}", self.id),
+        }
+    }
+}
+
+fn reference_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?:^|[\s(\[])@(?:(gh|gl)(?::([\w.-]+(?:/[\w.-]+)+))?#(\d+)|jira:([A-Z][A-Z0-9_]*-\d+))",
+        )
+        .unwrap()
+    })
+}
+
+/// Issue references mentioned in `text`, in order and without duplicates.
+pub fn find_issue_references(text: &str) -> Vec<IssueReference> {
+    let mut references: Vec<IssueReference> = Vec::new();
+    for caps in reference_regex().captures_iter(text) {
+        let reference = if let Some(key) = caps.get(4) {
+            IssueReference {
+                tracker: IssueTrackerKind::Jira,
+                project: None,
+                id: key.as_str().to_string(),
+            }
+        } else {
+            let (Some(prefix), Some(id)) = (caps.get(1), caps.get(3)) else {
+                continue;
+            };
+            IssueReference {
+                tracker: if prefix.as_str() == "gh" {
+                    IssueTrackerKind::GitHub
+                } else {
+                    IssueTrackerKind::GitLab
+                },
+                project: caps.get(2).map(|m| m.as_str().to_string()),
+                id: id.as_str().to_string(),
+            }
+        };
+        if !references.contains(&reference) {
+            references.push(reference);
+        }
+    }
+    references
+}
+
+/// Parse the argument of `/issue`: `ABC-42` (Jira), `#1234` or `1234`
+/// (GitHub), `owner/repo#1234`, `gh#1234`, `gl#56`, or a full `@` reference.
+pub fn parse_issue_argument(arg: &str) -> Option<IssueReference> {
+    let arg = arg.trim();
+    let arg = arg.strip_prefix('@').unwrap_or(arg);
+    if let Some(reference) = find_issue_references(&format!("@{arg}")).into_iter().next() {
+        return Some(reference);
+    }
+    let is_jira_key = arg.split_once('-').is_some_and(|(project, number)| {
+        project.starts_with(|c: char| c.is_ascii_uppercase())
+            && project
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    });
+    if is_jira_key {
+        return Some(IssueReference {
+            tracker: IssueTrackerKind::Jira,
+            project: None,
+            id: arg.to_string(),
+        });
+    }
+    let (project, number) = match arg.rsplit_once('#') {
+        Some((project, number)) => (Some(project), number),
+        None => (None, arg),
+    };
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let project = project.filter(|p| !p.is_empty());
+    if project.is_some_and(|p| !p.contains('/')) {
+        return None;
+    }
+    Some(IssueReference {
+        tracker: IssueTrackerKind::GitHub,
+        project: project.map(str::to_string),
+        id: number.to_string(),
+    })
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IssueFetchError {
+    #[error("no {0} repository is configured and none could be derived from the git remote")]
+    NoProject(&'static str),
+
+    #[error("`issue_trackers.jira.base_url` is not configured")]
+    JiraNotConfigured,
+
+    #[error("{tracker} returned HTTP {status}")]
+    Status { tracker: &'static str, status: u16 },
+
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueComment {
+    pub author: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueDetails {
+    pub tracker: IssueTrackerKind,
+    /// Display id such as `owner/repo#1234` or `ABC-42`.
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    pub state: Option<String>,
+    pub author: Option<String>,
+    pub body: String,
+    /// Most recent comments, oldest first.
+    pub comments: Vec<IssueComment>,
+}
+
+impl IssueDetails {
+    /// Text of the `<issue_context>` block given to the model.
+    pub fn to_context(&self) -> String {
+        let mut out = format!(
+            "{ISSUE_CONTEXT_OPEN_TAG}\n{} issue {}: {}\nURL: {}\n",
+            self.tracker.label(),
+            self.id,
+            self.title,
+            self.url
+        );
+        if let Some(state) = &self.state {
+            out.push_str(&format!("State: {state}\n"));
+        }
+        if let Some(author) = &self.author {
+            out.push_str(&format!("Author: {author}\n"));
+        }
+        let body = self.body.trim();
+        if !body.is_empty() {
+            out.push_str(&format!("\n{}\n", prepare_text(body)));
+        }
+        if !self.comments.is_empty() {
+            out.push_str("\nRecent comments:\n");
+            for comment in &self.comments {
+                let author = comment.author.as_deref().unwrap_or("unknown");
+                out.push_str(&format!(
+                    "\n{author} wrote:\n{}\n",
+                    prepare_text(comment.body.trim())
+                ));
+            }
+        }
+        out.push_str(ISSUE_CONTEXT_CLOSE_TAG);
+        out
+    }
+}
+
+fn prepare_text(text: &str) -> String {
+    truncate_middle(&redact_attachments(text), MAX_TEXT_BYTES).0
+}
+
+fn attachment_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    PATTERNS.get_or_init(|| {
+        [
+            // Markdown images.
+            r"!\[[^\]]*\]\([^)]*\)",
+            // HTML images.
+            r"(?i)<img\b[^>]*>",
+            // Markdown links to uploaded files (GitHub, GitLab, Jira).
+            r"\[[^\]]*\]\([^)\s]*/(?:user-attachments|uploads|secure/attachment)/[^)]*\)",
+            // Bare links to uploaded files.
+            r"https?://\S*/(?:user-attachments|uploads|secure/attachment)/\S+",
+            // Jira wiki images (`!screenshot.png|thumbnail!`) and file links (`[^log.txt]`).
+            r"(?i)![^!\s|]+\.(?:png|jpe?g|gif|bmp|svg|webp)(?:\|[^!\n]*)?!",
+            r"\[\^[^\]\n]+\]",
+        ]
+        .into_iter()
+        .map(|pattern| Regex::new(pattern).unwrap())
+        .collect()
+    })
+}
+
+/// Replace images and attachment links with a placeholder. Attachments are
+/// often large or private and cannot be read by the model anyway.
+fn redact_attachments(text: &str) -> String {
+    attachment_patterns()
+        .iter()
+        .fold(text.to_string(), |text, pattern| {
+            pattern
+                .replace_all(&text, ATTACHMENT_PLACEHOLDER)
+                .into_owned()
+        })
+}
+
+type CacheKey = (IssueTrackerKind, String, String);
+
+static ISSUE_CACHE: LazyLock<Mutex<HashMap<CacheKey, (Instant, IssueDetails)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn cached_issue(key: &CacheKey) -> Option<IssueDetails> {
+    let cache = ISSUE_CACHE.lock().ok()?;
+    let (fetched_at, details) = cache.get(key)?;
+    (fetched_at.elapsed() < CACHE_TTL).then(|| details.clone())
+}
+
+fn cache_issue(key: CacheKey, details: &IssueDetails) {
+    if let Ok(mut cache) = ISSUE_CACHE.lock() {
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < CACHE_TTL);
+        cache.insert(key, (Instant::now(), details.clone()));
+    }
+}
+
+/// Fetch `reference`, using the cache when it is fresh.
+pub async fn fetch_issue(
+    trackers: &IssueTrackers,
+    reference: &IssueReference,
+    cwd: &Path,
+) -> Result<IssueDetails, IssueFetchError> {
+    let project = match reference.tracker {
+        IssueTrackerKind::GitHub => {
+            resolve_project(reference, trackers.github.repo.as_deref(), cwd).await
+        }
+        IssueTrackerKind::GitLab => {
+            resolve_project(reference, trackers.gitlab.project.as_deref(), cwd).await
+        }
+        IssueTrackerKind::Jira => Some(String::new()),
+    }
+    .ok_or(IssueFetchError::NoProject(reference.tracker.label()))?;
+
+    let key = (reference.tracker, project.clone(), reference.id.clone());
+    if let Some(details) = cached_issue(&key) {
+        return Ok(details);
+    }
+    let details = match reference.tracker {
+        IssueTrackerKind::GitHub => fetch_github(&trackers.github, &project, &reference.id).await,
+        IssueTrackerKind::GitLab => fetch_gitlab(&trackers.gitlab, &project, &reference.id).await,
+        IssueTrackerKind::Jira => fetch_jira(&trackers.jira, &reference.id).await,
+    }?;
+    cache_issue(key, &details);
+    Ok(details)
+}
+
+/// Issue context for the references in a user's input, to be recorded right
+/// after it.
+pub(crate) struct IssueContextMessage {
+    /// `None` when no reference could be fetched.
+    pub(crate) item: Option<ResponseItem>,
+    /// One status line per reference, for the client.
+    pub(crate) notes: Vec<String>,
+}
+
+pub(crate) async fn issue_context_for_input(
+    trackers: &IssueTrackers,
+    input: &[InputItem],
+    cwd: &Path,
+) -> Option<IssueContextMessage> {
+    let text = input
+        .iter()
+        .filter_map(|item| match item {
+            InputItem::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let references = find_issue_references(&text);
+    if references.is_empty() {
+        return None;
+    }
+
+    let results = join_all(
+        references
+            .iter()
+            .map(|reference| fetch_issue(trackers, reference, cwd)),
+    )
+    .await;
+
+    let mut content: Vec<ContentItem> = Vec::new();
+    let mut notes: Vec<String> = Vec::new();
+    for (reference, result) in references.iter().zip(results) {
+        match result {
+            Ok(details) => {
+                notes.push(format!(
+                    "Added {} issue {} to the context: {}",
+                    details.tracker.label(),
+                    details.id,
+                    details.title
+                ));
+                content.push(ContentItem::InputText {
+                    text: details.to_context(),
+                });
+            }
+            Err(err) => notes.push(format!("Could not fetch {reference}: {err}")),
+        }
+    }
+    let item = (!content.is_empty()).then(|| ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content,
+    });
+    Some(IssueContextMessage { item, notes })
+}
+
+async fn resolve_project(
+    reference: &IssueReference,
+    configured: Option<&str>,
+    cwd: &Path,
+) -> Option<String> {
+    match &reference.project {
+        Some(project) => Some(project.clone()),
+        None => default_project(configured, cwd).await,
+    }
+}
+
+/// The configured project, or the one the `origin` remote points at.
+pub(crate) async fn default_project(configured: Option<&str>, cwd: &Path) -> Option<String> {
+    if let Some(project) = configured {
+        return Some(project.to_string());
+    }
+    let remote = collect_git_info(cwd).await?.repository_url?;
+    remote_project_path(&remote)
+}
+
+/// `owner/repo` from a remote such as `git@github.com:owner/repo.git` or
+/// `https://gitlab.com/group/sub/project`.
+fn remote_project_path(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    let path = path.trim_matches('/');
+    path.contains('/').then(|| path.to_string())
+}
+
+fn token_from_env(configured: Option<&str>, default: &str) -> Option<String> {
+    std::env::var(configured.unwrap_or(default))
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+}
+
+async fn send(
+    request: RequestBuilder,
+    tracker: IssueTrackerKind,
+) -> Result<Response, IssueFetchError> {
+    let response = request.timeout(REQUEST_TIMEOUT).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(IssueFetchError::Status {
+            tracker: tracker.label(),
+            status: status.as_u16(),
+        });
+    }
+    Ok(response)
+}
+
+async fn get_json<T: DeserializeOwned>(
+    request: RequestBuilder,
+    tracker: IssueTrackerKind,
+) -> Result<T, IssueFetchError> {
+    Ok(send(request, tracker).await?.json::<T>().await?)
+}
+
+/// Client for the GitHub REST API configured by `issue_trackers.github`.
+pub(crate) struct GitHubApi {
+    client: reqwest::Client,
+    api_url: String,
+    token: Option<String>,
+}
+
+impl GitHubApi {
+    pub(crate) fn new(config: &GitHubTracker) -> Self {
+        Self {
+            client: create_client(),
+            api_url: config
+                .api_url
+                .as_deref()
+                .unwrap_or(GITHUB_API_URL)
+                .trim_end_matches('/')
+                .to_string(),
+            token: token_from_env(config.token_env.as_deref(), GITHUB_TOKEN_ENV),
+        }
+    }
+
+    pub(crate) fn has_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    fn get(&self, path: &str) -> RequestBuilder {
+        let request = self
+            .client
+            .get(format!("{}{path}", self.api_url))
+            .header("Accept", "application/vnd.github+json");
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    pub(crate) async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<T, IssueFetchError> {
+        get_json(self.get(path), IssueTrackerKind::GitHub).await
+    }
+
+    pub(crate) async fn get_text(&self, path: &str) -> Result<String, IssueFetchError> {
+        Ok(send(self.get(path), IssueTrackerKind::GitHub)
+            .await?
+            .text()
+            .await?)
+    }
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubIssue {
+    title: String,
+    body: Option<String>,
+    state: String,
+    html_url: String,
+    user: Option<GitHubUser>,
+    #[serde(default)]
+    comments: u64,
+}
+
+#[derive(Deserialize)]
+struct GitHubComment {
+    body: Option<String>,
+    user: Option<GitHubUser>,
+}
+
+async fn fetch_github(
+    config: &GitHubTracker,
+    repo: &str,
+    number: &str,
+) -> Result<IssueDetails, IssueFetchError> {
+    let api = GitHubApi::new(config);
+    let issue: GitHubIssue = api
+        .get_json(&format!("/repos/{repo}/issues/{number}"))
+        .await?;
+    // Comments are listed oldest first, so the most recent are on the last
+    // page, or on the last two when the last page is short.
+    let mut comments = Vec::new();
+    if issue.comments > 0 {
+        let page = issue.comments.div_ceil(GITHUB_COMMENTS_PER_PAGE);
+        comments = fetch_github_comments(&api, repo, number, page).await?;
+        if comments.len() < RECENT_COMMENTS && page > 1 {
+            let mut earlier = fetch_github_comments(&api, repo, number, page - 1).await?;
+            earlier.append(&mut comments);
+            comments = earlier;
+        }
+    }
+    let skip = comments.len().saturating_sub(RECENT_COMMENTS);
+
+    Ok(IssueDetails {
+        tracker: IssueTrackerKind::GitHub,
+        id: format!("{repo}#{number}"),
+        title: issue.title,
+        url: issue.html_url,
+        state: Some(issue.state),
+        author: issue.user.map(|user| user.login),
+        body: issue.body.unwrap_or_default(),
+        comments: comments
+            .into_iter()
+            .skip(skip)
+            .map(|comment| IssueComment {
+                author: comment.user.map(|user| user.login),
+                body: comment.body.unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
+
+async fn fetch_github_comments(
+    api: &GitHubApi,
+    repo: &str,
+    number: &str,
+    page: u64,
+) -> Result<Vec<GitHubComment>, IssueFetchError> {
+    api.get_json(&format!(
+        "/repos/{repo}/issues/{number}/comments?per_page={GITHUB_COMMENTS_PER_PAGE}&page={page}"
+    ))
+    .await
+}
+
+#[derive(Deserialize)]
+struct GitLabUser {
+    username: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabIssue {
+    title: String,
+    description: Option<String>,
+    state: String,
+    web_url: String,
+    author: Option<GitLabUser>,
+}
+
+#[derive(Deserialize)]
+struct GitLabNote {
+    body: String,
+    #[serde(default)]
+    system: bool,
+    author: Option<GitLabUser>,
+}
+
+async fn fetch_gitlab(
+    config: &GitLabTracker,
+    project: &str,
+    iid: &str,
+) -> Result<IssueDetails, IssueFetchError> {
+    let client = create_client();
+    let base_url = config
+        .base_url
+        .as_deref()
+        .unwrap_or(GITLAB_URL)
+        .trim_end_matches('/');
+    let token = token_from_env(config.token_env.as_deref(), GITLAB_TOKEN_ENV);
+    let get = |url: String| {
+        let request = client.get(url);
+        match &token {
+            Some(token) => request.header("PRIVATE-TOKEN", token),
+            None => request,
+        }
+    };
+    let issue_url = format!(
+        "{base_url}/api/v4/projects/{}/issues/{iid}",
+        project.replace('/', "%2F")
+    );
+
+    let issue: GitLabIssue = get_json(get(issue_url.clone()), IssueTrackerKind::GitLab).await?;
+    // Newest first; system notes (label changes and the like) are skipped.
+    let notes: Vec<GitLabNote> = get_json(
+        get(format!(
+            "{issue_url}/notes?sort=desc&order_by=created_at&per_page=50"
+        )),
+        IssueTrackerKind::GitLab,
+    )
+    .await?;
+    let mut comments: Vec<IssueComment> = notes
+        .into_iter()
+        .filter(|note| !note.system)
+        .take(RECENT_COMMENTS)
+        .map(|note| IssueComment {
+            author: note.author.map(|user| user.username),
+            body: note.body,
+        })
+        .collect();
+    comments.reverse();
+
+    Ok(IssueDetails {
+        tracker: IssueTrackerKind::GitLab,
+        id: format!("{project}#{iid}"),
+        title: issue.title,
+        url: issue.web_url,
+        state: Some(issue.state),
+        author: issue.author.map(|user| user.username),
+        body: issue.description.unwrap_or_default(),
+        comments,
+    })
+}
+
+#[derive(Deserialize)]
+struct JiraUser {
+    #[serde(rename = "displayName")]
+    display_name: String,
+}
+
+#[derive(Deserialize)]
+struct JiraStatus {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct JiraComment {
+    author: Option<JiraUser>,
+    #[serde(default)]
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct JiraComments {
+    #[serde(default)]
+    comments: Vec<JiraComment>,
+}
+
+#[derive(Deserialize)]
+struct JiraFields {
+    summary: String,
+    description: Option<String>,
+    status: Option<JiraStatus>,
+    reporter: Option<JiraUser>,
+    comment: Option<JiraComments>,
+}
+
+#[derive(Deserialize)]
+struct JiraIssue {
+    key: String,
+    fields: JiraFields,
+}
+
+async fn fetch_jira(config: &JiraTracker, key: &str) -> Result<IssueDetails, IssueFetchError> {
+    let base_url = config
+        .base_url
+        .as_deref()
+        .ok_or(IssueFetchError::JiraNotConfigured)?
+        .trim_end_matches('/');
+    let token = token_from_env(config.token_env.as_deref(), JIRA_TOKEN_ENV);
+    let request = create_client().get(format!(
+        "{base_url}/rest/api/2/issue/{key}?fields=summary,description,status,reporter,comment"
+    ));
+    let request = match (&config.email, &token) {
+        (Some(email), Some(token)) => request.basic_auth(email, Some(token)),
+        (None, Some(token)) => request.bearer_auth(token),
+        (_, None) => request,
+    };
+
+    let issue: JiraIssue = get_json(request, IssueTrackerKind::Jira).await?;
+    let comments = issue
+        .fields
+        .comment
+        .map(|comments| comments.comments)
+        .unwrap_or_default();
+    let skip = comments.len().saturating_sub(RECENT_COMMENTS);
+
+    Ok(IssueDetails {
+        tracker: IssueTrackerKind::Jira,
+        url: format!("{base_url}/browse/{}", issue.key),
+        id: issue.key,
+        title: issue.fields.summary,
+        state: issue.fields.status.map(|status| status.name),
+        author: issue.fields.reporter.map(|user| user.display_name),
+        body: issue.fields.description.unwrap_or_default(),
+        comments: comments
+            .into_iter()
+            .skip(skip)
+            .map(|comment| IssueComment {
+                author: comment.author.map(|user| user.display_name),
+                body: comment.body,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core_test_support::skip_if_no_network;
+    use pretty_assertions::assert_eq;
+
+    fn github(project: Option<&str>, id: &str) -> IssueReference {
+        IssueReference {
+            tracker: IssueTrackerKind::GitHub,
+            project: project.map(str::to_string),
+            id: id.to_string(),
+        }
+    }
+
+    #[test]
+    fn finds_references_in_text() {
+        let text = "Fix @gh#1234 (see @gl:group/app#7), also @jira:ABC-42 and @gh#1234 again. \
+                    Not me@gh#1 or @docs/readme.md";
+        assert_eq!(
+            find_issue_references(text),
+            vec![
+                github(None, "1234"),
+                IssueReference {
+                    tracker: IssueTrackerKind::GitLab,
+                    project: Some("group/app".to_string()),
+                    id: "7".to_string(),
+                },
+                IssueReference {
+                    tracker: IssueTrackerKind::Jira,
+                    project: None,
+                    id: "ABC-42".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            find_issue_references("@gh:openai/codex#99")[0].to_string(),
+            "@gh:openai/codex#99"
+        );
+    }
+
+    #[test]
+    fn parses_issue_command_arguments() {
+        assert_eq!(
+            parse_issue_argument("ABC-42").map(|r| r.to_string()),
+            Some("@jira:ABC-42".to_string())
+        );
+        assert_eq!(parse_issue_argument("#12"), Some(github(None, "12")));
+        assert_eq!(parse_issue_argument("12"), Some(github(None, "12")));
+        assert_eq!(
+            parse_issue_argument("openai/codex#12"),
+            Some(github(Some("openai/codex"), "12"))
+        );
+        assert_eq!(
+            parse_issue_argument("gl#5").map(|r| r.tracker),
+            Some(IssueTrackerKind::GitLab)
+        );
+        assert_eq!(parse_issue_argument("codex#12"), None);
+        assert_eq!(parse_issue_argument("abc-42"), None);
+    }
+
+    #[test]
+    fn redacts_images_and_attachments() {
+        let text = "Steps:\n![screenshot](https://github.com/user-attachments/assets/abc)\n\
+                    <img src=\"x.png\" width=\"300\">\n\
+                    Log: [build.log](/uploads/0123/build.log)\n\
+                    Jira: !crash.png|thumbnail! and [^trace.txt]\n\
+                    Keep [docs](https://example.com/docs)!";
+        assert_eq!(
+            redact_attachments(text),
+            "Steps:\n[attachment removed]\n[attachment removed]\n\
+             Log: [attachment removed]\n\
+             Jira: [attachment removed] and [attachment removed]\n\
+             Keep [docs](https://example.com/docs)!"
+        );
+    }
+
+    #[test]
+    fn derives_project_from_remote() {
+        assert_eq!(
+            remote_project_path("git@github.com:openai/codex.git"),
+            Some("openai/codex".to_string())
+        );
+        assert_eq!(
+            remote_project_path("https://gitlab.com/group/sub/app/"),
+            Some("group/sub/app".to_string())
+        );
+        assert_eq!(
+            remote_project_path("ssh://git@host:2222/team/app.git"),
+            Some("team/app".to_string())
+        );
+        assert_eq!(remote_project_path("https://example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn short_last_comment_page_includes_the_previous_page() {
+        skip_if_no_network!();
+
+        use wiremock::Mock;
+        use wiremock::MockServer;
+        use wiremock::ResponseTemplate;
+        use wiremock::matchers::method;
+        use wiremock::matchers::path;
+        use wiremock::matchers::query_param;
+
+        let comments = |range: std::ops::Range<usize>| {
+            serde_json::Value::Array(
+                range
+                    .map(|n| serde_json::json!({ "body": format!("comment {n}"), "user": null }))
+                    .collect(),
+            )
+        };
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/openai/codex/issues/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "title": "Crash on start",
+                "body": null,
+                "state": "open",
+                "html_url": "https://github.com/openai/codex/issues/1",
+                "user": null,
+                "comments": 101,
+            })))
+            .mount(&server)
+            .await;
+        for (page, range) in [("1", 1..101), ("2", 101..102)] {
+            Mock::given(method("GET"))
+                .and(path("/repos/openai/codex/issues/1/comments"))
+                .and(query_param("page", page))
+                .respond_with(ResponseTemplate::new(200).set_body_json(comments(range)))
+                .mount(&server)
+                .await;
+        }
+
+        let config = GitHubTracker {
+            api_url: Some(server.uri()),
+            ..Default::default()
+        };
+        let details = fetch_github(&config, "openai/codex", "1")
+            .await
+            .expect("fetch issue");
+        assert_eq!(
+            details
+                .comments
+                .iter()
+                .map(|comment| comment.body.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "comment 97",
+                "comment 98",
+                "comment 99",
+                "comment 100",
+                "comment 101"
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_issue_context() {
+        let details = IssueDetails {
+            tracker: IssueTrackerKind::GitHub,
+            id: "openai/codex#1".to_string(),
+            title: "Crash on start".to_string(),
+            url: "https://github.com/openai/codex/issues/1".to_string(),
+            state: Some("open".to_string()),
+            author: Some("alice".to_string()),
+            body: "It crashes.\n![trace](https://x/y.png)".to_string(),
+            comments: vec![IssueComment {
+                author: Some("bob".to_string()),
+                body: "Same here.".to_string(),
+            }],
+        };
+        assert_eq!(
+            details.to_context(),
+            "<issue_context>\nGitHub issue openai/codex#1: Crash on start\n\
+             URL: https://github.com/openai/codex/issues/1\nState: open\nAuthor: alice\n\n\
+             It crashes.\n[attachment removed]\n\nRecent comments:\n\nbob wrote:\nSame here.\n\
+             </issue_context>"
+        );
+    }
+}
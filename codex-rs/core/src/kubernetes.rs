@@ -0,0 +1,154 @@
+//! Running the agent's shell commands in a Kubernetes pod, for workloads
+//! that only run in-cluster. Commands go through `kubectl exec`; `apply_patch`
+//! keeps editing the local files.
+
+use std::env;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::config_types::KubernetesExec;
+use crate::exec::ExecParams;
+use crate::remote::quote;
+
+/// The `current-context` of the active kubeconfig (`$KUBECONFIG`, else
+/// `~/.kube/config`), as `kubectl` would pick it.
+pub fn current_context() -> Option<String> {
+    kube_config_paths().into_iter().find_map(|path| {
+        let contents = std::fs::read_to_string(path).ok()?;
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.strip_prefix("current-context:"))
+            .map(|value| value.trim().trim_matches(['"', '\'']))
+            .find(|context| !context.is_empty())
+            .map(str::to_string)
+    })
+}
+
+fn kube_config_paths() -> Vec<PathBuf> {
+    if let Some(paths) = env::var_os("KUBECONFIG") {
+        env::split_paths(&paths).collect()
+    } else if let Some(home) = env::var_os("HOME") {
+        vec![PathBuf::from(home).join(".kube/config")]
+    } else {
+        Vec::new()
+    }
+}
+
+impl KubernetesExec {
+    /// Rewrite `params` so the command runs in the configured pod. With
+    /// `cwd` set, a command's directory under `session_cwd` maps to the same
+    /// relative directory under it; otherwise commands start in the
+    /// container's working directory. The local `kubectl` process gets this
+    /// process's environment; the command sees the container's.
+    pub(crate) fn wrap_exec_params(&self, params: ExecParams, session_cwd: &Path) -> ExecParams {
+        let mut command = vec!["kubectl".to_string()];
+        if let Some(context) = &self.context {
+            command.extend(["--context".to_string(), context.clone()]);
+        }
+        if let Some(namespace) = &self.namespace {
+            command.extend(["--namespace".to_string(), namespace.clone()]);
+        }
+        command.extend(["exec".to_string(), self.pod.clone()]);
+        if let Some(container) = &self.container {
+            command.extend(["--container".to_string(), container.clone()]);
+        }
+        command.push("--".to_string());
+        match &self.cwd {
+            Some(root) => {
+                let container_cwd = match params.cwd.strip_prefix(session_cwd) {
+                    Ok(relative) => root.join(relative),
+                    Err(_) => root.clone(),
+                };
+                let script = format!(
+                    "cd {} && exec {}",
+                    quote(&container_cwd.to_string_lossy()),
+                    params
+                        .command
+                        .iter()
+                        .map(String::as_str)
+                        .map(quote)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+                command.extend(["sh".to_string(), "-c".to_string(), script]);
+            }
+            None => command.extend(params.command.iter().cloned()),
+        }
+        ExecParams {
+            command,
+            env: std::env::vars().collect(),
+            ..params
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn params(cwd: &str) -> ExecParams {
+        ExecParams {
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: PathBuf::from(cwd),
+            timeout_ms: None,
+            env: HashMap::new(),
+            with_escalated_permissions: None,
+            justification: None,
+        }
+    }
+
+    #[test]
+    fn wraps_command_in_kubectl_exec() {
+        let target = KubernetesExec {
+            pod: "api-0".to_string(),
+            container: Some("app".to_string()),
+            namespace: Some("staging".to_string()),
+            context: Some("arn:aws:eks:us-east-1:123:cluster/prod".to_string()),
+            cwd: Some(PathBuf::from("/srv/app")),
+        };
+
+        let wrapped =
+            target.wrap_exec_params(params("/home/me/app/crates"), Path::new("/home/me/app"));
+
+        assert_eq!(
+            wrapped.command,
+            vec![
+                "kubectl",
+                "--context",
+                "arn:aws:eks:us-east-1:123:cluster/prod",
+                "--namespace",
+                "staging",
+                "exec",
+                "api-0",
+                "--container",
+                "app",
+                "--",
+                "sh",
+                "-c",
+                "cd '/srv/app/crates' && exec 'cargo' 'test'",
+            ]
+        );
+    }
+
+    #[test]
+    fn runs_in_container_workdir_without_cwd() {
+        let target = KubernetesExec {
+            pod: "api-0".to_string(),
+            container: None,
+            namespace: None,
+            context: None,
+            cwd: None,
+        };
+
+        let wrapped = target.wrap_exec_params(params("/home/me/app"), Path::new("/home/me/app"));
+
+        assert_eq!(
+            wrapped.command,
+            vec!["kubectl", "exec", "api-0", "--", "cargo", "test"]
+        );
+    }
+}
@@ -8,7 +8,10 @@
 mod apply_patch;
 pub mod auth;
 pub mod bash;
+pub mod bump;
+pub mod changelog;
 mod chat_completions;
+pub mod ci_failure;
 mod client;
 mod client_common;
 pub mod codex;
@@ -23,24 +26,36 @@ pub mod config_profile;
 pub mod config_types;
 mod conversation_history;
 pub mod custom_prompts;
+pub mod devcontainer;
 mod environment_context;
 pub mod error;
 pub mod exec;
 mod exec_command;
 pub mod exec_env;
 pub mod executor;
+mod external_edits;
+pub mod file_lock;
 mod flags;
 pub mod git_info;
+pub mod issue_tracker;
+pub mod kubernetes;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
 mod mcp_tool_call;
+pub mod merge_conflicts;
 mod message_history;
+mod model_capabilities;
+mod model_metadata;
 mod model_provider_info;
 pub mod parse_command;
+pub mod rebase;
 mod truncate;
+mod turn_budget;
 mod unified_exec;
 mod user_instructions;
+mod user_note;
+mod write_quota;
 pub use model_provider_info::BUILT_IN_OSS_MODEL_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::WireApi;
@@ -59,15 +74,27 @@ pub mod default_client;
 pub mod model_family;
 mod openai_model_info;
 mod openai_tools;
+mod package_installs;
+pub mod pricing;
 pub mod project_doc;
+pub mod project_env;
+pub mod provenance;
+mod remote;
+pub mod replay;
 mod rollout;
 pub(crate) mod safety;
+pub mod sarif;
 pub mod seatbelt;
+pub mod security_audit;
 pub mod shell;
 pub mod spawn;
+pub mod state_home;
+pub mod system_info;
 pub mod terminal;
+pub mod todos;
 mod tools;
 pub mod turn_diff_tracker;
+pub mod unattended;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
@@ -82,6 +109,7 @@ mod state;
 mod tasks;
 mod user_notification;
 pub mod util;
+mod verify_commands;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
 pub use command_safety::is_safe_command;
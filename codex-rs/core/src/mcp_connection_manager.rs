@@ -32,6 +32,7 @@ use tracing::warn;
 
 use crate::config_types::McpServerConfig;
 use crate::config_types::McpServerTransportConfig;
+use crate::protocol::McpServerState;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -148,6 +149,13 @@ impl McpClientAdapter {
         }
     }
 
+    async fn is_closed(&self) -> bool {
+        match self {
+            McpClientAdapter::Legacy(client) => client.is_closed(),
+            McpClientAdapter::Rmcp(client) => client.is_closed().await,
+        }
+    }
+
     async fn call_tool(
         &self,
         name: String,
@@ -352,6 +360,35 @@ impl McpConnectionManager {
             .get(tool_name)
             .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
     }
+
+    /// State of every server in `mcp_servers` right after start-up: the ones
+    /// without a running client failed to start.
+    pub fn server_states(
+        &self,
+        mcp_servers: &HashMap<String, McpServerConfig>,
+    ) -> HashMap<String, McpServerState> {
+        mcp_servers
+            .iter()
+            .map(|(name, cfg)| {
+                let state = if !cfg.enabled {
+                    McpServerState::Disabled
+                } else if self.clients.contains_key(name) {
+                    McpServerState::Connected
+                } else {
+                    McpServerState::Failed
+                };
+                (name.clone(), state)
+            })
+            .collect()
+    }
+
+    /// Whether the connection to `server` has gone away since it started.
+    pub async fn is_closed(&self, server: &str) -> bool {
+        match self.clients.get(server) {
+            Some(managed) => managed.client.is_closed().await,
+            None => false,
+        }
+    }
 }
 
 fn resolve_bearer_token(
@@ -466,6 +503,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn servers_without_a_client_failed_to_start() {
+        let server = |enabled: bool| McpServerConfig {
+            transport: McpServerTransportConfig::Stdio {
+                command: "mcp-server".to_string(),
+                args: Vec::new(),
+                env: None,
+            },
+            enabled,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+        };
+        let mcp_servers = HashMap::from([
+            ("docs".to_string(), server(true)),
+            ("search".to_string(), server(false)),
+        ]);
+
+        let states = McpConnectionManager::default().server_states(&mcp_servers);
+
+        assert_eq!(states.get("docs"), Some(&McpServerState::Failed));
+        assert_eq!(states.get("search"), Some(&McpServerState::Disabled));
+    }
+
     #[test]
     fn test_qualify_tools_short_non_duplicated_names() {
         let tools = vec![
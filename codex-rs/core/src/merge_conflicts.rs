@@ -0,0 +1,210 @@
+//! Context for `codex resolve`: the files with unresolved merge conflicts in
+//! the current repository, and each conflict with the code around it.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::git_info::unmerged_paths;
+
+/// Lines of unchanged code shown before and after each conflict.
+const CONTEXT_LINES: usize = 8;
+/// Conflicts shown per file; the rest are only counted.
+const MAX_CONFLICTS_PER_FILE: usize = 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MergeConflictError {
+    #[error("not inside a git repository")]
+    NotARepository,
+
+    #[error("no files with unresolved merge conflicts")]
+    NoConflicts,
+}
+
+/// A conflict with the lines around it, numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// Line of the `<<<<<<<` marker.
+    pub start_line: usize,
+    /// Line of the `>>>>>>>` marker.
+    pub end_line: usize,
+    /// The conflict and its context, each line prefixed with its number.
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedFile {
+    /// Path relative to the working directory when it is inside it.
+    pub path: PathBuf,
+    /// Conflicts marked in the file. Empty for conflicts without markers,
+    /// such as a file deleted on one side, or for binary files.
+    pub conflicts: Vec<Conflict>,
+}
+
+/// Unmerged files of the repository containing `cwd`, in the order git
+/// lists them.
+pub async fn conflicted_files(cwd: &Path) -> Result<Vec<ConflictedFile>, MergeConflictError> {
+    let paths = unmerged_paths(cwd)
+        .await
+        .ok_or(MergeConflictError::NotARepository)?;
+    if paths.is_empty() {
+        return Err(MergeConflictError::NoConflicts);
+    }
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        let conflicts = match tokio::fs::read_to_string(&path).await {
+            Ok(text) => find_conflicts(&text),
+            Err(_) => Vec::new(),
+        };
+        let path = path
+            .strip_prefix(cwd)
+            .map(Path::to_path_buf)
+            .unwrap_or(path);
+        files.push(ConflictedFile { path, conflicts });
+    }
+    Ok(files)
+}
+
+/// Conflicts in `text` marked by `<<<<<<<`, an optional `|||||||` base
+/// section, `=======` and `>>>>>>>`. Unterminated conflicts are ignored.
+pub fn find_conflicts(text: &str) -> Vec<Conflict> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut conflicts = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        if !is_marker(lines[index], '<') {
+            index += 1;
+            continue;
+        }
+        let start = index;
+        let separator = (start + 1..lines.len()).find(|&i| is_separator(lines[i]));
+        let end =
+            separator.and_then(|sep| (sep + 1..lines.len()).find(|&i| is_marker(lines[i], '>')));
+        let Some(end) = end else {
+            break;
+        };
+        let from = start.saturating_sub(CONTEXT_LINES);
+        let to = (end + CONTEXT_LINES).min(lines.len() - 1);
+        let width = (to + 1).to_string().len();
+        let excerpt = (from..=to)
+            .map(|i| format!("{:>width$} | {}", i + 1, lines[i]))
+            .collect::<Vec<_>>()
+            .join("\n");
+        conflicts.push(Conflict {
+            start_line: start + 1,
+            end_line: end + 1,
+            excerpt,
+        });
+        index = end + 1;
+    }
+    conflicts
+}
+
+/// `<<<<<<<` or `>>>>>>>`, optionally followed by a label.
+fn is_marker(line: &str, c: char) -> bool {
+    let Some(rest) = line.strip_prefix(&String::from(c).repeat(7)) else {
+        return false;
+    };
+    rest.is_empty() || rest.starts_with(' ')
+}
+
+fn is_separator(line: &str) -> bool {
+    line.trim_end() == "======="
+}
+
+/// Initial prompt asking the agent to resolve the conflicts in `files`.
+pub fn resolve_prompt(files: &[ConflictedFile]) -> String {
+    let mut prompt = format!(
+        "This repository has unresolved merge conflicts in {} file(s), listed below with the code around each conflict. \
+         Resolve them one file at a time. For each file, work out what both sides intended, using the surrounding code and \
+         `git log`/`git show` on either side when that is unclear, then make a single apply_patch call that resolves every \
+         conflict in that file and removes all of its conflict markers, and explain the resolution in a sentence or two before \
+         moving on to the next file. Each patch is shown to me for approval; if I reject one, ask how I want that file resolved. \
+         Do not change code outside the conflicts and do not stage or commit anything. Finish with a summary of how each file was resolved.\n\n<merge_conflicts>",
+        files.len()
+    );
+    for file in files {
+        let path = file.path.display();
+        if file.conflicts.is_empty() {
+            prompt.push_str(&format!(
+                "\nFile: {path}\n(no conflict markers: binary file or a conflict such as modify/delete; check `git status` and ask me how to resolve it)\n"
+            ));
+            continue;
+        }
+        prompt.push_str(&format!(
+            "\nFile: {path} ({} conflict(s))\n",
+            file.conflicts.len()
+        ));
+        for conflict in file.conflicts.iter().take(MAX_CONFLICTS_PER_FILE) {
+            prompt.push_str(&format!(
+                "Lines {}-{}:\n```\n{}\n```\n",
+                conflict.start_line, conflict.end_line, conflict.excerpt
+            ));
+        }
+        if file.conflicts.len() > MAX_CONFLICTS_PER_FILE {
+            prompt.push_str(&format!(
+                "({} more conflict(s); read the file for them)\n",
+                file.conflicts.len() - MAX_CONFLICTS_PER_FILE
+            ));
+        }
+    }
+    prompt.push_str("</merge_conflicts>");
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn finds_conflicts_with_context() {
+        let mut text: Vec<String> = (1..=10).map(|i| format!("line {i}")).collect();
+        text.extend([
+            "<<<<<<< HEAD".to_string(),
+            "ours".to_string(),
+            "||||||| base".to_string(),
+            "base".to_string(),
+            "=======".to_string(),
+            "theirs".to_string(),
+            ">>>>>>> feature".to_string(),
+            "after".to_string(),
+            // Unterminated conflicts are not reported.
+            "<<<<<<< HEAD".to_string(),
+            "dangling".to_string(),
+        ]);
+        let conflicts = find_conflicts(&text.join("\n"));
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!((conflicts[0].start_line, conflicts[0].end_line), (11, 17));
+        assert_eq!(
+            conflicts[0].excerpt,
+            [
+                " 3 | line 3",
+                " 4 | line 4",
+                " 5 | line 5",
+                " 6 | line 6",
+                " 7 | line 7",
+                " 8 | line 8",
+                " 9 | line 9",
+                "10 | line 10",
+                "11 | <<<<<<< HEAD",
+                "12 | ours",
+                "13 | ||||||| base",
+                "14 | base",
+                "15 | =======",
+                "16 | theirs",
+                "17 | >>>>>>> feature",
+                "18 | after",
+                "19 | <<<<<<< HEAD",
+                "20 | dangling",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn ignores_marker_lookalikes() {
+        assert!(find_conflicts("<<<<<<<<< not a marker\n=======\n>>>>>>>\n").is_empty());
+        assert!(find_conflicts("=======\n").is_empty());
+    }
+}
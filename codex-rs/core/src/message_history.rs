@@ -52,7 +52,7 @@ pub struct HistoryEntry {
 }
 
 fn history_filepath(config: &Config) -> PathBuf {
-    let mut path = config.codex_home.clone();
+    let mut path = config.state_home.path.clone();
     path.push(HISTORY_FILENAME);
     path
 }
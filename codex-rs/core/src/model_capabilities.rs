@@ -0,0 +1,129 @@
+//! Adapt a prompt to what the active model family can accept, so unsupported
+//! content degrades with a warning instead of the provider rejecting the turn.
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+
+use crate::client_common::tools::ToolSpec;
+use crate::model_family::ModelFamily;
+
+pub(crate) const IMAGE_OMITTED_PLACEHOLDER: &str =
+    "[image omitted: the current model does not accept image input]";
+
+/// Content that had to be dropped for the prompt to be accepted.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct PromptAdaptation {
+    pub(crate) images_omitted: usize,
+    pub(crate) tools_omitted: bool,
+}
+
+impl PromptAdaptation {
+    pub(crate) fn warning_message(&self, model: &str) -> Option<String> {
+        match (self.images_omitted, self.tools_omitted) {
+            (0, false) => None,
+            (0, true) => Some(format!(
+                "{model} does not support tool calls; sending the request without tools"
+            )),
+            (images, false) => Some(format!(
+                "{model} does not support image input; omitted {images} image(s) from the request"
+            )),
+            (images, true) => Some(format!(
+                "{model} does not support image input or tool calls; omitted {images} image(s) and all tools from the request"
+            )),
+        }
+    }
+}
+
+/// Strip whatever `model_family` cannot accept from the outgoing `input` and
+/// `tools`: images become a text placeholder and tools are dropped entirely.
+pub(crate) fn adapt_prompt_for_model(
+    model_family: &ModelFamily,
+    input: &mut [ResponseItem],
+    tools: &mut Vec<ToolSpec>,
+) -> PromptAdaptation {
+    let mut adaptation = PromptAdaptation::default();
+
+    if !model_family.supports_tools && !tools.is_empty() {
+        tools.clear();
+        adaptation.tools_omitted = true;
+    }
+
+    if model_family.supports_image_input {
+        return adaptation;
+    }
+    for item in input.iter_mut() {
+        let ResponseItem::Message { content, .. } = item else {
+            continue;
+        };
+        for content_item in content.iter_mut() {
+            if matches!(content_item, ContentItem::InputImage { .. }) {
+                *content_item = ContentItem::InputText {
+                    text: IMAGE_OMITTED_PLACEHOLDER.to_string(),
+                };
+                adaptation.images_omitted += 1;
+            }
+        }
+    }
+    adaptation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model_family::find_family_for_model;
+    use pretty_assertions::assert_eq;
+
+    fn user_message_with_image() -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![
+                ContentItem::InputText {
+                    text: "what is this?".to_string(),
+                },
+                ContentItem::InputImage {
+                    image_url: "data:image/png;base64,AAAA".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn images_are_replaced_for_text_only_models() {
+        let family = find_family_for_model("gpt-oss-20b").expect("known model family");
+        let mut input = vec![user_message_with_image()];
+        let mut tools = Vec::new();
+
+        let adaptation = adapt_prompt_for_model(&family, &mut input, &mut tools);
+
+        assert_eq!(
+            adaptation,
+            PromptAdaptation {
+                images_omitted: 1,
+                tools_omitted: false,
+            }
+        );
+        let ResponseItem::Message { content, .. } = &input[0] else {
+            panic!("expected message");
+        };
+        assert_eq!(
+            content[1],
+            ContentItem::InputText {
+                text: IMAGE_OMITTED_PLACEHOLDER.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn images_are_kept_for_vision_models() {
+        let family = find_family_for_model("gpt-5-codex").expect("known model family");
+        let mut input = vec![user_message_with_image()];
+        let mut tools = Vec::new();
+
+        let adaptation = adapt_prompt_for_model(&family, &mut input, &mut tools);
+
+        assert_eq!(adaptation, PromptAdaptation::default());
+        assert_eq!(adaptation.warning_message("gpt-5-codex"), None);
+        assert_eq!(input, vec![user_message_with_image()]);
+    }
+}
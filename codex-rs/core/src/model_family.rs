@@ -39,6 +39,14 @@ pub struct ModelFamily {
     /// Responses API.
     pub supports_parallel_tool_calls: bool,
 
+    /// Whether the model accepts image content. When false, image inputs are
+    /// replaced with a text placeholder before the request is sent.
+    pub supports_image_input: bool,
+
+    /// Whether the model accepts tool definitions at all. When false, requests
+    /// are sent without tools so the provider does not reject the turn.
+    pub supports_tools: bool,
+
     /// Present if the model performs better when `apply_patch` is provided as
     /// a tool call instead of just a bash command
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
@@ -63,6 +71,8 @@ macro_rules! model_family {
             reasoning_summary_format: ReasoningSummaryFormat::None,
             uses_local_shell_tool: false,
             supports_parallel_tool_calls: false,
+            supports_image_input: true,
+            supports_tools: true,
             apply_patch_tool_type: None,
             base_instructions: BASE_INSTRUCTIONS.to_string(),
             experimental_supported_tools: Vec::new(),
@@ -107,11 +117,19 @@ pub fn find_family_for_model(mut slug: &str) -> Option<ModelFamily> {
             needs_special_apply_patch_instructions: true,
         )
     } else if slug.starts_with("gpt-oss") || slug.starts_with("openai/gpt-oss") {
-        model_family!(slug, "gpt-oss", apply_patch_tool_type: Some(ApplyPatchToolType::Function))
+        model_family!(
+            slug, "gpt-oss",
+            apply_patch_tool_type: Some(ApplyPatchToolType::Function),
+            supports_image_input: false,
+        )
     } else if slug.starts_with("gpt-4o") {
         model_family!(slug, "gpt-4o", needs_special_apply_patch_instructions: true)
     } else if slug.starts_with("gpt-3.5") {
-        model_family!(slug, "gpt-3.5", needs_special_apply_patch_instructions: true)
+        model_family!(
+            slug, "gpt-3.5",
+            needs_special_apply_patch_instructions: true,
+            supports_image_input: false,
+        )
     } else if slug.starts_with("test-gpt-5-codex") {
         model_family!(
             slug, slug,
@@ -172,6 +190,8 @@ pub fn derive_default_model_family(model: &str) -> ModelFamily {
         reasoning_summary_format: ReasoningSummaryFormat::None,
         uses_local_shell_tool: false,
         supports_parallel_tool_calls: false,
+        supports_image_input: true,
+        supports_tools: true,
         apply_patch_tool_type: None,
         base_instructions: BASE_INSTRUCTIONS.to_string(),
         experimental_supported_tools: Vec::new(),
@@ -0,0 +1,177 @@
+//! Discover token limits for models Codex has no built-in metadata for by
+//! asking the provider's OpenAI-compatible `GET /models` endpoint.
+//!
+//! Servers disagree on field names: vLLM reports `max_model_len`, OpenRouter
+//! `context_length` (plus `top_provider.max_completion_tokens`), and
+//! LiteLLM-style proxies `context_window` / `max_output_tokens`.
+//!
+//! Each provider is asked about a model once per process, so switching back
+//! and forth with `/model` does not query it again.
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::Value;
+use tracing::debug;
+
+use crate::default_client::create_client;
+use crate::model_provider_info::ModelProviderInfo;
+
+/// Upper bound on how long session startup may wait for the model listing.
+const MODELS_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+const CONTEXT_WINDOW_KEYS: &[&str] = &[
+    "context_window",
+    "context_length",
+    "max_model_len",
+    "max_context_length",
+];
+
+const MAX_OUTPUT_TOKENS_KEYS: &[&str] = &["max_output_tokens", "max_completion_tokens"];
+
+/// Answers so far, by provider base URL and model; `None` when the provider
+/// had nothing for the model.
+type DetectedLimits = HashMap<(Option<String>, String), Option<DetectedModelLimits>>;
+
+static DETECTED: LazyLock<Mutex<DetectedLimits>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct DetectedModelLimits {
+    pub(crate) context_window: Option<u64>,
+    pub(crate) max_output_tokens: Option<u64>,
+}
+
+/// Query the provider's model listing and return the limits reported for
+/// `model`. Any failure (no `base_url`, network error, unknown model) yields
+/// `None` so callers fall back to whatever the config already resolved.
+pub(crate) async fn detect_model_limits(
+    provider: &ModelProviderInfo,
+    model: &str,
+) -> Option<DetectedModelLimits> {
+    let key = (provider.base_url.clone(), model.to_string());
+    if let Some(limits) = DETECTED
+        .lock()
+        .ok()
+        .and_then(|detected| detected.get(&key).copied())
+    {
+        return limits;
+    }
+    let limits = query_model_limits(provider, model).await;
+    if let Ok(mut detected) = DETECTED.lock() {
+        detected.insert(key, limits);
+    }
+    limits
+}
+
+async fn query_model_limits(
+    provider: &ModelProviderInfo,
+    model: &str,
+) -> Option<DetectedModelLimits> {
+    let client = create_client();
+    let request = provider.create_models_request_builder(&client)?;
+    let response = match request.timeout(MODELS_REQUEST_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            debug!("failed to query model listing: {err}");
+            return None;
+        }
+    };
+    if !response.status().is_success() {
+        debug!("model listing returned status {}", response.status());
+        return None;
+    }
+    let body: Value = match response.json().await {
+        Ok(body) => body,
+        Err(err) => {
+            debug!("failed to parse model listing: {err}");
+            return None;
+        }
+    };
+    parse_model_limits(&body, model)
+}
+
+fn parse_model_limits(body: &Value, model: &str) -> Option<DetectedModelLimits> {
+    let entries = body
+        .get("data")
+        .and_then(Value::as_array)
+        .or_else(|| body.as_array())?;
+    let entry = entries
+        .iter()
+        .find(|entry| entry.get("id").and_then(Value::as_str) == Some(model))?;
+
+    let context_window = first_u64(entry, CONTEXT_WINDOW_KEYS);
+    let max_output_tokens = first_u64(entry, MAX_OUTPUT_TOKENS_KEYS).or_else(|| {
+        entry
+            .get("top_provider")
+            .and_then(|top| first_u64(top, MAX_OUTPUT_TOKENS_KEYS))
+    });
+
+    if context_window.is_none() && max_output_tokens.is_none() {
+        return None;
+    }
+    Some(DetectedModelLimits {
+        context_window,
+        max_output_tokens,
+    })
+}
+
+fn first_u64(value: &Value, keys: &[&str]) -> Option<u64> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(Value::as_u64))
+        .filter(|tokens| *tokens > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn parses_vllm_max_model_len() {
+        let body = json!({
+            "object": "list",
+            "data": [
+                { "id": "other", "max_model_len": 8192 },
+                { "id": "qwen3-coder", "max_model_len": 65536 },
+            ],
+        });
+
+        assert_eq!(
+            parse_model_limits(&body, "qwen3-coder"),
+            Some(DetectedModelLimits {
+                context_window: Some(65_536),
+                max_output_tokens: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_openrouter_top_provider_limits() {
+        let body = json!({
+            "data": [{
+                "id": "deepseek/deepseek-chat",
+                "context_length": 163840,
+                "top_provider": { "max_completion_tokens": 16384 },
+            }],
+        });
+
+        assert_eq!(
+            parse_model_limits(&body, "deepseek/deepseek-chat"),
+            Some(DetectedModelLimits {
+                context_window: Some(163_840),
+                max_output_tokens: Some(16_384),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_model_or_missing_limits_yield_none() {
+        let body = json!({ "data": [{ "id": "llama3", "owned_by": "library" }] });
+
+        assert_eq!(parse_model_limits(&body, "llama3"), None);
+        assert_eq!(parse_model_limits(&body, "mistral"), None);
+    }
+}
@@ -162,6 +162,31 @@ impl ModelProviderInfo {
         }
     }
 
+    /// URL of the provider's OpenAI-compatible `GET /models` listing. Only
+    /// providers with an explicit `base_url` are eligible; the OpenAI defaults
+    /// are covered by the built-in model metadata.
+    pub(crate) fn get_models_url(&self) -> Option<String> {
+        let base_url = self.base_url.as_ref()?;
+        let query_string = self.get_query_string();
+        Some(format!(
+            "{}/models{query_string}",
+            base_url.trim_end_matches('/')
+        ))
+    }
+
+    /// Construct a `GET` RequestBuilder for the model listing, applying the
+    /// same headers and API key that model requests use.
+    pub(crate) fn create_models_request_builder(
+        &self,
+        client: &reqwest::Client,
+    ) -> Option<reqwest::RequestBuilder> {
+        let mut builder = client.get(self.get_models_url()?);
+        if let Ok(Some(key)) = self.api_key() {
+            builder = builder.bearer_auth(key);
+        }
+        Some(self.apply_http_headers(builder))
+    }
+
     pub(crate) fn is_azure_responses_endpoint(&self) -> bool {
         if self.wire_api != WireApi::Responses {
             return false;
@@ -0,0 +1,555 @@
+//! Packages the agent installs with `npm`, `yarn`, `pnpm`, `pip`, `uv` or
+//! `cargo add`: recognized from the command before it runs, so adding a
+//! dependency can require its own approval, and recorded with the versions
+//! that were actually resolved in a per-session manifest (a lightweight SBOM
+//! with the command, directory and time each package came from).
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use codex_protocol::ConversationId;
+use serde::Serialize;
+
+use crate::bash::parse_bash_lc_plain_commands;
+use crate::config_types::PackageInstalls;
+
+/// Directory under `CODEX_HOME` holding one manifest per session.
+pub(crate) const PACKAGE_INSTALLS_DIR: &str = "package-installs";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PackageManager {
+    Npm,
+    Yarn,
+    Pnpm,
+    Pip,
+    Uv,
+    Cargo,
+}
+
+impl PackageManager {
+    /// Package URL type, see <https://github.com/package-url/purl-spec>.
+    fn purl_type(self) -> &'static str {
+        match self {
+            PackageManager::Npm | PackageManager::Yarn | PackageManager::Pnpm => "npm",
+            PackageManager::Pip | PackageManager::Uv => "pypi",
+            PackageManager::Cargo => "cargo",
+        }
+    }
+}
+
+/// An install command and the packages it names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PackageInstall {
+    pub(crate) manager: PackageManager,
+    /// Packages named on the command line; empty for installs from a
+    /// lockfile or requirements file.
+    pub(crate) packages: Vec<RequestedPackage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RequestedPackage {
+    pub(crate) name: String,
+    /// Version or requirement as written, e.g. `^4.17` or `>=2.31`.
+    pub(crate) version: Option<String>,
+}
+
+/// The package install `command` performs, looking inside `bash -lc`
+/// scripts.
+pub(crate) fn detect_package_install(command: &[String]) -> Option<PackageInstall> {
+    match parse_bash_lc_plain_commands(command) {
+        Some(commands) => commands.iter().find_map(|words| detect_in_words(words)),
+        None => detect_in_words(command),
+    }
+}
+
+fn detect_in_words(words: &[String]) -> Option<PackageInstall> {
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let words = match words.as_slice() {
+        ["sudo", rest @ ..] => rest,
+        words => words,
+    };
+    let program = words
+        .first()
+        .copied()
+        .map(|program| program.rsplit('/').next().unwrap_or(program))?;
+    let (manager, args) = match (program, &words[1..]) {
+        ("npm", [sub, args @ ..]) if matches!(*sub, "install" | "i" | "add") => {
+            (PackageManager::Npm, args)
+        }
+        ("yarn", ["add", args @ ..]) => (PackageManager::Yarn, args),
+        ("pnpm", [sub, args @ ..]) if matches!(*sub, "add" | "install" | "i") => {
+            (PackageManager::Pnpm, args)
+        }
+        ("pip" | "pip3", ["install", args @ ..]) => (PackageManager::Pip, args),
+        ("python" | "python3", ["-m", "pip", "install", args @ ..]) => (PackageManager::Pip, args),
+        ("uv", ["pip", "install", args @ ..]) | ("uv", ["add", args @ ..]) => {
+            (PackageManager::Uv, args)
+        }
+        ("cargo", ["add", args @ ..]) => (PackageManager::Cargo, args),
+        _ => return None,
+    };
+
+    let packages = positional_args(manager, args)
+        .into_iter()
+        .filter_map(|spec| parse_spec(manager, spec))
+        .collect();
+    Some(PackageInstall { manager, packages })
+}
+
+/// Arguments that are not flags or the values of flags.
+fn positional_args<'a>(manager: PackageManager, args: &[&'a str]) -> Vec<&'a str> {
+    let takes_value: &[&str] = match manager {
+        PackageManager::Npm | PackageManager::Yarn | PackageManager::Pnpm => {
+            &["--registry", "--prefix", "--workspace", "-w", "--filter"]
+        }
+        PackageManager::Pip | PackageManager::Uv => &[
+            "-r",
+            "--requirement",
+            "-c",
+            "--constraint",
+            "-e",
+            "--editable",
+            "-i",
+            "--index-url",
+            "--extra-index-url",
+            "-t",
+            "--target",
+            "--prefix",
+            "-f",
+            "--find-links",
+            "--python",
+            "--group",
+            "--optional",
+        ],
+        PackageManager::Cargo => &[
+            "-F",
+            "--features",
+            "-p",
+            "--package",
+            "--rename",
+            "--path",
+            "--git",
+            "--branch",
+            "--tag",
+            "--rev",
+            "--registry",
+            "--manifest-path",
+            "--target",
+        ],
+    };
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if takes_value.contains(arg) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            positional.push(*arg);
+        }
+    }
+    positional
+}
+
+fn parse_spec(manager: PackageManager, spec: &str) -> Option<RequestedPackage> {
+    // Paths, URLs and archives are not registry packages.
+    if spec.is_empty() || spec.starts_with('.') || spec.starts_with('/') || spec.contains("://") {
+        return None;
+    }
+    let (name, version) = match manager {
+        PackageManager::Npm | PackageManager::Yarn | PackageManager::Pnpm => {
+            // `@scope/name@1.2.3`: the version follows the last `@` that is
+            // not the scope's.
+            match spec.get(1..).and_then(|rest| rest.rfind('@')) {
+                Some(index) => (&spec[..=index], Some(&spec[index + 2..])),
+                None => (spec, None),
+            }
+        }
+        PackageManager::Pip | PackageManager::Uv => {
+            match spec.find(['=', '<', '>', '!', '~', '[', ';']) {
+                Some(index) => {
+                    let requirement = spec[index..].trim_start_matches('[');
+                    let version = requirement
+                        .split_once(']')
+                        .map_or(requirement, |(_, rest)| rest);
+                    (&spec[..index], Some(version))
+                }
+                None => (spec, None),
+            }
+        }
+        PackageManager::Cargo => match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version)),
+            None => (spec, None),
+        },
+    };
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(RequestedPackage {
+        name: name.to_string(),
+        version: version
+            .map(str::trim)
+            .filter(|version| !version.is_empty())
+            .map(str::to_string),
+    })
+}
+
+/// Packages and resolved versions reported by a finished install: pip's
+/// `Successfully installed` line, uv's `+ name==version` lines and cargo's
+/// `Adding name vX.Y.Z` lines. The JS package managers do not print
+/// versions, so those are read from `node_modules` under `cwd`.
+fn resolved_packages(install: &PackageInstall, output: &str, cwd: &Path) -> Vec<(String, String)> {
+    match install.manager {
+        PackageManager::Pip => output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Successfully installed "))
+            .flat_map(str::split_whitespace)
+            .filter_map(|package| package.rsplit_once('-'))
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect(),
+        PackageManager::Uv => output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("+ "))
+            .filter_map(|package| package.trim().split_once("=="))
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect(),
+        PackageManager::Cargo => output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Adding "))
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let name = words.next()?;
+                let version = words.next()?.strip_prefix('v')?;
+                Some((name.to_string(), version.to_string()))
+            })
+            .collect(),
+        PackageManager::Npm | PackageManager::Yarn | PackageManager::Pnpm => install
+            .packages
+            .iter()
+            .filter_map(|package| {
+                let manifest = cwd
+                    .join("node_modules")
+                    .join(&package.name)
+                    .join("package.json");
+                let contents = std::fs::read_to_string(manifest).ok()?;
+                let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+                let version = json.get("version")?.as_str()?;
+                Some((package.name.clone(), version.to_string()))
+            })
+            .collect(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct InstalledPackage {
+    pub(crate) manager: PackageManager,
+    pub(crate) name: String,
+    /// Resolved version, or the requested one when the install did not
+    /// report it.
+    pub(crate) version: Option<String>,
+    pub(crate) purl: String,
+    /// Whether the package was named in the command rather than pulled in
+    /// by one that was.
+    pub(crate) direct: bool,
+    pub(crate) command: String,
+    pub(crate) cwd: PathBuf,
+    pub(crate) installed_at: String,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    session_id: &'a str,
+    packages: &'a [InstalledPackage],
+}
+
+/// What has been installed in this session, and the settings for it.
+#[derive(Debug)]
+pub(crate) struct PackageInstallLog {
+    session_id: String,
+    /// Where the manifest is written; `None` when recording is off.
+    manifest_path: Option<PathBuf>,
+    require_approval: bool,
+    packages: Mutex<Vec<InstalledPackage>>,
+}
+
+impl PackageInstallLog {
+    /// `None` when neither recording nor approval is enabled.
+    pub(crate) fn new(
+        settings: PackageInstalls,
+        codex_home: &Path,
+        session_id: ConversationId,
+    ) -> Option<Self> {
+        if !settings.record && !settings.require_approval {
+            return None;
+        }
+        let session_id = session_id.to_string();
+        Some(Self {
+            manifest_path: settings.record.then(|| {
+                codex_home
+                    .join(PACKAGE_INSTALLS_DIR)
+                    .join(format!("{session_id}.json"))
+            }),
+            session_id,
+            require_approval: settings.require_approval,
+            packages: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Packages `install` names that have not been installed earlier in the
+    /// session, when those need approval.
+    pub(crate) fn new_dependencies(&self, install: &PackageInstall) -> Vec<String> {
+        if !self.require_approval {
+            return Vec::new();
+        }
+        let Ok(installed) = self.packages.lock() else {
+            return Vec::new();
+        };
+        install
+            .packages
+            .iter()
+            .filter(|package| {
+                !installed.iter().any(|installed| {
+                    installed.manager.purl_type() == install.manager.purl_type()
+                        && installed.name == package.name
+                })
+            })
+            .map(|package| match &package.version {
+                Some(version) => format!("{}@{version}", package.name),
+                None => package.name.clone(),
+            })
+            .collect()
+    }
+
+    /// Record what a successful `install` put in place and rewrite the
+    /// manifest.
+    pub(crate) async fn record(
+        &self,
+        install: &PackageInstall,
+        command: &[String],
+        cwd: &Path,
+        output: &str,
+    ) {
+        let Some(manifest_path) = &self.manifest_path else {
+            return;
+        };
+        let resolved = resolved_packages(install, output, cwd);
+        let command = shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" "));
+        let installed_at = chrono::Utc::now().to_rfc3339();
+        let entry = |name: &str, version: Option<String>, direct: bool| InstalledPackage {
+            manager: install.manager,
+            purl: match &version {
+                Some(version) => format!("pkg:{}/{name}@{version}", install.manager.purl_type()),
+                None => format!("pkg:{}/{name}", install.manager.purl_type()),
+            },
+            name: name.to_string(),
+            version,
+            direct,
+            command: command.clone(),
+            cwd: cwd.to_path_buf(),
+            installed_at: installed_at.clone(),
+        };
+
+        let mut entries: Vec<InstalledPackage> = install
+            .packages
+            .iter()
+            .map(|package| {
+                let version = resolved
+                    .iter()
+                    .find(|(name, _)| same_package(name, &package.name))
+                    .map(|(_, version)| version.clone())
+                    .or_else(|| package.version.clone());
+                entry(&package.name, version, true)
+            })
+            .collect();
+        entries.extend(
+            resolved
+                .iter()
+                .filter(|(name, _)| {
+                    !install
+                        .packages
+                        .iter()
+                        .any(|package| same_package(name, &package.name))
+                })
+                .map(|(name, version)| entry(name, Some(version.clone()), false)),
+        );
+        if entries.is_empty() {
+            return;
+        }
+
+        let json = {
+            let Ok(mut packages) = self.packages.lock() else {
+                return;
+            };
+            packages.extend(entries);
+            serde_json::to_vec_pretty(&Manifest {
+                session_id: &self.session_id,
+                packages: &packages,
+            })
+        };
+        let Ok(json) = json else {
+            return;
+        };
+        if let Some(parent) = manifest_path.parent()
+            && let Err(err) = tokio::fs::create_dir_all(parent).await
+        {
+            tracing::warn!("failed to create {}: {err}", parent.display());
+            return;
+        }
+        if let Err(err) = tokio::fs::write(manifest_path, json).await {
+            tracing::warn!("failed to write {}: {err}", manifest_path.display());
+        }
+    }
+}
+
+/// Package names compare case-insensitively with `-` and `_` equivalent,
+/// as PyPI normalizes them.
+fn same_package(a: &str, b: &str) -> bool {
+    let normalize = |name: &str| name.to_ascii_lowercase().replace('_', "-");
+    normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    fn requested(name: &str, version: Option<&str>) -> RequestedPackage {
+        RequestedPackage {
+            name: name.to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn detects_installs_and_their_packages() {
+        assert_eq!(
+            detect_package_install(&vec_str(&[
+                "bash",
+                "-lc",
+                "cd web && npm install --save-dev @types/node@20 lodash"
+            ])),
+            Some(PackageInstall {
+                manager: PackageManager::Npm,
+                packages: vec![
+                    requested("@types/node", Some("20")),
+                    requested("lodash", None)
+                ],
+            })
+        );
+        assert_eq!(
+            detect_package_install(&vec_str(&[
+                "pip",
+                "install",
+                "-r",
+                "requirements.txt",
+                "requests[socks]>=2.31",
+                "Flask==3.0.0"
+            ])),
+            Some(PackageInstall {
+                manager: PackageManager::Pip,
+                packages: vec![
+                    requested("requests", Some(">=2.31")),
+                    requested("Flask", Some("==3.0.0"))
+                ],
+            })
+        );
+        assert_eq!(
+            detect_package_install(&vec_str(&["cargo", "add", "serde@1", "-F", "derive"])),
+            Some(PackageInstall {
+                manager: PackageManager::Cargo,
+                packages: vec![requested("serde", Some("1"))],
+            })
+        );
+        assert_eq!(
+            detect_package_install(&vec_str(&["npm", "install"])),
+            Some(PackageInstall {
+                manager: PackageManager::Npm,
+                packages: Vec::new(),
+            })
+        );
+        assert_eq!(detect_package_install(&vec_str(&["npm", "test"])), None);
+    }
+
+    #[test]
+    fn reads_resolved_versions_from_output() {
+        let pip = PackageInstall {
+            manager: PackageManager::Pip,
+            packages: vec![requested("requests", None)],
+        };
+        assert_eq!(
+            resolved_packages(
+                &pip,
+                "Collecting requests\nSuccessfully installed idna-3.6 requests-2.31.0\n",
+                Path::new("/")
+            ),
+            vec![
+                ("idna".to_string(), "3.6".to_string()),
+                ("requests".to_string(), "2.31.0".to_string())
+            ]
+        );
+
+        let cargo = PackageInstall {
+            manager: PackageManager::Cargo,
+            packages: vec![requested("serde", None)],
+        };
+        assert_eq!(
+            resolved_packages(
+                &cargo,
+                "    Updating crates.io index\n      Adding serde v1.0.190 to dependencies\n",
+                Path::new("/")
+            ),
+            vec![("serde".to_string(), "1.0.190".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn records_manifest_and_tracks_new_dependencies() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let session_id = ConversationId::new();
+        let log = PackageInstallLog::new(
+            PackageInstalls {
+                record: true,
+                require_approval: true,
+            },
+            codex_home.path(),
+            session_id,
+        )
+        .expect("log");
+        let command = vec_str(&["pip", "install", "requests"]);
+        let install = detect_package_install(&command).expect("install");
+
+        assert_eq!(log.new_dependencies(&install), vec!["requests".to_string()]);
+        log.record(
+            &install,
+            &command,
+            Path::new("/repo"),
+            "Successfully installed idna-3.6 requests-2.31.0",
+        )
+        .await;
+        assert_eq!(log.new_dependencies(&install), Vec::<String>::new());
+
+        let manifest = std::fs::read_to_string(
+            codex_home
+                .path()
+                .join(PACKAGE_INSTALLS_DIR)
+                .join(format!("{session_id}.json")),
+        )
+        .expect("manifest");
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).expect("json");
+        let purls: Vec<&str> = manifest["packages"]
+            .as_array()
+            .expect("packages")
+            .iter()
+            .filter_map(|package| package["purl"].as_str())
+            .collect();
+        assert_eq!(purls, vec!["pkg:pypi/requests@2.31.0", "pkg:pypi/idna@3.6"]);
+        assert_eq!(manifest["packages"][0]["direct"], true);
+        assert_eq!(manifest["packages"][0]["command"], "pip install requests");
+    }
+}
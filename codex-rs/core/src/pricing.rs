@@ -0,0 +1,126 @@
+//! Estimated cost of token usage, from `[model_pricing]` or a built-in list
+//! of published API prices.
+
+use std::collections::HashMap;
+
+use crate::config_types::ModelPricing;
+
+/// Prices in USD per million tokens: input, cached input, output.
+const BUILT_IN_PRICES: &[(&str, f64, f64, f64)] = &[
+    ("gpt-5-codex", 1.25, 0.125, 10.0),
+    ("gpt-5-mini", 0.25, 0.025, 2.0),
+    ("gpt-5-nano", 0.05, 0.005, 0.4),
+    ("gpt-5", 1.25, 0.125, 10.0),
+    ("gpt-4.1-mini", 0.4, 0.1, 1.6),
+    ("gpt-4.1-nano", 0.1, 0.025, 0.4),
+    ("gpt-4.1", 2.0, 0.5, 8.0),
+    ("gpt-4o-mini", 0.15, 0.075, 0.6),
+    ("gpt-4o", 2.5, 1.25, 10.0),
+    ("o4-mini", 1.1, 0.275, 4.4),
+    ("o3", 2.0, 0.5, 8.0),
+    ("codex-mini-latest", 1.5, 0.375, 6.0),
+];
+
+/// Prices for `model`, from the longest slug it starts with, so dated
+/// snapshots such as `gpt-5-2025-08-07` are priced like `gpt-5`.
+/// `[model_pricing]` entries win over the built-in prices.
+pub fn model_pricing(
+    model: &str,
+    overrides: &HashMap<String, ModelPricing>,
+) -> Option<ModelPricing> {
+    let configured = overrides
+        .iter()
+        .map(|(slug, pricing)| (slug.as_str(), *pricing));
+    let built_in = BUILT_IN_PRICES
+        .iter()
+        .map(|&(slug, input, cached_input, output)| {
+            let pricing = ModelPricing {
+                input_per_million: input,
+                cached_input_per_million: Some(cached_input),
+                output_per_million: output,
+            };
+            (slug, pricing)
+        });
+    longest_prefix(model, configured).or_else(|| longest_prefix(model, built_in))
+}
+
+fn longest_prefix<'a>(
+    model: &str,
+    prices: impl Iterator<Item = (&'a str, ModelPricing)>,
+) -> Option<ModelPricing> {
+    prices
+        .filter(|(slug, _)| model.starts_with(slug))
+        .max_by_key(|(slug, _)| slug.len())
+        .map(|(_, pricing)| pricing)
+}
+
+impl ModelPricing {
+    /// Cost in USD. `input_tokens` includes the `cached_input_tokens`, as
+    /// reported in token usage events.
+    pub fn cost_usd(&self, input_tokens: u64, cached_input_tokens: u64, output_tokens: u64) -> f64 {
+        let cached = cached_input_tokens.min(input_tokens);
+        let uncached = input_tokens - cached;
+        let cached_price = self
+            .cached_input_per_million
+            .unwrap_or(self.input_per_million);
+        (uncached as f64 * self.input_per_million
+            + cached as f64 * cached_price
+            + output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// `$0.42`, `<$0.01` for amounts that round to nothing, whole dollars from
+/// $100 up.
+pub fn format_usd(cost: f64) -> String {
+    match cost {
+        cost if cost <= 0.0 => "$0.00".to_string(),
+        cost if cost < 0.01 => "<$0.01".to_string(),
+        cost if cost < 100.0 => format!("${cost:.2}"),
+        cost => format!("${cost:.0}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn configured_prices_win_and_prefixes_match_snapshots() {
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "gpt-5".to_string(),
+            ModelPricing {
+                input_per_million: 2.0,
+                cached_input_per_million: None,
+                output_per_million: 20.0,
+            },
+        );
+
+        let dated = model_pricing("gpt-5-2025-08-07", &overrides).expect("gpt-5 prefix");
+        assert_eq!(dated.input_per_million, 2.0);
+        // A longer built-in slug does not beat a configured prefix.
+        let codex = model_pricing("gpt-5-codex", &overrides).expect("configured gpt-5");
+        assert_eq!(codex.output_per_million, 20.0);
+        let mini = model_pricing("gpt-5-mini", &HashMap::new()).expect("built-in gpt-5-mini");
+        assert_eq!(mini.input_per_million, 0.25);
+        assert_eq!(model_pricing("qwen3-coder", &overrides), None);
+    }
+
+    #[test]
+    fn cost_prices_cached_input_separately() {
+        let pricing = ModelPricing {
+            input_per_million: 1.25,
+            cached_input_per_million: Some(0.125),
+            output_per_million: 10.0,
+        };
+        let cost = pricing.cost_usd(1_000_000, 800_000, 100_000);
+        assert!((cost - (0.25 + 0.1 + 1.0)).abs() < 1e-9);
+
+        assert_eq!(format_usd(cost), "$1.35");
+        assert_eq!(format_usd(0.004), "<$0.01");
+        assert_eq!(format_usd(0.0), "$0.00");
+        assert_eq!(format_usd(123.4), "$123");
+    }
+}
@@ -0,0 +1,221 @@
+//! Project environment files: `.env` and direnv's `.envrc`.
+//!
+//! Developers often rely on these to set up the variables their tools need.
+//! Once the user trusts a project's files, the variables they define that
+//! match the `[project_env] allow` list are added to the environment of shell
+//! tool calls. Trust is tied to a digest of the files, so editing them asks
+//! again.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use sha1::Digest;
+use sha1::Sha1;
+
+use crate::config_types::EnvironmentVariablePattern;
+use crate::git_info::get_git_repo_root;
+
+const DOTENV_FILE: &str = ".env";
+const ENVRC_FILE: &str = ".envrc";
+
+/// Environment files found for the working directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectEnv {
+    /// Directory the files were found in.
+    pub root: PathBuf,
+    /// `.env` and/or `.envrc`, in the order they are applied.
+    pub files: Vec<PathBuf>,
+    /// SHA-1 of the files' contents, recorded when the user trusts them.
+    pub digest: String,
+}
+
+/// Look for `.env`/`.envrc` in `cwd` and its parents, up to the enclosing git
+/// repository root.
+pub fn find_project_env(cwd: &Path) -> Option<ProjectEnv> {
+    let repo_root = get_git_repo_root(cwd);
+    for dir in cwd.ancestors() {
+        let files: Vec<PathBuf> = [DOTENV_FILE, ENVRC_FILE]
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        if !files.is_empty() {
+            let mut hasher = Sha1::new();
+            for file in &files {
+                hasher.update(file.to_string_lossy().as_bytes());
+                hasher.update(std::fs::read(file).unwrap_or_default());
+            }
+            return Some(ProjectEnv {
+                root: dir.to_path_buf(),
+                files,
+                digest: format!("{:x}", hasher.finalize()),
+            });
+        }
+        if repo_root.as_deref().is_none_or(|root| root == dir) {
+            break;
+        }
+    }
+    None
+}
+
+/// The variables the project's files define whose names match `allow`.
+/// `.envrc` is evaluated with `direnv` when it is installed (which applies
+/// direnv's own `direnv allow` check) and otherwise read for plain
+/// `export NAME=value` lines; it takes precedence over `.env`.
+pub fn load_project_env(
+    project: &ProjectEnv,
+    allow: &[EnvironmentVariablePattern],
+) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for file in &project.files {
+        let exported = if file.file_name().is_some_and(|name| name == ENVRC_FILE) {
+            direnv_export(&project.root)
+        } else {
+            None
+        };
+        let loaded = match exported {
+            Some(exported) => exported,
+            None => std::fs::read_to_string(file)
+                .map(|text| parse_dotenv(&text))
+                .unwrap_or_default(),
+        };
+        vars.extend(loaded);
+    }
+    vars.retain(|name, _| allow.iter().any(|pattern| pattern.matches(name)));
+    vars
+}
+
+/// Variables direnv would set when entering `root`, or `None` when direnv is
+/// unavailable or refuses (e.g. the `.envrc` is not allowed).
+fn direnv_export(root: &Path) -> Option<Vec<(String, String)>> {
+    let direnv = which::which("direnv").ok()?;
+    let output = std::process::Command::new(direnv)
+        .args(["export", "json"])
+        .current_dir(root)
+        .stdin(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Some(Vec::new());
+    }
+    // Unset variables are reported as `null`; there is nothing to add for them.
+    let exported: BTreeMap<String, Option<String>> = serde_json::from_slice(&output.stdout).ok()?;
+    Some(
+        exported
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with("DIRENV_"))
+            .filter_map(|(name, value)| Some((name, value?)))
+            .collect(),
+    )
+}
+
+/// Parse `NAME=value` lines, optionally prefixed with `export`. Values may be
+/// single-quoted (literal), double-quoted (with `\n`, `\"` and `\\` escapes)
+/// or bare (up to a ` #` comment). Anything else, including lines that run
+/// commands, is skipped; nothing is expanded.
+fn parse_dotenv(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=')?;
+            let name = name.trim();
+            let mut chars = name.chars();
+            let valid_name = chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid_name {
+                return None;
+            }
+            Some((name.to_string(), parse_value(value.trim())?))
+        })
+        .collect()
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        return rest.strip_suffix('\'').map(str::to_string);
+    }
+    if let Some(rest) = value.strip_prefix('"') {
+        let inner = rest.strip_suffix('"')?;
+        let mut out = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some('n')) => {
+                    chars.next();
+                    out.push('\n');
+                }
+                ('\\', Some(escaped @ ('"' | '\\'))) => {
+                    chars.next();
+                    out.push(escaped);
+                }
+                _ => out.push(c),
+            }
+        }
+        return Some(out);
+    }
+    let bare = value.split(" #").next().unwrap_or_default().trim_end();
+    // Command substitution and expansions need a shell; leave them out.
+    if bare.contains(['$', '`']) {
+        return None;
+    }
+    Some(bare.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parses_dotenv_lines() {
+        let text = r#"
+# comment
+DATABASE_URL=postgres://localhost/app # local db
+export RUST_LOG="debug\nmore \"quoted\""
+GREETING='hello $USER'
+use flake
+PATH_ADD=$PWD/bin
+1BAD=x
+"#;
+        assert_eq!(
+            parse_dotenv(text),
+            vec![
+                (
+                    "DATABASE_URL".to_string(),
+                    "postgres://localhost/app".to_string()
+                ),
+                ("RUST_LOG".to_string(), "debug\nmore \"quoted\"".to_string()),
+                ("GREETING".to_string(), "hello $USER".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn loads_only_allowed_variables() {
+        let dir = TempDir::new().expect("tempdir");
+        std::fs::create_dir(dir.path().join(".git")).expect("git dir");
+        std::fs::write(dir.path().join(".env"), "APP_PORT=8080\nAWS_SECRET=x\n").expect("write");
+        let nested = dir.path().join("src");
+        std::fs::create_dir(&nested).expect("nested dir");
+
+        let project = find_project_env(&nested).expect("env file found");
+        assert_eq!(project.root, dir.path());
+        let allow = [EnvironmentVariablePattern::new_case_insensitive("APP_*")];
+        assert_eq!(
+            load_project_env(&project, &allow),
+            BTreeMap::from([("APP_PORT".to_string(), "8080".to_string())])
+        );
+
+        std::fs::write(dir.path().join(".env"), "APP_PORT=9090\n").expect("write");
+        let changed = find_project_env(&nested).expect("env file found");
+        assert_ne!(changed.digest, project.digest);
+    }
+}
@@ -0,0 +1,291 @@
+//! Provenance of the code the agent writes. With `record_provenance` on,
+//! the lines each applied patch adds are recorded with the session, model
+//! and time in a `.codex-provenance.json` sidecar at the repository root,
+//! and `codex blame` matches a file's current lines against those records.
+//!
+//! Lines are stored as hashes of their trimmed text rather than by number,
+//! so the records survive edits elsewhere in the file; a line that has been
+//! changed since no longer matches and is attributed to nobody.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::SecondsFormat;
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+
+use crate::git_info::get_git_repo_root;
+use crate::protocol::FileChange;
+
+/// Sidecar file, at the repository root, holding the records.
+pub const PROVENANCE_FILE: &str = ".codex-provenance.json";
+
+/// Hex digits of the SHA-1 kept per line.
+const LINE_HASH_LEN: usize = 16;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceLog {
+    /// Records per file, keyed by the path relative to the root with `/`
+    /// separators, oldest first.
+    #[serde(default)]
+    pub files: BTreeMap<String, Vec<ProvenanceRecord>>,
+}
+
+/// The lines one patch added to one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceRecord {
+    pub session_id: String,
+    pub model: String,
+    /// RFC 3339, UTC.
+    pub timestamp: String,
+    /// [`line_hash`] of each added line.
+    pub lines: Vec<String>,
+}
+
+/// Hash identifying a line regardless of indentation, or `None` for lines
+/// without any letters or digits (blank lines, lone braces), which say
+/// nothing about who wrote them.
+pub fn line_hash(line: &str) -> Option<String> {
+    let line = line.trim();
+    if !line.chars().any(char::is_alphanumeric) {
+        return None;
+    }
+    let mut hash = format!("{:x}", Sha1::digest(line.as_bytes()));
+    hash.truncate(LINE_HASH_LEN);
+    Some(hash)
+}
+
+/// The nearest directory at or above `path` holding a [`PROVENANCE_FILE`].
+pub fn find_provenance_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(PROVENANCE_FILE).is_file())
+        .map(Path::to_path_buf)
+}
+
+impl ProvenanceLog {
+    /// The log under `root`, empty when there is none yet.
+    pub fn load(root: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(root.join(PROVENANCE_FILE)) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, root: &Path) -> std::io::Result<()> {
+        let mut contents = serde_json::to_string_pretty(self)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        contents.push('\n');
+        std::fs::write(root.join(PROVENANCE_FILE), contents)
+    }
+
+    /// Record the lines `changes` add to files under `root`. Deleted files
+    /// lose their records and moved files take theirs along.
+    pub(crate) fn record_changes(
+        &mut self,
+        root: &Path,
+        changes: &HashMap<PathBuf, FileChange>,
+        session_id: &str,
+        model: &str,
+        timestamp: &str,
+    ) {
+        for (path, change) in changes {
+            let Some(key) = relative_key(root, path) else {
+                continue;
+            };
+            let (key, added): (String, Vec<&str>) = match change {
+                FileChange::Add { content } => {
+                    self.files.remove(&key);
+                    (key, content.lines().collect())
+                }
+                FileChange::Delete { .. } => {
+                    self.files.remove(&key);
+                    continue;
+                }
+                FileChange::Update {
+                    unified_diff,
+                    move_path,
+                } => {
+                    let added = unified_diff
+                        .lines()
+                        .filter(|line| !line.starts_with("+++"))
+                        .filter_map(|line| line.strip_prefix('+'))
+                        .collect();
+                    match move_path
+                        .as_deref()
+                        .and_then(|dest| relative_key(root, dest))
+                    {
+                        Some(dest) => {
+                            if let Some(records) = self.files.remove(&key) {
+                                self.files.insert(dest.clone(), records);
+                            }
+                            (dest, added)
+                        }
+                        None => (key, added),
+                    }
+                }
+            };
+            let lines: Vec<String> = added.into_iter().filter_map(line_hash).collect();
+            if lines.is_empty() {
+                continue;
+            }
+            self.files.entry(key).or_default().push(ProvenanceRecord {
+                session_id: session_id.to_string(),
+                model: model.to_string(),
+                timestamp: timestamp.to_string(),
+                lines,
+            });
+        }
+    }
+
+    /// The latest record that added each line of `contents`, the current
+    /// text of `file` (relative to the root, `/` separated).
+    pub fn blame<'a>(&'a self, file: &str, contents: &str) -> Vec<Option<&'a ProvenanceRecord>> {
+        let records = self.files.get(file).map(Vec::as_slice).unwrap_or_default();
+        contents
+            .lines()
+            .map(|line| {
+                let hash = line_hash(line)?;
+                records
+                    .iter()
+                    .rev()
+                    .find(|record| record.lines.contains(&hash))
+            })
+            .collect()
+    }
+}
+
+/// Append the lines an applied patch added to the log of the repository
+/// containing `cwd` (or of `cwd` itself outside a repository).
+pub(crate) fn record_patch(
+    cwd: &Path,
+    changes: &HashMap<PathBuf, FileChange>,
+    session_id: &str,
+    model: &str,
+) -> std::io::Result<()> {
+    let root = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+    let mut log = ProvenanceLog::load(&root)?;
+    let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    log.record_changes(&root, changes, session_id, model, &timestamp);
+    log.save(&root)
+}
+
+/// `path` relative to `root` as a log key, or `None` outside `root`.
+pub fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn update(diff: &str) -> FileChange {
+        FileChange::Update {
+            unified_diff: diff.to_string(),
+            move_path: None,
+        }
+    }
+
+    #[test]
+    fn blame_attributes_lines_to_the_latest_session_that_wrote_them() {
+        let root = Path::new("/repo");
+        let file = root.join("src/lib.rs");
+        let mut log = ProvenanceLog::default();
+        log.record_changes(
+            root,
+            &HashMap::from([(
+                file.clone(),
+                FileChange::Add {
+                    content: "fn one() {\n    1\n}\n".to_string(),
+                },
+            )]),
+            "session-a",
+            "gpt-5",
+            "2025-01-01T00:00:00Z",
+        );
+        log.record_changes(
+            root,
+            &HashMap::from([(
+                file,
+                update("@@ -1,3 +1,3 @@\n fn one() {\n-    1\n+    2\n }\n"),
+            )]),
+            "session-b",
+            "gpt-5-codex",
+            "2025-01-02T00:00:00Z",
+        );
+
+        let blame = log.blame("src/lib.rs", "fn one() {\n    2\n}\nfn mine() {}\n");
+        let sessions: Vec<Option<&str>> = blame
+            .iter()
+            .map(|record| record.map(|record| record.session_id.as_str()))
+            .collect();
+        assert_eq!(
+            sessions,
+            vec![Some("session-a"), Some("session-b"), None, None]
+        );
+    }
+
+    #[test]
+    fn moves_keep_records_and_deletes_drop_them() {
+        let root = Path::new("/repo");
+        let mut log = ProvenanceLog::default();
+        let record = |log: &mut ProvenanceLog, changes: HashMap<PathBuf, FileChange>| {
+            log.record_changes(root, &changes, "s", "m", "t");
+        };
+        record(
+            &mut log,
+            HashMap::from([
+                (
+                    root.join("a.txt"),
+                    FileChange::Add {
+                        content: "alpha\n".to_string(),
+                    },
+                ),
+                (
+                    root.join("b.txt"),
+                    FileChange::Add {
+                        content: "beta\n".to_string(),
+                    },
+                ),
+            ]),
+        );
+        record(
+            &mut log,
+            HashMap::from([
+                (
+                    root.join("a.txt"),
+                    FileChange::Update {
+                        unified_diff: "@@ -1 +1,2 @@\n alpha\n+gamma\n".to_string(),
+                        move_path: Some(root.join("c.txt")),
+                    },
+                ),
+                (
+                    root.join("b.txt"),
+                    FileChange::Delete {
+                        content: "beta\n".to_string(),
+                    },
+                ),
+            ]),
+        );
+
+        assert_eq!(log.files.keys().collect::<Vec<_>>(), vec!["c.txt"]);
+        assert_eq!(log.files["c.txt"].len(), 2);
+        assert!(
+            log.blame("c.txt", "alpha\ngamma\n")
+                .iter()
+                .all(Option::is_some)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Context for `codex rebase`: the commits of the current branch since it
+//! left its base, and the prompt that has the agent plan an interactive
+//! rebase of them, run it once the plan is approved and help with any
+//! conflicts on the way.
+
+use std::path::Path;
+
+use crate::changelog::ChangelogCommit;
+use crate::changelog::ChangelogError;
+use crate::changelog::commits_in_range;
+use crate::git_info::collect_git_info;
+use crate::git_info::merge_base_with_head;
+use crate::git_info::rebase_in_progress;
+use crate::git_info::upstream_or_default_branch;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RebaseError {
+    #[error("not inside a git repository")]
+    NotARepository,
+
+    #[error(
+        "a rebase is already in progress; finish it with `codex resolve` or `git rebase --abort`"
+    )]
+    InProgress,
+
+    #[error("no upstream or default branch to rebase onto; pass --onto <REV>")]
+    NoBase,
+
+    #[error("HEAD has no common ancestor with {0}")]
+    NoMergeBase(String),
+
+    #[error("no commits on this branch since {0}")]
+    NothingToRebase(String),
+
+    #[error(transparent)]
+    Log(ChangelogError),
+}
+
+/// The commits an interactive rebase of the current branch would rewrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebaseContext {
+    /// Base as the user knows it, e.g. `origin/main`.
+    pub base: String,
+    /// Common ancestor of `HEAD` and `base`; the rebase starts after it.
+    pub merge_base: String,
+    /// `HEAD` before the rebase.
+    pub head: String,
+    /// Commits being rebased, oldest first.
+    pub commits: Vec<ChangelogCommit>,
+}
+
+/// Commits of the current branch since it left `onto`, or its upstream (the
+/// default branch when it has none).
+pub async fn rebase_context(cwd: &Path, onto: Option<&str>) -> Result<RebaseContext, RebaseError> {
+    let head = collect_git_info(cwd)
+        .await
+        .and_then(|info| info.commit_hash)
+        .ok_or(RebaseError::NotARepository)?;
+    if rebase_in_progress(cwd).await {
+        return Err(RebaseError::InProgress);
+    }
+    let base = match onto {
+        Some(onto) => onto.to_string(),
+        None => upstream_or_default_branch(cwd)
+            .await
+            .ok_or(RebaseError::NoBase)?,
+    };
+    let merge_base = merge_base_with_head(cwd, &base)
+        .await
+        .ok_or_else(|| RebaseError::NoMergeBase(base.clone()))?;
+    let commits = match commits_in_range(cwd, &merge_base, "HEAD").await {
+        Ok(commits) => commits,
+        Err(ChangelogError::Empty(_)) => return Err(RebaseError::NothingToRebase(base)),
+        Err(err) => return Err(RebaseError::Log(err)),
+    };
+    Ok(RebaseContext {
+        base,
+        merge_base,
+        head,
+        commits,
+    })
+}
+
+impl RebaseContext {
+    /// Initial prompt asking the agent to plan the rebase, wait for approval
+    /// and then carry it out.
+    pub fn prompt(&self) -> String {
+        let merge_base = short_sha(&self.merge_base);
+        let head = short_sha(&self.head);
+        let mut prompt = format!(
+            "I want to clean up the {count} commit(s) on this branch since it left {base} (merge base {merge_base}, HEAD \
+             {head}) with an interactive rebase. They are listed below, oldest first, with the files each commit changed.\n\n\
+             1. Propose a plan first and change nothing yet: the rebase todo list you would use, with one `pick`, `fixup` or \
+             `drop` line per commit in the new order, and an `exec git commit --amend --only -m '<message>'` line after each \
+             commit whose message should change, including commits that others are folded into. Write new messages in the \
+             style of the existing ones. Say in a sentence for each change why commits are squashed, reordered, reworded or \
+             dropped, and run `git show <sha>` when a subject does not make a commit clear. Then wait until I approve the plan \
+             or ask for changes.\n\
+             2. Once I approve, run the whole plan in a single command so that I can check the todo list in the approval \
+             prompt: `GIT_EDITOR=true GIT_SEQUENCE_EDITOR=\"printf '%s\\n' '<todo line>' '<todo line>' ... >\" git rebase -i \
+             {merge_base}`.\n\
+             3. If the rebase stops on conflicts, tell me which commit and files are affected, resolve the files one at a \
+             time with a single apply_patch call each that removes all of the conflict markers, explaining each resolution in \
+             a sentence or two, then `git add` them and run `GIT_EDITOR=true git rebase --continue`. If I reject a \
+             resolution, ask how I want that file resolved. If I ask to stop, run `git rebase --abort`.\n\
+             4. When the rebase is done, show `git log --oneline {merge_base}..HEAD` and check with `git diff --stat {head} \
+             HEAD` that the final tree only differs from the original where commits were dropped.\n\n<commits>\n",
+            count = self.commits.len(),
+            base = self.base,
+        );
+        for commit in &self.commits {
+            prompt.push_str(&commit.render());
+        }
+        prompt.push_str("</commits>");
+        prompt
+    }
+}
+
+fn short_sha(sha: &str) -> String {
+    sha.chars().take(10).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn prompt_lists_commits_and_the_rebase_range() {
+        let context = RebaseContext {
+            base: "origin/main".to_string(),
+            merge_base: "1111111111aaaaaaaaaa".to_string(),
+            head: "2222222222bbbbbbbbbb".to_string(),
+            commits: vec![ChangelogCommit {
+                sha: "3333333333cccccccccc".to_string(),
+                subject: "wip".to_string(),
+                body: String::new(),
+                files: vec![("codex-rs/core/src/rebase.rs".to_string(), Some((10, 2)))],
+            }],
+        };
+        let prompt = context.prompt();
+
+        assert!(
+            prompt.contains("since it left origin/main (merge base 1111111111, HEAD 2222222222)")
+        );
+        assert!(prompt.contains("git rebase -i 1111111111`"));
+        assert!(prompt.contains("git diff --stat 2222222222 HEAD"));
+        assert_eq!(
+            prompt.split_once("<commits>\n").map(|(_, commits)| commits),
+            Some(
+                "commit 3333333333: wip\nareas: codex-rs/core\n  codex-rs/core/src/rebase.rs (+10 -2)\n</commits>"
+            )
+        );
+    }
+}
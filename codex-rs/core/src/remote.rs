@@ -0,0 +1,378 @@
+//! Remote workspaces: the agent's shell commands and `apply_patch` calls run
+//! on another host over SSH, while the UI and model calls stay local.
+//!
+//! Nothing is installed on the remote host. Commands are handed to the remote
+//! login shell, files a patch updates or `read_file` reads are fetched with
+//! `cat`, and the patched contents are written back by a POSIX `sh` script
+//! fed over stdin.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_apply_patch::maybe_parse_apply_patch;
+use codex_apply_patch::maybe_parse_apply_patch_verified_with;
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::config_types::RemoteWorkspace;
+use crate::exec::ExecParams;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+
+/// Agent socket `ssh` authenticates with, kept whatever the environment
+/// policy says.
+const SSH_AUTH_SOCK: &str = "SSH_AUTH_SOCK";
+
+/// Exit code reported when `ssh` could not be run or timed out.
+const SSH_FAILURE_EXIT_CODE: i32 = 255;
+
+impl RemoteWorkspace {
+    /// `ssh` invocation that runs `script` in the remote login shell.
+    fn ssh_argv(&self, script: String) -> Vec<String> {
+        let mut argv = vec!["ssh".to_string()];
+        argv.extend(self.ssh_args.iter().cloned());
+        // No tty and no password prompts: nobody is there to answer them.
+        argv.extend(["-T", "-o", "BatchMode=yes"].map(String::from));
+        argv.push(self.host.clone());
+        argv.push("--".to_string());
+        argv.push(script);
+        argv
+    }
+
+    /// Rewrite `params` so the command runs on the remote host, in
+    /// `params.cwd` there. The local `ssh` process keeps the command's
+    /// environment, which `shell_environment_policy` already filtered, plus
+    /// the agent socket it authenticates with.
+    pub(crate) fn wrap_exec_params(&self, params: ExecParams) -> ExecParams {
+        let script = format!(
+            "cd {} && {}",
+            quote(&params.cwd.to_string_lossy()),
+            params
+                .command
+                .iter()
+                .map(String::as_str)
+                .map(quote)
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+        let mut env = params.env;
+        if let Ok(socket) = std::env::var(SSH_AUTH_SOCK) {
+            env.insert(SSH_AUTH_SOCK.to_string(), socket);
+        }
+        ExecParams {
+            command: self.ssh_argv(script),
+            cwd: std::env::temp_dir(),
+            env,
+            ..params
+        }
+    }
+
+    /// `ssh` command that runs `script` in the remote login shell, with
+    /// nothing on its stdin.
+    pub(crate) fn command(&self, script: String) -> Command {
+        let mut command = ssh_command(&self.ssh_argv(script));
+        command.stdin(Stdio::null());
+        command
+    }
+
+    /// Read a remote file as UTF-8.
+    pub(crate) async fn read_file(&self, path: &Path) -> std::io::Result<String> {
+        let output = self.command(cat_script(path)).output().await?;
+        if !output.status.success() {
+            return Err(read_error(&output.stderr));
+        }
+        String::from_utf8(output.stdout)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// Copy a remote file into a local temporary file, so that tools which
+    /// read files can work on the copy unchanged.
+    pub(crate) async fn fetch_file(&self, path: &Path) -> std::io::Result<NamedTempFile> {
+        let file = NamedTempFile::new()?;
+        let output = self
+            .command(cat_script(path))
+            .stdout(Stdio::from(file.reopen()?))
+            .stderr(Stdio::piped())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(read_error(&output.stderr));
+        }
+        Ok(file)
+    }
+
+    /// [`codex_apply_patch::maybe_parse_apply_patch_verified`] against the
+    /// remote filesystem: the files the patch updates or deletes are fetched
+    /// first, then the patch is verified against their contents.
+    pub(crate) async fn verify_apply_patch(
+        &self,
+        argv: &[String],
+        cwd: &Path,
+    ) -> MaybeApplyPatchVerified {
+        let mut contents: HashMap<PathBuf, std::io::Result<String>> = HashMap::new();
+        if let MaybeApplyPatch::Body(args) = maybe_parse_apply_patch(argv) {
+            for path in args.files_read(cwd) {
+                let read = self.read_file(&path).await;
+                contents.insert(path, read);
+            }
+        }
+        maybe_parse_apply_patch_verified_with(argv, cwd, &mut |path| {
+            contents.remove(path).unwrap_or_else(|| {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("{} was not fetched from {}", path.display(), self.host),
+                ))
+            })
+        })
+    }
+
+    /// Write the changes of a verified patch to the remote host.
+    pub(crate) async fn apply_patch(
+        &self,
+        action: &ApplyPatchAction,
+        timeout: Duration,
+    ) -> ExecToolCallOutput {
+        let start = Instant::now();
+        let (script, summary) = apply_patch_script(action);
+        let argv = self.ssh_argv("sh -s".to_string());
+        let result = tokio::time::timeout(timeout, async {
+            let mut child = ssh_command(&argv)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true)
+                .spawn()?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(script.as_bytes()).await?;
+            }
+            child.wait_with_output().await
+        })
+        .await;
+
+        let (exit_code, stdout, stderr, timed_out) = match result {
+            Ok(Ok(output)) if output.status.success() => (0, summary, String::new(), false),
+            Ok(Ok(output)) => (
+                output.status.code().unwrap_or(SSH_FAILURE_EXIT_CODE),
+                String::from_utf8_lossy(&output.stdout).into_owned(),
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+                false,
+            ),
+            Ok(Err(err)) => (
+                SSH_FAILURE_EXIT_CODE,
+                String::new(),
+                format!("failed to run ssh: {err}"),
+                false,
+            ),
+            Err(_) => (
+                SSH_FAILURE_EXIT_CODE,
+                String::new(),
+                format!("timed out applying patch on {}", self.host),
+                true,
+            ),
+        };
+        ExecToolCallOutput {
+            exit_code,
+            aggregated_output: StreamOutput::new(format!("{stdout}{stderr}")),
+            stdout: StreamOutput::new(stdout),
+            stderr: StreamOutput::new(stderr),
+            duration: start.elapsed(),
+            timed_out,
+        }
+    }
+}
+
+fn cat_script(path: &Path) -> String {
+    format!("cat -- {}", quote_path(path))
+}
+
+/// The error for a `cat` that failed with `stderr`.
+fn read_error(stderr: &[u8]) -> std::io::Error {
+    let stderr = String::from_utf8_lossy(stderr);
+    let kind = if stderr.contains("No such file") {
+        std::io::ErrorKind::NotFound
+    } else if stderr.contains("Is a directory") {
+        std::io::ErrorKind::IsADirectory
+    } else {
+        std::io::ErrorKind::Other
+    };
+    std::io::Error::new(kind, stderr.trim().to_string())
+}
+
+fn ssh_command(argv: &[String]) -> Command {
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..]);
+    command
+}
+
+/// Shell script that makes the remote files match `action`, plus the summary
+/// `apply_patch` prints on success.
+fn apply_patch_script(action: &ApplyPatchAction) -> (String, String) {
+    let mut changes: Vec<_> = action.changes().iter().collect();
+    changes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut script = String::from("set -e\n");
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    let mut deleted = Vec::new();
+    for (path, change) in changes {
+        match change {
+            ApplyPatchFileChange::Add { content } => {
+                write_file_script(&mut script, path, content);
+                added.push(path.as_path());
+            }
+            ApplyPatchFileChange::Delete { .. } => {
+                script.push_str(&format!("rm -f -- {}\n", quote_path(path)));
+                deleted.push(path.as_path());
+            }
+            ApplyPatchFileChange::Update {
+                move_path,
+                new_content,
+                ..
+            } => {
+                let destination = move_path.as_deref().unwrap_or(path);
+                write_file_script(&mut script, destination, new_content);
+                if destination != path.as_path() {
+                    script.push_str(&format!("rm -f -- {}\n", quote_path(path)));
+                }
+                modified.push(destination);
+            }
+        }
+    }
+
+    let mut summary = String::from("Success. Updated the following files:\n");
+    for (marker, paths) in [("A", added), ("M", modified), ("D", deleted)] {
+        for path in paths {
+            summary.push_str(&format!("{marker} {}\n", path.display()));
+        }
+    }
+    (script, summary)
+}
+
+/// Append commands that create `path` (and its parent directories) with
+/// exactly `content`.
+fn write_file_script(script: &mut String, path: &Path, content: &str) {
+    if let Some(parent) = path.parent() {
+        script.push_str(&format!("mkdir -p -- {}\n", quote_path(parent)));
+    }
+    let target = quote_path(path);
+    if content.is_empty() || !content.ends_with('\n') {
+        script.push_str(&format!("printf '%s' {} > {target}\n", quote(content)));
+        return;
+    }
+    // A quoted heredoc passes the content through untouched; pick a
+    // delimiter that does not occur as a line of it.
+    let mut delimiter = String::from("CODEX_PATCH_EOF");
+    while content.lines().any(|line| line == delimiter) {
+        delimiter.push('_');
+    }
+    script.push_str(&format!(
+        "cat > {target} <<'{delimiter}'\n{content}{delimiter}\n"
+    ));
+}
+
+fn quote_path(path: &Path) -> String {
+    quote(&path.to_string_lossy())
+}
+
+/// Single-quote `arg` for a POSIX shell.
+pub(crate) fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn remote() -> RemoteWorkspace {
+        RemoteWorkspace {
+            host: "deploy@staging".to_string(),
+            cwd: PathBuf::from("/srv/app"),
+            ssh_args: vec!["-p".to_string(), "2222".to_string()],
+        }
+    }
+
+    #[test]
+    fn wraps_command_in_ssh() {
+        let params = ExecParams {
+            command: vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "echo 'hi' && ls".to_string(),
+            ],
+            cwd: PathBuf::from("/srv/app/src"),
+            timeout_ms: Some(1000),
+            env: HashMap::from([("PATH".to_string(), "/usr/bin".to_string())]),
+            with_escalated_permissions: None,
+            justification: None,
+        };
+
+        let wrapped = remote().wrap_exec_params(params);
+
+        assert_eq!(
+            wrapped.command,
+            vec![
+                "ssh",
+                "-p",
+                "2222",
+                "-T",
+                "-o",
+                "BatchMode=yes",
+                "deploy@staging",
+                "--",
+                r#"cd '/srv/app/src' && 'bash' '-lc' 'echo '\''hi'\'' && ls'"#,
+            ]
+        );
+        assert_eq!(wrapped.timeout_ms, Some(1000));
+        // Only the policy-filtered environment reaches `ssh`.
+        let mut vars: Vec<&str> = wrapped
+            .env
+            .keys()
+            .map(String::as_str)
+            .filter(|key| *key != SSH_AUTH_SOCK)
+            .collect();
+        vars.sort_unstable();
+        assert_eq!(vars, vec!["PATH"]);
+    }
+
+    #[test]
+    fn patch_script_writes_and_removes_files() {
+        let argv = vec![
+            "apply_patch".to_string(),
+            "*** Begin Patch\n*** Add File: notes/new.txt\n+CODEX_PATCH_EOF\n*** Delete File: old.txt\n*** End Patch"
+                .to_string(),
+        ];
+        let verified =
+            maybe_parse_apply_patch_verified_with(&argv, Path::new("/srv/app"), &mut |_| {
+                Ok("old\n".to_string())
+            });
+        let MaybeApplyPatchVerified::Body(action) = verified else {
+            panic!("expected verified patch, got {verified:?}");
+        };
+
+        let (script, summary) = apply_patch_script(&action);
+
+        assert_eq!(
+            script,
+            "set -e\n\
+             mkdir -p -- '/srv/app/notes'\n\
+             cat > '/srv/app/notes/new.txt' <<'CODEX_PATCH_EOF_'\n\
+             CODEX_PATCH_EOF\n\
+             CODEX_PATCH_EOF_\n\
+             rm -f -- '/srv/app/old.txt'\n"
+        );
+        assert_eq!(
+            summary,
+            "Success. Updated the following files:\nA /srv/app/notes/new.txt\nD /srv/app/old.txt\n"
+        );
+    }
+}
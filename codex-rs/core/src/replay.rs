@@ -0,0 +1,531 @@
+//! Record and replay of sessions, for reproducing bugs in the core state
+//! machine deterministically.
+//!
+//! Recording appends every model response (the events the client produced
+//! for one request, errors included) and the result of every shell command
+//! to a JSONL file. Replaying serves the model responses from that file in
+//! order and the command results by call id, so the session runs again
+//! without network or processes while turn handling, retries, compaction
+//! and tool routing take their real code paths. Patches are applied
+//! in-process to the working directory, so the files evolve as they did
+//! while recording. Errors come back as stream errors.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::error::SandboxErr;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::executor::ExecutionMode;
+use crate::executor::errors::ExecError;
+use crate::protocol::RateLimitSnapshot;
+use crate::protocol::TokenUsage;
+
+/// Whether a session is recorded to, or replayed from, a replay log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayMode {
+    Record(PathBuf),
+    Replay(PathBuf),
+}
+
+/// One line of a replay log.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "entry", rename_all = "snake_case")]
+enum ReplayEntry {
+    ModelResponse {
+        events: Vec<RecordedEvent>,
+    },
+    Exec {
+        call_id: String,
+        result: RecordedExec,
+    },
+}
+
+/// [`ResponseEvent`], or the error that ended the stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RecordedEvent {
+    Created,
+    OutputItemDone {
+        item: ResponseItem,
+    },
+    Completed {
+        response_id: String,
+        token_usage: Option<TokenUsage>,
+    },
+    OutputTextDelta {
+        delta: String,
+    },
+    ReasoningSummaryDelta {
+        delta: String,
+    },
+    ReasoningContentDelta {
+        delta: String,
+    },
+    ReasoningSummaryPartAdded,
+    WebSearchCallBegin {
+        call_id: String,
+    },
+    RateLimits {
+        snapshot: RateLimitSnapshot,
+    },
+    Error {
+        message: String,
+        retry_after_ms: Option<u64>,
+    },
+}
+
+impl RecordedEvent {
+    fn record(event: &Result<ResponseEvent>) -> Self {
+        match event {
+            Ok(ResponseEvent::Created) => Self::Created,
+            Ok(ResponseEvent::OutputItemDone(item)) => Self::OutputItemDone { item: item.clone() },
+            Ok(ResponseEvent::Completed {
+                response_id,
+                token_usage,
+            }) => Self::Completed {
+                response_id: response_id.clone(),
+                token_usage: token_usage.clone(),
+            },
+            Ok(ResponseEvent::OutputTextDelta(delta)) => Self::OutputTextDelta {
+                delta: delta.clone(),
+            },
+            Ok(ResponseEvent::ReasoningSummaryDelta(delta)) => Self::ReasoningSummaryDelta {
+                delta: delta.clone(),
+            },
+            Ok(ResponseEvent::ReasoningContentDelta(delta)) => Self::ReasoningContentDelta {
+                delta: delta.clone(),
+            },
+            Ok(ResponseEvent::ReasoningSummaryPartAdded) => Self::ReasoningSummaryPartAdded,
+            Ok(ResponseEvent::WebSearchCallBegin { call_id }) => Self::WebSearchCallBegin {
+                call_id: call_id.clone(),
+            },
+            Ok(ResponseEvent::RateLimits(snapshot)) => Self::RateLimits {
+                snapshot: snapshot.clone(),
+            },
+            Err(err) => Self::error(err),
+        }
+    }
+
+    fn error(err: &CodexErr) -> Self {
+        let retry_after_ms = match err {
+            CodexErr::Stream(_, Some(delay)) => Some(millis(*delay)),
+            _ => None,
+        };
+        let message = match err {
+            CodexErr::Stream(message, _) => message.clone(),
+            err => err.to_string(),
+        };
+        Self::Error {
+            message,
+            retry_after_ms,
+        }
+    }
+
+    fn replay(self) -> Result<ResponseEvent> {
+        Ok(match self {
+            Self::Created => ResponseEvent::Created,
+            Self::OutputItemDone { item } => ResponseEvent::OutputItemDone(item),
+            Self::Completed {
+                response_id,
+                token_usage,
+            } => ResponseEvent::Completed {
+                response_id,
+                token_usage,
+            },
+            Self::OutputTextDelta { delta } => ResponseEvent::OutputTextDelta(delta),
+            Self::ReasoningSummaryDelta { delta } => ResponseEvent::ReasoningSummaryDelta(delta),
+            Self::ReasoningContentDelta { delta } => ResponseEvent::ReasoningContentDelta(delta),
+            Self::ReasoningSummaryPartAdded => ResponseEvent::ReasoningSummaryPartAdded,
+            Self::WebSearchCallBegin { call_id } => ResponseEvent::WebSearchCallBegin { call_id },
+            Self::RateLimits { snapshot } => ResponseEvent::RateLimits(snapshot),
+            Self::Error {
+                message,
+                retry_after_ms,
+            } => {
+                return Err(CodexErr::Stream(
+                    message,
+                    retry_after_ms.map(Duration::from_millis),
+                ));
+            }
+        })
+    }
+}
+
+/// The outcome of a shell command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum RecordedExec {
+    Output(RecordedOutput),
+    TimedOut(RecordedOutput),
+    /// Refused before running, e.g. denied by the user.
+    Rejected {
+        message: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedOutput {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+    aggregated_output: String,
+    duration_ms: u64,
+    timed_out: bool,
+}
+
+impl RecordedOutput {
+    fn record(output: &ExecToolCallOutput) -> Self {
+        Self {
+            exit_code: output.exit_code,
+            stdout: output.stdout.text.clone(),
+            stderr: output.stderr.text.clone(),
+            aggregated_output: output.aggregated_output.text.clone(),
+            duration_ms: millis(output.duration),
+            timed_out: output.timed_out,
+        }
+    }
+
+    fn replay(self) -> ExecToolCallOutput {
+        ExecToolCallOutput {
+            exit_code: self.exit_code,
+            stdout: StreamOutput::new(self.stdout),
+            stderr: StreamOutput::new(self.stderr),
+            aggregated_output: StreamOutput::new(self.aggregated_output),
+            duration: Duration::from_millis(self.duration_ms),
+            timed_out: self.timed_out,
+        }
+    }
+}
+
+impl RecordedExec {
+    fn record(result: &std::result::Result<ExecToolCallOutput, ExecError>) -> Self {
+        match result {
+            Ok(output) => Self::Output(RecordedOutput::record(output)),
+            Err(ExecError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output }))) => {
+                Self::TimedOut(RecordedOutput::record(output))
+            }
+            Err(ExecError::Function(err)) => Self::Rejected {
+                message: err.to_string(),
+            },
+            Err(ExecError::Codex(err)) => Self::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+
+    fn replay(self) -> std::result::Result<ExecToolCallOutput, ExecError> {
+        match self {
+            Self::Output(output) => Ok(output.replay()),
+            Self::TimedOut(output) => Err(CodexErr::Sandbox(SandboxErr::Timeout {
+                output: Box::new(output.replay()),
+            })
+            .into()),
+            Self::Rejected { message } => Err(ExecError::rejection(message)),
+            Self::Error { message } => Err(CodexErr::Io(std::io::Error::other(message)).into()),
+        }
+    }
+}
+
+/// The replay side of a session, held by its services.
+pub(crate) enum Replay {
+    Record(Arc<ReplayRecorder>),
+    Replay(ReplayLog),
+}
+
+impl Replay {
+    pub(crate) fn open(mode: &ReplayMode) -> std::io::Result<Self> {
+        match mode {
+            ReplayMode::Record(path) => Ok(Self::Record(Arc::new(ReplayRecorder::create(path)?))),
+            ReplayMode::Replay(path) => Ok(Self::Replay(ReplayLog::load(path)?)),
+        }
+    }
+}
+
+/// Appends the entries of a session being recorded.
+pub(crate) struct ReplayRecorder {
+    file: Mutex<File>,
+}
+
+impl ReplayRecorder {
+    fn create(path: &Path) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    fn write(&self, entry: &ReplayEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("failed to serialize replay entry: {err}");
+                return;
+            }
+        };
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Err(err) = writeln!(file, "{line}") {
+            warn!("failed to write replay entry: {err}");
+        }
+    }
+
+    /// Forward `stream`, recording its events once it ends.
+    pub(crate) fn record_stream(self: &Arc<Self>, mut stream: ResponseStream) -> ResponseStream {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        let recorder = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = stream.next().await {
+                let last = matches!(event, Ok(ResponseEvent::Completed { .. }) | Err(_));
+                events.push(RecordedEvent::record(&event));
+                if last {
+                    // Written before the turn sees the end of the response,
+                    // so the log is complete once the turn is.
+                    recorder.write(&ReplayEntry::ModelResponse {
+                        events: std::mem::take(&mut events),
+                    });
+                    let _ = tx_event.send(event).await;
+                    return;
+                }
+                if tx_event.send(event).await.is_err() {
+                    break;
+                }
+            }
+            recorder.write(&ReplayEntry::ModelResponse { events });
+        });
+        ResponseStream { rx_event }
+    }
+
+    /// Record a request that failed before streaming anything.
+    pub(crate) fn record_request_error(&self, err: &CodexErr) {
+        self.write(&ReplayEntry::ModelResponse {
+            events: vec![RecordedEvent::error(err)],
+        });
+    }
+
+    pub(crate) fn record_exec(
+        &self,
+        call_id: &str,
+        result: &std::result::Result<ExecToolCallOutput, ExecError>,
+    ) {
+        self.write(&ReplayEntry::Exec {
+            call_id: call_id.to_string(),
+            result: RecordedExec::record(result),
+        });
+    }
+}
+
+/// A recorded session being served back.
+pub(crate) struct ReplayLog {
+    responses: Mutex<VecDeque<Vec<RecordedEvent>>>,
+    execs: Mutex<HashMap<String, VecDeque<RecordedExec>>>,
+}
+
+impl ReplayLog {
+    fn load(path: &Path) -> std::io::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::parse(&contents)
+    }
+
+    fn parse(contents: &str) -> std::io::Result<Self> {
+        let mut responses = VecDeque::new();
+        let mut execs: HashMap<String, VecDeque<RecordedExec>> = HashMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: ReplayEntry = serde_json::from_str(line).map_err(|err| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("replay log line {}: {err}", index + 1),
+                )
+            })?;
+            match entry {
+                ReplayEntry::ModelResponse { events } => responses.push_back(events),
+                ReplayEntry::Exec { call_id, result } => {
+                    execs.entry(call_id).or_default().push_back(result);
+                }
+            }
+        }
+        Ok(Self {
+            responses: Mutex::new(responses),
+            execs: Mutex::new(execs),
+        })
+    }
+
+    /// The next recorded model response, in recording order.
+    pub(crate) fn next_response(&self) -> Result<ResponseStream> {
+        let events = self
+            .responses
+            .lock()
+            .ok()
+            .and_then(|mut responses| responses.pop_front())
+            .ok_or_else(|| {
+                CodexErr::Fatal("the replay log has no more model responses".to_string())
+            })?;
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(events.len().max(1));
+        for event in events {
+            // The channel holds every event, so sending cannot fail.
+            let _ = tx_event.try_send(event.replay());
+        }
+        Ok(ResponseStream { rx_event })
+    }
+
+    /// The recorded result of the command with `call_id`; patches are
+    /// applied to the working directory instead.
+    pub(crate) fn exec_result(
+        &self,
+        call_id: &str,
+        mode: &ExecutionMode,
+    ) -> std::result::Result<ExecToolCallOutput, ExecError> {
+        let recorded = self
+            .execs
+            .lock()
+            .ok()
+            .and_then(|mut execs| execs.get_mut(call_id).and_then(VecDeque::pop_front));
+        match (mode, recorded) {
+            // A patch the user declined stays declined.
+            (
+                ExecutionMode::ApplyPatch(_),
+                Some(recorded @ (RecordedExec::Rejected { .. } | RecordedExec::Error { .. })),
+            ) => recorded.replay(),
+            (ExecutionMode::ApplyPatch(exec), _) => Ok(apply_patch_in_process(&exec.action)),
+            (_, Some(recorded)) => recorded.replay(),
+            (_, None) => Err(ExecError::rejection(format!(
+                "the replay log has no result for call {call_id}"
+            ))),
+        }
+    }
+}
+
+/// Write the verified changes of `action` and summarize them the way
+/// `apply_patch` does.
+fn apply_patch_in_process(action: &ApplyPatchAction) -> ExecToolCallOutput {
+    let mut changes: Vec<_> = action.changes().iter().collect();
+    changes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut summary = String::from("Success. Updated the following files:\n");
+    let mut failure = None;
+    for (path, change) in changes {
+        let (marker, shown, result) = match change {
+            ApplyPatchFileChange::Add { content } => ("A", path, write_file(path, content)),
+            ApplyPatchFileChange::Delete { .. } => ("D", path, std::fs::remove_file(path)),
+            ApplyPatchFileChange::Update {
+                move_path,
+                new_content,
+                ..
+            } => {
+                let destination = move_path.as_ref().unwrap_or(path);
+                let mut result = write_file(destination, new_content);
+                if result.is_ok() && destination != path {
+                    result = std::fs::remove_file(path);
+                }
+                ("M", destination, result)
+            }
+        };
+        if let Err(err) = result {
+            failure = Some(format!("failed to update {}: {err}\n", path.display()));
+            break;
+        }
+        summary.push_str(&format!("{marker} {}\n", shown.display()));
+    }
+
+    let (exit_code, stdout, stderr) = match failure {
+        Some(message) => (1, String::new(), message),
+        None => (0, summary, String::new()),
+    };
+    ExecToolCallOutput {
+        exit_code,
+        aggregated_output: StreamOutput::new(format!("{stdout}{stderr}")),
+        stdout: StreamOutput::new(stdout),
+        stderr: StreamOutput::new(stderr),
+        duration: Duration::ZERO,
+        timed_out: false,
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn replay_log_serves_responses_in_order_and_commands_by_call_id() {
+        let log = [
+            r#"{"entry":"model_response","events":[{"type":"created"},{"type":"error","message":"disconnected","retry_after_ms":null}]}"#,
+            r#"{"entry":"exec","call_id":"b","result":{"status":"rejected","message":"denied"}}"#,
+            r#"{"entry":"exec","call_id":"a","result":{"status":"output","exit_code":0,"stdout":"hi\n","stderr":"","aggregated_output":"hi\n","duration_ms":3,"timed_out":false}}"#,
+            r#"{"entry":"model_response","events":[{"type":"completed","response_id":"resp-2","token_usage":null}]}"#,
+        ]
+        .join("\n");
+        let log = ReplayLog::parse(&log).expect("log parses");
+
+        let mut first = log.next_response().expect("first response");
+        let mut first_events = Vec::new();
+        while let Some(event) = first.next().await {
+            first_events.push(event);
+        }
+        assert!(matches!(first_events[0], Ok(ResponseEvent::Created)));
+        assert!(
+            matches!(&first_events[1], Err(CodexErr::Stream(message, None)) if message == "disconnected")
+        );
+
+        let output = log
+            .exec_result("a", &ExecutionMode::Shell)
+            .expect("recorded output");
+        assert_eq!(output.stdout.text, "hi\n");
+        assert_eq!(output.duration, Duration::from_millis(3));
+        assert!(matches!(
+            log.exec_result("b", &ExecutionMode::Shell),
+            Err(ExecError::Function(_))
+        ));
+        assert!(log.exec_result("a", &ExecutionMode::Shell).is_err());
+
+        assert!(log.next_response().is_ok());
+        assert!(matches!(log.next_response(), Err(CodexErr::Fatal(_))));
+    }
+}
@@ -1,4 +1,10 @@
+use std::path::Path;
+
 use crate::protocol::ReviewFinding;
+use crate::protocol::ReviewRequest;
+use crate::sarif::SarifLevel;
+use crate::sarif::SarifResult;
+use crate::sarif::SarifRule;
 
 // Note: We keep this module UI-agnostic. It returns plain strings that
 // higher layers (e.g., TUI) may style as needed.
@@ -53,3 +59,130 @@ pub fn format_review_findings_block(
 
     lines.join("\n")
 }
+
+/// Review of the staged, unstaged and untracked changes.
+pub fn uncommitted_changes_review_request() -> ReviewRequest {
+    ReviewRequest {
+        prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
+        user_facing_hint: "current changes".to_string(),
+    }
+}
+
+/// Review of what the current branch would merge into `branch`.
+pub fn base_branch_review_request(branch: &str) -> ReviewRequest {
+    ReviewRequest {
+        prompt: format!(
+            "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
+        ),
+        user_facing_hint: format!("changes against '{branch}'"),
+    }
+}
+
+/// Review of the changes introduced by commit `sha`.
+pub fn commit_review_request(sha: &str, subject: Option<&str>) -> ReviewRequest {
+    let short = sha.chars().take(7).collect::<String>();
+    let prompt = match subject {
+        Some(subject) => format!(
+            "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
+        ),
+        None => format!(
+            "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings."
+        ),
+    };
+    ReviewRequest {
+        prompt,
+        user_facing_hint: format!("commit {short}"),
+    }
+}
+
+/// SARIF rules and results for review findings, one rule per priority
+/// (`codex-review/P0` to `codex-review/P3`). Paths are made relative to
+/// `root` when they are inside it. A fenced ```` ```suggestion ```` block in
+/// the body becomes the suggested fix.
+pub fn review_findings_to_sarif(
+    findings: &[ReviewFinding],
+    root: &Path,
+) -> (Vec<SarifRule>, Vec<SarifResult>) {
+    let mut rules: Vec<SarifRule> = Vec::new();
+    let mut results = Vec::with_capacity(findings.len());
+    for finding in findings {
+        let priority = finding.priority.clamp(0, 3);
+        let rule_id = format!("codex-review/P{priority}");
+        if !rules.iter().any(|rule| rule.id == rule_id) {
+            rules.push(SarifRule {
+                id: rule_id.clone(),
+                name: format!("P{priority} review finding"),
+                cwe: None,
+                security_severity: None,
+            });
+        }
+        let location = &finding.code_location;
+        let path = location
+            .absolute_file_path
+            .strip_prefix(root)
+            .unwrap_or(&location.absolute_file_path);
+        results.push(SarifResult {
+            rule_id,
+            level: match priority {
+                0 | 1 => SarifLevel::Error,
+                2 => SarifLevel::Warning,
+                _ => SarifLevel::Note,
+            },
+            message: format!("{}: {}", finding.title, finding.body.trim()),
+            path: path.to_path_buf(),
+            start_line: location.line_range.start,
+            end_line: location.line_range.end,
+            suggested_fix: suggestion_block(&finding.body),
+        });
+    }
+    rules.sort_by(|a, b| a.id.cmp(&b.id));
+    (rules, results)
+}
+
+/// Contents of the first ```` ```suggestion ```` block of `body`.
+fn suggestion_block(body: &str) -> Option<String> {
+    let mut lines = body.lines();
+    lines.find(|line| line.trim() == "```suggestion")?;
+    let suggestion: Vec<&str> = lines.take_while(|line| line.trim() != "```").collect();
+    Some(suggestion.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn review_findings_become_sarif_results_per_priority() {
+        let finding = |priority: i32, body: &str| ReviewFinding {
+            title: format!("[P{priority}] Finding"),
+            body: body.to_string(),
+            confidence_score: 0.8,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 10, end: 12 },
+            },
+        };
+        let findings = vec![
+            finding(2, "Off by one.\n\n```suggestion\nfor i in 0..len {\n```\n"),
+            finding(0, "Panics on empty input."),
+        ];
+
+        let (rules, results) = review_findings_to_sarif(&findings, Path::new("/repo"));
+
+        let rule_ids: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(rule_ids, vec!["codex-review/P0", "codex-review/P2"]);
+        assert_eq!(results[0].level, SarifLevel::Warning);
+        assert_eq!(results[0].path, PathBuf::from("src/lib.rs"));
+        assert_eq!(
+            results[0].suggested_fix.as_deref(),
+            Some("for i in 0..len {")
+        );
+        assert_eq!(results[1].level, SarifLevel::Error);
+        assert_eq!(results[1].suggested_fix, None);
+    }
+}
@@ -0,0 +1,113 @@
+//! Environment snapshot written into the rollout header so a session's
+//! conditions (OS, toolchains, repository state, configuration) can be
+//! reconstructed when it is resumed or inspected later.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Stdio;
+
+use codex_protocol::protocol::EnvironmentSnapshot;
+use futures::future::join_all;
+use sha1::Digest;
+use sha1::Sha1;
+use tokio::process::Command;
+use tokio::time::Duration;
+use tokio::time::timeout;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::git_info::git_worktree_dirty;
+
+/// Toolchains probed at session start, with the arguments that print their
+/// version.
+const TOOLCHAIN_PROBES: &[(&str, &[&str])] = &[
+    ("rustc", &["--version"]),
+    ("node", &["--version"]),
+    ("python3", &["--version"]),
+    ("go", &["version"]),
+];
+
+/// Timeout for each version probe so a broken shim cannot stall the writer.
+const TOOLCHAIN_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Length of the hex config hash kept in the snapshot.
+const CONFIG_HASH_LEN: usize = 12;
+
+pub(crate) async fn collect_environment_snapshot(
+    cwd: &Path,
+    config_hash: Option<String>,
+    project_env: Vec<String>,
+) -> EnvironmentSnapshot {
+    let info = os_info::get();
+    let probes = TOOLCHAIN_PROBES
+        .iter()
+        .map(|(program, args)| toolchain_version(program, args, cwd));
+    let (git_dirty, versions) = tokio::join!(git_worktree_dirty(cwd), join_all(probes));
+
+    let toolchains: BTreeMap<String, String> = TOOLCHAIN_PROBES
+        .iter()
+        .zip(versions)
+        .filter_map(|((program, _), version)| Some(((*program).to_string(), version?)))
+        .collect();
+
+    EnvironmentSnapshot {
+        os: format!("{} {}", info.os_type(), info.version()),
+        arch: std::env::consts::ARCH.to_string(),
+        git_dirty,
+        toolchains,
+        config_hash,
+        project_env,
+    }
+}
+
+/// Short SHA-1 of `config.toml` under `codex_home`, or `None` when there is
+/// no config file.
+pub(crate) fn config_hash(codex_home: &Path) -> Option<String> {
+    let contents = std::fs::read(codex_home.join(CONFIG_TOML_FILE)).ok()?;
+    let digest = format!("{:x}", Sha1::digest(&contents));
+    Some(digest[..CONFIG_HASH_LEN].to_string())
+}
+
+async fn toolchain_version(program: &str, args: &[&str], cwd: &Path) -> Option<String> {
+    let output = timeout(
+        TOOLCHAIN_PROBE_TIMEOUT,
+        Command::new(program)
+            .args(args)
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn config_hash_tracks_config_contents() {
+        let codex_home = TempDir::new().expect("tempdir");
+        assert_eq!(config_hash(codex_home.path()), None);
+
+        let config_path = codex_home.path().join(CONFIG_TOML_FILE);
+        std::fs::write(&config_path, "model = \"o3\"\n").expect("write config");
+        let first = config_hash(codex_home.path()).expect("hash");
+        assert_eq!(first.len(), CONFIG_HASH_LEN);
+        assert_eq!(config_hash(codex_home.path()), Some(first.clone()));
+
+        std::fs::write(&config_path, "model = \"gpt-5\"\n").expect("write config");
+        assert_ne!(config_hash(codex_home.path()), Some(first));
+    }
+}
@@ -375,7 +375,7 @@ async fn read_head_and_tail(
             RolloutItem::TurnContext(_) => {
                 // Not included in `head`; skip.
             }
-            RolloutItem::Compacted(_) => {
+            RolloutItem::Compacted(_) | RolloutItem::PinnedContext(_) => {
                 // Not included in `head`; skip.
             }
             RolloutItem::EventMsg(ev) => {
@@ -463,7 +463,9 @@ fn collect_last_response_values(
         }
         let parsed: serde_json::Result<RolloutLine> = serde_json::from_str(trimmed);
         let Ok(rollout_line) = parsed else { continue };
-        let RolloutLine { timestamp, item } = rollout_line;
+        let RolloutLine {
+            timestamp, item, ..
+        } = rollout_line;
         if let RolloutItem::ResponseItem(item) = item
             && let Ok(val) = serde_json::to_value(&item)
         {
@@ -504,7 +506,8 @@ pub async fn find_conversation_path_by_id_str(
     #[allow(clippy::unwrap_used)]
     let threads = NonZero::new(2).unwrap();
     let cancel = Arc::new(AtomicBool::new(false));
-    let exclude: Vec<String> = Vec::new();
+    // Skip the `.lock` files that sit beside rollouts open in some process.
+    let exclude = vec!["*.lock".to_string()];
     let compute_indices = false;
 
     let results = file_search::run(
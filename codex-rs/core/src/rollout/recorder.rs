@@ -5,12 +5,16 @@ use std::fs::{self};
 use std::io::Error as IoError;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use codex_protocol::ConversationId;
 use serde_json::Value;
 use time::OffsetDateTime;
 use time::format_description::FormatItem;
 use time::macros::format_description;
+use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::mpsc::{self};
@@ -19,12 +23,18 @@ use tracing::info;
 use tracing::warn;
 
 use super::SESSIONS_SUBDIR;
+use super::environment::collect_environment_snapshot;
+use super::environment::config_hash;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
 use super::policy::is_persisted_response_item;
+use super::recovery::Recovery;
+use super::recovery::recover_rollout;
 use crate::config::Config;
 use crate::default_client::originator;
+use crate::file_lock::SessionLock;
+use crate::file_lock::lock_session;
 use crate::git_info::collect_git_info;
 use codex_protocol::protocol::InitialHistory;
 use codex_protocol::protocol::ResumedHistory;
@@ -35,7 +45,10 @@ use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
 
 /// Records all [`ResponseItem`]s for a session and flushes them to disk after
-/// every update.
+/// every update. Writes are fsynced at least every [`SYNC_INTERVAL`] and on
+/// flush/shutdown, so at most the last moments of a session are lost on a
+/// power failure; a record torn by a crash is repaired when the session is
+/// resumed.
 ///
 /// Rollouts are recorded as JSONL and can be inspected with tools such as:
 ///
@@ -105,7 +118,7 @@ impl RolloutRecorder {
     /// cannot be created or the rollout file cannot be opened we return the
     /// error so the caller can decide whether to disable persistence.
     pub async fn new(config: &Config, params: RolloutRecorderParams) -> std::io::Result<Self> {
-        let (file, rollout_path, meta) = match params {
+        let (file, rollout_path, session_lock, meta, elapsed_base) = match params {
             RolloutRecorderParams::Create {
                 conversation_id,
                 instructions,
@@ -117,6 +130,7 @@ impl RolloutRecorder {
                     conversation_id: session_id,
                     timestamp,
                 } = create_log_file(config, conversation_id)?;
+                let session_lock = lock_session(&path)?;
 
                 let timestamp_format: &[FormatItem] = format_description!(
                     "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
@@ -129,6 +143,7 @@ impl RolloutRecorder {
                 (
                     tokio::fs::File::from_std(file),
                     path,
+                    session_lock,
                     Some(SessionMeta {
                         id: session_id,
                         timestamp,
@@ -138,20 +153,42 @@ impl RolloutRecorder {
                         instructions,
                         source,
                     }),
+                    Duration::ZERO,
+                )
+            }
+            RolloutRecorderParams::Resume { path } => {
+                // Claim the session before touching the file so another Codex
+                // process cannot be appending to it at the same time.
+                let session_lock = lock_session(&path)?;
+                match recover_rollout(&path) {
+                    Ok(Recovery::Clean) => {}
+                    Ok(recovery) => warn!("repaired torn last record in {path:?}: {recovery:?}"),
+                    Err(e) => warn!("failed to check {path:?} for a torn last record: {e}"),
+                }
+                // Continue the monotonic clock from where the previous run left
+                // off so elapsed times keep increasing across resumes.
+                let elapsed_base = last_elapsed(&path).await;
+                (
+                    tokio::fs::OpenOptions::new()
+                        .append(true)
+                        .open(&path)
+                        .await?,
+                    path,
+                    session_lock,
+                    None,
+                    elapsed_base,
                 )
             }
-            RolloutRecorderParams::Resume { path } => (
-                tokio::fs::OpenOptions::new()
-                    .append(true)
-                    .open(&path)
-                    .await?,
-                path,
-                None,
-            ),
         };
 
-        // Clone the cwd for the spawned task to collect git info asynchronously
-        let cwd = config.cwd.clone();
+        // Capture what the spawned task needs to collect git info and the
+        // environment snapshot asynchronously
+        let header = meta.map(|meta| PendingHeader {
+            meta,
+            cwd: config.cwd.clone(),
+            config_hash: config_hash(&config.codex_home),
+            project_env: config.project_env_vars.clone(),
+        });
 
         // A reasonably-sized bounded channel. If the buffer fills up the send
         // future will yield, which is fine – we only need to ensure we do not
@@ -161,7 +198,7 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        tokio::task::spawn(rollout_writer(file, session_lock, rx, header, elapsed_base));
 
         Ok(Self { tx, rollout_path })
     }
@@ -237,6 +274,9 @@ impl RolloutRecorder {
                     RolloutItem::TurnContext(item) => {
                         items.push(RolloutItem::TurnContext(item));
                     }
+                    RolloutItem::PinnedContext(item) => {
+                        items.push(RolloutItem::PinnedContext(item));
+                    }
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
@@ -267,6 +307,22 @@ impl RolloutRecorder {
         }))
     }
 
+    /// Read the session header (the first `SessionMeta` line) of a rollout.
+    pub async fn read_session_meta(path: &Path) -> std::io::Result<SessionMetaLine> {
+        let file = tokio::fs::File::open(path).await?;
+        let mut lines = tokio::io::BufReader::new(file).lines();
+        while let Some(line) = lines.next_line().await? {
+            if let Ok(RolloutLine {
+                item: RolloutItem::SessionMeta(meta_line),
+                ..
+            }) = serde_json::from_str::<RolloutLine>(&line)
+            {
+                return Ok(meta_line);
+            }
+        }
+        Err(IoError::other("rollout has no session metadata"))
+    }
+
     pub(crate) fn get_rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
@@ -308,7 +364,7 @@ fn create_log_file(
     // Resolve ~/.codex/sessions/YYYY/MM/DD and create it if missing.
     let timestamp = OffsetDateTime::now_local()
         .map_err(|e| IoError::other(format!("failed to get local time: {e}")))?;
-    let mut dir = config.codex_home.clone();
+    let mut dir = config.state_home.path.clone();
     dir.push(SESSIONS_SUBDIR);
     dir.push(timestamp.year().to_string());
     dir.push(format!("{:02}", u8::from(timestamp.month())));
@@ -339,79 +395,177 @@ fn create_log_file(
     })
 }
 
+/// Header of a new rollout, written by the writer task before anything else.
+struct PendingHeader {
+    meta: SessionMeta,
+    cwd: PathBuf,
+    config_hash: Option<String>,
+    /// Variables loaded from the project's `.env`/`.envrc`.
+    project_env: Vec<String>,
+}
+
 async fn rollout_writer(
     file: tokio::fs::File,
+    // Held until the writer stops so the session stays claimed by this process.
+    _session_lock: Arc<SessionLock>,
     mut rx: mpsc::Receiver<RolloutCmd>,
-    mut meta: Option<SessionMeta>,
-    cwd: std::path::PathBuf,
+    header: Option<PendingHeader>,
+    elapsed_base: Duration,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
-
-    // If we have a meta, collect git info asynchronously and write meta first
-    if let Some(session_meta) = meta.take() {
-        let git_info = collect_git_info(&cwd).await;
+    let mut writer = JsonlWriter {
+        file,
+        started_at: Instant::now(),
+        elapsed_base,
+        unsynced: false,
+        last_sync: Instant::now(),
+    };
+
+    // If we have a meta, collect git info and the environment snapshot
+    // asynchronously and write meta first
+    if let Some(PendingHeader {
+        meta,
+        cwd,
+        config_hash,
+        project_env,
+    }) = header
+    {
+        let (git_info, environment) = tokio::join!(
+            collect_git_info(&cwd),
+            collect_environment_snapshot(&cwd, config_hash, project_env)
+        );
         let session_meta_line = SessionMetaLine {
-            meta: session_meta,
+            meta,
             git: git_info,
+            environment: Some(environment),
         };
 
         // Write the SessionMeta as the first item in the file, wrapped in a rollout line
         writer
-            .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
+            .write_rollout_items(vec![RolloutItem::SessionMeta(session_meta_line)])
             .await?;
+        writer.sync().await?;
     }
 
+    // Catch writes that are followed by a quiet period.
+    let mut sync_ticker = tokio::time::interval(SYNC_INTERVAL);
+    sync_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
     // Process rollout commands
-    while let Some(cmd) = rx.recv().await {
+    loop {
+        let cmd = tokio::select! {
+            cmd = rx.recv() => cmd,
+            _ = sync_ticker.tick() => {
+                writer.sync().await?;
+                continue;
+            }
+        };
+        let Some(cmd) = cmd else {
+            break;
+        };
         match cmd {
             RolloutCmd::AddItems(items) => {
-                for item in items {
-                    if is_persisted_response_item(&item) {
-                        writer.write_rollout_item(item).await?;
-                    }
-                }
+                writer
+                    .write_rollout_items(
+                        items
+                            .into_iter()
+                            .filter(is_persisted_response_item)
+                            .collect(),
+                    )
+                    .await?;
             }
             RolloutCmd::Flush { ack } => {
-                // Ensure underlying file is flushed and then ack.
-                if let Err(e) = writer.file.flush().await {
-                    let _ = ack.send(());
-                    return Err(e);
-                }
+                // Ensure the data is on disk and then ack.
+                let result = writer.sync().await;
                 let _ = ack.send(());
+                result?;
             }
             RolloutCmd::Shutdown { ack } => {
+                let result = writer.sync().await;
                 let _ = ack.send(());
+                result?;
             }
         }
     }
 
-    Ok(())
+    writer.sync().await
 }
 
+/// Elapsed time recorded on the last line of an existing rollout, or zero
+/// when the file has none (e.g. it predates monotonic timestamps).
+async fn last_elapsed(path: &Path) -> Duration {
+    let Ok(text) = tokio::fs::read_to_string(path).await else {
+        return Duration::ZERO;
+    };
+    text.lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .find_map(|line| line.elapsed_ms)
+        .map(Duration::from_millis)
+        .unwrap_or_default()
+}
+
+/// Longest time written records may stay without an fsync.
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Monotonic reference point for `RolloutLine::elapsed_ms`.
+    started_at: Instant,
+    elapsed_base: Duration,
+    /// Whether records were written since the last fsync.
+    unsynced: bool,
+    last_sync: Instant,
 }
 
 impl JsonlWriter {
-    async fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
+    /// Append `rollout_items` with a single write, so a batch is never
+    /// interleaved with a partially written record, and fsync when the last
+    /// sync is older than [`SYNC_INTERVAL`].
+    async fn write_rollout_items(
+        &mut self,
+        rollout_items: Vec<RolloutItem>,
+    ) -> std::io::Result<()> {
+        if rollout_items.is_empty() {
+            return Ok(());
+        }
         let timestamp_format: &[FormatItem] = format_description!(
             "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
         );
         let timestamp = OffsetDateTime::now_utc()
             .format(timestamp_format)
             .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
+        let elapsed = self.elapsed_base + self.started_at.elapsed();
+        let elapsed_ms = Some(u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX));
+
+        let mut buffer = String::new();
+        for item in rollout_items {
+            let line = RolloutLine {
+                timestamp: timestamp.clone(),
+                elapsed_ms,
+                item,
+            };
+            buffer.push_str(&serde_json::to_string(&line)?);
+            buffer.push('\n');
+        }
+        self.file.write_all(buffer.as_bytes()).await?;
+        self.file.flush().await?;
+        self.unsynced = true;
 
-        let line = RolloutLine {
-            timestamp,
-            item: rollout_item,
-        };
-        self.write_line(&line).await
+        if self.last_sync.elapsed() >= SYNC_INTERVAL {
+            self.sync().await?;
+        }
+        Ok(())
     }
-    async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
-        let mut json = serde_json::to_string(item)?;
-        json.push('\n');
-        self.file.write_all(json.as_bytes()).await?;
+
+    /// Flush written records to stable storage.
+    async fn sync(&mut self) -> std::io::Result<()> {
+        if !self.unsynced {
+            return Ok(());
+        }
         self.file.flush().await?;
+        self.file.sync_data().await?;
+        self.unsynced = false;
+        self.last_sync = Instant::now();
         Ok(())
     }
 }
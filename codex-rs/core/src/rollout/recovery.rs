@@ -0,0 +1,121 @@
+//! Repair of rollout files whose last record was cut short, e.g. by a crash
+//! or power loss while it was being written.
+//!
+//! Records are appended one JSON object per line, so damage is confined to
+//! the final line. Before a rollout is appended to again, that line is either
+//! terminated (when it is a complete record missing only its newline) or cut
+//! off and moved to a `.torn` file next to the rollout, so new records do not
+//! get glued onto the fragment.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::RolloutLine;
+
+/// Outcome of [`recover_rollout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Recovery {
+    /// The file ended with a complete record.
+    Clean,
+    /// The last record was complete but unterminated; a newline was added.
+    Terminated,
+    /// The last record was incomplete and was removed from the rollout.
+    Truncated {
+        dropped_bytes: u64,
+        /// Where the removed bytes were saved.
+        fragment_path: PathBuf,
+    },
+}
+
+/// Make sure the rollout at `path` ends with a complete, newline-terminated
+/// record.
+pub(crate) fn recover_rollout(path: &Path) -> std::io::Result<Recovery> {
+    let contents = std::fs::read(path)?;
+    if contents.last().is_none_or(|&byte| byte == b'\n') {
+        return Ok(Recovery::Clean);
+    }
+    let record_start = contents
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |newline| newline + 1);
+    let fragment = &contents[record_start..];
+
+    if serde_json::from_slice::<RolloutLine>(fragment).is_ok() {
+        let mut file = OpenOptions::new().append(true).open(path)?;
+        file.write_all(b"\n")?;
+        file.sync_data()?;
+        return Ok(Recovery::Terminated);
+    }
+
+    let mut fragment_path = path.as_os_str().to_owned();
+    fragment_path.push(".torn");
+    let fragment_path = PathBuf::from(fragment_path);
+    std::fs::write(&fragment_path, fragment)?;
+
+    let file = OpenOptions::new().write(true).open(path)?;
+    file.set_len(record_start as u64)?;
+    file.sync_data()?;
+    Ok(Recovery::Truncated {
+        dropped_bytes: fragment.len() as u64,
+        fragment_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    const RECORD: &str = r#"{"timestamp":"2025-01-01T00:00:00.000Z","type":"event_msg","payload":{"type":"agent_message","message":"hi"}}"#;
+
+    fn rollout_with(contents: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new().expect("tempdir");
+        let path = dir.path().join("rollout.jsonl");
+        std::fs::write(&path, contents).expect("write rollout");
+        (dir, path)
+    }
+
+    #[test]
+    fn leaves_complete_rollouts_alone() {
+        let contents = format!("{RECORD}\n{RECORD}\n");
+        let (_dir, path) = rollout_with(&contents);
+        assert_eq!(recover_rollout(&path).expect("recover"), Recovery::Clean);
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), contents);
+    }
+
+    #[test]
+    fn terminates_complete_last_record() {
+        let (_dir, path) = rollout_with(&format!("{RECORD}\n{RECORD}"));
+        assert_eq!(
+            recover_rollout(&path).expect("recover"),
+            Recovery::Terminated
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            format!("{RECORD}\n{RECORD}\n")
+        );
+    }
+
+    #[test]
+    fn cuts_off_torn_last_record() {
+        let torn = &RECORD[..40];
+        let (_dir, path) = rollout_with(&format!("{RECORD}\n{torn}"));
+        let recovery = recover_rollout(&path).expect("recover");
+        let fragment_path = PathBuf::from(format!("{}.torn", path.display()));
+        assert_eq!(
+            recovery,
+            Recovery::Truncated {
+                dropped_bytes: 40,
+                fragment_path: fragment_path.clone(),
+            }
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            format!("{RECORD}\n")
+        );
+        assert_eq!(std::fs::read_to_string(fragment_path).expect("read"), torn);
+    }
+}
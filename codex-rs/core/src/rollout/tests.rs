@@ -524,6 +524,7 @@ async fn test_tail_includes_last_response_items() -> Result<()> {
     let conversation_id = ConversationId::from_string(&uuid.to_string())?;
     let meta_line = RolloutLine {
         timestamp: ts.to_string(),
+        elapsed_ms: None,
         item: RolloutItem::SessionMeta(SessionMetaLine {
             meta: SessionMeta {
                 id: conversation_id,
@@ -535,12 +536,14 @@ async fn test_tail_includes_last_response_items() -> Result<()> {
                 source: SessionSource::VSCode,
             },
             git: None,
+            environment: None,
         }),
     };
     writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
 
     let user_event_line = RolloutLine {
         timestamp: ts.to_string(),
+        elapsed_ms: None,
         item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
             message: "hello".into(),
             kind: Some(InputMessageKind::Plain),
@@ -553,6 +556,7 @@ async fn test_tail_includes_last_response_items() -> Result<()> {
     for idx in 0..total_messages {
         let response_line = RolloutLine {
             timestamp: format!("{ts}-{idx:02}"),
+            elapsed_ms: None,
             item: RolloutItem::ResponseItem(ResponseItem::Message {
                 id: None,
                 role: "assistant".into(),
@@ -608,6 +612,7 @@ async fn test_tail_handles_short_sessions() -> Result<()> {
     let conversation_id = ConversationId::from_string(&uuid.to_string())?;
     let meta_line = RolloutLine {
         timestamp: ts.to_string(),
+        elapsed_ms: None,
         item: RolloutItem::SessionMeta(SessionMetaLine {
             meta: SessionMeta {
                 id: conversation_id,
@@ -619,12 +624,14 @@ async fn test_tail_handles_short_sessions() -> Result<()> {
                 source: SessionSource::VSCode,
             },
             git: None,
+            environment: None,
         }),
     };
     writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
 
     let user_event_line = RolloutLine {
         timestamp: ts.to_string(),
+        elapsed_ms: None,
         item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
             message: "hi".into(),
             kind: Some(InputMessageKind::Plain),
@@ -636,6 +643,7 @@ async fn test_tail_handles_short_sessions() -> Result<()> {
     for idx in 0..3 {
         let response_line = RolloutLine {
             timestamp: format!("{ts}-{idx:02}"),
+            elapsed_ms: None,
             item: RolloutItem::ResponseItem(ResponseItem::Message {
                 id: None,
                 role: "assistant".into(),
@@ -693,6 +701,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
     let conversation_id = ConversationId::from_string(&uuid.to_string())?;
     let meta_line = RolloutLine {
         timestamp: ts.to_string(),
+        elapsed_ms: None,
         item: RolloutItem::SessionMeta(SessionMetaLine {
             meta: SessionMeta {
                 id: conversation_id,
@@ -704,12 +713,14 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
                 source: SessionSource::VSCode,
             },
             git: None,
+            environment: None,
         }),
     };
     writeln!(file, "{}", serde_json::to_string(&meta_line)?)?;
 
     let user_event_line = RolloutLine {
         timestamp: ts.to_string(),
+        elapsed_ms: None,
         item: RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
             message: "hello".into(),
             kind: Some(InputMessageKind::Plain),
@@ -721,6 +732,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
     for idx in 0..4 {
         let response_line = RolloutLine {
             timestamp: format!("{ts}-{idx:02}"),
+            elapsed_ms: None,
             item: RolloutItem::ResponseItem(ResponseItem::Message {
                 id: None,
                 role: "assistant".into(),
@@ -734,6 +746,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
 
     let compacted_line = RolloutLine {
         timestamp: format!("{ts}-compacted"),
+        elapsed_ms: None,
         item: RolloutItem::Compacted(CompactedItem {
             message: "compacted".into(),
         }),
@@ -742,6 +755,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
 
     let shutdown_event = RolloutLine {
         timestamp: format!("{ts}-shutdown"),
+        elapsed_ms: None,
         item: RolloutItem::EventMsg(EventMsg::ShutdownComplete),
     };
     writeln!(file, "{}", serde_json::to_string(&shutdown_event)?)?;
@@ -0,0 +1,194 @@
+//! Minimal SARIF 2.1.0 writer, so findings can be uploaded to GitHub code
+//! scanning and other dashboards that read static analysis results.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde_json::Value;
+use serde_json::json;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SarifRule {
+    pub id: String,
+    pub name: String,
+    /// e.g. `CWE-89`; added as a `external/cwe/cwe-89` tag.
+    pub cwe: Option<String>,
+    /// Score from 0.0 to 10.0 that GitHub uses to rank security alerts;
+    /// rules with a score are also tagged `security`.
+    pub security_severity: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SarifLevel {
+    Error,
+    Warning,
+    Note,
+}
+
+impl SarifLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            SarifLevel::Error => "error",
+            SarifLevel::Warning => "warning",
+            SarifLevel::Note => "note",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub level: SarifLevel,
+    pub message: String,
+    /// Relative to the repository root.
+    pub path: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub suggested_fix: Option<String>,
+}
+
+/// A SARIF log with a single run of `tool_name`.
+pub fn sarif_log(
+    tool_name: &str,
+    tool_version: &str,
+    rules: &[SarifRule],
+    results: &[SarifResult],
+) -> Value {
+    let rules: Vec<Value> = rules.iter().map(rule_json).collect();
+    let results: Vec<Value> = results.iter().map(result_json).collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": tool_name,
+                    "version": tool_version,
+                    "informationUri": "https://github.com/openai/codex",
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+fn rule_json(rule: &SarifRule) -> Value {
+    let mut tags = Vec::new();
+    if rule.security_severity.is_some() {
+        tags.push("security".to_string());
+    }
+    let mut help_uri = None;
+    if let Some(number) = rule.cwe.as_deref().and_then(cwe_number) {
+        tags.push(format!("external/cwe/cwe-{number}"));
+        help_uri = Some(format!(
+            "https://cwe.mitre.org/data/definitions/{number}.html"
+        ));
+    }
+    let mut properties = json!({ "tags": tags });
+    if let Some(score) = &rule.security_severity {
+        properties["security-severity"] = json!(score);
+    }
+    let mut value = json!({
+        "id": rule.id,
+        "name": rule.name,
+        "shortDescription": { "text": rule.name },
+        "properties": properties,
+    });
+    if let Some(help_uri) = help_uri {
+        value["helpUri"] = json!(help_uri);
+    }
+    value
+}
+
+fn result_json(result: &SarifResult) -> Value {
+    let start_line = result.start_line.max(1);
+    let end_line = result.end_line.max(start_line);
+    let mut message = result.message.clone();
+    if let Some(fix) = &result.suggested_fix {
+        message.push_str(&format!("\n\nSuggested fix: {fix}"));
+    }
+    let mut value = json!({
+        "ruleId": result.rule_id,
+        "level": result.level.as_str(),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": artifact_uri(&result.path),
+                    "uriBaseId": "%SRCROOT%",
+                },
+                "region": { "startLine": start_line, "endLine": end_line },
+            }
+        }],
+    });
+    if let Some(fix) = &result.suggested_fix {
+        value["properties"] = json!({ "suggestedFix": fix });
+    }
+    value
+}
+
+/// `CWE-89` as `89`.
+fn cwe_number(cwe: &str) -> Option<u32> {
+    cwe.trim()
+        .to_ascii_uppercase()
+        .strip_prefix("CWE-")?
+        .parse()
+        .ok()
+}
+
+/// Forward-slash path, as SARIF URIs require.
+fn artifact_uri(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn writes_rules_and_results() {
+        let log = sarif_log(
+            "codex",
+            "1.0.0",
+            &[SarifRule {
+                id: "owasp/sql-injection".to_string(),
+                name: "SQL injection".to_string(),
+                cwe: Some("CWE-89".to_string()),
+                security_severity: Some("9.5".to_string()),
+            }],
+            &[SarifResult {
+                rule_id: "owasp/sql-injection".to_string(),
+                level: SarifLevel::Error,
+                message: "Query built with format!".to_string(),
+                path: PathBuf::from("src").join("db.rs"),
+                start_line: 40,
+                end_line: 0,
+                suggested_fix: Some("Bind the parameter.".to_string()),
+            }],
+        );
+
+        let run = &log["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["properties"],
+            json!({ "tags": ["security", "external/cwe/cwe-89"], "security-severity": "9.5" })
+        );
+        assert_eq!(
+            run["results"][0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/db.rs", "uriBaseId": "%SRCROOT%" },
+                "region": { "startLine": 40, "endLine": 40 },
+            })
+        );
+        assert_eq!(
+            run["results"][0]["message"]["text"],
+            "Query built with format!\n\nSuggested fix: Bind the parameter."
+        );
+    }
+}
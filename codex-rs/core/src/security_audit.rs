@@ -0,0 +1,449 @@
+//! Context for `codex audit-code`: curated rule packs, the prompt asking for
+//! a security review of a diff or a set of paths, and the structured
+//! findings the agent replies with.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::sarif::SarifLevel;
+use crate::sarif::SarifResult;
+use crate::sarif::SarifRule;
+
+/// A class of vulnerability the audit looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditRule {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// e.g. `CWE-89`.
+    pub cwe: &'static str,
+    /// What to look for, as told to the model.
+    pub guidance: &'static str,
+}
+
+/// A named set of rules selected with `--rules`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RulePack {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub rules: &'static [AuditRule],
+}
+
+/// Packs used when `--rules` is not given.
+pub const DEFAULT_RULE_PACKS: &[&str] = &["owasp", "secrets"];
+
+pub const RULE_PACKS: &[RulePack] = &[
+    RulePack {
+        id: "owasp",
+        description: "Common web and service vulnerabilities from the OWASP Top 10",
+        rules: &[
+            AuditRule {
+                id: "owasp/sql-injection",
+                name: "SQL injection",
+                cwe: "CWE-89",
+                guidance: "queries built by concatenating or formatting untrusted input instead of binding parameters",
+            },
+            AuditRule {
+                id: "owasp/command-injection",
+                name: "OS command injection",
+                cwe: "CWE-78",
+                guidance: "untrusted input reaching a shell, `sh -c`, `system`, `exec` or similar without strict validation",
+            },
+            AuditRule {
+                id: "owasp/xss",
+                name: "Cross-site scripting",
+                cwe: "CWE-79",
+                guidance: "untrusted input rendered into HTML or JavaScript without context-aware escaping",
+            },
+            AuditRule {
+                id: "owasp/path-traversal",
+                name: "Path traversal",
+                cwe: "CWE-22",
+                guidance: "file paths built from untrusted input without canonicalizing and checking them against a base directory",
+            },
+            AuditRule {
+                id: "owasp/access-control",
+                name: "Missing authorization",
+                cwe: "CWE-862",
+                guidance: "endpoints or operations on other users' data that do not check the caller's permissions",
+            },
+            AuditRule {
+                id: "owasp/ssrf",
+                name: "Server-side request forgery",
+                cwe: "CWE-918",
+                guidance: "outgoing requests to URLs or hosts that untrusted input controls",
+            },
+            AuditRule {
+                id: "owasp/deserialization",
+                name: "Unsafe deserialization",
+                cwe: "CWE-502",
+                guidance: "deserializing untrusted data with formats or libraries that can instantiate arbitrary types",
+            },
+        ],
+    },
+    RulePack {
+        id: "secrets",
+        description: "Credentials and keys in code, configuration and logs",
+        rules: &[
+            AuditRule {
+                id: "secrets/hardcoded-credential",
+                name: "Hard-coded credential",
+                cwe: "CWE-798",
+                guidance: "passwords, API keys, tokens or connection strings with credentials written into source or config files",
+            },
+            AuditRule {
+                id: "secrets/private-key",
+                name: "Committed private key",
+                cwe: "CWE-321",
+                guidance: "private keys, certificates with keys, or signing secrets checked into the repository",
+            },
+            AuditRule {
+                id: "secrets/logged-secret",
+                name: "Secret written to logs",
+                cwe: "CWE-532",
+                guidance: "tokens, passwords or other secrets included in log lines, error messages or telemetry",
+            },
+        ],
+    },
+    RulePack {
+        id: "crypto",
+        description: "Misuse of cryptography and TLS",
+        rules: &[
+            AuditRule {
+                id: "crypto/weak-algorithm",
+                name: "Weak cryptographic algorithm",
+                cwe: "CWE-327",
+                guidance: "MD5 or SHA-1 for security purposes, DES/RC4, ECB mode, or RSA keys shorter than 2048 bits",
+            },
+            AuditRule {
+                id: "crypto/weak-randomness",
+                name: "Predictable randomness",
+                cwe: "CWE-338",
+                guidance: "non-cryptographic random number generators used for tokens, keys, nonces or passwords",
+            },
+            AuditRule {
+                id: "crypto/tls-verification",
+                name: "Disabled certificate validation",
+                cwe: "CWE-295",
+                guidance: "TLS clients that accept invalid certificates or skip hostname verification",
+            },
+        ],
+    },
+    RulePack {
+        id: "memory",
+        description: "Memory safety in unsafe Rust, C and C++",
+        rules: &[
+            AuditRule {
+                id: "memory/out-of-bounds",
+                name: "Out-of-bounds access",
+                cwe: "CWE-787",
+                guidance: "unchecked indexing, pointer arithmetic or copies that can read or write past a buffer",
+            },
+            AuditRule {
+                id: "memory/use-after-free",
+                name: "Use after free",
+                cwe: "CWE-416",
+                guidance: "pointers or references used after the memory they point to is freed or moved",
+            },
+            AuditRule {
+                id: "memory/integer-overflow",
+                name: "Integer overflow in size computation",
+                cwe: "CWE-190",
+                guidance: "arithmetic on lengths or sizes that can wrap before an allocation or bounds check",
+            },
+        ],
+    },
+];
+
+pub fn rule_pack(id: &str) -> Option<&'static RulePack> {
+    RULE_PACKS.iter().find(|pack| pack.id == id)
+}
+
+/// The rule with `id` in any pack.
+pub fn find_rule(id: &str) -> Option<&'static AuditRule> {
+    RULE_PACKS
+        .iter()
+        .flat_map(|pack| pack.rules)
+        .find(|rule| rule.id == id)
+}
+
+/// What to audit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditScope {
+    /// Uncommitted changes, staged or not.
+    WorkingTree,
+    /// Changes of the current branch since it left `base`.
+    Branch { base: String },
+    /// The full contents of these files or directories.
+    Paths(Vec<PathBuf>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    fn sarif_level(self) -> SarifLevel {
+        match self {
+            Severity::Critical | Severity::High => SarifLevel::Error,
+            Severity::Medium => SarifLevel::Warning,
+            Severity::Low | Severity::Info => SarifLevel::Note,
+        }
+    }
+
+    /// CVSS-like score GitHub code scanning uses to rank security alerts.
+    fn security_severity(self) -> &'static str {
+        match self {
+            Severity::Critical => "9.5",
+            Severity::High => "8.0",
+            Severity::Medium => "5.5",
+            Severity::Low => "3.0",
+            Severity::Info => "0.0",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditFinding {
+    /// Id of the matching rule, or a short id of its own for issues no rule
+    /// covers.
+    pub rule_id: String,
+    pub title: String,
+    pub severity: Severity,
+    /// e.g. `CWE-89`.
+    pub cwe: Option<String>,
+    /// Path relative to the working directory.
+    pub file: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub description: String,
+    pub suggested_fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditReport {
+    /// The agent's final message, which the output schema constrains to a
+    /// report. Findings are sorted by severity, most severe first.
+    pub fn parse(message: &str) -> Result<Self, serde_json::Error> {
+        let mut report: AuditReport = serde_json::from_str(message.trim())?;
+        report.findings.sort_by(|a, b| b.severity.cmp(&a.severity));
+        Ok(report)
+    }
+
+    /// Findings with paths made relative to `cwd`.
+    pub fn relativize(&mut self, cwd: &Path) {
+        for finding in &mut self.findings {
+            if let Ok(relative) = finding.file.strip_prefix(cwd) {
+                finding.file = relative.to_path_buf();
+            }
+        }
+    }
+
+    /// Human-readable listing, one block per finding.
+    pub fn render_text(&self) -> String {
+        if self.findings.is_empty() {
+            return "No security findings.".to_string();
+        }
+        let mut out = String::new();
+        for finding in &self.findings {
+            let severity = format!("{:?}", finding.severity).to_uppercase();
+            let cwe = finding
+                .cwe
+                .as_deref()
+                .map(|cwe| format!(" ({cwe})"))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "[{severity}] {}{cwe}\n  {}:{}-{} [{}]\n  {}\n",
+                finding.title,
+                finding.file.display(),
+                finding.start_line,
+                finding.end_line,
+                finding.rule_id,
+                finding.description.trim()
+            ));
+            if let Some(fix) = finding.suggested_fix.as_deref() {
+                out.push_str(&format!("  Suggested fix: {}\n", fix.trim()));
+            }
+            out.push('\n');
+        }
+        out.trim_end().to_string()
+    }
+
+    /// SARIF rules and results for the findings.
+    pub fn to_sarif(&self) -> (Vec<SarifRule>, Vec<SarifResult>) {
+        let mut rules: Vec<SarifRule> = Vec::new();
+        for finding in &self.findings {
+            if rules.iter().any(|rule| rule.id == finding.rule_id) {
+                continue;
+            }
+            let known = find_rule(&finding.rule_id);
+            rules.push(SarifRule {
+                id: finding.rule_id.clone(),
+                name: known.map_or_else(|| finding.title.clone(), |rule| rule.name.to_string()),
+                cwe: known
+                    .map(|rule| rule.cwe.to_string())
+                    .or_else(|| finding.cwe.clone()),
+                security_severity: Some(finding.severity.security_severity().to_string()),
+            });
+        }
+        let results = self
+            .findings
+            .iter()
+            .map(|finding| SarifResult {
+                rule_id: finding.rule_id.clone(),
+                level: finding.severity.sarif_level(),
+                message: format!("{}: {}", finding.title, finding.description.trim()),
+                path: finding.file.clone(),
+                start_line: finding.start_line,
+                end_line: finding.end_line,
+                suggested_fix: finding.suggested_fix.clone(),
+            })
+            .collect();
+        (rules, results)
+    }
+}
+
+/// JSON schema of [`AuditReport`], passed as the final output schema so
+/// the reply can be parsed.
+pub fn report_schema() -> Value {
+    let nullable_string = json!({ "type": ["string", "null"] });
+    json!({
+        "type": "object",
+        "properties": {
+            "findings": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "rule_id": { "type": "string" },
+                        "title": { "type": "string" },
+                        "severity": {
+                            "type": "string",
+                            "enum": ["critical", "high", "medium", "low", "info"]
+                        },
+                        "cwe": nullable_string,
+                        "file": { "type": "string" },
+                        "start_line": { "type": "integer" },
+                        "end_line": { "type": "integer" },
+                        "description": { "type": "string" },
+                        "suggested_fix": nullable_string,
+                    },
+                    "required": [
+                        "rule_id", "title", "severity", "cwe", "file", "start_line",
+                        "end_line", "description", "suggested_fix"
+                    ],
+                    "additionalProperties": false
+                }
+            }
+        },
+        "required": ["findings"],
+        "additionalProperties": false
+    })
+}
+
+/// Prompt asking for a security review of `scope` against `packs`.
+pub fn audit_prompt(packs: &[&RulePack], scope: &AuditScope) -> String {
+    let target = match scope {
+        AuditScope::WorkingTree => {
+            "the uncommitted changes in this repository (`git diff HEAD`, plus untracked files from `git status`)"
+                .to_string()
+        }
+        AuditScope::Branch { base } => format!(
+            "the changes on this branch since it left {base} (`git diff {base}...HEAD`)"
+        ),
+        AuditScope::Paths(paths) => {
+            let paths: Vec<String> = paths
+                .iter()
+                .map(|path| format!("`{}`", path.display()))
+                .collect();
+            format!("the code in {}", paths.join(", "))
+        }
+    };
+    let mut prompt = format!(
+        "Perform a security audit of {target}. Read the code you need to understand how data flows into the code \
+         under review, but only report issues in that code. Report real, exploitable problems with concrete evidence; \
+         skip style issues, hypothetical hardening and issues in tests unless they leak real secrets. Look for the \
+         rules below; report other serious vulnerabilities too, with a short rule id of your own and their CWE.\n\n\
+         For each finding give the rule id, a short title, its severity (critical, high, medium, low or info), the CWE, \
+         the file path relative to the repository root with the first and last affected line, a description of the \
+         issue and how it can be exploited, and a suggested fix. Reply with the findings only, as JSON; reply with an \
+         empty list if you find nothing.\n\n<rules>\n"
+    );
+    for pack in packs {
+        prompt.push_str(&format!("# {}: {}\n", pack.id, pack.description));
+        for rule in pack.rules {
+            prompt.push_str(&format!(
+                "- {} ({}, {}): {}\n",
+                rule.id, rule.name, rule.cwe, rule.guidance
+            ));
+        }
+    }
+    prompt.push_str("</rules>");
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_report_and_sorts_by_severity() {
+        let message = r#"{"findings": [
+            {"rule_id": "secrets/logged-secret", "title": "Token logged", "severity": "low", "cwe": "CWE-532",
+             "file": "/repo/src/auth.rs", "start_line": 12, "end_line": 12, "description": "The token is logged.",
+             "suggested_fix": null},
+            {"rule_id": "owasp/sql-injection", "title": "SQL built with format!", "severity": "critical",
+             "cwe": "CWE-89", "file": "/repo/src/db.rs", "start_line": 40, "end_line": 42,
+             "description": "User input is formatted into the query.", "suggested_fix": "Bind the parameter."}
+        ]}"#;
+        let mut report = AuditReport::parse(message).expect("report should parse");
+        report.relativize(Path::new("/repo"));
+
+        assert_eq!(
+            report
+                .findings
+                .iter()
+                .map(|finding| (finding.rule_id.as_str(), finding.file.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("owasp/sql-injection", PathBuf::from("src/db.rs")),
+                ("secrets/logged-secret", PathBuf::from("src/auth.rs")),
+            ]
+        );
+        let (rules, results) = report.to_sarif();
+        assert_eq!(rules[0].name, "SQL injection");
+        assert_eq!(rules[0].cwe.as_deref(), Some("CWE-89"));
+        assert_eq!(results[0].level, SarifLevel::Error);
+        assert_eq!(results[1].level, SarifLevel::Note);
+    }
+
+    #[test]
+    fn prompt_lists_selected_rules() {
+        let packs = [rule_pack("secrets").expect("secrets pack")];
+        let prompt = audit_prompt(
+            &packs,
+            &AuditScope::Branch {
+                base: "main".to_string(),
+            },
+        );
+
+        assert!(prompt.contains("`git diff main...HEAD`"));
+        assert!(prompt.contains("- secrets/hardcoded-credential (Hard-coded credential, CWE-798)"));
+        assert!(!prompt.contains("owasp/"));
+    }
+}
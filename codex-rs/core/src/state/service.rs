@@ -2,8 +2,10 @@ use crate::RolloutRecorder;
 use crate::exec_command::ExecSessionManager;
 use crate::executor::Executor;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::replay::Replay;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use crate::verify_commands::VerifyCommand;
 use tokio::sync::Mutex;
 
 pub(crate) struct SessionServices {
@@ -14,5 +16,12 @@ pub(crate) struct SessionServices {
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
+    pub(crate) record_provenance: bool,
+    /// See [`crate::config::Config::structured_failures`].
+    pub(crate) structured_failures: bool,
+    pub(crate) replay: Option<Replay>,
+    /// Verify commands detected at session start; see
+    /// [`crate::verify_commands`].
+    pub(crate) verify_commands: Vec<VerifyCommand>,
     pub(crate) executor: Executor,
 }
@@ -1,11 +1,16 @@
 //! Session-wide mutable state.
 
+use std::collections::HashMap;
+
 use codex_protocol::models::ResponseItem;
 
 use crate::conversation_history::ConversationHistory;
+use crate::external_edits::ReadFileTracker;
+use crate::protocol::McpServerState;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::write_quota::WriteQuota;
 
 /// Persistent, session-scoped state previously stored directly on `Session`.
 #[derive(Default)]
@@ -13,6 +18,17 @@ pub(crate) struct SessionState {
     pub(crate) history: ConversationHistory,
     pub(crate) token_info: Option<TokenUsageInfo>,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Whether the user was already told that content was dropped to fit the
+    /// model's capabilities; the warning is only emitted once per session.
+    pub(crate) capability_warning_sent: bool,
+    /// Excerpts the user pinned in the UI; re-added verbatim after compaction.
+    pub(crate) pinned_context: Vec<String>,
+    /// Files the agent has read, to notice edits made outside the session.
+    pub(crate) read_files: ReadFileTracker,
+    /// Bytes written with `apply_patch` against `max_session_write_mb`.
+    pub(crate) write_quota: WriteQuota,
+    /// Configured MCP servers and whether they are still connected.
+    pub(crate) mcp_servers: HashMap<String, McpServerState>,
 }
 
 impl SessionState {
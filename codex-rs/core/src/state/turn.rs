@@ -10,7 +10,9 @@ use codex_protocol::models::ResponseInputItem;
 use tokio::sync::oneshot;
 
 use crate::protocol::ReviewDecision;
+use crate::protocol::TurnSummary;
 use crate::tasks::SessionTask;
+use crate::turn_diff_tracker::DiffStats;
 
 /// Metadata about the currently running turn.
 pub(crate) struct ActiveTurn {
@@ -60,7 +62,15 @@ impl ActiveTurn {
 #[derive(Default)]
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
+    /// Questions asked with the `ask_user` tool, by call id.
+    pending_questions: HashMap<String, oneshot::Sender<Option<String>>>,
     pending_input: Vec<ResponseInputItem>,
+    /// Commands the user edited before approving, by submission id, until
+    /// the approval request picks them up.
+    pending_command_edits: HashMap<String, Vec<String>>,
+    /// Edited commands by call id, until their tool result reports the edit.
+    command_edits: HashMap<String, Vec<String>>,
+    summary: TurnSummary,
 }
 
 impl TurnState {
@@ -79,15 +89,68 @@ impl TurnState {
         self.pending_approvals.remove(key)
     }
 
+    pub(crate) fn insert_pending_question(
+        &mut self,
+        call_id: String,
+        tx: oneshot::Sender<Option<String>>,
+    ) -> Option<oneshot::Sender<Option<String>>> {
+        self.pending_questions.insert(call_id, tx)
+    }
+
+    pub(crate) fn remove_pending_question(
+        &mut self,
+        call_id: &str,
+    ) -> Option<oneshot::Sender<Option<String>>> {
+        self.pending_questions.remove(call_id)
+    }
+
     pub(crate) fn clear_pending(&mut self) {
         self.pending_approvals.clear();
+        self.pending_questions.clear();
         self.pending_input.clear();
+        self.pending_command_edits.clear();
+    }
+
+    pub(crate) fn insert_pending_command_edit(&mut self, key: String, command: Vec<String>) {
+        self.pending_command_edits.insert(key, command);
+    }
+
+    /// Move the edit approved for submission `key`, if any, to `call_id`.
+    pub(crate) fn claim_command_edit(&mut self, key: &str, call_id: &str) {
+        if let Some(command) = self.pending_command_edits.remove(key) {
+            self.command_edits.insert(call_id.to_string(), command);
+        }
+    }
+
+    pub(crate) fn command_edit(&self, call_id: &str) -> Option<Vec<String>> {
+        self.command_edits.get(call_id).cloned()
+    }
+
+    pub(crate) fn take_command_edit(&mut self, call_id: &str) -> Option<Vec<String>> {
+        self.command_edits.remove(call_id)
     }
 
     pub(crate) fn push_pending_input(&mut self, input: ResponseInputItem) {
         self.pending_input.push(input);
     }
 
+    pub(crate) fn record_test_run(&mut self, passed: bool) {
+        self.summary.test_runs += 1;
+        if !passed {
+            self.summary.failed_test_runs += 1;
+        }
+    }
+
+    pub(crate) fn set_diff_stats(&mut self, stats: DiffStats) {
+        self.summary.files_changed = stats.files_changed;
+        self.summary.lines_added = stats.lines_added;
+        self.summary.lines_removed = stats.lines_removed;
+    }
+
+    pub(crate) fn summary(&self) -> Option<TurnSummary> {
+        (!self.summary.is_empty()).then(|| self.summary.clone())
+    }
+
     pub(crate) fn take_pending_input(&mut self) -> Vec<ResponseInputItem> {
         if self.pending_input.is_empty() {
             Vec::with_capacity(0)
@@ -0,0 +1,179 @@
+//! Where Codex writes the state it accumulates while running: session
+//! rollouts, logs, message history, package install manifests, keyboard
+//! macros and exported pins.
+//!
+//! State lives in `CODEX_STATE_HOME` when that is set and in `CODEX_HOME`
+//! otherwise. Some environments (Nix-managed homes, locked-down CI runners)
+//! mount those read-only; rather than failing at the first write, Codex then
+//! moves its state to a fresh temporary directory and turns off the features
+//! whose whole point is to outlive the process. The directory is created once
+//! per process and removed by [`remove_fallback_dir`] on exit.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Environment variable that points Codex's state somewhere other than
+/// `CODEX_HOME`.
+pub const CODEX_STATE_HOME_ENV_VAR: &str = "CODEX_STATE_HOME";
+
+/// Prefix of the temporary directory used when the state home is read-only.
+const FALLBACK_PREFIX: &str = "codex-state-";
+
+/// The temporary directory standing in for read-only state homes, shared by
+/// every configuration this process loads; `None` when it could not be
+/// created.
+static FALLBACK_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The directory Codex writes its state to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateHome {
+    pub path: PathBuf,
+    /// When set, `path` is a temporary directory standing in for this
+    /// read-only one; nothing written there survives the process.
+    pub read_only: Option<PathBuf>,
+}
+
+impl StateHome {
+    /// Resolve the state home for `codex_home`, falling back to a temporary
+    /// directory when the preferred one cannot be written.
+    pub fn resolve(codex_home: &Path) -> Self {
+        Self::resolve_in(preferred_dir(codex_home))
+    }
+
+    fn resolve_in(preferred: PathBuf) -> Self {
+        if is_writable(&preferred) {
+            return Self {
+                path: preferred,
+                read_only: None,
+            };
+        }
+        match fallback_dir() {
+            Some(path) => {
+                tracing::warn!(
+                    "{} is read-only; keeping state in {} for this session",
+                    preferred.display(),
+                    path.display()
+                );
+                Self {
+                    path,
+                    read_only: Some(preferred),
+                }
+            }
+            // Nowhere to fall back to: keep the preferred directory and let
+            // individual writes report their own errors.
+            None => Self {
+                path: preferred,
+                read_only: None,
+            },
+        }
+    }
+
+    /// One-line explanation for users when state is not being persisted.
+    pub fn notice(&self) -> Option<String> {
+        self.read_only.as_ref().map(|dir| {
+            format!(
+                "{} is read-only: sessions, logs and other saved state go to {} and are not kept, and message history is off.",
+                dir.display(),
+                self.path.display()
+            )
+        })
+    }
+}
+
+/// `CODEX_STATE_HOME` when set, `codex_home` otherwise. Commands that only
+/// read earlier state use this directly, read-only or not.
+pub fn preferred_dir(codex_home: &Path) -> PathBuf {
+    std::env::var(CODEX_STATE_HOME_ENV_VAR)
+        .ok()
+        .filter(|val| !val.is_empty())
+        .map_or_else(|| codex_home.to_path_buf(), PathBuf::from)
+}
+
+/// Whether Codex can create files in `dir`. A directory that does not exist
+/// yet counts as writable when its nearest existing ancestor is, since Codex
+/// creates its directories on first use.
+pub fn is_writable(dir: &Path) -> bool {
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+        return false;
+    };
+    tempfile::Builder::new()
+        .prefix(".codex-write-probe")
+        .tempfile_in(existing)
+        .is_ok()
+}
+
+fn fallback_dir() -> Option<PathBuf> {
+    FALLBACK_DIR
+        .get_or_init(
+            || match tempfile::Builder::new().prefix(FALLBACK_PREFIX).tempdir() {
+                Ok(dir) => Some(dir.keep()),
+                Err(err) => {
+                    tracing::warn!("failed to create a temporary state directory: {err}");
+                    None
+                }
+            },
+        )
+        .clone()
+}
+
+/// Remove the temporary state directory, if this process created one. Call
+/// once the process is done with its configuration.
+pub fn remove_fallback_dir() {
+    if let Some(Some(dir)) = FALLBACK_DIR.get()
+        && let Err(err) = std::fs::remove_dir_all(dir)
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        tracing::warn!("failed to remove {}: {err}", dir.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn writable_homes_are_used_as_is() {
+        let home = TempDir::new().expect("tempdir");
+        let missing = home.path().join("not").join("yet");
+        assert!(is_writable(&missing));
+        assert_eq!(
+            StateHome::resolve_in(missing.clone()),
+            StateHome {
+                path: missing,
+                read_only: None,
+            }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_homes_fall_back_to_a_temporary_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let home = TempDir::new().expect("tempdir");
+        std::fs::set_permissions(home.path(), std::fs::Permissions::from_mode(0o555))
+            .expect("chmod");
+        if is_writable(home.path()) {
+            // Running as root: permissions are not enforced.
+            return;
+        }
+
+        let state = StateHome::resolve_in(home.path().to_path_buf());
+        assert_eq!(state.read_only.as_deref(), Some(home.path()));
+        assert!(state.path.is_dir());
+        assert!(is_writable(&state.path));
+        assert!(state.notice().is_some());
+
+        // Loading the configuration again reuses the same directory.
+        let again = StateHome::resolve_in(home.path().to_path_buf());
+        assert_eq!(again, state);
+
+        remove_fallback_dir();
+        assert!(!state.path.exists());
+        std::fs::set_permissions(home.path(), std::fs::Permissions::from_mode(0o755))
+            .expect("chmod");
+    }
+}
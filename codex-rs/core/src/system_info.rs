@@ -0,0 +1,349 @@
+//! CPU load, memory, disk space, GPUs and battery of the machine Codex runs
+//! on, so the agent (through the `system_info` tool) and the user (through
+//! the status line) notice a build about to exhaust them before it fails
+//! cryptically.
+
+use std::path::Path;
+use std::process::Command;
+use std::process::Stdio;
+
+use serde::Serialize;
+
+/// Volumes with less free space than this are reported as low, whatever
+/// their size.
+const LOW_DISK_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// Volumes or memory with less than this share free are reported as low.
+const LOW_FREE_PERCENT: u64 = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SystemResources {
+    /// Load averages over 1, 5 and 15 minutes, where the platform has them.
+    pub load_average: Option<[f64; 3]>,
+    /// Logical CPUs, to put the load average in proportion.
+    pub cpus: usize,
+    /// Physical memory, where the platform reports it.
+    pub memory: Option<Capacity>,
+    /// The volume holding the workspace.
+    pub disk: Option<Capacity>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Capacity {
+    pub available: u64,
+    pub total: u64,
+}
+
+impl Capacity {
+    pub fn used_percent(&self) -> u64 {
+        if self.total == 0 {
+            return 0;
+        }
+        100 - (self.available.min(self.total) * 100 / self.total)
+    }
+
+    /// Whether less than a tenth is free.
+    pub fn is_low(&self) -> bool {
+        self.available * 100 < self.total * LOW_FREE_PERCENT
+    }
+}
+
+impl SystemResources {
+    /// Whether the workspace volume is close to full: less than 5 GiB or a
+    /// tenth of it free.
+    pub fn disk_is_low(&self) -> bool {
+        self.disk
+            .is_some_and(|disk| disk.available < LOW_DISK_BYTES || disk.is_low())
+    }
+
+    pub fn memory_is_low(&self) -> bool {
+        self.memory.is_some_and(|memory| memory.is_low())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GpuInfo {
+    pub name: String,
+    /// Utilization over the last sample period, in percent.
+    pub utilization_percent: u64,
+    pub memory: Capacity,
+}
+
+/// Sample the machine's resources; `workspace` selects the disk volume.
+pub fn collect_system_resources(workspace: &Path) -> SystemResources {
+    let disk = match (fs2::available_space(workspace), fs2::total_space(workspace)) {
+        (Ok(available), Ok(total)) => Some(Capacity { available, total }),
+        _ => None,
+    };
+    SystemResources {
+        load_average: load_average(),
+        cpus: std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
+        memory: memory(),
+        disk,
+    }
+}
+
+#[cfg(unix)]
+fn load_average() -> Option<[f64; 3]> {
+    let mut loads = [0f64; 3];
+    // SAFETY: `loads` has room for the three samples requested.
+    let filled = unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) };
+    (filled == 3).then_some(loads)
+}
+
+#[cfg(not(unix))]
+fn load_average() -> Option<[f64; 3]> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn memory() -> Option<Capacity> {
+    parse_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory() -> Option<Capacity> {
+    None
+}
+
+/// `MemAvailable` and `MemTotal` from `/proc/meminfo`, which are in KiB.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo(meminfo: &str) -> Option<Capacity> {
+    Some(Capacity {
+        available: kib_field(meminfo, "MemAvailable")?,
+        total: kib_field(meminfo, "MemTotal")?,
+    })
+}
+
+/// Resident memory of this process in bytes; only reported on Linux.
+pub fn process_memory() -> Option<u64> {
+    platform_process_memory()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_process_memory() -> Option<u64> {
+    kib_field(&std::fs::read_to_string("/proc/self/status").ok()?, "VmRSS")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn platform_process_memory() -> Option<u64> {
+    None
+}
+
+/// A `Name:   1234 kB` line of a `/proc` file, in bytes.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn kib_field(text: &str, name: &str) -> Option<u64> {
+    text.lines().find_map(|line| {
+        let value = line.strip_prefix(name)?.strip_prefix(':')?;
+        let kib = value.trim().trim_end_matches("kB").trim();
+        kib.parse::<u64>().ok().map(|kib| kib * 1024)
+    })
+}
+
+/// NVIDIA GPUs as reported by `nvidia-smi`; empty when it is not installed.
+pub fn collect_gpus() -> Vec<GpuInfo> {
+    let Ok(nvidia_smi) = which::which("nvidia-smi") else {
+        return Vec::new();
+    };
+    let output = Command::new(nvidia_smi)
+        .args([
+            "--query-gpu=name,utilization.gpu,memory.used,memory.total",
+            "--format=csv,noheader,nounits",
+        ])
+        .stdin(Stdio::null())
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Lines of `name, utilization %, used MiB, total MiB`.
+fn parse_nvidia_smi(csv: &str) -> Vec<GpuInfo> {
+    csv.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [name, utilization, used, total] = fields.as_slice() else {
+                return None;
+            };
+            let mib = |value: &str| value.parse::<u64>().ok().map(|mib| mib * 1024 * 1024);
+            let total = mib(total)?;
+            Some(GpuInfo {
+                name: (*name).to_string(),
+                utilization_percent: utilization.parse().unwrap_or(0),
+                memory: Capacity {
+                    available: total.saturating_sub(mib(used)?),
+                    total,
+                },
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Battery {
+    pub percent: u8,
+    /// On external power, whether charging or full.
+    pub plugged_in: bool,
+}
+
+/// Charge of the laptop battery: from `/sys/class/power_supply` on Linux and
+/// `pmset` on macOS. `None` on machines without one.
+pub fn battery() -> Option<Battery> {
+    platform_battery()
+}
+
+#[cfg(target_os = "linux")]
+fn platform_battery() -> Option<Battery> {
+    std::fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .find_map(|entry| sysfs_battery(&entry.path()))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_battery() -> Option<Battery> {
+    let output = Command::new("pmset")
+        .args(["-g", "batt"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn platform_battery() -> Option<Battery> {
+    None
+}
+
+/// A `power_supply` device, when it is a battery.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn sysfs_battery(device: &Path) -> Option<Battery> {
+    let read = |name: &str| std::fs::read_to_string(device.join(name)).ok();
+    if read("type")?.trim() != "Battery" {
+        return None;
+    }
+    let percent = read("capacity")?.trim().parse::<u8>().ok()?;
+    let status = read("status").unwrap_or_default();
+    Some(Battery {
+        percent: percent.min(100),
+        plugged_in: status.trim() != "Discharging",
+    })
+}
+
+/// The internal battery line of `pmset -g batt`, e.g.
+/// ` -InternalBattery-0 (id=4653155)\t85%; discharging; 4:12 remaining present: true`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> Option<Battery> {
+    output.lines().find_map(|line| {
+        let (_, rest) = line.split_once('\t')?;
+        let mut fields = rest.split(';').map(str::trim);
+        let percent = fields.next()?.strip_suffix('%')?.parse::<u8>().ok()?;
+        let status = fields.next().unwrap_or_default();
+        Some(Battery {
+            percent: percent.min(100),
+            plugged_in: status != "discharging",
+        })
+    })
+}
+
+/// `bytes` in binary units with one decimal, e.g. `12.3 GiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_meminfo_and_nvidia_smi() {
+        let meminfo = "MemTotal:       16318480 kB\nMemFree:         1048576 kB\nMemAvailable:    1572864 kB\n";
+        let memory = parse_meminfo(meminfo).expect("memory fields");
+        assert_eq!(
+            memory,
+            Capacity {
+                available: 1_572_864 * 1024,
+                total: 16_318_480 * 1024,
+            }
+        );
+        assert!(memory.is_low());
+        assert_eq!(memory.used_percent(), 91);
+
+        let gpus = parse_nvidia_smi("NVIDIA A100-SXM4-40GB, 87, 30720, 40960\ngarbage\n");
+        assert_eq!(
+            gpus,
+            vec![GpuInfo {
+                name: "NVIDIA A100-SXM4-40GB".to_string(),
+                utilization_percent: 87,
+                memory: Capacity {
+                    available: 10240 * 1024 * 1024,
+                    total: 40960 * 1024 * 1024,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_sysfs_and_pmset_batteries() {
+        let power_supply = tempfile::tempdir().expect("tempdir");
+        let adapter = power_supply.path().join("AC");
+        std::fs::create_dir(&adapter).expect("create AC");
+        std::fs::write(adapter.join("type"), "Mains\n").expect("write type");
+        assert_eq!(sysfs_battery(&adapter), None);
+
+        let battery = power_supply.path().join("BAT0");
+        std::fs::create_dir(&battery).expect("create BAT0");
+        std::fs::write(battery.join("type"), "Battery\n").expect("write type");
+        std::fs::write(battery.join("capacity"), "42\n").expect("write capacity");
+        std::fs::write(battery.join("status"), "Discharging\n").expect("write status");
+        assert_eq!(
+            sysfs_battery(&battery),
+            Some(Battery {
+                percent: 42,
+                plugged_in: false,
+            })
+        );
+
+        let pmset = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t97%; charging; 0:20 remaining present: true\n";
+        assert_eq!(
+            parse_pmset(pmset),
+            Some(Battery {
+                percent: 97,
+                plugged_in: true,
+            })
+        );
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), None);
+    }
+
+    #[test]
+    fn flags_low_disk_by_size_or_share() {
+        let resources = |available: u64, total: u64| SystemResources {
+            load_average: None,
+            cpus: 1,
+            memory: None,
+            disk: Some(Capacity { available, total }),
+        };
+        const GIB: u64 = 1024 * 1024 * 1024;
+        assert!(resources(4 * GIB, 100 * GIB).disk_is_low());
+        assert!(resources(50 * GIB, 1000 * GIB).disk_is_low());
+        assert!(!resources(50 * GIB, 100 * GIB).disk_is_low());
+        assert_eq!(format_bytes(50 * GIB + GIB / 2), "50.5 GiB");
+        assert_eq!(format_bytes(512), "512 B");
+    }
+}
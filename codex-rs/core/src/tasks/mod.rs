@@ -105,15 +105,20 @@ impl Session {
         last_agent_message: Option<String>,
     ) {
         let mut active = self.active_turn.lock().await;
-        if let Some(at) = active.as_mut()
-            && at.remove_task(&sub_id)
-        {
-            *active = None;
+        let mut summary = None;
+        if let Some(at) = active.as_mut() {
+            summary = at.turn_state.lock().await.summary();
+            if at.remove_task(&sub_id) {
+                *active = None;
+            }
         }
         drop(active);
         let event = Event {
             id: sub_id,
-            msg: EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }),
+            msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message,
+                summary,
+            }),
         };
         self.send_event(event).await;
     }
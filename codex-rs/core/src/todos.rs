@@ -0,0 +1,419 @@
+//! TODO, FIXME and HACK comments across the workspace, for the `todos` tool
+//! and the TUI's `/todos`. The walk honors `.gitignore` and `.codexignore`
+//! files and skips hidden and large files.
+//!
+//! Results are cached per workspace: a rescan only reads files whose size or
+//! modification time changed, which keeps repeated scans of large
+//! repositories cheap.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use ignore::WalkBuilder;
+
+/// Name of the ignore files, in `.gitignore` syntax, whose paths Codex
+/// leaves out of workspace scans.
+pub const CODEX_IGNORE_FILENAME: &str = ".codexignore";
+
+/// Files larger than this are generated or vendored more often than not.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Where a comment may start on a line; the tag must come after one.
+const COMMENT_MARKERS: &[&str] = &["//", "/*", "#", "--", "<!--", "%"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TodoTag {
+    Todo,
+    Fixme,
+    Hack,
+}
+
+impl TodoTag {
+    const ALL: [TodoTag; 3] = [TodoTag::Todo, TodoTag::Fixme, TodoTag::Hack];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TodoTag::Todo => "TODO",
+            TodoTag::Fixme => "FIXME",
+            TodoTag::Hack => "HACK",
+        }
+    }
+}
+
+impl fmt::Display for TodoTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoComment {
+    /// Path relative to the scanned directory.
+    pub path: PathBuf,
+    /// Line of the comment, numbered from 1.
+    pub line: usize,
+    pub tag: TodoTag,
+    /// Who the comment is assigned to, as in `TODO(alice): ...`.
+    pub owner: Option<String>,
+    /// The comment after the tag and owner.
+    pub text: String,
+}
+
+impl TodoComment {
+    /// `TODO(alice): text`, as it would be written.
+    pub fn label(&self) -> String {
+        let owner = self
+            .owner
+            .as_ref()
+            .map(|owner| format!("({owner})"))
+            .unwrap_or_default();
+        if self.text.is_empty() {
+            format!("{}{owner}", self.tag)
+        } else {
+            format!("{}{owner}: {}", self.tag, self.text)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TodoGrouping {
+    #[default]
+    File,
+    Owner,
+}
+
+#[derive(Debug, Clone)]
+struct CachedFile {
+    modified: Option<SystemTime>,
+    len: u64,
+    todos: Vec<TodoComment>,
+}
+
+/// Scanned files per scanned directory.
+static CACHE: LazyLock<Mutex<HashMap<PathBuf, HashMap<PathBuf, CachedFile>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// TODO, FIXME and HACK comments in the files under `root`, ordered by path
+/// and line. This walks the tree, so call it off the async runtime.
+pub fn harvest_todos(root: &Path) -> Vec<TodoComment> {
+    let previous = CACHE
+        .lock()
+        .ok()
+        .and_then(|mut cache| cache.remove(root))
+        .unwrap_or_default();
+    let mut scanned = HashMap::with_capacity(previous.len());
+
+    let walker = WalkBuilder::new(root)
+        .require_git(false)
+        .add_custom_ignore_filename(CODEX_IGNORE_FILENAME)
+        .build();
+    for entry in walker.flatten() {
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        let path = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_path_buf();
+        let modified = metadata.modified().ok();
+        let file = match previous.get(&path) {
+            Some(cached)
+                if modified.is_some()
+                    && cached.modified == modified
+                    && cached.len == metadata.len() =>
+            {
+                cached.clone()
+            }
+            _ => CachedFile {
+                modified,
+                len: metadata.len(),
+                // Binary and non-UTF-8 files have no comments worth listing.
+                todos: std::fs::read_to_string(entry.path())
+                    .map(|text| find_todos(&path, &text))
+                    .unwrap_or_default(),
+            },
+        };
+        scanned.insert(path, file);
+    }
+
+    let mut todos: Vec<TodoComment> = scanned
+        .values()
+        .flat_map(|file| file.todos.iter().cloned())
+        .collect();
+    todos.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.insert(root.to_path_buf(), scanned);
+    }
+    todos
+}
+
+/// The comments of one file's `text`.
+fn find_todos(path: &Path, text: &str) -> Vec<TodoComment> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (tag, owner, text) = parse_todo(line)?;
+            Some(TodoComment {
+                path: path.to_path_buf(),
+                line: index + 1,
+                tag,
+                owner,
+                text,
+            })
+        })
+        .collect()
+}
+
+/// The tag, owner and text of a TODO comment on `line`, if it has one. Tags
+/// count only in upper case, as whole words, and inside a comment, so code
+/// mentioning e.g. a `todo!()` macro or a `TodoList` is not listed.
+fn parse_todo(line: &str) -> Option<(TodoTag, Option<String>, String)> {
+    let trimmed = line.trim_start();
+    let comment_start = if trimmed.starts_with('*') {
+        line.len() - trimmed.len()
+    } else {
+        COMMENT_MARKERS
+            .iter()
+            .filter_map(|marker| line.find(marker))
+            .min()?
+    };
+    let comment = &line[comment_start..];
+
+    let (start, tag) = TodoTag::ALL
+        .iter()
+        .filter_map(|&tag| {
+            comment.match_indices(tag.as_str()).find_map(|(start, _)| {
+                let before = comment[..start].chars().next_back();
+                let after = comment[start + tag.as_str().len()..].chars().next();
+                let word_start = !before.is_some_and(|c| c.is_alphanumeric() || c == '_');
+                let word_end = after.is_none_or(|c| c == '(' || c == ':' || c.is_whitespace());
+                (word_start && word_end).then_some((start, tag))
+            })
+        })
+        .min_by_key(|(start, _)| *start)?;
+
+    let mut rest = &comment[start + tag.as_str().len()..];
+    let mut owner = None;
+    if let Some(after_paren) = rest.strip_prefix('(')
+        && let Some((name, after)) = after_paren.split_once(')')
+    {
+        let name = name.trim().trim_start_matches('@');
+        if !name.is_empty() {
+            owner = Some(name.to_string());
+        }
+        rest = after;
+    }
+    let text = rest
+        .trim_start_matches([':', '-', ' ', '\t'])
+        .trim_end()
+        .trim_end_matches("-->")
+        .trim_end_matches("*/")
+        .trim_end();
+    Some((tag, owner, text.to_string()))
+}
+
+/// `todos` grouped by file or owner, groups in alphabetical order and
+/// unowned comments last.
+pub fn group_todos(
+    todos: &[TodoComment],
+    grouping: TodoGrouping,
+) -> Vec<(String, Vec<&TodoComment>)> {
+    let mut groups: BTreeMap<(bool, String), Vec<&TodoComment>> = BTreeMap::new();
+    for todo in todos {
+        let key = match grouping {
+            TodoGrouping::File => (false, todo.path.display().to_string()),
+            TodoGrouping::Owner => match &todo.owner {
+                Some(owner) => (false, owner.clone()),
+                None => (true, "unowned".to_string()),
+            },
+        };
+        groups.entry(key).or_default().push(todo);
+    }
+    groups
+        .into_iter()
+        .map(|((_, name), todos)| (name, todos))
+        .collect()
+}
+
+/// A plain-text listing of at most `limit` of `todos`, grouped.
+pub fn format_todos(todos: &[TodoComment], grouping: TodoGrouping, limit: usize) -> String {
+    if todos.is_empty() {
+        return "No TODO, FIXME or HACK comments found.".to_string();
+    }
+    let files = group_todos(todos, TodoGrouping::File).len();
+    let mut lines = vec![format!(
+        "{} TODO/FIXME/HACK comments in {files} files",
+        todos.len()
+    )];
+    let mut shown = 0;
+    for (name, group) in group_todos(todos, grouping) {
+        if shown == limit {
+            break;
+        }
+        lines.push(format!("{name} ({})", group.len()));
+        for todo in group.into_iter().take(limit - shown) {
+            shown += 1;
+            lines.push(match grouping {
+                TodoGrouping::File => format!("  {}: {}", todo.line, todo.label()),
+                TodoGrouping::Owner => {
+                    format!("  {}:{}: {}", todo.path.display(), todo.line, todo.label())
+                }
+            });
+        }
+    }
+    if shown < todos.len() {
+        lines.push(format!("... and {} more", todos.len() - shown));
+    }
+    lines.join("\n")
+}
+
+/// A prompt asking the agent to resolve `todos`.
+pub fn fix_todos_prompt(todos: &[&TodoComment]) -> String {
+    let mut prompt = String::from(
+        "Resolve these TODO/FIXME/HACK comments. For each one, make the change it asks for and \
+         remove the comment. Leave any that cannot be done safely and say why.\n",
+    );
+    for todo in todos {
+        prompt.push_str(&format!(
+            "\n- {}:{} {}",
+            todo.path.display(),
+            todo.line,
+            todo.label()
+        ));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parsed(line: &str) -> Option<(TodoTag, Option<String>, String)> {
+        parse_todo(line)
+    }
+
+    #[test]
+    fn parses_tags_owners_and_text_in_comments() {
+        assert_eq!(
+            parsed("    // TODO(alice): handle the error"),
+            Some((
+                TodoTag::Todo,
+                Some("alice".to_string()),
+                "handle the error".to_string()
+            ))
+        );
+        assert_eq!(
+            parsed("x = 1  # FIXME overflow on 32-bit"),
+            Some((TodoTag::Fixme, None, "overflow on 32-bit".to_string()))
+        );
+        assert_eq!(
+            parsed(" * HACK(@bob) - until the API is fixed */"),
+            Some((
+                TodoTag::Hack,
+                Some("bob".to_string()),
+                "until the API is fixed".to_string()
+            ))
+        );
+        assert_eq!(
+            parsed("<!-- TODO: screenshots -->"),
+            Some((TodoTag::Todo, None, "screenshots".to_string()))
+        );
+        assert_eq!(
+            parsed("// TODO"),
+            Some((TodoTag::Todo, None, String::new()))
+        );
+    }
+
+    #[test]
+    fn ignores_code_and_partial_words() {
+        assert_eq!(parsed("let todo = TODO_LIST;"), None);
+        assert_eq!(parsed("    todo!()"), None);
+        assert_eq!(parsed("// See TODOS.md and the FIXMEs"), None);
+        assert_eq!(parsed(r#"let s = "TODO: not a comment";"#), None);
+    }
+
+    #[test]
+    fn harvest_honors_codexignore_and_picks_up_changes() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        std::fs::create_dir(root.join("src")).expect("mkdir src");
+        std::fs::create_dir(root.join("vendor")).expect("mkdir vendor");
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "fn main() {}\n// TODO(alice): handle errors\n",
+        )
+        .expect("write lib.rs");
+        std::fs::write(root.join("vendor/dep.rs"), "// FIXME: not ours\n").expect("write dep.rs");
+        std::fs::write(root.join(CODEX_IGNORE_FILENAME), "vendor/\n").expect("write ignore");
+
+        let todos = harvest_todos(root);
+        assert_eq!(
+            todos,
+            vec![TodoComment {
+                path: PathBuf::from("src/lib.rs"),
+                line: 2,
+                tag: TodoTag::Todo,
+                owner: Some("alice".to_string()),
+                text: "handle errors".to_string(),
+            }]
+        );
+
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// HACK: temporary\nfn main() {}\n// TODO(alice): handle errors\n",
+        )
+        .expect("rewrite lib.rs");
+        let lines: Vec<_> = harvest_todos(root)
+            .iter()
+            .map(|todo| (todo.line, todo.tag))
+            .collect();
+        assert_eq!(lines, vec![(1, TodoTag::Hack), (3, TodoTag::Todo)]);
+    }
+
+    #[test]
+    fn formats_groups_by_owner() {
+        let todo = |path: &str, line, owner: Option<&str>| TodoComment {
+            path: PathBuf::from(path),
+            line,
+            tag: TodoTag::Todo,
+            owner: owner.map(str::to_string),
+            text: "do it".to_string(),
+        };
+        let todos = vec![
+            todo("a.rs", 1, None),
+            todo("a.rs", 5, Some("zoe")),
+            todo("b.rs", 2, Some("al")),
+        ];
+
+        assert_eq!(
+            format_todos(&todos, TodoGrouping::Owner, 2),
+            "3 TODO/FIXME/HACK comments in 2 files\n\
+             al (1)\n  b.rs:2: TODO(al): do it\n\
+             zoe (1)\n  a.rs:5: TODO(zoe): do it\n\
+             ... and 1 more"
+        );
+        assert_eq!(
+            fix_todos_prompt(&todos.iter().take(1).collect::<Vec<_>>())
+                .lines()
+                .last(),
+            Some("- a.rs:1 TODO: do it")
+        );
+    }
+}
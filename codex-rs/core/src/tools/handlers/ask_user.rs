@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::protocol::UserQuestionKind;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+const NO_ANSWER: &str = "The user did not answer.";
+
+pub struct AskUserHandler;
+
+#[derive(Deserialize)]
+struct AskUserArgs {
+    question: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    options: Vec<String>,
+}
+
+#[async_trait]
+impl ToolHandler for AskUserHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            sub_id,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "ask_user handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: AskUserArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+        let kind = question_kind(args.kind.as_deref(), args.options)?;
+
+        let answer = session
+            .ask_user(&sub_id, call_id, args.question, kind)
+            .await;
+        Ok(ToolOutput::Function {
+            content: answer.unwrap_or_else(|| NO_ANSWER.to_string()),
+            success: Some(true),
+        })
+    }
+}
+
+fn question_kind(
+    kind: Option<&str>,
+    options: Vec<String>,
+) -> Result<UserQuestionKind, FunctionCallError> {
+    match kind {
+        Some("choice") if options.len() < 2 => Err(FunctionCallError::RespondToModel(
+            "a choice needs at least two options".to_string(),
+        )),
+        Some("choice") => Ok(UserQuestionKind::Choice { options }),
+        None | Some("text") => Ok(UserQuestionKind::Text { placeholder: None }),
+        Some("file") => Ok(UserQuestionKind::File),
+        Some(other) => Err(FunctionCallError::RespondToModel(format!(
+            "kind must be \"choice\", \"text\" or \"file\", not {other:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn choices_need_two_options() {
+        let options = vec!["yes".to_string(), "no".to_string()];
+        assert_eq!(
+            question_kind(Some("choice"), options.clone()).ok(),
+            Some(UserQuestionKind::Choice { options })
+        );
+        assert!(question_kind(Some("choice"), vec!["yes".to_string()]).is_err());
+        assert_eq!(
+            question_kind(None, Vec::new()).ok(),
+            Some(UserQuestionKind::Text { placeholder: None })
+        );
+        assert!(question_kind(Some("date"), Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::rollout::RolloutRecorder;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+const DEFAULT_MAX_RESULTS: usize = 5;
+const MAX_RESULTS_LIMIT: usize = 20;
+/// Bytes of context shown on each side of the first match.
+const SNIPPET_CONTEXT: usize = 240;
+
+pub struct ConversationSearchHandler;
+
+#[derive(Deserialize)]
+struct ConversationSearchArgs {
+    query: String,
+    #[serde(default)]
+    max_results: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for ConversationSearchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "conversation_search handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: ConversationSearchArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+        if args.query.trim().is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+
+        let Some(rollout_path) = session.flushed_rollout_path().await else {
+            return Err(FunctionCallError::RespondToModel(
+                "this session's history is not being recorded".to_string(),
+            ));
+        };
+        let history = RolloutRecorder::get_rollout_history(&rollout_path)
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to read the session history: {err}"
+                ))
+            })?;
+
+        let max_results = args
+            .max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT);
+        Ok(ToolOutput::Function {
+            content: search_history(&history.get_rollout_items(), &args.query, max_results),
+            success: Some(true),
+        })
+    }
+}
+
+/// One searchable piece of the history: a message, a tool call together
+/// with its output, or a compaction summary.
+#[derive(Debug, PartialEq)]
+struct HistoryEntry {
+    /// Position in the rollout, so results can be told apart and ordered.
+    position: usize,
+    label: String,
+    text: String,
+}
+
+fn history_entries(items: &[RolloutItem]) -> Vec<HistoryEntry> {
+    let mut entries = Vec::new();
+    // call_id -> description of the call, for labelling its output.
+    let mut calls: HashMap<&str, String> = HashMap::new();
+    for (position, item) in items.iter().enumerate() {
+        let item = match item {
+            RolloutItem::ResponseItem(item) => item,
+            RolloutItem::Compacted(compacted) => {
+                entries.push(HistoryEntry {
+                    position,
+                    label: "compaction summary".to_string(),
+                    text: compacted.message.clone(),
+                });
+                continue;
+            }
+            _ => continue,
+        };
+        match item {
+            ResponseItem::Message { role, content, .. } => {
+                let text = content
+                    .iter()
+                    .filter_map(|content| match content {
+                        ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                            Some(text.as_str())
+                        }
+                        ContentItem::InputImage { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                entries.push(HistoryEntry {
+                    position,
+                    label: format!("{role} message"),
+                    text,
+                });
+            }
+            ResponseItem::FunctionCall {
+                name,
+                arguments,
+                call_id,
+                ..
+            } => {
+                calls.insert(call_id, describe_function_call(name, arguments));
+            }
+            ResponseItem::LocalShellCall {
+                call_id: Some(call_id),
+                action: LocalShellAction::Exec(exec),
+                ..
+            } => {
+                calls.insert(call_id, format!("shell: {}", exec.command.join(" ")));
+            }
+            ResponseItem::CustomToolCall {
+                name,
+                input,
+                call_id,
+                ..
+            } => {
+                calls.insert(call_id, format!("{name}: {input}"));
+            }
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                entries.push(output_entry(position, &calls, call_id, &output.content));
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                entries.push(output_entry(position, &calls, call_id, output));
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+fn output_entry(
+    position: usize,
+    calls: &HashMap<&str, String>,
+    call_id: &str,
+    output: &str,
+) -> HistoryEntry {
+    let call = calls
+        .get(call_id)
+        .cloned()
+        .unwrap_or_else(|| "tool call".to_string());
+    HistoryEntry {
+        position,
+        label: "tool call".to_string(),
+        text: format!("{call}\n{output}"),
+    }
+}
+
+/// Shell calls by their command, anything else by name and arguments.
+fn describe_function_call(name: &str, arguments: &str) -> String {
+    let command = serde_json::from_str::<serde_json::Value>(arguments)
+        .ok()
+        .and_then(|args| {
+            let command = args.get("command")?.as_array()?;
+            let words: Vec<&str> = command.iter().filter_map(|word| word.as_str()).collect();
+            Some(words.join(" "))
+        });
+    match command {
+        Some(command) => format!("{name}: {command}"),
+        None => format!("{name}: {arguments}"),
+    }
+}
+
+/// Entries containing every word of `query`, case-insensitively, most
+/// recent first, each with a snippet around its first match.
+fn search_history(items: &[RolloutItem], query: &str, max_results: usize) -> String {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let entries = history_entries(items);
+    let matches: Vec<&HistoryEntry> = entries
+        .iter()
+        .rev()
+        .filter(|entry| {
+            let haystack = entry.text.to_ascii_lowercase();
+            terms.iter().all(|term| haystack.contains(term.as_str()))
+        })
+        .collect();
+    if matches.is_empty() {
+        return format!(
+            "No earlier messages or tool calls contain \"{query}\" ({} entries searched).",
+            entries.len()
+        );
+    }
+
+    let mut out = format!(
+        "{} match(es) for \"{query}\", showing {} most recent first:",
+        matches.len(),
+        matches.len().min(max_results)
+    );
+    for entry in matches.into_iter().take(max_results) {
+        out.push_str(&format!(
+            "\n\n[#{} {}]\n{}",
+            entry.position,
+            entry.label,
+            snippet(&entry.text, &terms[0])
+        ));
+    }
+    out
+}
+
+/// The text around the first occurrence of `term`, which is lowercase.
+fn snippet(text: &str, term: &str) -> String {
+    let at = text.to_ascii_lowercase().find(term).unwrap_or(0);
+    let mut start = at.saturating_sub(SNIPPET_CONTEXT);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (at + term.len() + SNIPPET_CONTEXT).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    format!("{prefix}{}{suffix}", text[start..end].trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::protocol::CompactedItem;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        })
+    }
+
+    #[test]
+    fn finds_the_command_behind_an_error() {
+        let items = vec![
+            message("user", "fix the build"),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                id: None,
+                name: "shell".to_string(),
+                arguments: r#"{"command":["cargo","build"]}"#.to_string(),
+                call_id: "call-1".to_string(),
+            }),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "error[E0425]: cannot find value `x` in this scope".to_string(),
+                    success: Some(false),
+                },
+            }),
+            RolloutItem::Compacted(CompactedItem {
+                message: "Fixed E0425 in src/lib.rs".to_string(),
+            }),
+        ];
+
+        assert_eq!(
+            search_history(&items, "E0425 cannot", 5),
+            "1 match(es) for \"E0425 cannot\", showing 1 most recent first:\n\n\
+             [#2 tool call]\nshell: cargo build\nerror[E0425]: cannot find value `x` in this scope"
+        );
+        assert_eq!(
+            search_history(&items, "e0425", 1),
+            "2 match(es) for \"e0425\", showing 1 most recent first:\n\n\
+             [#3 compaction summary]\nFixed E0425 in src/lib.rs"
+        );
+        assert_eq!(
+            search_history(&items, "segfault", 5),
+            "No earlier messages or tool calls contain \"segfault\" (3 entries searched)."
+        );
+    }
+
+    #[test]
+    fn snippet_trims_long_text_around_the_match() {
+        let text = format!("{}needle{}", "a".repeat(500), "é".repeat(500));
+        let snippet = snippet(&text, "needle");
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+    }
+}
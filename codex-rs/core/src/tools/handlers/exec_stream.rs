@@ -19,6 +19,10 @@ impl ToolHandler for ExecStreamHandler {
         ToolKind::Function
     }
 
+    fn local_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::process::Output;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -6,7 +7,9 @@ use serde::Deserialize;
 use tokio::process::Command;
 use tokio::time::timeout;
 
+use crate::config_types::RemoteWorkspace;
 use crate::function_tool::FunctionCallError;
+use crate::remote::quote;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -18,6 +21,8 @@ pub struct GrepFilesHandler;
 const DEFAULT_LIMIT: usize = 100;
 const MAX_LIMIT: usize = 2000;
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+/// Exit code the remote shell reports when `rg` is not installed.
+const COMMAND_NOT_FOUND_EXIT_CODE: i32 = 127;
 
 fn default_limit() -> usize {
     DEFAULT_LIMIT
@@ -41,7 +46,12 @@ impl ToolHandler for GrepFilesHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, turn, .. } = invocation;
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -74,8 +84,6 @@ impl ToolHandler for GrepFilesHandler {
         let limit = args.limit.min(MAX_LIMIT);
         let search_path = turn.resolve_path(args.path.clone());
 
-        verify_path_exists(&search_path).await?;
-
         let include = args.include.as_deref().map(str::trim).and_then(|val| {
             if val.is_empty() {
                 None
@@ -84,8 +92,23 @@ impl ToolHandler for GrepFilesHandler {
             }
         });
 
-        let search_results =
-            run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?;
+        let search_results = match session.services.executor.remote() {
+            Some(remote) => {
+                run_remote_rg_search(
+                    &remote,
+                    pattern,
+                    include.as_deref(),
+                    &search_path,
+                    limit,
+                    &turn.cwd,
+                )
+                .await?
+            }
+            None => {
+                verify_path_exists(&search_path).await?;
+                run_rg_search(pattern, include.as_deref(), &search_path, limit, &turn.cwd).await?
+            }
+        };
 
         if search_results.is_empty() {
             Ok(ToolOutput::Function {
@@ -118,17 +141,9 @@ async fn run_rg_search(
     let mut command = Command::new("rg");
     command
         .current_dir(cwd)
-        .arg("--files-with-matches")
-        .arg("--sortr=modified")
-        .arg("--regexp")
-        .arg(pattern)
-        .arg("--no-messages");
-
-    if let Some(glob) = include {
-        command.arg("--glob").arg(glob);
-    }
-
-    command.arg("--").arg(search_path);
+        .args(rg_args(pattern, include))
+        .arg("--")
+        .arg(search_path);
 
     let output = timeout(COMMAND_TIMEOUT, command.output())
         .await
@@ -141,6 +156,75 @@ async fn run_rg_search(
             ))
         })?;
 
+    rg_results(&output, limit)
+}
+
+/// [`run_rg_search`] on the remote host, where `search_path` and `cwd` are.
+async fn run_remote_rg_search(
+    remote: &RemoteWorkspace,
+    pattern: &str,
+    include: Option<&str>,
+    search_path: &Path,
+    limit: usize,
+    cwd: &Path,
+) -> Result<Vec<String>, FunctionCallError> {
+    let script = remote_rg_script(pattern, include, search_path, cwd);
+    let output = timeout(COMMAND_TIMEOUT, remote.command(script).output())
+        .await
+        .map_err(|_| {
+            FunctionCallError::RespondToModel(format!(
+                "rg on {} timed out after 30 seconds",
+                remote.host
+            ))
+        })?
+        .map_err(|err| FunctionCallError::RespondToModel(format!("failed to run ssh: {err}")))?;
+
+    if output.status.code() == Some(COMMAND_NOT_FOUND_EXIT_CODE) {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "rg is not installed on {}; use the shell tool instead",
+            remote.host
+        )));
+    }
+    rg_results(&output, limit)
+}
+
+/// Shell script that checks `search_path` exists, since `--no-messages`
+/// would hide that, then runs `rg` in `cwd`.
+fn remote_rg_script(
+    pattern: &str,
+    include: Option<&str>,
+    search_path: &Path,
+    cwd: &Path,
+) -> String {
+    let search_path = quote(&search_path.to_string_lossy());
+    let args = rg_args(pattern, include)
+        .iter()
+        .map(String::as_str)
+        .map(quote)
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "cd -- {} && ls -d -- {search_path} > /dev/null && rg {args} -- {search_path}",
+        quote(&cwd.to_string_lossy())
+    )
+}
+
+fn rg_args(pattern: &str, include: Option<&str>) -> Vec<String> {
+    let mut args = vec![
+        "--files-with-matches".to_string(),
+        "--sortr=modified".to_string(),
+        "--regexp".to_string(),
+        pattern.to_string(),
+        "--no-messages".to_string(),
+    ];
+    if let Some(glob) = include {
+        args.push("--glob".to_string());
+        args.push(glob.to_string());
+    }
+    args
+}
+
+fn rg_results(output: &Output, limit: usize) -> Result<Vec<String>, FunctionCallError> {
     match output.status.code() {
         Some(0) => Ok(parse_results(&output.stdout, limit)),
         Some(1) => Ok(Vec::new()),
@@ -198,6 +282,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remote_search_runs_rg_in_the_remote_cwd() {
+        let script = remote_rg_script(
+            "fn main",
+            Some("*.rs"),
+            Path::new("/srv/app/src"),
+            Path::new("/srv/app"),
+        );
+        assert_eq!(
+            script,
+            "cd -- '/srv/app' && ls -d -- '/srv/app/src' > /dev/null && rg '--files-with-matches' '--sortr=modified' '--regexp' 'fn main' '--no-messages' '--glob' '*.rs' -- '/srv/app/src'"
+        );
+    }
+
     #[tokio::test]
     async fn run_search_returns_results() -> anyhow::Result<()> {
         if !rg_available() {
@@ -1,15 +1,20 @@
+use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::ffi::OsStr;
 use std::fs::FileType;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
 use tokio::fs;
+use tokio::time::timeout;
 
+use crate::config_types::RemoteWorkspace;
 use crate::function_tool::FunctionCallError;
+use crate::remote::quote;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -20,6 +25,14 @@ pub struct ListDirHandler;
 
 const MAX_ENTRY_LENGTH: usize = 500;
 const INDENTATION_SPACES: usize = 2;
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run by `find -exec` on the remote host: prints each path it is given
+/// after a letter for its kind, NUL-terminated so any file name survives.
+const REMOTE_ENTRY_SCRIPT: &str = r#"for p do
+if [ -L "$p" ]; then t=l; elif [ -d "$p" ]; then t=d; elif [ -f "$p" ]; then t=f; else t=o; fi
+printf '%s%s\0' "$t" "$p"
+done"#;
 
 fn default_offset() -> usize {
     1
@@ -51,7 +64,9 @@ impl ToolHandler for ListDirHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -100,7 +115,10 @@ impl ToolHandler for ListDirHandler {
             ));
         }
 
-        let entries = list_dir_slice(&path, offset, limit, depth).await?;
+        let entries = match session.services.executor.remote() {
+            Some(remote) => list_remote_dir_slice(&remote, &path, offset, limit, depth).await?,
+            None => list_dir_slice(&path, offset, limit, depth).await?,
+        };
         let mut output = Vec::with_capacity(entries.len() + 1);
         output.push(format!("Absolute path: {}", path.display()));
         output.extend(entries);
@@ -119,7 +137,48 @@ async fn list_dir_slice(
 ) -> Result<Vec<String>, FunctionCallError> {
     let mut entries = Vec::new();
     collect_entries(path, Path::new(""), depth, &mut entries).await?;
+    select_entries(&entries, offset, limit)
+}
+
+/// [`list_dir_slice`] for a directory on the remote host.
+async fn list_remote_dir_slice(
+    remote: &RemoteWorkspace,
+    path: &Path,
+    offset: usize,
+    limit: usize,
+    depth: usize,
+) -> Result<Vec<String>, FunctionCallError> {
+    let script = format!(
+        "cd -- {} && find . -mindepth 1 -maxdepth {depth} -exec sh -c {} sh {{}} +",
+        quote(&path.to_string_lossy()),
+        quote(REMOTE_ENTRY_SCRIPT)
+    );
+    let output = timeout(REMOTE_TIMEOUT, remote.command(script).output())
+        .await
+        .map_err(|_| {
+            FunctionCallError::RespondToModel(format!(
+                "listing the directory on {} timed out",
+                remote.host
+            ))
+        })?
+        .map_err(|err| FunctionCallError::RespondToModel(format!("failed to run ssh: {err}")))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(FunctionCallError::RespondToModel(format!(
+            "failed to read directory on {}: {}",
+            remote.host,
+            stderr.trim()
+        )));
+    }
+    let entries = remote_entries(&output.stdout, depth);
+    select_entries(&entries, offset, limit)
+}
 
+fn select_entries(
+    entries: &[DirEntry],
+    offset: usize,
+    limit: usize,
+) -> Result<Vec<String>, FunctionCallError> {
     if entries.is_empty() {
         return Ok(Vec::new());
     }
@@ -172,28 +231,9 @@ async fn collect_entries(
                 FunctionCallError::RespondToModel(format!("failed to inspect entry: {err}"))
             })?;
 
-            let file_name = entry.file_name();
-            let relative_path = if prefix.as_os_str().is_empty() {
-                PathBuf::from(&file_name)
-            } else {
-                prefix.join(&file_name)
-            };
-
-            let display_name = format_entry_component(&file_name);
-            let display_depth = prefix.components().count();
-            let sort_key = format_entry_name(&relative_path);
             let kind = DirEntryKind::from(&file_type);
-            dir_entries.push((
-                entry.path(),
-                relative_path,
-                kind,
-                DirEntry {
-                    name: sort_key,
-                    display_name,
-                    depth: display_depth,
-                    kind,
-                },
-            ));
+            let (relative_path, dir_entry) = dir_entry(&prefix, &entry.file_name(), kind);
+            dir_entries.push((entry.path(), relative_path, kind, dir_entry));
         }
 
         dir_entries.sort_unstable_by(|a, b| a.3.name.cmp(&b.3.name));
@@ -209,6 +249,68 @@ async fn collect_entries(
     Ok(())
 }
 
+/// The entries `find` printed on the remote host, in the order
+/// [`collect_entries`] walks a local directory. Each record is a kind letter
+/// followed by a `./`-prefixed path and a NUL byte.
+fn remote_entries(stdout: &[u8], depth: usize) -> Vec<DirEntry> {
+    let mut children: HashMap<PathBuf, Vec<(PathBuf, DirEntryKind)>> = HashMap::new();
+    for record in stdout.split(|byte| *byte == 0) {
+        let Some((&kind, path)) = record.split_first() else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(path);
+        let Some(path) = path.strip_prefix("./") else {
+            continue;
+        };
+        let path = PathBuf::from(path);
+        let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        children
+            .entry(parent)
+            .or_default()
+            .push((path, DirEntryKind::from_letter(kind)));
+    }
+
+    let mut entries = Vec::new();
+    let mut queue = VecDeque::from([(PathBuf::new(), depth)]);
+    while let Some((prefix, remaining_depth)) = queue.pop_front() {
+        let mut dir_entries: Vec<_> = children
+            .remove(&prefix)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(path, kind)| {
+                let (relative_path, entry) = dir_entry(&prefix, path.file_name()?, kind);
+                Some((relative_path, kind, entry))
+            })
+            .collect();
+        dir_entries.sort_unstable_by(|a, b| a.2.name.cmp(&b.2.name));
+
+        for (relative_path, kind, dir_entry) in dir_entries {
+            if kind == DirEntryKind::Directory && remaining_depth > 1 {
+                queue.push_back((relative_path, remaining_depth - 1));
+            }
+            entries.push(dir_entry);
+        }
+    }
+    entries
+}
+
+/// The entry for `file_name` in the directory at `prefix`, relative to the
+/// listed directory, along with its relative path.
+fn dir_entry(prefix: &Path, file_name: &OsStr, kind: DirEntryKind) -> (PathBuf, DirEntry) {
+    let relative_path = if prefix.as_os_str().is_empty() {
+        PathBuf::from(file_name)
+    } else {
+        prefix.join(file_name)
+    };
+    let entry = DirEntry {
+        name: format_entry_name(&relative_path),
+        display_name: format_entry_component(file_name),
+        depth: prefix.components().count(),
+        kind,
+    };
+    (relative_path, entry)
+}
+
 fn format_entry_name(path: &Path) -> String {
     let normalized = path.to_string_lossy().replace("\\", "/");
     if normalized.len() > MAX_ENTRY_LENGTH {
@@ -269,6 +371,18 @@ impl From<&FileType> for DirEntryKind {
     }
 }
 
+impl DirEntryKind {
+    /// The kind [`REMOTE_ENTRY_SCRIPT`] printed as `letter`.
+    fn from_letter(letter: u8) -> Self {
+        match letter {
+            b'd' => DirEntryKind::Directory,
+            b'f' => DirEntryKind::File,
+            b'l' => DirEntryKind::Symlink,
+            _ => DirEntryKind::Other,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,6 +446,25 @@ mod tests {
         assert_eq!(entries, expected);
     }
 
+    #[test]
+    fn lists_remote_entries_like_local_ones() {
+        let stdout = b"d./nested\0f./entry.txt\0l./link\0d./nested/deeper\0f./nested/child.txt\0f./nested/deeper/grandchild.txt\0";
+
+        let entries = select_entries(&remote_entries(stdout, 3), 1, 20).expect("list directory");
+
+        assert_eq!(
+            entries,
+            vec![
+                "entry.txt".to_string(),
+                "link@".to_string(),
+                "nested/".to_string(),
+                "  child.txt".to_string(),
+                "  deeper/".to_string(),
+                "    grandchild.txt".to_string(),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn errors_when_offset_exceeds_entries() {
         let temp = tempdir().expect("create tempdir");
@@ -1,4 +1,6 @@
 pub mod apply_patch;
+mod ask_user;
+mod conversation_search;
 mod exec_stream;
 mod grep_files;
 mod list_dir;
@@ -6,13 +8,17 @@ mod mcp;
 mod plan;
 mod read_file;
 mod shell;
+mod system_info;
 mod test_sync;
+mod todos;
 mod unified_exec;
 mod view_image;
 
 pub use plan::PLAN_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use ask_user::AskUserHandler;
+pub use conversation_search::ConversationSearchHandler;
 pub use exec_stream::ExecStreamHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
@@ -20,6 +26,8 @@ pub use mcp::McpHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
 pub use shell::ShellHandler;
+pub use system_info::SystemInfoHandler;
 pub use test_sync::TestSyncHandler;
+pub use todos::TodosHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
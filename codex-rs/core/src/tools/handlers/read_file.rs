@@ -1,9 +1,11 @@
+use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
 use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
+use tempfile::NamedTempFile;
 
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
@@ -82,6 +84,13 @@ impl LineRecord {
         self.trimmed().is_empty()
     }
 
+    fn numbered(&self) -> NumberedLine {
+        NumberedLine {
+            number: self.number,
+            text: self.display.clone(),
+        }
+    }
+
     fn is_comment(&self) -> bool {
         COMMENT_PREFIXES
             .iter()
@@ -96,7 +105,9 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation {
+            session, payload, ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -140,38 +151,160 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        // In a remote workspace the path names a file on the remote host;
+        // read a local copy of it.
+        let remote_copy = match session.services.executor.remote() {
+            Some(remote) => Some(remote.fetch_file(&path).await.map_err(|err| {
+                if err.kind() == std::io::ErrorKind::IsADirectory {
+                    FunctionCallError::RespondToModel(
+                        "file_path is a directory; use list_dir instead".to_string(),
+                    )
+                } else {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to read file on {}: {err}",
+                        remote.host
+                    ))
+                }
+            })?),
+            None => None,
+        };
+        let local_path = remote_copy
+            .as_ref()
+            .map_or(path.as_path(), NamedTempFile::path);
+
+        let info = inspect::inspect(local_path).await?;
+        if let FileEncoding::Binary(kind) = info.encoding {
+            return Ok(ToolOutput::Function {
+                content: format!(
+                    "{file_path} is a binary file ({kind}, {} bytes); its contents were not returned",
+                    info.size
+                ),
+                success: Some(false),
+            });
+        }
+
         let collected = match mode {
-            ReadMode::Slice => slice::read(&path, offset, limit).await?,
+            ReadMode::Slice => slice::read(local_path, offset, limit).await?,
             ReadMode::Indentation => {
                 let indentation = indentation.unwrap_or_default();
-                indentation::read_block(&path, offset, limit, indentation).await?
+                indentation::read_block(local_path, offset, limit, indentation).await?
             }
         };
+
+        // External edits are only tracked for local files.
+        if remote_copy.is_none() {
+            session.note_file_read(&path).await;
+        }
+
+        let mut content = info.header(&collected);
+        for line in &collected {
+            content.push('\n');
+            content.push_str(&line.render());
+        }
         Ok(ToolOutput::Function {
-            content: collected.join("\n"),
+            content,
             success: Some(true),
         })
     }
 }
 
+/// Text encodings the reader can transcode, or the reason a file is refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileEncoding {
+    Utf8,
+    /// UTF-8 prefixed with a byte order mark, which is skipped.
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// Text that is not all valid UTF-8. Lines that are valid UTF-8 are read
+    /// as such; in the others every byte maps to one code point.
+    Latin1,
+    /// Best guess at the file type, e.g. "PNG image".
+    Binary(&'static str),
+}
+
+impl FileEncoding {
+    fn label(self) -> &'static str {
+        match self {
+            FileEncoding::Utf8 => "utf-8",
+            FileEncoding::Utf8Bom => "utf-8 (bom)",
+            FileEncoding::Utf16Le => "utf-16le",
+            FileEncoding::Utf16Be => "utf-16be",
+            FileEncoding::Latin1 => "latin-1",
+            FileEncoding::Binary(_) => "binary",
+        }
+    }
+
+    fn decode_line(self, bytes: &[u8]) -> Cow<'_, str> {
+        match self {
+            FileEncoding::Latin1 => match std::str::from_utf8(bytes) {
+                Ok(text) => Cow::Borrowed(text),
+                Err(_) => Cow::Owned(bytes.iter().map(|&b| char::from(b)).collect()),
+            },
+            _ => String::from_utf8_lossy(bytes),
+        }
+    }
+}
+
+/// Metadata reported to the model alongside the requested lines.
+#[derive(Debug)]
+struct FileInfo {
+    size: u64,
+    encoding: FileEncoding,
+    /// Total number of lines; `None` when the file is too large to count cheaply.
+    line_count: Option<usize>,
+}
+
+/// A line returned to the model, with its 1-indexed number in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NumberedLine {
+    number: usize,
+    text: String,
+}
+
+impl NumberedLine {
+    fn render(&self) -> String {
+        format!("L{}: {}", self.number, self.text)
+    }
+}
+
+impl FileInfo {
+    /// One-line summary prepended to the returned lines, including where to
+    /// resume when the file continues past the returned range.
+    fn header(&self, collected: &[NumberedLine]) -> String {
+        let mut fields = vec![
+            format!("size: {} bytes", self.size),
+            format!("encoding: {}", self.encoding.label()),
+        ];
+        if let Some(line_count) = self.line_count {
+            fields.push(format!("lines: {line_count}"));
+        }
+
+        if let (Some(first), Some(last)) = (collected.first(), collected.last()) {
+            let (first, last) = (first.number, last.number);
+            fields.push(format!("showing: L{first}-L{last}"));
+            if self.line_count.is_none_or(|count| last < count) {
+                fields.push(format!("next offset: {}", last + 1));
+            }
+        }
+        format!("[{}]", fields.join(", "))
+    }
+}
+
 mod slice {
     use crate::function_tool::FunctionCallError;
+    use crate::tools::handlers::read_file::NumberedLine;
     use crate::tools::handlers::read_file::format_line;
+    use crate::tools::handlers::read_file::inspect::open_lines;
     use std::path::Path;
-    use tokio::fs::File;
     use tokio::io::AsyncBufReadExt;
-    use tokio::io::BufReader;
 
     pub async fn read(
         path: &Path,
         offset: usize,
         limit: usize,
-    ) -> Result<Vec<String>, FunctionCallError> {
-        let file = File::open(path).await.map_err(|err| {
-            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
-        })?;
-
-        let mut reader = BufReader::new(file);
+    ) -> Result<Vec<NumberedLine>, FunctionCallError> {
+        let (mut reader, encoding) = open_lines(path).await?;
         let mut collected = Vec::new();
         let mut seen = 0usize;
         let mut buffer = Vec::new();
@@ -203,8 +336,10 @@ mod slice {
                 break;
             }
 
-            let formatted = format_line(&buffer);
-            collected.push(format!("L{seen}: {formatted}"));
+            collected.push(NumberedLine {
+                number: seen,
+                text: format_line(&buffer, encoding),
+            });
 
             if collected.len() == limit {
                 break;
@@ -225,21 +360,21 @@ mod indentation {
     use crate::function_tool::FunctionCallError;
     use crate::tools::handlers::read_file::IndentationArgs;
     use crate::tools::handlers::read_file::LineRecord;
+    use crate::tools::handlers::read_file::NumberedLine;
     use crate::tools::handlers::read_file::TAB_WIDTH;
     use crate::tools::handlers::read_file::format_line;
+    use crate::tools::handlers::read_file::inspect::open_lines;
     use crate::tools::handlers::read_file::trim_empty_lines;
     use std::collections::VecDeque;
     use std::path::Path;
-    use tokio::fs::File;
     use tokio::io::AsyncBufReadExt;
-    use tokio::io::BufReader;
 
     pub async fn read_block(
         path: &Path,
         offset: usize,
         limit: usize,
         options: IndentationArgs,
-    ) -> Result<Vec<String>, FunctionCallError> {
+    ) -> Result<Vec<NumberedLine>, FunctionCallError> {
         let anchor_line = options.anchor_line.unwrap_or(offset);
         if anchor_line == 0 {
             return Err(FunctionCallError::RespondToModel(
@@ -276,10 +411,7 @@ mod indentation {
         let final_limit = limit.min(guard_limit).min(collected.len());
 
         if final_limit == 1 {
-            return Ok(vec![format!(
-                "L{}: {}",
-                collected[anchor_index].number, collected[anchor_index].display
-            )]);
+            return Ok(vec![collected[anchor_index].numbered()]);
         }
 
         // Cursors
@@ -360,18 +492,11 @@ mod indentation {
         // Trim empty lines
         trim_empty_lines(&mut out);
 
-        Ok(out
-            .into_iter()
-            .map(|record| format!("L{}: {}", record.number, record.display))
-            .collect())
+        Ok(out.into_iter().map(LineRecord::numbered).collect())
     }
 
     async fn collect_file_lines(path: &Path) -> Result<Vec<LineRecord>, FunctionCallError> {
-        let file = File::open(path).await.map_err(|err| {
-            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
-        })?;
-
-        let mut reader = BufReader::new(file);
+        let (mut reader, encoding) = open_lines(path).await?;
         let mut buffer = Vec::new();
         let mut lines = Vec::new();
         let mut number = 0usize;
@@ -394,9 +519,9 @@ mod indentation {
             }
 
             number += 1;
-            let raw = String::from_utf8_lossy(&buffer).into_owned();
+            let raw = encoding.decode_line(&buffer).into_owned();
             let indent = measure_indent(&raw);
-            let display = format_line(&buffer);
+            let display = format_line(&buffer, encoding);
             lines.push(LineRecord {
                 number,
                 raw,
@@ -430,8 +555,231 @@ mod indentation {
     }
 }
 
-fn format_line(bytes: &[u8]) -> String {
-    let decoded = String::from_utf8_lossy(bytes);
+mod inspect {
+    use crate::function_tool::FunctionCallError;
+    use crate::tools::handlers::read_file::FileEncoding;
+    use crate::tools::handlers::read_file::FileInfo;
+    use std::collections::HashMap;
+    use std::fs::Metadata;
+    use std::io::Cursor;
+    use std::io::SeekFrom;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::sync::LazyLock;
+    use std::sync::Mutex;
+    use std::time::SystemTime;
+    use tokio::fs::File;
+    use tokio::io::AsyncBufRead;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncSeekExt;
+    use tokio::io::BufReader;
+
+    /// Bytes sniffed from the start of the file to pick an encoding.
+    const SAMPLE_BYTES: usize = 8 * 1024;
+    /// Files larger than this are not scanned up front to count lines.
+    const MAX_LINE_COUNT_BYTES: u64 = 64 * 1024 * 1024;
+    /// UTF-16 files are transcoded in memory, so they are capped.
+    const MAX_TRANSCODE_BYTES: u64 = 16 * 1024 * 1024;
+    /// Line counts kept; the cache starts over once it is full.
+    const MAX_CACHED_LINE_COUNTS: usize = 64;
+
+    /// Line counts of recently read files, valid while a file's modification
+    /// time and size are unchanged, so paging through a file does not rescan
+    /// it for every range.
+    static LINE_COUNTS: LazyLock<Mutex<HashMap<PathBuf, CountedLines>>> =
+        LazyLock::new(|| Mutex::new(HashMap::new()));
+
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    struct CountedLines {
+        modified: SystemTime,
+        size: u64,
+        count: usize,
+    }
+
+    const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "PNG image"),
+        (b"\xff\xd8\xff", "JPEG image"),
+        (b"GIF8", "GIF image"),
+        (b"%PDF-", "PDF document"),
+        (b"PK\x03\x04", "zip archive"),
+        (b"\x1f\x8b", "gzip archive"),
+        (b"\x7fELF", "ELF executable"),
+        (b"\0asm", "WebAssembly module"),
+    ];
+
+    pub type LineReader = Box<dyn AsyncBufRead + Unpin + Send>;
+
+    pub async fn inspect(path: &Path) -> Result<FileInfo, FunctionCallError> {
+        let metadata = tokio::fs::metadata(path).await.map_err(read_error)?;
+        if metadata.is_dir() {
+            return Err(FunctionCallError::RespondToModel(
+                "file_path is a directory; use list_dir instead".to_string(),
+            ));
+        }
+        let size = metadata.len();
+        let encoding = detect_encoding(path, size).await?;
+
+        let line_count = match encoding {
+            FileEncoding::Binary(_) => None,
+            _ if size > MAX_LINE_COUNT_BYTES => None,
+            _ => Some(cached_line_count(path, &metadata).await?),
+        };
+
+        Ok(FileInfo {
+            size,
+            encoding,
+            line_count,
+        })
+    }
+
+    /// Open `path` as a stream of UTF-8 (or Latin-1) lines, transcoding
+    /// UTF-16 and skipping byte order marks.
+    pub async fn open_lines(path: &Path) -> Result<(LineReader, FileEncoding), FunctionCallError> {
+        let size = tokio::fs::metadata(path).await.map_err(read_error)?.len();
+        let encoding = detect_encoding(path, size).await?;
+        let mut file = File::open(path).await.map_err(read_error)?;
+
+        let reader: LineReader = match encoding {
+            FileEncoding::Utf16Le | FileEncoding::Utf16Be => {
+                if size > MAX_TRANSCODE_BYTES {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "UTF-16 file is too large to transcode ({size} bytes)"
+                    )));
+                }
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes).await.map_err(read_error)?;
+                let text = decode_utf16(&bytes[2..], encoding == FileEncoding::Utf16Le);
+                Box::new(Cursor::new(text.into_bytes()))
+            }
+            FileEncoding::Utf8Bom => {
+                file.seek(SeekFrom::Start(3)).await.map_err(read_error)?;
+                Box::new(BufReader::new(file))
+            }
+            _ => Box::new(BufReader::new(file)),
+        };
+        Ok((reader, encoding))
+    }
+
+    async fn detect_encoding(path: &Path, size: u64) -> Result<FileEncoding, FunctionCallError> {
+        let file = File::open(path).await.map_err(read_error)?;
+        let mut sample = Vec::with_capacity(SAMPLE_BYTES);
+        file.take(SAMPLE_BYTES as u64)
+            .read_to_end(&mut sample)
+            .await
+            .map_err(read_error)?;
+        Ok(classify(&sample, size))
+    }
+
+    pub fn classify(sample: &[u8], size: u64) -> FileEncoding {
+        if sample.starts_with(b"\xef\xbb\xbf") {
+            return FileEncoding::Utf8Bom;
+        }
+        // A UTF-16 body is always an even number of bytes; anything else
+        // merely happens to start with BOM-like bytes.
+        let utf16_candidate = size >= 2 && size.is_multiple_of(2);
+        if utf16_candidate && sample.starts_with(b"\xff\xfe") {
+            return FileEncoding::Utf16Le;
+        }
+        if utf16_candidate && sample.starts_with(b"\xfe\xff") {
+            return FileEncoding::Utf16Be;
+        }
+        if let Some(&(_, kind)) = MAGIC_NUMBERS
+            .iter()
+            .find(|(magic, _)| sample.starts_with(magic))
+        {
+            return FileEncoding::Binary(kind);
+        }
+        if sample.contains(&0) {
+            return FileEncoding::Binary("unknown type");
+        }
+
+        match std::str::from_utf8(sample) {
+            Ok(_) => FileEncoding::Utf8,
+            // The sample may end in the middle of a multi-byte sequence.
+            Err(err) if err.error_len().is_none() => FileEncoding::Utf8,
+            // BOM-like prefixes that failed the UTF-16 check are left to the
+            // lossy UTF-8 decoder rather than being reinterpreted.
+            Err(_) if sample.starts_with(b"\xff\xfe") || sample.starts_with(b"\xfe\xff") => {
+                FileEncoding::Utf8
+            }
+            Err(_) => FileEncoding::Latin1,
+        }
+    }
+
+    fn decode_utf16(bytes: &[u8], little_endian: bool) -> String {
+        let units = bytes.chunks_exact(2).map(|pair| {
+            if little_endian {
+                u16::from_le_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_be_bytes([pair[0], pair[1]])
+            }
+        });
+        char::decode_utf16(units)
+            .map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
+    /// [`count_lines`], served from [`LINE_COUNTS`] when the file has not
+    /// changed since it was last counted.
+    async fn cached_line_count(
+        path: &Path,
+        metadata: &Metadata,
+    ) -> Result<usize, FunctionCallError> {
+        let Ok(modified) = metadata.modified() else {
+            return count_lines(path).await;
+        };
+        let size = metadata.len();
+        let cached = LINE_COUNTS
+            .lock()
+            .ok()
+            .and_then(|counts| counts.get(path).copied())
+            .filter(|counted| counted.modified == modified && counted.size == size);
+        if let Some(counted) = cached {
+            return Ok(counted.count);
+        }
+
+        let count = count_lines(path).await?;
+        if let Ok(mut counts) = LINE_COUNTS.lock() {
+            if counts.len() >= MAX_CACHED_LINE_COUNTS {
+                counts.clear();
+            }
+            counts.insert(
+                path.to_path_buf(),
+                CountedLines {
+                    modified,
+                    size,
+                    count,
+                },
+            );
+        }
+        Ok(count)
+    }
+
+    async fn count_lines(path: &Path) -> Result<usize, FunctionCallError> {
+        let (mut reader, _) = open_lines(path).await?;
+        let mut buffer = Vec::new();
+        let mut count = 0usize;
+        loop {
+            buffer.clear();
+            let bytes_read = reader
+                .read_until(b'\n', &mut buffer)
+                .await
+                .map_err(read_error)?;
+            if bytes_read == 0 {
+                return Ok(count);
+            }
+            count += 1;
+        }
+    }
+
+    fn read_error(err: std::io::Error) -> FunctionCallError {
+        FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+    }
+}
+
+fn format_line(bytes: &[u8], encoding: FileEncoding) -> String {
+    let decoded = encoding.decode_line(bytes);
     if decoded.len() > MAX_LINE_LENGTH {
         take_bytes_at_char_boundary(&decoded, MAX_LINE_LENGTH).to_string()
     } else {
@@ -492,12 +840,32 @@ mod defaults {
 
 #[cfg(test)]
 mod tests {
-    use super::indentation::read_block;
-    use super::slice::read;
     use super::*;
     use pretty_assertions::assert_eq;
+    use std::path::Path;
     use tempfile::NamedTempFile;
 
+    /// [`slice::read`], rendered the way the model sees the lines.
+    async fn read(
+        path: &Path,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<String>, FunctionCallError> {
+        let lines = slice::read(path, offset, limit).await?;
+        Ok(lines.iter().map(NumberedLine::render).collect())
+    }
+
+    /// [`indentation::read_block`], rendered the way the model sees the lines.
+    async fn read_block(
+        path: &Path,
+        offset: usize,
+        limit: usize,
+        options: IndentationArgs,
+    ) -> Result<Vec<String>, FunctionCallError> {
+        let lines = indentation::read_block(path, offset, limit, options).await?;
+        Ok(lines.iter().map(NumberedLine::render).collect())
+    }
+
     #[tokio::test]
     async fn reads_requested_range() -> anyhow::Result<()> {
         let mut temp = NamedTempFile::new()?;
@@ -543,6 +911,79 @@ gamma
         Ok(())
     }
 
+    #[tokio::test]
+    async fn transcodes_utf16_files() -> anyhow::Result<()> {
+        let mut temp = NamedTempFile::new()?;
+        use std::io::Write as _;
+        let mut bytes = vec![0xff, 0xfe];
+        for unit in "héllo\r\nwörld\n".encode_utf16() {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        temp.as_file_mut().write_all(&bytes)?;
+
+        let lines = read(temp.path(), 1, 2).await?;
+        assert_eq!(
+            lines,
+            vec!["L1: héllo".to_string(), "L2: wörld".to_string()]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn transcodes_latin1_files() -> anyhow::Result<()> {
+        let mut temp = NamedTempFile::new()?;
+        use std::io::Write as _;
+        temp.as_file_mut().write_all(b"caf\xe9\nna\xefve\n")?;
+
+        let lines = read(temp.path(), 1, 2).await?;
+        assert_eq!(lines, vec!["L1: café".to_string(), "L2: naïve".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn latin1_files_keep_their_utf8_lines() -> anyhow::Result<()> {
+        let mut temp = NamedTempFile::new()?;
+        use std::io::Write as _;
+        let mut bytes = b"caf\xe9\n".to_vec();
+        bytes.extend_from_slice("naïve\n".as_bytes());
+        temp.as_file_mut().write_all(&bytes)?;
+
+        let lines = read(temp.path(), 1, 2).await?;
+        assert_eq!(lines, vec!["L1: café".to_string(), "L2: naïve".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn inspect_reports_metadata_and_binary_files() -> anyhow::Result<()> {
+        let mut text = NamedTempFile::new()?;
+        use std::io::Write as _;
+        write!(text, "one\ntwo\nthree\n")?;
+        let info = inspect::inspect(text.path()).await?;
+        assert_eq!(info.encoding, FileEncoding::Utf8);
+        assert_eq!(info.line_count, Some(3));
+        assert_eq!(
+            info.header(&[
+                NumberedLine {
+                    number: 1,
+                    text: "one".to_string(),
+                },
+                NumberedLine {
+                    number: 2,
+                    text: "two".to_string(),
+                },
+            ]),
+            "[size: 14 bytes, encoding: utf-8, lines: 3, showing: L1-L2, next offset: 3]"
+        );
+
+        let mut png = NamedTempFile::new()?;
+        png.as_file_mut()
+            .write_all(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR")?;
+        let info = inspect::inspect(png.path()).await?;
+        assert_eq!(info.encoding, FileEncoding::Binary("PNG image"));
+        assert_eq!(info.line_count, None);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn trims_crlf_endings() -> anyhow::Result<()> {
         let mut temp = NamedTempFile::new()?;
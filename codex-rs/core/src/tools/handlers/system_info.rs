@@ -0,0 +1,134 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+
+use crate::function_tool::FunctionCallError;
+use crate::system_info::GpuInfo;
+use crate::system_info::SystemResources;
+use crate::system_info::collect_gpus;
+use crate::system_info::collect_system_resources;
+use crate::system_info::format_bytes;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SystemInfoHandler;
+
+#[async_trait]
+impl ToolHandler for SystemInfoHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn local_only(&self) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { turn, payload, .. } = invocation;
+
+        if !matches!(payload, ToolPayload::Function { .. }) {
+            return Err(FunctionCallError::RespondToModel(
+                "system_info handler received unsupported payload".to_string(),
+            ));
+        }
+
+        let cwd = turn.cwd.clone();
+        let (resources, gpus) =
+            tokio::task::spawn_blocking(move || (collect_system_resources(&cwd), collect_gpus()))
+                .await
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to collect system info: {err}"
+                    ))
+                })?;
+
+        Ok(ToolOutput::Function {
+            content: format_report(&turn.cwd, &resources, &gpus),
+            success: Some(true),
+        })
+    }
+}
+
+fn format_report(cwd: &Path, resources: &SystemResources, gpus: &[GpuInfo]) -> String {
+    let mut lines = Vec::new();
+    lines.push(match resources.load_average {
+        Some([one, five, fifteen]) => format!(
+            "cpu: load {one:.2} / {five:.2} / {fifteen:.2} (1m / 5m / 15m) across {} cpus",
+            resources.cpus
+        ),
+        None => format!("cpu: {} cpus, load unknown", resources.cpus),
+    });
+    lines.push(match resources.memory {
+        Some(memory) => format!(
+            "memory: {} free of {} ({}% used){}",
+            format_bytes(memory.available),
+            format_bytes(memory.total),
+            memory.used_percent(),
+            if resources.memory_is_low() {
+                " - LOW"
+            } else {
+                ""
+            }
+        ),
+        None => "memory: unknown".to_string(),
+    });
+    lines.push(match resources.disk {
+        Some(disk) => format!(
+            "disk ({}): {} free of {} ({}% used){}",
+            cwd.display(),
+            format_bytes(disk.available),
+            format_bytes(disk.total),
+            disk.used_percent(),
+            if resources.disk_is_low() {
+                " - LOW, free space before large builds"
+            } else {
+                ""
+            }
+        ),
+        None => format!("disk ({}): unknown", cwd.display()),
+    });
+    for (index, gpu) in gpus.iter().enumerate() {
+        lines.push(format!(
+            "gpu {index}: {}, {}% busy, {} of {} memory free",
+            gpu.name,
+            gpu.utilization_percent,
+            format_bytes(gpu.memory.available),
+            format_bytes(gpu.memory.total),
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::system_info::Capacity;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reports_low_disk() {
+        const GIB: u64 = 1024 * 1024 * 1024;
+        let resources = SystemResources {
+            load_average: Some([3.5, 2.25, 1.0]),
+            cpus: 8,
+            memory: Some(Capacity {
+                available: 8 * GIB,
+                total: 16 * GIB,
+            }),
+            disk: Some(Capacity {
+                available: 2 * GIB,
+                total: 100 * GIB,
+            }),
+        };
+
+        assert_eq!(
+            format_report(Path::new("/work/app"), &resources, &[]),
+            "cpu: load 3.50 / 2.25 / 1.00 (1m / 5m / 15m) across 8 cpus\n\
+             memory: 8.0 GiB free of 16.0 GiB (50% used)\n\
+             disk (/work/app): 2.0 GiB free of 100.0 GiB (98% used) - LOW, free space before large builds"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::todos::TodoGrouping;
+use crate::todos::format_todos;
+use crate::todos::harvest_todos;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+const DEFAULT_MAX_RESULTS: usize = 100;
+const MAX_RESULTS_LIMIT: usize = 1000;
+
+pub struct TodosHandler;
+
+#[derive(Deserialize)]
+struct TodosArgs {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    group_by: Option<String>,
+    #[serde(default)]
+    max_results: Option<usize>,
+}
+
+#[async_trait]
+impl ToolHandler for TodosHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn local_only(&self) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { payload, turn, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "todos handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: TodosArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+
+        let grouping = match args.group_by.as_deref() {
+            None | Some("file") => TodoGrouping::File,
+            Some("owner") => TodoGrouping::Owner,
+            Some(other) => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "group_by must be \"file\" or \"owner\", not {other:?}"
+                )));
+            }
+        };
+        let max_results = args
+            .max_results
+            .unwrap_or(DEFAULT_MAX_RESULTS)
+            .clamp(1, MAX_RESULTS_LIMIT);
+        let root = turn.resolve_path(args.path);
+        if !root.is_dir() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "{} is not a directory",
+                root.display()
+            )));
+        }
+
+        let todos = tokio::task::spawn_blocking(move || harvest_todos(&root))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to scan for TODOs: {err}"))
+            })?;
+
+        Ok(ToolOutput::Function {
+            content: format_todos(&todos, grouping, max_results),
+            success: Some(true),
+        })
+    }
+}
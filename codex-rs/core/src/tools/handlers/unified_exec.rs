@@ -26,6 +26,10 @@ impl ToolHandler for UnifiedExecHandler {
         ToolKind::UnifiedExec
     }
 
+    fn local_only(&self) -> bool {
+        true
+    }
+
     fn matches_kind(&self, payload: &ToolPayload) -> bool {
         matches!(
             payload,
@@ -72,6 +76,13 @@ impl ToolHandler for UnifiedExecHandler {
             None
         };
 
+        // Input to a running session is stdin, not a command.
+        if parsed_session_id.is_none()
+            && let Some(refusal) = session.command_deny_list_refusal(&input)
+        {
+            return Err(FunctionCallError::RespondToModel(refusal));
+        }
+
         let request = UnifiedExecRequest {
             session_id: parsed_session_id,
             input_chunks: &input,
@@ -26,6 +26,10 @@ impl ToolHandler for ViewImageHandler {
         ToolKind::Function
     }
 
+    fn local_only(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -1,5 +1,6 @@
 pub mod context;
 pub(crate) mod handlers;
+pub(crate) mod output_parsers;
 pub mod parallel;
 pub mod registry;
 pub mod router;
@@ -23,6 +24,10 @@ use crate::function_tool::FunctionCallError;
 use crate::tools::context::ApplyPatchCommandContext;
 use crate::tools::context::ExecCommandContext;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::output_parsers::StructuredFailures;
+use crate::tools::output_parsers::parse_command_failures;
+use crate::verify_commands::combined_command;
+use crate::write_quota::patch_write_bytes;
 use codex_apply_patch::MaybeApplyPatchVerified;
 use codex_apply_patch::maybe_parse_apply_patch_verified;
 use codex_protocol::protocol::AskForApproval;
@@ -68,7 +73,15 @@ pub(crate) async fn handle_container_exec_with_params(
     }
 
     // check if this was a patch, and apply it if so
-    let apply_patch_exec = match maybe_parse_apply_patch_verified(&params.command, &params.cwd) {
+    let verified = match sess.services.executor.remote() {
+        Some(remote) => {
+            remote
+                .verify_apply_patch(&params.command, &params.cwd)
+                .await
+        }
+        None => maybe_parse_apply_patch_verified(&params.command, &params.cwd),
+    };
+    let apply_patch_exec = match verified {
         MaybeApplyPatchVerified::Body(changes) => {
             match apply_patch::apply_patch(
                 sess.as_ref(),
@@ -105,6 +118,16 @@ pub(crate) async fn handle_container_exec_with_params(
     } else {
         params.command.clone()
     };
+    let command_for_parsing = command_for_display.clone();
+    let is_shell_command = apply_patch_exec.is_none();
+    let cwd = params.cwd.clone();
+    let provenance_changes = apply_patch_exec
+        .as_ref()
+        .filter(|_| sess.services.record_provenance)
+        .map(|exec| convert_apply_patch_to_protocol(&exec.action));
+    let patch_bytes = apply_patch_exec
+        .as_ref()
+        .map(|exec| patch_write_bytes(&exec.action));
 
     let exec_command_context = ExecCommandContext {
         sub_id: sub_id.clone(),
@@ -155,12 +178,38 @@ pub(crate) async fn handle_container_exec_with_params(
         )
         .await;
 
+    // What ran, if the user edited the proposed command before approving it.
+    let edited_command = sess.take_command_edit(&call_id).await;
+    let command_for_parsing = edited_command.clone().unwrap_or(command_for_parsing);
+    let edited_command = edited_command.as_deref();
+
     // always make sure to truncate the output if its length isn't controlled.
     match output_result {
         Ok(output) => {
             let ExecToolCallOutput { exit_code, .. } = &output;
-            let content = format_exec_output_apply_patch(&output);
+            // A successful patch names the verify commands as the next step.
+            let verify_with = match &provenance_changes {
+                Some(_) if *exit_code == 0 => combined_command(&sess.services.verify_commands),
+                _ => None,
+            };
+            let content = format_exec_output_apply_patch(
+                &output,
+                &command_for_parsing,
+                sess.services.structured_failures,
+                edited_command,
+                verify_with.as_deref(),
+            );
             if *exit_code == 0 {
+                if is_shell_command {
+                    sess.note_files_read_by_command(&command_for_parsing, &cwd)
+                        .await;
+                }
+                if let Some(changes) = &provenance_changes {
+                    sess.record_provenance(&turn_context, changes).await;
+                }
+                if let Some(bytes) = patch_bytes {
+                    sess.record_bytes_written(bytes).await;
+                }
                 Ok(content)
             } else {
                 Err(FunctionCallError::RespondToModel(content))
@@ -168,7 +217,13 @@ pub(crate) async fn handle_container_exec_with_params(
         }
         Err(ExecError::Function(err)) => Err(truncate_function_error(err)),
         Err(ExecError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output }))) => Err(
-            FunctionCallError::RespondToModel(format_exec_output_apply_patch(&output)),
+            FunctionCallError::RespondToModel(format_exec_output_apply_patch(
+                &output,
+                &command_for_parsing,
+                sess.services.structured_failures,
+                edited_command,
+                None,
+            )),
         ),
         Err(ExecError::Codex(err)) => {
             let message = format!("execution error: {err:?}");
@@ -179,7 +234,18 @@ pub(crate) async fn handle_container_exec_with_params(
     }
 }
 
-pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> String {
+/// Serialize the exec result for the model. With `structured_failures` on,
+/// failed runs of well-known commands also carry a `failures` list parsed
+/// from the output. Commands the user edited before approving say what
+/// actually ran, and successful patches carry the `verify_with` command to
+/// run next.
+pub fn format_exec_output_apply_patch(
+    exec_output: &ExecToolCallOutput,
+    command: &[String],
+    structured_failures: bool,
+    user_edited_command: Option<&[String]>,
+    verify_with: Option<&str>,
+) -> String {
     let ExecToolCallOutput {
         exit_code,
         duration,
@@ -196,12 +262,23 @@ pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> Strin
     struct ExecOutput<'a> {
         output: &'a str,
         metadata: ExecMetadata,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        failures: Option<StructuredFailures>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user_edited_command: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verify_with: Option<&'a str>,
     }
 
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
     let formatted_output = format_exec_output_str(exec_output);
+    let failures = if structured_failures && *exit_code != 0 {
+        parse_command_failures(command, &exec_output.aggregated_output.text)
+    } else {
+        None
+    };
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -209,12 +286,25 @@ pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> Strin
             exit_code: *exit_code,
             duration_seconds,
         },
+        failures,
+        user_edited_command: user_edited_command.map(display_command),
+        verify_with,
     };
 
     #[expect(clippy::expect_used)]
     serde_json::to_string(&payload).expect("serialize ExecOutput")
 }
 
+/// `command` as the user typed it: the script of a `bash -lc` invocation,
+/// otherwise the shell-quoted argv.
+fn display_command(command: &[String]) -> String {
+    match command {
+        [bash, flag, script] if bash == "bash" && flag == "-lc" => script.clone(),
+        _ => shlex::try_join(command.iter().map(String::as_str))
+            .unwrap_or_else(|_| command.join(" ")),
+    }
+}
+
 pub fn format_exec_output_str(exec_output: &ExecToolCallOutput) -> String {
     let ExecToolCallOutput {
         aggregated_output, ..
@@ -312,7 +402,9 @@ fn truncate_formatted_exec_output(content: &str, total_lines: usize) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::exec::StreamOutput;
     use regex_lite::Regex;
+    use std::time::Duration;
 
     fn assert_truncated_message_matches(message: &str, line: &str, total_lines: usize) {
         let pattern = truncated_message_pattern(line, total_lines);
@@ -343,6 +435,84 @@ mod tests {
         )
     }
 
+    #[test]
+    fn edited_commands_are_reported_to_the_model() {
+        let output = ExecToolCallOutput {
+            exit_code: 0,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new("done\n".to_string()),
+            duration: Duration::from_millis(1200),
+            timed_out: false,
+        };
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "git push --dry-run".to_string(),
+        ];
+
+        let content =
+            format_exec_output_apply_patch(&output, &command, false, Some(&command), None);
+        let payload: serde_json::Value = serde_json::from_str(&content).expect("json output");
+        assert_eq!(payload["user_edited_command"], "git push --dry-run");
+
+        let content = format_exec_output_apply_patch(&output, &command, false, None, None);
+        let payload: serde_json::Value = serde_json::from_str(&content).expect("json output");
+        assert!(payload.get("user_edited_command").is_none());
+    }
+
+    #[test]
+    fn failures_are_parsed_only_when_enabled() {
+        let output = ExecToolCallOutput {
+            exit_code: 2,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new(
+                "src/app.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n"
+                    .to_string(),
+            ),
+            duration: Duration::from_millis(300),
+            timed_out: false,
+        };
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "tsc --noEmit".to_string(),
+        ];
+
+        let content = format_exec_output_apply_patch(&output, &command, true, None, None);
+        let payload: serde_json::Value = serde_json::from_str(&content).expect("json output");
+        assert_eq!(payload["failures"]["parser"], "tsc");
+
+        let content = format_exec_output_apply_patch(&output, &command, false, None, None);
+        let payload: serde_json::Value = serde_json::from_str(&content).expect("json output");
+        assert!(payload.get("failures").is_none());
+    }
+
+    #[test]
+    fn patches_name_the_verify_command() {
+        let output = ExecToolCallOutput {
+            exit_code: 0,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(String::new()),
+            aggregated_output: StreamOutput::new(
+                "Success. Updated the following files:\n".to_string(),
+            ),
+            duration: Duration::from_millis(10),
+            timed_out: false,
+        };
+        let command = vec!["apply_patch".to_string(), "*** Begin Patch".to_string()];
+
+        let content =
+            format_exec_output_apply_patch(&output, &command, false, None, Some("cargo check"));
+        let payload: serde_json::Value = serde_json::from_str(&content).expect("json output");
+        assert_eq!(payload["verify_with"], "cargo check");
+
+        let content = format_exec_output_apply_patch(&output, &command, false, None, None);
+        let payload: serde_json::Value = serde_json::from_str(&content).expect("json output");
+        assert!(payload.get("verify_with").is_none());
+    }
+
     #[test]
     fn truncate_formatted_exec_output_truncates_large_error() {
         let line = "very long execution error line that should trigger truncation\n";
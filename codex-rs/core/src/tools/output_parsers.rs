@@ -0,0 +1,513 @@
+//! Turn the output of well-known build and test commands into a structured
+//! list of failures so the model gets precise file/line targets instead of
+//! having to fish them out of (possibly truncated) log text.
+
+use std::sync::OnceLock;
+
+use regex_lite::Regex;
+use serde::Serialize;
+
+/// Upper bound on failures attached to a single tool result.
+const MAX_FAILURES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OutputParser {
+    Cargo,
+    Pytest,
+    Eslint,
+    Tsc,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub(crate) struct CommandFailure {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) line: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) column: Option<u32>,
+    /// Name of the failing test, when the failure comes from a test runner.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) test: Option<String>,
+    pub(crate) message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct StructuredFailures {
+    pub(crate) parser: OutputParser,
+    pub(crate) failures: Vec<CommandFailure>,
+    /// Number of failures dropped because of [`MAX_FAILURES`].
+    #[serde(skip_serializing_if = "is_zero")]
+    pub(crate) omitted: usize,
+}
+
+// `skip_serializing_if` passes the field by reference.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Parse `output` produced by `command` when it is a recognised tool.
+/// Returns `None` for unknown commands or when nothing could be extracted.
+pub(crate) fn parse_command_failures(
+    command: &[String],
+    output: &str,
+) -> Option<StructuredFailures> {
+    let parser = detect_parser(command)?;
+    let mut failures = match parser {
+        OutputParser::Cargo => parse_cargo(output),
+        OutputParser::Pytest => parse_pytest(output),
+        OutputParser::Eslint => parse_eslint(output),
+        OutputParser::Tsc => parse_tsc(output),
+    };
+    if failures.is_empty() {
+        return None;
+    }
+    let omitted = failures.len().saturating_sub(MAX_FAILURES);
+    failures.truncate(MAX_FAILURES);
+    Some(StructuredFailures {
+        parser,
+        failures,
+        omitted,
+    })
+}
+
+/// Whether `command` runs a test suite, for the per-task summary.
+pub(crate) fn is_test_command(command: &[String]) -> bool {
+    let script = command_script(command);
+    let words = script_words(&script);
+    words.iter().enumerate().any(|(idx, word)| match *word {
+        "pytest" | "py.test" | "jest" | "vitest" | "mocha" | "rspec" | "phpunit" => true,
+        "cargo" => matches!(words.get(idx + 1), Some(&("test" | "nextest"))),
+        "go" | "npm" | "yarn" | "pnpm" | "bun" | "dotnet" | "mvn" | "gradle" | "gradlew"
+        | "make" => matches!(words.get(idx + 1), Some(&"test")),
+        _ => false,
+    })
+}
+
+/// `bash -lc "<script>"` is the common shape; inspect the script itself.
+fn command_script(command: &[String]) -> String {
+    match command {
+        [_, flag, script] if flag == "-lc" || flag == "-c" => script.clone(),
+        _ => command.join(" "),
+    }
+}
+
+/// Program names and arguments in `script`, with directories stripped from
+/// program paths.
+fn script_words(script: &str) -> Vec<&str> {
+    script
+        .split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '(' | ')'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.rsplit('/').next().unwrap_or(word))
+        .collect()
+}
+
+fn detect_parser(command: &[String]) -> Option<OutputParser> {
+    let script = command_script(command);
+    let words = script_words(&script);
+
+    words
+        .iter()
+        .enumerate()
+        .find_map(|(idx, word)| match *word {
+            "cargo" => words.get(idx + 1).and_then(|sub| {
+                matches!(
+                    *sub,
+                    "test" | "nextest" | "build" | "check" | "clippy" | "run"
+                )
+                .then_some(OutputParser::Cargo)
+            }),
+            "pytest" | "py.test" => Some(OutputParser::Pytest),
+            "eslint" => Some(OutputParser::Eslint),
+            "tsc" | "vue-tsc" => Some(OutputParser::Tsc),
+            _ => None,
+        })
+}
+
+fn parse_cargo(output: &str) -> Vec<CommandFailure> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut failures = Vec::new();
+
+    for (idx, line) in lines.iter().enumerate() {
+        // rustc diagnostics: `error[E0308]: mismatched types` followed by
+        // `  --> src/main.rs:4:5` a line or two later.
+        if let Some(message) = line
+            .strip_prefix("error")
+            .and_then(|rest| rest.split_once(": "))
+            .filter(|(code, _)| code.is_empty() || code.starts_with('['))
+            .map(|(_, message)| message)
+        {
+            let location = lines
+                .iter()
+                .skip(idx + 1)
+                .take(3)
+                .find_map(|next| next.trim_start().strip_prefix("--> "));
+            if let Some(location) = location {
+                let (file, line, column) = split_location(location);
+                failures.push(CommandFailure {
+                    file: Some(file),
+                    line,
+                    column,
+                    test: None,
+                    message: message.to_string(),
+                });
+            }
+            continue;
+        }
+
+        // Test panics: `thread 'tests::it_works' panicked at src/lib.rs:10:9:`
+        // with the message on the following line.
+        if let Some(caps) = cargo_panic_regex().captures(line) {
+            let test = Some(caps[1].to_string());
+            let (location, inline_message) = parse_panic_rest(&caps[2]);
+            let message = inline_message.unwrap_or_else(|| {
+                lines
+                    .iter()
+                    .skip(idx + 1)
+                    .take_while(|next| !next.trim().is_empty() && !next.starts_with("note:"))
+                    .map(|next| next.trim())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+            let (file, line, column) = split_location(location);
+            failures.push(CommandFailure {
+                file: Some(file),
+                line,
+                column,
+                test,
+                message,
+            });
+        }
+    }
+    failures
+}
+
+/// Handles both `panicked at src/lib.rs:10:9:` and the pre-1.73
+/// `panicked at 'message', src/lib.rs:10:9` forms.
+fn parse_panic_rest(rest: &str) -> (&str, Option<String>) {
+    let rest = rest.trim();
+    if let Some(quoted) = rest.strip_prefix('\'')
+        && let Some((message, location)) = quoted.rsplit_once("', ")
+    {
+        return (location, Some(message.to_string()));
+    }
+    (rest.trim_end_matches(':'), None)
+}
+
+fn parse_pytest(output: &str) -> Vec<CommandFailure> {
+    // Map each failing test to the last `path:line: Error` frame in its section.
+    let mut section: Option<String> = None;
+    let mut frames: Vec<(String, String, u32)> = Vec::new();
+    for line in output.lines() {
+        if let Some(caps) = pytest_section_regex().captures(line) {
+            section = caps.get(1).map(|m| m.as_str().to_string());
+            continue;
+        }
+        if let (Some(test), Some(caps)) = (section.as_ref(), pytest_frame_regex().captures(line))
+            && let Some(line_number) = caps.get(2).and_then(|m| m.as_str().parse().ok())
+        {
+            frames.retain(|(name, _, _)| name != test);
+            frames.push((test.clone(), caps[1].to_string(), line_number));
+        }
+    }
+
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line
+                .strip_prefix("FAILED ")
+                .or_else(|| line.strip_prefix("ERROR "))?;
+            let (node, message) = match rest.split_once(" - ") {
+                Some((node, message)) => (node, message.to_string()),
+                None => (rest, String::from("failed")),
+            };
+            let (file, test) = match node.split_once("::") {
+                Some((file, test)) => (file.to_string(), Some(test.to_string())),
+                None => (node.to_string(), None),
+            };
+            let short_name = test
+                .as_deref()
+                .map(|test| test.rsplit("::").next().unwrap_or(test));
+            let frame = short_name.and_then(|short| {
+                frames
+                    .iter()
+                    .find(|(name, _, _)| name == short || name.ends_with(&format!(".{short}")))
+            });
+            Some(CommandFailure {
+                file: Some(frame.map_or(file, |(_, path, _)| path.clone())),
+                line: frame.map(|(_, _, line)| *line),
+                column: None,
+                test,
+                message,
+            })
+        })
+        .collect()
+}
+
+fn parse_eslint(output: &str) -> Vec<CommandFailure> {
+    let mut failures = Vec::new();
+    let mut current_file: Option<&str> = None;
+    for line in output.lines() {
+        // Compact formatter: `/path/a.js: line 1, col 10, Error - message (rule)`.
+        if let Some(caps) = eslint_compact_regex().captures(line) {
+            failures.push(CommandFailure {
+                file: Some(caps[1].to_string()),
+                line: caps[2].parse().ok(),
+                column: caps[3].parse().ok(),
+                test: None,
+                message: caps[4].to_string(),
+            });
+            continue;
+        }
+        // Stylish formatter: a file header followed by indented problems.
+        if let Some(caps) = eslint_stylish_regex().captures(line) {
+            if let Some(file) = current_file {
+                let message = match caps.get(4) {
+                    Some(rule) => format!("{} ({})", caps[3].trim(), rule.as_str()),
+                    None => caps[3].trim().to_string(),
+                };
+                failures.push(CommandFailure {
+                    file: Some(file.to_string()),
+                    line: caps[1].parse().ok(),
+                    column: caps[2].parse().ok(),
+                    test: None,
+                    message,
+                });
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        if !trimmed.is_empty()
+            && !line.starts_with(char::is_whitespace)
+            && !trimmed.starts_with('✖')
+        {
+            current_file = Some(trimmed);
+        }
+    }
+    failures
+}
+
+fn parse_tsc(output: &str) -> Vec<CommandFailure> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let caps = tsc_regex()
+                .captures(line)
+                .or_else(|| tsc_pretty_regex().captures(line))?;
+            Some(CommandFailure {
+                file: Some(caps[1].to_string()),
+                line: caps[2].parse().ok(),
+                column: caps[3].parse().ok(),
+                test: None,
+                message: caps[4].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn split_location(location: &str) -> (String, Option<u32>, Option<u32>) {
+    let mut parts = location.trim().rsplitn(3, ':');
+    let last = parts.next();
+    let middle = parts.next();
+    let rest = parts.next();
+    match (rest, middle, last) {
+        (Some(file), Some(line), Some(column)) => {
+            (file.to_string(), line.parse().ok(), column.parse().ok())
+        }
+        (None, Some(file), Some(line)) => (file.to_string(), line.parse().ok(), None),
+        _ => (location.trim().to_string(), None, None),
+    }
+}
+
+fn cargo_panic_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^thread '([^']+)' panicked at (.+)$").unwrap())
+}
+
+fn pytest_section_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^_{3,} (\S+) _{3,}$").unwrap())
+}
+
+fn pytest_frame_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^(\S+\.py):(\d+): \w+").unwrap())
+}
+
+fn eslint_stylish_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^\s+(\d+):(\d+)\s+error\s+(.+?)(?:\s{2,}(\S+))?\s*$").unwrap())
+}
+
+fn eslint_compact_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^(.+): line (\d+), col (\d+), Error - (.+)$").unwrap())
+}
+
+fn tsc_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^(.+?)\((\d+),(\d+)\): error (TS\d+: .+)$").unwrap())
+}
+
+/// `tsc --pretty` output: `src/a.ts:3:7 - error TS2322: ...`.
+fn tsc_pretty_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+
+    #[expect(clippy::unwrap_used)]
+    RE.get_or_init(|| Regex::new(r"^(.+?):(\d+):(\d+) - error (TS\d+: .+)$").unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn bash(script: &str) -> Vec<String> {
+        vec!["bash".to_string(), "-lc".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn detects_known_commands_inside_shell_scripts() {
+        assert_eq!(
+            detect_parser(&bash("cd core && cargo test -p codex-core")),
+            Some(OutputParser::Cargo)
+        );
+        assert_eq!(
+            detect_parser(&bash("python -m pytest -x tests/")),
+            Some(OutputParser::Pytest)
+        );
+        assert_eq!(
+            detect_parser(&bash("./node_modules/.bin/eslint src")),
+            Some(OutputParser::Eslint)
+        );
+        assert_eq!(
+            detect_parser(&bash("npx tsc --noEmit")),
+            Some(OutputParser::Tsc)
+        );
+        assert_eq!(detect_parser(&bash("cargo fmt")), None);
+        assert_eq!(detect_parser(&bash("ls -la")), None);
+
+        assert!(is_test_command(&bash(
+            "cd core && cargo test -p codex-core"
+        )));
+        assert!(is_test_command(&bash("npm test -- --watch=false")));
+        assert!(!is_test_command(&bash("cargo build")));
+        assert!(!is_test_command(&bash("cat test/fixtures.json")));
+    }
+
+    #[test]
+    fn parses_rustc_errors_and_test_panics() {
+        let output = "\
+error[E0308]: mismatched types
+  --> src/main.rs:4:18
+   |
+4  |     let x: u32 = \"a\";
+
+---- tests::adds stdout ----
+
+thread 'tests::adds' panicked at src/lib.rs:10:9:
+assertion `left == right` failed
+  left: 3
+ right: 4
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+";
+        let parsed = parse_command_failures(&bash("cargo test"), output).expect("failures parsed");
+
+        assert_eq!(
+            parsed.failures,
+            vec![
+                CommandFailure {
+                    file: Some("src/main.rs".to_string()),
+                    line: Some(4),
+                    column: Some(18),
+                    test: None,
+                    message: "mismatched types".to_string(),
+                },
+                CommandFailure {
+                    file: Some("src/lib.rs".to_string()),
+                    line: Some(10),
+                    column: Some(9),
+                    test: Some("tests::adds".to_string()),
+                    message: "assertion `left == right` failed\nleft: 3\nright: 4".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_pytest_summary_with_frame_locations() {
+        let output = "\
+________________________________ test_total ________________________________
+
+    def test_total():
+>       assert total([1, 2]) == 4
+E       assert 3 == 4
+
+tests/test_cart.py:7: AssertionError
+=========================== short test summary info ============================
+FAILED tests/test_cart.py::test_total - assert 3 == 4
+";
+        let parsed = parse_command_failures(&bash("pytest"), output).expect("failures parsed");
+
+        assert_eq!(
+            parsed.failures,
+            vec![CommandFailure {
+                file: Some("tests/test_cart.py".to_string()),
+                line: Some(7),
+                column: None,
+                test: Some("test_total".to_string()),
+                message: "assert 3 == 4".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_eslint_stylish_and_tsc_output() {
+        let eslint = "\
+/repo/src/app.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  2:1   warning  Unexpected console statement     no-console
+
+✖ 2 problems (1 error, 1 warning)
+";
+        let parsed = parse_command_failures(&bash("eslint src"), eslint).expect("eslint parsed");
+        assert_eq!(
+            parsed.failures,
+            vec![CommandFailure {
+                file: Some("/repo/src/app.js".to_string()),
+                line: Some(1),
+                column: Some(10),
+                test: None,
+                message: "'foo' is defined but never used (no-unused-vars)".to_string(),
+            }]
+        );
+
+        let tsc =
+            "src/a.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\n";
+        let parsed = parse_command_failures(&bash("tsc --noEmit"), tsc).expect("tsc parsed");
+        assert_eq!(
+            parsed.failures,
+            vec![CommandFailure {
+                file: Some("src/a.ts".to_string()),
+                line: Some(3),
+                column: Some(7),
+                test: None,
+                message: "TS2322: Type 'string' is not assignable to type 'number'.".to_string(),
+            }]
+        );
+    }
+}
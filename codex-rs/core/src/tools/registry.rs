@@ -32,6 +32,12 @@ pub trait ToolHandler: Send + Sync {
         )
     }
 
+    /// Whether the tool works on this machine's files or processes directly,
+    /// so it cannot serve a remote workspace.
+    fn local_only(&self) -> bool {
+        false
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError>;
 }
 
@@ -96,6 +102,21 @@ impl ToolRegistry {
             return Err(FunctionCallError::Fatal(message));
         }
 
+        if handler.local_only() && invocation.session.services.executor.remote().is_some() {
+            let message = format!(
+                "{tool_name} is not available in a remote workspace; use the shell tool instead"
+            );
+            otel.tool_result(
+                tool_name.as_ref(),
+                &call_id_owned,
+                log_payload.as_ref(),
+                Duration::ZERO,
+                false,
+                &message,
+            );
+            return Err(FunctionCallError::RespondToModel(message));
+        }
+
         let output_cell = tokio::sync::Mutex::new(None);
 
         let result = otel
@@ -27,6 +27,10 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub system_info_tool: bool,
+    pub conversation_search_tool: bool,
+    pub todos_tool: bool,
+    pub ask_user_tool: bool,
     pub experimental_unified_exec_tool: bool,
     pub experimental_supported_tools: Vec<String>,
 }
@@ -38,6 +42,10 @@ pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) include_web_search_request: bool,
     pub(crate) use_streamable_shell_tool: bool,
     pub(crate) include_view_image_tool: bool,
+    pub(crate) include_system_info_tool: bool,
+    pub(crate) include_conversation_search_tool: bool,
+    pub(crate) include_todos_tool: bool,
+    pub(crate) include_ask_user_tool: bool,
     pub(crate) experimental_unified_exec_tool: bool,
 }
 
@@ -50,6 +58,10 @@ impl ToolsConfig {
             include_web_search_request,
             use_streamable_shell_tool,
             include_view_image_tool,
+            include_system_info_tool,
+            include_conversation_search_tool,
+            include_todos_tool,
+            include_ask_user_tool,
             experimental_unified_exec_tool,
         } = params;
         let shell_type = if *use_streamable_shell_tool {
@@ -77,7 +89,11 @@ impl ToolsConfig {
             plan_tool: *include_plan_tool,
             apply_patch_tool_type,
             web_search_request: *include_web_search_request,
-            include_view_image_tool: *include_view_image_tool,
+            include_view_image_tool: *include_view_image_tool && model_family.supports_image_input,
+            system_info_tool: *include_system_info_tool,
+            conversation_search_tool: *include_conversation_search_tool,
+            todos_tool: *include_todos_tool,
+            ask_user_tool: *include_ask_user_tool,
             experimental_unified_exec_tool: *experimental_unified_exec_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
         }
@@ -258,6 +274,143 @@ fn create_view_image_tool() -> ToolSpec {
     })
 }
 
+fn create_system_info_tool() -> ToolSpec {
+    ToolSpec::Function(ResponsesApiTool {
+        name: "system_info".to_string(),
+        description: "Reports CPU load, free memory, free space on the workspace volume and GPU \
+                      usage of the machine commands run on. Check it before builds or tests that \
+                      need a lot of disk or memory, or when a command fails for no clear reason."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_conversation_search_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "query".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Words to look for; an entry matches when it contains all of them, ignoring case."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_results".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of matches to return (default 5, at most 20).".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "conversation_search".to_string(),
+        description: "Searches this session's full recorded history, including messages and tool \
+                      output that were summarized away by compaction, e.g. to find which command \
+                      produced an error. Returns the most recent matches first."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_todos_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "path".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Directory to scan, relative to the working directory (default: the working \
+                 directory)."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "group_by".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "\"file\" (default) or \"owner\", the name in e.g. TODO(alice).".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "max_results".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum number of comments to list (default 100, at most 1000).".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "todos".to_string(),
+        description: "Lists the TODO, FIXME and HACK comments in the workspace with their file, \
+                      line and owner, skipping files ignored by .gitignore or .codexignore. Use \
+                      it to find known unfinished work instead of grepping the whole tree."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_ask_user_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "question".to_string(),
+        JsonSchema::String {
+            description: Some("The question to ask, in one or two sentences.".to_string()),
+        },
+    );
+    properties.insert(
+        "kind".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "\"choice\" to pick one of `options`, \"text\" (default) for a free-form \
+                 answer, or \"file\" for a file path."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "options".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some("The choices, at least two, when kind is \"choice\".".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "ask_user".to_string(),
+        description: "Asks the user a question and waits for the answer. Use it when you \
+                      cannot continue without a decision from the user, instead of ending \
+                      your turn with a question in prose. Returns the answer, or a note that \
+                      the user did not answer."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["question".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_test_sync_tool() -> ToolSpec {
     let mut properties = BTreeMap::new();
     properties.insert(
@@ -457,7 +610,9 @@ fn create_read_file_tool() -> ToolSpec {
     ToolSpec::Function(ResponsesApiTool {
         name: "read_file".to_string(),
         description:
-            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes."
+            "Reads a local file with 1-indexed line numbers, supporting slice and indentation-aware block modes. \
+             The first line reports the file size, detected encoding, line count and the next offset to \
+             continue from; UTF-16 and Latin-1 files are transcoded and binary files are refused."
                 .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
@@ -720,6 +875,8 @@ pub(crate) fn build_specs(
     use crate::exec_command::create_exec_command_tool_for_responses_api;
     use crate::exec_command::create_write_stdin_tool_for_responses_api;
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::AskUserHandler;
+    use crate::tools::handlers::ConversationSearchHandler;
     use crate::tools::handlers::ExecStreamHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
@@ -727,7 +884,9 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SystemInfoHandler;
     use crate::tools::handlers::TestSyncHandler;
+    use crate::tools::handlers::TodosHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
     use std::sync::Arc;
@@ -834,6 +993,26 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.system_info_tool {
+        builder.push_spec_with_parallel_support(create_system_info_tool(), true);
+        builder.register_handler("system_info", Arc::new(SystemInfoHandler));
+    }
+
+    if config.conversation_search_tool {
+        builder.push_spec_with_parallel_support(create_conversation_search_tool(), true);
+        builder.register_handler("conversation_search", Arc::new(ConversationSearchHandler));
+    }
+
+    if config.todos_tool {
+        builder.push_spec_with_parallel_support(create_todos_tool(), true);
+        builder.register_handler("todos", Arc::new(TodosHandler));
+    }
+
+    if config.ask_user_tool {
+        builder.push_spec(create_ask_user_tool());
+        builder.register_handler("ask_user", Arc::new(AskUserHandler));
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -913,6 +1092,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
         let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
@@ -933,6 +1116,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
         let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
@@ -955,6 +1142,10 @@ mod tests {
             include_web_search_request: false,
             use_streamable_shell_tool: false,
             include_view_image_tool: false,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
         let (tools, _) = build_specs(&config, None).build();
@@ -965,6 +1156,94 @@ mod tests {
         assert!(find_tool(&tools, "read_file").supports_parallel_tool_calls);
     }
 
+    #[test]
+    fn test_build_specs_includes_system_info_when_enabled() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: false,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            include_system_info_tool: true,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
+            experimental_unified_exec_tool: true,
+        });
+        let (tools, _) = build_specs(&config, None).build();
+
+        assert_eq_tool_names(&tools, &["unified_exec", "system_info"]);
+        assert!(find_tool(&tools, "system_info").supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_includes_conversation_search_when_enabled() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: false,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            include_system_info_tool: false,
+            include_conversation_search_tool: true,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
+            experimental_unified_exec_tool: true,
+        });
+        let (tools, _) = build_specs(&config, None).build();
+
+        assert_eq_tool_names(&tools, &["unified_exec", "conversation_search"]);
+        assert!(find_tool(&tools, "conversation_search").supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_includes_todos_when_enabled() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: false,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: true,
+            include_ask_user_tool: false,
+            experimental_unified_exec_tool: true,
+        });
+        let (tools, _) = build_specs(&config, None).build();
+
+        assert_eq_tool_names(&tools, &["unified_exec", "todos"]);
+        assert!(find_tool(&tools, "todos").supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_includes_ask_user_when_enabled() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: false,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: true,
+            experimental_unified_exec_tool: true,
+        });
+        let (tools, _) = build_specs(&config, None).build();
+
+        assert_eq_tool_names(&tools, &["unified_exec", "ask_user"]);
+        assert!(!find_tool(&tools, "ask_user").supports_parallel_tool_calls);
+    }
+
     #[test]
     fn test_test_model_family_includes_sync_tool() {
         let model_family = find_family_for_model("test-gpt-5-codex")
@@ -976,6 +1255,10 @@ mod tests {
             include_web_search_request: false,
             use_streamable_shell_tool: false,
             include_view_image_tool: false,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: false,
         });
         let (tools, _) = build_specs(&config, None).build();
@@ -1008,6 +1291,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
         let (tools, _) = build_specs(
@@ -1113,6 +1400,10 @@ mod tests {
             include_web_search_request: false,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1190,6 +1481,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1259,6 +1554,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1323,6 +1622,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1390,6 +1693,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
 
@@ -1469,6 +1776,10 @@ mod tests {
             include_web_search_request: true,
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
+            include_system_info_tool: false,
+            include_conversation_search_tool: false,
+            include_todos_tool: false,
+            include_ask_user_tool: false,
             experimental_unified_exec_tool: true,
         });
         let (tools, _) = build_specs(
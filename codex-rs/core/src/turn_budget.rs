@@ -0,0 +1,139 @@
+//! Soft limits on how long a single task may keep the agent busy.
+//!
+//! When a limit is reached the model is asked once to summarize its progress
+//! and stop. Stopping between model turns means every tool call already has
+//! its output recorded, so the session can be resumed with a follow-up
+//! message.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TurnLimit {
+    Duration(Duration),
+    ToolCalls(u32),
+}
+
+impl TurnLimit {
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            TurnLimit::Duration(limit) => {
+                format!("max_turn_duration_sec ({}s)", limit.as_secs())
+            }
+            TurnLimit::ToolCalls(limit) => format!("max_tool_calls_per_turn ({limit})"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TurnBudget {
+    max_duration: Option<Duration>,
+    max_tool_calls: Option<u32>,
+    started_at: Instant,
+    tool_calls: u32,
+    wrap_up_requested: bool,
+}
+
+impl TurnBudget {
+    pub(crate) fn new(max_duration: Option<Duration>, max_tool_calls: Option<u32>) -> Self {
+        Self {
+            max_duration,
+            max_tool_calls,
+            started_at: Instant::now(),
+            tool_calls: 0,
+            wrap_up_requested: false,
+        }
+    }
+
+    pub(crate) fn record_tool_calls(&mut self, count: usize) {
+        let count = u32::try_from(count).unwrap_or(u32::MAX);
+        self.tool_calls = self.tool_calls.saturating_add(count);
+    }
+
+    pub(crate) fn wrap_up_requested(&self) -> bool {
+        self.wrap_up_requested
+    }
+
+    /// Returns the limit that was hit, the first time one is exceeded.
+    /// Subsequent calls return `None` so the wrap-up is only requested once.
+    pub(crate) fn take_exceeded(&mut self) -> Option<TurnLimit> {
+        if self.wrap_up_requested {
+            return None;
+        }
+        let limit = self.exceeded_after(self.started_at.elapsed())?;
+        self.wrap_up_requested = true;
+        Some(limit)
+    }
+
+    fn exceeded_after(&self, elapsed: Duration) -> Option<TurnLimit> {
+        if let Some(max) = self.max_tool_calls
+            && self.tool_calls >= max
+        {
+            return Some(TurnLimit::ToolCalls(max));
+        }
+        if let Some(max) = self.max_duration
+            && elapsed >= max
+        {
+            return Some(TurnLimit::Duration(max));
+        }
+        None
+    }
+}
+
+/// Message injected into the conversation asking the model to stop.
+pub(crate) fn wrap_up_message(limit: TurnLimit) -> ResponseItem {
+    let reason = match limit {
+        TurnLimit::Duration(max) => format!(
+            "This task has been running for more than {} seconds, the configured limit.",
+            max.as_secs()
+        ),
+        TurnLimit::ToolCalls(max) => {
+            format!("This task has made {max} tool calls, the configured limit.")
+        }
+    };
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!(
+                "{reason} Do not call any more tools. Reply with a concise summary of what you have done so far, what remains, and how to continue, then stop."
+            ),
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tool_call_limit_is_reported_once() {
+        let mut budget = TurnBudget::new(None, Some(3));
+        budget.record_tool_calls(2);
+        assert_eq!(budget.take_exceeded(), None);
+
+        budget.record_tool_calls(1);
+        assert_eq!(budget.take_exceeded(), Some(TurnLimit::ToolCalls(3)));
+        assert!(budget.wrap_up_requested());
+        assert_eq!(budget.take_exceeded(), None);
+    }
+
+    #[test]
+    fn duration_limit_uses_elapsed_time() {
+        let budget = TurnBudget::new(Some(Duration::from_secs(60)), None);
+
+        assert_eq!(budget.exceeded_after(Duration::from_secs(59)), None);
+        assert_eq!(
+            budget.exceeded_after(Duration::from_secs(60)),
+            Some(TurnLimit::Duration(Duration::from_secs(60)))
+        );
+        assert_eq!(
+            TurnBudget::new(None, None).exceeded_after(Duration::MAX),
+            None
+        );
+    }
+}
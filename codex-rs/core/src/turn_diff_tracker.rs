@@ -249,6 +249,14 @@ impl TurnDiffTracker {
         }
     }
 
+    /// Size of the aggregated diff for the turn so far.
+    pub fn diff_stats(&mut self) -> DiffStats {
+        match self.get_unified_diff() {
+            Ok(Some(diff)) => DiffStats::from_unified_diff(&diff),
+            _ => DiffStats::default(),
+        }
+    }
+
     fn get_file_diff(&mut self, internal_file_name: &str) -> String {
         let mut aggregated = String::new();
 
@@ -368,6 +376,34 @@ impl TurnDiffTracker {
     }
 }
 
+/// Size of a unified diff: files touched and lines added/removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStats {
+    pub files_changed: u64,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+}
+
+impl DiffStats {
+    pub fn from_unified_diff(diff: &str) -> Self {
+        let mut stats = Self::default();
+        let mut in_hunk = false;
+        for line in diff.lines() {
+            if line.starts_with("diff --git ") {
+                stats.files_changed += 1;
+                in_hunk = false;
+            } else if line.starts_with("@@") {
+                in_hunk = true;
+            } else if in_hunk && line.starts_with('+') {
+                stats.lines_added += 1;
+            } else if in_hunk && line.starts_with('-') {
+                stats.lines_removed += 1;
+            }
+        }
+        stats
+    }
+}
+
 /// Compute the Git SHA-1 blob object ID for the given content (bytes).
 fn git_blob_sha1_hex_bytes(data: &[u8]) -> Output<sha1::Sha1> {
     // Git blob hash is sha1 of: "blob <len>\0<data>"
@@ -569,6 +605,14 @@ index {ZERO_OID}..{right_oid}
             )
         };
         assert_eq!(combined, expected_combined);
+        assert_eq!(
+            acc.diff_stats(),
+            DiffStats {
+                files_changed: 1,
+                lines_added: 2,
+                lines_removed: 0,
+            }
+        );
     }
 
     #[test]
@@ -0,0 +1,283 @@
+//! Guards for `codex exec --unattended`: a run window that ends after a
+//! maximum run time or at a time of day, and a checkpoint of the working
+//! tree taken when the run ends so it can be reviewed in the morning.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::Datelike;
+use chrono::NaiveDateTime;
+use chrono::NaiveTime;
+use chrono::Weekday;
+use tokio::process::Command;
+
+/// Ref namespace the checkpoints are kept under, one per session.
+pub const CHECKPOINT_REF_PREFIX: &str = "refs/codex/checkpoints";
+
+const CHECKPOINT_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "Codex"),
+    ("GIT_AUTHOR_EMAIL", "codex@localhost"),
+    ("GIT_COMMITTER_NAME", "Codex"),
+    ("GIT_COMMITTER_EMAIL", "codex@localhost"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum UnattendedError {
+    #[error("failed to run git: {0}")]
+    Git(#[from] std::io::Error),
+
+    #[error("git {command} failed: {stderr}")]
+    GitFailed { command: String, stderr: String },
+}
+
+/// Parses a run time such as `45m`, `8h` or `1h30m`. A bare number counts
+/// minutes.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let secs = match value.parse::<u64>() {
+        Ok(minutes) => minutes.saturating_mul(60),
+        Err(_) => sum_units(value)?,
+    };
+    if secs == 0 {
+        return Err(format!(
+            "expected a run time longer than zero, got `{value}`"
+        ));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Seconds in `value` written as numbers followed by `h`, `m` or `s`.
+fn sum_units(value: &str) -> Result<u64, String> {
+    let mut secs = 0u64;
+    let mut digits = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(format!("unknown unit `{c}` in `{value}`; use h, m or s")),
+        };
+        let amount: u64 = digits
+            .parse()
+            .map_err(|_| format!("expected a number before `{c}` in `{value}`"))?;
+        secs = secs.saturating_add(amount.saturating_mul(unit));
+        digits.clear();
+    }
+    if !digits.is_empty() {
+        return Err(format!("missing a unit after `{digits}` in `{value}`"));
+    }
+    Ok(secs)
+}
+
+/// A local time of day to stop at, `09:00`, optionally on a given day of
+/// the week, `mon 09:00`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StopAt {
+    weekday: Option<Weekday>,
+    time: NaiveTime,
+}
+
+impl FromStr for StopAt {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.split_whitespace();
+        let (weekday, time) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(time), None, None) => (None, time),
+            (Some(day), Some(time), None) => {
+                let weekday = day
+                    .parse::<Weekday>()
+                    .map_err(|_| format!("unknown day `{day}`; use e.g. `mon`"))?;
+                (Some(weekday), time)
+            }
+            _ => return Err(format!("expected `HH:MM` or `mon HH:MM`, got `{value}`")),
+        };
+        let time = NaiveTime::parse_from_str(time, "%H:%M")
+            .map_err(|_| format!("expected a time as `HH:MM`, got `{time}`"))?;
+        Ok(Self { weekday, time })
+    }
+}
+
+impl StopAt {
+    /// The first moment after `now` matching the time (and day).
+    pub fn next_after(&self, now: NaiveDateTime) -> NaiveDateTime {
+        let mut candidate = now.date().and_time(self.time);
+        if candidate <= now {
+            candidate += chrono::Duration::days(1);
+        }
+        if let Some(weekday) = self.weekday {
+            let days_ahead = (7 + weekday.num_days_from_monday()
+                - candidate.weekday().num_days_from_monday())
+                % 7;
+            candidate += chrono::Duration::days(i64::from(days_ahead));
+        }
+        candidate
+    }
+}
+
+/// How long a run starting at `now` (local time) may go on: until the
+/// earlier of `max_run_time` and `stop_at`. `None` when neither is set.
+pub fn run_window(
+    max_run_time: Option<Duration>,
+    stop_at: Option<StopAt>,
+    now: NaiveDateTime,
+) -> Option<Duration> {
+    let until_stop = stop_at.and_then(|stop_at| (stop_at.next_after(now) - now).to_std().ok());
+    match (max_run_time, until_stop) {
+        (Some(max), Some(until)) => Some(max.min(until)),
+        (max, until) => max.or(until),
+    }
+}
+
+/// Commits the working tree, untracked files included, on top of `HEAD`
+/// without touching the branch, the index or the files, and keeps it as
+/// `refs/codex/checkpoints/<name>`. Returns the ref.
+pub async fn checkpoint(cwd: &Path, name: &str, message: &str) -> Result<String, UnattendedError> {
+    let index_dir = tempfile::tempdir()?;
+    let index = index_dir.path().join("index");
+    let env = [("GIT_INDEX_FILE", index.as_os_str())];
+
+    git(cwd, &["add", "--all"], &env).await?;
+    let tree = git(cwd, &["write-tree"], &env).await?;
+    let head = git(cwd, &["rev-parse", "--verify", "--quiet", "HEAD"], &[])
+        .await
+        .ok();
+    let mut args = vec!["commit-tree", tree.as_str(), "-m", message];
+    if let Some(head) = head.as_deref() {
+        args.extend(["-p", head]);
+    }
+    let identity = CHECKPOINT_IDENTITY.map(|(key, value)| (key, std::ffi::OsStr::new(value)));
+    let commit = git(cwd, &args, &identity).await?;
+
+    let reference = format!("{CHECKPOINT_REF_PREFIX}/{name}");
+    git(cwd, &["update-ref", &reference, &commit], &[]).await?;
+    Ok(reference)
+}
+
+async fn git(
+    cwd: &Path,
+    args: &[&str],
+    env: &[(&str, &std::ffi::OsStr)],
+) -> Result<String, UnattendedError> {
+    let output = Command::new("git")
+        .current_dir(cwd)
+        .args(args)
+        .envs(env.iter().copied())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(UnattendedError::GitFailed {
+            command: args.first().copied().unwrap_or_default().to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use pretty_assertions::assert_eq;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2025-06-02 is a Monday.
+        NaiveDate::from_ymd_opt(2025, 6, day)
+            .and_then(|date| date.and_hms_opt(hour, minute, 0))
+            .expect("valid date")
+    }
+
+    #[test]
+    fn durations_accept_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90 * 60)));
+        assert_eq!(parse_duration("8h"), Ok(Duration::from_secs(8 * 3600)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("45s"), Ok(Duration::from_secs(45)));
+        assert!(parse_duration("2d").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("0m").is_err());
+    }
+
+    #[test]
+    fn stop_at_picks_the_next_matching_time() {
+        let nine: StopAt = "09:00".parse().expect("time");
+        assert_eq!(nine.next_after(at(2, 23, 15)), at(3, 9, 0));
+        assert_eq!(nine.next_after(at(3, 8, 0)), at(3, 9, 0));
+        assert_eq!(nine.next_after(at(3, 9, 0)), at(4, 9, 0));
+
+        let monday: StopAt = "mon 09:00".parse().expect("day and time");
+        // Friday night stops on Monday morning.
+        assert_eq!(monday.next_after(at(6, 22, 0)), at(9, 9, 0));
+        assert_eq!(monday.next_after(at(2, 8, 0)), at(2, 9, 0));
+
+        assert!("25:00".parse::<StopAt>().is_err());
+        assert!("someday 09:00".parse::<StopAt>().is_err());
+    }
+
+    #[test]
+    fn run_window_ends_at_the_earlier_limit() {
+        let nine: StopAt = "09:00".parse().expect("time");
+        let now = at(2, 23, 0);
+        assert_eq!(
+            run_window(None, Some(nine), now),
+            Some(Duration::from_secs(10 * 3600))
+        );
+        assert_eq!(
+            run_window(Some(Duration::from_secs(3600)), Some(nine), now),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(run_window(None, None, now), None);
+    }
+
+    #[tokio::test]
+    async fn checkpoint_keeps_the_working_tree_without_touching_head() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let cwd = repo.path();
+        let run = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .current_dir(cwd)
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .status()
+                .expect("git");
+            assert!(status.success(), "git {args:?} failed");
+        };
+        run(&["init", "--quiet"]);
+        std::fs::write(cwd.join("tracked.txt"), "before").expect("write");
+        run(&["add", "tracked.txt"]);
+        run(&["commit", "--quiet", "-m", "initial"]);
+        std::fs::write(cwd.join("tracked.txt"), "after").expect("write");
+        std::fs::write(cwd.join("new.txt"), "new").expect("write");
+
+        let reference = checkpoint(cwd, "session", "checkpoint")
+            .await
+            .expect("checkpoint");
+        assert_eq!(reference, "refs/codex/checkpoints/session");
+
+        let show = |spec: &str| {
+            let output = std::process::Command::new("git")
+                .current_dir(cwd)
+                .args(["show", spec])
+                .output()
+                .expect("git show");
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        assert_eq!(show(&format!("{reference}:tracked.txt")), "after");
+        assert_eq!(show(&format!("{reference}:new.txt")), "new");
+        assert_eq!(show("HEAD:tracked.txt"), "before");
+        let status = std::process::Command::new("git")
+            .current_dir(cwd)
+            .args(["status", "--porcelain"])
+            .output()
+            .expect("git status");
+        assert_eq!(
+            String::from_utf8_lossy(&status.stdout),
+            " M tracked.txt\n?? new.txt\n"
+        );
+    }
+}
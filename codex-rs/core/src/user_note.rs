@@ -0,0 +1,54 @@
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::USER_NOTE_CLOSE_TAG;
+use codex_protocol::protocol::USER_NOTE_OPEN_TAG;
+
+/// A note the user added to the transcript, wrapped in a tag so the model can
+/// tell it from a request.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UserNote {
+    text: String,
+}
+
+impl UserNote {
+    pub fn new<T: Into<String>>(text: T) -> Self {
+        Self { text: text.into() }
+    }
+
+    /// Serializes the note to an XML-like tagged block that starts with
+    /// <user_note> so clients can classify it.
+    pub fn serialize_to_xml(self) -> String {
+        format!("{USER_NOTE_OPEN_TAG}\n{}\n{USER_NOTE_CLOSE_TAG}", self.text)
+    }
+}
+
+impl From<UserNote> for ResponseInputItem {
+    fn from(note: UserNote) -> Self {
+        ResponseInputItem::Message {
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: note.serialize_to_xml(),
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::InputMessageKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn serialized_note_is_classified_as_a_note() {
+        let xml = UserNote::new("went with the second migration plan").serialize_to_xml();
+        assert_eq!(
+            xml,
+            "<user_note>\nwent with the second migration plan\n</user_note>"
+        );
+        assert!(matches!(
+            InputMessageKind::from(("user", xml.as_str())),
+            InputMessageKind::Note
+        ));
+    }
+}
@@ -56,6 +56,10 @@ pub(crate) enum UserNotification {
 
         /// The last message sent by the assistant in the turn.
         last_assistant_message: Option<String>,
+
+        /// One-line account of files changed and tests run during the turn.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        summary: Option<String>,
     },
 }
 
@@ -72,6 +76,7 @@ mod tests {
             last_assistant_message: Some(
                 "Rename complete and verified `cargo build` succeeds.".to_string(),
             ),
+            summary: None,
         };
         let serialized = serde_json::to_string(&notification)?;
         assert_eq!(
@@ -80,4 +85,20 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_user_notification_with_summary() -> Result<()> {
+        let notification = UserNotification::AgentTurnComplete {
+            turn_id: "7".to_string(),
+            input_messages: Vec::new(),
+            last_assistant_message: None,
+            summary: Some("2 files changed (+10 -3), 1 test run (passed)".to_string()),
+        };
+        let serialized = serde_json::to_string(&notification)?;
+        assert_eq!(
+            serialized,
+            r#"{"type":"agent-turn-complete","turn-id":"7","input-messages":[],"last-assistant-message":null,"summary":"2 files changed (+10 -3), 1 test run (passed)"}"#
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,145 @@
+//! `[verify]`: the build systems found in the working directory when the
+//! session starts, each with the command that checks a change. The agent is
+//! told about them in its instructions, for test-first work, and a
+//! successful `apply_patch` names them as the next step.
+
+use std::path::Path;
+
+use crate::config_types::VerifyCommands;
+
+/// Build systems by name, the files that mark them and their built-in
+/// verify command.
+const BUILD_SYSTEMS: [(&str, &[&str], &str); 6] = [
+    ("cargo", &["Cargo.toml"], "cargo check"),
+    ("npm", &["package.json"], "npm test"),
+    (
+        "pip",
+        &["pyproject.toml", "requirements.txt"],
+        "python3 -m pytest",
+    ),
+    ("go", &["go.mod"], "go test ./..."),
+    ("maven", &["pom.xml"], "mvn -q test"),
+    (
+        "gradle",
+        &["build.gradle", "build.gradle.kts"],
+        "gradle test",
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VerifyCommand {
+    pub(crate) build_system: String,
+    pub(crate) command: String,
+}
+
+/// The verify commands for the build systems with a marker file in `cwd`,
+/// in [`BUILD_SYSTEMS`] order; none unless `verify.enabled` is set.
+pub(crate) fn detect_verify_commands(cwd: &Path, config: &VerifyCommands) -> Vec<VerifyCommand> {
+    if !config.enabled {
+        return Vec::new();
+    }
+    BUILD_SYSTEMS
+        .iter()
+        .filter(|(_, markers, _)| markers.iter().any(|marker| cwd.join(marker).is_file()))
+        .filter_map(|(name, _, default)| {
+            let command = config
+                .commands
+                .get(*name)
+                .map_or(*default, String::as_str)
+                .trim();
+            (!command.is_empty()).then(|| VerifyCommand {
+                build_system: (*name).to_string(),
+                command: command.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The commands as one, for the `verify_with` of a successful patch.
+pub(crate) fn combined_command(commands: &[VerifyCommand]) -> Option<String> {
+    if commands.is_empty() {
+        return None;
+    }
+    let commands: Vec<&str> = commands.iter().map(|c| c.command.as_str()).collect();
+    Some(commands.join(" && "))
+}
+
+/// `instructions` followed by the verify commands, when there are any.
+pub(crate) fn with_verify_instructions(
+    instructions: Option<String>,
+    commands: &[VerifyCommand],
+) -> Option<String> {
+    if commands.is_empty() {
+        return instructions;
+    }
+    let list: String = commands
+        .iter()
+        .map(|c| format!("- {}: `{}`\n", c.build_system, c.command))
+        .collect();
+    let section = format!(
+        "<verify_commands>\n\
+         Check changes with the verify commands of this workspace:\n{list}\
+         When working test first, run them to see the new test fail before the change and pass after it. \
+         Run them again after editing files, and fix what they report before you finish.\n\
+         </verify_commands>"
+    );
+    Some(match instructions {
+        Some(instructions) => format!("{instructions}\n\n{section}"),
+        None => section,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn command(build_system: &str, command: &str) -> VerifyCommand {
+        VerifyCommand {
+            build_system: build_system.to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn commands_follow_the_build_systems_in_the_workspace() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("Cargo.toml"), "").expect("write");
+        std::fs::write(dir.path().join("package.json"), "{}").expect("write");
+        std::fs::write(dir.path().join("go.mod"), "").expect("write");
+
+        let mut config = VerifyCommands::default();
+        assert_eq!(detect_verify_commands(dir.path(), &config), Vec::new());
+
+        config.enabled = true;
+        config.commands = HashMap::from([
+            ("npm".to_string(), "npm run check".to_string()),
+            ("go".to_string(), String::new()),
+        ]);
+        assert_eq!(
+            detect_verify_commands(dir.path(), &config),
+            vec![
+                command("cargo", "cargo check"),
+                command("npm", "npm run check")
+            ]
+        );
+    }
+
+    #[test]
+    fn instructions_list_the_commands() {
+        let commands = vec![command("cargo", "cargo check")];
+        let instructions =
+            with_verify_instructions(Some("Be brief.".to_string()), &commands).expect("some");
+        assert!(instructions.starts_with("Be brief.\n\n<verify_commands>\n"));
+        assert!(instructions.contains("- cargo: `cargo check`\n"));
+        assert_eq!(
+            with_verify_instructions(Some("Be brief.".to_string()), &[]),
+            Some("Be brief.".to_string())
+        );
+        assert_eq!(
+            combined_command(&[command("cargo", "cargo check"), command("npm", "npm test")]),
+            Some("cargo check && npm test".to_string())
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Session-wide limit on how much the agent writes to files.
+//!
+//! Every applied patch counts the size of the files it leaves behind, so a
+//! loop that keeps regenerating files eventually stops for approval instead
+//! of filling the disk. Files written by shell commands are not counted.
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+
+#[derive(Debug, Default)]
+pub(crate) struct WriteQuota {
+    /// The configured quota.
+    quota: Option<u64>,
+    /// Where writing has to stop, raised each time the user allows more.
+    limit: Option<u64>,
+    written: u64,
+}
+
+impl WriteQuota {
+    pub(crate) fn new(quota: Option<u64>) -> Self {
+        Self {
+            quota,
+            limit: quota,
+            written: 0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, bytes: u64) {
+        self.written = self.written.saturating_add(bytes);
+    }
+
+    /// Why writing `bytes` more would go over the quota, if it would.
+    pub(crate) fn overrun(&self, bytes: u64) -> Option<String> {
+        let limit = self.limit?;
+        if self.written.saturating_add(bytes) <= limit {
+            return None;
+        }
+        Some(format!(
+            "This patch writes {} but only {} of the session's {} write quota is left.",
+            format_bytes(bytes),
+            format_bytes(limit.saturating_sub(self.written)),
+            format_bytes(limit),
+        ))
+    }
+
+    /// Makes room for `bytes` more plus another full quota, after the user
+    /// allowed a write that went over it.
+    pub(crate) fn extend(&mut self, bytes: u64) {
+        if let Some(quota) = self.quota {
+            self.limit = Some(self.written.saturating_add(bytes).saturating_add(quota));
+        }
+    }
+}
+
+/// Bytes a patch writes: the full contents of every added or updated file.
+pub(crate) fn patch_write_bytes(action: &ApplyPatchAction) -> u64 {
+    action
+        .changes()
+        .values()
+        .map(|change| match change {
+            ApplyPatchFileChange::Add { content } => content.len() as u64,
+            ApplyPatchFileChange::Update { new_content, .. } => new_content.len() as u64,
+            ApplyPatchFileChange::Delete { .. } => 0,
+        })
+        .sum()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = KIB * 1024;
+    if bytes >= MIB {
+        format!("{:.1} MB", bytes as f64 / MIB as f64)
+    } else if bytes >= KIB {
+        format!("{:.1} KB", bytes as f64 / KIB as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn writes_within_the_quota_pass() {
+        let mut quota = WriteQuota::new(Some(100));
+        quota.record(60);
+        assert_eq!(quota.overrun(40), None);
+        assert_eq!(
+            quota.overrun(41),
+            Some(
+                "This patch writes 41 B but only 40 B of the session's 100 B write quota is left."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn extending_makes_room_for_another_quota() {
+        let mut quota = WriteQuota::new(Some(100));
+        quota.record(90);
+        quota.extend(50);
+        quota.record(50);
+        assert_eq!(quota.overrun(100), None);
+        assert!(quota.overrun(101).is_some());
+
+        // Each extension adds the configured quota, not the last limit.
+        quota.extend(10);
+        quota.record(10);
+        assert_eq!(quota.overrun(100), None);
+        assert!(quota.overrun(101).is_some());
+    }
+
+    #[test]
+    fn no_limit_never_overruns() {
+        let mut quota = WriteQuota::new(None);
+        quota.record(u64::MAX);
+        assert_eq!(quota.overrun(u64::MAX), None);
+    }
+
+    #[test]
+    fn patch_bytes_count_added_content() {
+        let dir = tempfile::tempdir().expect("tmp");
+        let action =
+            ApplyPatchAction::new_add_for_test(&dir.path().join("a.txt"), "hello".to_string());
+        assert_eq!(patch_write_bytes(&action), 5);
+    }
+}
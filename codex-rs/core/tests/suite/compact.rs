@@ -4,6 +4,7 @@ use codex_core::ModelProviderInfo;
 use codex_core::NewConversation;
 use codex_core::built_in_model_providers;
 use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
@@ -586,13 +587,14 @@ async fn auto_compact_stops_after_failed_attempt() {
         .unwrap();
 
     let error_event = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
-    let EventMsg::Error(ErrorEvent { message }) = error_event else {
+    let EventMsg::Error(ErrorEvent { message, kind }) = error_event else {
         panic!("expected error event");
     };
     assert!(
         message.contains("limit"),
         "error message should include limit information: {message}"
     );
+    assert_eq!(kind, ErrorKind::Provider);
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
 
     let requests = server.received_requests().await.unwrap();
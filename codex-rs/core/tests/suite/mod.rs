@@ -18,6 +18,7 @@ mod model_tools;
 mod otel;
 mod prompt_caching;
 mod read_file;
+mod replay;
 mod review;
 mod rmcp_client;
 mod rollout_list_find;
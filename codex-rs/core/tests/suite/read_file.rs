@@ -93,7 +93,10 @@ async fn read_file_tool_returns_requested_lines() -> anyhow::Result<()> {
             _ => None,
         })
         .expect("output text present");
-    assert_eq!(output_text, "L2: second\nL3: third");
+    assert_eq!(
+        output_text,
+        "[size: 26 bytes, encoding: utf-8, lines: 4, showing: L2-L3, next offset: 4]\nL2: second\nL3: third"
+    );
 
     Ok(())
 }
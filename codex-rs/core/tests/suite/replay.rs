@@ -0,0 +1,152 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used)]
+
+use codex_core::CodexConversation;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::replay::ReplayMode;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::responses;
+use core_test_support::responses::ev_apply_patch_function_call;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_local_shell_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use wiremock::matchers::any;
+
+/// The command output and final agent message of one turn.
+async fn run_turn(codex: &CodexConversation, cwd: &Path, model: String) -> (String, String) {
+    codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "run the command and write the notes".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await
+        .expect("submit turn");
+
+    let mut stdout = String::new();
+    let mut message = String::new();
+    wait_for_event(codex, |event| match event {
+        EventMsg::ExecCommandEnd(end) if end.call_id == "shell-call" => {
+            stdout = end.stdout.clone();
+            false
+        }
+        EventMsg::AgentMessage(agent) => {
+            message = agent.message.clone();
+            false
+        }
+        EventMsg::TaskComplete(_) => true,
+        _ => false,
+    })
+    .await;
+    (stdout, message)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn recorded_session_replays_without_model_or_commands() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let log_dir = TempDir::new()?;
+    let log_path = log_dir.path().join("session.jsonl");
+
+    let server = start_mock_server().await;
+    let record_path = log_path.clone();
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex()
+        .with_config(move |config| {
+            config.include_apply_patch_tool = true;
+            config.replay = Some(ReplayMode::Record(record_path));
+        })
+        .build(&server)
+        .await?;
+
+    let patch = "*** Begin Patch\n*** Add File: notes.txt\n+replayed notes\n*** End Patch";
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_local_shell_call("shell-call", "completed", vec!["/bin/echo", "recorded"]),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_response_created("resp-2"),
+            ev_apply_patch_function_call("patch-call", patch),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_assistant_message("msg-1", "all done"),
+            ev_completed("resp-3"),
+        ]),
+    )
+    .await;
+
+    let recorded = run_turn(&codex, cwd.path(), session_configured.model.clone()).await;
+    assert_eq!(recorded, ("recorded\n".to_string(), "all done".to_string()));
+
+    // Replay against a server that answers nothing, in a fresh directory.
+    let empty_server = start_mock_server().await;
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex()
+        .with_config(move |config| {
+            config.include_apply_patch_tool = true;
+            config.replay = Some(ReplayMode::Replay(log_path));
+        })
+        .build(&empty_server)
+        .await?;
+
+    let replayed = run_turn(&codex, cwd.path(), session_configured.model.clone()).await;
+    assert_eq!(replayed, recorded);
+    assert_eq!(
+        std::fs::read_to_string(cwd.path().join("notes.txt"))?,
+        "replayed notes\n"
+    );
+    assert_eq!(
+        empty_server
+            .received_requests()
+            .await
+            .expect("request recording enabled")
+            .len(),
+        0
+    );
+
+    Ok(())
+}
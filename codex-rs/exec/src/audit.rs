@@ -0,0 +1,167 @@
+//! `codex audit-code`: a security review of the current changes or of
+//! selected paths against curated rule packs, run by the agent in a
+//! read-only session and reported as text, JSON or SARIF.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::SessionSource;
+use codex_core::sarif::sarif_log;
+use codex_core::security_audit::AuditReport;
+use codex_core::security_audit::AuditScope;
+use codex_core::security_audit::DEFAULT_RULE_PACKS;
+use codex_core::security_audit::RULE_PACKS;
+use codex_core::security_audit::audit_prompt;
+use codex_core::security_audit::report_schema;
+use codex_core::security_audit::rule_pack;
+use codex_protocol::config_types::SandboxMode;
+
+use crate::single_turn::run_prompt;
+
+#[derive(Parser, Debug)]
+pub struct AuditCli {
+    /// Files or directories to audit in full. Without paths, the
+    /// uncommitted changes (or the branch, with --base) are audited.
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<PathBuf>,
+
+    /// Rule packs to apply, comma separated: owasp, secrets, crypto, memory.
+    #[arg(long, value_delimiter = ',', default_values = DEFAULT_RULE_PACKS.iter().copied())]
+    pub rules: Vec<String>,
+
+    /// Audit the changes of the current branch since it left this branch.
+    #[arg(long, value_name = "BRANCH", conflicts_with = "paths")]
+    pub base: Option<String>,
+
+    /// How to print the findings.
+    #[arg(long, value_enum, default_value_t = AuditFormat::Text)]
+    pub format: AuditFormat,
+
+    /// Write the findings to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Model the agent should use.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Repository to audit; defaults to the current directory.
+    #[clap(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum AuditFormat {
+    Text,
+    Json,
+    /// SARIF 2.1.0, e.g. for GitHub code scanning.
+    Sarif,
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+pub async fn run_audit(
+    cli: AuditCli,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let AuditCli {
+        paths,
+        rules,
+        base,
+        format,
+        output,
+        model,
+        config_profile,
+        cwd,
+        config_overrides,
+    } = cli;
+
+    let mut packs = Vec::with_capacity(rules.len());
+    for id in &rules {
+        let Some(pack) = rule_pack(id.trim()) else {
+            let known: Vec<&str> = RULE_PACKS.iter().map(|pack| pack.id).collect();
+            anyhow::bail!("unknown rule pack `{id}`; available: {}", known.join(", "));
+        };
+        packs.push(pack);
+    }
+    let scope = match (base, paths.is_empty()) {
+        (Some(base), _) => AuditScope::Branch { base },
+        (None, true) => AuditScope::WorkingTree,
+        (None, false) => AuditScope::Paths(paths),
+    };
+
+    let overrides = ConfigOverrides {
+        model,
+        config_profile,
+        // The agent only reads the code; nobody is there to approve.
+        approval_policy: Some(AskForApproval::Never),
+        sandbox_mode: Some(SandboxMode::ReadOnly),
+        cwd: cwd.map(|p| p.canonicalize().unwrap_or(p)),
+        codex_linux_sandbox_exe,
+        ..Default::default()
+    };
+    let cli_kv_overrides = config_overrides
+        .parse_overrides()
+        .map_err(|e| anyhow::anyhow!("Error parsing -c overrides: {e}"))?;
+    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+
+    let auth_manager = AuthManager::shared(config.codex_home.clone(), true);
+    let conversation_manager = ConversationManager::new(auth_manager, SessionSource::Exec);
+
+    eprintln!(
+        "Auditing with rule packs: {}…",
+        packs
+            .iter()
+            .map(|pack| pack.id)
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let prompt = audit_prompt(&packs, &scope);
+    let message = run_prompt(
+        &conversation_manager,
+        &config,
+        prompt,
+        Some(report_schema()),
+    )
+    .await?;
+    let mut report =
+        AuditReport::parse(&message).context("the agent's findings are not valid JSON")?;
+    report.relativize(&config.cwd);
+
+    let rendered = match format {
+        AuditFormat::Text => report.render_text(),
+        AuditFormat::Json => serde_json::to_string_pretty(&report)?,
+        AuditFormat::Sarif => {
+            let (rules, results) = report.to_sarif();
+            let log = sarif_log(
+                "codex audit-code",
+                env!("CARGO_PKG_VERSION"),
+                &rules,
+                &results,
+            );
+            serde_json::to_string_pretty(&log)?
+        }
+    };
+    match output {
+        Some(path) => std::fs::write(&path, format!("{rendered}\n"))
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => println!("{rendered}"),
+    }
+    eprintln!("{} finding(s).", report.findings.len());
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! `codex changelog`: release notes for a range of commits, written by the
+//! agent in a read-only session. Long ranges are summarized in chunks whose
+//! notes are then merged, so every commit gets looked at.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::changelog::ChangelogFormat;
+use codex_core::changelog::ChangelogRequest;
+use codex_core::changelog::chunk_commits;
+use codex_core::changelog::commit_date;
+use codex_core::changelog::commits_in_range;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::SessionSource;
+use codex_protocol::config_types::SandboxMode;
+
+use crate::single_turn::run_prompt;
+
+#[derive(Parser, Debug)]
+pub struct ChangelogCli {
+    /// Start of the range (exclusive), e.g. the previous release tag.
+    #[arg(long, value_name = "REV")]
+    pub from: String,
+
+    /// End of the range (inclusive).
+    #[arg(long, value_name = "REV", default_value = "HEAD")]
+    pub to: String,
+
+    /// Layout of the release notes.
+    #[arg(long, value_enum, default_value_t = ChangelogFormatArg::Markdown)]
+    pub format: ChangelogFormatArg,
+
+    /// Write the release notes to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Model the agent should use.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Repository to read the commits from; defaults to the current directory.
+    #[clap(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ChangelogFormatArg {
+    /// Sections per area of the codebase.
+    Markdown,
+    /// Added/Changed/Fixed/... sections as on keepachangelog.com.
+    KeepAChangelog,
+}
+
+impl From<ChangelogFormatArg> for ChangelogFormat {
+    fn from(value: ChangelogFormatArg) -> Self {
+        match value {
+            ChangelogFormatArg::Markdown => ChangelogFormat::Markdown,
+            ChangelogFormatArg::KeepAChangelog => ChangelogFormat::KeepAChangelog,
+        }
+    }
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+pub async fn run_changelog(
+    cli: ChangelogCli,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let ChangelogCli {
+        from,
+        to,
+        format,
+        output,
+        model,
+        config_profile,
+        cwd,
+        config_overrides,
+    } = cli;
+
+    let overrides = ConfigOverrides {
+        model,
+        config_profile,
+        // The agent only reads the repository; nobody is there to approve.
+        approval_policy: Some(AskForApproval::Never),
+        sandbox_mode: Some(SandboxMode::ReadOnly),
+        cwd: cwd.map(|p| p.canonicalize().unwrap_or(p)),
+        codex_linux_sandbox_exe,
+        ..Default::default()
+    };
+    let cli_kv_overrides = config_overrides
+        .parse_overrides()
+        .map_err(|e| anyhow::anyhow!("Error parsing -c overrides: {e}"))?;
+    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+
+    let commits = commits_in_range(&config.cwd, &from, &to).await?;
+    let request = ChangelogRequest {
+        date: commit_date(&config.cwd, &to).await,
+        from,
+        to,
+        format: format.into(),
+    };
+
+    let auth_manager = AuthManager::shared(config.codex_home.clone(), true);
+    let conversation_manager = ConversationManager::new(auth_manager, SessionSource::Exec);
+
+    let chunks = chunk_commits(&commits);
+    let prompt = if let [chunk] = chunks.as_slice() {
+        eprintln!("Summarizing {} commit(s)…", chunk.len());
+        request.single_prompt(chunk)
+    } else {
+        let mut notes = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            eprintln!(
+                "Summarizing part {} of {} ({} commit(s))…",
+                index + 1,
+                chunks.len(),
+                chunk.len()
+            );
+            let prompt = request.chunk_prompt(chunk, index, chunks.len());
+            notes.push(run_prompt(&conversation_manager, &config, prompt, None).await?);
+        }
+        eprintln!("Writing release notes…");
+        request.merge_prompt(&notes)
+    };
+    let notes = run_prompt(&conversation_manager, &config, prompt, None).await?;
+
+    match output {
+        Some(path) => std::fs::write(&path, format!("{}\n", notes.trim_end()))
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => println!("{}", notes.trim_end()),
+    }
+    Ok(())
+}
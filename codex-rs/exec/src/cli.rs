@@ -1,7 +1,10 @@
 use clap::Parser;
 use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
+use codex_core::unattended::StopAt;
+use codex_core::unattended::parse_duration;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(version)]
@@ -56,6 +59,16 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Record the model responses and command results of this session to FILE,
+    /// for replaying it later with `--replay`.
+    #[arg(long = "record-replay", value_name = "FILE", conflicts_with = "replay")]
+    pub record_replay: Option<PathBuf>,
+
+    /// Replay a session recorded with `--record-replay` from FILE, without
+    /// contacting the model or running commands.
+    #[arg(long = "replay", value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -71,6 +84,25 @@ pub struct Cli {
     #[arg(long = "include-plan-tool", default_value_t = false)]
     pub include_plan_tool: bool,
 
+    /// Guard an unattended run: when its window ends, ask the agent for a
+    /// summary and stop, and checkpoint the working tree when it finishes.
+    #[arg(long = "unattended", default_value_t = false)]
+    pub unattended: bool,
+
+    /// Longest the unattended run may take, e.g. `8h` or `1h30m`.
+    #[arg(
+        long = "max-run-time",
+        value_name = "DURATION",
+        requires = "unattended",
+        value_parser = parse_duration
+    )]
+    pub max_run_time: Option<Duration>,
+
+    /// Local time at which the unattended run stops for review, e.g. `09:00`
+    /// or `mon 09:00`.
+    #[arg(long = "stop-at", value_name = "TIME", requires = "unattended")]
+    pub stop_at: Option<StopAt>,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -17,6 +17,8 @@ use crate::exec_events::McpToolCallItem;
 use crate::exec_events::McpToolCallStatus;
 use crate::exec_events::PatchApplyStatus;
 use crate::exec_events::PatchChangeKind;
+use crate::exec_events::QuestionKind;
+use crate::exec_events::QuestionRequestedEvent;
 use crate::exec_events::ReasoningItem;
 use crate::exec_events::ThreadErrorEvent;
 use crate::exec_events::ThreadEvent;
@@ -33,6 +35,7 @@ use crate::exec_events::WebSearchItem;
 use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningEvent;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
@@ -45,6 +48,8 @@ use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
+use codex_core::protocol::UserQuestionKind;
+use codex_core::protocol::UserQuestionRequestEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -121,14 +126,23 @@ impl EventProcessorWithJsonOutput {
             EventMsg::Error(ev) => {
                 let error = ThreadErrorEvent {
                     message: ev.message.clone(),
+                    code: ev.kind,
                 };
                 self.last_critical_error = Some(error.clone());
                 vec![ThreadEvent::Error(error)]
             }
+            // The request is being retried: the connection broke off, or the
+            // provider rate limited it.
             EventMsg::StreamError(ev) => vec![ThreadEvent::Error(ThreadErrorEvent {
                 message: ev.message.clone(),
+                code: if ev.rate_limit_retry_after_ms.is_some() {
+                    ErrorKind::Provider
+                } else {
+                    ErrorKind::Network
+                },
             })],
             EventMsg::PlanUpdate(ev) => self.handle_plan_update(ev),
+            EventMsg::UserQuestionRequest(ev) => Self::handle_user_question(ev),
             _ => Vec::new(),
         }
     }
@@ -358,6 +372,21 @@ impl EventProcessorWithJsonOutput {
             .collect()
     }
 
+    fn handle_user_question(ev: &UserQuestionRequestEvent) -> Vec<ThreadEvent> {
+        let kind = match &ev.kind {
+            UserQuestionKind::Choice { options } => QuestionKind::Choice {
+                options: options.clone(),
+            },
+            UserQuestionKind::Text { .. } => QuestionKind::Text,
+            UserQuestionKind::File => QuestionKind::File,
+        };
+        vec![ThreadEvent::QuestionRequested(QuestionRequestedEvent {
+            call_id: ev.call_id.clone(),
+            question: ev.question.clone(),
+            kind,
+        })]
+    }
+
     fn handle_plan_update(&mut self, args: &UpdatePlanArgs) -> Vec<ThreadEvent> {
         let items = self.todo_items_from_plan(args);
 
@@ -444,7 +473,10 @@ impl EventProcessor for EventProcessorWithJsonOutput {
 
         let Event { msg, .. } = event;
 
-        if let EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) = msg {
+        if let EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message, ..
+        }) = msg
+        {
             if let Some(output_file) = self.last_message_path.as_deref() {
                 handle_last_message(last_agent_message.as_deref(), output_file);
             }
@@ -1,3 +1,4 @@
+use codex_protocol::protocol::ErrorKind;
 use serde::Deserialize;
 use serde::Serialize;
 use ts_rs::TS;
@@ -31,6 +32,10 @@ pub enum ThreadEvent {
     /// Represents an unrecoverable error emitted directly by the event stream.
     #[serde(rename = "error")]
     Error(ThreadErrorEvent),
+    /// The agent asked the user a question. Answer it by writing a
+    /// [`QuestionAnswer`] line to stdin.
+    #[serde(rename = "question.requested")]
+    QuestionRequested(QuestionRequestedEvent),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
@@ -83,6 +88,10 @@ pub struct ItemUpdatedEvent {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
 pub struct ThreadErrorEvent {
     pub message: String,
+    /// What failed: `auth`, `network`, `provider`, `sandbox`, `patch`,
+    /// `config` or `internal`.
+    #[serde(default)]
+    pub code: ErrorKind,
 }
 
 /// Canonical representation of a thread item and its domain-specific payload.
@@ -226,3 +235,30 @@ pub struct TodoItem {
 pub struct TodoListItem {
     pub items: Vec<TodoItem>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct QuestionRequestedEvent {
+    pub call_id: String,
+    pub question: String,
+    pub kind: QuestionKind,
+}
+
+/// The form a question is answered in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuestionKind {
+    /// Answer with one of `options`.
+    Choice { options: Vec<String> },
+    /// Answer with free-form text.
+    Text,
+    /// Answer with a file path.
+    File,
+}
+
+/// A line written to stdin to answer a [`QuestionRequestedEvent`]. A `null`
+/// answer dismisses the question.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct QuestionAnswer {
+    pub call_id: String,
+    pub answer: Option<String>,
+}
@@ -4,37 +4,54 @@
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+pub mod audit;
+pub mod changelog;
 mod cli;
 mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+pub mod review;
+mod single_turn;
 
+use chrono::Local;
 pub use cli::Cli;
 use codex_core::AuthManager;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::replay::ReplayMode;
+use codex_core::unattended::checkpoint;
+use codex_core::unattended::run_window;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
+use exec_events::QuestionAnswer;
 use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use supports_color::Stream;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::BufReader;
+use tokio::time::Instant;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -47,6 +64,10 @@ use crate::event_processor::EventProcessor;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_conversation_path_by_id_str;
 
+/// How long an agent may keep going after the unattended run window ended
+/// and it was asked to wrap up, before it is interrupted.
+const UNATTENDED_GRACE_PERIOD: Duration = Duration::from_secs(10 * 60);
+
 pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
     if let Err(err) = set_default_originator("codex_exec".to_string()) {
         tracing::warn!(?err, "Failed to set codex exec originator override {err:?}");
@@ -69,6 +90,11 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         prompt,
         output_schema: output_schema_path,
         include_plan_tool,
+        record_replay,
+        replay,
+        unattended,
+        max_run_time,
+        stop_at,
         config_overrides,
     } = cli;
 
@@ -80,6 +106,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         None => prompt,
     };
 
+    // In --json mode a wrapper may answer the agent's questions over stdin,
+    // unless the prompt was read from it.
+    let mut answers_on_stdin = json_mode;
     let prompt = match prompt_arg {
         Some(p) if p != "-" => p,
         // Either `-` was passed or no positional arg.
@@ -102,6 +131,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             if !force_stdin {
                 eprintln!("Reading prompt from stdin...");
             }
+            answers_on_stdin = false;
             let mut buffer = String::new();
             if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
                 eprintln!("Failed to read prompt from stdin: {e}");
@@ -187,11 +217,35 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         Ok(v) => v,
         Err(e) => {
             eprintln!("Error parsing -c overrides: {e}");
-            std::process::exit(1);
+            std::process::exit(ErrorKind::Config.exit_code());
         }
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+    let mut config = match Config::load_with_cli_overrides(cli_kv_overrides, overrides).await {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading configuration: {e}");
+            std::process::exit(ErrorKind::Config.exit_code());
+        }
+    };
+    if let Some(notice) = config.state_home.notice() {
+        eprintln!("{notice}");
+    }
+    config.replay = match (record_replay, replay) {
+        (Some(path), _) => Some(ReplayMode::Record(path)),
+        (None, Some(path)) => Some(ReplayMode::Replay(path)),
+        (None, None) => None,
+    };
+    // The run window ends the task the way `max_turn_duration_sec` does:
+    // the agent is asked to summarize its progress and stop.
+    let unattended_window = run_window(max_run_time, stop_at, Local::now().naive_local());
+    if let Some(window) = unattended_window {
+        config.max_turn_duration = Some(
+            config
+                .max_turn_duration
+                .map_or(window, |limit| limit.min(window)),
+        );
+    }
 
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
 
@@ -249,7 +303,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
 
     // Handle resume subcommand by resolving a rollout path and using explicit resume API.
     let NewConversation {
-        conversation_id: _,
+        conversation_id,
         conversation,
         session_configured,
     } = if let Some(ExecCommand::Resume(args)) = command {
@@ -314,6 +368,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         });
     }
 
+    if answers_on_stdin {
+        tokio::spawn(forward_question_answers(conversation.clone()));
+    }
+
     // Send images first, if any.
     if !images.is_empty() {
         let items: Vec<InputItem> = images
@@ -324,12 +382,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         info!("Sent images with event ID: {initial_images_event_id}");
         while let Ok(event) = conversation.next_event().await {
             if event.id == initial_images_event_id
-                && matches!(
-                    event.msg,
-                    EventMsg::TaskComplete(TaskCompleteEvent {
-                        last_agent_message: _,
-                    })
-                )
+                && matches!(event.msg, EventMsg::TaskComplete(TaskCompleteEvent { .. }))
             {
                 break;
             }
@@ -341,7 +394,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     let initial_prompt_task_id = conversation
         .submit(Op::UserTurn {
             items,
-            cwd: default_cwd,
+            cwd: default_cwd.clone(),
             approval_policy: default_approval_policy,
             sandbox_policy: default_sandbox_policy,
             model: default_model,
@@ -353,12 +406,41 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
-    // Track whether a fatal error was reported by the server so we can
-    // exit with a non-zero status for automation-friendly signaling.
-    let mut error_seen = false;
-    while let Some(event) = rx.recv().await {
-        if matches!(event.msg, EventMsg::Error(_)) {
-            error_seen = true;
+    // Track the last fatal error reported by the server so we can exit with
+    // its kind's status for automation-friendly signaling.
+    let mut error_seen: Option<ErrorKind> = None;
+    // An agent still busy in a long command when the window ends is
+    // interrupted once the grace period is over too.
+    let hard_stop = unattended_window.and_then(|window| {
+        Instant::now().checked_add(window.saturating_add(UNATTENDED_GRACE_PERIOD))
+    });
+    let mut interrupted = false;
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            _ = sleep_until_deadline(hard_stop), if !interrupted => {
+                eprintln!("The unattended run window has ended; interrupting the agent.");
+                conversation.submit(Op::Interrupt).await?;
+                interrupted = true;
+                continue;
+            }
+        };
+        if let EventMsg::Error(error) = &event.msg {
+            error_seen = Some(error.kind);
+        }
+        // Nobody can answer the agent's questions; let it carry on.
+        if let EventMsg::UserQuestionRequest(request) = &event.msg
+            && !answers_on_stdin
+        {
+            conversation
+                .submit(Op::UserQuestionAnswer {
+                    call_id: request.call_id.clone(),
+                    answer: None,
+                })
+                .await?;
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
@@ -372,19 +454,74 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
-    if error_seen {
-        std::process::exit(1);
+    if unattended {
+        report_checkpoint(&default_cwd, &conversation_id.to_string()).await;
+    }
+    if let Some(kind) = error_seen {
+        std::process::exit(kind.exit_code());
     }
 
     Ok(())
 }
 
+/// Reads [`QuestionAnswer`] lines from stdin and passes them on to the
+/// agent until stdin closes.
+async fn forward_question_answers(conversation: Arc<CodexConversation>) {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(err) => {
+                error!("Failed to read answers from stdin: {err}");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<QuestionAnswer>(&line) {
+            Ok(QuestionAnswer { call_id, answer }) => {
+                if let Err(err) = conversation
+                    .submit(Op::UserQuestionAnswer { call_id, answer })
+                    .await
+                {
+                    error!("Failed to submit answer: {err}");
+                    break;
+                }
+            }
+            Err(err) => eprintln!("Ignoring invalid answer on stdin: {err}"),
+        }
+    }
+}
+
+/// Waits for `deadline`, or forever without one.
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Checkpoints the working tree at the end of an unattended run and tells
+/// the user how to review it and pick the session up again.
+async fn report_checkpoint(cwd: &Path, session_id: &str) {
+    let message = format!("codex exec --unattended checkpoint for session {session_id}");
+    match checkpoint(cwd, session_id, &message).await {
+        Ok(reference) => eprintln!(
+            "Checkpoint of the working tree saved as {reference}.\n\
+             Review it with `git diff HEAD {reference}` and continue with `codex exec resume {session_id}`."
+        ),
+        Err(err) => eprintln!("Failed to checkpoint the working tree: {err}"),
+    }
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
 ) -> anyhow::Result<Option<PathBuf>> {
     if args.last {
-        match codex_core::RolloutRecorder::list_conversations(&config.codex_home, 1, None, &[])
+        match codex_core::RolloutRecorder::list_conversations(&config.state_home.path, 1, None, &[])
             .await
         {
             Ok(page) => Ok(page.items.first().map(|it| it.path.clone())),
@@ -394,7 +531,7 @@ async fn resolve_resume_path(
             }
         }
     } else if let Some(id_str) = args.session_id.as_deref() {
-        let path = find_conversation_path_by_id_str(&config.codex_home, id_str).await?;
+        let path = find_conversation_path_by_id_str(&config.state_home.path, id_str).await?;
         Ok(path)
     } else {
         Ok(None)
@@ -0,0 +1,169 @@
+//! `codex review`: the `/review` code review without a UI, printed as text,
+//! JSON or SARIF so the findings can be uploaded to code scanning.
+
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::Parser;
+use clap::ValueEnum;
+use codex_common::CliConfigOverrides;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::SessionSource;
+use codex_core::review_format::base_branch_review_request;
+use codex_core::review_format::commit_review_request;
+use codex_core::review_format::format_review_findings_block;
+use codex_core::review_format::review_findings_to_sarif;
+use codex_core::review_format::uncommitted_changes_review_request;
+use codex_core::sarif::sarif_log;
+use codex_protocol::config_types::SandboxMode;
+
+#[derive(Parser, Debug)]
+pub struct ReviewCli {
+    /// Custom review instructions. Without them, the uncommitted changes are
+    /// reviewed (or the branch, with --base, or a commit, with --commit).
+    #[arg(value_name = "INSTRUCTIONS", conflicts_with_all = ["base", "commit"])]
+    pub instructions: Option<String>,
+
+    /// Review what the current branch would merge into this branch.
+    #[arg(long, value_name = "BRANCH", conflicts_with = "commit")]
+    pub base: Option<String>,
+
+    /// Review the changes introduced by this commit.
+    #[arg(long, value_name = "SHA")]
+    pub commit: Option<String>,
+
+    /// How to print the findings.
+    #[arg(long, value_enum, default_value_t = ReviewFormat::Text)]
+    pub format: ReviewFormat,
+
+    /// Write the findings to this file instead of stdout.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Model the agent should use; defaults to `review_model`.
+    #[arg(long, short = 'm')]
+    pub model: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[arg(long = "profile", short = 'p')]
+    pub config_profile: Option<String>,
+
+    /// Repository to review; defaults to the current directory.
+    #[clap(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ReviewFormat {
+    Text,
+    Json,
+    /// SARIF 2.1.0, e.g. for GitHub code scanning.
+    Sarif,
+}
+
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+pub async fn run_review(
+    cli: ReviewCli,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let ReviewCli {
+        instructions,
+        base,
+        commit,
+        format,
+        output,
+        model,
+        config_profile,
+        cwd,
+        config_overrides,
+    } = cli;
+
+    let review_request = match (instructions, base, commit) {
+        (Some(instructions), _, _) => ReviewRequest {
+            prompt: instructions.clone(),
+            user_facing_hint: instructions,
+        },
+        (None, Some(base), _) => base_branch_review_request(&base),
+        (None, None, Some(sha)) => commit_review_request(&sha, None),
+        (None, None, None) => uncommitted_changes_review_request(),
+    };
+
+    let overrides = ConfigOverrides {
+        review_model: model,
+        config_profile,
+        // The reviewer only reads the code; nobody is there to approve.
+        approval_policy: Some(AskForApproval::Never),
+        sandbox_mode: Some(SandboxMode::ReadOnly),
+        cwd: cwd.map(|p| p.canonicalize().unwrap_or(p)),
+        codex_linux_sandbox_exe,
+        ..Default::default()
+    };
+    let cli_kv_overrides = config_overrides
+        .parse_overrides()
+        .map_err(|e| anyhow::anyhow!("Error parsing -c overrides: {e}"))?;
+    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+
+    let auth_manager = AuthManager::shared(config.codex_home.clone(), true);
+    let conversation_manager = ConversationManager::new(auth_manager, SessionSource::Exec);
+    let NewConversation { conversation, .. } = conversation_manager
+        .new_conversation(config.clone())
+        .await?;
+
+    eprintln!("Reviewing {}…", review_request.user_facing_hint);
+    conversation.submit(Op::Review { review_request }).await?;
+    let mut review_output = None;
+    let result = loop {
+        let event = conversation.next_event().await?;
+        match event.msg {
+            EventMsg::ExitedReviewMode(ExitedReviewModeEvent {
+                review_output: output,
+            }) => review_output = output,
+            EventMsg::TaskComplete(_) => break Ok(()),
+            EventMsg::Error(err) => break Err(anyhow::anyhow!(err.message)),
+            _ => {}
+        }
+    };
+    conversation.submit(Op::Shutdown).await.ok();
+    result?;
+    let review = review_output.context("the reviewer finished without a review")?;
+
+    let rendered = match format {
+        ReviewFormat::Text => render_text(&review),
+        ReviewFormat::Json => serde_json::to_string_pretty(&review)?,
+        ReviewFormat::Sarif => {
+            let (rules, results) = review_findings_to_sarif(&review.findings, &config.cwd);
+            let log = sarif_log("codex review", env!("CARGO_PKG_VERSION"), &rules, &results);
+            serde_json::to_string_pretty(&log)?
+        }
+    };
+    match output {
+        Some(path) => std::fs::write(&path, format!("{rendered}\n"))
+            .with_context(|| format!("failed to write {}", path.display()))?,
+        None => println!("{rendered}"),
+    }
+    eprintln!("{} finding(s).", review.findings.len());
+    Ok(())
+}
+
+fn render_text(review: &ReviewOutputEvent) -> String {
+    let explanation = review.overall_explanation.trim();
+    if review.findings.is_empty() {
+        return explanation.to_string();
+    }
+    let findings = format_review_findings_block(&review.findings, None);
+    format!("{explanation}\n{findings}").trim().to_string()
+}
@@ -0,0 +1,52 @@
+//! Runs a single prompt to completion in a fresh conversation, for the
+//! subcommands that drive the agent without a UI.
+
+use anyhow::Context;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::TaskCompleteEvent;
+use serde_json::Value;
+
+/// Runs `prompt` in a fresh conversation and returns the agent's final
+/// message, constrained to `output_schema` when given.
+pub(crate) async fn run_prompt(
+    conversation_manager: &ConversationManager,
+    config: &Config,
+    prompt: String,
+    output_schema: Option<Value>,
+) -> anyhow::Result<String> {
+    let NewConversation { conversation, .. } = conversation_manager
+        .new_conversation(config.clone())
+        .await?;
+    conversation
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text { text: prompt }],
+            cwd: config.cwd.clone(),
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            model: config.model.clone(),
+            effort: config.model_reasoning_effort,
+            summary: config.model_reasoning_summary,
+            final_output_json_schema: output_schema,
+        })
+        .await?;
+
+    let result = loop {
+        let event = conversation.next_event().await?;
+        match event.msg {
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message, ..
+            }) => {
+                break last_agent_message.context("the agent finished without a final message");
+            }
+            EventMsg::Error(err) => break Err(anyhow::anyhow!(err.message)),
+            _ => {}
+        }
+    };
+    conversation.submit(Op::Shutdown).await.ok();
+    result
+}
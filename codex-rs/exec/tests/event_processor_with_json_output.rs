@@ -1,6 +1,7 @@
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
@@ -12,6 +13,8 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::UserQuestionKind;
+use codex_core::protocol::UserQuestionRequestEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_exec::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use codex_exec::exec_events::AgentMessageItem;
@@ -24,6 +27,8 @@ use codex_exec::exec_events::McpToolCallItem;
 use codex_exec::exec_events::McpToolCallStatus;
 use codex_exec::exec_events::PatchApplyStatus;
 use codex_exec::exec_events::PatchChangeKind;
+use codex_exec::exec_events::QuestionKind;
+use codex_exec::exec_events::QuestionRequestedEvent;
 use codex_exec::exec_events::ReasoningItem;
 use codex_exec::exec_events::ThreadErrorEvent;
 use codex_exec::exec_events::ThreadEvent;
@@ -68,6 +73,7 @@ fn session_configured_produces_thread_started_event() {
             history_log_id: 0,
             history_entry_count: 0,
             initial_messages: None,
+            pinned_context: Vec::new(),
             rollout_path,
         }),
     );
@@ -203,6 +209,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
         "p3",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: None,
+            summary: None,
         }),
     );
     let out_complete = ep.collect_thread_events(&complete);
@@ -356,6 +363,7 @@ fn plan_update_after_complete_starts_new_todo_list_with_new_id() {
         "t2",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: None,
+            summary: None,
         }),
     );
     let _ = ep.collect_thread_events(&complete);
@@ -434,12 +442,14 @@ fn error_event_produces_error() {
         "e1",
         EventMsg::Error(codex_core::protocol::ErrorEvent {
             message: "boom".to_string(),
+            kind: ErrorKind::Auth,
         }),
     ));
     assert_eq!(
         out,
         vec![ThreadEvent::Error(ThreadErrorEvent {
             message: "boom".to_string(),
+            code: ErrorKind::Auth,
         })]
     );
 }
@@ -451,16 +461,34 @@ fn stream_error_event_produces_error() {
         "e1",
         EventMsg::StreamError(codex_core::protocol::StreamErrorEvent {
             message: "retrying".to_string(),
+            rate_limit_retry_after_ms: None,
         }),
     ));
     assert_eq!(
         out,
         vec![ThreadEvent::Error(ThreadErrorEvent {
             message: "retrying".to_string(),
+            code: ErrorKind::Network,
         })]
     );
 }
 
+#[test]
+fn error_codes_are_stable_in_jsonl() {
+    let error = ThreadEvent::Error(ThreadErrorEvent {
+        message: "sandbox denied".to_string(),
+        code: ErrorKind::Sandbox,
+    });
+    assert_eq!(
+        serde_json::to_value(&error).expect("serialize"),
+        serde_json::json!({
+            "type": "error",
+            "message": "sandbox denied",
+            "code": "sandbox",
+        })
+    );
+}
+
 #[test]
 fn error_followed_by_task_complete_produces_turn_failed() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
@@ -469,12 +497,14 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e1",
         EventMsg::Error(ErrorEvent {
             message: "boom".to_string(),
+            kind: ErrorKind::Provider,
         }),
     );
     assert_eq!(
         ep.collect_thread_events(&error_event),
         vec![ThreadEvent::Error(ThreadErrorEvent {
             message: "boom".to_string(),
+            code: ErrorKind::Provider,
         })]
     );
 
@@ -482,6 +512,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e2",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: None,
+            summary: None,
         }),
     );
     assert_eq!(
@@ -489,6 +520,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         vec![ThreadEvent::TurnFailed(TurnFailedEvent {
             error: ThreadErrorEvent {
                 message: "boom".to_string(),
+                code: ErrorKind::Provider,
             },
         })]
     );
@@ -634,6 +666,41 @@ fn exec_command_end_without_begin_is_ignored() {
     assert!(out.is_empty());
 }
 
+#[test]
+fn user_question_produces_question_requested() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let ev = event(
+        "q1",
+        EventMsg::UserQuestionRequest(UserQuestionRequestEvent {
+            call_id: "call-1".to_string(),
+            question: "Which database?".to_string(),
+            kind: UserQuestionKind::Choice {
+                options: vec!["postgres".to_string(), "sqlite".to_string()],
+            },
+        }),
+    );
+    let out = ep.collect_thread_events(&ev);
+    assert_eq!(
+        out,
+        vec![ThreadEvent::QuestionRequested(QuestionRequestedEvent {
+            call_id: "call-1".to_string(),
+            question: "Which database?".to_string(),
+            kind: QuestionKind::Choice {
+                options: vec!["postgres".to_string(), "sqlite".to_string()],
+            },
+        })]
+    );
+    assert_eq!(
+        serde_json::to_value(&out[0]).unwrap(),
+        serde_json::json!({
+            "type": "question.requested",
+            "call_id": "call-1",
+            "question": "Which database?",
+            "kind": {"type": "choice", "options": ["postgres", "sqlite"]},
+        })
+    );
+}
+
 #[test]
 fn patch_apply_success_produces_item_completed_patchapply() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
@@ -801,6 +868,7 @@ fn task_complete_produces_turn_completed_with_usage() {
         "e2",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: Some("done".to_string()),
+            summary: None,
         }),
     );
     let out = ep.collect_thread_events(&complete_event);
@@ -1,12 +1,14 @@
 #![cfg(not(target_os = "windows"))]
 #![allow(clippy::expect_used, clippy::unwrap_used)]
 
+use codex_core::protocol::ErrorKind;
 use core_test_support::responses;
 use core_test_support::test_codex_exec::test_codex_exec;
 use wiremock::matchers::any;
 
 /// Verify that when the server reports an error, `codex-exec` exits with a
-/// non-zero status code so automation can detect failures.
+/// non-zero status code so automation can detect failures. The retries after
+/// the failed response find no mock, and the provider's 404 ends the run.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn exits_non_zero_when_server_reports_error() -> anyhow::Result<()> {
     let test = test_codex_exec();
@@ -28,7 +30,7 @@ async fn exits_non_zero_when_server_reports_error() -> anyhow::Result<()> {
         .arg("tell me something")
         .arg("--experimental-json")
         .assert()
-        .code(1);
+        .code(ErrorKind::Provider.exit_code());
 
     Ok(())
 }
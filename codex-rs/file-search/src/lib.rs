@@ -7,6 +7,7 @@ use nucleo_matcher::pattern::CaseMatching;
 use nucleo_matcher::pattern::Normalization;
 use nucleo_matcher::pattern::Pattern;
 use serde::Serialize;
+use std::borrow::Cow;
 use std::cell::UnsafeCell;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
@@ -187,7 +188,7 @@ pub fn run(
 
         Box::new(move |entry| {
             if let Some(path) = get_file_path(&entry, search_directory) {
-                best_list.insert(path);
+                best_list.insert(&path);
             }
 
             processed += 1;
@@ -202,7 +203,7 @@ pub fn run(
     fn get_file_path<'a>(
         entry_result: &'a Result<ignore::DirEntry, ignore::Error>,
         search_directory: &std::path::Path,
-    ) -> Option<&'a str> {
+    ) -> Option<Cow<'a, str>> {
         let entry = match entry_result {
             Ok(e) => e,
             Err(_) => return None,
@@ -212,7 +213,7 @@ pub fn run(
         }
         let path = entry.path();
         match path.strip_prefix(search_directory) {
-            Ok(rel_path) => rel_path.to_str(),
+            Ok(rel_path) => rel_path.to_str().map(portable_path),
             Err(_) => None,
         }
     }
@@ -386,6 +387,16 @@ fn create_pattern(pattern: &str) -> Pattern {
     )
 }
 
+/// Report `/`-separated paths on every platform, so a query like `src/main`
+/// matches on Windows too and selected paths read the same everywhere.
+fn portable_path(path: &str) -> Cow<'_, str> {
+    if cfg!(windows) && path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,4 +431,13 @@ mod tests {
 
         assert_eq!(matches, expected);
     }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_paths_are_reported_with_forward_slashes() {
+        assert_eq!(
+            portable_path(r"src\bottom_pane\mod.rs"),
+            "src/bottom_pane/mod.rs"
+        );
+    }
 }
@@ -14,6 +14,7 @@
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
@@ -76,6 +77,9 @@ pub struct McpClient {
 
     /// Monotonically increasing counter used to generate request IDs.
     id_counter: AtomicI64,
+
+    /// Set once the server's STDOUT closes, i.e. the process has gone away.
+    closed: Arc<AtomicBool>,
 }
 
 impl McpClient {
@@ -139,8 +143,10 @@ impl McpClient {
 
         // Spawn reader task. It reads line-delimited JSON from the child's
         // STDOUT and dispatches responses to the pending map.
+        let closed = Arc::new(AtomicBool::new(false));
         let reader_handle = {
             let pending = pending.clone();
+            let closed = closed.clone();
             let mut lines = BufReader::new(stdout).lines();
 
             tokio::spawn(async move {
@@ -167,6 +173,7 @@ impl McpClient {
                         }
                     }
                 }
+                closed.store(true, Ordering::SeqCst);
             })
         };
 
@@ -181,9 +188,15 @@ impl McpClient {
             outgoing_tx,
             pending,
             id_counter: AtomicI64::new(1),
+            closed,
         })
     }
 
+    /// Whether the server process has exited or closed its STDOUT.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
     /// Send an arbitrary MCP request and await the typed result.
     ///
     /// If `timeout` is `None` the call waits indefinitely. If `Some(duration)`
@@ -22,6 +22,7 @@ use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::UserQuestionRequestEvent;
 use codex_protocol::ConversationId;
 use mcp_types::CallToolResult;
 use mcp_types::ContentBlock;
@@ -220,7 +221,23 @@ async fn run_codex_tool_session_inner(
                         .await;
                         continue;
                     }
-                    EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                    EventMsg::UserQuestionRequest(UserQuestionRequestEvent { call_id, .. }) => {
+                        // MCP clients have no way to answer yet; let the
+                        // agent carry on without an answer.
+                        if let Err(err) = codex
+                            .submit(Op::UserQuestionAnswer {
+                                call_id,
+                                answer: None,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to dismiss question: {err}");
+                        }
+                        continue;
+                    }
+                    EventMsg::TaskComplete(TaskCompleteEvent {
+                        last_agent_message, ..
+                    }) => {
                         let text = match last_agent_message {
                             Some(msg) => msg,
                             None => "".to_string(),
@@ -263,7 +280,9 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpToolCallBegin(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
+                    | EventMsg::McpServerStatus(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::SessionStatusResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
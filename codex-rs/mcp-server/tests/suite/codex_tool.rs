@@ -362,8 +362,15 @@ async fn codex_tool_passes_base_instructions() -> anyhow::Result<()> {
         codex_response
     );
 
+    // The unknown mock model also has its limits looked up via `GET /models`.
     let requests = server.received_requests().await.unwrap();
-    let request = requests[0].body_json::<serde_json::Value>().unwrap();
+    #[expect(clippy::expect_used)]
+    let request = requests
+        .iter()
+        .find(|request| request.url.path().ends_with("/chat/completions"))
+        .expect("chat completions request")
+        .body_json::<serde_json::Value>()
+        .unwrap();
     let instructions = request["messages"][0]["content"].as_str().unwrap();
     assert!(instructions.starts_with("You are a helpful assistant."));
 
@@ -0,0 +1,189 @@
+//! Lenient decoding of protocol messages, for peers that may run a different
+//! version of the protocol.
+//!
+//! [`Event`] and [`Submission`] decode strictly: an `EventMsg` or `Op` whose
+//! `type` this build does not know fails the whole message. A UI talking to a
+//! newer core (or a core serving an older UI) decodes into [`LenientEvent`] or
+//! [`LenientSubmission`] instead, which keep what they cannot interpret as raw
+//! JSON. Unknown variants, and known variants whose payload no longer parses,
+//! become [`Lenient::Unknown`]; fields a known variant does not have are kept
+//! in its `unknown_fields`. Serializing writes both back out
+//! unchanged, so a message can be forwarded without losing anything.
+
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use serde::de::DeserializeOwned;
+use serde::ser::Error as _;
+use serde_json::Map;
+use serde_json::Value;
+
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::Op;
+use crate::protocol::Submission;
+
+/// A value of `T`, or the raw JSON when it is not one this build understands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lenient<T> {
+    Known {
+        value: T,
+        /// Top-level fields of the JSON object that `T` does not have.
+        unknown_fields: Map<String, Value>,
+    },
+    Unknown(Value),
+}
+
+impl<T> Lenient<T> {
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            Self::Known { value, .. } => Some(value),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    pub fn into_known(self) -> Option<T> {
+        match self {
+            Self::Known { value, .. } => Some(value),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// The `type` tag of an unknown value, for logging.
+    pub fn unknown_type(&self) -> Option<&str> {
+        match self {
+            Self::Known { .. } => None,
+            Self::Unknown(raw) => raw.get("type").and_then(Value::as_str),
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> Lenient<T> {
+    pub fn from_value(raw: Value) -> Self {
+        let value = match T::deserialize(&raw) {
+            Ok(value) => value,
+            Err(_) => return Self::Unknown(raw),
+        };
+        let unknown_fields = match (raw, serde_json::to_value(&value)) {
+            (Value::Object(raw), Ok(Value::Object(known))) => raw
+                .into_iter()
+                // Nulls are what absent optional fields look like.
+                .filter(|(key, field)| !field.is_null() && !known.contains_key(key))
+                .collect(),
+            _ => Map::new(),
+        };
+        Self::Known {
+            value,
+            unknown_fields,
+        }
+    }
+}
+
+impl<'de, T: Serialize + DeserializeOwned> Deserialize<'de> for Lenient<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Self::from_value)
+    }
+}
+
+impl<T: Serialize> Serialize for Lenient<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Known {
+                value,
+                unknown_fields,
+            } => {
+                let mut value = serde_json::to_value(value).map_err(S::Error::custom)?;
+                if let Value::Object(map) = &mut value {
+                    map.extend(unknown_fields.clone());
+                }
+                value.serialize(serializer)
+            }
+            Self::Unknown(raw) => raw.serialize(serializer),
+        }
+    }
+}
+
+/// [`Event`], decoded leniently.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LenientEvent {
+    pub id: String,
+    pub msg: Lenient<EventMsg>,
+}
+
+impl LenientEvent {
+    /// The event, when its message is one this build knows.
+    pub fn into_event(self) -> Option<Event> {
+        let msg = self.msg.into_known()?;
+        Some(Event { id: self.id, msg })
+    }
+}
+
+/// [`Submission`], decoded leniently.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LenientSubmission {
+    pub id: String,
+    pub op: Lenient<Op>,
+}
+
+impl LenientSubmission {
+    /// The submission, when its op is one this build knows.
+    pub fn into_submission(self) -> Option<Submission> {
+        let op = self.op.into_known()?;
+        Some(Submission { id: self.id, op })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unknown_variants_and_fields_are_kept_as_raw_json() {
+        let future = json!({
+            "id": "1",
+            "msg": {"type": "hologram_begin", "call_id": "c1"},
+        });
+        let event: LenientEvent = serde_json::from_value(future.clone()).expect("lenient event");
+        assert_eq!(event.msg.unknown_type(), Some("hologram_begin"));
+        assert_eq!(serde_json::to_value(&event).expect("serialize"), future);
+        assert!(event.into_event().is_none());
+        assert!(serde_json::from_value::<Event>(future).is_err());
+
+        let extended = json!({
+            "id": "2",
+            "msg": {"type": "agent_message", "message": "hi", "mood": "cheerful"},
+        });
+        let event: LenientEvent = serde_json::from_value(extended.clone()).expect("lenient event");
+        let Lenient::Known { unknown_fields, .. } = &event.msg else {
+            panic!("agent_message should be known: {event:?}");
+        };
+        assert_eq!(
+            unknown_fields,
+            &Map::from_iter([("mood".to_string(), json!("cheerful"))])
+        );
+        assert_eq!(serde_json::to_value(&event).expect("serialize"), extended);
+        assert!(matches!(
+            event.into_event().map(|event| event.msg),
+            Some(EventMsg::AgentMessage(message)) if message.message == "hi"
+        ));
+    }
+
+    #[test]
+    fn known_ops_decode_and_unknown_ops_do_not_fail() {
+        let submission: LenientSubmission =
+            serde_json::from_value(json!({"id": "s1", "op": {"type": "interrupt"}}))
+                .expect("lenient submission");
+        assert!(matches!(
+            submission.into_submission().map(|submission| submission.op),
+            Some(Op::Interrupt)
+        ));
+
+        let submission: LenientSubmission =
+            serde_json::from_value(json!({"id": "s2", "op": {"type": "teleport", "to": "mars"}}))
+                .expect("lenient submission");
+        assert_eq!(submission.op.unknown_type(), Some("teleport"));
+        assert!(submission.into_submission().is_none());
+    }
+}
@@ -2,6 +2,7 @@ mod conversation_id;
 pub use conversation_id::ConversationId;
 pub mod config_types;
 pub mod custom_prompts;
+pub mod lenient;
 pub mod message_history;
 pub mod models;
 pub mod num_format;
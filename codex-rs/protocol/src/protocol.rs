@@ -3,6 +3,7 @@
 //! Uses a SQ (Submission Queue) / EQ (Event Queue) pattern to asynchronously communicate
 //! between user and agent.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
@@ -35,6 +36,8 @@ pub const USER_INSTRUCTIONS_OPEN_TAG: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_CLOSE_TAG: &str = "</user_instructions>";
 pub const ENVIRONMENT_CONTEXT_OPEN_TAG: &str = "<environment_context>";
 pub const ENVIRONMENT_CONTEXT_CLOSE_TAG: &str = "</environment_context>";
+pub const USER_NOTE_OPEN_TAG: &str = "<user_note>";
+pub const USER_NOTE_CLOSE_TAG: &str = "</user_note>";
 pub const USER_MESSAGE_BEGIN: &str = "## My request for Codex:";
 
 /// Submission Queue Entry - requests from user
@@ -135,6 +138,16 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Approve a command execution after the user edited the command. The
+    /// edited command runs in place of the proposed one, and the tool result
+    /// tells the model so.
+    ExecApprovalWithEdit {
+        /// The id of the submission we are approving
+        id: String,
+        /// The command to run instead.
+        command: Vec<String>,
+    },
+
     /// Approve a code patch
     PatchApproval {
         /// The id of the submission we are approving
@@ -143,6 +156,14 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Answer a question the agent asked with the `ask_user` tool.
+    UserQuestionAnswer {
+        /// The call id of the question being answered.
+        call_id: String,
+        /// The answer, or `None` when the user dismissed the question.
+        answer: Option<String>,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -166,6 +187,23 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the session's configuration and usage, as shown by `/status`.
+    /// Reply is delivered via `EventMsg::SessionStatusResponse`.
+    GetSessionStatus,
+
+    /// Replace the set of history excerpts the user pinned. Pinned text is
+    /// carried verbatim across compaction instead of being summarized.
+    UpdatePinnedContext { items: Vec<String> },
+
+    /// Add a note from the user to the transcript. The note is stored in the
+    /// rollout as a `UserMessage` of kind `Note`; with `include_in_context`
+    /// the model also sees it, wrapped in `<user_note>` tags, on its next
+    /// request.
+    AddNote {
+        text: String,
+        include_in_context: bool,
+    },
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -485,6 +523,10 @@ pub enum EventMsg {
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    /// The agent asked the user a question and waits for
+    /// [`Op::UserQuestionAnswer`].
+    UserQuestionRequest(UserQuestionRequestEvent),
+
     BackgroundEvent(BackgroundEventEvent),
 
     /// Notification that a model stream experienced an error or disconnect
@@ -506,9 +548,16 @@ pub enum EventMsg {
     /// List of MCP tools available to the agent.
     McpListToolsResponse(McpListToolsResponseEvent),
 
+    /// State of the configured MCP servers, sent once they have started and
+    /// again whenever one of them goes away.
+    McpServerStatus(McpServerStatusEvent),
+
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Response to `Op::GetSessionStatus`.
+    SessionStatusResponse(SessionStatus),
+
     PlanUpdate(UpdatePlanArgs),
 
     TurnAborted(TurnAbortedEvent),
@@ -535,11 +584,120 @@ pub struct ExitedReviewModeEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct ErrorEvent {
     pub message: String,
+    #[serde(default)]
+    pub kind: ErrorKind,
+}
+
+/// What failed, for clients that react to an [`ErrorEvent`] without parsing
+/// its message. Serialized as the stable codes from [`ErrorKind::code`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// Credentials are missing, expired or were rejected.
+    Auth,
+    /// The model provider could not be reached, or the stream broke off.
+    Network,
+    /// The model provider refused the request: usage limits, overload or a
+    /// full context window.
+    Provider,
+    /// A command was denied or could not run in the sandbox.
+    Sandbox,
+    /// A patch could not be applied.
+    Patch,
+    /// The configuration, or an MCP server it names, is invalid.
+    Config,
+    /// Anything else.
+    #[default]
+    Internal,
+}
+
+impl ErrorKind {
+    /// The kind as it appears in JSON, e.g. `auth`.
+    pub fn code(self) -> &'static str {
+        match self {
+            ErrorKind::Auth => "auth",
+            ErrorKind::Network => "network",
+            ErrorKind::Provider => "provider",
+            ErrorKind::Sandbox => "sandbox",
+            ErrorKind::Patch => "patch",
+            ErrorKind::Config => "config",
+            ErrorKind::Internal => "internal",
+        }
+    }
+
+    /// Exit status of a non-interactive run that failed with this kind of
+    /// error; 1 for anything unclassified, as before kinds existed.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorKind::Internal => 1,
+            ErrorKind::Auth => 3,
+            ErrorKind::Network => 4,
+            ErrorKind::Provider => 5,
+            ErrorKind::Sandbox => 6,
+            ErrorKind::Patch => 7,
+            ErrorKind::Config => 8,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct TaskCompleteEvent {
     pub last_agent_message: Option<String>,
+    /// What the task changed, when it changed anything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<TurnSummary>,
+}
+
+/// Files edited and tests run during a task, used to triage completion
+/// notifications without switching back to the session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+pub struct TurnSummary {
+    pub files_changed: u64,
+    pub lines_added: u64,
+    pub lines_removed: u64,
+    pub test_runs: u64,
+    pub failed_test_runs: u64,
+}
+
+impl TurnSummary {
+    pub fn is_empty(&self) -> bool {
+        self.files_changed == 0 && self.test_runs == 0
+    }
+}
+
+impl fmt::Display for TurnSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = Vec::new();
+        if self.files_changed > 0 {
+            let noun = if self.files_changed == 1 {
+                "file"
+            } else {
+                "files"
+            };
+            parts.push(format!(
+                "{} {noun} changed (+{} -{})",
+                self.files_changed, self.lines_added, self.lines_removed
+            ));
+        }
+        if self.test_runs > 0 {
+            let noun = if self.test_runs == 1 {
+                "test run"
+            } else {
+                "test runs"
+            };
+            let outcome = if self.failed_test_runs > 0 {
+                format!("{} failed", self.failed_test_runs)
+            } else {
+                "passed".to_string()
+            };
+            parts.push(format!("{} {noun} ({outcome})", self.test_runs));
+        }
+        if parts.is_empty() {
+            write!(f, "no changes")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -766,6 +924,8 @@ pub enum InputMessageKind {
     UserInstructions,
     /// XML-wrapped environment context (<environment_context>...)
     EnvironmentContext,
+    /// A note the user added to the transcript (<user_note>...)
+    Note,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -794,6 +954,10 @@ where
             && ends_with_ignore_ascii_case(trimmed, USER_INSTRUCTIONS_CLOSE_TAG)
         {
             InputMessageKind::UserInstructions
+        } else if starts_with_ignore_ascii_case(trimmed, USER_NOTE_OPEN_TAG)
+            && ends_with_ignore_ascii_case(trimmed, USER_NOTE_CLOSE_TAG)
+        {
+            InputMessageKind::Note
         } else {
             InputMessageKind::Plain
         }
@@ -951,6 +1115,23 @@ impl InitialHistory {
             ),
         }
     }
+
+    /// The excerpts pinned when the history was recorded.
+    pub fn get_pinned_context(&self) -> Vec<String> {
+        let items = match self {
+            InitialHistory::New => return Vec::new(),
+            InitialHistory::Resumed(resumed) => &resumed.history,
+            InitialHistory::Forked(items) => items,
+        };
+        items
+            .iter()
+            .rev()
+            .find_map(|ri| match ri {
+                RolloutItem::PinnedContext(pinned) => Some(pinned.items.clone()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, TS, Default)]
@@ -998,6 +1179,8 @@ pub struct SessionMetaLine {
     pub meta: SessionMeta,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git: Option<GitInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<EnvironmentSnapshot>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, TS)]
@@ -1008,6 +1191,7 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    PinnedContext(PinnedContextItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, TS)]
@@ -1027,6 +1211,13 @@ impl From<CompactedItem> for ResponseItem {
     }
 }
 
+/// The excerpts pinned from this point on, recorded whenever the user pins
+/// or unpins one, so resumed and forked sessions keep them.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, TS)]
+pub struct PinnedContextItem {
+    pub items: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, TS)]
 pub struct TurnContextItem {
     pub cwd: PathBuf,
@@ -1041,6 +1232,10 @@ pub struct TurnContextItem {
 #[derive(Serialize, Deserialize, Clone)]
 pub struct RolloutLine {
     pub timestamp: String,
+    /// Milliseconds since the session started, measured with a monotonic
+    /// clock so event spacing survives wall-clock adjustments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
     #[serde(flatten)]
     pub item: RolloutItem,
 }
@@ -1058,6 +1253,30 @@ pub struct GitInfo {
     pub repository_url: Option<String>,
 }
 
+/// Machine and toolchain details captured when a session starts, so the
+/// conditions an agent worked under can be reconstructed later. The commit and
+/// Codex version live in [`GitInfo`] and [`SessionMeta`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, TS)]
+pub struct EnvironmentSnapshot {
+    /// Operating system name and version, e.g. `Mac OS 14.5.0`.
+    pub os: String,
+    pub arch: String,
+    /// Whether the working tree had uncommitted changes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_dirty: Option<bool>,
+    /// First line of `--version` output for toolchains found on `PATH`,
+    /// keyed by program name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toolchains: BTreeMap<String, String>,
+    /// Short hash of `config.toml`, to tell whether configuration changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    /// Names (never values) of the variables loaded from the project's
+    /// `.env`/`.envrc` into the environment of shell commands.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub project_env: Vec<String>,
+}
+
 /// Review request sent to the review session.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 pub struct ReviewRequest {
@@ -1194,6 +1413,29 @@ pub struct ApplyPatchApprovalRequestEvent {
     pub grant_root: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct UserQuestionRequestEvent {
+    /// Call id to answer with.
+    pub call_id: String,
+    pub question: String,
+    pub kind: UserQuestionKind,
+}
+
+/// The form a question is answered in.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UserQuestionKind {
+    /// Pick one of `options`.
+    Choice { options: Vec<String> },
+    /// Free-form text.
+    Text {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        placeholder: Option<String>,
+    },
+    /// A path to a file.
+    File,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct BackgroundEventEvent {
     pub message: String,
@@ -1202,6 +1444,11 @@ pub struct BackgroundEventEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct StreamErrorEvent {
     pub message: String,
+    /// Milliseconds until the retry when the request was rate limited
+    /// (HTTP 429 or a `rate_limit_exceeded` error).
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub rate_limit_retry_after_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -1275,6 +1522,61 @@ impl fmt::Display for McpAuthStatus {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct McpServerStatusEvent {
+    /// State of every configured server, by name.
+    pub servers: std::collections::HashMap<String, McpServerState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum McpServerState {
+    /// `enabled = false` in the config.
+    Disabled,
+    /// Could not be started.
+    Failed,
+    Connected,
+    /// Started, then went away during the session.
+    Crashed,
+}
+
+/// The session's configuration and usage, for support requests and tools
+/// that want what `/status` shows.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SessionStatus {
+    pub conversation_id: ConversationId,
+    pub model: String,
+    pub model_provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffortConfig>,
+    pub cwd: PathBuf,
+    /// `None` when not logged in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<StatusAccount>,
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+    /// State of every configured MCP server, by name.
+    pub mcp_servers: HashMap<String, McpServerState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_usage: Option<TokenUsageInfo>,
+    /// `AGENTS.md` files loaded into the instructions, outermost first.
+    pub project_docs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StatusAccount {
+    #[serde(rename = "chatgpt")]
+    ChatGpt {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        email: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        plan: Option<String>,
+    },
+    ApiKey,
+}
+
 /// Response payload for `Op::ListCustomPrompts`.
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
 pub struct ListCustomPromptsResponseEvent {
@@ -1304,6 +1606,10 @@ pub struct SessionConfiguredEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub initial_messages: Option<Vec<EventMsg>>,
 
+    /// Excerpts pinned in the resumed or forked session.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_context: Vec<String>,
+
     pub rollout_path: PathBuf,
 }
 
@@ -1387,6 +1693,7 @@ mod tests {
                 history_log_id: 0,
                 history_entry_count: 0,
                 initial_messages: None,
+                pinned_context: Vec::new(),
                 rollout_path: rollout_file.path().to_path_buf(),
             }),
         };
@@ -1424,4 +1731,45 @@ mod tests {
         assert_eq!(deserialized, event);
         Ok(())
     }
+
+    #[test]
+    fn error_kinds_serialize_as_their_codes() -> Result<()> {
+        for kind in [
+            ErrorKind::Auth,
+            ErrorKind::Network,
+            ErrorKind::Provider,
+            ErrorKind::Sandbox,
+            ErrorKind::Patch,
+            ErrorKind::Config,
+            ErrorKind::Internal,
+        ] {
+            assert_eq!(serde_json::to_value(kind)?, json!(kind.code()));
+        }
+        // Events recorded before errors had kinds.
+        let event: ErrorEvent = serde_json::from_value(json!({ "message": "boom" }))?;
+        assert_eq!(event.kind, ErrorKind::Internal);
+        Ok(())
+    }
+
+    #[test]
+    fn turn_summary_renders_one_line() {
+        let summary = TurnSummary {
+            files_changed: 3,
+            lines_added: 42,
+            lines_removed: 7,
+            test_runs: 2,
+            failed_test_runs: 1,
+        };
+        assert_eq!(
+            summary.to_string(),
+            "3 files changed (+42 -7), 2 test runs (1 failed)"
+        );
+
+        let tests_only = TurnSummary {
+            test_runs: 1,
+            ..TurnSummary::default()
+        };
+        assert_eq!(tests_only.to_string(), "1 test run (passed)");
+        assert!(TurnSummary::default().is_empty());
+    }
 }
@@ -257,6 +257,16 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    /// Whether the connection to the server has gone away after it was
+    /// initialized.
+    pub async fn is_closed(&self) -> bool {
+        let guard = self.state.lock().await;
+        match &*guard {
+            ClientState::Ready { service, .. } => service.peer().is_transport_closed(),
+            ClientState::Connecting { .. } => false,
+        }
+    }
+
     async fn service(&self) -> Result<Arc<RunningService<RoleClient, LoggingClientHandler>>> {
         let guard = self.state.lock().await;
         match &*guard {
@@ -0,0 +1,104 @@
+//! Benchmarks for the TUI render path: the status line, laying out the
+//! history at several terminal widths, and rendering agent markdown.
+//!
+//! Compare a change against `main` with criterion baselines:
+//!
+//! ```text
+//! git switch main && cargo bench -p codex-tui --bench render -- --save-baseline main
+//! git switch - && cargo bench -p codex-tui --bench render -- --baseline main
+//! ```
+
+use std::hint::black_box;
+
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::ConfigToml;
+use codex_tui::bench_support::StatusLineFixture;
+use codex_tui::bench_support::TranscriptFixture;
+use codex_tui::bench_support::render_markdown;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+
+const WIDTHS: [u16; 4] = [40, 80, 120, 200];
+
+fn config() -> Config {
+    match Config::load_from_base_config_with_overrides(
+        ConfigToml::default(),
+        ConfigOverrides::default(),
+        std::env::temp_dir(),
+    ) {
+        Ok(config) => config,
+        Err(err) => panic!("load default config: {err}"),
+    }
+}
+
+/// An agent message with `sections` sections of prose, lists and code, like
+/// a long review or plan.
+fn agent_markdown(sections: usize) -> String {
+    (0..sections)
+        .map(|section| {
+            format!(
+                "## Section {section}\n\n\
+                 The change in `src/module_{section}.rs` moves **parsing** out of the \
+                 render loop, so each frame only lays out lines that were already \
+                 styled. See [the design notes](docs/design_{section}.md) for details.\n\n\
+                 - keep the public API unchanged\n\
+                 - cache the wrapped lines per width\n  \
+                   - invalidate on resize\n\
+                 - add a regression test\n\n\
+                 ```rust\nfn render_{section}(width: u16) -> Vec<Line<'static>> {{\n    \
+                 layout(width).into_iter().map(Line::from).collect()\n}}\n```\n\n"
+            )
+        })
+        .collect()
+}
+
+fn status_line(c: &mut Criterion) {
+    let config = config();
+    let mut group = c.benchmark_group("status_line");
+    for queued_bytes in [0, 1024 * 1024] {
+        let fixture = StatusLineFixture::new(&config, queued_bytes);
+        for width in WIDTHS {
+            group.bench_with_input(
+                BenchmarkId::new(format!("queued_{queued_bytes}"), width),
+                &width,
+                |b, &width| b.iter(|| black_box(fixture.render(width))),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn history_layout(c: &mut Criterion) {
+    let config = config();
+    let fixture = TranscriptFixture::new(&config, &agent_markdown(4), 200, 500);
+    let mut group = c.benchmark_group("history_layout");
+    for width in WIDTHS {
+        group.bench_with_input(BenchmarkId::from_parameter(width), &width, |b, &width| {
+            b.iter(|| black_box(fixture.layout(width)))
+        });
+    }
+    group.bench_function("transcript_overlay", |b| {
+        b.iter(|| black_box(fixture.transcript_lines()))
+    });
+    group.finish();
+}
+
+fn markdown(c: &mut Criterion) {
+    let config = config();
+    let mut group = c.benchmark_group("markdown");
+    for sections in [10, 100, 1_000] {
+        let source = agent_markdown(sections);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(sections),
+            &source,
+            |b, source| b.iter(|| black_box(render_markdown(&config, source))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, status_line, history_layout, markdown);
+criterion_main!(benches);
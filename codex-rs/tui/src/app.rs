@@ -6,18 +6,33 @@ use crate::chatwidget::ChatWidget;
 use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::file_search::FileSearchManager;
+use crate::history_cell;
 use crate::history_cell::HistoryCell;
+use crate::key_macros::KeyMacros;
+use crate::key_macros::MacroKey;
+use crate::lock::SessionLock;
+use crate::lock::render_lock_screen;
 use crate::pager_overlay::Overlay;
+use crate::perf_hud::PerfHud;
+use crate::pins::Pins;
+use crate::pins::latest_agent_message;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::resume_picker::ResumeSelection;
+use crate::runbook::Runbook;
+use crate::runbook::RunbookAction;
+use crate::runbook::RunbookRun;
+use crate::runbook::run_verification;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_ansi_escape::ansi_escape_line;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
+use codex_core::RolloutRecorder;
 use codex_core::config::Config;
 use codex_core::config::persist_model_selection;
 use codex_core::model_family::find_family_for_model;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
@@ -27,14 +42,22 @@ use color_eyre::eyre::WrapErr;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
+use ratatui::widgets::Widget;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::Duration;
+use std::time::Instant;
 use tokio::select;
 use tokio::sync::mpsc::unbounded_channel;
 // use uuid::Uuid;
@@ -59,6 +82,15 @@ pub(crate) struct App {
 
     pub(crate) transcript_cells: Vec<Arc<dyn HistoryCell>>,
 
+    /// Transcript entries the user pinned via `/pin` or `p` in the transcript.
+    pub(crate) pins: Pins,
+    /// Transcript bookmarks (`m<char>` in the transcript), kept across openings.
+    pub(crate) bookmarks: BTreeMap<char, usize>,
+    /// Transcript keyboard macros (`tui.macros`), saved per project.
+    pub(crate) macros: KeyMacros,
+    /// Runbook started with `codex runbook`, until it finishes or is stopped.
+    pub(crate) runbook: Option<RunbookRun>,
+
     // Pager overlay state (Transcript or Static like Diff)
     pub(crate) overlay: Option<Overlay>,
     pub(crate) deferred_history_lines: Vec<Line<'static>>,
@@ -71,9 +103,16 @@ pub(crate) struct App {
 
     // Esc-backtracking state grouped
     pub(crate) backtrack: crate::app_backtrack::BacktrackState,
+
+    /// Frame and event-queue statistics shown above the chat (`/debug perf`).
+    perf_hud: Option<PerfHud>,
+
+    /// `tui.lock_after_secs`; see [`crate::lock`].
+    lock: SessionLock,
 }
 
 impl App {
+    #[allow(clippy::too_many_arguments)]
     pub async fn run(
         tui: &mut tui::Tui,
         auth_manager: Arc<AuthManager>,
@@ -82,6 +121,7 @@ impl App {
         initial_prompt: Option<String>,
         initial_images: Vec<PathBuf>,
         resume_selection: ResumeSelection,
+        runbook: Option<Runbook>,
     ) -> Result<AppExitInfo> {
         use tokio_stream::StreamExt;
         let (app_event_tx, mut app_event_rx) = unbounded_channel();
@@ -92,6 +132,10 @@ impl App {
             SessionSource::Cli,
         ));
 
+        tui.set_idle_after(config.tui_idle_after);
+        if config.tui_mouse {
+            tui.enable_mouse_capture();
+        }
         let enhanced_keys_supported = tui.enhanced_keys_supported();
 
         let chat_widget = match resume_selection {
@@ -118,6 +162,13 @@ impl App {
                     .wrap_err_with(|| {
                         format!("Failed to resume session from {}", path.display())
                     })?;
+                // Queued before the agent starts forwarding events so the
+                // recorded environment is shown ahead of the resumed history.
+                if let Ok(meta_line) = RolloutRecorder::read_session_meta(&path).await {
+                    app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                        history_cell::new_recorded_environment(&meta_line),
+                    )));
+                }
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
@@ -133,9 +184,30 @@ impl App {
                     resumed.session_configured,
                 )
             }
+            ResumeSelection::Replay { path, speed } => {
+                let replay = crate::replay::load_replay(&path)
+                    .await
+                    .wrap_err_with(|| format!("Failed to load session from {}", path.display()))?;
+                let init = crate::chatwidget::ChatWidgetInit {
+                    config: config.clone(),
+                    frame_requester: tui.frame_requester(),
+                    app_event_tx: app_event_tx.clone(),
+                    initial_prompt: None,
+                    initial_images: Vec::new(),
+                    enhanced_keys_supported,
+                    auth_manager: auth_manager.clone(),
+                };
+                ChatWidget::new_replay(init, replay, speed)
+            }
         };
 
         let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
+        let lock = SessionLock::new(config.tui_lock_after, Instant::now());
+        let macros = if config.tui_macros {
+            KeyMacros::load(&config.state_home.path, &config.cwd)
+        } else {
+            KeyMacros::default()
+        };
 
         let mut app = Self {
             server: conversation_manager,
@@ -147,11 +219,17 @@ impl App {
             file_search,
             enhanced_keys_supported,
             transcript_cells: Vec::new(),
+            pins: Pins::default(),
+            bookmarks: BTreeMap::new(),
+            macros,
+            runbook: runbook.map(RunbookRun::new),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
+            perf_hud: None,
+            lock,
         };
 
         let tui_events = tui.event_stream();
@@ -161,12 +239,20 @@ impl App {
 
         while select! {
             Some(event) = app_event_rx.recv() => {
+                if let Some(hud) = app.perf_hud.as_mut() {
+                    hud.record_event(app_event_rx.len());
+                }
                 app.handle_event(tui, event).await?
             }
             Some(event) = tui_events.next() => {
                 app.handle_tui_event(tui, event).await?
             }
+            () = sleep_until(app.lock.deadline()) => {
+                app.lock_session(tui);
+                true
+            }
         } {}
+        tui.restore_title();
         tui.terminal.clear()?;
         Ok(AppExitInfo {
             token_usage: app.token_usage(),
@@ -179,8 +265,41 @@ impl App {
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
+        if self.lock.is_locked() {
+            self.handle_locked_event(tui, event)?;
+            return Ok(true);
+        }
+        if matches!(
+            event,
+            TuiEvent::Key(_) | TuiEvent::Paste(_) | TuiEvent::Mouse(_)
+        ) {
+            self.lock.record_input(Instant::now());
+        }
         if self.overlay.is_some() {
-            let _ = self.handle_backtrack_overlay_event(tui, event).await?;
+            let event = match event {
+                TuiEvent::Mouse(mouse_event) => match wheel_as_arrow_key(mouse_event) {
+                    Some(key_event) => TuiEvent::Key(key_event),
+                    None => return Ok(true),
+                },
+                event => event,
+            };
+            match self.handle_macro_overlay_event(&event) {
+                MacroKey::Forward => self.forward_overlay_event(tui, event).await?,
+                MacroKey::Consumed(status) => {
+                    self.show_macro_status(status);
+                    tui.frame_requester().schedule_frame();
+                }
+                MacroKey::Replay(keys, status) => {
+                    for key in keys {
+                        if self.overlay.is_none() {
+                            break;
+                        }
+                        self.forward_overlay_event(tui, TuiEvent::Key(key)).await?;
+                    }
+                    self.show_macro_status(Some(status));
+                    tui.frame_requester().schedule_frame();
+                }
+            }
         } else {
             match event {
                 TuiEvent::Key(key_event) => {
@@ -194,6 +313,9 @@ impl App {
                     let pasted = pasted.replace("\r", "\n");
                     self.chat_widget.handle_paste(pasted);
                 }
+                TuiEvent::Mouse(mouse_event) => {
+                    self.chat_widget.handle_mouse_event(mouse_event);
+                }
                 TuiEvent::Draw => {
                     self.chat_widget.maybe_post_pending_notification(tui);
                     if self
@@ -202,15 +324,36 @@ impl App {
                     {
                         return Ok(true);
                     }
-                    tui.draw(
-                        self.chat_widget.desired_height(tui.terminal.size()?.width),
-                        |frame| {
-                            frame.render_widget_ref(&self.chat_widget, frame.area());
-                            if let Some((x, y)) = self.chat_widget.cursor_pos(frame.area()) {
-                                frame.set_cursor_position((x, y));
-                            }
-                        },
-                    )?;
+                    let hud = self.perf_hud.as_ref().map(PerfHud::line);
+                    let height = self.chat_widget.desired_height(tui.terminal.size()?.width)
+                        + u16::from(hud.is_some());
+                    let started = Instant::now();
+                    tui.draw(height, |frame| {
+                        let mut area = frame.area();
+                        if let Some(hud) = hud {
+                            let [hud_area, chat_area] =
+                                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+                                    .areas(area);
+                            hud.render(hud_area, frame.buffer_mut());
+                            area = chat_area;
+                        }
+                        frame.render_widget_ref(&self.chat_widget, area);
+                        if let Some((x, y)) = self.chat_widget.cursor_pos(area) {
+                            frame.set_cursor_position((x, y));
+                        }
+                    })?;
+                    if let Some(title) = self.chat_widget.terminal_title() {
+                        tui.set_title(&title);
+                    }
+                    if let Some(hud) = self.perf_hud.as_mut()
+                        && let Some(refresh_in) = hud.record_draw(
+                            started.elapsed(),
+                            tui.frames_scheduled(),
+                            Instant::now(),
+                        )
+                    {
+                        tui.frame_requester().schedule_frame_in(refresh_in);
+                    }
                 }
             }
         }
@@ -230,6 +373,8 @@ impl App {
                     auth_manager: self.auth_manager.clone(),
                 };
                 self.chat_widget = ChatWidget::new(init, self.server.clone());
+                self.pins.clear();
+                self.runbook = None;
                 tui.frame_requester().schedule_frame();
             }
             AppEvent::InsertHistoryCell(cell) => {
@@ -251,7 +396,7 @@ impl App {
                             self.has_emitted_history_lines = true;
                         }
                     }
-                    if self.overlay.is_some() {
+                    if self.overlay.is_some() || self.lock.is_locked() {
                         self.deferred_history_lines.extend(display);
                     } else {
                         tui.insert_history_lines(display);
@@ -281,7 +426,16 @@ impl App {
                 self.chat_widget.on_commit_tick();
             }
             AppEvent::CodexEvent(event) => {
+                let session_configured = matches!(event.msg, EventMsg::SessionConfigured(_));
                 self.chat_widget.handle_codex_event(event);
+                self.app_event_tx.codex_event_handled();
+                if session_configured && let Some(run) = self.runbook.as_mut() {
+                    let action = run.start();
+                    self.apply_runbook_action(action);
+                }
+            }
+            AppEvent::ReplayEvent(msg) => {
+                self.chat_widget.handle_replayed_event(msg);
             }
             AppEvent::ConversationHistory(ev) => {
                 self.on_conversation_history_for_backtrack(tui, ev).await?;
@@ -289,6 +443,13 @@ impl App {
             AppEvent::ExitRequest => {
                 return Ok(false);
             }
+            AppEvent::TogglePerfHud => {
+                self.perf_hud = match self.perf_hud.take() {
+                    Some(_) => None,
+                    None => Some(PerfHud::new(tui.frames_scheduled(), Instant::now())),
+                };
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::CodexOp(op) => self.chat_widget.submit_op(op),
             AppEvent::DiffResult(text) => {
                 // Clear the in-progress state in the bottom pane
@@ -306,6 +467,35 @@ impl App {
                 ));
                 tui.frame_requester().schedule_frame();
             }
+            AppEvent::McpResultFiltered(ev, filtered) => {
+                self.chat_widget.on_mcp_result_filtered(ev, filtered);
+            }
+            AppEvent::TodosResult(todos) => {
+                self.chat_widget.on_todos_result(todos);
+            }
+            AppEvent::SubmitText(text) => {
+                self.chat_widget.submit_text_message(text);
+            }
+            AppEvent::ActionDenied(request) => {
+                self.chat_widget.on_action_denied(request);
+            }
+            AppEvent::RetryDeniedAction(index) => {
+                self.chat_widget.retry_denied_action(index);
+            }
+            AppEvent::PinLatestAgentMessage => {
+                self.pin_latest_agent_message();
+            }
+            AppEvent::OpenPins => {
+                self.open_pins_overlay(tui);
+            }
+            AppEvent::RestorePins(items) => {
+                self.pins.restore(items.clone());
+                // The agent restored these itself; only sync when pins kept
+                // across a backtrack changed the set.
+                if self.pins.context_items() != items {
+                    self.sync_pinned_context();
+                }
+            }
             AppEvent::StartFileSearch(query) => {
                 if !query.is_empty() {
                     self.file_search.on_user_query(query);
@@ -327,12 +517,25 @@ impl App {
             AppEvent::OpenReasoningPopup { model, presets } => {
                 self.chat_widget.open_reasoning_popup(model, presets);
             }
-            AppEvent::StatusLineGit(snapshot) => {
-                self.chat_widget.update_statusline_git(snapshot);
+            AppEvent::StatusLineProbe(report) => {
+                // The status line requests a frame when the report changes it.
+                self.chat_widget.update_statusline_probe(report);
+            }
+            AppEvent::StatusLineAzureSubscription(subscription) => {
+                self.chat_widget
+                    .update_statusline_azure_subscription(subscription);
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::StatusLineDocker(docker) => {
+                self.chat_widget.update_statusline_docker(docker);
+                tui.frame_requester().schedule_frame();
+            }
+            AppEvent::StatusLineResources(resources) => {
+                self.chat_widget.update_statusline_resources(resources);
                 tui.frame_requester().schedule_frame();
             }
-            AppEvent::StatusLineKubeContext(context) => {
-                self.chat_widget.update_statusline_kube_context(context);
+            AppEvent::StatusLineBattery(battery) => {
+                self.chat_widget.update_statusline_battery(battery);
                 tui.frame_requester().schedule_frame();
             }
             AppEvent::PersistModelSelection { model, effort } => {
@@ -389,6 +592,44 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::TurnFinished => {
+                if let Some(run) = self.runbook.as_mut() {
+                    let action = run.turn_finished();
+                    self.apply_runbook_action(action);
+                }
+            }
+            AppEvent::RunbookVerified { step, outcome } => {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_runbook_verification(&outcome),
+                )));
+                if let Some(run) = self.runbook.as_mut() {
+                    let action = run.verification_finished(step, outcome.failure);
+                    self.apply_runbook_action(action);
+                }
+            }
+            AppEvent::RunbookContinue => {
+                if let Some(run) = self.runbook.as_mut() {
+                    let action = run.confirm();
+                    self.apply_runbook_action(action);
+                }
+            }
+            AppEvent::RunbookRetry => {
+                if let Some(prompt) = self.runbook.as_mut().and_then(RunbookRun::retry) {
+                    self.chat_widget.submit_text_message(prompt);
+                }
+            }
+            AppEvent::RunbookSkip => {
+                if let Some(run) = self.runbook.as_mut() {
+                    let action = run.skip_failed();
+                    self.apply_runbook_action(action);
+                }
+            }
+            AppEvent::RunbookStop => {
+                if let Some(run) = self.runbook.take() {
+                    self.chat_widget
+                        .add_info_message(format!("{} stopped.", run.runbook().title()), None);
+                }
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -412,15 +653,233 @@ impl App {
         Ok(true)
     }
 
+    /// Hide the session behind the lock screen; see [`crate::lock`].
+    fn lock_session(&mut self, tui: &mut tui::Tui) {
+        // Pager overlays are already on the alternate screen.
+        if self.overlay.is_none() {
+            let _ = tui.enter_alt_screen();
+        }
+        self.lock.lock();
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// While locked, any key press unlocks and everything else is dropped.
+    fn handle_locked_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(KeyEvent {
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.lock.unlock(Instant::now());
+                if self.overlay.is_none() {
+                    let _ = tui.leave_alt_screen();
+                    if !self.deferred_history_lines.is_empty() {
+                        let lines = std::mem::take(&mut self.deferred_history_lines);
+                        tui.insert_history_lines(lines);
+                    }
+                }
+                tui.frame_requester().schedule_frame();
+            }
+            TuiEvent::Key(_) | TuiEvent::Paste(_) | TuiEvent::Mouse(_) => {}
+            TuiEvent::Draw => {
+                let height = tui.terminal.size()?.height;
+                tui.draw(height, |frame| {
+                    let area = frame.area();
+                    render_lock_screen(area, frame.buffer_mut());
+                })?;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn token_usage(&self) -> codex_core::protocol::TokenUsage {
         self.chat_widget.token_usage()
     }
 
+    fn apply_runbook_action(&mut self, action: RunbookAction) {
+        let Some(run) = self.runbook.as_ref() else {
+            return;
+        };
+        let runbook = run.runbook();
+        match action {
+            RunbookAction::None => {}
+            RunbookAction::Start(idx) => {
+                let Some(step) = run.step(idx) else {
+                    return;
+                };
+                let prompt = step.prompt.clone();
+                self.chat_widget.add_info_message(
+                    format!("{}: {}", runbook.title(), runbook.step_label(idx)),
+                    None,
+                );
+                self.chat_widget.submit_text_message(prompt);
+            }
+            RunbookAction::Verify(idx) => {
+                let Some(step) = run.step(idx) else {
+                    return;
+                };
+                let commands = step.verify.clone();
+                self.chat_widget
+                    .add_info_message(format!("Verifying {}…", runbook.step_label(idx)), None);
+                let cwd = self.chat_widget.config_ref().cwd.clone();
+                let tx = self.app_event_tx.clone();
+                tokio::spawn(async move {
+                    let outcome = run_verification(commands, cwd).await;
+                    tx.send(AppEvent::RunbookVerified { step: idx, outcome });
+                });
+            }
+            RunbookAction::Confirm(idx) => {
+                self.chat_widget
+                    .open_runbook_confirm_popup(runbook.title(), runbook.step_label(idx));
+            }
+            RunbookAction::Failed(idx) => {
+                if let Some(failure) = run.failure() {
+                    self.chat_widget.open_runbook_failure_popup(
+                        format!(
+                            "{}: {} failed verification",
+                            runbook.title(),
+                            runbook.step_label(idx)
+                        ),
+                        failure.command.clone(),
+                    );
+                }
+            }
+            RunbookAction::Finished => {
+                self.chat_widget
+                    .add_info_message(format!("{} finished.", runbook.title()), None);
+                self.runbook = None;
+            }
+        }
+    }
+
     fn on_update_reasoning_effort(&mut self, effort: Option<ReasoningEffortConfig>) {
         self.chat_widget.set_reasoning_effort(effort);
         self.config.model_reasoning_effort = effort;
     }
 
+    async fn forward_overlay_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        if !self.handle_pin_overlay_event(tui, &event) {
+            let _ = self.handle_backtrack_overlay_event(tui, event).await?;
+        }
+        Ok(())
+    }
+
+    /// Macro keys inside the transcript overlay when `tui.macros` is on; see
+    /// [`crate::key_macros`].
+    fn handle_macro_overlay_event(&mut self, event: &TuiEvent) -> MacroKey {
+        let (TuiEvent::Key(key), Some(Overlay::Transcript(t))) = (event, &self.overlay) else {
+            return MacroKey::Forward;
+        };
+        if !self.config.tui_macros {
+            return MacroKey::Forward;
+        }
+        // The name of a mark may be `q` or `@`.
+        if t.is_awaiting_mark_name() {
+            self.macros.record(*key);
+            return MacroKey::Forward;
+        }
+        self.macros.handle_key(*key)
+    }
+
+    fn show_macro_status(&mut self, status: Option<String>) {
+        if let Some(Overlay::Transcript(t)) = &mut self.overlay {
+            t.set_macro_status(status, self.macros.recording());
+        }
+    }
+
+    /// Pin keys inside pager overlays: `p` toggles the focused transcript
+    /// entry and `e` exports the `/pins` view. Returns whether the event was
+    /// consumed.
+    fn handle_pin_overlay_event(&mut self, tui: &mut tui::Tui, event: &TuiEvent) -> bool {
+        let TuiEvent::Key(KeyEvent {
+            code: KeyCode::Char(ch),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return false;
+        };
+        match (*ch, &mut self.overlay) {
+            ('p', Some(Overlay::Transcript(t))) if !t.is_awaiting_mark_name() => {
+                let Some(idx) = t.focused_cell() else {
+                    return true;
+                };
+                self.pins.toggle(&self.transcript_cells, idx);
+                t.set_pinned_cells(self.pins.pinned_indices(&self.transcript_cells));
+                self.sync_pinned_context();
+            }
+            ('e', Some(Overlay::Pins(_))) => {
+                self.close_transcript_overlay(tui);
+                self.export_pins();
+            }
+            _ => return false,
+        }
+        tui.frame_requester().schedule_frame();
+        true
+    }
+
+    fn pin_latest_agent_message(&mut self) {
+        let Some(idx) = latest_agent_message(&self.transcript_cells) else {
+            self.chat_widget
+                .add_info_message("There is no response to pin yet.".to_string(), None);
+            return;
+        };
+        let pinned = self.pins.toggle(&self.transcript_cells, idx);
+        self.sync_pinned_context();
+        let message = if pinned {
+            "Pinned the latest response"
+        } else {
+            "Unpinned the latest response"
+        };
+        self.chat_widget.add_info_message(
+            message.to_string(),
+            Some("Use /pins to view or export pins, or p in the transcript (ctrl + t)".to_string()),
+        );
+    }
+
+    fn open_pins_overlay(&mut self, tui: &mut tui::Tui) {
+        if self.pins.is_empty() {
+            self.chat_widget.add_info_message(
+                "Nothing pinned yet.".to_string(),
+                Some("Use /pin, or p in the transcript (ctrl + t)".to_string()),
+            );
+            return;
+        }
+        let _ = tui.enter_alt_screen();
+        self.overlay = Some(Overlay::new_pins(self.pins.overlay_lines()));
+        tui.frame_requester().schedule_frame();
+    }
+
+    /// Write all pins to `pins/<conversation>.md` in the state home.
+    fn export_pins(&mut self) {
+        let conversation_id = self.chat_widget.conversation_id();
+        let file_name = conversation_id
+            .map(|id| format!("{id}.md"))
+            .unwrap_or_else(|| "session.md".to_string());
+        let dir = self.config.state_home.path.join("pins");
+        let path = dir.join(file_name);
+        let result = std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(&path, self.pins.to_markdown(conversation_id)));
+        match result {
+            // The fallback state dir is removed on exit; say so with the path.
+            Ok(()) => self.chat_widget.add_info_message(
+                format!("Exported {} pin(s) to {}", self.pins.len(), path.display()),
+                self.config.state_home.notice(),
+            ),
+            Err(err) => self
+                .chat_widget
+                .add_error_message(format!("Failed to export pins: {err}")),
+        }
+    }
+
+    /// Tell the agent which excerpts are pinned so compaction keeps them.
+    pub(crate) fn sync_pinned_context(&self) {
+        self.chat_widget.submit_op(Op::UpdatePinnedContext {
+            items: self.pins.context_items(),
+        });
+    }
+
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
         match key_event {
             KeyEvent {
@@ -430,9 +889,7 @@ impl App {
                 ..
             } => {
                 // Enter alternate screen and set viewport to full size.
-                let _ = tui.enter_alt_screen();
-                self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
-                tui.frame_requester().schedule_frame();
+                self.open_transcript_overlay(tui);
             }
             // Esc primes/advances backtracking only in normal (not working) mode
             // with an empty composer. In any other state, forward Esc so the
@@ -481,6 +938,25 @@ impl App {
     }
 }
 
+/// Resolves at `deadline`, or never.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// With `tui.mouse` on, the wheel no longer scrolls the pager overlays by
+/// itself; it becomes the arrow keys they scroll with.
+fn wheel_as_arrow_key(mouse_event: MouseEvent) -> Option<KeyEvent> {
+    let code = match mouse_event.kind {
+        MouseEventKind::ScrollUp => KeyCode::Up,
+        MouseEventKind::ScrollDown => KeyCode::Down,
+        _ => return None,
+    };
+    Some(KeyEvent::new(code, KeyModifiers::NONE))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -522,12 +998,18 @@ mod tests {
             active_profile: None,
             file_search,
             transcript_cells: Vec::new(),
+            pins: Pins::default(),
+            bookmarks: BTreeMap::new(),
+            macros: KeyMacros::default(),
+            runbook: None,
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
             enhanced_keys_supported: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
+            perf_hud: None,
+            lock: SessionLock::new(None, Instant::now()),
         }
     }
 
@@ -574,6 +1056,7 @@ mod tests {
                 history_log_id: 0,
                 history_entry_count: 0,
                 initial_messages: None,
+                pinned_context: Vec::new(),
                 rollout_path: PathBuf::new(),
             };
             Arc::new(new_session_info(
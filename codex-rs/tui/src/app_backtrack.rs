@@ -110,7 +110,12 @@ impl App {
     /// Open transcript overlay (enters alternate screen and shows full transcript).
     pub(crate) fn open_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.enter_alt_screen();
-        self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
+        self.overlay = Some(Overlay::new_transcript(
+            self.transcript_cells.clone(),
+            self.pins.pinned_indices(&self.transcript_cells),
+            self.bookmarks.clone(),
+            self.config.tui_macros,
+        ));
         tui.frame_requester().schedule_frame();
     }
 
@@ -118,6 +123,10 @@ impl App {
     pub(crate) fn close_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.leave_alt_screen();
         let was_backtrack = self.backtrack.overlay_preview_active;
+        if let Some(Overlay::Transcript(t)) = &self.overlay {
+            self.bookmarks = t.bookmarks().clone();
+            self.macros.cancel();
+        }
         if !self.deferred_history_lines.is_empty() {
             let lines = std::mem::take(&mut self.deferred_history_lines);
             tui.insert_history_lines(lines);
@@ -342,6 +351,10 @@ impl App {
             crate::chatwidget::ChatWidget::new_from_existing(init, conv, session_configured);
         // Trim transcript up to the selected user message and re-render it.
         self.trim_transcript_for_backtrack(nth_user_message);
+        self.pins.retain_present(&self.transcript_cells);
+        let len = self.transcript_cells.len();
+        self.bookmarks.retain(|_, idx| *idx < len);
+        self.sync_pinned_context();
         self.render_transcript_once(tui);
         if !prefill.is_empty() {
             self.chat_widget.set_composer_text(prefill.to_string());
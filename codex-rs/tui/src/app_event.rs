@@ -3,11 +3,18 @@ use std::path::PathBuf;
 use codex_common::model_presets::ModelPreset;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::system_info::Battery;
+use codex_core::system_info::SystemResources;
+use codex_core::todos::TodoComment;
 use codex_file_search::FileMatch;
 
 use crate::bottom_pane::ApprovalRequest;
 use crate::history_cell::HistoryCell;
-use crate::statusline::StatusLineGitSnapshot;
+use crate::runbook::VerifyOutcome;
+use crate::statusline::StatusLineDockerSnapshot;
+use crate::statusline::probes::ProbeReport;
 
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
@@ -18,6 +25,9 @@ use codex_core::protocol_config_types::ReasoningEffort;
 pub(crate) enum AppEvent {
     CodexEvent(Event),
 
+    /// An event from a recorded session played back by `codex replay`.
+    ReplayEvent(EventMsg),
+
     /// Start a new session.
     NewSession,
 
@@ -44,6 +54,36 @@ pub(crate) enum AppEvent {
     /// Result of computing a `/diff` command.
     DiffResult(String),
 
+    /// An MCP tool call result piped through the `json` output filter; `None`
+    /// when the filter failed and the result is shown as is.
+    McpResultFiltered(McpToolCallEndEvent, Option<String>),
+
+    /// Comments found by a `/todos` scan.
+    TodosResult(Vec<TodoComment>),
+
+    /// Send text as if the user had typed it, e.g. a prompt picked in a popup.
+    SubmitText(String),
+
+    /// The user denied this request in the approval prompt; kept for
+    /// `/retry-denied`.
+    ActionDenied(ApprovalRequest),
+
+    /// Put the prompt retrying the denied action at this index in the
+    /// composer (`/retry-denied`).
+    RetryDeniedAction(usize),
+
+    /// Toggle the pin on the most recent agent message (`/pin`).
+    PinLatestAgentMessage,
+
+    /// Show all pinned entries (`/pins`).
+    OpenPins,
+
+    /// Pins recorded by the resumed or forked session.
+    RestorePins(Vec<String>),
+
+    /// Show or hide the performance HUD (`/debug perf`).
+    TogglePerfHud,
+
     InsertHistoryCell(Box<dyn HistoryCell>),
 
     StartCommitAnimation,
@@ -68,8 +108,12 @@ pub(crate) enum AppEvent {
         presets: Vec<ModelPreset>,
     },
 
-    StatusLineGit(Option<StatusLineGitSnapshot>),
-    StatusLineKubeContext(Option<String>),
+    /// A status line probe's result; see [`crate::statusline::probes`].
+    StatusLineProbe(ProbeReport),
+    StatusLineAzureSubscription(Option<String>),
+    StatusLineDocker(Option<StatusLineDockerSnapshot>),
+    StatusLineResources(SystemResources),
+    StatusLineBattery(Option<Battery>),
     /// Update the current approval policy in the running app and widget.
     UpdateAskForApprovalPolicy(AskForApproval),
 
@@ -90,4 +134,25 @@ pub(crate) enum AppEvent {
 
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
+
+    /// The agent finished a turn and no queued message was sent after it.
+    TurnFinished,
+
+    /// The verification commands of a runbook step finished.
+    RunbookVerified {
+        step: usize,
+        outcome: VerifyOutcome,
+    },
+
+    /// Start the runbook step the user was asked to confirm.
+    RunbookContinue,
+
+    /// Ask the agent to fix the failed verification of the current runbook step.
+    RunbookRetry,
+
+    /// Move on to the next runbook step despite a failed verification.
+    RunbookSkip,
+
+    /// Abandon the running runbook.
+    RunbookStop,
 }
@@ -1,16 +1,51 @@
+use std::sync::Arc;
+
+use codex_core::protocol::Event;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::app_event::AppEvent;
 use crate::session_log;
 
+/// Agent events forwarded to the app but not yet handled. Once this many are
+/// queued, forwarding waits, so a busy agent cannot flood the queue that key
+/// presses share.
+const MAX_UNHANDLED_CODEX_EVENTS: usize = 256;
+
 #[derive(Clone, Debug)]
 pub(crate) struct AppEventSender {
     pub app_event_tx: UnboundedSender<AppEvent>,
+    codex_event_slots: Arc<Semaphore>,
 }
 
 impl AppEventSender {
     pub(crate) fn new(app_event_tx: UnboundedSender<AppEvent>) -> Self {
-        Self { app_event_tx }
+        Self {
+            app_event_tx,
+            codex_event_slots: Arc::new(Semaphore::new(MAX_UNHANDLED_CODEX_EVENTS)),
+        }
+    }
+
+    /// Wait until the app has room for another agent event.
+    pub(crate) async fn codex_event_slot(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.codex_event_slots)
+            .acquire_owned()
+            .await
+            .ok()
+    }
+
+    /// Forward an agent event into a slot from [`Self::codex_event_slot`];
+    /// the slot is freed by [`Self::codex_event_handled`].
+    pub(crate) fn send_codex_event(&self, slot: OwnedSemaphorePermit, event: Event) {
+        slot.forget();
+        self.send(AppEvent::CodexEvent(event));
+    }
+
+    pub(crate) fn codex_event_handled(&self) {
+        if self.codex_event_slots.available_permits() < MAX_UNHANDLED_CODEX_EVENTS {
+            self.codex_event_slots.add_permits(1);
+        }
     }
 
     /// Send an event to the app event channel. If it fails, we swallow the
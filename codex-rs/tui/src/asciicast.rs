@@ -0,0 +1,126 @@
+//! Recording of the TUI's terminal output as an [asciicast v2] file, for
+//! `codex replay <SESSION_ID> --record <FILE>`. The recording is what the
+//! replay draws, so it plays back in `asciinema play` or the web player
+//! exactly as it looked in the terminal.
+//!
+//! [asciicast v2]: https://docs.asciinema.org/manual/asciicast/v2/
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Result;
+use std::io::Write;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+use serde_json::json;
+
+pub(crate) struct AsciicastRecorder<W: Write> {
+    out: W,
+    started: Instant,
+    /// Trailing bytes of an incomplete UTF-8 sequence, held until the rest
+    /// of it is written; asciicast events are strings.
+    pending: Vec<u8>,
+}
+
+impl AsciicastRecorder<BufWriter<File>> {
+    /// Create `path` for a `width` x `height` terminal.
+    pub(crate) fn create(path: &Path, width: u16, height: u16) -> Result<Self> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_secs())
+            .unwrap_or_default();
+        Self::new(
+            BufWriter::new(File::create(path)?),
+            width,
+            height,
+            timestamp,
+        )
+    }
+}
+
+impl<W: Write> AsciicastRecorder<W> {
+    fn new(mut out: W, width: u16, height: u16, timestamp: u64) -> Result<Self> {
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": timestamp,
+        });
+        writeln!(out, "{header}")?;
+        Ok(Self {
+            out,
+            started: Instant::now(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Record `bytes` written to the terminal now.
+    pub(crate) fn record(&mut self, bytes: &[u8]) -> Result<()> {
+        self.record_at(self.started.elapsed(), bytes)
+    }
+
+    fn record_at(&mut self, elapsed: Duration, bytes: &[u8]) -> Result<()> {
+        self.pending.extend_from_slice(bytes);
+        let mut data = String::new();
+        let mut rest = self.pending.as_slice();
+        while !rest.is_empty() {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    data.push_str(valid);
+                    rest = &[];
+                }
+                Err(err) => {
+                    let (valid, after) = rest.split_at(err.valid_up_to());
+                    data.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                    // An unfinished sequence waits for the next write;
+                    // invalid bytes are replaced.
+                    let Some(len) = err.error_len() else {
+                        rest = after;
+                        break;
+                    };
+                    data.push(char::REPLACEMENT_CHARACTER);
+                    rest = &after[len..];
+                }
+            }
+        }
+        self.pending = rest.to_vec();
+        if data.is_empty() {
+            return Ok(());
+        }
+        let event = json!([elapsed.as_secs_f64(), "o", data]);
+        writeln!(self.out, "{event}")
+    }
+
+    pub(crate) fn flush(&mut self) -> Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn writes_a_header_and_output_events() {
+        let mut recorder =
+            AsciicastRecorder::new(Vec::new(), 80, 24, 1_700_000_000).expect("header is written");
+        recorder
+            .record_at(Duration::from_millis(0), b"\x1b[2Jcaf\xc3")
+            .expect("event is written");
+        recorder
+            .record_at(Duration::from_millis(1500), b"\xa9 \"ok\"\n")
+            .expect("event is written");
+
+        let lines = String::from_utf8(recorder.out).expect("recording is UTF-8");
+        assert_eq!(
+            lines.lines().collect::<Vec<_>>(),
+            vec![
+                r#"{"version":2,"width":80,"height":24,"timestamp":1700000000}"#,
+                r#"[0.0,"o","\u001b[2Jcaf"]"#,
+                r#"[1.5,"o","é \"ok\"\n"]"#,
+            ]
+        );
+    }
+}
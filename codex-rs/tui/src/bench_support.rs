@@ -0,0 +1,109 @@
+//! Entry points into the render path for the benchmarks in `benches/`. Not a
+//! stable API: it exists so the benchmarks can drive crate-private widgets.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_core::config::Config;
+use codex_protocol::parse_command::ParsedCommand;
+use ratatui::text::Line;
+
+use crate::exec_cell::CommandOutput;
+use crate::exec_cell::new_active_exec_command;
+use crate::history_cell;
+use crate::history_cell::AgentMessageCell;
+use crate::history_cell::HistoryCell;
+use crate::markdown::append_markdown;
+use crate::statusline::StatusLineState;
+use crate::tui::FrameRequester;
+
+/// A status line mid-turn, with four queued messages.
+pub struct StatusLineFixture {
+    state: StatusLineState,
+}
+
+impl StatusLineFixture {
+    pub fn new(config: &Config, queued_message_bytes: usize) -> Self {
+        let mut state = StatusLineState::new(config, FrameRequester::test_dummy());
+        state.set_hostname(Some("build-host".to_string()));
+        state.set_aws_profile(Some("prod".to_string()));
+        state.set_kubernetes_context(Some("codex-dev".to_string()));
+        state.start_task("Working");
+        state.set_queued_messages(vec!["x".repeat(queued_message_bytes); 4]);
+        Self { state }
+    }
+
+    pub fn render(&self, width: u16) -> Line<'static> {
+        self.state.render_line(width)
+    }
+}
+
+/// A transcript of `turns` turns, each a user prompt, a command with
+/// `output_lines` lines of output and an agent message rendered from
+/// `markdown`.
+pub struct TranscriptFixture {
+    cells: Vec<Arc<dyn HistoryCell>>,
+}
+
+impl TranscriptFixture {
+    pub fn new(config: &Config, markdown: &str, turns: usize, output_lines: usize) -> Self {
+        let output: Arc<str> = (0..output_lines)
+            .map(|line| format!("line {line}\n"))
+            .collect::<String>()
+            .into();
+        let mut cells: Vec<Arc<dyn HistoryCell>> = Vec::with_capacity(turns * 3);
+        for turn in 0..turns {
+            cells.push(Arc::new(history_cell::new_user_prompt(format!(
+                "turn {turn}: explain the change"
+            ))));
+            let call_id = format!("call-{turn}");
+            let mut exec = new_active_exec_command(
+                call_id.clone(),
+                vec!["cargo".to_string(), "test".to_string()],
+                vec![ParsedCommand::Unknown {
+                    cmd: "cargo test".to_string(),
+                }],
+            );
+            exec.complete_call(
+                &call_id,
+                CommandOutput {
+                    exit_code: 0,
+                    stdout: output.clone(),
+                    stderr: "".into(),
+                    formatted_output: output.clone(),
+                },
+                Duration::from_secs(1),
+            );
+            cells.push(Arc::new(exec));
+            cells.push(Arc::new(AgentMessageCell::new(
+                render_markdown(config, markdown),
+                true,
+            )));
+        }
+        Self { cells }
+    }
+
+    /// Lay the transcript out at `width`, as inserting it into the history
+    /// does; returns the number of lines.
+    pub fn layout(&self, width: u16) -> usize {
+        self.cells
+            .iter()
+            .map(|cell| cell.display_lines(width).len())
+            .sum()
+    }
+
+    /// Lines for the transcript overlay (`Ctrl+T`), which does not wrap.
+    pub fn transcript_lines(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|cell| cell.transcript_lines().len())
+            .sum()
+    }
+}
+
+/// Render an agent message's markdown to unwrapped lines.
+pub fn render_markdown(config: &Config, markdown: &str) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    append_markdown(markdown, None, &mut lines, config);
+    lines
+}
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::BottomPaneView;
 use crate::bottom_pane::CancellationEvent;
+use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::list_selection_view::ListSelectionView;
 use crate::bottom_pane::list_selection_view::SelectionItem;
 use crate::bottom_pane::list_selection_view::SelectionViewParams;
@@ -48,14 +52,24 @@ pub(crate) enum ApprovalRequest {
     },
 }
 
+/// Most queued requests listed under the current one.
+const MAX_QUEUED_PREVIEW: usize = 5;
+
 /// Modal overlay asking the user to approve or deny one or more requests.
+/// Requests arriving while one is shown wait in a queue, listed under it,
+/// and can be decided together or skipped past with Tab. A command can be
+/// edited with `e` and the edited version approved instead.
 pub(crate) struct ApprovalOverlay {
     current_request: Option<ApprovalRequest>,
     current_variant: Option<ApprovalVariant>,
-    queue: Vec<ApprovalRequest>,
+    queue: VecDeque<ApprovalRequest>,
     app_event_tx: AppEventSender,
     list: ListSelectionView,
     options: Vec<ApprovalOption>,
+    /// Editor for the current command while it is being edited.
+    editor: Option<CustomPromptView>,
+    /// What the editor submitted, picked up when it closes.
+    edited_command: Arc<Mutex<Option<String>>>,
     current_complete: bool,
     done: bool,
 }
@@ -65,10 +79,12 @@ impl ApprovalOverlay {
         let mut view = Self {
             current_request: None,
             current_variant: None,
-            queue: Vec::new(),
+            queue: VecDeque::new(),
             app_event_tx: app_event_tx.clone(),
             list: ListSelectionView::new(Default::default(), app_event_tx),
             options: Vec::new(),
+            editor: None,
+            edited_command: Arc::new(Mutex::new(None)),
             current_complete: false,
             done: false,
         };
@@ -77,24 +93,42 @@ impl ApprovalOverlay {
     }
 
     pub fn enqueue_request(&mut self, req: ApprovalRequest) {
-        self.queue.push(req);
+        self.queue.push_back(req);
+        if !self.current_complete {
+            self.rebuild();
+        }
     }
 
     fn set_current(&mut self, request: ApprovalRequest) {
-        self.current_request = Some(request.clone());
+        self.current_request = Some(request);
+        self.current_complete = false;
+        self.list = ListSelectionView::new(Default::default(), self.app_event_tx.clone());
+        self.rebuild();
+    }
+
+    /// Rebuild the view for the current request and the queue behind it,
+    /// keeping the highlighted option.
+    fn rebuild(&mut self) {
+        let Some(request) = self.current_request.clone() else {
+            return;
+        };
         let ApprovalRequestState { variant, header } = ApprovalRequestState::from(request);
         self.current_variant = Some(variant.clone());
-        self.current_complete = false;
-        let (options, params) = Self::build_options(variant, header);
+        let selected = self.list.selected_index();
+        let (options, params) = Self::build_options(variant, header, &self.queue);
         self.options = options;
         self.list = ListSelectionView::new(params, self.app_event_tx.clone());
+        if let Some(selected) = selected {
+            self.list.select_index(selected);
+        }
     }
 
     fn build_options(
         variant: ApprovalVariant,
         header: Box<dyn Renderable>,
+        queue: &VecDeque<ApprovalRequest>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
-        let (options, title) = match &variant {
+        let (mut options, title) = match &variant {
             ApprovalVariant::Exec { .. } => (
                 exec_options(),
                 "Would you like to run the following command?".to_string(),
@@ -104,12 +138,19 @@ impl ApprovalOverlay {
                 "Would you like to make the following edits?".to_string(),
             ),
         };
+        if !queue.is_empty() {
+            options.extend(queue_options(&variant, queue));
+        }
 
-        let header = Box::new(ColumnRenderable::new([
+        let mut header: Vec<Box<dyn Renderable>> = vec![
             Box::new(Line::from(title.bold())),
             Box::new(Line::from("")),
             header,
-        ]));
+        ];
+        if !queue.is_empty() {
+            header.push(Box::new(queued_preview(queue)));
+        }
+        let header = Box::new(ColumnRenderable::new(header));
 
         let items = options
             .iter()
@@ -121,14 +162,29 @@ impl ApprovalOverlay {
             })
             .collect();
 
+        let mut footer_hint = vec![
+            "Press ".into(),
+            key_hint::plain(KeyCode::Enter).into(),
+            " to confirm or ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to cancel".into(),
+        ];
+        if matches!(variant, ApprovalVariant::Exec { .. }) {
+            footer_hint.extend([
+                ", ".into(),
+                key_hint::plain(KeyCode::Char('e')).into(),
+                " to edit the command".into(),
+            ]);
+        }
+        if !queue.is_empty() {
+            footer_hint.extend([
+                ", ".into(),
+                key_hint::plain(KeyCode::Tab).into(),
+                " to decide the next one first".into(),
+            ]);
+        }
         let params = SelectionViewParams {
-            footer_hint: Some(Line::from(vec![
-                "Press ".into(),
-                key_hint::plain(KeyCode::Enter).into(),
-                " to confirm or ".into(),
-                key_hint::plain(KeyCode::Esc).into(),
-                " to cancel".into(),
-            ])),
+            footer_hint: Some(Line::from(footer_hint)),
             items,
             header,
             ..Default::default()
@@ -141,7 +197,7 @@ impl ApprovalOverlay {
         if self.current_complete {
             return;
         }
-        let Some(option) = self.options.get(actual_idx) else {
+        let Some(option) = self.options.get(actual_idx).cloned() else {
             return;
         };
         if let Some(variant) = self.current_variant.as_ref() {
@@ -154,11 +210,59 @@ impl ApprovalOverlay {
                 }
             }
         }
+        if let Some(request) = self.current_request.as_ref() {
+            self.remember_denial(request, option.decision);
+        }
+
+        let decided: Vec<ApprovalRequest> = match &option.scope {
+            DecisionScope::Current => Vec::new(),
+            DecisionScope::AllQueued => self.queue.drain(..).collect(),
+            DecisionScope::Program(program) => {
+                let (matching, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queue)
+                    .into_iter()
+                    .partition(|request| request_program(request).as_ref() == Some(program));
+                self.queue = rest.into();
+                matching
+            }
+        };
+        for request in decided {
+            self.send_decision(&request, option.decision);
+        }
 
         self.current_complete = true;
         self.advance_queue();
     }
 
+    fn send_decision(&self, request: &ApprovalRequest, decision: ReviewDecision) {
+        self.remember_denial(request, decision);
+        match request {
+            ApprovalRequest::Exec { id, command, .. } => {
+                self.handle_exec_decision(id, command, decision);
+            }
+            ApprovalRequest::ApplyPatch { id, .. } => self.handle_patch_decision(id, decision),
+        }
+    }
+
+    /// Keep a denied request for `/retry-denied`.
+    fn remember_denial(&self, request: &ApprovalRequest, decision: ReviewDecision) {
+        if matches!(decision, ReviewDecision::Denied | ReviewDecision::Abort) {
+            self.app_event_tx
+                .send(AppEvent::ActionDenied(request.clone()));
+        }
+    }
+
+    /// Put the current request at the back of the queue and show the next.
+    fn skip_current(&mut self) -> bool {
+        if self.current_complete || self.queue.is_empty() {
+            return false;
+        }
+        if let Some(current) = self.current_request.take() {
+            self.queue.push_back(current);
+        }
+        self.advance_queue();
+        true
+    }
+
     fn handle_exec_decision(&self, id: &str, command: &[String], decision: ReviewDecision) {
         if let Some(lines) = build_exec_history_lines(command.to_vec(), decision) {
             self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
@@ -171,6 +275,73 @@ impl ApprovalOverlay {
         }));
     }
 
+    /// Open an editor on the current command.
+    fn start_editing(&mut self) -> bool {
+        if self.current_complete {
+            return false;
+        }
+        let Some(ApprovalVariant::Exec { command, .. }) = self.current_variant.as_ref() else {
+            return false;
+        };
+        let edited_command = self.edited_command.clone();
+        let editor = CustomPromptView::new(
+            "Edit the command to run".to_string(),
+            "Type the command".to_string(),
+            None,
+            Box::new(move |text: String| {
+                if let Ok(mut slot) = edited_command.lock() {
+                    *slot = Some(text);
+                }
+            }),
+        )
+        .with_text(&strip_bash_lc_and_escape(command));
+        self.editor = Some(editor);
+        true
+    }
+
+    /// Close the editor once it is done, approving what it submitted.
+    fn finish_editing(&mut self) {
+        if !self
+            .editor
+            .as_ref()
+            .is_some_and(BottomPaneView::is_complete)
+        {
+            return;
+        }
+        self.editor = None;
+        let text = self
+            .edited_command
+            .lock()
+            .ok()
+            .and_then(|mut slot| slot.take());
+        if let Some(text) = text {
+            self.approve_edited(&text);
+        }
+    }
+
+    /// Approve the current command as edited into `text`. An unchanged
+    /// command is a plain approval.
+    fn approve_edited(&mut self, text: &str) {
+        let Some(ApprovalVariant::Exec { id, command }) = self.current_variant.clone() else {
+            return;
+        };
+        let edited = edited_command(&command, text);
+        if edited == command {
+            self.handle_exec_decision(&id, &command, ReviewDecision::Approved);
+        } else {
+            self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                history_cell::new_user_approval_decision(build_edited_exec_history_lines(&edited)),
+            )));
+            self.app_event_tx
+                .send(AppEvent::CodexOp(Op::ExecApprovalWithEdit {
+                    id,
+                    command: edited,
+                }));
+        }
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
     fn handle_patch_decision(&self, id: &str, decision: ReviewDecision) {
         self.app_event_tx.send(AppEvent::CodexOp(Op::PatchApproval {
             id: id.to_string(),
@@ -179,7 +350,7 @@ impl ApprovalOverlay {
     }
 
     fn advance_queue(&mut self) {
-        if let Some(next) = self.queue.pop() {
+        if let Some(next) = self.queue.pop_front() {
             self.set_current(next);
         } else {
             self.done = true;
@@ -202,6 +373,17 @@ impl ApprovalOverlay {
                     false
                 }
             }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Tab,
+                ..
+            } => self.skip_current(),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => self.start_editing(),
             e => {
                 if let Some(idx) = self
                     .options
@@ -220,6 +402,11 @@ impl ApprovalOverlay {
 
 impl BottomPaneView for ApprovalOverlay {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if let Some(editor) = self.editor.as_mut() {
+            editor.handle_key_event(key_event);
+            self.finish_editing();
+            return;
+        }
         if self.try_handle_shortcut(&key_event) {
             return;
         }
@@ -233,6 +420,10 @@ impl BottomPaneView for ApprovalOverlay {
         if self.done {
             return CancellationEvent::Handled;
         }
+        // Leave the editor, back to the options.
+        if self.editor.take().is_some() {
+            return CancellationEvent::Handled;
+        }
         if !self.current_complete
             && let Some(variant) = self.current_variant.as_ref()
         {
@@ -244,6 +435,9 @@ impl BottomPaneView for ApprovalOverlay {
                     self.handle_patch_decision(id, ReviewDecision::Abort);
                 }
             }
+            if let Some(request) = self.current_request.as_ref() {
+                self.remember_denial(request, ReviewDecision::Abort);
+            }
         }
         self.queue.clear();
         self.done = true;
@@ -262,18 +456,33 @@ impl BottomPaneView for ApprovalOverlay {
         None
     }
 
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        self.editor
+            .as_mut()
+            .is_some_and(|editor| editor.handle_paste(pasted))
+    }
+
     fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
-        self.list.cursor_pos(area)
+        match &self.editor {
+            Some(editor) => editor.cursor_pos(area),
+            None => self.list.cursor_pos(area),
+        }
     }
 }
 
 impl Renderable for ApprovalOverlay {
     fn desired_height(&self, width: u16) -> u16 {
-        self.list.desired_height(width)
+        match &self.editor {
+            Some(editor) => editor.desired_height(width),
+            None => self.list.desired_height(width),
+        }
     }
 
     fn render(&self, area: Rect, buf: &mut Buffer) {
-        self.list.render(area, buf);
+        match &self.editor {
+            Some(editor) => editor.render(area, buf),
+            None => self.list.render(area, buf),
+        }
     }
 }
 
@@ -343,10 +552,20 @@ enum ApprovalVariant {
 struct ApprovalOption {
     label: String,
     decision: ReviewDecision,
+    /// Queued requests that get the same decision.
+    scope: DecisionScope,
     display_shortcut: Option<KeyBinding>,
     additional_shortcuts: Vec<KeyBinding>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+enum DecisionScope {
+    Current,
+    AllQueued,
+    /// Queued commands running the same program as the current one.
+    Program(String),
+}
+
 impl ApprovalOption {
     fn shortcuts(&self) -> impl Iterator<Item = KeyBinding> + '_ {
         self.display_shortcut
@@ -360,18 +579,21 @@ fn exec_options() -> Vec<ApprovalOption> {
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ReviewDecision::Approved,
+            scope: DecisionScope::Current,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
             label: "Yes, and don't ask again for this command".to_string(),
             decision: ReviewDecision::ApprovedForSession,
+            scope: DecisionScope::Current,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ReviewDecision::Abort,
+            scope: DecisionScope::Current,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
@@ -383,18 +605,107 @@ fn patch_options() -> Vec<ApprovalOption> {
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ReviewDecision::Approved,
+            scope: DecisionScope::Current,
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
             decision: ReviewDecision::Abort,
+            scope: DecisionScope::Current,
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
     ]
 }
 
+/// Batch options offered while other requests are queued.
+fn queue_options(
+    variant: &ApprovalVariant,
+    queue: &VecDeque<ApprovalRequest>,
+) -> Vec<ApprovalOption> {
+    let mut options = Vec::new();
+    if let ApprovalVariant::Exec { command, .. } = variant
+        && let Some(program) = command_program(command)
+    {
+        let matching = queue
+            .iter()
+            .filter(|request| request_program(request).as_ref() == Some(&program))
+            .count();
+        if matching > 0 {
+            options.push(ApprovalOption {
+                label: format!(
+                    "Yes, and the {matching} queued `{program}` command{}",
+                    if matching == 1 { "" } else { "s" }
+                ),
+                decision: ReviewDecision::Approved,
+                scope: DecisionScope::Program(program),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('m'))],
+            });
+        }
+    }
+    let count = queue.len();
+    options.push(ApprovalOption {
+        label: format!("Yes to this and all {count} queued"),
+        decision: ReviewDecision::Approved,
+        scope: DecisionScope::AllQueued,
+        display_shortcut: None,
+        additional_shortcuts: vec![
+            key_hint::shift(KeyCode::Char('Y')),
+            key_hint::plain(KeyCode::Char('Y')),
+        ],
+    });
+    options.push(ApprovalOption {
+        label: format!("No to this and all {count} queued"),
+        decision: ReviewDecision::Abort,
+        scope: DecisionScope::AllQueued,
+        display_shortcut: None,
+        additional_shortcuts: vec![
+            key_hint::shift(KeyCode::Char('N')),
+            key_hint::plain(KeyCode::Char('N')),
+        ],
+    });
+    options
+}
+
+/// One line per queued request, below the current one.
+fn queued_preview(queue: &VecDeque<ApprovalRequest>) -> Paragraph<'static> {
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(format!("Queued after this one ({}):", queue.len()).dim()),
+    ];
+    for request in queue.iter().take(MAX_QUEUED_PREVIEW) {
+        let summary = match request {
+            ApprovalRequest::Exec { command, .. } => format!("$ {}", exec_snippet(command)),
+            ApprovalRequest::ApplyPatch { changes, .. } => match changes.len() {
+                1 => "edit 1 file".to_string(),
+                count => format!("edit {count} files"),
+            },
+        };
+        lines.push(Line::from(format!("  {summary}")).dim());
+    }
+    if queue.len() > MAX_QUEUED_PREVIEW {
+        lines.push(Line::from(format!("  … and {} more", queue.len() - MAX_QUEUED_PREVIEW)).dim());
+    }
+    Paragraph::new(lines).wrap(Wrap { trim: false })
+}
+
+/// The program a command runs, e.g. `cargo` for `bash -lc "cargo test"`.
+fn command_program(command: &[String]) -> Option<String> {
+    strip_bash_lc_and_escape(command)
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+fn request_program(request: &ApprovalRequest) -> Option<String> {
+    match request {
+        ApprovalRequest::Exec { command, .. } => command_program(command),
+        ApprovalRequest::ApplyPatch { .. } => None,
+    }
+}
+
 fn build_exec_history_lines(
     command: Vec<String>,
     decision: ReviewDecision,
@@ -462,6 +773,29 @@ fn build_exec_history_lines(
     Some(lines)
 }
 
+fn build_edited_exec_history_lines(command: &[String]) -> Vec<Line<'static>> {
+    vec![Line::from(vec![
+        "✔ ".green(),
+        "You ".into(),
+        "approved".bold(),
+        " codex to run ".into(),
+        Span::from(exec_snippet(command)).dim(),
+        " as you edited it".bold(),
+    ])]
+}
+
+/// The argv to run for `command` edited into `text`: a `bash -lc` script is
+/// replaced in place, anything else is split like a shell would.
+fn edited_command(command: &[String], text: &str) -> Vec<String> {
+    match command {
+        [bash, flag, _] if bash == "bash" && flag == "-lc" => {
+            vec![bash.clone(), flag.clone(), text.to_string()]
+        }
+        _ => shlex::split(text)
+            .unwrap_or_else(|| vec!["bash".to_string(), "-lc".to_string(), text.to_string()]),
+    }
+}
+
 fn truncate_exec_snippet(full_cmd: &str) -> String {
     let mut snippet = match full_cmd.split_once('\n') {
         Some((first, _)) => format!("{first} ..."),
@@ -480,6 +814,7 @@ fn exec_snippet(command: &[String]) -> String {
 mod tests {
     use super::*;
     use crate::app_event::AppEvent;
+    use pretty_assertions::assert_eq;
     use tokio::sync::mpsc::unbounded_channel;
 
     fn make_exec_request() -> ApprovalRequest {
@@ -501,6 +836,77 @@ mod tests {
         assert!(view.is_complete());
     }
 
+    #[test]
+    fn denied_requests_are_kept_for_retry() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(exec_request("1", &["cargo", "test"]), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+
+        let mut denied = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::ActionDenied(ApprovalRequest::Exec { id, .. }) = ev {
+                denied.push(id);
+            }
+        }
+        assert_eq!(denied, vec!["1".to_string()]);
+    }
+
+    fn exec_request(id: &str, command: &[&str]) -> ApprovalRequest {
+        ApprovalRequest::Exec {
+            id: id.to_string(),
+            command: command.iter().map(ToString::to_string).collect(),
+            reason: None,
+        }
+    }
+
+    fn approved_ids(rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>) -> Vec<String> {
+        let mut ids = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ExecApproval { id, decision }) = ev {
+                assert_eq!(decision, ReviewDecision::Approved);
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    #[test]
+    fn approves_queued_commands_running_the_same_program() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(exec_request("1", &["cargo", "build"]), tx);
+        view.enqueue_request(exec_request("2", &["rm", "-rf", "target"]));
+        view.enqueue_request(exec_request("3", &["cargo", "test"]));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE));
+
+        assert_eq!(approved_ids(&mut rx), vec!["1", "3"]);
+        assert!(!view.is_complete());
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::Exec { ref id, .. }) if id == "2"
+        ));
+    }
+
+    #[test]
+    fn tab_defers_current_request_and_approve_all_drains_queue() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(exec_request("1", &["ls"]), tx);
+        view.enqueue_request(exec_request("2", &["pwd"]));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::Exec { ref id, .. }) if id == "2"
+        ));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT));
+        assert_eq!(approved_ids(&mut rx), vec!["2", "1"]);
+        assert!(view.is_complete());
+    }
+
     #[test]
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -571,4 +977,64 @@ mod tests {
         }
         assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
     }
+
+    #[test]
+    fn edited_command_is_approved_in_place_of_the_proposed_one() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view =
+            ApprovalOverlay::new(exec_request("1", &["bash", "-lc", "git push --force"]), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        for _ in 0.."--force".len() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        }
+        for c in "--dry-run".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(view.is_complete());
+
+        let mut approvals = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                AppEvent::CodexOp(Op::ExecApprovalWithEdit { id, command }) => {
+                    approvals.push((id, command));
+                }
+                AppEvent::CodexOp(Op::ExecApproval { .. }) => {
+                    panic!("the edited command should not be approved as proposed");
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            approvals,
+            vec![(
+                "1".to_string(),
+                vec![
+                    "bash".to_string(),
+                    "-lc".to_string(),
+                    "git push --dry-run".to_string()
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn esc_leaves_the_editor_without_deciding() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(exec_request("1", &["cargo", "test"]), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        assert!(view.editor.is_some());
+
+        assert_eq!(CancellationEvent::Handled, view.on_ctrl_c());
+        assert!(view.editor.is_none());
+        assert!(!view.is_complete());
+        assert!(rx.try_recv().is_err());
+
+        // An unchanged command is a plain approval.
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(approved_ids(&mut rx), vec!["1".to_string()]);
+    }
 }
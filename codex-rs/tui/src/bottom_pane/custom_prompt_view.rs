@@ -24,12 +24,16 @@ use super::textarea::TextAreaState;
 /// Callback invoked when the user submits a custom prompt.
 pub(crate) type PromptSubmitted = Box<dyn Fn(String) + Send + Sync>;
 
+/// Callback invoked when the user dismisses the view without submitting.
+pub(crate) type PromptCancelled = Box<dyn FnOnce() + Send + Sync>;
+
 /// Minimal multi-line text input view to collect custom review instructions.
 pub(crate) struct CustomPromptView {
     title: String,
     placeholder: String,
     context_label: Option<String>,
     on_submit: PromptSubmitted,
+    on_cancel: Option<PromptCancelled>,
 
     // UI state
     textarea: TextArea,
@@ -49,11 +53,24 @@ impl CustomPromptView {
             placeholder,
             context_label,
             on_submit,
+            on_cancel: None,
             textarea: TextArea::new(),
             textarea_state: RefCell::new(TextAreaState::default()),
             complete: false,
         }
     }
+
+    /// Start with `text` in the input, the cursor after it.
+    pub(crate) fn with_text(mut self, text: &str) -> Self {
+        self.textarea.set_text(text);
+        self.textarea.set_cursor(text.len());
+        self
+    }
+
+    pub(crate) fn with_on_cancel(mut self, on_cancel: PromptCancelled) -> Self {
+        self.on_cancel = Some(on_cancel);
+        self
+    }
 }
 
 impl BottomPaneView for CustomPromptView {
@@ -88,6 +105,9 @@ impl BottomPaneView for CustomPromptView {
     }
 
     fn on_ctrl_c(&mut self) -> CancellationEvent {
+        if let Some(on_cancel) = self.on_cancel.take() {
+            on_cancel();
+        }
         self.complete = true;
         CancellationEvent::Handled
     }
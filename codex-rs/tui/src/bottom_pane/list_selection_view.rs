@@ -52,6 +52,8 @@ pub(crate) struct SelectionViewParams {
     pub is_searchable: bool,
     pub search_placeholder: Option<String>,
     pub header: Box<dyn Renderable>,
+    /// Run when the view is dismissed without a selection.
+    pub on_cancel: Option<SelectionAction>,
 }
 
 impl Default for SelectionViewParams {
@@ -64,6 +66,7 @@ impl Default for SelectionViewParams {
             is_searchable: false,
             search_placeholder: None,
             header: Box::new(()),
+            on_cancel: None,
         }
     }
 }
@@ -80,6 +83,7 @@ pub(crate) struct ListSelectionView {
     filtered_indices: Vec<usize>,
     last_selected_actual_idx: Option<usize>,
     header: Box<dyn Renderable>,
+    on_cancel: Option<SelectionAction>,
 }
 
 impl ListSelectionView {
@@ -110,6 +114,7 @@ impl ListSelectionView {
             filtered_indices: Vec::new(),
             last_selected_actual_idx: None,
             header,
+            on_cancel: params.on_cancel,
         };
         s.apply_filter();
         s
@@ -246,6 +251,20 @@ impl ListSelectionView {
     pub(crate) fn take_last_selected_index(&mut self) -> Option<usize> {
         self.last_selected_actual_idx.take()
     }
+
+    /// Index of the highlighted item among the visible ones.
+    pub(crate) fn selected_index(&self) -> Option<usize> {
+        self.state.selected_idx
+    }
+
+    /// Highlight the visible item at `idx`, if there is one.
+    pub(crate) fn select_index(&mut self, idx: usize) {
+        let len = self.visible_len();
+        if idx < len {
+            self.state.selected_idx = Some(idx);
+            self.state.ensure_visible(len, Self::max_visible_rows(len));
+        }
+    }
 }
 
 impl BottomPaneView for ListSelectionView {
@@ -313,6 +332,9 @@ impl BottomPaneView for ListSelectionView {
     }
 
     fn on_ctrl_c(&mut self) -> CancellationEvent {
+        if let Some(on_cancel) = self.on_cancel.take() {
+            on_cancel(&self.app_event_tx);
+        }
         self.complete = true;
         CancellationEvent::Handled
     }
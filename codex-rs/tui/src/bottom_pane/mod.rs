@@ -154,10 +154,13 @@ impl BottomPane {
         if let Some(view) = self.view_stack.last_mut() {
             if key_event.code == KeyCode::Esc
                 && matches!(view.on_ctrl_c(), CancellationEvent::Handled)
-                && view.is_complete()
             {
-                self.view_stack.pop();
-                self.on_active_view_complete();
+                // A view that stays open handled Esc itself, e.g. by leaving
+                // an editing mode.
+                if view.is_complete() {
+                    self.view_stack.pop();
+                    self.on_active_view_complete();
+                }
             } else {
                 view.handle_key_event(key_event);
                 if view.is_complete() {
@@ -275,6 +278,7 @@ impl BottomPane {
     pub fn set_task_running(&mut self, running: bool) {
         self.is_task_running = running;
         self.composer.set_task_running(running);
+        self.frame_requester.set_task_running(running);
         self.request_redraw();
     }
 
@@ -6,7 +6,6 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 use std::env;
-use std::fs;
 
 #[cfg(test)]
 use lazy_static::lazy_static;
@@ -16,6 +15,7 @@ use codex_core::config_types::Notifications;
 use codex_core::git_info::collect_git_info;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
+use codex_core::issue_tracker::parse_issue_argument;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
@@ -25,6 +25,7 @@ use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
 use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -42,32 +43,52 @@ use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::SessionStatus;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnSummary;
 use codex_core::protocol::UserMessageEvent;
+use codex_core::protocol::UserQuestionKind;
+use codex_core::protocol::UserQuestionRequestEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::review_format::base_branch_review_request;
+use codex_core::review_format::commit_review_request;
+use codex_core::review_format::uncommitted_changes_review_request;
+use codex_core::todos::TodoComment;
+use codex_core::todos::TodoGrouping;
+use codex_core::todos::fix_todos_prompt;
+use codex_core::todos::group_todos;
+use codex_core::todos::harvest_todos;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use rand::Rng;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
+use ratatui::layout::Position;
 use ratatui::layout::Rect;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Widget;
 use ratatui::widgets::WidgetRef;
+use std::process::Stdio;
+use std::time::Duration;
+use std::time::Instant;
 use tokio::process::Command;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::AbortHandle;
 use tokio::task::spawn_blocking;
 use tracing::debug;
 
@@ -83,7 +104,9 @@ use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
+use crate::clipboard_copy::copy_to_clipboard;
 use crate::clipboard_paste::paste_image_to_temp_png;
+use crate::denied_actions::DeniedActions;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
@@ -94,12 +117,23 @@ use crate::history_cell::AgentMessageCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::McpToolCallCell;
 use crate::markdown::append_markdown;
+use crate::output_filter;
+use crate::replay::Replay;
+use crate::replay::spawn_replay;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::statusline::StatusLineDockerSnapshot;
 use crate::statusline::StatusLineGitSnapshot;
 use crate::statusline::StatusLineState;
+use crate::statusline::StatusLineTarget;
+use crate::statusline::probes::Probe;
+use crate::statusline::probes::ProbeKind;
+use crate::statusline::probes::ProbeReport;
+use crate::statusline::probes::ProbeUpdate;
+use crate::statusline::probes::StatusLineProbes;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
+mod activity;
 mod interrupts;
 use self::interrupts::InterruptManager;
 mod agent;
@@ -120,6 +154,10 @@ use codex_core::ConversationManager;
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::system_info::Battery;
+use codex_core::system_info::SystemResources;
+use codex_core::system_info::battery;
+use codex_core::system_info::collect_system_resources;
 use codex_file_search::FileMatch;
 use codex_git_tooling::CreateGhostCommitOptions;
 use codex_git_tooling::GhostCommit;
@@ -141,6 +179,12 @@ struct RunningCommand {
 
 const RATE_LIMIT_WARNING_THRESHOLDS: [f64; 3] = [75.0, 90.0, 95.0];
 
+/// Where `/status json` and `/statusline dump` put their JSON.
+enum StatusExport {
+    Clipboard,
+    File(PathBuf),
+}
+
 #[derive(Default)]
 struct RateLimitWarningState {
     secondary_index: usize,
@@ -224,6 +268,18 @@ pub(crate) fn get_limits_duration(windows_minutes: u64) -> String {
     }
 }
 
+/// Time until the last of the used-up rate limit windows resets, if any
+/// window is used up.
+fn exhausted_rate_limit_reset(snapshot: &RateLimitSnapshot) -> Option<Duration> {
+    [snapshot.primary.as_ref(), snapshot.secondary.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter(|window| window.used_percent >= 100.0)
+        .filter_map(|window| window.resets_in_seconds)
+        .max()
+        .map(Duration::from_secs)
+}
+
 /// Common initialization parameters shared by all `ChatWidget` constructors.
 pub(crate) struct ChatWidgetInit {
     pub(crate) config: Config,
@@ -254,6 +310,9 @@ pub(crate) struct ChatWidget {
     task_complete_pending: bool,
     // Queue of interruptive UI events deferred during an active write cycle
     interrupts: InterruptManager,
+    // An MCP result is being piped through the `json` output filter; later
+    // interruptive events wait in `interrupts` until it is shown.
+    json_filter_pending: bool,
     // Accumulates the current reasoning block text to extract a header
     reasoning_buffer: String,
     // Accumulates full reasoning content for transcript-only recording
@@ -267,6 +326,8 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
+    // Requests denied in the approval prompt, for `/retry-denied`
+    denied_actions: DeniedActions,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
     // Simple review mode flag; used to adjust layout and banners.
@@ -276,8 +337,24 @@ pub(crate) struct ChatWidget {
     ghost_snapshots_disabled: bool,
     // Whether to add a final message separator after the last message
     needs_final_message_separator: bool,
+    // While set and in the future, the status line shows what the agent is
+    // doing instead of generic headers (`/pair`).
+    pair_mode_until: Option<Instant>,
+    // Where `/status json` puts the status once core has sent it.
+    status_export: Option<StatusExport>,
+    // Git, Kubernetes, AWS and `[[statusline.segments]]` probes; stopped when
+    // the widget is dropped.
+    status_probes: StatusLineProbes,
+    // Poller sampling the battery; aborted when the widget is dropped.
+    status_segment_tasks: Vec<AbortHandle>,
+    // When the Docker context was last looked up; lookups within
+    // `DOCKER_REFRESH_INTERVAL` reuse the status line's current value.
+    docker_refreshed_at: std::cell::Cell<Option<Instant>>,
 
     last_rendered_width: std::cell::Cell<Option<usize>>,
+    /// Where the clickable status line segments were last drawn, for
+    /// `tui.mouse`.
+    status_line_hits: std::cell::RefCell<Vec<(Rect, StatusLineTarget)>>,
 }
 
 struct UserMessage {
@@ -302,6 +379,14 @@ fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Optio
     }
 }
 
+impl Drop for ChatWidget {
+    fn drop(&mut self) {
+        for task in &self.status_segment_tasks {
+            task.abort();
+        }
+    }
+}
+
 impl ChatWidget {
     fn model_description_for(slug: &str) -> Option<&'static str> {
         if slug.starts_with("gpt-5-codex") {
@@ -323,13 +408,25 @@ impl ChatWidget {
 
     fn bootstrap_status_line(&mut self) {
         self.sync_status_line_model();
+        self.sync_status_line_sandbox();
         let initial_tokens = self.token_info.clone();
         self.status_line.update_tokens(initial_tokens);
         self.status_line.set_devspace(detect_devspace());
         self.status_line.set_hostname(detect_hostname());
         self.status_line.set_aws_profile(detect_aws_profile());
+        self.status_line.set_gcp_project(detect_gcp_project());
+        self.status_line
+            .set_node_version(detect_node_version(&self.config.cwd));
         self.refresh_queued_user_messages();
         self.spawn_status_line_background_tasks();
+        self.spawn_status_line_probes();
+        self.spawn_status_segment_pollers();
+        for warning in self.config.statusline.warnings.clone() {
+            self.add_to_history(history_cell::new_warning_event(warning));
+        }
+        if let Some(notice) = self.config.state_home.notice() {
+            self.add_to_history(history_cell::new_warning_event(notice));
+        }
     }
 
     fn sync_status_line_model(&mut self) {
@@ -339,40 +436,185 @@ impl ChatWidget {
         );
     }
 
+    fn sync_status_line_sandbox(&mut self) {
+        self.status_line
+            .set_sandbox_policy(&self.config.sandbox_policy, self.config.approval_policy);
+    }
+
     fn spawn_status_line_background_tasks(&self) {
-        self.spawn_git_refresh();
-        self.spawn_kube_refresh();
+        self.spawn_azure_refresh();
+        self.spawn_docker_refresh();
+        self.spawn_resources_refresh();
     }
 
-    fn spawn_git_refresh(&self) {
+    /// Start the git, Kubernetes, AWS and `[[statusline.segments]]` probes;
+    /// see [`crate::statusline::probes`].
+    fn spawn_status_line_probes(&mut self) {
+        let cwd = self.config.cwd.clone();
+        let mut probes = vec![
+            Probe::new(ProbeKind::Git, GIT_PROBE_INTERVAL, move || {
+                let cwd = cwd.clone();
+                async move { ProbeUpdate::Git(collect_status_line_git_snapshot(cwd).await) }
+            }),
+            Probe::new(ProbeKind::KubeContext, KUBE_PROBE_INTERVAL, || async {
+                ProbeUpdate::KubeContext(detect_kube_context_async().await)
+            }),
+            Probe::new(ProbeKind::AwsProfile, AWS_PROBE_INTERVAL, || async {
+                ProbeUpdate::AwsProfile(detect_aws_profile())
+            }),
+        ];
+        for segment in &self.config.statusline.custom_segments {
+            let name = segment.name.clone();
+            let command = segment.command.clone();
+            let cwd = self.config.cwd.clone();
+            probes.push(Probe::new(
+                ProbeKind::Custom(name.clone()),
+                Duration::from_secs(segment.interval_secs.max(1)),
+                move || {
+                    let name = name.clone();
+                    let command = command.clone();
+                    let cwd = cwd.clone();
+                    async move {
+                        let text = run_status_segment_command(&command, &cwd).await;
+                        ProbeUpdate::Custom { name, text }
+                    }
+                },
+            ));
+        }
+        self.status_probes = StatusLineProbes::spawn(probes, self.app_event_tx.clone());
+    }
+
+    /// Re-read the git status now, e.g. after a command may have changed it.
+    fn refresh_git_status(&self) {
+        self.status_probes.refresh(&ProbeKind::Git);
+    }
+
+    /// Read the Azure CLI's subscription, when `statusline.show_azure` is on.
+    fn spawn_azure_refresh(&self) {
+        if !self.config.statusline.show_azure {
+            return;
+        }
         let Ok(handle) = Handle::try_current() else {
             return;
         };
-        let cwd = self.config.cwd.clone();
         let tx = self.app_event_tx.clone();
         handle.spawn(async move {
-            let snapshot = collect_status_line_git_snapshot(cwd).await;
-            tx.send(AppEvent::StatusLineGit(snapshot));
+            if let Ok(subscription) = spawn_blocking(detect_azure_subscription).await {
+                tx.send(AppEvent::StatusLineAzureSubscription(subscription));
+            }
         });
     }
 
-    fn spawn_kube_refresh(&self) {
+    /// Look up the Docker context, and with `statusline.docker_probe` whether
+    /// its daemon answers. Runs at session start and after each turn, at most
+    /// once per `DOCKER_REFRESH_INTERVAL`, since the probe can take seconds
+    /// against a remote or stopped daemon.
+    fn spawn_docker_refresh(&self) {
+        if self
+            .docker_refreshed_at
+            .get()
+            .is_some_and(|at| at.elapsed() < DOCKER_REFRESH_INTERVAL)
+        {
+            return;
+        }
         let Ok(handle) = Handle::try_current() else {
             return;
         };
+        self.docker_refreshed_at.set(Some(Instant::now()));
+        let probe = self.config.statusline.docker_probe;
         let tx = self.app_event_tx.clone();
         handle.spawn(async move {
-            let context = detect_kube_context_async().await;
-            tx.send(AppEvent::StatusLineKubeContext(context));
+            let Ok(context) = spawn_blocking(detect_docker_context).await else {
+                return;
+            };
+            let docker = match context {
+                Some(context) => Some(StatusLineDockerSnapshot {
+                    context,
+                    reachable: if probe {
+                        Some(docker_daemon_reachable().await)
+                    } else {
+                        None
+                    },
+                }),
+                None => None,
+            };
+            tx.send(AppEvent::StatusLineDocker(docker));
+        });
+    }
+
+    /// Sample free disk, memory and load for the status line, when enabled.
+    /// Runs at session start and after each command, so a build filling the
+    /// disk shows up while the turn is still running.
+    fn spawn_resources_refresh(&self) {
+        if !self.config.tui_system_resources {
+            return;
+        }
+        let Ok(handle) = Handle::try_current() else {
+            return;
+        };
+        let cwd = self.config.cwd.clone();
+        let tx = self.app_event_tx.clone();
+        handle.spawn(async move {
+            if let Ok(resources) = spawn_blocking(move || collect_system_resources(&cwd)).await {
+                tx.send(AppEvent::StatusLineResources(resources));
+            }
         });
     }
 
-    pub(crate) fn update_statusline_git(&mut self, git: Option<StatusLineGitSnapshot>) {
-        self.status_line.set_git_info(git);
+    /// Sample the battery on an interval when it is shown.
+    fn spawn_status_segment_pollers(&mut self) {
+        let Ok(handle) = Handle::try_current() else {
+            return;
+        };
+        if self.config.statusline.shows_battery() {
+            let tx = self.app_event_tx.clone();
+            let task = handle.spawn(async move {
+                loop {
+                    if let Ok(charge) = spawn_blocking(battery).await {
+                        tx.send(AppEvent::StatusLineBattery(charge));
+                    }
+                    tokio::time::sleep(BATTERY_REFRESH_INTERVAL).await;
+                }
+            });
+            self.status_segment_tasks.push(task.abort_handle());
+        }
+    }
+
+    /// See [`StatusLineState::terminal_title`].
+    pub(crate) fn terminal_title(&self) -> Option<String> {
+        self.status_line.terminal_title()
+    }
+
+    pub(crate) fn update_statusline_probe(&mut self, report: ProbeReport) {
+        match report {
+            ProbeReport::Changed(ProbeUpdate::Git(git)) => self.status_line.set_git_info(git),
+            ProbeReport::Changed(ProbeUpdate::KubeContext(context)) => {
+                self.status_line.set_kubernetes_context(context);
+            }
+            ProbeReport::Changed(ProbeUpdate::AwsProfile(profile)) => {
+                self.status_line.set_aws_profile(profile);
+            }
+            ProbeReport::Changed(ProbeUpdate::Custom { name, text }) => {
+                self.status_line.set_custom_segment(&name, text);
+            }
+            ProbeReport::Unchanged(kind) => self.status_line.mark_probe_fresh(&kind),
+        }
+    }
+
+    pub(crate) fn update_statusline_azure_subscription(&mut self, subscription: Option<String>) {
+        self.status_line.set_azure_subscription(subscription);
     }
 
-    pub(crate) fn update_statusline_kube_context(&mut self, context: Option<String>) {
-        self.status_line.set_kubernetes_context(context);
+    pub(crate) fn update_statusline_docker(&mut self, docker: Option<StatusLineDockerSnapshot>) {
+        self.status_line.set_docker(docker);
+    }
+
+    pub(crate) fn update_statusline_resources(&mut self, resources: SystemResources) {
+        self.status_line.set_system_resources(Some(resources));
+    }
+
+    pub(crate) fn update_statusline_battery(&mut self, battery: Option<Battery>) {
+        self.status_line.set_battery(battery);
     }
 
     // --- Small event handlers ---
@@ -383,10 +625,13 @@ impl ChatWidget {
         self.status_line
             .set_session_id(Some(event.session_id.to_string()));
         let initial_messages = event.initial_messages.clone();
+        let pinned_context = event.pinned_context.clone();
         let model_for_header = event.model.clone();
         self.session_header.set_model(&model_for_header);
         self.sync_status_line_model();
         self.spawn_status_line_background_tasks();
+        self.refresh_git_status();
+        self.status_probes.refresh(&ProbeKind::KubeContext);
         self.add_to_history(history_cell::new_session_info(
             &self.config,
             event,
@@ -395,6 +640,10 @@ impl ChatWidget {
         if let Some(messages) = initial_messages {
             self.replay_initial_messages(messages);
         }
+        if !pinned_context.is_empty() {
+            self.app_event_tx
+                .send(AppEvent::RestorePins(pinned_context));
+        }
         // Ask codex-core to enumerate custom prompts for this session.
         self.submit_op(Op::ListCustomPrompts);
         if let Some(user_message) = self.initial_user_message.take() {
@@ -417,6 +666,9 @@ impl ChatWidget {
     }
 
     fn on_agent_message_delta(&mut self, delta: String) {
+        if self.pair_mode_active() {
+            self.status_line.update_run_header(activity::REPLYING);
+        }
         self.handle_streaming_delta(delta);
     }
 
@@ -426,7 +678,9 @@ impl ChatWidget {
         // (between **/**) as the chunk header. Show this header as status.
         self.reasoning_buffer.push_str(&delta);
 
-        if let Some(header) = extract_first_bold(&self.reasoning_buffer) {
+        if self.pair_mode_active() {
+            self.status_line.update_run_header(activity::THINKING);
+        } else if let Some(header) = extract_first_bold(&self.reasoning_buffer) {
             self.status_line.update_run_header(&header);
         }
         self.request_redraw();
@@ -465,27 +719,46 @@ impl ChatWidget {
         self.request_redraw();
     }
 
-    fn on_task_complete(&mut self, last_agent_message: Option<String>) {
+    fn on_task_complete(
+        &mut self,
+        last_agent_message: Option<String>,
+        summary: Option<TurnSummary>,
+    ) {
         // If a stream is currently active, finalize it.
         self.flush_answer_stream_with_separator();
         // Mark task stopped and request redraw now that all content is in history.
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
         self.status_line.complete_task();
-        self.spawn_git_refresh();
+        self.status_line.set_devspace(detect_devspace());
+        self.refresh_git_status();
+        self.status_probes.refresh(&ProbeKind::KubeContext);
+        self.status_probes.refresh(&ProbeKind::AwsProfile);
+        self.spawn_docker_refresh();
+        self.spawn_resources_refresh();
         self.request_redraw();
 
         // If there is a queued user message, send exactly one now to begin the next turn.
+        let has_queued_input = !self.queued_user_messages.is_empty();
         self.maybe_send_next_queued_input();
         // Emit a notification when the turn completes (suppressed if focused).
         self.notify(Notification::AgentTurnComplete {
             response: last_agent_message.unwrap_or_default(),
+            summary: summary.as_ref().map(ToString::to_string),
         });
+        if !has_queued_input {
+            self.app_event_tx.send(AppEvent::TurnFinished);
+        }
     }
 
     pub(crate) fn set_token_info(&mut self, info: Option<TokenUsageInfo>) {
         self.token_info = info.clone();
         self.status_line.update_tokens(info);
+        if let Some(percent_left) = self.status_line.take_context_critical_notice() {
+            self.add_to_history(history_cell::new_warning_event(format!(
+                "Only {percent_left}% of the context window is left. Use /compact to summarize the conversation, or /new to start over."
+            )));
+        }
     }
 
     fn on_rate_limit_snapshot(&mut self, snapshot: Option<RateLimitSnapshot>) {
@@ -506,6 +779,10 @@ impl ChatWidget {
                     .and_then(|window| window.window_minutes),
             );
 
+            if let Some(cooldown) = exhausted_rate_limit_reset(&snapshot) {
+                self.status_line.set_rate_limit_cooldown(cooldown);
+            }
+
             let display = crate::status::rate_limit_snapshot_display(&snapshot, Local::now());
             self.rate_limit_snapshot = Some(display);
 
@@ -528,12 +805,12 @@ impl ChatWidget {
         self.running_commands.clear();
         self.stream_controller = None;
         self.status_line.complete_task();
-        self.spawn_git_refresh();
+        self.refresh_git_status();
     }
 
-    fn on_error(&mut self, message: String) {
+    fn on_error(&mut self, message: String, kind: ErrorKind) {
         self.finalize_turn();
-        self.add_to_history(history_cell::new_error_event(message));
+        self.add_to_history(history_cell::new_error_event_with_hint(message, kind));
         self.request_redraw();
 
         // After an error ends the turn, try sending the next queued input.
@@ -600,6 +877,14 @@ impl ChatWidget {
         );
     }
 
+    fn on_user_question_request(&mut self, ev: UserQuestionRequestEvent) {
+        let ev2 = ev.clone();
+        self.defer_or_handle(
+            |q| q.push_user_question(ev),
+            |s| s.handle_user_question_now(ev2),
+        );
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -615,7 +900,12 @@ impl ChatWidget {
 
     fn on_patch_apply_begin(&mut self, event: PatchApplyBeginEvent) {
         self.status_line.resume_timer();
-        self.status_line.update_run_header("Applying patch");
+        let header = if self.pair_mode_active() {
+            activity::patch_activity(&event.changes, &self.config.cwd)
+        } else {
+            "Applying patch".to_string()
+        };
+        self.status_line.update_run_header(&header);
         self.add_to_history(history_cell::new_patch_event(
             event.changes,
             &self.config.cwd,
@@ -640,6 +930,7 @@ impl ChatWidget {
     }
 
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
+        self.spawn_resources_refresh();
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_exec_end(ev), |s| s.handle_exec_end_now(ev2));
     }
@@ -656,6 +947,9 @@ impl ChatWidget {
 
     fn on_web_search_begin(&mut self, _ev: WebSearchBeginEvent) {
         self.flush_answer_stream_with_separator();
+        if self.pair_mode_active() {
+            self.status_line.update_run_header(activity::SEARCHING_WEB);
+        }
     }
 
     fn on_web_search_end(&mut self, ev: WebSearchEndEvent) {
@@ -691,9 +985,13 @@ impl ChatWidget {
         debug!("BackgroundEvent: {message}");
     }
 
-    fn on_stream_error(&mut self, message: String) {
+    fn on_stream_error(&mut self, message: String, rate_limit_retry_after_ms: Option<u64>) {
         // Show stream errors in the transcript so users see retry/backoff info.
         self.add_to_history(history_cell::new_stream_error_event(message));
+        if let Some(ms) = rate_limit_retry_after_ms {
+            self.status_line
+                .set_rate_limit_cooldown(Duration::from_millis(ms));
+        }
         self.request_redraw();
     }
 
@@ -726,7 +1024,10 @@ impl ChatWidget {
         // Preserve deterministic FIFO across queued interrupts: once anything
         // is queued due to an active write cycle, continue queueing until the
         // queue is flushed to avoid reordering (e.g., ExecEnd before ExecBegin).
-        if self.stream_controller.is_some() || !self.interrupts.is_empty() {
+        if self.stream_controller.is_some()
+            || !self.interrupts.is_empty()
+            || self.json_filter_pending
+        {
             push(&mut self.interrupts);
         } else {
             handle(self);
@@ -795,9 +1096,9 @@ impl ChatWidget {
                 &ev.call_id,
                 CommandOutput {
                     exit_code: ev.exit_code,
-                    stdout: ev.stdout.clone(),
-                    stderr: ev.stderr.clone(),
-                    formatted_output: ev.formatted_output.clone(),
+                    stdout: ev.stdout.as_str().into(),
+                    stderr: ev.stderr.as_str().into(),
+                    formatted_output: ev.formatted_output.as_str().into(),
                 },
                 ev.duration,
             );
@@ -806,8 +1107,8 @@ impl ChatWidget {
             }
         }
         if self.running_commands.is_empty() {
-            self.status_line.update_run_header("Working");
-            self.spawn_git_refresh();
+            self.show_idle_header();
+            self.refresh_git_status();
         }
     }
 
@@ -818,8 +1119,8 @@ impl ChatWidget {
         if !event.success {
             self.add_to_history(history_cell::new_patch_apply_failure(event.stderr));
         }
-        self.status_line.update_run_header("Working");
-        self.spawn_git_refresh();
+        self.show_idle_header();
+        self.refresh_git_status();
     }
 
     pub(crate) fn handle_exec_approval_now(&mut self, id: String, ev: ExecApprovalRequestEvent) {
@@ -839,6 +1140,100 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// Shows the agent's question as a form: a list for choices, a text
+    /// input otherwise. Dismissing it answers with nothing.
+    pub(crate) fn handle_user_question_now(&mut self, ev: UserQuestionRequestEvent) {
+        self.flush_answer_stream_with_separator();
+        self.notify(Notification::QuestionAsked {
+            question: ev.question.clone(),
+        });
+        self.status_line.update_run_header("Awaiting your answer");
+
+        let UserQuestionRequestEvent {
+            call_id,
+            question,
+            kind,
+        } = ev;
+        let cancel_call_id = call_id.clone();
+        match kind {
+            UserQuestionKind::Choice { options } => {
+                let items = options
+                    .into_iter()
+                    .map(|option| {
+                        let call_id = call_id.clone();
+                        let answer = option.clone();
+                        SelectionItem {
+                            name: option,
+                            actions: vec![Box::new(move |tx: &AppEventSender| {
+                                tx.send(AppEvent::CodexOp(Op::UserQuestionAnswer {
+                                    call_id: call_id.clone(),
+                                    answer: Some(answer.clone()),
+                                }));
+                            })],
+                            dismiss_on_select: true,
+                            ..Default::default()
+                        }
+                    })
+                    .collect();
+                self.bottom_pane.show_selection_view(SelectionViewParams {
+                    title: Some(question),
+                    subtitle: Some("Codex is asking".to_string()),
+                    footer_hint: Some(standard_popup_hint_line()),
+                    items,
+                    on_cancel: Some(Box::new(move |tx: &AppEventSender| {
+                        tx.send(AppEvent::CodexOp(Op::UserQuestionAnswer {
+                            call_id: cancel_call_id.clone(),
+                            answer: None,
+                        }));
+                    })),
+                    ..Default::default()
+                });
+            }
+            UserQuestionKind::Text { placeholder } => {
+                let placeholder =
+                    placeholder.unwrap_or_else(|| "Type your answer and press Enter".to_string());
+                self.show_user_question_prompt(call_id, question, placeholder);
+            }
+            UserQuestionKind::File => {
+                self.show_user_question_prompt(
+                    call_id,
+                    question,
+                    "Type a file path and press Enter".to_string(),
+                );
+            }
+        }
+        self.request_redraw();
+    }
+
+    fn show_user_question_prompt(
+        &mut self,
+        call_id: String,
+        question: String,
+        placeholder: String,
+    ) {
+        let tx = self.app_event_tx.clone();
+        let cancel_tx = self.app_event_tx.clone();
+        let cancel_call_id = call_id.clone();
+        let view = CustomPromptView::new(
+            question,
+            placeholder,
+            Some("Codex is asking".to_string()),
+            Box::new(move |answer: String| {
+                tx.send(AppEvent::CodexOp(Op::UserQuestionAnswer {
+                    call_id: call_id.clone(),
+                    answer: Some(answer),
+                }));
+            }),
+        )
+        .with_on_cancel(Box::new(move || {
+            cancel_tx.send(AppEvent::CodexOp(Op::UserQuestionAnswer {
+                call_id: cancel_call_id,
+                answer: None,
+            }));
+        }));
+        self.bottom_pane.show_view(Box::new(view));
+    }
+
     pub(crate) fn handle_apply_patch_approval_now(
         &mut self,
         id: String,
@@ -864,8 +1259,12 @@ impl ChatWidget {
 
     pub(crate) fn handle_exec_begin_now(&mut self, ev: ExecCommandBeginEvent) {
         self.status_line.resume_timer();
-        self.status_line
-            .update_run_header(&Self::exec_status_label(&ev.command));
+        let header = if self.pair_mode_active() {
+            activity::exec_activity(&ev.command, &ev.parsed_cmd)
+        } else {
+            Self::exec_status_label(&ev.command)
+        };
+        self.status_line.update_run_header(&header);
         self.running_commands.insert(
             ev.call_id.clone(),
             RunningCommand {
@@ -910,6 +1309,42 @@ impl ChatWidget {
         self.request_redraw();
     }
     pub(crate) fn handle_mcp_end_now(&mut self, ev: McpToolCallEndEvent) {
+        // The filter runs off the UI thread, like the `/diff` one; the call
+        // is shown once it is done.
+        if let Some((command, json)) = self.json_filter_input(&ev.result) {
+            self.json_filter_pending = true;
+            let tx = self.app_event_tx.clone();
+            tokio::spawn(async move {
+                let filtered = spawn_blocking(move || output_filter::apply(&command, &json))
+                    .await
+                    .ok()
+                    .flatten();
+                tx.send(AppEvent::McpResultFiltered(ev, filtered));
+            });
+            return;
+        }
+        self.complete_mcp_call(ev, None);
+    }
+
+    /// The `json` output filter finished with the result of `ev`.
+    pub(crate) fn on_mcp_result_filtered(
+        &mut self,
+        ev: McpToolCallEndEvent,
+        filtered: Option<String>,
+    ) {
+        self.json_filter_pending = false;
+        self.complete_mcp_call(ev, filtered);
+        if self.stream_controller.is_none() {
+            self.flush_interrupt_queue();
+        }
+    }
+
+    /// Whether an MCP result is waiting on the `json` output filter.
+    pub(crate) fn json_filter_pending(&self) -> bool {
+        self.json_filter_pending
+    }
+
+    fn complete_mcp_call(&mut self, ev: McpToolCallEndEvent, filtered: Option<String>) {
         self.flush_answer_stream_with_separator();
 
         let McpToolCallEndEvent {
@@ -924,10 +1359,14 @@ impl ChatWidget {
             .as_mut()
             .and_then(|cell| cell.as_any_mut().downcast_mut::<McpToolCallCell>())
         {
-            Some(cell) if cell.call_id() == call_id => cell.complete(duration, result),
+            Some(cell) if cell.call_id() == call_id => {
+                cell.set_filtered_output(filtered);
+                cell.complete(duration, result)
+            }
             _ => {
                 self.flush_active_cell();
                 let mut cell = history_cell::new_active_mcp_tool_call(call_id, invocation);
+                cell.set_filtered_output(filtered);
                 let extra_cell = cell.complete(duration, result);
                 self.active_cell = Some(Box::new(cell));
                 extra_cell
@@ -938,11 +1377,30 @@ impl ChatWidget {
         if let Some(extra) = extra_cell {
             self.add_boxed_history(extra);
         }
-        self.status_line.update_run_header("Working");
+        self.show_idle_header();
+    }
+
+    /// The `json` output filter and the JSON tool result to pipe through it,
+    /// when one is configured and the result is JSON.
+    fn json_filter_input(
+        &self,
+        result: &Result<mcp_types::CallToolResult, String>,
+    ) -> Option<(Vec<String>, String)> {
+        let command = self.config.tui_output_filters.json.as_ref()?;
+        let Ok(mcp_types::CallToolResult { content, .. }) = result else {
+            return None;
+        };
+        let [mcp_types::ContentBlock::TextContent(text)] = content.as_slice() else {
+            return None;
+        };
+        if !output_filter::is_json(&text.text) {
+            return None;
+        }
+        Some((command.clone(), text.text.clone()))
     }
 
     fn layout_areas(&self, area: Rect) -> [Rect; 4] {
-        let status_height = if area.height > 0 { 1 } else { 0 };
+        let status_height = self.status_line.height().min(area.height);
         let available = area.height.saturating_sub(status_height);
         let bottom_min = self.bottom_pane.desired_height(area.width).min(available);
         let remaining = available.saturating_sub(bottom_min);
@@ -1014,10 +1472,12 @@ impl ChatWidget {
             running_commands: HashMap::new(),
             task_complete_pending: false,
             interrupts: InterruptManager::new(),
+            json_filter_pending: false,
             reasoning_buffer: String::new(),
             full_reasoning_buffer: String::new(),
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            denied_actions: DeniedActions::default(),
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
             pending_notification: None,
@@ -1025,7 +1485,13 @@ impl ChatWidget {
             ghost_snapshots: Vec::new(),
             ghost_snapshots_disabled: true,
             needs_final_message_separator: false,
+            pair_mode_until: None,
+            status_export: None,
+            status_probes: StatusLineProbes::default(),
+            status_segment_tasks: Vec::new(),
+            docker_refreshed_at: std::cell::Cell::new(None),
             last_rendered_width: std::cell::Cell::new(None),
+            status_line_hits: std::cell::RefCell::default(),
         };
 
         widget.bootstrap_status_line();
@@ -1038,6 +1504,22 @@ impl ChatWidget {
         conversation: std::sync::Arc<codex_core::CodexConversation>,
         session_configured: codex_core::protocol::SessionConfiguredEvent,
     ) -> Self {
+        let codex_op_tx = spawn_agent_from_existing(
+            conversation,
+            session_configured,
+            common.app_event_tx.clone(),
+        );
+        Self::new_with_op_sender(common, codex_op_tx)
+    }
+
+    /// Create a ChatWidget that plays back a recorded session instead of
+    /// talking to an agent.
+    pub(crate) fn new_replay(common: ChatWidgetInit, replay: Replay, speed: f64) -> Self {
+        let codex_op_tx = spawn_replay(replay, speed, common.app_event_tx.clone());
+        Self::new_with_op_sender(common, codex_op_tx)
+    }
+
+    fn new_with_op_sender(common: ChatWidgetInit, codex_op_tx: UnboundedSender<Op>) -> Self {
         let ChatWidgetInit {
             config,
             frame_requester,
@@ -1050,9 +1532,6 @@ impl ChatWidget {
         let mut rng = rand::rng();
         let placeholder = EXAMPLE_PROMPTS[rng.random_range(0..EXAMPLE_PROMPTS.len())].to_string();
 
-        let codex_op_tx =
-            spawn_agent_from_existing(conversation, session_configured, app_event_tx.clone());
-
         let frame_requester_clone = frame_requester.clone();
         let app_event_tx_clone = app_event_tx.clone();
         let status_line = StatusLineState::new(&config, frame_requester_clone.clone());
@@ -1086,10 +1565,12 @@ impl ChatWidget {
             running_commands: HashMap::new(),
             task_complete_pending: false,
             interrupts: InterruptManager::new(),
+            json_filter_pending: false,
             reasoning_buffer: String::new(),
             full_reasoning_buffer: String::new(),
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            denied_actions: DeniedActions::default(),
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
             pending_notification: None,
@@ -1097,7 +1578,13 @@ impl ChatWidget {
             ghost_snapshots: Vec::new(),
             ghost_snapshots_disabled: true,
             needs_final_message_separator: false,
+            pair_mode_until: None,
+            status_export: None,
+            status_probes: StatusLineProbes::default(),
+            status_segment_tasks: Vec::new(),
+            docker_refreshed_at: std::cell::Cell::new(None),
             last_rendered_width: std::cell::Cell::new(None),
+            status_line_hits: std::cell::RefCell::default(),
         };
 
         widget.bootstrap_status_line();
@@ -1110,7 +1597,7 @@ impl ChatWidget {
                 .active_cell
                 .as_ref()
                 .map_or(0, |c| c.desired_height(width) + 1)
-            + 1
+            + self.status_line.height()
     }
 
     pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) {
@@ -1135,6 +1622,15 @@ impl ChatWidget {
                 }
                 return;
             }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            } if modifiers.contains(KeyModifiers::CONTROL) && c.eq_ignore_ascii_case(&'y') => {
+                self.copy_resume_command();
+                return;
+            }
             other if other.kind == KeyEventKind::Press => {
                 self.bottom_pane.clear_ctrl_c_quit_hint();
             }
@@ -1158,6 +1654,21 @@ impl ChatWidget {
             _ => {
                 match self.bottom_pane.handle_key_event(key_event) {
                     InputResult::Submitted(text) => {
+                        if self.handle_note_command(&text) {
+                            return;
+                        }
+                        if self.handle_debug_command(&text) {
+                            return;
+                        }
+                        if self.handle_status_command(&text) {
+                            return;
+                        }
+                        if self.handle_statusline_command(&text) {
+                            return;
+                        }
+                        let Some(text) = self.expand_issue_command(text) else {
+                            return;
+                        };
                         // If a task is running, queue the user input to be sent after the turn completes.
                         let user_message = UserMessage {
                             text,
@@ -1179,6 +1690,152 @@ impl ChatWidget {
         }
     }
 
+    /// Rewrite `/issue <ref> [request]` into `@<ref> [request]` so core pulls
+    /// the issue into context. Returns `None` (keeping the text in the
+    /// composer) when the reference cannot be parsed.
+    fn expand_issue_command(&mut self, text: String) -> Option<String> {
+        let Some(rest) = text.strip_prefix("/issue ") else {
+            return Some(text);
+        };
+        let rest = rest.trim_start();
+        let (argument, request) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let Some(reference) = parse_issue_argument(argument) else {
+            self.add_error_message(format!(
+                "Not an issue reference: `{argument}`. Use e.g. /issue 1234, /issue owner/repo#1234, /issue gl#56 or /issue ABC-42."
+            ));
+            self.bottom_pane.set_composer_text(text);
+            return None;
+        };
+        let request = request.trim();
+        Some(if request.is_empty() {
+            format!("Look at {reference}.")
+        } else {
+            format!("{reference} {request}")
+        })
+    }
+
+    /// Add `/note <text>` to the transcript and the session. Returns whether
+    /// `text` was a `/note` command.
+    fn handle_note_command(&mut self, text: &str) -> bool {
+        let Some(note) = text.strip_prefix("/note ") else {
+            return false;
+        };
+        let note = note.trim();
+        if note.is_empty() {
+            self.add_error_message("Nothing to note. Use e.g. /note chose plan B.".to_string());
+            return true;
+        }
+        self.add_to_history(history_cell::new_user_note(note));
+        self.submit_op(Op::AddNote {
+            text: note.to_string(),
+            include_in_context: !self.config.tui_private_notes,
+        });
+        true
+    }
+
+    /// Run `/debug <what>`. Returns whether `text` was a `/debug` command.
+    fn handle_debug_command(&mut self, text: &str) -> bool {
+        let Some(what) = text.strip_prefix("/debug ") else {
+            return false;
+        };
+        match what.trim() {
+            "perf" => self.app_event_tx.send(AppEvent::TogglePerfHud),
+            other => {
+                self.add_error_message(format!(
+                    "Unknown debug view: `{other}`. Use /debug perf to toggle frame timings."
+                ));
+                self.bottom_pane.set_composer_text(text.to_string());
+            }
+        }
+        true
+    }
+
+    /// Run `/status json [path]`. Returns whether `text` was a `/status`
+    /// command.
+    fn handle_status_command(&mut self, text: &str) -> bool {
+        let Some(args) = text.strip_prefix("/status ") else {
+            return false;
+        };
+        let args = args.trim();
+        let Some(export) = self.parse_export(args, "json") else {
+            self.add_error_message(format!(
+                "Unknown /status option: `{args}`. Use /status json, or /status json <file>."
+            ));
+            self.bottom_pane.set_composer_text(text.to_string());
+            return true;
+        };
+        self.status_export = Some(export);
+        self.submit_op(Op::GetSessionStatus);
+        true
+    }
+
+    /// Run `/statusline dump [path]`, for bug reports about how the status
+    /// line renders. Returns whether `text` was a `/statusline` command.
+    fn handle_statusline_command(&mut self, text: &str) -> bool {
+        let Some(args) = text.strip_prefix("/statusline ") else {
+            return false;
+        };
+        let args = args.trim();
+        let Some(export) = self.parse_export(args, "dump") else {
+            self.add_error_message(format!(
+                "Unknown /statusline option: `{args}`. Use /statusline dump, or /statusline dump <file>."
+            ));
+            self.bottom_pane.set_composer_text(text.to_string());
+            return true;
+        };
+        match self.status_line.dump_json() {
+            Ok(json) => self.export_json("the status line", export, &json),
+            Err(err) => {
+                self.add_error_message(format!("Failed to serialize the status line: {err}"))
+            }
+        }
+        true
+    }
+
+    /// `<keyword>` copies to the clipboard, `<keyword> <path>` writes to a
+    /// file relative to the working directory.
+    fn parse_export(&self, args: &str, keyword: &str) -> Option<StatusExport> {
+        match args.strip_prefix(keyword)? {
+            "" => Some(StatusExport::Clipboard),
+            path if path.starts_with(char::is_whitespace) => {
+                Some(StatusExport::File(self.config.cwd.join(path.trim())))
+            }
+            _ => None,
+        }
+    }
+
+    fn on_session_status(&mut self, status: SessionStatus) {
+        let Some(export) = self.status_export.take() else {
+            return;
+        };
+        let json = match serde_json::to_string_pretty(&status) {
+            Ok(json) => json,
+            Err(err) => {
+                self.add_error_message(format!("Failed to serialize the status: {err}"));
+                return;
+            }
+        };
+        self.export_json("the status", export, &json);
+    }
+
+    fn export_json(&mut self, what: &str, export: StatusExport, json: &str) {
+        match export {
+            StatusExport::Clipboard => match copy_to_clipboard(json) {
+                Ok(()) => self.add_info_message(format!("Copied {what} as JSON"), None),
+                Err(err) => self.add_error_message(format!("Failed to copy {what}: {err}")),
+            },
+            StatusExport::File(path) => match std::fs::write(&path, json) {
+                Ok(()) => {
+                    self.add_info_message(format!("Wrote {what} to {}", path.display()), None)
+                }
+                Err(err) => self.add_error_message(format!(
+                    "Failed to write {what} to {}: {err}",
+                    path.display()
+                )),
+            },
+        }
+    }
+
     pub(crate) fn attach_image(
         &mut self,
         path: PathBuf,
@@ -1240,11 +1897,12 @@ impl ChatWidget {
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
+                let diff_filter = self.config.tui_output_filters.diff.clone();
                 tokio::spawn(async move {
                     let text = match get_git_diff().await {
                         Ok((is_git_repo, diff_text)) => {
                             if is_git_repo {
-                                diff_text
+                                filter_diff(diff_filter, diff_text).await
                             } else {
                                 "`/diff` — _not inside a git repository_".to_string()
                             }
@@ -1254,15 +1912,49 @@ impl ChatWidget {
                     tx.send(AppEvent::DiffResult(text));
                 });
             }
+            SlashCommand::Pin => {
+                self.app_event_tx.send(AppEvent::PinLatestAgentMessage);
+            }
+            SlashCommand::Pins => {
+                self.app_event_tx.send(AppEvent::OpenPins);
+            }
             SlashCommand::Mention => {
                 self.insert_str("@");
             }
+            SlashCommand::Issue => {
+                self.insert_str("/issue ");
+            }
+            SlashCommand::Todos => {
+                let cwd = self.config.cwd.clone();
+                let tx = self.app_event_tx.clone();
+                tokio::spawn(async move {
+                    match spawn_blocking(move || harvest_todos(&cwd)).await {
+                        Ok(todos) => tx.send(AppEvent::TodosResult(todos)),
+                        Err(err) => tracing::error!("failed to scan for TODOs: {err}"),
+                    }
+                });
+            }
+            SlashCommand::Note => {
+                self.insert_str("/note ");
+            }
+            SlashCommand::RetryDenied => {
+                self.open_retry_denied_popup();
+            }
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Pair => {
+                self.toggle_pair_mode();
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Statusline => {
+                self.insert_str("/statusline dump");
+            }
+            SlashCommand::Debug => {
+                self.insert_str("/debug ");
+            }
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => {
                 use codex_core::protocol::EventMsg;
@@ -1304,6 +1996,27 @@ impl ChatWidget {
         }
     }
 
+    /// With `tui.mouse` on, clicking the model, git or token usage segment
+    /// of the status line opens the model picker, `/diff` or `/status`.
+    pub(crate) fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        if mouse_event.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+        let position = Position::new(mouse_event.column, mouse_event.row);
+        let target = self
+            .status_line_hits
+            .borrow()
+            .iter()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, target)| *target);
+        match target {
+            Some(StatusLineTarget::Model) => self.dispatch_command(SlashCommand::Model),
+            Some(StatusLineTarget::Git) => self.dispatch_command(SlashCommand::Diff),
+            Some(StatusLineTarget::Usage) => self.dispatch_command(SlashCommand::Status),
+            None => {}
+        }
+    }
+
     pub(crate) fn handle_paste(&mut self, text: String) {
         self.bottom_pane.handle_paste(text);
     }
@@ -1457,6 +2170,11 @@ impl ChatWidget {
         self.dispatch_event_msg(Some(id), msg, false);
     }
 
+    /// Render an event from a session being played back by `codex replay`.
+    pub(crate) fn handle_replayed_event(&mut self, msg: EventMsg) {
+        self.dispatch_event_msg(None, msg, true);
+    }
+
     /// Dispatch a protocol `EventMsg` to the appropriate handler.
     ///
     /// `id` is `Some` for live events and `None` for replayed events from
@@ -1489,21 +2207,23 @@ impl ChatWidget {
             }
             EventMsg::AgentReasoningSectionBreak(_) => self.on_reasoning_section_break(),
             EventMsg::TaskStarted(_) => self.on_task_started(),
-            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
-                self.on_task_complete(last_agent_message)
-            }
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message,
+                summary,
+            }) => self.on_task_complete(last_agent_message, summary),
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
                 self.on_rate_limit_snapshot(ev.rate_limits);
             }
-            EventMsg::Error(ErrorEvent { message }) => self.on_error(message),
+            EventMsg::Error(ErrorEvent { message, kind }) => self.on_error(message, kind),
             EventMsg::TurnAborted(ev) => match ev.reason {
                 TurnAbortReason::Interrupted => {
                     self.on_interrupted_turn(ev.reason);
                 }
-                TurnAbortReason::Replaced => {
-                    self.on_error("Turn aborted: replaced by a new task".to_owned())
-                }
+                TurnAbortReason::Replaced => self.on_error(
+                    "Turn aborted: replaced by a new task".to_owned(),
+                    ErrorKind::Internal,
+                ),
                 TurnAbortReason::ReviewEnded => {
                     self.on_interrupted_turn(ev.reason);
                 }
@@ -1516,6 +2236,7 @@ impl ChatWidget {
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::UserQuestionRequest(ev) => self.on_user_question_request(ev),
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
@@ -1528,13 +2249,18 @@ impl ChatWidget {
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
+            EventMsg::McpServerStatus(ev) => self.status_line.set_mcp_status(&ev.servers),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
+            EventMsg::SessionStatusResponse(status) => self.on_session_status(status),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
-            EventMsg::StreamError(StreamErrorEvent { message }) => self.on_stream_error(message),
+            EventMsg::StreamError(StreamErrorEvent {
+                message,
+                rate_limit_retry_after_ms,
+            }) => self.on_stream_error(message, rate_limit_retry_after_ms),
             EventMsg::UserMessage(ev) => {
                 if from_replay {
                     self.on_user_message_event(ev);
@@ -1606,6 +2332,12 @@ impl ChatWidget {
             | Some(InputMessageKind::UserInstructions) => {
                 // Skip XML‑wrapped context blocks in the transcript.
             }
+            Some(InputMessageKind::Note) => {
+                let note = event.message.trim();
+                if !note.is_empty() {
+                    self.add_to_history(history_cell::new_user_note(note));
+                }
+            }
             Some(InputMessageKind::Plain) | None => {
                 let message = event.message.trim();
                 if !message.is_empty() {
@@ -1682,6 +2414,47 @@ impl ChatWidget {
         format!("Awaiting {subject} approval")
     }
 
+    /// Header between tool calls, while the model decides what to do next.
+    fn show_idle_header(&mut self) {
+        let header = if self.pair_mode_active() {
+            activity::THINKING
+        } else {
+            "Working"
+        };
+        self.status_line.update_run_header(header);
+    }
+
+    /// Whether pair mode is on, turning it off once its time is up.
+    fn pair_mode_active(&mut self) -> bool {
+        match self.pair_mode_until {
+            Some(until) if Instant::now() < until => true,
+            Some(_) => {
+                self.pair_mode_until = None;
+                self.add_info_message(
+                    "Pair mode ended; the status line is back to its usual headers.".to_string(),
+                    Some("Run /pair to turn it on again.".to_string()),
+                );
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn toggle_pair_mode(&mut self) {
+        if self.pair_mode_until.take().is_some() {
+            self.add_info_message("Pair mode off.".to_string(), None);
+            return;
+        }
+        self.pair_mode_until = Some(Instant::now() + activity::PAIR_MODE_DURATION);
+        self.add_info_message(
+            format!(
+                "Pair mode on for {} minutes: the status line shows what Codex is doing right now.",
+                activity::PAIR_MODE_DURATION.as_secs() / 60
+            ),
+            Some("Run /pair again to turn it off.".to_string()),
+        );
+    }
+
     fn refresh_queued_user_messages(&mut self) {
         let messages: Vec<String> = self
             .queued_user_messages
@@ -1713,6 +2486,7 @@ impl ChatWidget {
             context_usage,
             &self.conversation_id,
             self.rate_limit_snapshot.as_ref(),
+            self.status_line.cost(),
         ));
     }
 
@@ -1938,11 +2712,13 @@ impl ChatWidget {
     /// Set the approval policy in the widget's config copy.
     pub(crate) fn set_approval_policy(&mut self, policy: AskForApproval) {
         self.config.approval_policy = policy;
+        self.sync_status_line_sandbox();
     }
 
     /// Set the sandbox policy in the widget's config copy.
     pub(crate) fn set_sandbox_policy(&mut self, policy: SandboxPolicy) {
         self.config.sandbox_policy = policy;
+        self.sync_status_line_sandbox();
     }
 
     /// Set the reasoning effort in the widget's config copy.
@@ -2061,16 +2837,11 @@ impl ChatWidget {
 
         items.push(SelectionItem {
             name: "Review uncommitted changes".to_string(),
-            actions: vec![Box::new(
-                move |tx: &AppEventSender| {
-                    tx.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
-                            user_facing_hint: "current changes".to_string(),
-                        },
-                    }));
-                },
-            )],
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::Review {
+                    review_request: uncommitted_changes_review_request(),
+                }));
+            })],
             dismiss_on_select: true,
             ..Default::default()
         });
@@ -2105,6 +2876,160 @@ impl ChatWidget {
         });
     }
 
+    /// Show the comments `/todos` found, then offer to have Codex resolve
+    /// all of them or those of one owner.
+    pub(crate) fn on_todos_result(&mut self, todos: Vec<TodoComment>) {
+        if todos.is_empty() {
+            self.add_info_message(
+                "No TODO, FIXME or HACK comments found.".to_string(),
+                Some("Files ignored by .gitignore or .codexignore are skipped.".to_string()),
+            );
+            return;
+        }
+        self.add_to_history(history_cell::new_todos_output(&todos));
+
+        let fix_item = |name: String, todos: &[&TodoComment]| {
+            let prompt = fix_todos_prompt(todos);
+            SelectionItem {
+                name,
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::SubmitText(prompt.clone()));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            }
+        };
+        let all: Vec<&TodoComment> = todos.iter().collect();
+        let mut items = vec![fix_item(format!("Fix all {}", todos.len()), &all)];
+        for (owner, owned) in group_todos(&todos, TodoGrouping::Owner) {
+            if owned.len() < todos.len() && owned.iter().any(|todo| todo.owner.is_some()) {
+                items.push(fix_item(
+                    format!("Fix the {} of {owner}", owned.len()),
+                    &owned,
+                ));
+            }
+        }
+        items.push(SelectionItem {
+            name: "Not now".to_string(),
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Ask Codex to resolve these comments?".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn on_action_denied(&mut self, request: ApprovalRequest) {
+        self.denied_actions.push(request);
+    }
+
+    /// List the denied commands and edits (`/retry-denied`); picking one puts
+    /// a prompt asking Codex to propose it again in the composer.
+    fn open_retry_denied_popup(&mut self) {
+        if self.denied_actions.is_empty() {
+            self.add_info_message("No denied commands or edits to retry.".to_string(), None);
+            return;
+        }
+        let items = self
+            .denied_actions
+            .entries(&self.config.cwd)
+            .into_iter()
+            .map(|(index, label, reason)| SelectionItem {
+                name: label,
+                description: reason,
+                actions: vec![Box::new(move |tx: &AppEventSender| {
+                    tx.send(AppEvent::RetryDeniedAction(index));
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            })
+            .collect();
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Retry a denied command or edit".to_string()),
+            subtitle: Some("The request goes to the composer, where it can be edited.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn retry_denied_action(&mut self, index: usize) {
+        if let Some(prompt) = self.denied_actions.take_retry_prompt(index) {
+            self.bottom_pane.set_composer_text(prompt);
+            self.request_redraw();
+        }
+    }
+
+    /// Ask whether to start the next runbook step.
+    pub(crate) fn open_runbook_confirm_popup(&mut self, title: String, step_label: String) {
+        let items = vec![
+            SelectionItem {
+                name: format!("Start {step_label}"),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::RunbookContinue);
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Stop the runbook".to_string(),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::RunbookStop);
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(title),
+            subtitle: Some("Press Esc to chat first; you will be asked again.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    /// Ask how to handle a failed runbook verification command.
+    pub(crate) fn open_runbook_failure_popup(&mut self, title: String, command: String) {
+        let items = vec![
+            SelectionItem {
+                name: "Ask Codex to fix it".to_string(),
+                description: Some("Send the failure output and verify again".to_string()),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::RunbookRetry);
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Continue anyway".to_string(),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::RunbookSkip);
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Stop the runbook".to_string(),
+                actions: vec![Box::new(|tx: &AppEventSender| {
+                    tx.send(AppEvent::RunbookStop);
+                })],
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some(title),
+            subtitle: Some(format!("`{command}` failed.")),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     pub(crate) async fn show_review_branch_picker(&mut self, cwd: &Path) {
         let branches = local_git_branches(cwd).await;
         let current_branch = current_branch_name(cwd)
@@ -2118,12 +3043,7 @@ impl ChatWidget {
                 name: format!("{current_branch} -> {branch}"),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: format!(
-                                "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
-                            ),
-                            user_facing_hint: format!("changes against '{branch}'"),
-                        },
+                        review_request: base_branch_review_request(&branch),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2149,21 +3069,13 @@ impl ChatWidget {
         for entry in commits {
             let subject = entry.subject.clone();
             let sha = entry.sha.clone();
-            let short = sha.chars().take(7).collect::<String>();
             let search_val = format!("{subject} {sha}");
 
             items.push(SelectionItem {
                 name: subject.clone(),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
-                    let hint = format!("commit {short}");
-                    let prompt = format!(
-                        "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                    );
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt,
-                            user_facing_hint: hint,
-                        },
+                        review_request: commit_review_request(&sha, Some(&subject)),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2225,6 +3137,20 @@ impl ChatWidget {
         self.conversation_id
     }
 
+    /// Copies `codex resume <id>` for this session, so it can be picked up
+    /// again later.
+    fn copy_resume_command(&mut self) {
+        let Some(conversation_id) = self.conversation_id else {
+            self.add_info_message("The session has not started yet.".to_string(), None);
+            return;
+        };
+        let command = format!("codex resume {conversation_id}");
+        match copy_to_clipboard(&command) {
+            Ok(()) => self.add_info_message(format!("Copied `{command}` to the clipboard."), None),
+            Err(err) => self.add_error_message(format!("Failed to copy `{command}`: {err}")),
+        }
+    }
+
     /// Return a reference to the widget's current config (includes any
     /// runtime overrides applied via TUI, e.g., model or approval policy).
     pub(crate) fn config_ref(&self) -> &Config {
@@ -2258,32 +3184,81 @@ impl WidgetRef for &ChatWidget {
                 tool.render_ref(area, buf);
             }
         }
+        let mut status_line_hits = Vec::new();
         if !status_area.is_empty() {
             if self.bottom_pane.has_active_view() {
                 Paragraph::new("").render(status_area, buf);
             } else {
-                let line = self.status_line.render_line(status_area.width);
-                Paragraph::new(line).render(status_area, buf);
+                let rows = self.status_line.render_rows(status_area.width);
+                for (y, row) in (status_area.y..).zip(&rows) {
+                    status_line_hits.extend(row.hits.iter().map(|hit| {
+                        let columns = Rect::new(
+                            status_area.x + hit.columns.start,
+                            y,
+                            hit.columns.end - hit.columns.start,
+                            1,
+                        );
+                        (columns.intersection(status_area), hit.target)
+                    }));
+                }
+                let lines: Vec<_> = rows.into_iter().map(|row| row.line).collect();
+                Paragraph::new(lines).render(status_area, buf);
             }
         }
+        *self.status_line_hits.borrow_mut() = status_line_hits;
         self.last_rendered_width.set(Some(area.width as usize));
     }
 }
 
+/// A status line filled in as a session starting in `config` would first
+/// show it, without a terminal; see [`crate::dump_status_line`].
+pub(crate) async fn headless_status_line(config: &Config) -> StatusLineState {
+    let mut status_line = StatusLineState::new(config, FrameRequester::test_dummy());
+    status_line.update_model(config.model.clone(), config.model_reasoning_effort);
+    status_line.set_sandbox_policy(&config.sandbox_policy, config.approval_policy);
+    status_line.set_devspace(detect_devspace());
+    status_line.set_hostname(detect_hostname());
+    status_line.set_aws_profile(detect_aws_profile());
+    status_line.set_gcp_project(detect_gcp_project());
+    status_line.set_node_version(detect_node_version(&config.cwd));
+    status_line.set_kubernetes_context(detect_kube_context_async().await);
+    status_line.set_git_info(collect_status_line_git_snapshot(config.cwd.clone()).await);
+    for segment in &config.statusline.custom_segments {
+        let text = run_status_segment_command(&segment.command, &config.cwd).await;
+        status_line.set_custom_segment(&segment.name, text);
+    }
+    status_line
+}
+
 async fn collect_status_line_git_snapshot(cwd: PathBuf) -> Option<StatusLineGitSnapshot> {
-    let info = collect_git_info(&cwd).await?;
-    let (dirty, ahead, behind) = git_status_porcelain(&cwd)
-        .await
-        .unwrap_or((false, None, None));
+    // Independent git calls; run them side by side so the segment updates
+    // as soon as the slowest one is done.
+    let (info, status, stash_count) = tokio::join!(
+        collect_git_info(&cwd),
+        git_status_porcelain(&cwd),
+        git_stash_count(&cwd)
+    );
+    let info = info?;
+    let status = status.unwrap_or_default();
     Some(StatusLineGitSnapshot {
         branch: info.branch,
-        dirty,
-        ahead,
-        behind,
+        dirty: status.dirty,
+        ahead: status.ahead,
+        behind: status.behind,
+        stash_count: stash_count.unwrap_or(0),
+        conflicts: status.conflicts,
     })
 }
 
-async fn git_status_porcelain(cwd: &Path) -> Option<(bool, Option<u32>, Option<u32>)> {
+#[derive(Debug, Default, PartialEq)]
+struct GitPorcelainStatus {
+    dirty: bool,
+    ahead: Option<u32>,
+    behind: Option<u32>,
+    conflicts: u32,
+}
+
+async fn git_status_porcelain(cwd: &Path) -> Option<GitPorcelainStatus> {
     let output = Command::new("git")
         .args(["status", "--porcelain=2", "--branch"])
         .current_dir(cwd)
@@ -2293,26 +3268,90 @@ async fn git_status_porcelain(cwd: &Path) -> Option<(bool, Option<u32>, Option<u
     if !output.status.success() {
         return None;
     }
-    let text = String::from_utf8_lossy(&output.stdout);
-    let mut dirty = false;
-    let mut ahead = None;
-    let mut behind = None;
+    Some(parse_git_status_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+fn parse_git_status_porcelain(text: &str) -> GitPorcelainStatus {
+    let mut status = GitPorcelainStatus::default();
     for line in text.lines() {
         if !line.starts_with('#') {
-            dirty = true;
+            status.dirty = true;
+            // Unmerged entries are reported as `u <XY> ...`.
+            if line.starts_with("u ") {
+                status.conflicts += 1;
+            }
             continue;
         }
         if let Some(rest) = line.strip_prefix("# branch.ab ") {
             let mut parts = rest.split_whitespace();
             if let Some(ahead_part) = parts.next() {
-                ahead = ahead_part.strip_prefix('+').and_then(|s| s.parse().ok());
+                status.ahead = ahead_part.strip_prefix('+').and_then(|s| s.parse().ok());
             }
             if let Some(behind_part) = parts.next() {
-                behind = behind_part.strip_prefix('-').and_then(|s| s.parse().ok());
+                status.behind = behind_part.strip_prefix('-').and_then(|s| s.parse().ok());
             }
         }
     }
-    Some((dirty, ahead, behind))
+    status
+}
+
+/// Number of stash entries; `None` when there is no stash.
+async fn git_stash_count(cwd: &Path) -> Option<u32> {
+    let output = Command::new("git")
+        .args(["rev-list", "--walk-reflogs", "--count", "refs/stash"])
+        .current_dir(cwd)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// `diff` piped through the `diff` output filter, or unchanged without one
+/// or when it fails.
+async fn filter_diff(filter: Option<Vec<String>>, diff: String) -> String {
+    let Some(command) = filter else {
+        return diff;
+    };
+    if diff.trim().is_empty() {
+        return diff;
+    }
+    let input = output_filter::strip_ansi(&diff);
+    match spawn_blocking(move || output_filter::apply(&command, &input)).await {
+        Ok(Some(filtered)) => filtered,
+        _ => diff,
+    }
+}
+
+/// First non-empty line `command` prints, or `None` when it fails or takes
+/// too long.
+async fn run_status_segment_command(command: &str, cwd: &Path) -> Option<String> {
+    const TIMEOUT: Duration = Duration::from_secs(5);
+    let output = tokio::time::timeout(
+        TIMEOUT,
+        Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .current_dir(cwd)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
 }
 
 async fn detect_kube_context_async() -> Option<String> {
@@ -2323,37 +3362,115 @@ async fn detect_kube_context_async() -> Option<String> {
 }
 
 fn detect_kube_context_sync() -> Option<String> {
-    for path in kube_config_paths() {
-        if let Ok(contents) = fs::read_to_string(&path) {
-            for line in contents.lines() {
-                let trimmed = line.trim();
-                if trimmed.starts_with('#') {
-                    continue;
-                }
-                if let Some(value) = trimmed.strip_prefix("current-context:") {
-                    let context = value.trim();
-                    if !context.is_empty() {
-                        return Some(trim_kube_context(context));
-                    }
-                }
-            }
-        }
-    }
-    None
+    codex_core::kubernetes::current_context().map(|context| trim_kube_context(&context))
+}
+
+fn trim_kube_context(context: &str) -> String {
+    context.rsplit('/').next().unwrap_or(context).to_string()
+}
+
+fn detect_azure_subscription() -> Option<String> {
+    let config_dir = env::var_os("AZURE_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".azure")));
+    let profile = config_dir
+        .and_then(|dir| std::fs::read_to_string(dir.join("azureProfile.json")).ok())
+        .unwrap_or_default();
+    azure_subscription(&profile, env::var("AZURE_SUBSCRIPTION_ID").ok().as_deref())
+}
+
+/// Name of the subscription `az` uses, from its `azureProfile.json`: the one
+/// `AZURE_SUBSCRIPTION_ID` names, else the default one. An id the profile
+/// does not know is shown as is.
+fn azure_subscription(profile: &str, selected_id: Option<&str>) -> Option<String> {
+    // `az` writes the profile with a byte order mark.
+    let profile: Option<serde_json::Value> =
+        serde_json::from_str(profile.trim_start_matches('\u{feff}')).ok();
+    let subscriptions = profile
+        .as_ref()
+        .and_then(|profile| profile.get("subscriptions"))
+        .and_then(serde_json::Value::as_array);
+    let name = |subscription: &serde_json::Value| {
+        subscription
+            .get("name")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    };
+    if let Some(id) = selected_id.map(str::trim).filter(|id| !id.is_empty()) {
+        let known = subscriptions.into_iter().flatten().find(|subscription| {
+            subscription.get("id").and_then(serde_json::Value::as_str) == Some(id)
+        });
+        return known.and_then(name).or_else(|| Some(id.to_string()));
+    }
+    subscriptions?
+        .iter()
+        .find(|subscription| {
+            subscription
+                .get("isDefault")
+                .and_then(serde_json::Value::as_bool)
+                == Some(true)
+        })
+        .and_then(name)
 }
 
-fn kube_config_paths() -> Vec<PathBuf> {
-    if let Some(paths) = env::var_os("KUBECONFIG") {
-        env::split_paths(&paths).collect()
-    } else if let Some(home) = env::var_os("HOME") {
-        vec![PathBuf::from(home).join(".kube/config")]
+const DOCKER_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Battery charge moves slowly; sampling it once a minute is plenty.
+const BATTERY_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Status line probe intervals. Git is also refreshed after each command and
+/// turn, and the Kubernetes context and AWS profile after each turn, so
+/// these only catch changes made outside Codex.
+const GIT_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+const KUBE_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+const AWS_PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+fn detect_docker_context() -> Option<String> {
+    let config_dir = env::var_os("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".docker")));
+    docker_context(|key| env::var(key).ok(), config_dir.as_deref())
+}
+
+/// The Docker context the `docker` CLI would use, as `docker context show`
+/// resolves it: `DOCKER_CONTEXT`, then `DOCKER_HOST` (shown without its
+/// scheme), then `currentContext` in `config.json`. `None` for the default
+/// context.
+fn docker_context(
+    var: impl Fn(&str) -> Option<String>,
+    config_dir: Option<&Path>,
+) -> Option<String> {
+    let set = |key: &str| var(key).filter(|value| !value.trim().is_empty());
+    let context = if let Some(context) = set("DOCKER_CONTEXT") {
+        context.trim().to_string()
+    } else if let Some(host) = set("DOCKER_HOST") {
+        let host = host.trim();
+        return Some(
+            host.split_once("://")
+                .map_or(host, |(_, rest)| rest)
+                .to_string(),
+        );
     } else {
-        Vec::new()
-    }
+        let contents = std::fs::read_to_string(config_dir?.join("config.json")).ok()?;
+        let config: serde_json::Value = serde_json::from_str(&contents).ok()?;
+        config.get("currentContext")?.as_str()?.trim().to_string()
+    };
+    (!context.is_empty() && context != "default").then_some(context)
 }
 
-fn trim_kube_context(context: &str) -> String {
-    context.rsplit('/').next().unwrap_or(context).to_string()
+/// Whether the daemon of the selected context answers `docker version`.
+async fn docker_daemon_reachable() -> bool {
+    const TIMEOUT: Duration = Duration::from_secs(3);
+    let output = tokio::time::timeout(
+        TIMEOUT,
+        Command::new("docker")
+            .args(["version", "--format", "{{.Server.Version}}"])
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+    matches!(output, Ok(Ok(output)) if output.status.success())
 }
 
 #[cfg(test)]
@@ -2395,6 +3512,80 @@ fn detect_aws_profile() -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
+fn detect_gcp_project() -> Option<String> {
+    let config_dir = env::var_os("CLOUDSDK_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config/gcloud")));
+    gcp_project(|key| env::var(key).ok(), config_dir.as_deref())
+}
+
+/// The project `gcloud` would use: `CLOUDSDK_CORE_PROJECT`, else `project`
+/// in the `[core]` section of the active configuration, which is
+/// `CLOUDSDK_ACTIVE_CONFIG_NAME` or the one named in `active_config`.
+fn gcp_project(var: impl Fn(&str) -> Option<String>, config_dir: Option<&Path>) -> Option<String> {
+    let set = |key: &str| {
+        var(key)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    if let Some(project) = set("CLOUDSDK_CORE_PROJECT") {
+        return Some(project);
+    }
+    let config_dir = config_dir?;
+    let active = set("CLOUDSDK_ACTIVE_CONFIG_NAME")
+        .or_else(|| {
+            std::fs::read_to_string(config_dir.join("active_config"))
+                .ok()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+        })
+        .unwrap_or_else(|| "default".to_string());
+    let contents = std::fs::read_to_string(
+        config_dir
+            .join("configurations")
+            .join(format!("config_{active}")),
+    )
+    .ok()?;
+    let mut in_core = false;
+    contents.lines().map(str::trim).find_map(|line| {
+        if line.starts_with('[') {
+            in_core = line == "[core]";
+            return None;
+        }
+        let (key, value) = line.split_once('=')?;
+        let value = value.trim();
+        (in_core && key.trim() == "project" && !value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// Node version pinned by the project in `cwd`: `.nvmrc`, then
+/// `.node-version`, then the `volta.node` pin in `package.json`. Only looked
+/// up when `cwd` holds a `package.json`.
+fn detect_node_version(cwd: &Path) -> Option<String> {
+    let package_json = std::fs::read_to_string(cwd.join("package.json")).ok()?;
+    let pinned = [".nvmrc", ".node-version"].iter().find_map(|file| {
+        std::fs::read_to_string(cwd.join(file))
+            .ok()
+            .and_then(|contents| {
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty())
+                    .map(str::to_string)
+            })
+    });
+    let version = pinned.or_else(|| {
+        let package: serde_json::Value = serde_json::from_str(&package_json).ok()?;
+        package
+            .pointer("/volta/node")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    })?;
+    let version = version.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    (!version.is_empty()).then(|| version.to_string())
+}
+
 fn detect_hostname() -> Option<String> {
     if let Ok(host) = env::var("HOSTNAME")
         && !host.trim().is_empty()
@@ -2405,17 +3596,32 @@ fn detect_hostname() -> Option<String> {
 }
 
 enum Notification {
-    AgentTurnComplete { response: String },
-    ExecApprovalRequested { command: String },
-    EditApprovalRequested { cwd: PathBuf, changes: Vec<PathBuf> },
+    AgentTurnComplete {
+        response: String,
+        summary: Option<String>,
+    },
+    ExecApprovalRequested {
+        command: String,
+    },
+    EditApprovalRequested {
+        cwd: PathBuf,
+        changes: Vec<PathBuf>,
+    },
+    QuestionAsked {
+        question: String,
+    },
 }
 
 impl Notification {
     fn display(&self) -> String {
         match self {
-            Notification::AgentTurnComplete { response } => {
-                Notification::agent_turn_preview(response)
-                    .unwrap_or_else(|| "Agent turn complete".to_string())
+            Notification::AgentTurnComplete { response, summary } => {
+                let preview = Notification::agent_turn_preview(response)
+                    .unwrap_or_else(|| "Agent turn complete".to_string());
+                match summary {
+                    Some(summary) => format!("{summary} · {preview}"),
+                    None => preview,
+                }
             }
             Notification::ExecApprovalRequested { command } => {
                 format!("Approval requested: {}", truncate_text(command, 30))
@@ -2431,6 +3637,9 @@ impl Notification {
                     }
                 )
             }
+            Notification::QuestionAsked { question } => {
+                format!("Codex asks: {}", truncate_text(question, 30))
+            }
         }
     }
 
@@ -2438,7 +3647,8 @@ impl Notification {
         match self {
             Notification::AgentTurnComplete { .. } => "agent-turn-complete",
             Notification::ExecApprovalRequested { .. }
-            | Notification::EditApprovalRequested { .. } => "approval-requested",
+            | Notification::EditApprovalRequested { .. }
+            | Notification::QuestionAsked { .. } => "approval-requested",
         }
     }
 
@@ -2516,21 +3726,13 @@ pub(crate) fn show_review_commit_picker_with_entries(
     for entry in entries {
         let subject = entry.subject.clone();
         let sha = entry.sha.clone();
-        let short = sha.chars().take(7).collect::<String>();
         let search_val = format!("{subject} {sha}");
 
         items.push(SelectionItem {
             name: subject.clone(),
             actions: vec![Box::new(move |tx3: &AppEventSender| {
-                let hint = format!("commit {short}");
-                let prompt = format!(
-                    "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                );
                 tx3.send(AppEvent::CodexOp(Op::Review {
-                    review_request: ReviewRequest {
-                        prompt,
-                        user_facing_hint: hint,
-                    },
+                    review_request: commit_review_request(&sha, Some(&subject)),
                 }));
             })],
             dismiss_on_select: true,
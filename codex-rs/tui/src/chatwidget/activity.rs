@@ -0,0 +1,129 @@
+//! Pair mode: for a fixed stretch of time the status line describes what the
+//! agent is doing right now ("reading src/main.rs", "running cargo check",
+//! "editing 3 files") instead of generic headers, so a long turn can be
+//! followed at a glance.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_core::protocol::FileChange;
+use codex_protocol::parse_command::ParsedCommand;
+
+/// How long `/pair` keeps the ticker on.
+pub(super) const PAIR_MODE_DURATION: Duration = Duration::from_secs(30 * 60);
+
+pub(super) const THINKING: &str = "thinking";
+pub(super) const REPLYING: &str = "writing the reply";
+pub(super) const SEARCHING_WEB: &str = "searching the web";
+
+/// Describe a shell call from its parsed form, falling back to the raw
+/// command.
+pub(super) fn exec_activity(command: &[String], parsed: &[ParsedCommand]) -> String {
+    let mut reads: Vec<&str> = Vec::new();
+    let mut parts: Vec<String> = Vec::new();
+    for parsed in parsed {
+        match parsed {
+            ParsedCommand::Read { name, .. } => reads.push(name),
+            ParsedCommand::ListFiles { path, .. } => parts.push(match path {
+                Some(path) => format!("listing {path}"),
+                None => "listing files".to_string(),
+            }),
+            ParsedCommand::Search { query, path, .. } => parts.push(match (query, path) {
+                (Some(query), Some(path)) => format!("searching for {query} in {path}"),
+                (Some(query), None) => format!("searching for {query}"),
+                (None, Some(path)) => format!("searching {path}"),
+                (None, None) => "searching files".to_string(),
+            }),
+            ParsedCommand::Unknown { cmd } => parts.push(format!("running {cmd}")),
+        }
+    }
+    match reads.as_slice() {
+        [] => {}
+        [name] => parts.insert(0, format!("reading {name}")),
+        [first, second] => parts.insert(0, format!("reading {first} and {second}")),
+        names => parts.insert(0, format!("reading {} files", names.len())),
+    }
+    if parts.is_empty() {
+        return format!("running {}", command.join(" "));
+    }
+    parts.join(", ")
+}
+
+/// Describe a patch: the file it touches, or how many.
+pub(super) fn patch_activity(changes: &HashMap<PathBuf, FileChange>, cwd: &Path) -> String {
+    let mut entries = changes.iter();
+    match (entries.next(), changes.len()) {
+        (Some((path, change)), 1) => {
+            let path = path.strip_prefix(cwd).unwrap_or(path).display();
+            match change {
+                FileChange::Add { .. } => format!("creating {path}"),
+                FileChange::Delete { .. } => format!("deleting {path}"),
+                FileChange::Update { .. } => format!("editing {path}"),
+            }
+        }
+        (_, count) => format!("editing {count} files"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn describes_commands_and_patches() {
+        let read = |name: &str| ParsedCommand::Read {
+            cmd: format!("cat {name}"),
+            name: name.to_string(),
+        };
+        assert_eq!(
+            exec_activity(&[], &[read("src/main.rs")]),
+            "reading src/main.rs"
+        );
+        assert_eq!(
+            exec_activity(&[], &[read("a.rs"), read("b.rs"), read("c.rs")]),
+            "reading 3 files"
+        );
+        assert_eq!(
+            exec_activity(
+                &[],
+                &[ParsedCommand::Search {
+                    cmd: "rg foo src".to_string(),
+                    query: Some("foo".to_string()),
+                    path: Some("src".to_string()),
+                }]
+            ),
+            "searching for foo in src"
+        );
+        assert_eq!(
+            exec_activity(
+                &["cargo".to_string(), "check".to_string()],
+                &[ParsedCommand::Unknown {
+                    cmd: "cargo check".to_string(),
+                }]
+            ),
+            "running cargo check"
+        );
+
+        let cwd = Path::new("/repo");
+        let update = || FileChange::Update {
+            unified_diff: String::new(),
+            move_path: None,
+        };
+        let one = HashMap::from([(PathBuf::from("/repo/src/main.rs"), update())]);
+        assert_eq!(patch_activity(&one, cwd), "editing src/main.rs");
+        let three = HashMap::from([
+            (PathBuf::from("/repo/a.rs"), update()),
+            (PathBuf::from("/repo/b.rs"), update()),
+            (
+                PathBuf::from("/repo/c.rs"),
+                FileChange::Add {
+                    content: String::new(),
+                },
+            ),
+        ]);
+        assert_eq!(patch_activity(&three, cwd), "editing 3 files");
+    }
+}
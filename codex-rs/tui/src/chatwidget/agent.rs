@@ -1,9 +1,12 @@
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
 use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::mpsc::unbounded_channel;
@@ -53,9 +56,7 @@ pub(crate) fn spawn_agent(
             }
         });
 
-        while let Ok(event) = conversation.next_event().await {
-            app_event_tx_clone.send(AppEvent::CodexEvent(event));
-        }
+        forward_events(conversation, app_event_tx_clone).await;
     });
 
     codex_op_tx
@@ -90,10 +91,147 @@ pub(crate) fn spawn_agent_from_existing(
             }
         });
 
-        while let Ok(event) = conversation.next_event().await {
-            app_event_tx_clone.send(AppEvent::CodexEvent(event));
-        }
+        forward_events(conversation, app_event_tx_clone).await;
     });
 
     codex_op_tx
 }
+
+/// Most events held here while the app is behind. Past this, reading stops
+/// and the agent's bounded event channel pushes back on the agent.
+const MAX_HELD_EVENTS: usize = 64;
+
+/// Largest delta merging grows; past it, new deltas are held separately and
+/// count toward [`MAX_HELD_EVENTS`].
+const MAX_MERGED_DELTA_BYTES: usize = 1024 * 1024;
+
+/// Forward the conversation's events to the app until it ends. While the app
+/// is behind, streamed text and command output arriving in the meantime are
+/// merged into the held deltas rather than queued one by one.
+async fn forward_events(conversation: Arc<CodexConversation>, app_event_tx: AppEventSender) {
+    let mut held: VecDeque<Event> = VecDeque::new();
+    let mut ended = false;
+    while !(ended && held.is_empty()) {
+        tokio::select! {
+            biased;
+            slot = app_event_tx.codex_event_slot(), if !held.is_empty() => {
+                let (Some(slot), Some(event)) = (slot, held.pop_front()) else {
+                    return;
+                };
+                app_event_tx.send_codex_event(slot, event);
+            }
+            event = conversation.next_event(), if !ended && held.len() < MAX_HELD_EVENTS => {
+                match event {
+                    Ok(event) => hold_event(&mut held, event),
+                    Err(_) => ended = true,
+                }
+            }
+        }
+    }
+}
+
+fn hold_event(held: &mut VecDeque<Event>, event: Event) {
+    let event = match held.back_mut() {
+        Some(last) if last.id == event.id => match merge_delta(&mut last.msg, event.msg) {
+            Some(msg) => Event { id: event.id, msg },
+            None => return,
+        },
+        _ => event,
+    };
+    held.push_back(event);
+}
+
+/// Append `next` to `last` when both are deltas of the same stream, or hand
+/// `next` back.
+fn merge_delta(last: &mut EventMsg, next: EventMsg) -> Option<EventMsg> {
+    match (last, next) {
+        (EventMsg::AgentMessageDelta(last), EventMsg::AgentMessageDelta(next))
+            if last.delta.len() < MAX_MERGED_DELTA_BYTES =>
+        {
+            last.delta.push_str(&next.delta);
+        }
+        (EventMsg::AgentReasoningDelta(last), EventMsg::AgentReasoningDelta(next))
+            if last.delta.len() < MAX_MERGED_DELTA_BYTES =>
+        {
+            last.delta.push_str(&next.delta);
+        }
+        (
+            EventMsg::AgentReasoningRawContentDelta(last),
+            EventMsg::AgentReasoningRawContentDelta(next),
+        ) if last.delta.len() < MAX_MERGED_DELTA_BYTES => {
+            last.delta.push_str(&next.delta);
+        }
+        (EventMsg::ExecCommandOutputDelta(last), EventMsg::ExecCommandOutputDelta(next))
+            if last.call_id == next.call_id
+                && last.stream == next.stream
+                && last.chunk.len() < MAX_MERGED_DELTA_BYTES =>
+        {
+            last.chunk.extend(next.chunk);
+        }
+        (_, next) => return Some(next),
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::AgentMessageDeltaEvent;
+    use codex_core::protocol::ExecCommandOutputDeltaEvent;
+    use codex_core::protocol::ExecOutputStream;
+    use pretty_assertions::assert_eq;
+
+    fn event(msg: EventMsg) -> Event {
+        Event {
+            id: "sub".to_string(),
+            msg,
+        }
+    }
+
+    fn text(delta: &str) -> Event {
+        event(EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: delta.to_string(),
+        }))
+    }
+
+    fn output(call_id: &str, chunk: &[u8]) -> Event {
+        event(EventMsg::ExecCommandOutputDelta(
+            ExecCommandOutputDeltaEvent {
+                call_id: call_id.to_string(),
+                stream: ExecOutputStream::Stdout,
+                chunk: chunk.to_vec(),
+            },
+        ))
+    }
+
+    #[test]
+    fn held_deltas_of_the_same_stream_are_merged() {
+        let mut held = VecDeque::new();
+        for next in [
+            text("Hel"),
+            text("lo"),
+            output("a", b"1\n"),
+            output("a", b"2\n"),
+            output("b", b"3\n"),
+            text("!"),
+        ] {
+            hold_event(&mut held, next);
+        }
+
+        let summary: Vec<String> = held
+            .into_iter()
+            .map(|event| match event.msg {
+                EventMsg::AgentMessageDelta(delta) => delta.delta,
+                EventMsg::ExecCommandOutputDelta(delta) => {
+                    format!(
+                        "{}:{}",
+                        delta.call_id,
+                        String::from_utf8_lossy(&delta.chunk)
+                    )
+                }
+                other => panic!("unexpected event {other:?}"),
+            })
+            .collect();
+        assert_eq!(summary, vec!["Hello", "a:1\n2\n", "b:3\n", "!"]);
+    }
+}
@@ -3,6 +3,8 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::chatwidget::clear_devspace_override_for_tests;
 use crate::chatwidget::set_devspace_override_for_tests;
+use crate::clock::Clock;
+use crate::clock::ManualClock;
 use crate::statusline::StatusLineState;
 use crate::test_backend::VT100Backend;
 use crate::tui::FrameRequester;
@@ -13,11 +15,15 @@ use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
 use codex_core::config::OPENAI_DEFAULT_MODEL;
+use codex_core::config_types::StatusLineItem;
+use codex_core::config_types::StatusLineLayout;
 use codex_core::protocol::AgentMessageDeltaEvent;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ErrorEvent;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -29,6 +35,7 @@ use codex_core::protocol::InputMessageKind;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::ReviewCodeLocation;
 use codex_core::protocol::ReviewFinding;
 use codex_core::protocol::ReviewLineRange;
@@ -37,6 +44,10 @@ use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
+use codex_core::protocol::TokenUsage;
+use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::UserQuestionKind;
+use codex_core::protocol::UserQuestionRequestEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_protocol::ConversationId;
 use codex_protocol::plan_tool::PlanItemArg;
@@ -112,6 +123,7 @@ fn resumed_initial_messages_render_history() {
                 message: "assistant reply".to_string(),
             }),
         ]),
+        pinned_context: Vec::new(),
         rollout_path: rollout_file.path().to_path_buf(),
     };
 
@@ -281,18 +293,26 @@ fn make_chatwidget_manual() -> (
         running_commands: HashMap::new(),
         task_complete_pending: false,
         interrupts: InterruptManager::new(),
+        json_filter_pending: false,
         reasoning_buffer: String::new(),
         full_reasoning_buffer: String::new(),
         conversation_id: None,
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
+        denied_actions: DeniedActions::default(),
         suppress_session_configured_redraw: false,
         pending_notification: None,
         is_review_mode: false,
         ghost_snapshots: Vec::new(),
         ghost_snapshots_disabled: true,
         needs_final_message_separator: false,
+        pair_mode_until: None,
+        status_export: None,
+        status_probes: StatusLineProbes::default(),
+        status_segment_tasks: Vec::new(),
+        docker_refreshed_at: std::cell::Cell::new(None),
         last_rendered_width: std::cell::Cell::new(None),
+        status_line_hits: std::cell::RefCell::default(),
     };
     // Force a deterministic devspace so status line snapshots stay stable.
     set_devspace_override_for_tests(Some("earth".to_string()));
@@ -386,6 +406,30 @@ fn test_rate_limit_warnings_monthly() {
     );
 }
 
+#[test]
+fn exhausted_rate_limit_reset_waits_for_the_last_used_up_window() {
+    let window = |used_percent: f64, resets_in_seconds: u64| RateLimitWindow {
+        used_percent,
+        window_minutes: None,
+        resets_in_seconds: Some(resets_in_seconds),
+    };
+
+    let snapshot = RateLimitSnapshot {
+        primary: Some(window(100.0, 42)),
+        secondary: Some(window(80.0, 86_400)),
+    };
+    assert_eq!(
+        exhausted_rate_limit_reset(&snapshot),
+        Some(Duration::from_secs(42))
+    );
+
+    let snapshot = RateLimitSnapshot {
+        primary: Some(window(99.0, 42)),
+        secondary: None,
+    };
+    assert_eq!(exhausted_rate_limit_reset(&snapshot), None);
+}
+
 // (removed experimental resize snapshot test)
 
 #[test]
@@ -654,6 +698,131 @@ fn streaming_final_answer_keeps_task_running_state() {
     assert!(chat.bottom_pane.ctrl_c_quit_hint_visible());
 }
 
+#[test]
+fn status_json_writes_the_status_to_a_file() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    let dir = tempfile::tempdir().expect("tempdir");
+    chat.config.cwd = dir.path().to_path_buf();
+
+    chat.bottom_pane
+        .set_composer_text("/status json status.json".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_matches!(op_rx.try_recv(), Ok(Op::GetSessionStatus));
+
+    let status = SessionStatus {
+        conversation_id: ConversationId::new(),
+        model: "gpt-5-codex".to_string(),
+        model_provider: "openai".to_string(),
+        reasoning_effort: None,
+        cwd: dir.path().to_path_buf(),
+        account: None,
+        approval_policy: AskForApproval::OnRequest,
+        sandbox_policy: SandboxPolicy::ReadOnly,
+        mcp_servers: HashMap::new(),
+        token_usage: None,
+        project_docs: Vec::new(),
+    };
+    chat.handle_codex_event(Event {
+        id: "status".into(),
+        msg: EventMsg::SessionStatusResponse(status),
+    });
+
+    let written = std::fs::read_to_string(dir.path().join("status.json")).expect("status file");
+    let json: serde_json::Value = serde_json::from_str(&written).expect("json");
+    assert_eq!(json["model"], "gpt-5-codex");
+    assert_eq!(json["approval_policy"], "on-request");
+
+    chat.bottom_pane
+        .set_composer_text("/status yaml".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(chat.bottom_pane.composer_text(), "/status yaml");
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn statusline_dump_writes_the_snapshot_to_a_file() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let dir = tempfile::tempdir().expect("tempdir");
+    chat.config.cwd = dir.path().to_path_buf();
+    chat.status_line.update_model("gpt-5-codex", None);
+    chat.status_line.start_task("Working");
+
+    chat.bottom_pane
+        .set_composer_text("/statusline dump statusline.json".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let written =
+        std::fs::read_to_string(dir.path().join("statusline.json")).expect("snapshot file");
+    let json: serde_json::Value = serde_json::from_str(&written).expect("json");
+    assert_eq!(json["model"]["label"], "gpt-5-codex");
+    assert_eq!(json["run_state"]["label"], "Working");
+    assert_eq!(json["run_state"]["queued_messages"], serde_json::json!([]));
+
+    chat.bottom_pane
+        .set_composer_text("/statusline show".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(chat.bottom_pane.composer_text(), "/statusline show");
+}
+
+#[test]
+fn debug_perf_toggles_the_perf_hud() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.bottom_pane
+        .set_composer_text("/debug perf".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let mut toggled = false;
+    while let Ok(event) = rx.try_recv() {
+        toggled |= matches!(event, AppEvent::TogglePerfHud);
+    }
+    assert!(toggled, "expected AppEvent::TogglePerfHud");
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+
+    chat.bottom_pane.set_composer_text("/debug gpu".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(chat.bottom_pane.composer_text(), "/debug gpu");
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn note_command_adds_a_note_to_the_transcript_and_session() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+
+    chat.bottom_pane
+        .set_composer_text("/note  chose the second migration plan ".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one note cell");
+    assert_eq!(
+        lines_to_single_string(&cells[0]).trim_end(),
+        "• Note chose the second migration plan"
+    );
+    match op_rx.try_recv() {
+        Ok(Op::AddNote {
+            text,
+            include_in_context,
+        }) => {
+            assert_eq!(text, "chose the second migration plan");
+            assert!(include_in_context);
+        }
+        other => panic!("expected Op::AddNote, got {other:?}"),
+    }
+
+    chat.config.tui_private_notes = true;
+    chat.bottom_pane
+        .set_composer_text("/note keep this to myself".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_matches!(
+        op_rx.try_recv(),
+        Ok(Op::AddNote {
+            include_in_context: false,
+            ..
+        })
+    );
+}
+
 #[test]
 fn exec_history_cell_shows_working_then_completed() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -824,6 +993,55 @@ fn custom_prompt_submit_sends_review_op() {
     }
 }
 
+fn next_question_answer(
+    rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>,
+) -> (String, Option<String>) {
+    while let Ok(evt) = rx.try_recv() {
+        if let AppEvent::CodexOp(Op::UserQuestionAnswer { call_id, answer }) = evt {
+            return (call_id, answer);
+        }
+    }
+    panic!("expected a UserQuestionAnswer op");
+}
+
+/// A choice question shows the options; picking one answers with it and
+/// dismissing a text question answers with nothing.
+#[test]
+fn user_question_sends_the_answer() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "sub-question".into(),
+        msg: EventMsg::UserQuestionRequest(UserQuestionRequestEvent {
+            call_id: "call-choice".into(),
+            question: "Which database?".into(),
+            kind: UserQuestionKind::Choice {
+                options: vec!["postgres".into(), "sqlite".into()],
+            },
+        }),
+    });
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    assert_eq!(
+        next_question_answer(&mut rx),
+        ("call-choice".to_string(), Some("sqlite".to_string()))
+    );
+
+    chat.handle_codex_event(Event {
+        id: "sub-question".into(),
+        msg: EventMsg::UserQuestionRequest(UserQuestionRequestEvent {
+            call_id: "call-text".into(),
+            question: "Name the new module".into(),
+            kind: UserQuestionKind::Text { placeholder: None },
+        }),
+    });
+    chat.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+    assert_eq!(
+        next_question_answer(&mut rx),
+        ("call-text".to_string(), None)
+    );
+}
+
 /// Hitting Enter on an empty custom prompt view does not submit.
 #[test]
 fn custom_prompt_enter_empty_does_not_send() {
@@ -1020,6 +1238,101 @@ fn model_selection_popup_snapshot() {
     assert_snapshot!("model_selection_popup", popup);
 }
 
+#[test]
+fn clicking_the_model_segment_opens_the_model_picker() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.config.model = "gpt-5-codex".to_string();
+    let area = Rect::new(0, 0, 120, chat.desired_height(120));
+    (&chat).render_ref(area, &mut Buffer::empty(area));
+    let model = chat
+        .status_line_hits
+        .borrow()
+        .iter()
+        .find(|(_, target)| *target == StatusLineTarget::Model)
+        .map(|(area, _)| *area)
+        .expect("model segment is clickable");
+
+    let click = |kind| MouseEvent {
+        kind,
+        column: model.x,
+        row: model.y,
+        modifiers: KeyModifiers::NONE,
+    };
+    chat.handle_mouse_event(click(MouseEventKind::Up(MouseButton::Left)));
+    assert!(!chat.bottom_pane.has_active_view());
+    chat.handle_mouse_event(click(MouseEventKind::Down(MouseButton::Left)));
+    assert!(render_bottom_popup(&chat, 120).contains("Select Model and Effort"));
+}
+
+#[test]
+fn todos_result_lists_comments_and_offers_to_fix_them() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    let todo = |path: &str, line, owner: Option<&str>, text: &str| TodoComment {
+        path: PathBuf::from(path),
+        line,
+        tag: codex_core::todos::TodoTag::Todo,
+        owner: owner.map(str::to_string),
+        text: text.to_string(),
+    };
+    chat.on_todos_result(vec![
+        todo("src/lib.rs", 3, Some("alice"), "handle errors"),
+        todo("src/main.rs", 10, None, "parse flags"),
+    ]);
+
+    let history = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert!(history.contains("src/lib.rs"), "{history}");
+    assert!(history.contains("TODO(alice): handle errors"), "{history}");
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("Fix all 2"), "{popup}");
+    assert!(popup.contains("Fix the 1 of alice"), "{popup}");
+    assert!(!popup.contains("unowned"), "{popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let prompt = std::iter::from_fn(|| rx.try_recv().ok())
+        .find_map(|event| match event {
+            AppEvent::SubmitText(text) => Some(text),
+            _ => None,
+        })
+        .expect("picking an item submits a prompt");
+    assert!(prompt.contains("- src/lib.rs:3 TODO(alice): handle errors"));
+    assert!(prompt.contains("- src/main.rs:10 TODO: parse flags"));
+}
+
+#[test]
+fn retry_denied_puts_the_denied_command_in_the_composer() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.dispatch_command(SlashCommand::RetryDenied);
+    let history = lines_to_single_string(&drain_insert_history(&mut rx).concat());
+    assert!(
+        history.contains("No denied commands or edits to retry."),
+        "{history}"
+    );
+
+    chat.on_action_denied(ApprovalRequest::Exec {
+        id: "call-1".to_string(),
+        command: vec!["cargo".to_string(), "publish".to_string()],
+        reason: Some("release the crate".to_string()),
+    });
+    chat.dispatch_command(SlashCommand::RetryDenied);
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(popup.contains("cargo publish"), "{popup}");
+    assert!(popup.contains("release the crate"), "{popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let index = std::iter::from_fn(|| rx.try_recv().ok())
+        .find_map(|event| match event {
+            AppEvent::RetryDeniedAction(index) => Some(index),
+            _ => None,
+        })
+        .expect("picking an item retries it");
+    chat.retry_denied_action(index);
+    assert_eq!(
+        chat.bottom_pane.composer_text(),
+        "Run this command that I denied earlier:\n\n```\ncargo publish\n```"
+    );
+    assert!(chat.denied_actions.is_empty());
+}
+
 #[test]
 fn model_reasoning_selection_popup_snapshot() {
     let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
@@ -1510,6 +1823,36 @@ fn ui_snapshots_small_heights_task_running() {
     }
 }
 
+#[test]
+fn crossing_the_critical_context_threshold_is_noted_once() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    // 100k tokens of the window are the conversation's; 12k are the baseline.
+    let usage = |total_tokens| TokenUsageInfo {
+        total_token_usage: TokenUsage {
+            total_tokens,
+            ..TokenUsage::default()
+        },
+        last_token_usage: TokenUsage::default(),
+        model_context_window: Some(112_000),
+    };
+
+    chat.set_token_info(Some(usage(62_000)));
+    assert!(drain_insert_history(&mut rx).is_empty());
+
+    chat.set_token_info(Some(usage(107_000)));
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1);
+    assert!(
+        lines_to_single_string(&cells[0]).contains("Only 5% of the context window is left"),
+        "{cells:?}"
+    );
+
+    chat.set_token_info(Some(usage(108_000)));
+    chat.set_token_info(Some(usage(62_000)));
+    chat.set_token_info(Some(usage(107_000)));
+    assert!(drain_insert_history(&mut rx).is_empty());
+}
+
 #[test]
 fn apply_patch_events_emit_history_cells() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -1955,6 +2298,7 @@ fn stream_error_is_rendered_to_history() {
         id: "sub-1".into(),
         msg: EventMsg::StreamError(StreamErrorEvent {
             message: msg.to_string(),
+            rate_limit_retry_after_ms: None,
         }),
     });
 
@@ -1966,6 +2310,33 @@ fn stream_error_is_rendered_to_history() {
     assert!(blob.contains("idle timeout waiting for SSE"));
 }
 
+#[test]
+fn errors_are_rendered_with_a_hint_for_their_kind() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "sub-1".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "unexpected status 401 Unauthorized".to_string(),
+            kind: ErrorKind::Auth,
+        }),
+    });
+    let cells = drain_insert_history(&mut rx);
+    let blob = lines_to_single_string(cells.last().expect("error cell"));
+    assert!(blob.contains("■ unexpected status 401 Unauthorized"));
+    assert!(blob.contains("codex login"), "{blob}");
+
+    chat.handle_codex_event(Event {
+        id: "sub-2".into(),
+        msg: EventMsg::Error(ErrorEvent {
+            message: "boom".to_string(),
+            kind: ErrorKind::Internal,
+        }),
+    });
+    let cells = drain_insert_history(&mut rx);
+    let blob = lines_to_single_string(cells.last().expect("error cell"));
+    assert_eq!(blob.trim(), "■ boom");
+}
+
 #[test]
 fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
@@ -1999,6 +2370,7 @@ fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            summary: None,
         }),
     });
 
@@ -2269,6 +2641,7 @@ printf 'fenced within fenced\n'
         id: "t1".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            summary: None,
         }),
     });
     for lines in drain_insert_history(&mut rx) {
@@ -2277,3 +2650,281 @@ printf 'fenced within fenced\n'
 
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[test]
+fn git_status_porcelain_counts_conflicts_and_upstream_distance() {
+    let text = "# branch.oid 1111111111111111111111111111111111111111
+# branch.head main
+# branch.upstream origin/main
+# branch.ab +2 -1
+1 .M N... 100644 100644 100644 aaaa bbbb src/lib.rs
+u UU N... 100644 100644 100644 100644 aaaa bbbb cccc src/main.rs
+u AA N... 000000 100644 100644 100644 aaaa bbbb cccc README.md
+? notes.txt
+";
+
+    assert_eq!(
+        parse_git_status_porcelain(text),
+        GitPorcelainStatus {
+            dirty: true,
+            ahead: Some(2),
+            behind: Some(1),
+            conflicts: 2,
+        }
+    );
+}
+
+#[test]
+fn node_version_prefers_nvmrc_then_node_version_then_volta() {
+    let project = tempfile::tempdir().expect("tempdir");
+    let cwd = project.path();
+    assert_eq!(detect_node_version(cwd), None);
+
+    std::fs::write(
+        cwd.join("package.json"),
+        r#"{"name": "app", "volta": {"node": "18.19.0"}}"#,
+    )
+    .expect("write package.json");
+    assert_eq!(detect_node_version(cwd), Some("18.19.0".to_string()));
+
+    std::fs::write(cwd.join(".node-version"), "20.11.1\n").expect("write .node-version");
+    assert_eq!(detect_node_version(cwd), Some("20.11.1".to_string()));
+
+    std::fs::write(cwd.join(".nvmrc"), "\nv22\n").expect("write .nvmrc");
+    assert_eq!(detect_node_version(cwd), Some("22".to_string()));
+
+    std::fs::remove_file(cwd.join("package.json")).expect("remove package.json");
+    assert_eq!(detect_node_version(cwd), None);
+}
+
+#[test]
+fn docker_context_prefers_env_over_config_file() {
+    let config_dir = tempfile::tempdir().expect("tempdir");
+    let context = |vars: &[(&str, &str)]| {
+        docker_context(
+            |key| {
+                vars.iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| value.to_string())
+            },
+            Some(config_dir.path()),
+        )
+    };
+    assert_eq!(context(&[]), None);
+
+    std::fs::write(
+        config_dir.path().join("config.json"),
+        r#"{"auths": {}, "currentContext": "colima"}"#,
+    )
+    .expect("write config.json");
+    assert_eq!(context(&[]), Some("colima".to_string()));
+    assert_eq!(
+        context(&[("DOCKER_HOST", "tcp://build-box:2376")]),
+        Some("build-box:2376".to_string())
+    );
+    assert_eq!(
+        context(&[
+            ("DOCKER_HOST", "tcp://build-box:2376"),
+            ("DOCKER_CONTEXT", "desktop-linux"),
+        ]),
+        Some("desktop-linux".to_string())
+    );
+    assert_eq!(context(&[("DOCKER_CONTEXT", "default")]), None);
+}
+
+#[test]
+fn gcp_project_prefers_env_then_active_configuration() {
+    let config_dir = tempfile::tempdir().expect("tempdir");
+    let project = |vars: &[(&str, &str)]| {
+        gcp_project(
+            |key| {
+                vars.iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| value.to_string())
+            },
+            Some(config_dir.path()),
+        )
+    };
+    assert_eq!(project(&[]), None);
+
+    let configurations = config_dir.path().join("configurations");
+    std::fs::create_dir(&configurations).expect("create configurations");
+    std::fs::write(
+        configurations.join("config_default"),
+        "[compute]\nproject = not-core\n[core]\naccount = me@example.com\nproject = sandbox-123\n",
+    )
+    .expect("write default config");
+    std::fs::write(
+        configurations.join("config_work"),
+        "[core]\nproject = prod-456\n",
+    )
+    .expect("write work config");
+    assert_eq!(project(&[]), Some("sandbox-123".to_string()));
+
+    std::fs::write(config_dir.path().join("active_config"), "work\n").expect("write active_config");
+    assert_eq!(project(&[]), Some("prod-456".to_string()));
+    assert_eq!(
+        project(&[("CLOUDSDK_ACTIVE_CONFIG_NAME", "default")]),
+        Some("sandbox-123".to_string())
+    );
+    assert_eq!(
+        project(&[("CLOUDSDK_CORE_PROJECT", "override-789")]),
+        Some("override-789".to_string())
+    );
+}
+
+#[test]
+fn azure_subscription_uses_the_selected_or_default_subscription() {
+    let profile = "\u{feff}{\"subscriptions\": [
+        {\"id\": \"1111\", \"name\": \"Dev\", \"isDefault\": false},
+        {\"id\": \"2222\", \"name\": \"Production\", \"isDefault\": true}
+    ]}";
+    assert_eq!(
+        azure_subscription(profile, None),
+        Some("Production".to_string())
+    );
+    assert_eq!(
+        azure_subscription(profile, Some("1111")),
+        Some("Dev".to_string())
+    );
+    assert_eq!(
+        azure_subscription(profile, Some("3333")),
+        Some("3333".to_string())
+    );
+    assert_eq!(azure_subscription("", None), None);
+}
+
+#[test]
+fn status_line_frames_share_queued_text() {
+    let mut status_line = StatusLineState::new(&test_config(), FrameRequester::test_dummy());
+    status_line.start_task("Working");
+    status_line.set_queued_messages(vec!["x".repeat(1024 * 1024); 4]);
+    let first = status_line.snapshot_for_render(Instant::now());
+    let second = status_line.snapshot_for_render(Instant::now());
+    let (Some(first), Some(second)) = (first.run_state, second.run_state) else {
+        panic!("a running task has a run state");
+    };
+    assert!(Arc::ptr_eq(&first.queued_messages, &second.queued_messages));
+}
+
+#[test]
+fn status_line_timer_and_countdown_follow_the_clock() {
+    let clock = Arc::new(ManualClock::new(Local::now()));
+    let (frame_requester, mut frames) = FrameRequester::test_with_clock(clock.clone());
+    let mut config = test_config();
+    config.statusline.spinner_interval = Some(Duration::from_millis(100));
+    let mut status_line = StatusLineState::new(&config, frame_requester);
+    status_line.start_task("Working");
+    clock.advance(Duration::from_secs(90));
+    assert_eq!(status_line.elapsed_seconds(), Some(90));
+
+    status_line.set_rate_limit_cooldown(Duration::from_secs(30));
+    clock.advance(Duration::from_secs(10));
+    while frames.try_recv().is_ok() {}
+    let snapshot = status_line.snapshot_for_render(clock.now());
+    assert_eq!(snapshot.rate_limit_cooldown, Some(Duration::from_secs(20)));
+    // A running timer keeps frames coming, one per spinner frame.
+    assert_eq!(
+        frames.try_recv().ok(),
+        Some(clock.now() + Duration::from_millis(100))
+    );
+}
+
+#[test]
+fn status_line_rows_change_only_with_what_they_show() {
+    let clock = Arc::new(ManualClock::new(Local::now()));
+    let (frame_requester, _frames) = FrameRequester::test_with_clock(clock.clone());
+    let mut config = test_config();
+    config.statusline.spinner_interval = Some(Duration::from_millis(100));
+    let mut status_line = StatusLineState::new(&config, frame_requester);
+    status_line.update_tokens(Some(TokenUsageInfo {
+        total_token_usage: TokenUsage::default(),
+        last_token_usage: TokenUsage::default(),
+        model_context_window: Some(112_000),
+    }));
+    status_line.start_task("Working");
+    let line = |status_line: &StatusLineState| status_line.render_rows(80)[0].line.clone();
+
+    let first = line(&status_line);
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(line(&status_line), first);
+    clock.advance(Duration::from_millis(950));
+    assert_ne!(line(&status_line), first, "the timer shows a second more");
+
+    let timed = line(&status_line);
+    status_line.update_model("o3", None);
+    assert_ne!(line(&status_line), timed);
+
+    // Without `{status}` nothing on the line moves, so no frames are asked for.
+    config.statusline.layout = Some(StatusLineLayout {
+        left: vec![StatusLineItem::Model],
+        right: Vec::new(),
+    });
+    let (frame_requester, mut frames) = FrameRequester::test_with_clock(clock.clone());
+    let mut status_line = StatusLineState::new(&config, frame_requester);
+    status_line.start_task("Working");
+    while frames.try_recv().is_ok() {}
+    let first = line(&status_line);
+    clock.advance(Duration::from_secs(5));
+    assert_eq!(line(&status_line), first);
+    assert!(frames.try_recv().is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn mcp_results_wait_for_the_json_filter_before_later_events() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.config.tui_output_filters.json = Some(vec!["cat".to_string()]);
+
+    let end = McpToolCallEndEvent {
+        call_id: "call-json".into(),
+        invocation: McpInvocation {
+            server: "search".into(),
+            tool: "find_docs".into(),
+            arguments: None,
+        },
+        duration: Duration::from_millis(10),
+        result: Ok(mcp_types::CallToolResult {
+            content: vec![mcp_types::ContentBlock::TextContent(
+                mcp_types::TextContent {
+                    annotations: None,
+                    text: r#"{"hits":2}"#.into(),
+                    r#type: "text".into(),
+                },
+            )],
+            is_error: None,
+            structured_content: None,
+        }),
+    };
+    chat.handle_codex_event(Event {
+        id: "sub".into(),
+        msg: EventMsg::McpToolCallEnd(end),
+    });
+    assert!(chat.json_filter_pending());
+
+    // A command starting meanwhile waits behind the filtered result.
+    chat.handle_codex_event(Event {
+        id: "sub".into(),
+        msg: EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: "call-exec".into(),
+            command: vec!["echo".into(), "hi".into()],
+            cwd: PathBuf::from("/"),
+            parsed_cmd: Vec::new(),
+        }),
+    });
+    assert!(chat.running_commands.is_empty());
+
+    let (ev, filtered) = loop {
+        match tokio::time::timeout(Duration::from_secs(5), rx.recv()).await {
+            Ok(Some(AppEvent::McpResultFiltered(ev, filtered))) => break (ev, filtered),
+            Ok(Some(_)) => continue,
+            other => panic!("expected the filtered result, got {other:?}"),
+        }
+    };
+    assert_eq!(filtered.as_deref(), Some(r#"{"hits":2}"#));
+    chat.on_mcp_result_filtered(ev, filtered);
+
+    assert!(!chat.json_filter_pending());
+    assert!(!drain_insert_history(&mut rx).is_empty());
+    assert!(chat.running_commands.contains_key("call-exec"));
+}
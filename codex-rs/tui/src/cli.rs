@@ -1,6 +1,7 @@
 use clap::Parser;
 use codex_common::ApprovalModeCliArg;
 use codex_common::CliConfigOverrides;
+use codex_core::bump::BumpOptions;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -27,6 +28,52 @@ pub struct Cli {
     #[clap(skip)]
     pub resume_session_id: Option<String>,
 
+    /// Internal: play back a recorded session by id (UUID). Set by the
+    /// top-level `codex replay <SESSION_ID>` wrapper; not exposed as a public flag.
+    #[clap(skip)]
+    pub replay_session_id: Option<String>,
+
+    /// Internal: playback speed multiplier for `codex replay`.
+    #[clap(skip)]
+    pub replay_speed: Option<f64>,
+
+    /// Internal: asciicast file to record `codex replay` into.
+    #[clap(skip)]
+    pub record_path: Option<PathBuf>,
+
+    /// Internal: runbook file to walk through. Set by the top-level
+    /// `codex runbook <FILE>` wrapper; not exposed as a public flag.
+    #[clap(skip)]
+    pub runbook: Option<PathBuf>,
+
+    /// Internal: start with the logs of the latest failed CI run as the
+    /// prompt. Set by the top-level `codex fix-ci` wrapper; not exposed as a
+    /// public flag.
+    #[clap(skip)]
+    pub fix_ci: bool,
+
+    /// Internal: start with the repository's merge conflicts as the prompt.
+    /// Set by the top-level `codex resolve` wrapper; not exposed as a public
+    /// flag.
+    #[clap(skip)]
+    pub resolve: bool,
+
+    /// Internal: start with a request to plan an interactive rebase of the
+    /// current branch. Set by the top-level `codex rebase` wrapper; not
+    /// exposed as a public flag.
+    #[clap(skip)]
+    pub rebase: bool,
+
+    /// Internal: base for `codex rebase --onto`.
+    #[clap(skip)]
+    pub rebase_onto: Option<String>,
+
+    /// Internal: start with a request to upgrade the outdated dependencies.
+    /// Set by the top-level `codex bump` wrapper; not exposed as a public
+    /// flag.
+    #[clap(skip)]
+    pub bump: Option<BumpOptions>,
+
     /// Model the agent should use.
     #[arg(long, short = 'm')]
     pub model: Option<String>,
@@ -0,0 +1,26 @@
+//! Copying text to the clipboard. The system clipboard is tried first; the
+//! OSC 52 escape sequence is always sent as well, so terminals reached over
+//! SSH or without a display server still get the text.
+
+use std::io::Write;
+
+use base64::Engine;
+
+pub(crate) fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    copy_to_system_clipboard(text);
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b]52;c;{encoded}\x07")?;
+    stdout.flush()
+}
+
+#[cfg(not(target_os = "android"))]
+fn copy_to_system_clipboard(text: &str) {
+    if let Err(err) = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+        tracing::debug!("system clipboard unavailable: {err}");
+    }
+}
+
+/// Android/Termux has no system clipboard support; OSC 52 alone is used.
+#[cfg(target_os = "android")]
+fn copy_to_system_clipboard(_text: &str) {}
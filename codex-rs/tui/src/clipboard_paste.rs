@@ -181,7 +181,9 @@ pub fn normalize_pasted_path(pasted: &str) -> Option<PathBuf> {
         drive || unc
     };
     if looks_like_windows_path {
-        return Some(PathBuf::from(pasted));
+        // Explorer and some terminals hand out verbatim paths
+        // (`\\?\C:\...`); use the plain form where one exists.
+        return Some(dunce::simplified(Path::new(pasted)).to_path_buf());
     }
 
     // shell-escaped single path → unescaped
@@ -307,6 +309,14 @@ mod pasted_paths_tests {
         );
     }
 
+    #[cfg(windows)]
+    #[test]
+    fn normalize_verbatim_windows_path() {
+        let input = r"\\?\C:\Users\Alice\file.png";
+        let result = normalize_pasted_path(input).expect("should accept verbatim windows path");
+        assert_eq!(result, PathBuf::from(r"C:\Users\Alice\file.png"));
+    }
+
     #[test]
     fn pasted_image_format_with_windows_style_paths() {
         assert_eq!(
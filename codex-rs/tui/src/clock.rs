@@ -0,0 +1,82 @@
+//! The time source of state that changes with time: the status line, its
+//! run timer and countdowns, and frame deadlines. Tests drive a
+//! [`ManualClock`] instead of sleeping.
+
+use std::fmt::Debug;
+use std::time::Instant;
+
+use chrono::DateTime;
+use chrono::Local;
+
+pub(crate) trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time, for what is shown as a time of day.
+    fn local_now(&self) -> DateTime<Local>;
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn local_now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+#[cfg(test)]
+pub(crate) use manual::ManualClock;
+
+#[cfg(test)]
+mod manual {
+    use std::sync::Mutex;
+    use std::sync::PoisonError;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use chrono::DateTime;
+    use chrono::Local;
+
+    use super::Clock;
+
+    /// A clock that only moves when told to.
+    #[derive(Debug)]
+    pub(crate) struct ManualClock {
+        start: Instant,
+        local_start: DateTime<Local>,
+        elapsed: Mutex<Duration>,
+    }
+
+    impl ManualClock {
+        pub(crate) fn new(local_start: DateTime<Local>) -> Self {
+            Self {
+                start: Instant::now(),
+                local_start,
+                elapsed: Mutex::new(Duration::ZERO),
+            }
+        }
+
+        pub(crate) fn advance(&self, by: Duration) {
+            *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner) += by;
+        }
+
+        fn elapsed(&self) -> Duration {
+            *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed()
+        }
+
+        fn local_now(&self) -> DateTime<Local> {
+            self.local_start
+                + chrono::Duration::from_std(self.elapsed()).unwrap_or(chrono::Duration::MAX)
+        }
+    }
+}
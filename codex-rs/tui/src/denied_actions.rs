@@ -0,0 +1,200 @@
+//! Commands and edits the user denied in the approval prompt, kept for
+//! `/retry-denied`. Retrying puts a prompt with the exact command or diff in
+//! the composer, so it can be edited before Codex proposes it again.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use codex_core::protocol::FileChange;
+
+use crate::bottom_pane::ApprovalRequest;
+use crate::exec_command::strip_bash_lc_and_escape;
+
+/// Most denied actions kept; older ones are forgotten first.
+const MAX_DENIED_ACTIONS: usize = 20;
+
+#[derive(Debug, Default)]
+pub(crate) struct DeniedActions {
+    /// Oldest first.
+    actions: VecDeque<ApprovalRequest>,
+}
+
+impl DeniedActions {
+    pub(crate) fn push(&mut self, request: ApprovalRequest) {
+        if self.actions.len() == MAX_DENIED_ACTIONS {
+            self.actions.pop_front();
+        }
+        self.actions.push_back(request);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Index, label and reason of each action, most recent first.
+    pub(crate) fn entries(&self, cwd: &Path) -> Vec<(usize, String, Option<String>)> {
+        self.actions
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(index, request)| {
+                let (label, reason) = match request {
+                    ApprovalRequest::Exec {
+                        command, reason, ..
+                    } => (strip_bash_lc_and_escape(command), reason),
+                    ApprovalRequest::ApplyPatch {
+                        changes, reason, ..
+                    } => {
+                        let mut paths: Vec<String> = changes
+                            .keys()
+                            .map(|path| relative_path(path, cwd))
+                            .collect();
+                        paths.sort();
+                        (format!("Edit {}", paths.join(", ")), reason)
+                    }
+                };
+                (index, label, reason.clone())
+            })
+            .collect()
+    }
+
+    /// Remove the action at `index` and return the prompt that asks Codex to
+    /// propose it again.
+    pub(crate) fn take_retry_prompt(&mut self, index: usize) -> Option<String> {
+        self.actions
+            .remove(index)
+            .map(|request| retry_prompt(&request))
+    }
+}
+
+fn retry_prompt(request: &ApprovalRequest) -> String {
+    match request {
+        ApprovalRequest::Exec { command, .. } => format!(
+            "Run this command that I denied earlier:\n\n```\n{}\n```",
+            strip_bash_lc_and_escape(command)
+        ),
+        ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
+            let mut paths: Vec<_> = changes.keys().collect();
+            paths.sort();
+            let mut diff = String::new();
+            for path in paths {
+                let path_display = relative_path(path, cwd);
+                match &changes[path] {
+                    FileChange::Add { content } => {
+                        let _ = writeln!(diff, "--- /dev/null\n+++ {path_display}");
+                        for line in content.lines() {
+                            let _ = writeln!(diff, "+{line}");
+                        }
+                    }
+                    FileChange::Delete { content } => {
+                        let _ = writeln!(diff, "--- {path_display}\n+++ /dev/null");
+                        for line in content.lines() {
+                            let _ = writeln!(diff, "-{line}");
+                        }
+                    }
+                    FileChange::Update {
+                        unified_diff,
+                        move_path,
+                    } => {
+                        let new_path = move_path
+                            .as_deref()
+                            .map_or_else(|| path_display.clone(), |p| relative_path(p, cwd));
+                        let _ = writeln!(diff, "--- {path_display}\n+++ {new_path}");
+                        diff.push_str(unified_diff);
+                        if !unified_diff.ends_with('\n') {
+                            diff.push('\n');
+                        }
+                    }
+                }
+            }
+            format!("Make these edits that I denied earlier:\n\n```diff\n{diff}```")
+        }
+    }
+}
+
+/// `path` relative to `cwd` when it is inside it.
+fn relative_path(path: &Path, cwd: &Path) -> String {
+    path.strip_prefix(cwd).unwrap_or(path).display().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn exec(command: &str) -> ApprovalRequest {
+        ApprovalRequest::Exec {
+            id: command.to_string(),
+            command: vec!["bash".into(), "-lc".into(), command.to_string()],
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn lists_most_recent_first_and_forgets_the_oldest() {
+        let mut denied = DeniedActions::default();
+        for n in 0..=MAX_DENIED_ACTIONS {
+            denied.push(exec(&format!("echo {n}")));
+        }
+        let entries = denied.entries(Path::new("/repo"));
+        assert_eq!(entries.len(), MAX_DENIED_ACTIONS);
+        assert_eq!(entries[0].1, format!("echo {MAX_DENIED_ACTIONS}"));
+        assert_eq!(entries[MAX_DENIED_ACTIONS - 1].1, "echo 1");
+
+        let index = entries[0].0;
+        assert_eq!(
+            denied.take_retry_prompt(index).as_deref(),
+            Some(
+                format!(
+                    "Run this command that I denied earlier:\n\n```\necho {MAX_DENIED_ACTIONS}\n```"
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(
+            denied.entries(Path::new("/repo")).len(),
+            MAX_DENIED_ACTIONS - 1
+        );
+    }
+
+    #[test]
+    fn retrying_an_edit_quotes_its_diff() {
+        let cwd = PathBuf::from("/repo");
+        let mut changes = HashMap::new();
+        changes.insert(
+            cwd.join("src/lib.rs"),
+            FileChange::Update {
+                unified_diff: "@@ -1 +1 @@\n-old\n+new\n".to_string(),
+                move_path: None,
+            },
+        );
+        changes.insert(
+            cwd.join("NOTES.md"),
+            FileChange::Add {
+                content: "hello\n".to_string(),
+            },
+        );
+        let mut denied = DeniedActions::default();
+        denied.push(ApprovalRequest::ApplyPatch {
+            id: "patch".to_string(),
+            reason: Some("tidy up".to_string()),
+            cwd: cwd.clone(),
+            changes,
+        });
+
+        let entries = denied.entries(&cwd);
+        assert_eq!(entries[0].1, "Edit NOTES.md, src/lib.rs");
+        assert_eq!(entries[0].2.as_deref(), Some("tidy up"));
+
+        assert_eq!(
+            denied.take_retry_prompt(0).as_deref(),
+            Some(
+                "Make these edits that I denied earlier:\n\n```diff\n--- /dev/null\n+++ NOTES.md\n+hello\n--- src/lib.rs\n+++ src/lib.rs\n@@ -1 +1 @@\n-old\n+new\n```"
+            )
+        );
+        assert!(denied.is_empty());
+    }
+}
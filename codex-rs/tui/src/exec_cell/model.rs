@@ -1,14 +1,17 @@
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 use codex_protocol::parse_command::ParsedCommand;
 
+/// Output of a finished command. The text is shared so copying a call, as
+/// merging exploring cells does, does not copy what it printed.
 #[derive(Clone, Debug)]
 pub(crate) struct CommandOutput {
     pub(crate) exit_code: i32,
-    pub(crate) stdout: String,
-    pub(crate) stderr: String,
-    pub(crate) formatted_output: String,
+    pub(crate) stdout: Arc<str>,
+    pub(crate) stderr: Arc<str>,
+    pub(crate) formatted_output: Arc<str>,
 }
 
 #[derive(Debug, Clone)]
@@ -82,9 +85,9 @@ impl ExecCell {
                 call.duration = Some(elapsed);
                 call.output = Some(CommandOutput {
                     exit_code: 1,
-                    stdout: String::new(),
-                    stderr: String::new(),
-                    formatted_output: String::new(),
+                    stdout: "".into(),
+                    stderr: "".into(),
+                    formatted_output: "".into(),
                 });
             }
         }
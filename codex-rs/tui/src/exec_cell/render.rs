@@ -67,14 +67,15 @@ pub(crate) fn output_lines(
     };
 
     let src = if *exit_code == 0 { stdout } else { stderr };
-    let lines: Vec<&str> = src.lines().collect();
-    let total = lines.len();
+    // Only the head and tail are shown; walk the lines rather than collecting
+    // them, since a command can print millions.
+    let total = src.lines().count();
     let limit = TOOL_CALL_MAX_LINES;
 
     let mut out = Vec::new();
 
     let head_end = total.min(limit);
-    for (i, raw) in lines[..head_end].iter().enumerate() {
+    for (i, raw) in src.lines().take(head_end).enumerate() {
         let mut line = ansi_escape_line(raw);
         let prefix = if !include_prefix {
             ""
@@ -101,7 +102,7 @@ pub(crate) fn output_lines(
     } else {
         head_end
     };
-    for raw in lines[tail_start..].iter() {
+    for raw in src.lines().skip(tail_start) {
         let mut line = ansi_escape_line(raw);
         if include_prefix {
             line.spans.insert(0, "    ".into());
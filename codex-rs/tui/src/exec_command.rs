@@ -22,14 +22,16 @@ pub(crate) fn relativize_to_home<P>(path: P) -> Option<PathBuf>
 where
     P: AsRef<Path>,
 {
-    let path = path.as_ref();
+    // Compare without Windows verbatim prefixes (`\\?\C:\...`), which
+    // canonicalized paths carry but the home directory does not.
+    let path = dunce::simplified(path.as_ref());
     if !path.is_absolute() {
         // If the path is not absolute, we can’t do anything with it.
         return None;
     }
 
     let home_dir = home_dir()?;
-    let rel = path.strip_prefix(&home_dir).ok()?;
+    let rel = path.strip_prefix(dunce::simplified(&home_dir)).ok()?;
     Some(rel.to_path_buf())
 }
 
@@ -50,4 +52,12 @@ mod tests {
         let cmdline = strip_bash_lc_and_escape(&args);
         assert_eq!(cmdline, "echo hello");
     }
+
+    #[cfg(windows)]
+    #[test]
+    fn relativize_to_home_ignores_verbatim_prefix() {
+        let home = home_dir().expect("home dir");
+        let verbatim = PathBuf::from(format!(r"\\?\{}", home.join("project").display()));
+        assert_eq!(relativize_to_home(verbatim), Some(PathBuf::from("project")));
+    }
 }
@@ -10,22 +10,30 @@ use crate::markdown::MarkdownCitationContext;
 use crate::markdown::append_markdown;
 use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
+use crate::runbook::VerifyOutcome;
 use crate::statusline::format_elapsed_compact;
 use crate::style::user_message_style;
 use crate::terminal_palette::default_bg;
 use crate::text_formatting::format_and_truncate_tool_result;
 use crate::ui_consts::LIVE_PREFIX_COLS;
+use crate::version::CODEX_CLI_VERSION;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use crate::wrapping::word_wrap_lines;
 use base64::Engine;
+use codex_ansi_escape::ansi_escape_line;
 use codex_core::config::Config;
 use codex_core::config_types::McpServerTransportConfig;
 use codex_core::config_types::ReasoningSummaryFormat;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::SessionMetaLine;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::todos::TodoComment;
+use codex_core::todos::TodoGrouping;
+use codex_core::todos::group_todos;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -380,6 +388,7 @@ pub(crate) fn new_session_info(
         history_log_id: _,
         history_entry_count: _,
         initial_messages: _,
+        pinned_context: _,
         rollout_path: _,
     } = event;
     if is_first_event {
@@ -448,6 +457,23 @@ pub(crate) fn new_user_prompt(message: String) -> UserHistoryCell {
     UserHistoryCell { message }
 }
 
+/// A note the user added with `/note`, set apart from prompts.
+pub(crate) fn new_user_note(text: &str) -> PlainHistoryCell {
+    let lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 {
+                vec!["• ".dim(), "Note ".bold()]
+            } else {
+                vec!["       ".into()]
+            };
+            Line::from([prefix, vec![line.to_string().italic()]].concat())
+        })
+        .collect();
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_user_approval_decision(lines: Vec<Line<'static>>) -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
@@ -487,7 +513,7 @@ impl SessionHeaderHistoryCell {
                 format!("~{}{}", std::path::MAIN_SEPARATOR, rel.display())
             }
         } else {
-            directory.display().to_string()
+            dunce::simplified(directory).display().to_string()
         };
 
         if let Some(max_width) = max_width {
@@ -604,6 +630,9 @@ pub(crate) struct McpToolCallCell {
     start_time: Instant,
     duration: Option<Duration>,
     result: Option<Result<mcp_types::CallToolResult, String>>,
+    /// The result as printed by the `json` output filter, shown in place of
+    /// the raw content.
+    filtered_output: Option<String>,
 }
 
 impl McpToolCallCell {
@@ -614,9 +643,14 @@ impl McpToolCallCell {
             start_time: Instant::now(),
             duration: None,
             result: None,
+            filtered_output: None,
         }
     }
 
+    pub(crate) fn set_filtered_output(&mut self, filtered_output: Option<String>) {
+        self.filtered_output = filtered_output;
+    }
+
     pub(crate) fn call_id(&self) -> &str {
         &self.call_id
     }
@@ -708,7 +742,18 @@ impl HistoryCell for McpToolCallCell {
 
         let mut detail_lines: Vec<Line<'static>> = Vec::new();
 
-        if let Some(result) = &self.result {
+        if let (Some(Ok(_)), Some(filtered)) = (&self.result, &self.filtered_output) {
+            detail_lines.extend(
+                filtered
+                    .lines()
+                    .take(TOOL_CALL_MAX_LINES)
+                    .map(ansi_escape_line),
+            );
+            let omitted = filtered.lines().count().saturating_sub(TOOL_CALL_MAX_LINES);
+            if omitted > 0 {
+                detail_lines.push(format!("… +{omitted} lines").dim().into());
+            }
+        } else if let Some(result) = &self.result {
             match result {
                 Ok(mcp_types::CallToolResult { content, .. }) => {
                     if !content.is_empty() {
@@ -922,6 +967,134 @@ pub(crate) fn new_info_event(message: String, hint: Option<String>) -> PlainHist
     PlainHistoryCell { lines }
 }
 
+/// Environment recorded in a rollout header, shown when the session is
+/// resumed so differences from the original run are easy to spot.
+pub(crate) fn new_recorded_environment(meta_line: &SessionMetaLine) -> PlainHistoryCell {
+    let SessionMetaLine {
+        meta,
+        git,
+        environment,
+    } = meta_line;
+    let mut lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".dim(),
+            "Resuming session recorded ".into(),
+            meta.timestamp.clone().into(),
+        ]
+        .into(),
+    ];
+    let mut detail = |label: &str, value: String| {
+        lines.push(vec!["  ".into(), format!("{label}: ").dim(), value.into()].into());
+    };
+
+    if meta.cli_version == CODEX_CLI_VERSION {
+        detail("codex", meta.cli_version.clone());
+    } else {
+        detail(
+            "codex",
+            format!("{} (now {CODEX_CLI_VERSION})", meta.cli_version),
+        );
+    }
+    if let Some(git) = git {
+        let mut value = git
+            .commit_hash
+            .as_deref()
+            .map(|hash| hash.chars().take(7).collect::<String>())
+            .unwrap_or_else(|| "unknown commit".to_string());
+        if let Some(branch) = &git.branch {
+            value.push_str(&format!(" on {branch}"));
+        }
+        if let Some(dirty) = environment.as_ref().and_then(|env| env.git_dirty) {
+            value.push_str(if dirty {
+                " (uncommitted changes)"
+            } else {
+                " (clean)"
+            });
+        }
+        detail("git", value);
+    }
+    if let Some(environment) = environment {
+        detail("os", format!("{} ({})", environment.os, environment.arch));
+        if !environment.toolchains.is_empty() {
+            // `node --version` prints only the number, so name the program
+            // unless its output already does.
+            let toolchains: Vec<String> = environment
+                .toolchains
+                .iter()
+                .map(|(program, version)| {
+                    if version.starts_with(program.as_str()) {
+                        version.clone()
+                    } else {
+                        format!("{program} {version}")
+                    }
+                })
+                .collect();
+            detail("toolchains", toolchains.join(" · "));
+        }
+        if let Some(hash) = &environment.config_hash {
+            detail("config", hash.clone());
+        }
+        if !environment.project_env.is_empty() {
+            detail("project env", environment.project_env.join(", "));
+        }
+    }
+    PlainHistoryCell { lines }
+}
+
+/// Results of a runbook step's verification commands.
+pub(crate) fn new_runbook_verification(outcome: &VerifyOutcome) -> PlainHistoryCell {
+    let mut lines: Vec<Line<'static>> = vec![vec!["• ".dim(), "Verification".bold()].into()];
+    for command in &outcome.passed {
+        lines.push(vec!["  ✓ ".green(), command.clone().into()].into());
+    }
+    if let Some(failure) = &outcome.failure {
+        let status = failure
+            .exit_code
+            .map(|code| format!(" (exit {code})"))
+            .unwrap_or_default();
+        lines.push(vec!["  ✗ ".red(), failure.command.clone().into(), status.dim()].into());
+        for line in failure.output.lines() {
+            lines.push(format!("    {line}").dim().into());
+        }
+    }
+    PlainHistoryCell { lines }
+}
+
+/// Comments listed in the `/todos` output; the fix prompt still covers all.
+const TODOS_SHOWN: usize = 50;
+
+/// The `/todos` listing, grouped by file.
+pub(crate) fn new_todos_output(todos: &[TodoComment]) -> PlainHistoryCell {
+    let groups = group_todos(todos, TodoGrouping::File);
+    let mut lines: Vec<Line<'static>> = vec![
+        vec![
+            "• ".dim(),
+            "TODOs".bold(),
+            format!(" {} in {} files", todos.len(), groups.len()).dim(),
+        ]
+        .into(),
+    ];
+    let mut shown = 0;
+    for (path, group) in groups {
+        if shown == TODOS_SHOWN {
+            break;
+        }
+        lines.push(vec!["  ".into(), path.cyan()].into());
+        for todo in group.into_iter().take(TODOS_SHOWN - shown) {
+            shown += 1;
+            lines.push(vec![format!("    {:>4} ", todo.line).dim(), todo.label().into()].into());
+        }
+    }
+    if shown < todos.len() {
+        lines.push(
+            format!("    … and {} more", todos.len() - shown)
+                .dim()
+                .into(),
+        );
+    }
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     // Use a hair space (U+200A) to create a subtle, near-invisible separation
     // before the text. VS16 is intentionally omitted to keep spacing tighter
@@ -930,6 +1103,37 @@ pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     PlainHistoryCell { lines }
 }
 
+/// An error event, followed by what to do about errors of its kind.
+pub(crate) fn new_error_event_with_hint(message: String, kind: ErrorKind) -> PlainHistoryCell {
+    let mut cell = new_error_event(message);
+    if let Some(hint) = error_hint(kind) {
+        cell.lines.push(vec!["  ".into(), hint.dim()].into());
+    }
+    cell
+}
+
+fn error_hint(kind: ErrorKind) -> Option<&'static str> {
+    match kind {
+        ErrorKind::Auth => {
+            Some("Sign in again with `codex login`, or check the API key of your model provider.")
+        }
+        ErrorKind::Network => {
+            Some("Check your connection and proxy settings, then send your message again.")
+        }
+        ErrorKind::Provider => {
+            Some("The model provider turned the request down; try again later, or /model.")
+        }
+        ErrorKind::Sandbox => {
+            Some("The sandbox stopped a command; /approvals changes what Codex may do.")
+        }
+        ErrorKind::Patch => Some("Ask Codex to read the file again and redo the edit."),
+        ErrorKind::Config => {
+            Some("Check config.toml in your Codex home; see docs/config.md for the settings.")
+        }
+        ErrorKind::Internal => None,
+    }
+}
+
 pub(crate) fn new_stream_error_event(message: String) -> PlainHistoryCell {
     let lines: Vec<Line<'static>> = vec![vec![padded_emoji("⚠️").into(), message.dim()].into()];
     PlainHistoryCell { lines }
@@ -1024,9 +1228,9 @@ pub(crate) fn new_patch_apply_failure(stderr: String) -> PlainHistoryCell {
         lines.extend(output_lines(
             Some(&CommandOutput {
                 exit_code: 1,
-                stdout: String::new(),
-                stderr,
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: stderr.into(),
+                formatted_output: "".into(),
             }),
             OutputLinesParams {
                 only_err: true,
@@ -1238,6 +1442,38 @@ mod tests {
         insta::assert_snapshot!(rendered);
     }
 
+    #[test]
+    fn completed_mcp_tool_call_shows_filtered_output() {
+        let invocation = McpInvocation {
+            server: "search".into(),
+            tool: "find_docs".into(),
+            arguments: None,
+        };
+        let result = CallToolResult {
+            content: vec![ContentBlock::TextContent(TextContent {
+                annotations: None,
+                text: r#"{"hits":2}"#.into(),
+                r#type: "text".into(),
+            })],
+            is_error: None,
+            structured_content: None,
+        };
+
+        let mut cell = new_active_mcp_tool_call("call-filtered".into(), invocation);
+        cell.set_filtered_output(Some("{\n  \u{1b}[34m\"hits\"\u{1b}[0m: 2\n}\n".into()));
+        cell.complete(Duration::from_millis(10), Ok(result));
+
+        let rendered = render_lines(&cell.display_lines(80));
+        assert_eq!(
+            rendered[1..],
+            [
+                "  └ {".to_string(),
+                "      \"hits\": 2".to_string(),
+                "    }".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn completed_mcp_tool_call_error_snapshot() {
         let invocation = McpInvocation {
@@ -1449,9 +1685,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1480,9 +1716,9 @@ mod tests {
             "c1",
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1501,9 +1737,9 @@ mod tests {
             "c2",
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1522,9 +1758,9 @@ mod tests {
             "c3",
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1561,9 +1797,9 @@ mod tests {
             "c1",
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1590,9 +1826,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1619,9 +1855,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1647,9 +1883,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1674,9 +1910,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1702,9 +1938,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: "".into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1734,9 +1970,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 1,
-                stdout: String::new(),
-                stderr,
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: stderr.into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(1),
         );
@@ -1778,9 +2014,9 @@ mod tests {
             &call_id,
             CommandOutput {
                 exit_code: 1,
-                stdout: String::new(),
-                stderr,
-                formatted_output: String::new(),
+                stdout: "".into(),
+                stderr: stderr.into(),
+                formatted_output: "".into(),
             },
             Duration::from_millis(5),
         );
@@ -1963,4 +2199,51 @@ mod tests {
             vec!["thinking", "We should fix the bug next."]
         );
     }
+
+    #[test]
+    fn recorded_environment_lists_snapshot_details() {
+        use codex_core::protocol::EnvironmentSnapshot;
+        use codex_core::protocol::GitInfo;
+        use codex_core::protocol::SessionMeta;
+
+        let meta_line = SessionMetaLine {
+            meta: SessionMeta {
+                timestamp: "2025-01-02T03:04:05.000Z".to_string(),
+                cli_version: "0.0.1".to_string(),
+                ..Default::default()
+            },
+            git: Some(GitInfo {
+                commit_hash: Some("abcdef0123456789".to_string()),
+                branch: Some("main".to_string()),
+                repository_url: None,
+            }),
+            environment: Some(EnvironmentSnapshot {
+                os: "Ubuntu 24.04".to_string(),
+                arch: "x86_64".to_string(),
+                git_dirty: Some(true),
+                toolchains: [
+                    ("node".to_string(), "v20.11.0".to_string()),
+                    ("rustc".to_string(), "rustc 1.89.0".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+                config_hash: Some("0123456789ab".to_string()),
+                project_env: vec!["APP_PORT".to_string(), "DATABASE_URL".to_string()],
+            }),
+        };
+
+        let cell = new_recorded_environment(&meta_line);
+        assert_eq!(
+            render_transcript(&cell),
+            vec![
+                "• Resuming session recorded 2025-01-02T03:04:05.000Z".to_string(),
+                format!("  codex: 0.0.1 (now {CODEX_CLI_VERSION})"),
+                "  git: abcdef0 on main (uncommitted changes)".to_string(),
+                "  os: Ubuntu 24.04 (x86_64)".to_string(),
+                "  toolchains: node v20.11.0 · rustc 1.89.0".to_string(),
+                "  config: 0123456789ab".to_string(),
+                "  project env: APP_PORT, DATABASE_URL".to_string(),
+            ]
+        );
+    }
 }
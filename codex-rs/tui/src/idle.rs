@@ -0,0 +1,118 @@
+//! Idle detection for frame scheduling. With no task running and no input
+//! for `tui.idle_after_secs`, [`FrameRequester::schedule_frame_in`] stops
+//! scheduling the delayed frames that drive animations, the status line
+//! clock and countdowns. The next key press, paste, resize or focus change
+//! counts as activity and draws a frame right away, and that frame
+//! schedules the animations again.
+//!
+//! [`FrameRequester::schedule_frame_in`]: crate::tui::FrameRequester::schedule_frame_in
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::clock::Clock;
+
+#[derive(Debug)]
+pub(crate) struct IdleTracker {
+    clock: Arc<dyn Clock>,
+    state: Mutex<IdleState>,
+}
+
+#[derive(Debug)]
+struct IdleState {
+    /// `None` never goes idle.
+    idle_after: Option<Duration>,
+    last_activity: Instant,
+    task_running: bool,
+}
+
+impl IdleTracker {
+    /// A tracker that never goes idle until [`Self::set_idle_after`].
+    pub(crate) fn new(clock: Arc<dyn Clock>) -> Self {
+        let last_activity = clock.now();
+        Self {
+            clock,
+            state: Mutex::new(IdleState {
+                idle_after: None,
+                last_activity,
+                task_running: false,
+            }),
+        }
+    }
+
+    pub(crate) fn set_idle_after(&self, idle_after: Option<Duration>) {
+        self.lock().idle_after = idle_after;
+    }
+
+    /// Input arrived.
+    pub(crate) fn record_activity(&self) {
+        let now = self.clock.now();
+        self.lock().last_activity = now;
+    }
+
+    /// A task started or ended; the idle time counts from the end of the
+    /// last task.
+    pub(crate) fn set_task_running(&self, running: bool) {
+        let now = self.clock.now();
+        let mut state = self.lock();
+        state.task_running = running;
+        state.last_activity = now;
+    }
+
+    pub(crate) fn is_idle(&self) -> bool {
+        let now = self.clock.now();
+        let state = self.lock();
+        match state.idle_after {
+            Some(idle_after) if !state.task_running => {
+                now.saturating_duration_since(state.last_activity) >= idle_after
+            }
+            _ => false,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, IdleState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use chrono::Local;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn goes_idle_only_without_input_or_a_running_task() {
+        let clock = Arc::new(ManualClock::new(Local::now()));
+        let idle = IdleTracker::new(clock.clone());
+        clock.advance(secs(600));
+        assert!(!idle.is_idle(), "never idle until configured");
+
+        idle.set_idle_after(Some(secs(120)));
+        assert!(idle.is_idle());
+
+        idle.record_activity();
+        clock.advance(secs(119));
+        assert!(!idle.is_idle());
+        clock.advance(secs(1));
+        assert!(idle.is_idle());
+
+        idle.set_task_running(true);
+        clock.advance(secs(600));
+        assert!(!idle.is_idle(), "a running task keeps frames coming");
+
+        idle.set_task_running(false);
+        clock.advance(secs(60));
+        assert!(!idle.is_idle(), "idle time counts from the end of the task");
+        clock.advance(secs(60));
+        assert!(idle.is_idle());
+    }
+}
@@ -0,0 +1,339 @@
+//! Keyboard macros for the transcript overlay (`tui.macros`): `q<letter>`
+//! records the keys pressed until the next `q`, `@<letter>` replays them and
+//! `@@` replays the last macro again. Macros are kept per project (the git
+//! repository root, or the working directory outside one) in `macros.json`
+//! in the state home (see `codex_core::state_home`), with keys written as
+//! `]`, `enter` or `ctrl+t`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::git_info::get_git_repo_root;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+
+const MACROS_FILE: &str = "macros.json";
+
+/// Saved macros: project path, then macro name, then keys.
+type MacrosFile = BTreeMap<String, BTreeMap<char, Vec<String>>>;
+
+/// First key of a two-key macro command, waiting for the macro name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PendingMacro {
+    Record,
+    Replay,
+}
+
+/// What the overlay should do with a key after the macros have seen it.
+#[derive(Debug, PartialEq)]
+pub(crate) enum MacroKey {
+    /// Not a macro command; handle the key as usual.
+    Forward,
+    /// Consumed by a macro command; show the message, if any.
+    Consumed(Option<String>),
+    /// Replay these keys, then show the message.
+    Replay(Vec<KeyEvent>, String),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct KeyMacros {
+    macros: BTreeMap<char, Vec<KeyEvent>>,
+    recording: Option<(char, Vec<KeyEvent>)>,
+    pending: Option<PendingMacro>,
+    last_replayed: Option<char>,
+    /// Where the macros are saved and the project they belong to; `None`
+    /// keeps them for this session only.
+    store: Option<(PathBuf, String)>,
+}
+
+impl KeyMacros {
+    /// The macros saved for the project `cwd` belongs to.
+    pub(crate) fn load(state_home: &Path, cwd: &Path) -> Self {
+        let project = get_git_repo_root(cwd)
+            .unwrap_or_else(|| cwd.to_path_buf())
+            .to_string_lossy()
+            .into_owned();
+        let path = state_home.join(MACROS_FILE);
+        let macros = read_macros_file(&path)
+            .remove(&project)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, keys)| {
+                let keys = keys.iter().map(String::as_str).filter_map(parse_key);
+                (name, keys.collect())
+            })
+            .collect();
+        Self {
+            macros,
+            store: Some((path, project)),
+            ..Self::default()
+        }
+    }
+
+    /// The macro being recorded.
+    pub(crate) fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(name, _)| *name)
+    }
+
+    /// Drop a recording in progress, e.g. when the transcript closes.
+    pub(crate) fn cancel(&mut self) {
+        self.recording = None;
+        self.pending = None;
+    }
+
+    /// Add `key` to the macro being recorded, if any, without treating it
+    /// as a macro command.
+    pub(crate) fn record(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Release
+            && let Some((_, keys)) = &mut self.recording
+        {
+            keys.push(key);
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, key: KeyEvent) -> MacroKey {
+        if key.kind == KeyEventKind::Release {
+            return MacroKey::Forward;
+        }
+        if let Some(pending) = self.pending.take() {
+            return self.finish_command(pending, key);
+        }
+        let plain = !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('q') if plain => match self.recording.take() {
+                Some((name, keys)) => {
+                    let message = format!("recorded macro '{name}' ({} keys)", keys.len());
+                    self.macros.insert(name, keys);
+                    self.save();
+                    MacroKey::Consumed(Some(message))
+                }
+                None => {
+                    self.pending = Some(PendingMacro::Record);
+                    MacroKey::Consumed(Some("record: press a letter to name the macro".to_string()))
+                }
+            },
+            KeyCode::Char('@') if plain => {
+                self.pending = Some(PendingMacro::Replay);
+                MacroKey::Consumed(Some(if self.macros.is_empty() {
+                    "replay: no macros yet (press q and a letter to record one)".to_string()
+                } else {
+                    let names: String = self.macros.keys().collect();
+                    format!("replay macro: {names}")
+                }))
+            }
+            _ => {
+                self.record(key);
+                MacroKey::Forward
+            }
+        }
+    }
+
+    fn finish_command(&mut self, pending: PendingMacro, key: KeyEvent) -> MacroKey {
+        let name = match key.code {
+            KeyCode::Char(ch) if ch.is_ascii_alphanumeric() => ch,
+            KeyCode::Char('@') if pending == PendingMacro::Replay => match self.last_replayed {
+                Some(name) => name,
+                None => return MacroKey::Consumed(Some("no macro replayed yet".to_string())),
+            },
+            _ => return MacroKey::Consumed(None),
+        };
+        match pending {
+            PendingMacro::Record => {
+                self.recording = Some((name, Vec::new()));
+                MacroKey::Consumed(Some(format!("recording macro '{name}'; press q to stop")))
+            }
+            PendingMacro::Replay => {
+                let Some(keys) = self.macros.get(&name).cloned() else {
+                    return MacroKey::Consumed(Some(format!("no macro '{name}'")));
+                };
+                self.last_replayed = Some(name);
+                // Replaying while recording records the replayed keys.
+                if let Some((_, recorded)) = &mut self.recording {
+                    recorded.extend(keys.iter().copied());
+                }
+                MacroKey::Replay(keys, format!("replayed macro '{name}'"))
+            }
+        }
+    }
+
+    fn save(&self) {
+        let Some((path, project)) = &self.store else {
+            return;
+        };
+        let mut file = read_macros_file(path);
+        let macros = self
+            .macros
+            .iter()
+            .map(|(name, keys)| (*name, keys.iter().filter_map(format_key).collect()))
+            .collect();
+        file.insert(project.clone(), macros);
+        let result = serde_json::to_string_pretty(&file)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(path, json));
+        if let Err(err) = result {
+            tracing::warn!("failed to save macros to {}: {err}", path.display());
+        }
+    }
+}
+
+fn read_macros_file(path: &Path) -> MacrosFile {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return MacrosFile::new();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|err| {
+        tracing::warn!("ignoring unreadable macros in {}: {err}", path.display());
+        MacrosFile::new()
+    })
+}
+
+const MODIFIER_PREFIXES: [(&str, KeyModifiers); 3] = [
+    ("ctrl+", KeyModifiers::CONTROL),
+    ("alt+", KeyModifiers::ALT),
+    ("shift+", KeyModifiers::SHIFT),
+];
+
+const NAMED_KEYS: [(&str, KeyCode); 12] = [
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backspace", KeyCode::Backspace),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("pgup", KeyCode::PageUp),
+    ("pgdn", KeyCode::PageDown),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+];
+
+/// `key` as saved, e.g. `]`, `enter` or `ctrl+t`; `None` for keys a
+/// macro cannot hold.
+fn format_key(key: &KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(ch) => ch.to_string(),
+        code => NAMED_KEYS
+            .iter()
+            .find(|(_, named)| *named == code)
+            .map(|(name, _)| (*name).to_string())?,
+    };
+    let mut formatted = String::new();
+    for (prefix, modifier) in MODIFIER_PREFIXES {
+        if key.modifiers.contains(modifier) {
+            formatted.push_str(prefix);
+        }
+    }
+    formatted.push_str(&name);
+    Some(formatted)
+}
+
+fn parse_key(mut text: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    for (prefix, modifier) in MODIFIER_PREFIXES {
+        if let Some(rest) = text.strip_prefix(prefix)
+            && !rest.is_empty()
+        {
+            modifiers |= modifier;
+            text = rest;
+        }
+    }
+    let mut chars = text.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(ch), None) => KeyCode::Char(ch),
+        _ => NAMED_KEYS
+            .iter()
+            .find(|(name, _)| *name == text)
+            .map(|(_, code)| *code)?,
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn press(ch: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn keys_round_trip_through_their_saved_form() {
+        for key in [
+            press(']'),
+            KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+            KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL),
+            KeyEvent::new(KeyCode::PageDown, KeyModifiers::SHIFT),
+        ] {
+            let saved = format_key(&key).expect("saved");
+            assert_eq!(parse_key(&saved), Some(key), "{saved}");
+        }
+        assert_eq!(
+            format_key(&KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL)),
+            Some("ctrl+t".to_string())
+        );
+        assert_eq!(
+            format_key(&KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(parse_key("hyper+x"), None);
+    }
+
+    #[test]
+    fn recorded_keys_replay_and_persist_per_project() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let project = tempfile::tempdir().expect("tempdir");
+        let mut macros = KeyMacros::load(codex_home.path(), project.path());
+
+        assert!(matches!(
+            macros.handle_key(press('q')),
+            MacroKey::Consumed(_)
+        ));
+        assert!(matches!(
+            macros.handle_key(press('a')),
+            MacroKey::Consumed(_)
+        ));
+        assert_eq!(macros.recording(), Some('a'));
+        assert_eq!(macros.handle_key(press(']')), MacroKey::Forward);
+        assert_eq!(macros.handle_key(press('p')), MacroKey::Forward);
+        assert_eq!(
+            macros.handle_key(press('q')),
+            MacroKey::Consumed(Some("recorded macro 'a' (2 keys)".to_string()))
+        );
+        assert_eq!(macros.recording(), None);
+
+        macros.handle_key(press('@'));
+        assert_eq!(
+            macros.handle_key(press('a')),
+            MacroKey::Replay(
+                vec![press(']'), press('p')],
+                "replayed macro 'a'".to_string()
+            )
+        );
+        macros.handle_key(press('@'));
+        assert!(matches!(
+            macros.handle_key(press('@')),
+            MacroKey::Replay(..)
+        ));
+
+        let mut reloaded = KeyMacros::load(codex_home.path(), project.path());
+        reloaded.handle_key(press('@'));
+        assert!(matches!(
+            reloaded.handle_key(press('a')),
+            MacroKey::Replay(..)
+        ));
+
+        let other = tempfile::tempdir().expect("tempdir");
+        let mut elsewhere = KeyMacros::load(codex_home.path(), other.path());
+        elsewhere.handle_key(press('@'));
+        assert_eq!(
+            elsewhere.handle_key(press('a')),
+            MacroKey::Consumed(Some("no macro 'a'".to_string()))
+        );
+    }
+}
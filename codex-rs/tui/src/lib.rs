@@ -17,7 +17,9 @@ use codex_core::config::ConfigToml;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::find_conversation_path_by_id_str;
+use codex_core::project_env::load_project_env;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::ErrorKind;
 use codex_core::protocol::SandboxPolicy;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
@@ -34,13 +36,19 @@ mod app_backtrack;
 mod app_event;
 mod app_event_sender;
 mod ascii_animation;
+mod asciicast;
+#[doc(hidden)]
+pub mod bench_support;
 mod bottom_pane;
 mod chatwidget;
 mod citation_regex;
 mod cli;
+mod clipboard_copy;
 mod clipboard_paste;
+mod clock;
 mod color;
 pub mod custom_terminal;
+mod denied_actions;
 mod diff_render;
 mod exec_cell;
 mod exec_command;
@@ -48,17 +56,25 @@ mod file_search;
 mod frames;
 mod get_git_diff;
 mod history_cell;
+mod idle;
 pub mod insert_history;
 mod key_hint;
+mod key_macros;
 pub mod live_wrap;
+mod lock;
 mod markdown;
 mod markdown_render;
 mod markdown_stream;
 pub mod onboarding;
+mod output_filter;
 mod pager_overlay;
+mod perf_hud;
+mod pins;
 pub mod public_widgets;
 mod render;
+mod replay;
 mod resume_picker;
+mod runbook;
 mod session_log;
 mod shimmer;
 mod slash_command;
@@ -76,6 +92,8 @@ mod wrapping;
 #[cfg(test)]
 pub mod test_backend;
 
+use crate::onboarding::DevcontainerSelection;
+use crate::onboarding::ProjectEnvSelection;
 use crate::onboarding::TrustDirectorySelection;
 use crate::onboarding::WSL_INSTRUCTIONS;
 use crate::onboarding::onboarding_screen::OnboardingScreenArgs;
@@ -90,7 +108,7 @@ use std::io::Write as _;
 // (tests access modules directly within the crate)
 
 pub async fn run_main(
-    cli: Cli,
+    mut cli: Cli,
     codex_linux_sandbox_exe: Option<PathBuf>,
 ) -> std::io::Result<AppExitInfo> {
     let (sandbox_mode, approval_policy) = if cli.full_auto {
@@ -103,6 +121,32 @@ pub async fn run_main(
             Some(SandboxMode::DangerFullAccess),
             Some(AskForApproval::Never),
         )
+    } else if cli.bump.is_some() {
+        // Upgrades edit manifests and code and run the verify command in the
+        // workspace; fetching packages and changelogs goes through approval.
+        (
+            Some(
+                cli.sandbox_mode
+                    .map_or(SandboxMode::WorkspaceWrite, Into::<SandboxMode>::into),
+            ),
+            Some(
+                cli.approval_policy
+                    .map_or(AskForApproval::OnRequest, Into::into),
+            ),
+        )
+    } else if cli.resolve || cli.rebase {
+        // Keep the workspace read-only so that every resolved file, and the
+        // rebase itself, goes through approval.
+        (
+            Some(
+                cli.sandbox_mode
+                    .map_or(SandboxMode::ReadOnly, Into::<SandboxMode>::into),
+            ),
+            Some(
+                cli.approval_policy
+                    .map_or(AskForApproval::OnRequest, Into::into),
+            ),
+        )
     } else {
         (
             cli.sandbox_mode.map(Into::<SandboxMode>::into),
@@ -153,7 +197,7 @@ pub async fn run_main(
         #[allow(clippy::print_stderr)]
         Err(e) => {
             eprintln!("Error parsing -c overrides: {e}");
-            std::process::exit(1);
+            std::process::exit(ErrorKind::Config.exit_code());
         }
     };
 
@@ -165,7 +209,7 @@ pub async fn run_main(
             Ok(config) => config,
             Err(err) => {
                 eprintln!("Error loading configuration: {err}");
-                std::process::exit(1);
+                std::process::exit(ErrorKind::Config.exit_code());
             }
         }
     };
@@ -185,11 +229,41 @@ pub async fn run_main(
             Ok(config_toml) => config_toml,
             Err(err) => {
                 eprintln!("Error loading config.toml: {err}");
-                std::process::exit(1);
+                std::process::exit(ErrorKind::Config.exit_code());
             }
         }
     };
 
+    #[allow(clippy::print_stderr)]
+    let runbook = match cli
+        .runbook
+        .as_deref()
+        .map(runbook::Runbook::load)
+        .transpose()
+    {
+        Ok(runbook) => runbook,
+        Err(err) => {
+            eprintln!("Error loading runbook: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    if cli.fix_ci {
+        cli.prompt = Some(fix_ci_prompt(&config).await);
+    }
+
+    if cli.resolve {
+        cli.prompt = Some(resolve_prompt(&config).await);
+    }
+
+    if let Some(options) = cli.bump.as_ref() {
+        cli.prompt = Some(bump_prompt(&config, options).await);
+    }
+
+    if cli.rebase {
+        cli.prompt = Some(rebase_prompt(&config, cli.rebase_onto.as_deref()).await);
+    }
+
     let cli_profile_override = cli.config_profile.clone();
     let active_profile = cli_profile_override
         .clone()
@@ -268,14 +342,96 @@ pub async fn run_main(
         let _ = tracing_subscriber::registry().with(file_layer).try_init();
     };
 
-    run_ratatui_app(cli, config, active_profile, should_show_trust_screen)
+    run_ratatui_app(
+        cli,
+        config,
+        runbook,
+        active_profile,
+        should_show_trust_screen,
+    )
+    .await
+    .map_err(|err| std::io::Error::other(err.to_string()))
+}
+
+/// Initial prompt for `codex fix-ci`; exits when the CI logs cannot be loaded.
+#[allow(clippy::print_stderr)]
+async fn fix_ci_prompt(config: &Config) -> String {
+    eprintln!("Fetching the latest failed CI run…");
+    match codex_core::ci_failure::latest_failed_run(&config.issue_trackers.github, &config.cwd)
         .await
-        .map_err(|err| std::io::Error::other(err.to_string()))
+    {
+        Ok(run) => run.to_prompt(),
+        Err(err) => {
+            eprintln!("Error loading CI logs: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Initial prompt for `codex resolve`; exits when there is nothing to resolve.
+#[allow(clippy::print_stderr)]
+async fn resolve_prompt(config: &Config) -> String {
+    use codex_core::merge_conflicts::MergeConflictError;
+
+    match codex_core::merge_conflicts::conflicted_files(&config.cwd).await {
+        Ok(files) => codex_core::merge_conflicts::resolve_prompt(&files),
+        Err(MergeConflictError::NoConflicts) => {
+            eprintln!("No files with unresolved merge conflicts.");
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("Error finding merge conflicts: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Initial prompt for `codex rebase`; exits when there is nothing to rebase.
+#[allow(clippy::print_stderr)]
+async fn rebase_prompt(config: &Config, onto: Option<&str>) -> String {
+    use codex_core::rebase::RebaseError;
+
+    match codex_core::rebase::rebase_context(&config.cwd, onto).await {
+        Ok(context) => context.prompt(),
+        Err(err @ RebaseError::NothingToRebase(_)) => {
+            eprintln!("{err}.");
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("Error preparing the rebase: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Initial prompt for `codex bump`; exits when nothing is outdated.
+#[allow(clippy::print_stderr)]
+async fn bump_prompt(config: &Config, options: &codex_core::bump::BumpOptions) -> String {
+    use codex_core::bump::BumpError;
+
+    eprintln!("Looking for outdated dependencies…");
+    match codex_core::bump::bump_context(&config.cwd, options).await {
+        Ok(context) => {
+            for skipped in &context.skipped {
+                eprintln!("Skipped {skipped}");
+            }
+            context.prompt()
+        }
+        Err(err @ BumpError::NothingOutdated) => {
+            eprintln!("{err}.");
+            std::process::exit(0);
+        }
+        Err(err) => {
+            eprintln!("Error preparing the upgrades: {err}");
+            std::process::exit(1);
+        }
+    }
 }
 
 async fn run_ratatui_app(
     cli: Cli,
     config: Config,
+    runbook: Option<runbook::Runbook>,
     active_profile: Option<String>,
     should_show_trust_screen: bool,
 ) -> color_eyre::Result<AppExitInfo> {
@@ -292,6 +448,14 @@ async fn run_ratatui_app(
         prev_hook(info);
     }));
     let mut terminal = tui::init()?;
+    if let Some(path) = cli.record_path.as_deref() {
+        let size = terminal.size()?;
+        let recorder = asciicast::AsciicastRecorder::create(path, size.width, size.height)
+            .map_err(|err| {
+                color_eyre::eyre::eyre!("failed to create recording {}: {err}", path.display())
+            })?;
+        terminal.backend_mut().writer_mut().record(recorder);
+    }
     terminal.clear()?;
 
     let mut tui = Tui::new(terminal);
@@ -303,11 +467,22 @@ async fn run_ratatui_app(
     let login_status = get_login_status(&config);
     let should_show_windows_wsl_screen =
         cfg!(target_os = "windows") && !config.windows_wsl_setup_acknowledged;
+    let should_show_devcontainer_screen =
+        config.devcontainer.is_some() && config.use_devcontainer.is_none();
+    // Only ask about env files that define variables the allowlist admits.
+    let project_env_offer = match (&config.project_env, config.project_env_trusted) {
+        (Some(project_env), None) => {
+            let vars = load_project_env(project_env, &config.project_env_allow);
+            (!vars.is_empty()).then(|| vars.into_keys().collect::<Vec<_>>())
+        }
+        _ => None,
+    };
     let should_show_onboarding = should_show_onboarding(
         login_status,
         &config,
         should_show_trust_screen,
         should_show_windows_wsl_screen,
+        should_show_devcontainer_screen || project_env_offer.is_some(),
     );
     if should_show_onboarding {
         let onboarding_result = run_onboarding_app(
@@ -315,6 +490,8 @@ async fn run_ratatui_app(
                 show_windows_wsl_screen: should_show_windows_wsl_screen,
                 show_login_screen: should_show_login_screen(login_status, &config),
                 show_trust_screen: should_show_trust_screen,
+                show_devcontainer_screen: should_show_devcontainer_screen,
+                project_env_offer,
                 login_status,
                 auth_manager: auth_manager.clone(),
                 config: config.clone(),
@@ -341,11 +518,31 @@ async fn run_ratatui_app(
             config.approval_policy = AskForApproval::OnRequest;
             config.sandbox_policy = SandboxPolicy::new_workspace_write_policy();
         }
+        if let Some(decision) = onboarding_result.devcontainer_decision {
+            config.use_devcontainer = Some(decision == DevcontainerSelection::UseContainer);
+        }
+        if let Some(decision) = onboarding_result.project_env_decision {
+            config.project_env_trusted = Some(decision == ProjectEnvSelection::Load);
+            config.apply_project_env();
+        }
     }
 
-    // Determine resume behavior: explicit id, then resume last, then picker.
-    let resume_selection = if let Some(id_str) = cli.resume_session_id.as_deref() {
-        match find_conversation_path_by_id_str(&config.codex_home, id_str).await? {
+    // Determine resume behavior: replay, explicit id, then resume last, then picker.
+    let resume_selection = if let Some(id_str) = cli.replay_session_id.as_deref() {
+        let Some(path) = find_conversation_path_by_id_str(&config.state_home.path, id_str).await?
+        else {
+            restore();
+            session_log::log_session_end();
+            return Err(color_eyre::eyre::eyre!(
+                "No recorded session found with id {id_str}"
+            ));
+        };
+        resume_picker::ResumeSelection::Replay {
+            path,
+            speed: cli.replay_speed.unwrap_or(1.0),
+        }
+    } else if let Some(id_str) = cli.resume_session_id.as_deref() {
+        match find_conversation_path_by_id_str(&config.state_home.path, id_str).await? {
             Some(path) => resume_picker::ResumeSelection::Resume(path),
             None => {
                 error!("Error finding conversation path: {id_str}");
@@ -354,7 +551,7 @@ async fn run_ratatui_app(
         }
     } else if cli.resume_last {
         match RolloutRecorder::list_conversations(
-            &config.codex_home,
+            &config.state_home.path,
             1,
             None,
             INTERACTIVE_SESSION_SOURCES,
@@ -369,7 +566,7 @@ async fn run_ratatui_app(
             Err(_) => resume_picker::ResumeSelection::StartFresh,
         }
     } else if cli.resume_picker {
-        match resume_picker::run_resume_picker(&mut tui, &config.codex_home).await? {
+        match resume_picker::run_resume_picker(&mut tui, &config.state_home.path).await? {
             resume_picker::ResumeSelection::Exit => {
                 restore();
                 session_log::log_session_end();
@@ -394,6 +591,7 @@ async fn run_ratatui_app(
         prompt,
         images,
         resume_selection,
+        runbook,
     )
     .await;
 
@@ -404,6 +602,12 @@ async fn run_ratatui_app(
     app_result
 }
 
+/// The status line a session in `config` would start with, as JSON, for
+/// `codex debug statusline`. The probes run once instead of on intervals.
+pub async fn dump_status_line(config: &Config) -> serde_json::Result<String> {
+    chatwidget::headless_status_line(config).await.dump_json()
+}
+
 #[expect(
     clippy::print_stderr,
     reason = "TUI should no longer be displayed, so we can write to stderr."
@@ -481,12 +685,13 @@ fn should_show_onboarding(
     config: &Config,
     show_trust_screen: bool,
     show_windows_wsl_screen: bool,
+    show_project_screens: bool,
 ) -> bool {
     if show_windows_wsl_screen {
         return true;
     }
 
-    if show_trust_screen {
+    if show_trust_screen || show_project_screens {
         return true;
     }
 
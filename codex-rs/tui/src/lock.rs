@@ -0,0 +1,107 @@
+//! `tui.lock_after_secs`: after that long without input the session locks,
+//! hiding the transcript and composer behind a full-screen notice until a
+//! key is pressed, for sessions left open on shared machines.
+//!
+//! The lock screen is drawn on the alternate screen, so the scrollback with
+//! the transcript stays out of view. History inserted while locked is held
+//! back like it is for the pager overlays.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Constraint;
+use ratatui::layout::Flex;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+
+#[derive(Debug)]
+pub(crate) struct SessionLock {
+    /// `None` never locks.
+    lock_after: Option<Duration>,
+    last_input: Instant,
+    locked: bool,
+}
+
+impl SessionLock {
+    pub(crate) fn new(lock_after: Option<Duration>, now: Instant) -> Self {
+        Self {
+            lock_after,
+            last_input: now,
+            locked: false,
+        }
+    }
+
+    /// A key press or paste arrived while unlocked.
+    pub(crate) fn record_input(&mut self, now: Instant) {
+        self.last_input = now;
+    }
+
+    /// When the session locks unless there is input before; `None` while
+    /// locked or when locking is off.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        if self.locked {
+            return None;
+        }
+        self.lock_after.map(|after| self.last_input + after)
+    }
+
+    pub(crate) fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub(crate) fn lock(&mut self) {
+        self.locked = true;
+    }
+
+    /// Unlocking counts as input, so the lock timer starts over.
+    pub(crate) fn unlock(&mut self, now: Instant) {
+        self.locked = false;
+        self.last_input = now;
+    }
+}
+
+/// The notice shown instead of the session while locked.
+pub(crate) fn render_lock_screen(area: Rect, buf: &mut Buffer) {
+    let lines = vec![
+        Line::from("Session locked".bold()),
+        Line::from(""),
+        Line::from("Press any key to resume".dim()),
+    ];
+    let [notice] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+        .flex(Flex::Center)
+        .areas(area);
+    Paragraph::new(lines).centered().render(notice, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn locks_only_after_the_configured_time_without_input() {
+        let start = Instant::now();
+        let secs = Duration::from_secs;
+
+        let never = SessionLock::new(None, start);
+        assert_eq!(never.deadline(), None);
+
+        let mut lock = SessionLock::new(Some(secs(300)), start);
+        assert_eq!(lock.deadline(), Some(start + secs(300)));
+        lock.record_input(start + secs(100));
+        assert_eq!(lock.deadline(), Some(start + secs(400)));
+
+        lock.lock();
+        assert!(lock.is_locked());
+        assert_eq!(lock.deadline(), None, "no timer while locked");
+
+        lock.unlock(start + secs(1000));
+        assert!(!lock.is_locked());
+        assert_eq!(lock.deadline(), Some(start + secs(1300)));
+    }
+}
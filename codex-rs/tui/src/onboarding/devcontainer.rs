@@ -0,0 +1,176 @@
+use std::path::PathBuf;
+
+use codex_core::config::set_project_devcontainer;
+use codex_core::devcontainer::Devcontainer;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Color;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+
+use crate::onboarding::onboarding_screen::KeyboardHandler;
+use crate::onboarding::onboarding_screen::StepStateProvider;
+
+use super::onboarding_screen::StepState;
+
+pub(crate) struct DevcontainerWidget {
+    pub codex_home: PathBuf,
+    pub devcontainer: Devcontainer,
+    pub selection: Option<DevcontainerSelection>,
+    pub highlighted: DevcontainerSelection,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevcontainerSelection {
+    UseContainer,
+    UseHost,
+}
+
+impl DevcontainerWidget {
+    pub fn new(codex_home: PathBuf, devcontainer: Devcontainer) -> Self {
+        Self {
+            codex_home,
+            devcontainer,
+            selection: None,
+            highlighted: DevcontainerSelection::UseContainer,
+            error: None,
+        }
+    }
+
+    fn select(&mut self, selection: DevcontainerSelection) {
+        self.highlighted = selection;
+        let enabled = selection == DevcontainerSelection::UseContainer;
+        let project = &self.devcontainer.workspace_folder;
+        if let Err(err) = set_project_devcontainer(&self.codex_home, project, enabled) {
+            tracing::error!("Failed to persist devcontainer choice: {err:?}");
+            self.error = Some(format!(
+                "Failed to save this choice for {}: {err}",
+                project.display()
+            ));
+        }
+        self.selection = Some(selection);
+    }
+}
+
+impl WidgetRef for &DevcontainerWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines: Vec<Line> = vec![
+            Line::from(vec![
+                "> ".into(),
+                "This project has a devcontainer: ".bold(),
+                self.devcontainer
+                    .config_path
+                    .to_string_lossy()
+                    .to_string()
+                    .into(),
+            ]),
+            "".into(),
+            "  Codex can run its commands inside the container so they use the project's".into(),
+            "  toolchain. Edits are still made to the files on this machine.".into(),
+            "".into(),
+        ];
+
+        let create_option =
+            |idx: usize, option: DevcontainerSelection, text: &str| -> Line<'static> {
+                if self.highlighted == option {
+                    Line::from(format!("> {}. {text}", idx + 1)).cyan()
+                } else {
+                    Line::from(format!("  {}. {}", idx + 1, text))
+                }
+            };
+
+        lines.push(create_option(
+            0,
+            DevcontainerSelection::UseContainer,
+            "Yes, run commands in the devcontainer",
+        ));
+        lines.push(create_option(
+            1,
+            DevcontainerSelection::UseHost,
+            "No, run commands on this machine",
+        ));
+        lines.push("".into());
+
+        if let Some(error) = &self.error {
+            lines.push(Line::from(format!("  {error}")).fg(Color::Red));
+            lines.push("".into());
+        }
+
+        lines.push(Line::from(vec!["  Press Enter to continue".dim()]));
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+impl KeyboardHandler for DevcontainerWidget {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.highlighted = DevcontainerSelection::UseContainer;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.highlighted = DevcontainerSelection::UseHost;
+            }
+            KeyCode::Char('1') => self.select(DevcontainerSelection::UseContainer),
+            KeyCode::Char('2') => self.select(DevcontainerSelection::UseHost),
+            KeyCode::Enter => self.select(self.highlighted),
+            _ => {}
+        }
+    }
+}
+
+impl StepStateProvider for DevcontainerWidget {
+    fn get_step_state(&self) -> StepState {
+        match self.selection {
+            Some(_) => StepState::Complete,
+            None => StepState::InProgress,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn choice_is_saved_for_the_project() {
+        let codex_home = TempDir::new().expect("temp dir");
+        let mut widget = DevcontainerWidget::new(
+            codex_home.path().to_path_buf(),
+            Devcontainer {
+                workspace_folder: PathBuf::from("/work/app"),
+                config_path: PathBuf::from("/work/app/.devcontainer/devcontainer.json"),
+                container_workspace_folder: PathBuf::from("/workspaces/app"),
+            },
+        );
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(widget.selection, Some(DevcontainerSelection::UseHost));
+        assert_eq!(widget.get_step_state(), StepState::Complete);
+        let contents =
+            std::fs::read_to_string(codex_home.path().join("config.toml")).expect("config written");
+        assert!(
+            contents.contains("[projects.\"/work/app\"]\ndevcontainer = false\n"),
+            "unexpected config: {contents}"
+        );
+    }
+}
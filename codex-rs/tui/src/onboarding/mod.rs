@@ -1,5 +1,9 @@
 mod auth;
+mod devcontainer;
+pub use devcontainer::DevcontainerSelection;
 pub mod onboarding_screen;
+mod project_env;
+pub use project_env::ProjectEnvSelection;
 mod trust_directory;
 pub use trust_directory::TrustDirectorySelection;
 mod welcome;
@@ -16,6 +16,10 @@ use codex_app_server_protocol::AuthMode;
 use crate::LoginStatus;
 use crate::onboarding::auth::AuthModeWidget;
 use crate::onboarding::auth::SignInState;
+use crate::onboarding::devcontainer::DevcontainerSelection;
+use crate::onboarding::devcontainer::DevcontainerWidget;
+use crate::onboarding::project_env::ProjectEnvSelection;
+use crate::onboarding::project_env::ProjectEnvWidget;
 use crate::onboarding::trust_directory::TrustDirectorySelection;
 use crate::onboarding::trust_directory::TrustDirectoryWidget;
 use crate::onboarding::welcome::WelcomeWidget;
@@ -33,6 +37,8 @@ enum Step {
     Welcome(WelcomeWidget),
     Auth(AuthModeWidget),
     TrustDirectory(TrustDirectoryWidget),
+    Devcontainer(DevcontainerWidget),
+    ProjectEnv(ProjectEnvWidget),
 }
 
 pub(crate) trait KeyboardHandler {
@@ -62,6 +68,10 @@ pub(crate) struct OnboardingScreenArgs {
     pub show_windows_wsl_screen: bool,
     pub show_trust_screen: bool,
     pub show_login_screen: bool,
+    pub show_devcontainer_screen: bool,
+    /// Names of the variables to offer from the project's env files, when
+    /// the user has not decided on them yet.
+    pub project_env_offer: Option<Vec<String>>,
     pub login_status: LoginStatus,
     pub auth_manager: Arc<AuthManager>,
     pub config: Config,
@@ -70,6 +80,8 @@ pub(crate) struct OnboardingScreenArgs {
 pub(crate) struct OnboardingResult {
     pub directory_trust_decision: Option<TrustDirectorySelection>,
     pub windows_install_selected: bool,
+    pub devcontainer_decision: Option<DevcontainerSelection>,
+    pub project_env_decision: Option<ProjectEnvSelection>,
 }
 
 impl OnboardingScreen {
@@ -78,11 +90,15 @@ impl OnboardingScreen {
             show_windows_wsl_screen,
             show_trust_screen,
             show_login_screen,
+            show_devcontainer_screen,
+            project_env_offer,
             login_status,
             auth_manager,
             config,
         } = args;
         let cwd = config.cwd.clone();
+        let devcontainer = config.devcontainer.clone();
+        let project_env = config.project_env.clone();
         let codex_home = config.codex_home;
         let mut steps: Vec<Step> = Vec::new();
         if show_windows_wsl_screen {
@@ -113,13 +129,26 @@ impl OnboardingScreen {
         if show_trust_screen {
             steps.push(Step::TrustDirectory(TrustDirectoryWidget {
                 cwd,
-                codex_home,
+                codex_home: codex_home.clone(),
                 is_git_repo,
                 selection: None,
                 highlighted,
                 error: None,
             }))
         }
+        if show_devcontainer_screen && let Some(devcontainer) = devcontainer {
+            steps.push(Step::Devcontainer(DevcontainerWidget::new(
+                codex_home.clone(),
+                devcontainer,
+            )));
+        }
+        if let (Some(vars), Some(project_env)) = (project_env_offer, project_env) {
+            steps.push(Step::ProjectEnv(ProjectEnvWidget::new(
+                codex_home,
+                project_env,
+                vars,
+            )));
+        }
         // TODO: add git warning.
         Self {
             request_frame: tui.frame_requester(),
@@ -180,6 +209,20 @@ impl OnboardingScreen {
             .flatten()
     }
 
+    pub fn devcontainer_decision(&self) -> Option<DevcontainerSelection> {
+        self.steps.iter().find_map(|step| match step {
+            Step::Devcontainer(widget) => widget.selection,
+            _ => None,
+        })
+    }
+
+    pub fn project_env_decision(&self) -> Option<ProjectEnvSelection> {
+        self.steps.iter().find_map(|step| match step {
+            Step::ProjectEnv(widget) => widget.selection,
+            _ => None,
+        })
+    }
+
     pub fn windows_install_selected(&self) -> bool {
         self.windows_install_selected
     }
@@ -313,6 +356,8 @@ impl KeyboardHandler for Step {
             Step::Welcome(widget) => widget.handle_key_event(key_event),
             Step::Auth(widget) => widget.handle_key_event(key_event),
             Step::TrustDirectory(widget) => widget.handle_key_event(key_event),
+            Step::Devcontainer(widget) => widget.handle_key_event(key_event),
+            Step::ProjectEnv(widget) => widget.handle_key_event(key_event),
         }
     }
 
@@ -322,6 +367,8 @@ impl KeyboardHandler for Step {
             Step::Welcome(_) => {}
             Step::Auth(widget) => widget.handle_paste(pasted),
             Step::TrustDirectory(widget) => widget.handle_paste(pasted),
+            Step::Devcontainer(_) => {}
+            Step::ProjectEnv(_) => {}
         }
     }
 }
@@ -333,6 +380,8 @@ impl StepStateProvider for Step {
             Step::Welcome(w) => w.get_step_state(),
             Step::Auth(w) => w.get_step_state(),
             Step::TrustDirectory(w) => w.get_step_state(),
+            Step::Devcontainer(w) => w.get_step_state(),
+            Step::ProjectEnv(w) => w.get_step_state(),
         }
     }
 }
@@ -352,6 +401,12 @@ impl WidgetRef for Step {
             Step::TrustDirectory(widget) => {
                 widget.render_ref(area, buf);
             }
+            Step::Devcontainer(widget) => {
+                widget.render_ref(area, buf);
+            }
+            Step::ProjectEnv(widget) => {
+                widget.render_ref(area, buf);
+            }
         }
     }
 }
@@ -382,6 +437,7 @@ pub(crate) async fn run_onboarding_app(
                 TuiEvent::Paste(text) => {
                     onboarding_screen.handle_paste(text);
                 }
+                TuiEvent::Mouse(_) => {}
                 TuiEvent::Draw => {
                     if !did_full_clear_after_success
                         && onboarding_screen.steps.iter().any(|step| {
@@ -423,5 +479,7 @@ pub(crate) async fn run_onboarding_app(
     Ok(OnboardingResult {
         directory_trust_decision: onboarding_screen.directory_trust_decision(),
         windows_install_selected: onboarding_screen.windows_install_selected(),
+        devcontainer_decision: onboarding_screen.devcontainer_decision(),
+        project_env_decision: onboarding_screen.project_env_decision(),
     })
 }
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use codex_core::config::set_project_env_trust;
+use codex_core::project_env::ProjectEnv;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::prelude::Widget;
+use ratatui::style::Color;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+
+use crate::onboarding::onboarding_screen::KeyboardHandler;
+use crate::onboarding::onboarding_screen::StepStateProvider;
+
+use super::onboarding_screen::StepState;
+
+pub(crate) struct ProjectEnvWidget {
+    pub codex_home: PathBuf,
+    pub project_env: ProjectEnv,
+    /// Names of the allowed variables the files define.
+    pub vars: Vec<String>,
+    pub selection: Option<ProjectEnvSelection>,
+    pub highlighted: ProjectEnvSelection,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectEnvSelection {
+    Load,
+    Ignore,
+}
+
+impl ProjectEnvWidget {
+    pub fn new(codex_home: PathBuf, project_env: ProjectEnv, vars: Vec<String>) -> Self {
+        Self {
+            codex_home,
+            project_env,
+            vars,
+            selection: None,
+            highlighted: ProjectEnvSelection::Ignore,
+            error: None,
+        }
+    }
+
+    fn select(&mut self, selection: ProjectEnvSelection) {
+        self.highlighted = selection;
+        let trusted = selection == ProjectEnvSelection::Load;
+        if let Err(err) = set_project_env_trust(&self.codex_home, &self.project_env, trusted) {
+            tracing::error!("Failed to persist project env trust: {err:?}");
+            self.error = Some(format!(
+                "Failed to save this choice for {}: {err}",
+                self.project_env.root.display()
+            ));
+        }
+        self.selection = Some(selection);
+    }
+}
+
+impl WidgetRef for &ProjectEnvWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let files: Vec<String> = self
+            .project_env
+            .files
+            .iter()
+            .map(|file| file.to_string_lossy().to_string())
+            .collect();
+        let mut lines: Vec<Line> = vec![
+            Line::from(vec![
+                "> ".into(),
+                "Load environment from ".bold(),
+                files.join(" and ").into(),
+                "?".bold(),
+            ]),
+            "".into(),
+            "  These variables would be added to the environment of Codex's commands:".into(),
+            Line::from(format!("  {}", self.vars.join(", "))).cyan(),
+            "".into(),
+        ];
+
+        let create_option =
+            |idx: usize, option: ProjectEnvSelection, text: &str| -> Line<'static> {
+                if self.highlighted == option {
+                    Line::from(format!("> {}. {text}", idx + 1)).cyan()
+                } else {
+                    Line::from(format!("  {}. {}", idx + 1, text))
+                }
+            };
+
+        lines.push(create_option(
+            0,
+            ProjectEnvSelection::Load,
+            "Yes, I trust these files",
+        ));
+        lines.push(create_option(
+            1,
+            ProjectEnvSelection::Ignore,
+            "No, ignore them until they change",
+        ));
+        lines.push("".into());
+
+        if let Some(error) = &self.error {
+            lines.push(Line::from(format!("  {error}")).fg(Color::Red));
+            lines.push("".into());
+        }
+
+        lines.push(Line::from(vec!["  Press Enter to continue".dim()]));
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+impl KeyboardHandler for ProjectEnvWidget {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.highlighted = ProjectEnvSelection::Load;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.highlighted = ProjectEnvSelection::Ignore;
+            }
+            KeyCode::Char('1') => self.select(ProjectEnvSelection::Load),
+            KeyCode::Char('2') => self.select(ProjectEnvSelection::Ignore),
+            KeyCode::Enter => self.select(self.highlighted),
+            _ => {}
+        }
+    }
+}
+
+impl StepStateProvider for ProjectEnvWidget {
+    fn get_step_state(&self) -> StepState {
+        match self.selection {
+            Some(_) => StepState::Complete,
+            None => StepState::InProgress,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn trust_is_saved_with_the_files_digest() {
+        let codex_home = TempDir::new().expect("temp dir");
+        let mut widget = ProjectEnvWidget::new(
+            codex_home.path().to_path_buf(),
+            ProjectEnv {
+                root: PathBuf::from("/work/app"),
+                files: vec![PathBuf::from("/work/app/.env")],
+                digest: "abc123".to_string(),
+            },
+            vec!["APP_PORT".to_string()],
+        );
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE));
+
+        assert_eq!(widget.selection, Some(ProjectEnvSelection::Load));
+        let contents =
+            std::fs::read_to_string(codex_home.path().join("config.toml")).expect("config written");
+        assert!(
+            contents.contains(
+                "[projects.\"/work/app\"]\nenv_trust = { digest = \"abc123\", trusted = true }\n"
+            ),
+            "unexpected config: {contents}"
+        );
+    }
+}
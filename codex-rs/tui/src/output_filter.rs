@@ -0,0 +1,182 @@
+//! Output filters from `[tui.output_filters]`: local commands that diffs and
+//! JSON tool results are piped through before they are shown, e.g. `delta`
+//! or `jq -C .`. Results are cached, so redrawing the transcript does not run
+//! the command again.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::io::Read;
+use std::io::Write;
+use std::process::Child;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use codex_ansi_escape::ansi_escape;
+
+/// How long a filter may run before the output is shown unfiltered.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Filtered outputs kept; the cache starts over once it is full.
+const MAX_CACHED_OUTPUTS: usize = 128;
+
+fn cache() -> &'static Mutex<HashMap<u64, Option<String>>> {
+    static CACHE: OnceLock<Mutex<HashMap<u64, Option<String>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// `input` piped through `command`, or `None` when the command fails, prints
+/// nothing or does not finish in time. Blocks for up to [`FILTER_TIMEOUT`].
+pub(crate) fn apply(command: &[String], input: &str) -> Option<String> {
+    let mut hasher = DefaultHasher::new();
+    (command, input).hash(&mut hasher);
+    let key = hasher.finish();
+    if let Some(cached) = cache().lock().ok()?.get(&key) {
+        return cached.clone();
+    }
+
+    let output = run_with_timeout(command, input);
+    if let Ok(mut outputs) = cache().lock() {
+        if outputs.len() >= MAX_CACHED_OUTPUTS {
+            outputs.clear();
+        }
+        outputs.insert(key, output.clone());
+    }
+    output
+}
+
+/// Whether a tool result is JSON, so the `json` filter applies to it.
+pub(crate) fn is_json(text: &str) -> bool {
+    let trimmed = text.trim_start();
+    (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(text).is_ok()
+}
+
+/// `text` without its ANSI colors, for filters that expect plain input.
+pub(crate) fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    for line in ansi_escape(text).lines {
+        for span in line.spans {
+            plain.push_str(&span.content);
+        }
+        plain.push('\n');
+    }
+    plain
+}
+
+/// Runs `command` on `input`, killing it if it has not printed everything
+/// within [`FILTER_TIMEOUT`].
+fn run_with_timeout(command: &[String], input: &str) -> Option<String> {
+    let (program, args) = command.split_first()?;
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| tracing::warn!("failed to run output filter {program}: {err}"))
+        .ok()?;
+    // Written from another thread so a filter printing before it has read
+    // all of a large input cannot block on a full pipe.
+    if let Some(mut stdin) = child.stdin.take() {
+        let input = input.to_string();
+        std::thread::spawn(move || stdin.write_all(input.as_bytes()).ok());
+    }
+    let mut stdout = child.stdout.take()?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        tx.send(stdout.read_to_end(&mut bytes).map(|_| bytes)).ok();
+    });
+
+    let stdout = match rx.recv_timeout(FILTER_TIMEOUT) {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(err)) => {
+            tracing::warn!("failed to read output filter {program}: {err}");
+            kill(&mut child);
+            return None;
+        }
+        Err(_) => {
+            tracing::warn!("output filter did not finish within {FILTER_TIMEOUT:?}");
+            kill(&mut child);
+            return None;
+        }
+    };
+    let status = child.wait().ok()?;
+    if !status.success() {
+        tracing::warn!("output filter {program} failed: {status}");
+        return None;
+    }
+    let text = String::from_utf8_lossy(&stdout).into_owned();
+    (!text.trim().is_empty()).then_some(text)
+}
+
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn command(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn output_is_piped_through_the_command() {
+        let upper = command(&["tr", "a-z", "A-Z"]);
+        assert_eq!(apply(&upper, "diff --git"), Some("DIFF --GIT".to_string()));
+        // Served from the cache the second time.
+        assert_eq!(apply(&upper, "diff --git"), Some("DIFF --GIT".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_filters_leave_the_output_alone() {
+        assert_eq!(apply(&command(&["false"]), "{}"), None);
+        assert_eq!(apply(&command(&["codex-no-such-filter"]), "{}"), None);
+        assert_eq!(apply(&[], "{}"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn filters_that_time_out_are_killed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let pid_file = dir.path().join("pid");
+        let script = format!("echo $$ > '{}'; exec sleep 30", pid_file.display());
+        assert_eq!(apply(&command(&["sh", "-c", &script]), "{}"), None);
+
+        let pid = std::fs::read_to_string(&pid_file).expect("pid");
+        let alive = Command::new("kill")
+            .args(["-0", pid.trim()])
+            .stderr(Stdio::null())
+            .status()
+            .expect("kill -0");
+        assert!(!alive.success());
+    }
+
+    #[test]
+    fn colors_are_stripped_from_filter_input() {
+        assert_eq!(
+            strip_ansi("\u{1b}[32m+added\u{1b}[0m\n-removed"),
+            "+added\n-removed\n"
+        );
+    }
+
+    #[test]
+    fn only_objects_and_arrays_count_as_json() {
+        assert!(is_json(r#"{"ok": true}"#));
+        assert!(is_json("[1, 2]"));
+        assert!(!is_json("42"));
+        assert!(!is_json("{not json"));
+    }
+}
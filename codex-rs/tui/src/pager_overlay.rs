@@ -1,8 +1,10 @@
+use std::collections::BTreeMap;
 use std::io::Result;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::history_cell::HistoryCell;
+use crate::history_cell::UserHistoryCell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
 use crate::render::renderable::Renderable;
@@ -10,6 +12,7 @@ use crate::tui;
 use crate::tui::TuiEvent;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
 use ratatui::buffer::Buffer;
 use ratatui::buffer::Cell;
 use ratatui::layout::Rect;
@@ -26,11 +29,29 @@ use ratatui::widgets::Wrap;
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
     Static(StaticOverlay),
+    Pins(StaticOverlay),
 }
 
 impl Overlay {
-    pub(crate) fn new_transcript(cells: Vec<Arc<dyn HistoryCell>>) -> Self {
-        Self::Transcript(TranscriptOverlay::new(cells))
+    pub(crate) fn new_transcript(
+        cells: Vec<Arc<dyn HistoryCell>>,
+        pinned: Vec<usize>,
+        bookmarks: BTreeMap<char, usize>,
+        macros: bool,
+    ) -> Self {
+        let mut overlay = TranscriptOverlay::new(cells);
+        if !pinned.is_empty() {
+            overlay.set_pinned_cells(pinned);
+        }
+        overlay.bookmarks = bookmarks;
+        overlay.macros = macros;
+        Self::Transcript(overlay)
+    }
+
+    pub(crate) fn new_pins(lines: Vec<Line<'static>>) -> Self {
+        let mut overlay = StaticOverlay::with_title(lines, "P I N S".to_string());
+        overlay.hints.push((&[KEY_E], "to export"));
+        Self::Pins(overlay)
     }
 
     pub(crate) fn new_static_with_lines(lines: Vec<Line<'static>>, title: String) -> Self {
@@ -47,14 +68,14 @@ impl Overlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
-            Overlay::Static(o) => o.handle_event(tui, event),
+            Overlay::Static(o) | Overlay::Pins(o) => o.handle_event(tui, event),
         }
     }
 
     pub(crate) fn is_done(&self) -> bool {
         match self {
             Overlay::Transcript(o) => o.is_done(),
-            Overlay::Static(o) => o.is_done(),
+            Overlay::Static(o) | Overlay::Pins(o) => o.is_done(),
         }
     }
 }
@@ -67,6 +88,12 @@ const KEY_SPACE: KeyBinding = key_hint::plain(KeyCode::Char(' '));
 const KEY_HOME: KeyBinding = key_hint::plain(KeyCode::Home);
 const KEY_END: KeyBinding = key_hint::plain(KeyCode::End);
 const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
+const KEY_E: KeyBinding = key_hint::plain(KeyCode::Char('e'));
+const KEY_M: KeyBinding = key_hint::plain(KeyCode::Char('m'));
+const KEY_F: KeyBinding = key_hint::plain(KeyCode::Char('f'));
+const KEY_APOSTROPHE: KeyBinding = key_hint::plain(KeyCode::Char('\''));
+const KEY_PREV_TURN: KeyBinding = key_hint::plain(KeyCode::Char('['));
+const KEY_NEXT_TURN: KeyBinding = key_hint::plain(KeyCode::Char(']'));
 const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
 const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
 const KEY_CTRL_T: KeyBinding = key_hint::ctrl(KeyCode::Char('t'));
@@ -106,9 +133,14 @@ struct PagerView {
     scroll_offset: usize,
     title: String,
     last_content_height: Option<usize>,
+    last_content_width: Option<u16>,
     last_rendered_height: Option<usize>,
     /// If set, on next render ensure this chunk is visible.
     pending_scroll_chunk: Option<usize>,
+    /// If set, on next render scroll so this chunk starts at the top.
+    pending_top_chunk: Option<usize>,
+    /// Short state label drawn at the left of the bottom bar.
+    indicator: Option<&'static str>,
 }
 
 impl PagerView {
@@ -118,8 +150,11 @@ impl PagerView {
             scroll_offset,
             title,
             last_content_height: None,
+            last_content_width: None,
             last_rendered_height: None,
             pending_scroll_chunk: None,
+            pending_top_chunk: None,
+            indicator: None,
         }
     }
 
@@ -135,6 +170,7 @@ impl PagerView {
         self.render_header(area, buf);
         let content_area = self.content_area(area);
         self.update_last_content_height(content_area.height);
+        self.last_content_width = Some(content_area.width);
         let content_height = self.content_height(content_area.width);
         self.last_rendered_height = Some(content_height);
         // If there is a pending request to scroll a specific chunk into view,
@@ -142,6 +178,14 @@ impl PagerView {
         if let Some(idx) = self.pending_scroll_chunk.take() {
             self.ensure_chunk_visible(idx, content_area);
         }
+        if let Some(idx) = self.pending_top_chunk.take() {
+            self.scroll_offset = self
+                .renderables
+                .iter()
+                .take(idx)
+                .map(|r| r.desired_height(content_area.width) as usize)
+                .sum();
+        }
         self.scroll_offset = self
             .scroll_offset
             .min(content_height.saturating_sub(content_area.height as usize));
@@ -219,6 +263,13 @@ impl PagerView {
                     as u8
             }
         };
+        if let Some(indicator) = self.indicator {
+            let label = format!(" {indicator} ");
+            let label_w = (label.chars().count() as u16).min(sep_rect.width.saturating_sub(1));
+            Span::from(label)
+                .cyan()
+                .render_ref(Rect::new(sep_rect.x + 1, sep_rect.y, label_w, 1), buf);
+        }
         let pct_text = format!(" {percent}% ");
         let pct_w = pct_text.chars().count() as u16;
         let pct_x = sep_rect.x + sep_rect.width - pct_w - 1;
@@ -291,6 +342,29 @@ impl PagerView {
         self.scroll_offset >= max_scroll
     }
 
+    /// Index of the chunk shown on the first visible row, based on the most
+    /// recent render.
+    fn chunk_at_top(&self) -> Option<usize> {
+        let width = self.last_content_width?;
+        let max_scroll = self
+            .last_rendered_height?
+            .saturating_sub(self.last_content_height.unwrap_or(0));
+        let offset = self.scroll_offset.min(max_scroll);
+        let mut bottom = 0usize;
+        for (idx, renderable) in self.renderables.iter().enumerate() {
+            bottom += renderable.desired_height(width) as usize;
+            if bottom > offset {
+                return Some(idx);
+            }
+        }
+        self.renderables.len().checked_sub(1)
+    }
+
+    /// Request that the given chunk start at the top of the view on next render.
+    fn scroll_chunk_to_top(&mut self, chunk_index: usize) {
+        self.pending_top_chunk = Some(chunk_index);
+    }
+
     /// Request that the given text chunk index be scrolled into view on next render.
     fn scroll_chunk_into_view(&mut self, chunk_index: usize) {
         self.pending_scroll_chunk = Some(chunk_index);
@@ -351,19 +425,46 @@ pub(crate) struct TranscriptOverlay {
     view: PagerView,
     cells: Vec<Arc<dyn HistoryCell>>,
     highlight_cell: Option<usize>,
+    pinned_cells: Vec<usize>,
+    /// Named positions set with `m<char>` and revisited with `'<char>`.
+    bookmarks: BTreeMap<char, usize>,
+    pending_mark: Option<MarkCommand>,
+    /// One-line feedback for bookmark and turn navigation.
+    status: Option<String>,
+    /// Whether new cells scroll the view to the live end of the transcript.
+    /// Scrolling away from the end pauses following; `f` or End resumes it.
+    follow: bool,
+    /// Whether `q` records keyboard macros (`tui.macros`) instead of quitting.
+    macros: bool,
+    /// The macro being recorded, see [`crate::key_macros`].
+    recording: Option<char>,
     is_done: bool,
 }
 
+/// First key of a two-key bookmark command, waiting for the mark name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MarkCommand {
+    Set,
+    Jump,
+}
+
 impl TranscriptOverlay {
     pub(crate) fn new(transcript_cells: Vec<Arc<dyn HistoryCell>>) -> Self {
         Self {
             view: PagerView::new(
-                Self::render_cells_to_texts(&transcript_cells, None),
+                Self::render_cells_to_texts(&transcript_cells, None, &[]),
                 "T R A N S C R I P T".to_string(),
                 usize::MAX,
             ),
             cells: transcript_cells,
             highlight_cell: None,
+            pinned_cells: Vec::new(),
+            bookmarks: BTreeMap::new(),
+            pending_mark: None,
+            status: None,
+            follow: true,
+            macros: false,
+            recording: None,
             is_done: false,
         }
     }
@@ -371,6 +472,7 @@ impl TranscriptOverlay {
     fn render_cells_to_texts(
         cells: &[Arc<dyn HistoryCell>],
         highlight_cell: Option<usize>,
+        pinned_cells: &[usize],
     ) -> Vec<Box<dyn Renderable>> {
         let mut texts: Vec<Box<dyn Renderable>> = Vec::new();
         let mut first = true;
@@ -379,6 +481,9 @@ impl TranscriptOverlay {
             if !cell.is_stream_continuation() && !first {
                 lines.push(Line::from(""));
             }
+            if pinned_cells.contains(&idx) {
+                lines.push("▌ pinned".cyan().into());
+            }
             let cell_lines = if Some(idx) == highlight_cell {
                 cell.transcript_lines()
                     .into_iter()
@@ -397,7 +502,7 @@ impl TranscriptOverlay {
     }
 
     pub(crate) fn insert_cell(&mut self, cell: Arc<dyn HistoryCell>) {
-        let follow_bottom = self.view.is_scrolled_to_bottom();
+        let follow_bottom = self.follow && self.view.is_scrolled_to_bottom();
         // Append as a new Text chunk (with a separating blank if needed)
         let mut lines: Vec<Line<'static>> = Vec::new();
         if !cell.is_stream_continuation() && !self.cells.is_empty() {
@@ -415,29 +520,186 @@ impl TranscriptOverlay {
 
     pub(crate) fn set_highlight_cell(&mut self, cell: Option<usize>) {
         self.highlight_cell = cell;
-        self.view.renderables = Self::render_cells_to_texts(&self.cells, self.highlight_cell);
+        self.rerender_cells();
         if let Some(idx) = self.highlight_cell {
             self.view.scroll_chunk_into_view(idx);
         }
     }
 
+    /// Mark which cells start a pinned entry.
+    pub(crate) fn set_pinned_cells(&mut self, pinned_cells: Vec<usize>) {
+        self.pinned_cells = pinned_cells;
+        self.rerender_cells();
+    }
+
+    /// The cell a pin toggle applies to: the highlighted cell when one is
+    /// selected, otherwise the cell at the top of the viewport.
+    pub(crate) fn focused_cell(&self) -> Option<usize> {
+        self.highlight_cell.or_else(|| self.view.chunk_at_top())
+    }
+
+    /// True while `m` or `'` waits for the mark name.
+    pub(crate) fn is_awaiting_mark_name(&self) -> bool {
+        self.pending_mark.is_some()
+    }
+
+    pub(crate) fn bookmarks(&self) -> &BTreeMap<char, usize> {
+        &self.bookmarks
+    }
+
+    /// Show macro feedback in the status line below the hints.
+    pub(crate) fn set_macro_status(&mut self, status: Option<String>, recording: Option<char>) {
+        self.status = status;
+        self.recording = recording;
+    }
+
+    /// Handle bookmark (`m`/`'`) and turn navigation (`[`/`]`) keys.
+    /// Returns whether the key was consumed.
+    fn handle_navigation_key(&mut self, key_event: KeyEvent) -> bool {
+        if key_event.kind == KeyEventKind::Release {
+            return false;
+        }
+        if let Some(command) = self.pending_mark.take() {
+            self.finish_mark_command(command, key_event);
+            return true;
+        }
+        match key_event {
+            e if KEY_M.is_press(e) => {
+                self.pending_mark = Some(MarkCommand::Set);
+                self.status = Some("mark: press a letter to name this position".to_string());
+            }
+            e if KEY_APOSTROPHE.is_press(e) => {
+                self.pending_mark = Some(MarkCommand::Jump);
+                self.status = Some(if self.bookmarks.is_empty() {
+                    "jump: no marks set yet (press m and a letter to add one)".to_string()
+                } else {
+                    let names: String = self.bookmarks.keys().collect();
+                    format!("jump to mark: {names}")
+                });
+            }
+            e if KEY_NEXT_TURN.is_press(e) => self.jump_to_turn(true),
+            e if KEY_PREV_TURN.is_press(e) => self.jump_to_turn(false),
+            e if KEY_F.is_press(e) => self.set_follow(!self.follow),
+            _ => return false,
+        }
+        true
+    }
+
+    fn finish_mark_command(&mut self, command: MarkCommand, key_event: KeyEvent) {
+        let name = match key_event.code {
+            KeyCode::Char(ch) if ch.is_ascii_alphanumeric() => ch,
+            _ => {
+                self.status = None;
+                return;
+            }
+        };
+        match command {
+            MarkCommand::Set => {
+                let Some(idx) = self.view.chunk_at_top() else {
+                    self.status = None;
+                    return;
+                };
+                self.bookmarks.insert(name, idx);
+                self.status = Some(format!("mark '{name}' set"));
+            }
+            MarkCommand::Jump => match self.bookmarks.get(&name) {
+                Some(&idx) if idx < self.cells.len() => {
+                    self.follow = false;
+                    self.view.scroll_chunk_to_top(idx);
+                    self.status = Some(format!("jumped to mark '{name}'"));
+                }
+                _ => self.status = Some(format!("no mark '{name}'")),
+            },
+        }
+    }
+
+    /// Scroll to the start of the next (or previous) turn. Every user message
+    /// begins a turn, so these anchors exist without any bookkeeping.
+    fn jump_to_turn(&mut self, forward: bool) {
+        let anchors: Vec<usize> = self
+            .cells
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cell)| cell.as_any().is::<UserHistoryCell>().then_some(idx))
+            .collect();
+        let current = self.view.chunk_at_top().unwrap_or(0);
+        let target = if forward {
+            anchors.iter().position(|&idx| idx > current)
+        } else {
+            anchors.iter().rposition(|&idx| idx < current)
+        };
+        self.status = Some(match target {
+            Some(turn) => {
+                self.follow = false;
+                self.view.scroll_chunk_to_top(anchors[turn]);
+                format!("turn {} of {}", turn + 1, anchors.len())
+            }
+            None if forward => "no later turn".to_string(),
+            None => "no earlier turn".to_string(),
+        });
+    }
+
+    fn set_follow(&mut self, follow: bool) {
+        self.follow = follow;
+        if follow {
+            self.view.scroll_offset = usize::MAX;
+            self.status = Some("following live output".to_string());
+        } else {
+            self.status = Some("paused; press f or end to follow live output".to_string());
+        }
+    }
+
+    /// Keep follow mode in sync after a plain scroll key: End resumes
+    /// following, and leaving the live end pauses it.
+    fn update_follow_after_scroll(&mut self, key_event: KeyEvent) {
+        if KEY_END.is_press(key_event) {
+            if !self.follow {
+                self.set_follow(true);
+            }
+        } else if self.follow && !self.view.is_scrolled_to_bottom() {
+            self.follow = false;
+        }
+    }
+
+    fn rerender_cells(&mut self) {
+        self.view.renderables =
+            Self::render_cells_to_texts(&self.cells, self.highlight_cell, &self.pinned_cells);
+    }
+
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
 
-        let mut pairs: Vec<(&[KeyBinding], &str)> =
-            vec![(&[KEY_Q], "to quit"), (&[KEY_ESC], "to edit prev")];
+        let mut pairs: Vec<(&[KeyBinding], &str)> = if self.macros {
+            vec![
+                (&[KEY_CTRL_T], "to quit"),
+                (&[KEY_Q], "to record"),
+                (&[KEY_ESC], "to edit prev"),
+            ]
+        } else {
+            vec![(&[KEY_Q], "to quit"), (&[KEY_ESC], "to edit prev")]
+        };
         if self.highlight_cell.is_some() {
             pairs.push((&[KEY_ENTER], "to edit message"));
         }
         render_key_hints(line2, buf, &pairs);
+
+        if let Some(status) = &self.status {
+            let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
+            Paragraph::new(Line::from(format!(" {status}")).dim()).render_ref(line3, buf);
+        }
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
         let top_h = area.height.saturating_sub(3);
         let top = Rect::new(area.x, area.y, area.width, top_h);
         let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        self.view.indicator = if self.recording.is_some() {
+            Some("recording")
+        } else {
+            (!self.follow).then_some("paused")
+        };
         self.view.render(top, buf);
         self.render_hints(bottom, buf);
     }
@@ -447,11 +709,19 @@ impl TranscriptOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
             TuiEvent::Key(key_event) => match key_event {
+                other if self.handle_navigation_key(other) => {
+                    tui.frame_requester().schedule_frame();
+                    Ok(())
+                }
                 e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) || KEY_CTRL_T.is_press(e) => {
                     self.is_done = true;
                     Ok(())
                 }
-                other => self.view.handle_key_event(tui, other),
+                other => {
+                    self.view.handle_key_event(tui, other)?;
+                    self.update_follow_after_scroll(other);
+                    Ok(())
+                }
             },
             TuiEvent::Draw => {
                 tui.draw(u16::MAX, |frame| {
@@ -469,6 +739,7 @@ impl TranscriptOverlay {
 
 pub(crate) struct StaticOverlay {
     view: PagerView,
+    hints: Vec<(&'static [KeyBinding], &'static str)>,
     is_done: bool,
 }
 
@@ -485,6 +756,7 @@ impl StaticOverlay {
     pub(crate) fn with_renderables(renderables: Vec<Box<dyn Renderable>>, title: String) -> Self {
         Self {
             view: PagerView::new(renderables, title, 0),
+            hints: vec![(&[KEY_Q], "to quit")],
             is_done: false,
         }
     }
@@ -493,8 +765,7 @@ impl StaticOverlay {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
-        let pairs: Vec<(&[KeyBinding], &str)> = vec![(&[KEY_Q], "to quit")];
-        render_key_hints(line2, buf, &pairs);
+        render_key_hints(line2, buf, &self.hints);
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
@@ -571,6 +842,8 @@ mod tests {
     use crate::history_cell::new_patch_event;
     use codex_core::protocol::FileChange;
     use codex_protocol::parse_command::ParsedCommand;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
     use ratatui::text::Text;
@@ -624,6 +897,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn macro_hints_and_recording_indicator() {
+        let mut overlay = TranscriptOverlay::new(vec![Arc::new(TestCell {
+            lines: vec![Line::from("hello")],
+        })]);
+        overlay.macros = true;
+        overlay.set_macro_status(Some("recording macro 'a'".to_string()), Some('a'));
+
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+
+        let mut s = String::new();
+        for y in area.y..area.bottom() {
+            for x in area.x..area.right() {
+                s.push(buf[(x, y)].symbol().chars().next().unwrap_or(' '));
+            }
+            s.push('\n');
+        }
+        assert!(s.contains("ctrl + t to quit"), "got: {s:?}");
+        assert!(s.contains("q to record"), "got: {s:?}");
+        assert!(s.contains("recording macro 'a'"), "got: {s:?}");
+        assert!(s.contains(" recording "), "got: {s:?}");
+    }
+
     #[test]
     fn transcript_overlay_snapshot_basic() {
         // Prepare a transcript overlay with a few lines
@@ -706,7 +1004,7 @@ mod tests {
             CommandOutput {
                 exit_code: 0,
                 stdout: "src\nREADME.md\n".into(),
-                stderr: String::new(),
+                stderr: "".into(),
                 formatted_output: "src\nREADME.md\n".into(),
             },
             Duration::from_millis(420),
@@ -726,6 +1024,61 @@ mod tests {
         assert_snapshot!("transcript_overlay_apply_patch_scroll_vt100", snapshot);
     }
 
+    #[test]
+    fn transcript_overlay_jumps_between_turns_and_marks() {
+        let user = |text: &str| {
+            Arc::new(UserHistoryCell {
+                message: text.to_string(),
+            }) as Arc<dyn HistoryCell>
+        };
+        let body = |label: &str| {
+            Arc::new(TestCell {
+                lines: (0..6).map(|i| Line::from(format!("{label} {i}"))).collect(),
+            }) as Arc<dyn HistoryCell>
+        };
+        let mut overlay = TranscriptOverlay::new(vec![
+            user("first"),
+            body("a"),
+            user("second"),
+            body("b"),
+            user("third"),
+            body("c"),
+        ]);
+        let area = Rect::new(0, 0, 40, 10);
+        let mut buf = Buffer::empty(area);
+        overlay.view.scroll_offset = 0;
+        overlay.render(area, &mut buf);
+
+        let mut press = |ch: char| {
+            let handled =
+                overlay.handle_navigation_key(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+            overlay.render(area, &mut buf);
+            (handled, overlay.view.chunk_at_top(), overlay.status.clone())
+        };
+
+        assert_eq!(press(']'), (true, Some(2), Some("turn 2 of 3".to_string())));
+        press('m');
+        assert_eq!(
+            press('a'),
+            (true, Some(2), Some("mark 'a' set".to_string()))
+        );
+        assert_eq!(press(']'), (true, Some(4), Some("turn 3 of 3".to_string())));
+        assert_eq!(
+            press(']'),
+            (true, Some(4), Some("no later turn".to_string()))
+        );
+        press('\'');
+        assert_eq!(
+            press('a'),
+            (true, Some(2), Some("jumped to mark 'a'".to_string()))
+        );
+        assert_eq!(press('['), (true, Some(0), Some("turn 1 of 3".to_string())));
+        assert_eq!(
+            press('x'),
+            (false, Some(0), Some("turn 1 of 3".to_string()))
+        );
+    }
+
     #[test]
     fn transcript_overlay_keeps_scroll_pinned_at_bottom() {
         let mut overlay = TranscriptOverlay::new(
@@ -753,6 +1106,42 @@ mod tests {
         assert_eq!(overlay.view.scroll_offset, usize::MAX);
     }
 
+    #[test]
+    fn transcript_overlay_follow_toggle_pauses_new_output() {
+        let mut overlay = TranscriptOverlay::new(
+            (0..20)
+                .map(|i| {
+                    Arc::new(TestCell {
+                        lines: vec![Line::from(format!("line{i}"))],
+                    }) as Arc<dyn HistoryCell>
+                })
+                .collect(),
+        );
+        let area = Rect::new(0, 0, 40, 12);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        let bottom_offset = overlay.view.scroll_offset;
+
+        assert!(
+            overlay.handle_navigation_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE))
+        );
+        overlay.insert_cell(Arc::new(TestCell {
+            lines: vec!["tail".into()],
+        }));
+        overlay.render(area, &mut buf);
+
+        assert_eq!(overlay.view.scroll_offset, bottom_offset);
+        assert!(buffer_to_text(&buf, area).contains("─ paused ─"));
+
+        overlay.update_follow_after_scroll(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        overlay.insert_cell(Arc::new(TestCell {
+            lines: vec!["tail 2".into()],
+        }));
+
+        assert!(overlay.follow);
+        assert_eq!(overlay.view.scroll_offset, usize::MAX);
+    }
+
     #[test]
     fn transcript_overlay_preserves_manual_scroll_position() {
         let mut overlay = TranscriptOverlay::new(
@@ -0,0 +1,159 @@
+//! `/debug perf`: a line above the chat showing how long frames take to
+//! draw, how often frames are drawn and requested, how many app events are
+//! waiting, and the process's memory, for diagnosing a TUI that feels slow.
+//!
+//! Rates and the queue depth are measured over one-second windows; frame
+//! times over the last [`FRAME_SAMPLES`] frames.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::system_info::format_bytes;
+use codex_core::system_info::process_memory;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+const FRAME_SAMPLES: usize = 120;
+const WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub(crate) struct PerfHud {
+    frame_times: VecDeque<Duration>,
+    window_started_at: Instant,
+    draws_in_window: u64,
+    scheduled_at_window_start: u64,
+    max_queue_in_window: usize,
+    refresh_requested: bool,
+    /// Figures of the last complete window.
+    draws_per_sec: f64,
+    scheduled_per_sec: f64,
+    max_queue_depth: usize,
+    memory: Option<u64>,
+}
+
+impl PerfHud {
+    pub(crate) fn new(frames_scheduled: u64, now: Instant) -> Self {
+        Self {
+            frame_times: VecDeque::with_capacity(FRAME_SAMPLES),
+            window_started_at: now,
+            draws_in_window: 0,
+            scheduled_at_window_start: frames_scheduled,
+            max_queue_in_window: 0,
+            refresh_requested: false,
+            draws_per_sec: 0.0,
+            scheduled_per_sec: 0.0,
+            max_queue_depth: 0,
+            memory: process_memory(),
+        }
+    }
+
+    /// An app event was taken off the queue with `queue_depth` still waiting.
+    pub(crate) fn record_event(&mut self, queue_depth: usize) {
+        self.max_queue_in_window = self.max_queue_in_window.max(queue_depth);
+    }
+
+    /// A frame took `took` to draw; `frames_scheduled` is the running count
+    /// of frame requests. Returns when to draw again so the figures keep
+    /// updating while nothing else redraws, at most once per window.
+    pub(crate) fn record_draw(
+        &mut self,
+        took: Duration,
+        frames_scheduled: u64,
+        now: Instant,
+    ) -> Option<Duration> {
+        if self.frame_times.len() == FRAME_SAMPLES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(took);
+        self.draws_in_window += 1;
+
+        let elapsed = now.saturating_duration_since(self.window_started_at);
+        if elapsed < WINDOW {
+            if self.refresh_requested {
+                return None;
+            }
+            self.refresh_requested = true;
+            return Some(WINDOW - elapsed);
+        }
+        let secs = elapsed.as_secs_f64();
+        self.draws_per_sec = self.draws_in_window as f64 / secs;
+        self.scheduled_per_sec =
+            frames_scheduled.saturating_sub(self.scheduled_at_window_start) as f64 / secs;
+        self.max_queue_depth = self.max_queue_in_window;
+        self.memory = process_memory();
+        self.window_started_at = now;
+        self.draws_in_window = 0;
+        self.scheduled_at_window_start = frames_scheduled;
+        self.max_queue_in_window = 0;
+        self.refresh_requested = true;
+        Some(WINDOW)
+    }
+
+    pub(crate) fn line(&self) -> Line<'static> {
+        let mut sorted: Vec<Duration> = self.frame_times.iter().copied().collect();
+        sorted.sort_unstable();
+        let last = self.frame_times.back().copied().unwrap_or_default();
+        let p95 = sorted
+            .get((sorted.len() * 95 / 100).min(sorted.len().saturating_sub(1)))
+            .copied()
+            .unwrap_or_default();
+        let max = sorted.last().copied().unwrap_or_default();
+        let memory = self.memory.map_or_else(|| "n/a".to_string(), format_bytes);
+        let stats = format!(
+            "frame {} (p95 {}, max {}) · {:.0} draws/s · {:.0} scheduled/s · queue {} · rss {memory}",
+            millis(last),
+            millis(p95),
+            millis(max),
+            self.draws_per_sec,
+            self.scheduled_per_sec,
+            self.max_queue_depth,
+        );
+        vec!["perf ".cyan().bold(), stats.dim()].into()
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn rates_cover_the_last_complete_window() {
+        let start = Instant::now();
+        let mut hud = PerfHud::new(10, start);
+        let mut refreshes = Vec::new();
+        for frame in 1..=20u64 {
+            hud.record_event(frame as usize % 4);
+            refreshes.extend(hud.record_draw(
+                Duration::from_millis(frame),
+                10 + frame * 3,
+                start + Duration::from_millis(frame * 50),
+            ));
+        }
+        assert_eq!(
+            refreshes,
+            vec![Duration::from_millis(950), Duration::from_secs(1)]
+        );
+
+        let line = text(&hud.line());
+        assert!(
+            line.contains("frame 20.0ms (p95 20.0ms, max 20.0ms)"),
+            "{line}"
+        );
+        assert!(
+            line.contains("20 draws/s · 60 scheduled/s · queue 3"),
+            "{line}"
+        );
+    }
+}
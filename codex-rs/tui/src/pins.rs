@@ -0,0 +1,308 @@
+//! Transcript entries the user pinned so they stay easy to find, survive
+//! compaction, and can be exported together as a summary document.
+//!
+//! An entry is a history cell plus any streamed continuation cells that
+//! follow it, so pinning an agent message always captures the whole message.
+//! Pins carried over from a resumed or forked session are only known by
+//! their text; they show on whichever replayed entry renders the same text.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use codex_protocol::ConversationId;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+
+use crate::history_cell::AgentMessageCell;
+use crate::history_cell::HistoryCell;
+
+#[derive(Debug)]
+pub(crate) struct PinnedEntry {
+    cells: Vec<Arc<dyn HistoryCell>>,
+}
+
+impl PinnedEntry {
+    fn head(&self) -> &Arc<dyn HistoryCell> {
+        &self.cells[0]
+    }
+
+    fn transcript_lines(&self) -> Vec<Line<'static>> {
+        self.cells
+            .iter()
+            .flat_map(|cell| cell.transcript_lines())
+            .collect()
+    }
+
+    /// Plain-text rendering of the entry, used for the model context and
+    /// the exported document.
+    pub(crate) fn plain_text(&self) -> String {
+        plain_text(&self.transcript_lines())
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Pins {
+    /// Text of pins restored from the rollout, oldest first.
+    restored: Vec<String>,
+    entries: Vec<PinnedEntry>,
+}
+
+impl Pins {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.restored.is_empty() && self.entries.is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.restored.len() + self.entries.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.restored.clear();
+        self.entries.clear();
+    }
+
+    /// Add the pins a resumed or forked session recorded, skipping any that
+    /// are already pinned here.
+    pub(crate) fn restore(&mut self, items: Vec<String>) {
+        let current = self.context_items();
+        for item in items {
+            if !current.contains(&item) && !self.restored.contains(&item) {
+                self.restored.push(item);
+            }
+        }
+    }
+
+    /// Pin the entry containing `cells[idx]`, or unpin it if it is already
+    /// pinned. Returns whether the entry is pinned afterwards.
+    pub(crate) fn toggle(&mut self, cells: &[Arc<dyn HistoryCell>], idx: usize) -> bool {
+        let range = entry_range(cells, idx);
+        let Some(head) = cells.get(range.start) else {
+            return false;
+        };
+        if let Some(pos) = self.position_of(head) {
+            self.entries.remove(pos);
+            return false;
+        }
+        let entry = PinnedEntry {
+            cells: cells[range].to_vec(),
+        };
+        if let Some(pos) = self.restored_position(&entry.plain_text()) {
+            self.restored.remove(pos);
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    /// Whether `cell` starts a pinned entry.
+    pub(crate) fn is_pinned(&self, cell: &Arc<dyn HistoryCell>) -> bool {
+        self.position_of(cell).is_some()
+    }
+
+    /// Drop pins whose cells are no longer part of the transcript, e.g.
+    /// after backtracking trimmed it.
+    pub(crate) fn retain_present(&mut self, cells: &[Arc<dyn HistoryCell>]) {
+        self.entries
+            .retain(|entry| cells.iter().any(|cell| Arc::ptr_eq(cell, entry.head())));
+    }
+
+    /// Indices in `cells` of every pinned entry's first cell, including
+    /// entries matching a restored pin.
+    pub(crate) fn pinned_indices(&self, cells: &[Arc<dyn HistoryCell>]) -> Vec<usize> {
+        cells
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, cell)| {
+                let pinned = self.is_pinned(cell)
+                    || (!self.restored.is_empty()
+                        && !cell.is_stream_continuation()
+                        && self.restored_position(&entry_text(cells, idx)).is_some());
+                pinned.then_some(idx)
+            })
+            .collect()
+    }
+
+    /// Pinned text forwarded to the agent so it is kept through compaction.
+    pub(crate) fn context_items(&self) -> Vec<String> {
+        self.restored
+            .iter()
+            .cloned()
+            .chain(self.entries.iter().map(PinnedEntry::plain_text))
+            .collect()
+    }
+
+    /// Lines for the `/pins` view.
+    pub(crate) fn overlay_lines(&self) -> Vec<Line<'static>> {
+        let restored = self.restored.iter().map(|text| {
+            text.lines()
+                .map(|line| Line::from(line.to_string()))
+                .collect::<Vec<_>>()
+        });
+        let pinned = restored.chain(self.entries.iter().map(PinnedEntry::transcript_lines));
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        for (idx, pin) in pinned.enumerate() {
+            if idx > 0 {
+                lines.push(Line::from(""));
+            }
+            lines.push(format!("pin {}", idx + 1).dim().into());
+            lines.extend(pin);
+        }
+        lines
+    }
+
+    /// Render all pins as a Markdown summary document.
+    pub(crate) fn to_markdown(&self, conversation_id: Option<ConversationId>) -> String {
+        let mut out = String::from("# Pinned notes\n");
+        if let Some(id) = conversation_id {
+            out.push_str(&format!("\nSession `{id}`\n"));
+        }
+        for (idx, text) in self.context_items().iter().enumerate() {
+            out.push_str(&format!("\n## Pin {}\n\n{text}\n", idx + 1));
+        }
+        out
+    }
+
+    fn position_of(&self, cell: &Arc<dyn HistoryCell>) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| Arc::ptr_eq(entry.head(), cell))
+    }
+
+    fn restored_position(&self, text: &str) -> Option<usize> {
+        self.restored.iter().position(|item| item == text)
+    }
+}
+
+/// Plain text of the entry that starts at `cells[idx]`.
+fn entry_text(cells: &[Arc<dyn HistoryCell>], idx: usize) -> String {
+    let lines: Vec<Line<'static>> = cells[entry_range(cells, idx)]
+        .iter()
+        .flat_map(|cell| cell.transcript_lines())
+        .collect();
+    plain_text(&lines)
+}
+
+fn plain_text(lines: &[Line<'static>]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The cells making up the entry that contains `cells[idx]`.
+pub(crate) fn entry_range(cells: &[Arc<dyn HistoryCell>], idx: usize) -> Range<usize> {
+    if idx >= cells.len() {
+        return idx..idx;
+    }
+    let mut start = idx;
+    while start > 0 && cells[start].is_stream_continuation() {
+        start -= 1;
+    }
+    let mut end = idx + 1;
+    while end < cells.len() && cells[end].is_stream_continuation() {
+        end += 1;
+    }
+    start..end
+}
+
+/// Index of the most recent agent message in `cells`, the usual target of `/pin`.
+pub(crate) fn latest_agent_message(cells: &[Arc<dyn HistoryCell>]) -> Option<usize> {
+    let idx = cells
+        .iter()
+        .rposition(|cell| cell.as_any().is::<AgentMessageCell>())?;
+    Some(entry_range(cells, idx).start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history_cell::UserHistoryCell;
+    use pretty_assertions::assert_eq;
+
+    fn user(text: &str) -> Arc<dyn HistoryCell> {
+        Arc::new(UserHistoryCell {
+            message: text.to_string(),
+        })
+    }
+
+    fn agent(text: &str, is_first_line: bool) -> Arc<dyn HistoryCell> {
+        Arc::new(AgentMessageCell::new(
+            vec![Line::from(text.to_string())],
+            is_first_line,
+        ))
+    }
+
+    #[test]
+    fn pinning_an_agent_message_captures_its_continuations() {
+        let cells = vec![
+            user("plan it"),
+            agent("1. migrate", true),
+            agent("2. backfill", false),
+            user("go"),
+        ];
+        let mut pins = Pins::default();
+
+        assert_eq!(latest_agent_message(&cells), Some(1));
+        assert!(pins.toggle(&cells, 2));
+        assert_eq!(pins.pinned_indices(&cells), vec![1]);
+        assert_eq!(
+            pins.context_items(),
+            vec!["codex\n1. migrate\n2. backfill".to_string()]
+        );
+
+        assert!(!pins.toggle(&cells, 1));
+        assert!(pins.is_empty());
+    }
+
+    #[test]
+    fn exports_markdown_and_drops_trimmed_cells() {
+        let mut cells = vec![user("use sqlite"), agent("agreed", true)];
+        let mut pins = Pins::default();
+        pins.toggle(&cells, 0);
+        pins.toggle(&cells, 1);
+
+        assert_eq!(
+            pins.to_markdown(None),
+            "# Pinned notes\n\n## Pin 1\n\nuser\nuse sqlite\n\n## Pin 2\n\ncodex\nagreed\n"
+        );
+
+        cells.truncate(1);
+        pins.retain_present(&cells);
+        assert_eq!(pins.len(), 1);
+    }
+
+    #[test]
+    fn restored_pins_mark_matching_entries_until_unpinned() {
+        let cells = vec![
+            user("plan it"),
+            agent("1. migrate", true),
+            agent("2. backfill", false),
+        ];
+        let mut pins = Pins::default();
+        pins.toggle(&cells, 0);
+        pins.restore(vec![
+            "user\nplan it".to_string(),
+            "codex\n1. migrate\n2. backfill".to_string(),
+            "codex\ntrimmed away".to_string(),
+        ]);
+
+        assert_eq!(pins.len(), 3);
+        assert_eq!(pins.pinned_indices(&cells), vec![0, 1]);
+
+        assert!(!pins.toggle(&cells, 2));
+        assert_eq!(pins.pinned_indices(&cells), vec![0]);
+        assert_eq!(
+            pins.context_items(),
+            vec![
+                "codex\ntrimmed away".to_string(),
+                "user\nplan it".to_string()
+            ]
+        );
+    }
+}
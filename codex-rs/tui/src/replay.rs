@@ -0,0 +1,369 @@
+//! `codex replay`: re-render a recorded session in the TUI with its original
+//! pacing.
+//!
+//! Rollouts persist the user-visible events (messages, reasoning, token
+//! counts) and the raw tool calls. Shell calls are turned back into exec
+//! begin/end events so commands show up the way they did live. Gaps between
+//! events come from the monotonic `elapsed_ms` recorded on each line, falling
+//! back to wall-clock timestamps for older rollouts.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::DateTime;
+use codex_core::parse_command::parse_command;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecCommandBeginEvent;
+use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::Op;
+use codex_core::protocol::SessionConfiguredEvent;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::mpsc::unbounded_channel;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::history_cell;
+
+/// Longest pause between two replayed events. Keeps idle time (e.g. the user
+/// stepping away) from stalling a demo.
+const MAX_REPLAY_GAP: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub(crate) struct Replay {
+    pub(crate) session_configured: SessionConfiguredEvent,
+    pub(crate) steps: Vec<ReplayStep>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ReplayStep {
+    /// Recorded time between the previous step and this one.
+    pub(crate) gap: Duration,
+    pub(crate) msg: EventMsg,
+}
+
+pub(crate) async fn load_replay(path: &Path) -> std::io::Result<Replay> {
+    let text = tokio::fs::read_to_string(path).await?;
+    let lines = text
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .collect();
+    build_replay(lines, path.to_path_buf())
+}
+
+fn build_replay(lines: Vec<RolloutLine>, rollout_path: PathBuf) -> std::io::Result<Replay> {
+    let mut session_configured: Option<SessionConfiguredEvent> = None;
+    let mut steps: Vec<ReplayStep> = Vec::new();
+    let mut shell_calls: HashSet<String> = HashSet::new();
+    let mut cwd = PathBuf::new();
+    let mut pending_gap = Duration::ZERO;
+    let mut previous: Option<&RolloutLine> = None;
+
+    for line in &lines {
+        if let Some(previous) = previous {
+            pending_gap += gap_between(previous, line);
+        }
+        previous = Some(line);
+
+        let mut msgs: Vec<EventMsg> = Vec::new();
+        match &line.item {
+            RolloutItem::SessionMeta(meta_line) => {
+                if session_configured.is_none() {
+                    cwd = meta_line.meta.cwd.clone();
+                    session_configured = Some(SessionConfiguredEvent {
+                        session_id: meta_line.meta.id,
+                        model: String::new(),
+                        reasoning_effort: None,
+                        history_log_id: 0,
+                        history_entry_count: 0,
+                        initial_messages: None,
+                        pinned_context: Vec::new(),
+                        rollout_path: rollout_path.clone(),
+                    });
+                }
+            }
+            RolloutItem::TurnContext(turn_context) => {
+                cwd = turn_context.cwd.clone();
+                if let Some(configured) = session_configured.as_mut()
+                    && configured.model.is_empty()
+                {
+                    configured.model = turn_context.model.clone();
+                    configured.reasoning_effort = turn_context.effort;
+                }
+            }
+            RolloutItem::EventMsg(msg) => msgs.push(msg.clone()),
+            RolloutItem::ResponseItem(item) => {
+                if let Some(begin) = shell_call_begin(item, &cwd) {
+                    shell_calls.insert(begin.call_id.clone());
+                    msgs.push(EventMsg::ExecCommandBegin(begin));
+                } else if let ResponseItem::FunctionCallOutput { call_id, output } = item
+                    && shell_calls.remove(call_id)
+                {
+                    msgs.push(EventMsg::ExecCommandEnd(shell_call_end(
+                        call_id,
+                        &output.content,
+                        output.success,
+                    )));
+                }
+            }
+            RolloutItem::Compacted(_) | RolloutItem::PinnedContext(_) => {}
+        }
+
+        for msg in msgs {
+            steps.push(ReplayStep {
+                gap: std::mem::take(&mut pending_gap),
+                msg,
+            });
+        }
+    }
+
+    let session_configured = session_configured
+        .ok_or_else(|| std::io::Error::other("rollout has no session metadata"))?;
+    Ok(Replay {
+        session_configured,
+        steps,
+    })
+}
+
+/// Recorded time between two rollout lines. Lines written by different runs
+/// of a resumed session, or by versions without monotonic timestamps, fall
+/// back to their wall-clock timestamps.
+fn gap_between(previous: &RolloutLine, next: &RolloutLine) -> Duration {
+    if let (Some(prev_ms), Some(next_ms)) = (previous.elapsed_ms, next.elapsed_ms)
+        && next_ms >= prev_ms
+    {
+        return Duration::from_millis(next_ms - prev_ms);
+    }
+    let (Ok(prev), Ok(next)) = (
+        DateTime::parse_from_rfc3339(&previous.timestamp),
+        DateTime::parse_from_rfc3339(&next.timestamp),
+    ) else {
+        return Duration::ZERO;
+    };
+    (next - prev).to_std().unwrap_or_default()
+}
+
+fn shell_call_begin(item: &ResponseItem, cwd: &Path) -> Option<ExecCommandBeginEvent> {
+    let (call_id, command, workdir) = match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } if name == "shell" => {
+            let params: ShellToolCallParams = serde_json::from_str(arguments).ok()?;
+            (call_id.clone(), params.command, params.workdir)
+        }
+        ResponseItem::LocalShellCall {
+            call_id: Some(call_id),
+            action: LocalShellAction::Exec(action),
+            ..
+        } => (
+            call_id.clone(),
+            action.command.clone(),
+            action.working_directory.clone(),
+        ),
+        _ => return None,
+    };
+    Some(ExecCommandBeginEvent {
+        call_id,
+        parsed_cmd: parse_command(&command)
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        command,
+        cwd: workdir.map_or_else(|| cwd.to_path_buf(), |dir| cwd.join(dir)),
+    })
+}
+
+/// Rebuild the end event from the tool output the model saw. Shell outputs
+/// are JSON with the combined output and exit metadata; anything else is
+/// shown as-is.
+fn shell_call_end(call_id: &str, content: &str, success: Option<bool>) -> ExecCommandEndEvent {
+    let parsed: Option<serde_json::Value> = serde_json::from_str(content).ok();
+    let output = parsed
+        .as_ref()
+        .and_then(|value| value.get("output"))
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or(content)
+        .to_string();
+    let metadata = parsed.as_ref().and_then(|value| value.get("metadata"));
+    let exit_code = metadata
+        .and_then(|metadata| metadata.get("exit_code"))
+        .and_then(serde_json::Value::as_i64)
+        .and_then(|code| i32::try_from(code).ok())
+        .unwrap_or(i32::from(success == Some(false)));
+    let duration = metadata
+        .and_then(|metadata| metadata.get("duration_seconds"))
+        .and_then(serde_json::Value::as_f64)
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .unwrap_or_default();
+    ExecCommandEndEvent {
+        call_id: call_id.to_string(),
+        stdout: output.clone(),
+        stderr: String::new(),
+        aggregated_output: output,
+        exit_code,
+        duration,
+        formatted_output: content.to_string(),
+    }
+}
+
+fn replay_delay(gap: Duration, speed: f64) -> Duration {
+    gap.div_f64(speed).min(MAX_REPLAY_GAP)
+}
+
+/// Feed the recorded events to the UI with their original spacing, scaled by
+/// `speed`. Returns the op sender for the chat widget; ops are not executed
+/// since nothing is running behind a replay.
+pub(crate) fn spawn_replay(
+    replay: Replay,
+    speed: f64,
+    app_event_tx: AppEventSender,
+) -> UnboundedSender<Op> {
+    let (codex_op_tx, mut codex_op_rx) = unbounded_channel::<Op>();
+
+    let Replay {
+        session_configured,
+        steps,
+    } = replay;
+    let replay_tx = app_event_tx.clone();
+    tokio::spawn(async move {
+        replay_tx.send(AppEvent::CodexEvent(Event {
+            id: String::new(),
+            msg: EventMsg::SessionConfigured(session_configured),
+        }));
+        for step in steps {
+            tokio::time::sleep(replay_delay(step.gap, speed)).await;
+            replay_tx.send(AppEvent::ReplayEvent(step.msg));
+        }
+        replay_tx.send(AppEvent::InsertHistoryCell(Box::new(
+            history_cell::new_info_event("Replay finished.".to_string(), None),
+        )));
+    });
+
+    tokio::spawn(async move {
+        while let Some(op) = codex_op_rx.recv().await {
+            if matches!(op, Op::UserInput { .. }) {
+                app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_info_event(
+                        "This is a replay; messages are not sent to the model.".to_string(),
+                        Some("Use `codex resume` to continue the session.".to_string()),
+                    ),
+                )));
+            }
+        }
+    });
+
+    codex_op_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use pretty_assertions::assert_eq;
+
+    fn line(timestamp: &str, elapsed_ms: Option<u64>, item: RolloutItem) -> RolloutLine {
+        RolloutLine {
+            timestamp: timestamp.to_string(),
+            elapsed_ms,
+            item,
+        }
+    }
+
+    #[test]
+    fn replay_rebuilds_shell_calls_and_spacing() {
+        let meta = SessionMeta {
+            id: ConversationId::new(),
+            cwd: PathBuf::from("/repo"),
+            ..SessionMeta::default()
+        };
+        let lines = vec![
+            line(
+                "2025-01-01T00:00:00.000Z",
+                Some(0),
+                RolloutItem::SessionMeta(SessionMetaLine {
+                    meta,
+                    git: None,
+                    environment: None,
+                }),
+            ),
+            line(
+                "2025-01-01T00:00:02.000Z",
+                Some(1500),
+                RolloutItem::EventMsg(EventMsg::AgentMessage(AgentMessageEvent {
+                    message: "running tests".to_string(),
+                })),
+            ),
+            line(
+                "2025-01-01T00:00:03.000Z",
+                Some(2500),
+                RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+                    id: None,
+                    name: "shell".to_string(),
+                    arguments: r#"{"command":["cargo","test"]}"#.to_string(),
+                    call_id: "call-1".to_string(),
+                }),
+            ),
+            // A resumed run restarts the monotonic clock; fall back to wall time.
+            line(
+                "2025-01-01T00:00:07.000Z",
+                Some(10),
+                RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                    call_id: "call-1".to_string(),
+                    output: FunctionCallOutputPayload {
+                        content:
+                            r#"{"output":"ok","metadata":{"exit_code":101,"duration_seconds":1.5}}"#
+                                .to_string(),
+                        success: Some(true),
+                    },
+                }),
+            ),
+        ];
+
+        let replay = build_replay(lines, PathBuf::from("rollout.jsonl")).expect("replay");
+        let gaps: Vec<Duration> = replay.steps.iter().map(|step| step.gap).collect();
+        assert_eq!(
+            gaps,
+            vec![
+                Duration::from_millis(1500),
+                Duration::from_millis(1000),
+                Duration::from_secs(4),
+            ]
+        );
+
+        let EventMsg::ExecCommandBegin(begin) = &replay.steps[1].msg else {
+            panic!("expected exec begin, got {:?}", replay.steps[1].msg);
+        };
+        assert_eq!(begin.command, vec!["cargo".to_string(), "test".to_string()]);
+        assert_eq!(begin.cwd, PathBuf::from("/repo"));
+
+        let EventMsg::ExecCommandEnd(end) = &replay.steps[2].msg else {
+            panic!("expected exec end, got {:?}", replay.steps[2].msg);
+        };
+        assert_eq!(end.exit_code, 101);
+        assert_eq!(end.aggregated_output, "ok");
+        assert_eq!(end.duration, Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn replay_delay_scales_and_caps_gaps() {
+        assert_eq!(
+            replay_delay(Duration::from_secs(2), 4.0),
+            Duration::from_millis(500)
+        );
+        assert_eq!(replay_delay(Duration::from_secs(600), 2.0), MAX_REPLAY_GAP);
+    }
+}
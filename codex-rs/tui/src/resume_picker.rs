@@ -42,6 +42,11 @@ const LOAD_NEAR_THRESHOLD: usize = 5;
 pub enum ResumeSelection {
     StartFresh,
     Resume(PathBuf),
+    /// Play a recorded session back read-only (`codex replay`).
+    Replay {
+        path: PathBuf,
+        speed: f64,
+    },
     Exit,
 }
 
@@ -0,0 +1,399 @@
+//! `codex runbook`: walk the agent through a fixed sequence of prompts.
+//!
+//! A runbook is a YAML file listing steps. Each step's prompt is sent as a
+//! user message. When the turn finishes, the step's `verify` commands run in
+//! the session's working directory, and the next step only starts once the
+//! user confirms it (unless the step sets `pause: false`). A failed check
+//! holds the runbook until the user asks Codex to fix it, skips ahead, or
+//! stops.
+//!
+//! ```yaml
+//! name: Release
+//! steps:
+//!   - name: Bump version
+//!     prompt: Bump the crate version to the next minor release.
+//!     verify:
+//!       - cargo check
+//!   - name: Changelog
+//!     prompt: Add a changelog entry for the new version.
+//! ```
+
+use std::io::Error as IoError;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use serde::Deserialize;
+use tokio::process::Command;
+
+/// Trailing lines of output kept from a failed verification command.
+const FAILURE_OUTPUT_LINES: usize = 20;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Runbook {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    pub(crate) steps: Vec<RunbookStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RunbookStep {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    pub(crate) prompt: String,
+    /// Shell commands that must succeed before the runbook moves on.
+    #[serde(default)]
+    pub(crate) verify: Vec<String>,
+    /// Wait for confirmation before starting the following step.
+    #[serde(default = "default_pause")]
+    pub(crate) pause: bool,
+}
+
+fn default_pause() -> bool {
+    true
+}
+
+impl Runbook {
+    pub(crate) fn load(path: &Path) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text)
+    }
+
+    fn parse(text: &str) -> std::io::Result<Self> {
+        let runbook: Runbook = serde_yaml_ng::from_str(text)
+            .map_err(|e| IoError::other(format!("invalid runbook: {e}")))?;
+        if runbook.steps.is_empty() {
+            return Err(IoError::other("runbook has no steps"));
+        }
+        if let Some(idx) = runbook
+            .steps
+            .iter()
+            .position(|step| step.prompt.trim().is_empty())
+        {
+            return Err(IoError::other(format!(
+                "runbook step {} has an empty prompt",
+                idx + 1
+            )));
+        }
+        Ok(runbook)
+    }
+
+    /// Human-readable label such as `step 2/3 (Changelog)`.
+    pub(crate) fn step_label(&self, idx: usize) -> String {
+        let total = self.steps.len();
+        match self.steps.get(idx).and_then(|step| step.name.as_deref()) {
+            Some(name) => format!("step {}/{total} ({name})", idx + 1),
+            None => format!("step {}/{total}", idx + 1),
+        }
+    }
+
+    pub(crate) fn title(&self) -> String {
+        match &self.name {
+            Some(name) => format!("Runbook {name}"),
+            None => "Runbook".to_string(),
+        }
+    }
+}
+
+/// A verification command that exited unsuccessfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VerifyFailure {
+    pub(crate) command: String,
+    pub(crate) exit_code: Option<i32>,
+    /// Tail of the combined stdout and stderr.
+    pub(crate) output: String,
+}
+
+impl VerifyFailure {
+    /// Prompt asking the agent to fix the failure.
+    pub(crate) fn fix_prompt(&self) -> String {
+        let status = match self.exit_code {
+            Some(code) => format!("exited with status {code}"),
+            None => "failed".to_string(),
+        };
+        format!(
+            "The verification command `{}` {status}. Its output was:\n\n```\n{}\n```\n\nFix the problem so the command succeeds.",
+            self.command, self.output
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VerifyOutcome {
+    pub(crate) passed: Vec<String>,
+    pub(crate) failure: Option<VerifyFailure>,
+}
+
+/// Run `commands` in order in `cwd`, stopping at the first failure.
+pub(crate) async fn run_verification(commands: Vec<String>, cwd: PathBuf) -> VerifyOutcome {
+    let mut passed = Vec::new();
+    for command in commands {
+        let (program, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        let output = Command::new(program)
+            .arg(flag)
+            .arg(&command)
+            .current_dir(&cwd)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output()
+            .await;
+        let failure = match output {
+            Ok(output) if output.status.success() => None,
+            Ok(output) => {
+                let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+                combined.push_str(&String::from_utf8_lossy(&output.stderr));
+                Some(VerifyFailure {
+                    command: command.clone(),
+                    exit_code: output.status.code(),
+                    output: tail_lines(&combined, FAILURE_OUTPUT_LINES),
+                })
+            }
+            Err(err) => Some(VerifyFailure {
+                command: command.clone(),
+                exit_code: None,
+                output: err.to_string(),
+            }),
+        };
+        if let Some(failure) = failure {
+            return VerifyOutcome {
+                passed,
+                failure: Some(failure),
+            };
+        }
+        passed.push(command);
+    }
+    VerifyOutcome {
+        passed,
+        failure: None,
+    }
+}
+
+fn tail_lines(text: &str, max: usize) -> String {
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(max)..].join("\n")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RunbookState {
+    NotStarted,
+    /// The step's prompt was sent and the agent is working on it.
+    Running(usize),
+    Verifying(usize),
+    Failed(usize, VerifyFailure),
+    /// Waiting for the user to start the given step.
+    AwaitingConfirmation(usize),
+}
+
+/// What the app should do after the runbook advanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RunbookAction {
+    None,
+    /// Send the prompt of the given step.
+    Start(usize),
+    /// Run the verification commands of the given step.
+    Verify(usize),
+    /// Ask the user whether to start the given step.
+    Confirm(usize),
+    /// Ask the user how to handle the failed check of the given step.
+    Failed(usize),
+    Finished,
+}
+
+#[derive(Debug)]
+pub(crate) struct RunbookRun {
+    runbook: Runbook,
+    state: RunbookState,
+}
+
+impl RunbookRun {
+    pub(crate) fn new(runbook: Runbook) -> Self {
+        Self {
+            runbook,
+            state: RunbookState::NotStarted,
+        }
+    }
+
+    pub(crate) fn runbook(&self) -> &Runbook {
+        &self.runbook
+    }
+
+    pub(crate) fn step(&self, idx: usize) -> Option<&RunbookStep> {
+        self.runbook.steps.get(idx)
+    }
+
+    pub(crate) fn failure(&self) -> Option<&VerifyFailure> {
+        match &self.state {
+            RunbookState::Failed(_, failure) => Some(failure),
+            _ => None,
+        }
+    }
+
+    /// Called once the session is configured.
+    pub(crate) fn start(&mut self) -> RunbookAction {
+        if self.state != RunbookState::NotStarted {
+            return RunbookAction::None;
+        }
+        self.state = RunbookState::Running(0);
+        RunbookAction::Start(0)
+    }
+
+    /// Called whenever the agent becomes idle. Re-asks a pending question
+    /// when the user dismissed it and chatted in the meantime.
+    pub(crate) fn turn_finished(&mut self) -> RunbookAction {
+        match self.state {
+            RunbookState::Running(idx) => {
+                if self.runbook.steps[idx].verify.is_empty() {
+                    self.advance(idx)
+                } else {
+                    self.state = RunbookState::Verifying(idx);
+                    RunbookAction::Verify(idx)
+                }
+            }
+            RunbookState::AwaitingConfirmation(idx) => RunbookAction::Confirm(idx),
+            RunbookState::Failed(idx, _) => RunbookAction::Failed(idx),
+            RunbookState::NotStarted | RunbookState::Verifying(_) => RunbookAction::None,
+        }
+    }
+
+    pub(crate) fn verification_finished(
+        &mut self,
+        idx: usize,
+        failure: Option<VerifyFailure>,
+    ) -> RunbookAction {
+        if self.state != RunbookState::Verifying(idx) {
+            return RunbookAction::None;
+        }
+        match failure {
+            Some(failure) => {
+                self.state = RunbookState::Failed(idx, failure);
+                RunbookAction::Failed(idx)
+            }
+            None => self.advance(idx),
+        }
+    }
+
+    /// The user confirmed the pending step.
+    pub(crate) fn confirm(&mut self) -> RunbookAction {
+        match self.state {
+            RunbookState::AwaitingConfirmation(idx) => {
+                self.state = RunbookState::Running(idx);
+                RunbookAction::Start(idx)
+            }
+            _ => RunbookAction::None,
+        }
+    }
+
+    /// The user asked the agent to fix a failed check. Returns the prompt to
+    /// send; the step is verified again once that turn finishes.
+    pub(crate) fn retry(&mut self) -> Option<String> {
+        let RunbookState::Failed(idx, failure) = &self.state else {
+            return None;
+        };
+        let prompt = failure.fix_prompt();
+        self.state = RunbookState::Running(*idx);
+        Some(prompt)
+    }
+
+    /// The user chose to move on despite a failed check.
+    pub(crate) fn skip_failed(&mut self) -> RunbookAction {
+        match self.state {
+            RunbookState::Failed(idx, _) => self.advance(idx),
+            _ => RunbookAction::None,
+        }
+    }
+
+    fn advance(&mut self, idx: usize) -> RunbookAction {
+        let next = idx + 1;
+        if next >= self.runbook.steps.len() {
+            return RunbookAction::Finished;
+        }
+        if self.runbook.steps[idx].pause {
+            self.state = RunbookState::AwaitingConfirmation(next);
+            RunbookAction::Confirm(next)
+        } else {
+            self.state = RunbookState::Running(next);
+            RunbookAction::Start(next)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const RELEASE: &str = r#"
+name: Release
+steps:
+  - name: Bump version
+    prompt: Bump the version.
+    verify:
+      - cargo check
+  - prompt: Update the changelog.
+    pause: false
+  - name: Tag
+    prompt: Tag the release.
+"#;
+
+    #[test]
+    fn parses_steps_and_rejects_empty_runbooks() {
+        let runbook = Runbook::parse(RELEASE).expect("parse runbook");
+        assert_eq!(runbook.title(), "Runbook Release");
+        assert_eq!(runbook.steps.len(), 3);
+        assert_eq!(runbook.steps[0].verify, vec!["cargo check".to_string()]);
+        assert!(runbook.steps[0].pause);
+        assert!(!runbook.steps[1].pause);
+        assert_eq!(runbook.step_label(0), "step 1/3 (Bump version)");
+        assert_eq!(runbook.step_label(1), "step 2/3");
+
+        assert!(Runbook::parse("steps: []").is_err());
+        assert!(Runbook::parse("steps:\n  - prompt: ''").is_err());
+        assert!(Runbook::parse("steps:\n  - prompt: hi\n    verfiy: [ls]").is_err());
+    }
+
+    #[test]
+    fn walks_steps_with_verification_and_pauses() {
+        let mut run = RunbookRun::new(Runbook::parse(RELEASE).expect("parse runbook"));
+        assert_eq!(run.start(), RunbookAction::Start(0));
+        assert_eq!(run.start(), RunbookAction::None);
+
+        assert_eq!(run.turn_finished(), RunbookAction::Verify(0));
+        let failure = VerifyFailure {
+            command: "cargo check".to_string(),
+            exit_code: Some(101),
+            output: "error[E0308]".to_string(),
+        };
+        assert_eq!(
+            run.verification_finished(0, Some(failure.clone())),
+            RunbookAction::Failed(0)
+        );
+        assert_eq!(run.failure(), Some(&failure));
+        assert_eq!(run.turn_finished(), RunbookAction::Failed(0));
+
+        assert_eq!(run.retry(), Some(failure.fix_prompt()));
+        assert_eq!(run.turn_finished(), RunbookAction::Verify(0));
+        assert_eq!(
+            run.verification_finished(0, None),
+            RunbookAction::Confirm(1)
+        );
+        // Dismissing the question and chatting asks again.
+        assert_eq!(run.turn_finished(), RunbookAction::Confirm(1));
+
+        assert_eq!(run.confirm(), RunbookAction::Start(1));
+        // Step 2 does not pause, so step 3 starts right away.
+        assert_eq!(run.turn_finished(), RunbookAction::Start(2));
+        assert_eq!(run.turn_finished(), RunbookAction::Finished);
+    }
+
+    #[test]
+    fn tail_lines_keeps_the_end_of_the_output() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc");
+        assert_eq!(tail_lines("a", 5), "a");
+    }
+}
@@ -20,9 +20,18 @@ pub enum SlashCommand {
     Compact,
     Undo,
     Diff,
+    Pin,
+    Pins,
     Mention,
+    Issue,
+    Todos,
+    Note,
+    RetryDenied,
     Status,
+    Statusline,
+    Pair,
     Mcp,
+    Debug,
     Logout,
     Quit,
     #[cfg(debug_assertions)]
@@ -40,11 +49,20 @@ impl SlashCommand {
             SlashCommand::Undo => "restore the workspace to the last Codex snapshot",
             SlashCommand::Quit => "exit Codex",
             SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Pin => "pin or unpin the latest response so it survives compaction",
+            SlashCommand::Pins => "show pinned messages and export them",
             SlashCommand::Mention => "mention a file",
-            SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Issue => "pull a GitHub, GitLab or Jira issue into the conversation",
+            SlashCommand::Todos => "list TODO/FIXME/HACK comments and ask Codex to fix them",
+            SlashCommand::Note => "add a note to the transcript, e.g. /note chose plan B",
+            SlashCommand::RetryDenied => "retry a command or edit you denied, optionally edited",
+            SlashCommand::Status => "show session configuration and usage, /status json to export",
+            SlashCommand::Statusline => "dump the status line's data as JSON for a bug report",
+            SlashCommand::Pair => "show what Codex is doing in the status line for 30 minutes",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Debug => "show diagnostics, e.g. /debug perf for frame timings",
             SlashCommand::Logout => "log out of Codex",
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => "test approval request",
@@ -67,11 +85,20 @@ impl SlashCommand {
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::Review
+            | SlashCommand::Todos
             | SlashCommand::Logout => false,
             SlashCommand::Diff
+            | SlashCommand::Pin
+            | SlashCommand::Pins
             | SlashCommand::Mention
+            | SlashCommand::Issue
+            | SlashCommand::Note
+            | SlashCommand::RetryDenied
             | SlashCommand::Status
+            | SlashCommand::Statusline
+            | SlashCommand::Pair
             | SlashCommand::Mcp
+            | SlashCommand::Debug
             | SlashCommand::Quit => true,
 
             #[cfg(debug_assertions)]
@@ -2,9 +2,11 @@ use crate::history_cell::CompositeHistoryCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::PlainHistoryCell;
 use crate::history_cell::with_border_with_inner_width;
+use crate::statusline::StatusLineCostSnapshot;
 use crate::version::CODEX_CLI_VERSION;
 use codex_common::create_config_summary_entries;
 use codex_core::config::Config;
+use codex_core::pricing::format_usd;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsage;
 use codex_protocol::ConversationId;
@@ -55,6 +57,7 @@ struct StatusHistoryCell {
     account: Option<StatusAccountDisplay>,
     session_id: Option<String>,
     token_usage: StatusTokenUsageData,
+    cost: Option<StatusLineCostSnapshot>,
     rate_limits: StatusRateLimitData,
 }
 
@@ -64,9 +67,17 @@ pub(crate) fn new_status_output(
     context_usage: Option<&TokenUsage>,
     session_id: &Option<ConversationId>,
     rate_limits: Option<&RateLimitSnapshotDisplay>,
+    cost: Option<StatusLineCostSnapshot>,
 ) -> CompositeHistoryCell {
     let command = PlainHistoryCell::new(vec!["/status".magenta().into()]);
-    let card = StatusHistoryCell::new(config, total_usage, context_usage, session_id, rate_limits);
+    let card = StatusHistoryCell::new(
+        config,
+        total_usage,
+        context_usage,
+        session_id,
+        rate_limits,
+        cost,
+    );
 
     CompositeHistoryCell::new(vec![Box::new(command), Box::new(card)])
 }
@@ -78,6 +89,7 @@ impl StatusHistoryCell {
         context_usage: Option<&TokenUsage>,
         session_id: &Option<ConversationId>,
         rate_limits: Option<&RateLimitSnapshotDisplay>,
+        cost: Option<StatusLineCostSnapshot>,
     ) -> Self {
         let config_entries = create_config_summary_entries(config);
         let (model_name, model_details) = compose_model_display(config, &config_entries);
@@ -120,6 +132,7 @@ impl StatusHistoryCell {
             account,
             session_id,
             token_usage,
+            cost,
             rate_limits,
         }
     }
@@ -142,6 +155,17 @@ impl StatusHistoryCell {
         ]
     }
 
+    fn cost_spans(&self) -> Option<Vec<Span<'static>>> {
+        let cost = self.cost.as_ref()?;
+        Some(vec![
+            Span::from(format_usd(cost.session_usd)),
+            Span::from(" session").dim(),
+            Span::from(" (").dim(),
+            Span::from(format_usd(cost.last_turn_usd)).dim(),
+            Span::from(" last turn)").dim(),
+        ])
+    }
+
     fn context_window_spans(&self) -> Option<Vec<Span<'static>>> {
         let context = self.token_usage.context_window.as_ref()?;
         let percent = context.percent_remaining;
@@ -270,6 +294,9 @@ impl HistoryCell for StatusHistoryCell {
             push_label(&mut labels, &mut seen, "Session");
         }
         push_label(&mut labels, &mut seen, "Token usage");
+        if self.cost.is_some() {
+            push_label(&mut labels, &mut seen, "Estimated cost");
+        }
         if self.token_usage.context_window.is_some() {
             push_label(&mut labels, &mut seen, "Context window");
         }
@@ -305,6 +332,9 @@ impl HistoryCell for StatusHistoryCell {
         // Hide token usage only for ChatGPT subscribers
         if !matches!(self.account, Some(StatusAccountDisplay::ChatGpt { .. })) {
             lines.push(formatter.line("Token usage", self.token_usage_spans()));
+            if let Some(spans) = self.cost_spans() {
+                lines.push(formatter.line("Estimated cost", spans));
+            }
         }
 
         if let Some(spans) = self.context_window_spans() {
@@ -150,7 +150,7 @@ pub(crate) fn format_directory_display(directory: &Path, max_width: Option<usize
             format!("~{}{}", std::path::MAIN_SEPARATOR, rel.display())
         }
     } else {
-        directory.display().to_string()
+        dunce::simplified(directory).display().to_string()
     };
 
     if let Some(max_width) = max_width {
@@ -5,6 +5,7 @@ mod helpers;
 mod rate_limits;
 
 pub(crate) use card::new_status_output;
+#[cfg(test)]
 pub(crate) use format::line_display_width;
 pub(crate) use format::truncate_line_to_width;
 pub(crate) use helpers::format_directory_display;
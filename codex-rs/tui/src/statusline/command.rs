@@ -0,0 +1,244 @@
+//! `statusline.command`: an external program that draws the status line,
+//! like a starship prompt. It gets the status line's data as JSON on stdin
+//! ([`snapshot_json`]) and prints the rows to show; ANSI colors and styles
+//! are kept.
+//!
+//! The command runs again whenever its input changes, one run at a time.
+//! Until it first succeeds, and whenever it fails or times out, the built-in
+//! renderer draws the line instead.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_ansi_escape::ansi_escape;
+use ratatui::text::Line;
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::runtime::Handle;
+
+use super::StatusLineSnapshot;
+use crate::status::truncate_line_to_width;
+use crate::tui::FrameRequester;
+
+/// Longest a run may take before the built-in line is shown instead.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub(crate) struct StatusLineCommand {
+    command: String,
+    frame_requester: FrameRequester,
+    runs: Arc<Mutex<CommandRuns>>,
+}
+
+#[derive(Debug, Default)]
+struct CommandRuns {
+    /// Input of the latest run, finished or not.
+    last_input: Option<String>,
+    running: bool,
+    /// Stdout of the last run, if it succeeded.
+    output: Option<String>,
+}
+
+impl StatusLineCommand {
+    pub(crate) fn new(command: String, frame_requester: FrameRequester) -> Self {
+        Self {
+            command,
+            frame_requester,
+            runs: Arc::default(),
+        }
+    }
+
+    /// `rows` rows of the last run's output, cut to `width`; `None` when
+    /// there is none to show. Starts a run in `cwd` when `input` differs
+    /// from the last run's.
+    pub(crate) fn lines(
+        &self,
+        input: String,
+        cwd: &Path,
+        rows: u16,
+        width: u16,
+    ) -> Option<Vec<Line<'static>>> {
+        let Ok(mut runs) = self.runs.lock() else {
+            return None;
+        };
+        if !runs.running
+            && runs.last_input.as_ref() != Some(&input)
+            && let Ok(handle) = Handle::try_current()
+        {
+            runs.running = true;
+            runs.last_input = Some(input.clone());
+            let command = self.command.clone();
+            let cwd = cwd.to_path_buf();
+            let shared = Arc::clone(&self.runs);
+            let frame_requester = self.frame_requester.clone();
+            handle.spawn(async move {
+                let output = run_command(&command, &cwd, &input).await;
+                if let Ok(mut runs) = shared.lock() {
+                    runs.running = false;
+                    runs.output = output;
+                }
+                frame_requester.schedule_frame();
+            });
+        }
+        runs.output
+            .as_deref()
+            .map(|output| output_lines(output, rows, width))
+    }
+}
+
+/// Stdout of `sh -c command` given `input` on stdin, if it succeeds in time.
+async fn run_command(command: &str, cwd: &Path, input: &str) -> Option<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .ok()?;
+    let mut stdin = child.stdin.take()?;
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, async move {
+        // A command that exits without reading its input is fine.
+        let _ = stdin.write_all(input.as_bytes()).await;
+        drop(stdin);
+        child.wait_with_output().await
+    })
+    .await
+    .ok()?
+    .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The first `rows` lines of `output`, cut to `width` and padded with empty
+/// rows so the status line keeps its height.
+fn output_lines(output: &str, rows: u16, width: u16) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line<'static>> = ansi_escape(output.trim_end_matches('\n'))
+        .lines
+        .into_iter()
+        .take(usize::from(rows))
+        .map(|line| truncate_line_to_width(line, usize::from(width)))
+        .collect();
+    lines.resize(usize::from(rows), Line::default());
+    lines
+}
+
+/// The status line's data as given to `statusline.command`, for a line
+/// `width` columns wide: the snapshot's JSON plus `width`.
+pub(crate) fn snapshot_json(
+    snapshot: &StatusLineSnapshot,
+    width: u16,
+    now: Instant,
+) -> serde_json::Result<Value> {
+    let mut json = snapshot.to_json(now)?;
+    if let Value::Object(fields) = &mut json {
+        fields.insert("width".to_string(), Value::from(width));
+    }
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statusline::RunTimerSnapshot;
+    use crate::statusline::StatusLineGitSnapshot;
+    use crate::statusline::StatusLineModelSnapshot;
+    use crate::statusline::StatusLineRunState;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Color;
+    use serde_json::json;
+
+    #[test]
+    fn snapshot_json_describes_the_segments() {
+        let mut snapshot = StatusLineSnapshot {
+            cwd_display: Some("~/code/codex".to_string()),
+            model: Some(StatusLineModelSnapshot {
+                label: "gpt-5-codex".to_string(),
+                detail: Some("high".to_string()),
+            }),
+            git: Some(StatusLineGitSnapshot {
+                branch: Some("main".to_string()),
+                dirty: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        snapshot.environment.kubernetes_context = Some("prod".to_string());
+        snapshot.environment.custom = vec![("weather".to_string(), Some("sunny".to_string()))];
+
+        let now = Instant::now();
+        let json = snapshot_json(&snapshot, 120, now).expect("snapshot serializes");
+        assert_eq!(json["width"], 120);
+        assert_eq!(json["cwd_display"], "~/code/codex");
+        assert_eq!(
+            json["model"],
+            json!({ "label": "gpt-5-codex", "detail": "high" })
+        );
+        assert_eq!(json["git"]["branch"], "main");
+        assert_eq!(json["git"]["dirty"], true);
+        assert_eq!(json["run_state"], Value::Null);
+        assert_eq!(json["rate_limit_cooldown_secs"], Value::Null);
+        assert_eq!(json["environment"]["kubernetes_context"], "prod");
+        assert_eq!(json["environment"]["custom"], json!({ "weather": "sunny" }));
+    }
+
+    #[test]
+    fn snapshot_json_reads_the_run_timer_when_taken() {
+        let now = Instant::now();
+        let snapshot = StatusLineSnapshot {
+            run_state: Some(StatusLineRunState {
+                label: "Working".to_string(),
+                timer: Some(RunTimerSnapshot {
+                    elapsed_running: Duration::from_secs(5),
+                    last_resume_at: now.checked_sub(Duration::from_secs(3)),
+                    elapsed_paused: Duration::from_secs(2),
+                    last_pause_at: None,
+                    is_paused: false,
+                }),
+                queued_messages: Arc::from(["next".to_string()]),
+                ..Default::default()
+            }),
+            rate_limit_cooldown: Some(Duration::from_millis(4500)),
+            ..Default::default()
+        };
+
+        let json = snapshot_json(&snapshot, 80, now).expect("snapshot serializes");
+        assert_eq!(
+            json["run_state"],
+            json!({
+                "label": "Working",
+                "timer": { "elapsed_secs": 8, "paused_secs": 2, "paused": false },
+                "queued_messages": ["next"],
+                "show_interrupt_hint": false,
+            })
+        );
+        assert_eq!(json["rate_limit_cooldown_secs"], 4);
+    }
+
+    fn text(line: &Line<'static>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn output_keeps_ansi_styles_and_the_line_height() {
+        let lines = output_lines("\x1b[32mmain\x1b[0m gpt-5-codex\nextra\n", 1, 80);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(text(&lines[0]), "main gpt-5-codex");
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Green));
+
+        let lines = output_lines("only one row\n", 2, 4);
+        assert_eq!(lines.iter().map(text).collect::<Vec<_>>(), vec!["only", ""]);
+    }
+}
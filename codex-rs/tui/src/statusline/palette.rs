@@ -23,9 +23,6 @@ pub(crate) const ROSEWATER: Color = Color::Rgb(245, 224, 220);
 #[allow(clippy::disallowed_methods)]
 pub(crate) const TEAL: Color = Color::Rgb(148, 226, 213);
 #[allow(clippy::disallowed_methods)]
-#[allow(dead_code)]
-pub(crate) const SURFACE0: Color = Color::Rgb(49, 50, 68);
-#[allow(clippy::disallowed_methods)]
 pub(crate) const SUBTEXT0: Color = Color::Rgb(166, 173, 200);
 #[allow(clippy::disallowed_methods)]
 pub(crate) const GREEN_LIGHT: Color = Color::Rgb(86, 127, 81);
@@ -36,8 +33,37 @@ pub(crate) const PEACH_LIGHT: Color = Color::Rgb(150, 107, 81);
 #[allow(clippy::disallowed_methods)]
 pub(crate) const RED_LIGHT: Color = Color::Rgb(146, 83, 100);
 
-pub(crate) fn queue_preview_style() -> Style {
+/// The ANSI color standing in for a theme color in the plain style, per
+/// `tui/styles.md`: picked by hue, with grays, dark colors and yellows,
+/// which have no counterpart there, becoming the default foreground.
+pub(crate) fn ansi(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let (r, g, b) = (f32::from(r), f32::from(g), f32::from(b));
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    if max < 80.0 || delta / max < 0.2 {
+        return Color::Reset;
+    }
+    let hue = if max == r {
+        (60.0 * (g - b) / delta).rem_euclid(360.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    match hue {
+        hue if !(35.0..330.0).contains(&hue) => Color::Red,
+        hue if hue < 75.0 => Color::Reset,
+        hue if hue < 160.0 => Color::Green,
+        hue if hue < 215.0 => Color::Cyan,
+        _ => Color::Magenta,
+    }
+}
+
+pub(crate) fn queue_preview_style(color: Color) -> Style {
     Style::default()
-        .fg(SUBTEXT0)
+        .fg(color)
         .add_modifier(Modifier::ITALIC | Modifier::DIM)
 }
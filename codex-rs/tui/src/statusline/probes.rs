@@ -0,0 +1,221 @@
+//! Background probes for the status line: git, the Kubernetes context, the
+//! AWS profile and `[[statusline.segments]]` commands.
+//!
+//! Each registered probe runs on its own task, once at start and then every
+//! interval or when [`StatusLineProbes::refresh`] asks for it, so a slow
+//! probe only delays itself. Runs are cut off after [`PROBE_TIMEOUT`].
+//! Results go out as [`AppEvent::StatusLineProbe`] on the app event
+//! channel; a result equal to the probe's previous one is reported as
+//! [`ProbeReport::Unchanged`], which marks the segment fresh without a
+//! redraw.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::sync::watch;
+use tokio::task::AbortHandle;
+
+use super::StatusLineGitSnapshot;
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+
+/// Longest a single probe run may take before its result is dropped.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProbeKind {
+    Git,
+    KubeContext,
+    AwsProfile,
+    /// A `[[statusline.segments]]` entry, by name.
+    Custom(String),
+}
+
+/// A probe's result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ProbeUpdate {
+    Git(Option<StatusLineGitSnapshot>),
+    KubeContext(Option<String>),
+    AwsProfile(Option<String>),
+    Custom { name: String, text: Option<String> },
+}
+
+impl ProbeUpdate {
+    pub(crate) fn kind(&self) -> ProbeKind {
+        match self {
+            Self::Git(_) => ProbeKind::Git,
+            Self::KubeContext(_) => ProbeKind::KubeContext,
+            Self::AwsProfile(_) => ProbeKind::AwsProfile,
+            Self::Custom { name, .. } => ProbeKind::Custom(name.clone()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ProbeReport {
+    Changed(ProbeUpdate),
+    /// The probe ran and found what it reported last time.
+    Unchanged(ProbeKind),
+}
+
+type ProbeFuture = Pin<Box<dyn Future<Output = ProbeUpdate> + Send>>;
+
+pub(crate) struct Probe {
+    kind: ProbeKind,
+    interval: Duration,
+    run: Box<dyn Fn() -> ProbeFuture + Send + Sync>,
+}
+
+impl Probe {
+    pub(crate) fn new<F, Fut>(kind: ProbeKind, interval: Duration, run: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ProbeUpdate> + Send + 'static,
+    {
+        Self {
+            kind,
+            interval,
+            run: Box::new(move || Box::pin(run())),
+        }
+    }
+}
+
+/// The running probes; dropping it stops them.
+#[derive(Debug, Default)]
+pub(crate) struct StatusLineProbes {
+    probes: Vec<RunningProbe>,
+}
+
+#[derive(Debug)]
+struct RunningProbe {
+    kind: ProbeKind,
+    refresh: watch::Sender<()>,
+    task: AbortHandle,
+}
+
+impl StatusLineProbes {
+    /// Start `probes`; without a Tokio runtime nothing runs.
+    pub(crate) fn spawn(probes: Vec<Probe>, tx: AppEventSender) -> Self {
+        let Ok(handle) = Handle::try_current() else {
+            return Self::default();
+        };
+        let probes = probes
+            .into_iter()
+            .map(|probe| {
+                let (refresh, refresh_rx) = watch::channel(());
+                RunningProbe {
+                    kind: probe.kind.clone(),
+                    refresh,
+                    task: handle
+                        .spawn(run_probe(probe, refresh_rx, tx.clone()))
+                        .abort_handle(),
+                }
+            })
+            .collect();
+        Self { probes }
+    }
+
+    /// Run the probes of `kind` now instead of at their next interval.
+    pub(crate) fn refresh(&self, kind: &ProbeKind) {
+        for probe in self.probes.iter().filter(|probe| probe.kind == *kind) {
+            probe.refresh.send_replace(());
+        }
+    }
+}
+
+impl Drop for StatusLineProbes {
+    fn drop(&mut self) {
+        for probe in &self.probes {
+            probe.task.abort();
+        }
+    }
+}
+
+async fn run_probe(probe: Probe, mut refresh: watch::Receiver<()>, tx: AppEventSender) {
+    let mut last = None;
+    loop {
+        if let Ok(update) = tokio::time::timeout(PROBE_TIMEOUT, (probe.run)()).await {
+            tx.send(AppEvent::StatusLineProbe(report(&mut last, update)));
+        }
+        tokio::select! {
+            () = tokio::time::sleep(probe.interval) => {}
+            changed = refresh.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// `update` as a report against the `last` one delivered, which it replaces.
+fn report(last: &mut Option<ProbeUpdate>, update: ProbeUpdate) -> ProbeReport {
+    if last.as_ref() == Some(&update) {
+        return ProbeReport::Unchanged(update.kind());
+    }
+    *last = Some(update.clone());
+    ProbeReport::Changed(update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn custom(text: &str) -> ProbeUpdate {
+        ProbeUpdate::Custom {
+            name: "weather".to_string(),
+            text: Some(text.to_string()),
+        }
+    }
+
+    #[test]
+    fn identical_results_are_reported_unchanged() {
+        let mut last = None;
+        assert!(matches!(
+            report(&mut last, custom("sunny")),
+            ProbeReport::Changed(update) if update == custom("sunny")
+        ));
+        assert!(matches!(
+            report(&mut last, custom("sunny")),
+            ProbeReport::Unchanged(ProbeKind::Custom(name)) if name == "weather"
+        ));
+        assert!(matches!(
+            report(&mut last, custom("rain")),
+            ProbeReport::Changed(update) if update == custom("rain")
+        ));
+        assert_eq!(last, Some(custom("rain")));
+    }
+
+    #[tokio::test]
+    async fn refresh_reruns_a_probe_before_its_interval() {
+        let (tx, mut rx) = unbounded_channel();
+        let probes = StatusLineProbes::spawn(
+            vec![Probe::new(
+                ProbeKind::AwsProfile,
+                Duration::from_secs(3600),
+                || async { ProbeUpdate::AwsProfile(Some("dev".to_string())) },
+            )],
+            AppEventSender::new(tx),
+        );
+        let first = rx.recv().await;
+        assert!(matches!(
+            first,
+            Some(AppEvent::StatusLineProbe(ProbeReport::Changed(
+                ProbeUpdate::AwsProfile(Some(_))
+            )))
+        ));
+
+        probes.refresh(&ProbeKind::AwsProfile);
+        let second = rx.recv().await;
+        assert!(matches!(
+            second,
+            Some(AppEvent::StatusLineProbe(ProbeReport::Unchanged(
+                ProbeKind::AwsProfile
+            )))
+        ));
+    }
+}
@@ -0,0 +1,334 @@
+//! Time spent running a turn, paused while Codex waits on the user, and a
+//! set of such timers for runs that proceed side by side, e.g. sub-agents.
+//!
+//! Callers hand in `now`, and an `Instant` taken before the last resume
+//! (say, one captured at the start of a frame that a resume landed in)
+//! counts as no time passing rather than panicking or running backwards.
+//! Totals saturate instead of overflowing.
+
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::hash::Hash;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::RunTimerSnapshot;
+
+#[derive(Debug)]
+pub(crate) struct RunTimer {
+    /// Running time up to the last pause.
+    elapsed_running: Duration,
+    last_resume_at: Option<Instant>,
+    /// Paused time up to the last resume.
+    elapsed_paused: Duration,
+    last_pause_at: Option<Instant>,
+    is_paused: bool,
+    spinner_started_at: Instant,
+    /// Latest instant passed in, so a resume never starts before time that
+    /// was already counted.
+    latest: Instant,
+}
+
+impl RunTimer {
+    pub(crate) fn new(now: Instant) -> Self {
+        Self {
+            elapsed_running: Duration::ZERO,
+            last_resume_at: Some(now),
+            elapsed_paused: Duration::ZERO,
+            last_pause_at: None,
+            is_paused: false,
+            spinner_started_at: now,
+            latest: now,
+        }
+    }
+
+    pub(crate) fn resume(&mut self, now: Instant) {
+        self.latest = self.latest.max(now);
+        if self.is_paused {
+            self.elapsed_paused = self.paused(self.latest);
+            self.last_resume_at = Some(self.latest);
+            self.is_paused = false;
+        }
+    }
+
+    pub(crate) fn pause(&mut self, now: Instant) {
+        self.latest = self.latest.max(now);
+        if self.is_paused {
+            return;
+        }
+        self.elapsed_running = self.elapsed(now);
+        self.last_pause_at = Some(self.latest);
+        self.is_paused = true;
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    pub(crate) fn spinner_started_at(&self) -> Instant {
+        self.spinner_started_at
+    }
+
+    /// Running time up to `now`.
+    pub(crate) fn elapsed(&self, now: Instant) -> Duration {
+        match self.last_resume_at {
+            Some(last) if !self.is_paused => self
+                .elapsed_running
+                .saturating_add(now.saturating_duration_since(last)),
+            _ => self.elapsed_running,
+        }
+    }
+
+    /// Paused time up to `now`; the wall time of the run is this plus
+    /// [`Self::elapsed`].
+    pub(crate) fn paused(&self, now: Instant) -> Duration {
+        match self.last_pause_at {
+            Some(last) if self.is_paused => self
+                .elapsed_paused
+                .saturating_add(now.saturating_duration_since(last)),
+            _ => self.elapsed_paused,
+        }
+    }
+
+    /// The timer for rendering, which adds the time since the last resume
+    /// itself.
+    pub(crate) fn snapshot(&self) -> RunTimerSnapshot {
+        RunTimerSnapshot {
+            elapsed_running: self.elapsed_running,
+            last_resume_at: self.last_resume_at,
+            elapsed_paused: self.elapsed_paused,
+            last_pause_at: self.last_pause_at,
+            is_paused: self.is_paused,
+        }
+    }
+}
+
+/// One [`RunTimer`] per concurrent run, e.g. the main turn and each
+/// sub-agent it spawned. Test-only until the status line shows sub-agent
+/// runs.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct RunTimers<K> {
+    timers: HashMap<K, RunTimer>,
+}
+
+#[cfg(test)]
+impl<K: Eq + Hash> RunTimers<K> {
+    pub(crate) fn new() -> Self {
+        Self {
+            timers: HashMap::new(),
+        }
+    }
+
+    /// Start timing `run`, or resume it when it was paused.
+    pub(crate) fn start(&mut self, run: K, now: Instant) {
+        self.timers
+            .entry(run)
+            .and_modify(|timer| timer.resume(now))
+            .or_insert_with(|| RunTimer::new(now));
+    }
+
+    pub(crate) fn pause(&mut self, run: &K, now: Instant) {
+        if let Some(timer) = self.timers.get_mut(run) {
+            timer.pause(now);
+        }
+    }
+
+    /// Pause every run, e.g. while an approval prompt waits on the user.
+    pub(crate) fn pause_all(&mut self, now: Instant) {
+        for timer in self.timers.values_mut() {
+            timer.pause(now);
+        }
+    }
+
+    pub(crate) fn resume_all(&mut self, now: Instant) {
+        for timer in self.timers.values_mut() {
+            timer.resume(now);
+        }
+    }
+
+    /// Stop timing `run`; returns its running time.
+    pub(crate) fn finish(&mut self, run: &K, now: Instant) -> Option<Duration> {
+        let mut timer = self.timers.remove(run)?;
+        timer.pause(now);
+        Some(timer.elapsed(now))
+    }
+
+    pub(crate) fn get(&self, run: &K) -> Option<&RunTimer> {
+        self.timers.get(run)
+    }
+
+    /// Runs currently running, not paused.
+    pub(crate) fn running(&self) -> usize {
+        self.timers
+            .values()
+            .filter(|timer| !timer.is_paused())
+            .count()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::prelude::*;
+
+    const CASES: u64 = 500;
+    const STEPS: usize = 40;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn pause_and_resume_are_idempotent() {
+        let start = Instant::now();
+        let mut timer = RunTimer::new(start);
+        timer.pause(start + secs(3));
+        timer.pause(start + secs(10));
+        assert_eq!(timer.elapsed(start + secs(20)), secs(3));
+
+        timer.resume(start + secs(20));
+        timer.resume(start + secs(25));
+        assert_eq!(timer.elapsed(start + secs(30)), secs(13));
+    }
+
+    #[test]
+    fn snapshot_does_not_count_the_current_run_twice() {
+        let start = Instant::now();
+        let mut timer = RunTimer::new(start);
+        timer.pause(start + secs(5));
+        timer.resume(start + secs(8));
+        let now = start + secs(10);
+        let snapshot = timer.snapshot();
+        assert_eq!(snapshot.elapsed_running, secs(5));
+        assert_eq!(snapshot.elapsed_at(now), timer.elapsed(now));
+    }
+
+    #[test]
+    fn paused_time_adds_up_to_the_wall_time() {
+        let start = Instant::now();
+        let mut timer = RunTimer::new(start);
+        timer.pause(start + secs(5));
+        timer.resume(start + secs(8));
+        timer.pause(start + secs(10));
+        let now = start + secs(14);
+        assert_eq!(timer.elapsed(now), secs(7));
+        assert_eq!(timer.paused(now), secs(7));
+        assert_eq!(timer.snapshot().paused_at(now), secs(7));
+
+        timer.resume(now);
+        let later = start + secs(20);
+        assert_eq!(timer.paused(later), secs(7));
+        assert_eq!(timer.elapsed(later) + timer.paused(later), secs(20));
+        assert_eq!(timer.snapshot().paused_at(later), secs(7));
+    }
+
+    #[test]
+    fn total_saturates_instead_of_overflowing() {
+        let start = Instant::now();
+        let mut timer = RunTimer::new(start);
+        timer.elapsed_running = Duration::MAX - secs(1);
+        assert_eq!(timer.elapsed(start + secs(5)), Duration::MAX);
+        timer.pause(start + secs(5));
+        timer.resume(start + secs(6));
+        assert_eq!(timer.elapsed(start + secs(60)), Duration::MAX);
+    }
+
+    /// With time moving forward, the timer matches a model that sums the
+    /// running intervals, whatever the order of pauses, resumes and reads.
+    #[test]
+    fn elapsed_matches_the_sum_of_running_intervals() {
+        for seed in 0..CASES {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let start = Instant::now();
+            let mut now = start;
+            let mut timer = RunTimer::new(now);
+            let mut running_since = Some(now);
+            let mut expected = Duration::ZERO;
+            for _ in 0..STEPS {
+                now += Duration::from_millis(rng.random_range(0..5_000));
+                match rng.random_range(0..3) {
+                    0 => {
+                        timer.pause(now);
+                        if let Some(since) = running_since.take() {
+                            expected += now - since;
+                        }
+                    }
+                    1 => {
+                        timer.resume(now);
+                        running_since.get_or_insert(now);
+                    }
+                    _ => {}
+                }
+                let model = expected + running_since.map_or(Duration::ZERO, |since| now - since);
+                assert_eq!(timer.elapsed(now), model, "seed {seed}");
+                assert_eq!(timer.snapshot().elapsed_at(now), model, "seed {seed}");
+                assert_eq!(timer.is_paused(), running_since.is_none(), "seed {seed}");
+            }
+        }
+    }
+
+    /// With `now` jumping back and forth, the time counted never decreases
+    /// across pauses and never exceeds the span of instants seen.
+    #[test]
+    fn clock_jumps_never_run_the_timer_backwards_or_ahead() {
+        for seed in 0..CASES {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let start = Instant::now() + secs(3_600);
+            let mut timer = RunTimer::new(start);
+            let mut latest = start;
+            let mut counted = Duration::ZERO;
+            for _ in 0..STEPS {
+                let offset = Duration::from_millis(rng.random_range(0..10_000));
+                let now = if rng.random_bool(0.3) {
+                    latest.checked_sub(offset).unwrap_or(latest)
+                } else {
+                    latest + offset
+                };
+                latest = latest.max(now);
+                if rng.random_bool(0.5) {
+                    timer.pause(now);
+                } else {
+                    timer.resume(now);
+                }
+                let paused_total = timer.snapshot().elapsed_running;
+                assert!(paused_total >= counted, "seed {seed}");
+                counted = paused_total;
+                assert!(
+                    timer.elapsed(now) <= latest.duration_since(start),
+                    "seed {seed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn concurrent_runs_are_timed_independently() {
+        let start = Instant::now();
+        let mut timers = RunTimers::new();
+        timers.start("main", start);
+        timers.start("reviewer", start + secs(2));
+        assert_eq!(timers.running(), 2);
+
+        timers.pause_all(start + secs(5));
+        assert_eq!(timers.running(), 0);
+        timers.resume_all(start + secs(9));
+        timers.pause(&"reviewer", start + secs(10));
+
+        assert_eq!(timers.finish(&"reviewer", start + secs(30)), Some(secs(4)));
+        assert_eq!(
+            timers
+                .get(&"main")
+                .map(|timer| timer.elapsed(start + secs(12))),
+            Some(secs(8))
+        );
+        assert_eq!(timers.finish(&"main", start + secs(12)), Some(secs(8)));
+        assert!(timers.is_empty());
+        assert_eq!(timers.finish(&"main", start + secs(13)), None);
+    }
+}
@@ -0,0 +1,372 @@
+//! Status line fixtures rendered at a range of widths into one insta
+//! snapshot each, so a change to a segment or to the order segments shrink
+//! and drop out in shows up as a reviewable diff of every width at once.
+//!
+//! Each fixture is rendered with the built-in layout and with a template
+//! that places every segment, and one also with `statusline.lines = 2` and
+//! with `statusline.style = "plain"`. Rows show the text only; the styled
+//! spans are covered by the `statusline_*` snapshots in `mod.rs`.
+
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::config_types::StatusLineItem;
+use codex_core::config_types::StatusLineLayout;
+use codex_core::config_types::StatusLineStyle;
+use codex_core::system_info::Battery;
+use codex_core::system_info::Capacity;
+use codex_core::system_info::SystemResources;
+use insta::assert_snapshot;
+use ratatui::text::Line;
+use unicode_width::UnicodeWidthStr;
+
+use super::EnvironmentRefreshTimes;
+use super::RunTimerSnapshot;
+use super::StatusLineContextSnapshot;
+use super::StatusLineCostSnapshot;
+use super::StatusLineDevspaceSnapshot;
+use super::StatusLineDockerSnapshot;
+use super::StatusLineEnvironmentSnapshot;
+use super::StatusLineGitSnapshot;
+use super::StatusLineModelSnapshot;
+use super::StatusLineRenderer;
+use super::StatusLineRunState;
+use super::StatusLineSnapshot;
+use super::StatusLineTokenSnapshot;
+use super::TokenCountSnapshot;
+
+pub(crate) const WIDTHS: [u16; 8] = [20, 30, 40, 50, 60, 80, 100, 140];
+
+/// `snapshot` rendered by `renderer` at each of [`WIDTHS`], one row per
+/// width, padded to the width so overflow and slack are visible.
+pub(crate) fn render_matrix(
+    renderer: &StatusLineRenderer,
+    snapshot: &StatusLineSnapshot,
+    now: Instant,
+) -> String {
+    WIDTHS
+        .iter()
+        .map(|&width| {
+            row(
+                &width.to_string(),
+                width,
+                &renderer.render(snapshot, width, now),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// [`render_matrix`] for `statusline.lines = 2`, two rows per width.
+fn render_two_line_matrix(
+    renderer: &StatusLineRenderer,
+    snapshot: &StatusLineSnapshot,
+    now: Instant,
+) -> String {
+    WIDTHS
+        .iter()
+        .map(|&width| {
+            let [run_state, details] = renderer
+                .render_two_rows(snapshot, width, now)
+                .map(|row| row.line);
+            format!(
+                "{}\n{}",
+                row(&width.to_string(), width, &run_state),
+                row("", width, &details)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn row(label: &str, width: u16, line: &Line<'_>) -> String {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let slack = usize::from(width).saturating_sub(UnicodeWidthStr::width(text.as_str()));
+    format!("{label:>3} │{text}{}│", " ".repeat(slack))
+}
+
+/// [`render_matrix`] for the built-in layout and for [`template_layout`].
+fn layouts_matrix(snapshot: &StatusLineSnapshot, style: StatusLineStyle, now: Instant) -> String {
+    let builtin = StatusLineRenderer::new(None, true).with_style(style);
+    let template =
+        StatusLineRenderer::new(Some(template_layout(snapshot)), false).with_style(style);
+    format!(
+        "built-in layout\n{}\n\ntemplate layout\n{}",
+        render_matrix(&builtin, snapshot, now),
+        render_matrix(&template, snapshot, now)
+    )
+}
+
+/// Every segment, the run state and the working directory on the left and
+/// the environment on the right.
+pub(crate) fn template_layout(snapshot: &StatusLineSnapshot) -> StatusLineLayout {
+    let mut right = vec![
+        StatusLineItem::Git,
+        StatusLineItem::Devspace,
+        StatusLineItem::Hostname,
+        StatusLineItem::Python,
+        StatusLineItem::Node,
+        StatusLineItem::Aws,
+        StatusLineItem::Gcp,
+        StatusLineItem::Azure,
+        StatusLineItem::Kubernetes,
+        StatusLineItem::Docker,
+        StatusLineItem::Terraform,
+        StatusLineItem::Resources,
+        StatusLineItem::Battery,
+        StatusLineItem::Clock,
+    ];
+    right.extend(
+        snapshot
+            .environment
+            .custom
+            .iter()
+            .map(|(name, _)| StatusLineItem::Custom(name.clone())),
+    );
+    StatusLineLayout {
+        left: vec![
+            StatusLineItem::Status,
+            StatusLineItem::Cwd,
+            StatusLineItem::Model,
+            StatusLineItem::Cost,
+            StatusLineItem::Tokens,
+            StatusLineItem::Context,
+        ],
+        right,
+    }
+}
+
+fn tokens(total: u64) -> StatusLineTokenSnapshot {
+    StatusLineTokenSnapshot {
+        total: TokenCountSnapshot {
+            total_tokens: total,
+            input_tokens: total * 2 / 3,
+            cached_input_tokens: total / 6,
+            output_tokens: total / 3,
+            reasoning_output_tokens: 0,
+        },
+        last: None,
+        per_minute: None,
+    }
+}
+
+fn paused_run(label: &str, elapsed: Duration, now: Instant) -> StatusLineRunState {
+    StatusLineRunState {
+        label: label.to_string(),
+        spinner_started_at: None,
+        timer: Some(RunTimerSnapshot {
+            elapsed_running: elapsed,
+            last_resume_at: None,
+            elapsed_paused: Duration::ZERO,
+            last_pause_at: None,
+            is_paused: true,
+        }),
+        queued_messages: Arc::from([]),
+        show_interrupt_hint: true,
+        status_changed_at: now,
+    }
+}
+
+/// Outside a git repository, idle, with only the hostname known.
+pub(crate) fn no_git_fixture(now: Instant) -> StatusLineSnapshot {
+    StatusLineSnapshot {
+        cwd_display: Some("~/scratch".to_string()),
+        cwd_basename: Some("scratch".to_string()),
+        cwd_fallback: Some("scratch".to_string()),
+        model: Some(StatusLineModelSnapshot {
+            label: "gpt-5-codex".to_string(),
+            detail: Some("medium".to_string()),
+        }),
+        tokens: Some(tokens(0)),
+        context: Some(StatusLineContextSnapshot {
+            percent_remaining: 100,
+            tokens_in_context: 0,
+            window: 272_000,
+            flash_since: None,
+        }),
+        run_state: Some(StatusLineRunState {
+            status_changed_at: now,
+            ..StatusLineRunState::default()
+        }),
+        environment: StatusLineEnvironmentSnapshot {
+            hostname: Some("buildbox".to_string()),
+            ..StatusLineEnvironmentSnapshot::default()
+        },
+        ..StatusLineSnapshot::default()
+    }
+}
+
+/// A branch name far wider than any terminal, with conflicts, stashes and
+/// a diverged upstream, while a long-running command is in progress.
+pub(crate) fn long_branch_fixture(now: Instant) -> StatusLineSnapshot {
+    StatusLineSnapshot {
+        cwd_display: Some("~/src/platform/services/billing".to_string()),
+        cwd_basename: Some("billing".to_string()),
+        cwd_fallback: Some("billing".to_string()),
+        model: Some(StatusLineModelSnapshot {
+            label: "gpt-5-codex".to_string(),
+            detail: Some("high".to_string()),
+        }),
+        tokens: Some(tokens(184_500)),
+        context: Some(StatusLineContextSnapshot {
+            percent_remaining: 12,
+            tokens_in_context: 239_000,
+            window: 272_000,
+            flash_since: None,
+        }),
+        run_state: Some(paused_run(
+            "Running cargo test --workspace --all-features",
+            Duration::from_secs(754),
+            now,
+        )),
+        git: Some(StatusLineGitSnapshot {
+            branch: Some(
+                "feature/BILL-4821-migrate-invoice-rendering-to-the-new-templating-pipeline"
+                    .to_string(),
+            ),
+            dirty: true,
+            ahead: Some(3),
+            behind: Some(12),
+            stash_count: 2,
+            conflicts: 4,
+        }),
+        cost: Some(StatusLineCostSnapshot {
+            last_turn_usd: 0.87,
+            session_usd: 14.2,
+        }),
+        ..StatusLineSnapshot::default()
+    }
+}
+
+/// Every segment populated, mid-turn with queued messages and a rate-limit
+/// countdown.
+pub(crate) fn all_segments_fixture(now: Instant) -> StatusLineSnapshot {
+    const GIB: u64 = 1024 * 1024 * 1024;
+    let mut run = paused_run("Editing 3 files", Duration::from_secs(42), now);
+    run.queued_messages = Arc::from(["run the tests".to_string(), "then commit".to_string()]);
+    StatusLineSnapshot {
+        cwd_display: Some("~/workspace/codex".to_string()),
+        cwd_basename: Some("codex".to_string()),
+        cwd_fallback: Some("codex".to_string()),
+        model: Some(StatusLineModelSnapshot {
+            label: "gpt-5-codex".to_string(),
+            detail: Some("high".to_string()),
+        }),
+        tokens: Some(tokens(48_234)),
+        context: Some(StatusLineContextSnapshot {
+            percent_remaining: 68,
+            tokens_in_context: 52_000,
+            window: 160_000,
+            flash_since: None,
+        }),
+        run_state: Some(run),
+        git: Some(StatusLineGitSnapshot {
+            branch: Some("main".to_string()),
+            dirty: true,
+            ahead: Some(1),
+            behind: Some(0),
+            stash_count: 1,
+            conflicts: 0,
+        }),
+        environment: StatusLineEnvironmentSnapshot {
+            devspace: Some(StatusLineDevspaceSnapshot {
+                name: "earth".to_string(),
+            }),
+            hostname: Some("vermissian".to_string()),
+            aws_profile: Some("prod".to_string()),
+            gcp_project: Some("codex-staging".to_string()),
+            azure_subscription: Some("Contoso Dev".to_string()),
+            kubernetes_context: Some("gke_codex_us-central1_main".to_string()),
+            python_env: Some(".venv".to_string()),
+            node_version: Some("22.11.0".to_string()),
+            docker: Some(StatusLineDockerSnapshot {
+                context: "colima".to_string(),
+                reachable: Some(false),
+            }),
+            terraform_workspace: Some("staging".to_string()),
+            resources: Some(SystemResources {
+                load_average: Some([3.52, 2.1, 1.4]),
+                cpus: 8,
+                memory: Some(Capacity {
+                    available: 6 * GIB,
+                    total: 16 * GIB,
+                }),
+                disk: Some(Capacity {
+                    available: 120 * GIB,
+                    total: 500 * GIB,
+                }),
+            }),
+            clock: Some("14:05".to_string()),
+            battery: Some(Battery {
+                percent: 27,
+                plugged_in: false,
+            }),
+            custom: vec![("deploy".to_string(), Some("v2.4.1 live".to_string()))],
+            refreshed_at: EnvironmentRefreshTimes::default(),
+        },
+        cost: Some(StatusLineCostSnapshot {
+            last_turn_usd: 0.04,
+            session_usd: 1.27,
+        }),
+        rate_limit_cooldown: Some(Duration::from_secs(95)),
+        session_id: None,
+        mcp: None,
+        sandbox: None,
+        network: None,
+    }
+}
+
+#[test]
+fn statusline_matrix_two_lines() {
+    let now = Instant::now();
+    let snapshot = all_segments_fixture(now);
+    let builtin = StatusLineRenderer::new(None, true);
+    let template = StatusLineRenderer::new(Some(template_layout(&snapshot)), false);
+    let matrix = format!(
+        "built-in layout\n{}\n\ntemplate layout\n{}",
+        render_two_line_matrix(&builtin, &snapshot, now),
+        render_two_line_matrix(&template, &snapshot, now)
+    );
+    assert_snapshot!("statusline_matrix_two_lines", matrix);
+}
+
+#[test]
+fn statusline_matrix_no_git() {
+    let now = Instant::now();
+    assert_snapshot!(
+        "statusline_matrix_no_git",
+        layouts_matrix(&no_git_fixture(now), StatusLineStyle::Powerline, now)
+    );
+}
+
+#[test]
+fn statusline_matrix_long_branch() {
+    let now = Instant::now();
+    assert_snapshot!(
+        "statusline_matrix_long_branch",
+        layouts_matrix(&long_branch_fixture(now), StatusLineStyle::Powerline, now)
+    );
+}
+
+#[test]
+fn statusline_matrix_all_segments() {
+    let now = Instant::now();
+    assert_snapshot!(
+        "statusline_matrix_all_segments",
+        layouts_matrix(&all_segments_fixture(now), StatusLineStyle::Powerline, now)
+    );
+}
+
+#[test]
+fn statusline_matrix_plain() {
+    let now = Instant::now();
+    assert_snapshot!(
+        "statusline_matrix_plain",
+        layouts_matrix(&all_segments_fixture(now), StatusLineStyle::Plain, now)
+    );
+}
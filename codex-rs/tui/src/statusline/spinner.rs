@@ -0,0 +1,159 @@
+//! Frames of the run spinner in the status capsule, from
+//! `statusline.spinner` and `statusline.spinner_interval_ms`. The animation
+//! is a function of the time since the run's spinner started, so every
+//! redraw in the same frame interval shows the same glyph. Terminals
+//! without Unicode get the ascii glyphs whatever is configured.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_core::config_types::StatusLineSpinner;
+use ratatui::style::Stylize;
+use ratatui::text::Span;
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Spinner {
+    glyphs: StatusLineSpinner,
+    interval: Duration,
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::new(StatusLineSpinner::default(), None)
+    }
+}
+
+impl Spinner {
+    /// `interval` overrides the glyph set's own speed.
+    pub(crate) fn new(glyphs: StatusLineSpinner, interval: Option<Duration>) -> Self {
+        let default_interval = match glyphs {
+            StatusLineSpinner::Blink => 600,
+            StatusLineSpinner::Dots => 200,
+            StatusLineSpinner::Braille => 80,
+            StatusLineSpinner::Ascii => 120,
+        };
+        Self {
+            glyphs,
+            interval: interval.unwrap_or(Duration::from_millis(default_interval)),
+        }
+    }
+
+    /// `statusline.spinner`, or the ascii glyphs when the locale is not
+    /// UTF-8 or the terminal is the Linux console.
+    pub(crate) fn from_config(glyphs: StatusLineSpinner, interval: Option<Duration>) -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+        let term = std::env::var("TERM").ok();
+        if supports_unicode(locale.as_deref(), term.as_deref()) {
+            Self::new(glyphs, interval)
+        } else {
+            Self::new(StatusLineSpinner::Ascii, interval)
+        }
+    }
+
+    /// How long each frame is shown.
+    pub(crate) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn frames(&self) -> &'static [&'static str] {
+        match self.glyphs {
+            StatusLineSpinner::Blink => &["•", "◦"],
+            StatusLineSpinner::Dots => &["·", "•", "●", "•"],
+            StatusLineSpinner::Braille => &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"],
+            StatusLineSpinner::Ascii => &["|", "/", "-", "\\"],
+        }
+    }
+
+    /// The glyph to show at `now` for a spinner started at `started_at`;
+    /// without a start it stands still, dimmed.
+    pub(crate) fn frame(&self, started_at: Option<Instant>, now: Instant) -> Span<'static> {
+        let frames = self.frames();
+        let Some(started_at) = started_at else {
+            return frames[frames.len() - 1].dim();
+        };
+        let step = now.saturating_duration_since(started_at).as_millis()
+            / self.interval.as_millis().max(1);
+        let index = (step % frames.len() as u128) as usize;
+        let glyph = frames[index];
+        // The blink's second frame is the first one turned off.
+        if self.glyphs == StatusLineSpinner::Blink && index == 1 {
+            glyph.dim()
+        } else {
+            glyph.into()
+        }
+    }
+}
+
+/// Whether a terminal with `locale` (the first of `LC_ALL`, `LC_CTYPE` and
+/// `LANG` that is set, as for setlocale(3)) and `term` draws Unicode. An
+/// unset locale is taken to, as most terminal emulators do.
+fn supports_unicode(locale: Option<&str>, term: Option<&str>) -> bool {
+    let utf8_locale = locale.is_none_or(|locale| {
+        let locale = locale.to_ascii_lowercase();
+        locale.contains("utf-8") || locale.contains("utf8")
+    });
+    utf8_locale && term != Some("linux")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn glyphs(spinner: Spinner, started_at: Instant, steps: u32) -> Vec<String> {
+        (0..steps)
+            .map(|step| {
+                spinner
+                    .frame(Some(started_at), started_at + spinner.interval() * step)
+                    .content
+                    .into_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn frames_advance_once_per_interval() {
+        let started_at = Instant::now();
+        let ascii = Spinner::new(StatusLineSpinner::Ascii, Some(Duration::from_millis(50)));
+        assert_eq!(glyphs(ascii, started_at, 5), vec!["|", "/", "-", "\\", "|"]);
+        assert_eq!(
+            ascii
+                .frame(Some(started_at), started_at + Duration::from_millis(49))
+                .content,
+            "|"
+        );
+
+        let blink = Spinner::default();
+        assert_eq!(blink.interval(), Duration::from_millis(600));
+        assert_eq!(glyphs(blink, started_at, 3), vec!["•", "◦", "•"]);
+    }
+
+    #[test]
+    fn unicode_follows_the_locale_and_terminal() {
+        assert!(supports_unicode(
+            Some("en_US.UTF-8"),
+            Some("xterm-256color")
+        ));
+        assert!(supports_unicode(Some("C.utf8"), None));
+        assert!(supports_unicode(None, Some("xterm")));
+        assert!(!supports_unicode(Some("C"), Some("xterm")));
+        assert!(!supports_unicode(Some("en_US.ISO-8859-1"), None));
+        assert!(!supports_unicode(Some("en_US.UTF-8"), Some("linux")));
+    }
+
+    #[test]
+    fn an_idle_spinner_stands_still_and_dimmed() {
+        let now = Instant::now();
+        let idle = Spinner::default().frame(None, now);
+        assert_eq!(idle.content, "◦");
+        assert_eq!(idle, "◦".dim());
+        assert_eq!(
+            Spinner::new(StatusLineSpinner::Braille, None)
+                .frame(None, now)
+                .content,
+            "⣷"
+        );
+    }
+}
@@ -1,58 +1,179 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
+use chrono::Timelike;
 use codex_core::config::Config;
+use codex_core::config_types::ModelPricing;
+use codex_core::config_types::StatusLineContextLevel;
+use codex_core::config_types::StatusLineContextThresholds;
+use codex_core::config_types::StatusLineStyle;
+use codex_core::pricing::model_pricing;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::McpServerState;
+use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol_config_types::ReasoningEffort;
+use codex_core::system_info::Battery;
+use codex_core::system_info::SystemResources;
 use ratatui::text::Line;
 use unicode_width::UnicodeWidthStr;
 
+use crate::clock::Clock;
 use crate::status::format_directory_display;
 use crate::tui::FrameRequester;
 
+use super::CONTEXT_FLASH_DURATION;
+use super::CONTEXT_FLASH_INTERVAL;
 use super::DEFAULT_STATUS_MESSAGE;
 use super::MARQUEE_STEP_MS;
 use super::RunTimerSnapshot;
 use super::STATUS_CAPSULE_TEXT_WIDTH;
 use super::StatusLineContextSnapshot;
+use super::StatusLineCostSnapshot;
 use super::StatusLineDevspaceSnapshot;
+use super::StatusLineDockerSnapshot;
 use super::StatusLineGitSnapshot;
+use super::StatusLineMcpSnapshot;
 use super::StatusLineModelSnapshot;
+use super::StatusLineNetworkSnapshot;
 use super::StatusLineRenderer;
+use super::StatusLineRow;
 use super::StatusLineRunState;
+use super::StatusLineSandboxSnapshot;
 use super::StatusLineSnapshot;
+use super::StatusLineTheme;
 use super::StatusLineTokenSnapshot;
 use super::TokenCountSnapshot;
+use super::command::StatusLineCommand;
+use super::command::snapshot_json;
+use super::probes::ProbeKind;
+use super::run_timer::RunTimer;
+use super::spinner::Spinner;
 
 #[derive(Debug)]
 pub(crate) struct StatusLineState {
     cwd: PathBuf,
     frame_requester: FrameRequester,
+    /// The frame requester's clock.
+    clock: Arc<dyn Clock>,
     renderer: StatusLineRenderer,
     snapshot: StatusLineSnapshot,
     run_timer: Option<RunTimer>,
-    queued_messages: Vec<String>,
+    /// Shared with the run state so each frame's snapshot copies a pointer,
+    /// not the queued text.
+    queued_messages: Arc<[String]>,
     esc_hint: bool,
     context_window_hint: Option<u64>,
+    pricing_overrides: HashMap<String, ModelPricing>,
+    cost: CostTracker,
+    token_rate: TokenRate,
+    context_thresholds: StatusLineContextThresholds,
+    /// `statusline.context_flash`.
+    context_flash: bool,
+    /// Whether the context was past the critical threshold at the last
+    /// token update.
+    context_critical: bool,
+    /// When the context crossed the critical threshold, if it flashes.
+    context_flash_since: Option<Instant>,
+    /// Percent left when the context crossed the critical threshold, until
+    /// the transcript says so; see [`Self::take_context_critical_notice`].
+    context_critical_notice: Option<u8>,
+    /// The transcript said so already; once per conversation.
+    context_critical_noticed: bool,
+    rate_limited_until: Option<Instant>,
+    show_python_env: bool,
+    show_clock: bool,
+    /// `statusline.terminal_title`.
+    terminal_title: bool,
+    /// `statusline.stale_after_secs`.
+    stale_after: Option<Duration>,
+    /// `statusline.lines`.
+    lines: u16,
+    /// `statusline.command`, drawing the line instead of the renderer.
+    command: Option<StatusLineCommand>,
+    /// Bumped by every change to what the line shows.
+    revision: u64,
+    /// The rows last rendered; see [`Self::render_rows`].
+    render_cache: RefCell<Option<(RenderKey, Vec<StatusLineRow>)>>,
 }
 
 impl StatusLineState {
     pub(crate) fn new(config: &Config, frame_requester: FrameRequester) -> Self {
         let cwd = config.cwd.clone();
+        let command = config
+            .statusline
+            .command
+            .clone()
+            .map(|command| StatusLineCommand::new(command, frame_requester.clone()));
         let mut state = Self {
             cwd: cwd.clone(),
+            clock: frame_requester.clock(),
             frame_requester,
-            renderer: StatusLineRenderer,
+            renderer: StatusLineRenderer::new(
+                config.statusline.layout.clone(),
+                config.statusline.show_cost,
+            )
+            .with_style(effective_style(config.statusline.style))
+            .with_theme(StatusLineTheme::from_config(&config.statusline))
+            .with_spinner(Spinner::from_config(
+                config.statusline.spinner,
+                config.statusline.spinner_interval,
+            ))
+            .with_time_format(config.statusline.time_format)
+            .with_wall_time(config.statusline.show_wall_time)
+            .with_queue_preview(config.statusline.queue_preview)
+            .with_session(config.statusline.shows_session())
+            .with_mcp(config.statusline.shows_mcp())
+            .with_sandbox(config.statusline.shows_sandbox())
+            .with_network(config.statusline.shows_network())
+            .with_hostname(
+                config.statusline.hostname,
+                is_ssh_session(|key| std::env::var(key).ok()),
+            )
+            .with_staleness(config.statusline.stale_after, config.statusline.stale),
             snapshot: StatusLineSnapshot::default(),
             run_timer: None,
-            queued_messages: Vec::new(),
+            queued_messages: Arc::from([]),
             esc_hint: true,
             context_window_hint: config.model_context_window,
+            pricing_overrides: config.model_pricing.clone(),
+            cost: CostTracker::default(),
+            token_rate: TokenRate::default(),
+            context_thresholds: config.statusline.context_thresholds,
+            context_flash: config.statusline.context_flash,
+            context_critical: false,
+            context_flash_since: None,
+            context_critical_notice: None,
+            context_critical_noticed: false,
+            rate_limited_until: None,
+            show_python_env: config.statusline.show_python_env,
+            show_clock: config.statusline.shows_clock(),
+            terminal_title: config.statusline.terminal_title,
+            stale_after: config.statusline.stale_after,
+            lines: config.statusline.lines,
+            command,
+            revision: 0,
+            render_cache: RefCell::new(None),
         };
+        state.snapshot.environment.custom = config
+            .statusline
+            .custom_segments
+            .iter()
+            .map(|segment| (segment.name.clone(), None))
+            .collect();
+        state.snapshot.network =
+            proxy_host(|key| std::env::var(key).ok()).map(|proxy| StatusLineNetworkSnapshot {
+                proxy: Some(proxy),
+                offline: false,
+            });
         state.set_working_directory(&cwd);
-        state.set_idle_run_state(Instant::now());
+        state.set_idle_run_state(state.clock.now());
         state
     }
 
@@ -66,7 +187,11 @@ impl StatusLineState {
         self.snapshot.cwd_display = Some(display.clone());
         self.snapshot.cwd_basename = basename.clone();
         self.snapshot.cwd_fallback = basename.or(Some(display));
-        self.request_redraw();
+        self.snapshot.environment.python_env = self
+            .show_python_env
+            .then(|| python_env_name(cwd, |key| std::env::var(key).ok()))
+            .flatten();
+        self.set_terraform_workspace(terraform_workspace(cwd, |key| std::env::var(key).ok()));
     }
 
     pub(crate) fn update_model(
@@ -74,28 +199,71 @@ impl StatusLineState {
         label: impl Into<String>,
         effort: Option<ReasoningEffort>,
     ) {
+        let label = label.into();
+        self.cost.pricing = model_pricing(&label, &self.pricing_overrides);
+        self.snapshot.cost = self.cost.snapshot();
         let detail = reasoning_detail(effort);
-        self.snapshot.model = Some(StatusLineModelSnapshot {
-            label: label.into(),
-            detail,
-        });
+        self.snapshot.model = Some(StatusLineModelSnapshot { label, detail });
         self.request_redraw();
     }
 
     pub(crate) fn update_tokens(&mut self, info: Option<TokenUsageInfo>) {
         if let Some(info) = info {
             let context_window = info.model_context_window.or(self.context_window_hint);
-            let (token_snapshot, context_snapshot) =
+            let (mut token_snapshot, context_snapshot) =
                 token_snapshot_from_info(&info, context_window);
+            self.cost.record(&token_snapshot.total);
+            self.token_rate
+                .record(self.clock.now(), token_snapshot.total.blended_total());
+            token_snapshot.per_minute = self.token_rate.per_minute();
             self.snapshot.tokens = Some(token_snapshot);
             self.snapshot.context = context_snapshot;
+            self.track_context_level();
         } else {
+            self.cost.reset();
+            self.token_rate.reset();
+            self.context_critical = false;
+            self.context_flash_since = None;
+            self.context_critical_notice = None;
+            self.context_critical_noticed = false;
             self.snapshot.tokens = None;
             self.snapshot.context = None;
         }
+        self.snapshot.cost = self.cost.snapshot();
         self.request_redraw();
     }
 
+    /// Note when the context crosses the critical threshold, to flash it
+    /// and tell the transcript.
+    fn track_context_level(&mut self) {
+        let Some(context) = self.snapshot.context.as_mut() else {
+            return;
+        };
+        let critical = self
+            .context_thresholds
+            .level(f64::from(context.percent_remaining))
+            == StatusLineContextLevel::Critical;
+        if critical && !self.context_critical {
+            if self.context_flash {
+                self.context_flash_since = Some(self.clock.now());
+            }
+            if !self.context_critical_noticed {
+                self.context_critical_notice = Some(context.percent_remaining);
+                self.context_critical_noticed = true;
+            }
+        } else if !critical {
+            self.context_flash_since = None;
+        }
+        self.context_critical = critical;
+        context.flash_since = self.context_flash_since;
+    }
+
+    /// Percent of the context window left, the first time the context
+    /// crossed `statusline.context_critical_at` since the last call.
+    pub(crate) fn take_context_critical_notice(&mut self) -> Option<u8> {
+        self.context_critical_notice.take()
+    }
+
     pub(crate) fn set_git_info(&mut self, git: Option<StatusLineGitSnapshot>) {
         self.snapshot.git = git;
         self.request_redraw();
@@ -104,6 +272,7 @@ impl StatusLineState {
     pub(crate) fn set_devspace(&mut self, devspace: Option<String>) {
         self.snapshot.environment.devspace =
             devspace.map(|name| StatusLineDevspaceSnapshot { name });
+        self.snapshot.environment.refreshed_at.devspace = Some(self.clock.now());
         self.request_redraw();
     }
 
@@ -114,20 +283,142 @@ impl StatusLineState {
 
     pub(crate) fn set_aws_profile(&mut self, profile: Option<String>) {
         self.snapshot.environment.aws_profile = profile;
+        self.snapshot.environment.refreshed_at.aws_profile = Some(self.clock.now());
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_gcp_project(&mut self, project: Option<String>) {
+        self.snapshot.environment.gcp_project = project;
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_azure_subscription(&mut self, subscription: Option<String>) {
+        self.snapshot.environment.azure_subscription = subscription;
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_node_version(&mut self, version: Option<String>) {
+        self.snapshot.environment.node_version = version;
         self.request_redraw();
     }
 
     pub(crate) fn set_kubernetes_context(&mut self, context: Option<String>) {
         self.snapshot.environment.kubernetes_context = context;
+        self.snapshot.environment.refreshed_at.kubernetes_context = Some(self.clock.now());
+        self.request_redraw();
+    }
+
+    /// A probe found what it reported last time; see
+    /// [`super::probes::ProbeReport::Unchanged`]. Redraws only when that
+    /// brings back a segment shown as stale.
+    pub(crate) fn mark_probe_fresh(&mut self, kind: &ProbeKind) {
+        let refreshed_at = &mut self.snapshot.environment.refreshed_at;
+        let slot = match kind {
+            ProbeKind::KubeContext => &mut refreshed_at.kubernetes_context,
+            ProbeKind::AwsProfile => &mut refreshed_at.aws_profile,
+            ProbeKind::Git | ProbeKind::Custom(_) => return,
+        };
+        let now = self.clock.now();
+        let was_stale = self
+            .stale_after
+            .zip(*slot)
+            .is_some_and(|(after, at)| now.saturating_duration_since(at) >= after);
+        *slot = Some(now);
+        if was_stale {
+            self.request_redraw();
+        }
+    }
+
+    pub(crate) fn set_terraform_workspace(&mut self, workspace: Option<String>) {
+        self.snapshot.environment.terraform_workspace = workspace;
         self.request_redraw();
     }
 
+    pub(crate) fn set_docker(&mut self, docker: Option<StatusLineDockerSnapshot>) {
+        self.snapshot.environment.docker = docker;
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_system_resources(&mut self, resources: Option<SystemResources>) {
+        self.snapshot.environment.resources = resources;
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_battery(&mut self, battery: Option<Battery>) {
+        self.snapshot.environment.battery = battery;
+        self.request_redraw();
+    }
+
+    pub(crate) fn set_custom_segment(&mut self, name: &str, text: Option<String>) {
+        if let Some((_, value)) = self
+            .snapshot
+            .environment
+            .custom
+            .iter_mut()
+            .find(|(segment, _)| segment == name)
+        {
+            *value = text;
+            self.request_redraw();
+        }
+    }
+
     pub(crate) fn set_session_id(&mut self, session_id: Option<String>) {
-        let _ = session_id;
+        if self.snapshot.session_id != session_id {
+            self.snapshot.session_id = session_id;
+            self.request_redraw();
+        }
+    }
+
+    /// Also keeps the network segment's offline flag in sync.
+    pub(crate) fn set_sandbox_policy(
+        &mut self,
+        sandbox_policy: &SandboxPolicy,
+        approval: AskForApproval,
+    ) {
+        let sandbox = Some(StatusLineSandboxSnapshot {
+            policy: sandbox_policy.to_string(),
+            approval: approval.to_string(),
+            full_access: matches!(sandbox_policy, SandboxPolicy::DangerFullAccess),
+        });
+        if self.snapshot.sandbox != sandbox {
+            self.snapshot.sandbox = sandbox;
+            self.request_redraw();
+        }
+        self.set_network_offline(!sandbox_policy.has_full_network_access());
+    }
+
+    fn set_network_offline(&mut self, offline: bool) {
+        let proxy = self
+            .snapshot
+            .network
+            .as_ref()
+            .and_then(|network| network.proxy.clone());
+        let network =
+            (proxy.is_some() || offline).then_some(StatusLineNetworkSnapshot { proxy, offline });
+        if self.snapshot.network != network {
+            self.snapshot.network = network;
+            self.request_redraw();
+        }
+    }
+
+    /// Counts the configured MCP servers by state, from the latest
+    /// `McpServerStatus` event.
+    pub(crate) fn set_mcp_status(&mut self, servers: &HashMap<String, McpServerState>) {
+        let count = |state: McpServerState| servers.values().filter(|s| **s == state).count();
+        let mcp = (!servers.is_empty()).then(|| StatusLineMcpSnapshot {
+            configured: servers.len(),
+            enabled: servers.len() - count(McpServerState::Disabled),
+            connected: count(McpServerState::Connected),
+            crashed: count(McpServerState::Crashed),
+        });
+        if self.snapshot.mcp != mcp {
+            self.snapshot.mcp = mcp;
+            self.request_redraw();
+        }
     }
 
     pub(crate) fn set_queued_messages(&mut self, messages: Vec<String>) {
-        self.queued_messages = messages;
+        self.queued_messages = messages.into();
         if let Some(run_state) = self.snapshot.run_state.as_mut() {
             run_state.queued_messages = self.queued_messages.clone();
         }
@@ -138,7 +429,7 @@ impl StatusLineState {
         if let Some(run_state) = self.snapshot.run_state.as_mut() {
             if run_state.label != header {
                 run_state.label = header.to_string();
-                run_state.status_changed_at = Instant::now();
+                run_state.status_changed_at = self.clock.now();
                 self.request_redraw();
             }
         } else {
@@ -146,7 +437,7 @@ impl StatusLineState {
                 label: header.to_string(),
                 show_interrupt_hint: self.esc_hint,
                 queued_messages: self.queued_messages.clone(),
-                status_changed_at: Instant::now(),
+                status_changed_at: self.clock.now(),
                 ..StatusLineRunState::default()
             });
             self.request_redraw();
@@ -159,6 +450,8 @@ impl StatusLineState {
             timer: Some(RunTimerSnapshot {
                 elapsed_running: Duration::ZERO,
                 last_resume_at: None,
+                elapsed_paused: Duration::ZERO,
+                last_pause_at: None,
                 is_paused: true,
             }),
             queued_messages: self.queued_messages.clone(),
@@ -171,11 +464,13 @@ impl StatusLineState {
 
     pub(crate) fn start_task(&mut self, header: impl Into<String>) {
         let header = header.into();
-        let now = Instant::now();
+        let now = self.clock.now();
         match self.run_timer.as_mut() {
             Some(timer) => timer.resume(now),
             None => self.run_timer = Some(RunTimer::new(now)),
         }
+        self.cost.start_turn();
+        self.snapshot.cost = self.cost.snapshot();
         let mut run_state = self.snapshot.run_state.clone().unwrap_or_default();
         run_state.label = header;
         run_state.show_interrupt_hint = self.esc_hint;
@@ -186,7 +481,7 @@ impl StatusLineState {
     }
 
     pub(crate) fn complete_task(&mut self) {
-        let now = Instant::now();
+        let now = self.clock.now();
         if let Some(timer) = self.run_timer.as_mut() {
             timer.pause(now);
         }
@@ -197,14 +492,26 @@ impl StatusLineState {
 
     pub(crate) fn resume_timer(&mut self) {
         if let Some(timer) = self.run_timer.as_mut() {
-            timer.resume(Instant::now());
+            timer.resume(self.clock.now());
             self.request_redraw();
         }
     }
 
+    /// Count down `cooldown` in the status line, e.g. while a rate-limited
+    /// request waits to be retried.
+    pub(crate) fn set_rate_limit_cooldown(&mut self, cooldown: Duration) {
+        self.rate_limited_until = Some(self.clock.now() + cooldown);
+        self.request_redraw();
+    }
+
+    /// Estimated spend, for `/status`; `None` when the model has no price.
+    pub(crate) fn cost(&self) -> Option<StatusLineCostSnapshot> {
+        self.snapshot.cost
+    }
+
     pub(crate) fn elapsed_seconds(&self) -> Option<u64> {
         let timer = self.run_timer.as_ref()?;
-        Some(timer.snapshot(Instant::now()).elapsed_running.as_secs())
+        Some(timer.elapsed(self.clock.now()).as_secs())
     }
 
     pub(crate) fn snapshot_for_render(&self, now: Instant) -> StatusLineSnapshot {
@@ -212,87 +519,431 @@ impl StatusLineState {
         if let (Some(run_state), Some(timer)) =
             (snapshot.run_state.as_mut(), self.run_timer.as_ref())
         {
-            run_state.timer = Some(timer.snapshot(now));
-            run_state.spinner_started_at = Some(timer.spinner_started_at);
+            run_state.timer = Some(timer.snapshot());
+            run_state.spinner_started_at = Some(timer.spinner_started_at());
             run_state.queued_messages = self.queued_messages.clone();
             run_state.show_interrupt_hint = self.esc_hint;
         }
-        let timer_active = self
-            .run_timer
-            .as_ref()
-            .map(|timer| !timer.is_paused)
-            .unwrap_or(false);
-        if timer_active {
-            self.frame_requester
-                .schedule_frame_in(Duration::from_millis(48));
-        } else if let Some(run_state) = snapshot.run_state.as_ref()
-            && UnicodeWidthStr::width(run_state.label.as_str()) > STATUS_CAPSULE_TEXT_WIDTH
-        {
-            self.frame_requester
-                .schedule_frame_in(Duration::from_millis(MARQUEE_STEP_MS));
+        snapshot.rate_limit_cooldown = self
+            .rate_limited_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|remaining| !remaining.is_zero());
+        if self.show_clock {
+            snapshot.environment.clock = Some(self.clock.local_now().format("%H:%M").to_string());
         }
+        self.schedule_next_change(now);
         snapshot
     }
 
+    /// The snapshot the next frame renders, as pretty-printed JSON; see
+    /// `/statusline dump`.
+    pub(crate) fn dump_json(&self) -> serde_json::Result<String> {
+        let now = self.clock.now();
+        serde_json::to_string_pretty(&self.snapshot_for_render(now).to_json(now)?)
+    }
+
     pub(crate) fn render_line(&self, width: u16) -> Line<'static> {
-        let now = Instant::now();
+        let now = self.clock.now();
         let snapshot = self.snapshot_for_render(now);
         self.renderer.render(&snapshot, width, now)
     }
 
-    fn request_redraw(&self) {
-        self.frame_requester.schedule_frame();
+    /// The summary for the terminal title, when `statusline.terminal_title`
+    /// is on.
+    pub(crate) fn terminal_title(&self) -> Option<String> {
+        if !self.terminal_title {
+            return None;
+        }
+        let now = self.clock.now();
+        let snapshot = self.snapshot_for_render(now);
+        Some(self.renderer.title(&snapshot, now))
     }
-}
 
-#[derive(Debug)]
-struct RunTimer {
-    elapsed_running: Duration,
-    last_resume_at: Option<Instant>,
-    is_paused: bool,
-    spinner_started_at: Instant,
-}
+    /// Rows the status line takes.
+    pub(crate) fn height(&self) -> u16 {
+        self.lines
+    }
 
-impl RunTimer {
-    fn new(now: Instant) -> Self {
-        Self {
-            elapsed_running: Duration::ZERO,
-            last_resume_at: Some(now),
-            is_paused: false,
-            spinner_started_at: now,
+    /// The status line as [`Self::height`] rows, with where their clickable
+    /// segments are; `statusline.command` output has none.
+    ///
+    /// Rows are cached by width, revision and what the time-varying segments
+    /// show, so frames drawn for other reasons (typing, the transcript
+    /// scrolling) reuse them.
+    pub(crate) fn render_rows(&self, width: u16) -> Vec<StatusLineRow> {
+        let now = self.clock.now();
+        // `statusline.command` output arrives on its own schedule, so it is
+        // never cached.
+        let key = self.command.is_none().then(|| RenderKey {
+            width,
+            revision: self.revision,
+            ticks: self.ticks(now).0,
+        });
+        if let Some(key) = key.as_ref()
+            && let Some((cached_key, rows)) = self.render_cache.borrow().as_ref()
+            && cached_key == key
+        {
+            self.schedule_next_change(now);
+            return rows.clone();
         }
+        let snapshot = self.snapshot_for_render(now);
+        if let Some(lines) = self.command.as_ref().and_then(|command| {
+            let input = snapshot_json(&snapshot, width, now).ok()?.to_string();
+            command.lines(input, &self.cwd, self.lines, width)
+        }) {
+            return lines
+                .into_iter()
+                .map(|line| StatusLineRow {
+                    line,
+                    hits: Vec::new(),
+                })
+                .collect();
+        }
+        let rows: Vec<StatusLineRow> = if self.lines == 2 {
+            self.renderer.render_two_rows(&snapshot, width, now).into()
+        } else {
+            vec![self.renderer.render_row(&snapshot, width, now)]
+        };
+        if let Some(key) = key {
+            *self.render_cache.borrow_mut() = Some((key, rows.clone()));
+        }
+        rows
     }
 
-    fn resume(&mut self, now: Instant) {
-        if self.is_paused {
-            self.last_resume_at = Some(now);
-            self.is_paused = false;
+    /// What the time-varying segments show at `now`, and when the first of
+    /// them changes next. The spinner, timer, marquee and countdown only
+    /// count when the line shows the run state.
+    fn ticks(&self, now: Instant) -> (Ticks, Option<Instant>) {
+        let mut ticks = Ticks::default();
+        let mut next: Vec<Instant> = Vec::new();
+        if self.command.is_some() || self.renderer.shows_status() {
+            if let Some(timer) = self.run_timer.as_ref() {
+                let (step, step_ends) = step_at(
+                    timer.spinner_started_at(),
+                    self.renderer.spinner_interval(),
+                    now,
+                );
+                let elapsed = timer.elapsed(now);
+                ticks.spinner_step = Some(step);
+                ticks.timer_secs = Some((elapsed.as_secs(), timer.paused(now).as_secs()));
+                // A paused run keeps its frame until something else redraws.
+                if !timer.is_paused() {
+                    next.push(step_ends);
+                    let into_second = Duration::new(0, elapsed.subsec_nanos());
+                    next.push(now + Duration::from_secs(1).saturating_sub(into_second));
+                }
+            }
+            if let Some(run_state) = self.snapshot.run_state.as_ref()
+                && UnicodeWidthStr::width(run_state.label.as_str()) > STATUS_CAPSULE_TEXT_WIDTH
+            {
+                let (step, step_ends) = step_at(
+                    run_state.status_changed_at,
+                    Duration::from_millis(MARQUEE_STEP_MS),
+                    now,
+                );
+                ticks.marquee_step = Some(step);
+                next.push(step_ends);
+            }
+            // The countdown ticks also while idle after a turn ended on a
+            // usage limit.
+            if let Some(until) = self.rate_limited_until
+                && until > now
+            {
+                let secs = (until - now).as_millis().div_ceil(1000);
+                ticks.cooldown_secs = Some(secs);
+                let secs_left = u64::try_from(secs.saturating_sub(1)).unwrap_or(u64::MAX);
+                next.push(
+                    until
+                        .checked_sub(Duration::from_secs(secs_left))
+                        .unwrap_or(now),
+                );
+            }
+        }
+        // Flash the context after it crossed the critical threshold.
+        if let Some(since) = self.context_flash_since
+            && now.saturating_duration_since(since) < CONTEXT_FLASH_DURATION
+        {
+            let (step, step_ends) = step_at(since, CONTEXT_FLASH_INTERVAL, now);
+            ticks.flash_step = Some(step);
+            next.push(step_ends);
         }
+        // Redraw when a probed segment goes stale.
+        ticks.stale_at = self.stale_after.and_then(|after| {
+            self.snapshot
+                .environment
+                .refreshed_at
+                .next_stale_at(after, now)
+        });
+        next.extend(ticks.stale_at);
+        if self.show_clock {
+            let local = self.clock.local_now();
+            ticks.clock_minute = Some((local.hour(), local.minute()));
+            // Redraw when the minute turns over, even with nothing else going on.
+            let into_minute = Duration::new(u64::from(local.second()), local.nanosecond());
+            next.push(now + Duration::from_secs(60).saturating_sub(into_minute));
+        }
+        (ticks, next.into_iter().min())
     }
 
-    fn pause(&mut self, now: Instant) {
-        if self.is_paused {
-            return;
+    /// One frame for when the line next changes by itself, if it does.
+    fn schedule_next_change(&self, now: Instant) {
+        if let (_, Some(at)) = self.ticks(now) {
+            self.frame_requester
+                .schedule_frame_in(at.saturating_duration_since(now));
         }
-        if let Some(last) = self.last_resume_at {
-            self.elapsed_running += now.saturating_duration_since(last);
+    }
+
+    fn request_redraw(&mut self) {
+        self.revision = self.revision.wrapping_add(1);
+        self.frame_requester.schedule_frame();
+    }
+}
+
+/// What the cached rows were rendered from.
+#[derive(Debug, PartialEq, Eq)]
+struct RenderKey {
+    width: u16,
+    revision: u64,
+    ticks: Ticks,
+}
+
+/// What the time-varying segments show at one instant; `None` for those
+/// not shown.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Ticks {
+    spinner_step: Option<u128>,
+    /// Running and paused seconds.
+    timer_secs: Option<(u64, u64)>,
+    marquee_step: Option<u128>,
+    cooldown_secs: Option<u128>,
+    flash_step: Option<u128>,
+    stale_at: Option<Instant>,
+    clock_minute: Option<(u32, u32)>,
+}
+
+/// The step of an animation started at `since` with steps of `interval`
+/// at `now`, and when that step ends.
+fn step_at(since: Instant, interval: Duration, now: Instant) -> (u128, Instant) {
+    let interval_ms = interval.as_millis().max(1);
+    let step = now.saturating_duration_since(since).as_millis() / interval_ms;
+    let ends_ms = u64::try_from((step + 1) * interval_ms).unwrap_or(u64::MAX);
+    let ends = since
+        .checked_add(Duration::from_millis(ends_ms))
+        .unwrap_or(now);
+    (step, ends)
+}
+
+/// `statusline.style`, except that the powerline style needs truecolor for
+/// its segment backgrounds and falls back to plain without it.
+fn effective_style(configured: StatusLineStyle) -> StatusLineStyle {
+    let truecolor = supports_color::on_cached(supports_color::Stream::Stdout)
+        .is_some_and(|level| level.has_16m);
+    if truecolor {
+        configured
+    } else {
+        StatusLineStyle::Plain
+    }
+}
+
+/// Running cost of the session. Token totals are priced as they grow, so a
+/// model switch only changes the price of tokens used after it.
+#[derive(Debug, Default)]
+struct CostTracker {
+    pricing: Option<ModelPricing>,
+    previous_total: Option<TokenCountSnapshot>,
+    session_usd: f64,
+    turn_started_at_usd: f64,
+}
+
+impl CostTracker {
+    fn record(&mut self, total: &TokenCountSnapshot) {
+        let previous = self.previous_total.take().unwrap_or_default();
+        if let Some(pricing) = self.pricing {
+            self.session_usd += pricing.cost_usd(
+                total.input_tokens.saturating_sub(previous.input_tokens),
+                total
+                    .cached_input_tokens
+                    .saturating_sub(previous.cached_input_tokens),
+                total.output_tokens.saturating_sub(previous.output_tokens),
+            );
         }
-        self.is_paused = true;
+        self.previous_total = Some(total.clone());
+    }
+
+    fn start_turn(&mut self) {
+        self.turn_started_at_usd = self.session_usd;
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            pricing: self.pricing,
+            ..Self::default()
+        };
+    }
+
+    fn snapshot(&self) -> Option<StatusLineCostSnapshot> {
+        self.pricing.is_some().then_some(StatusLineCostSnapshot {
+            last_turn_usd: self.session_usd - self.turn_started_at_usd,
+            session_usd: self.session_usd,
+        })
     }
+}
 
-    fn snapshot(&self, now: Instant) -> RunTimerSnapshot {
-        let mut elapsed = self.elapsed_running;
-        if !self.is_paused
-            && let Some(last) = self.last_resume_at
+/// Most token totals kept for the burn rate.
+const TOKEN_RATE_SAMPLES: usize = 8;
+/// Totals older than this no longer count towards the burn rate.
+const TOKEN_RATE_WINDOW: Duration = Duration::from_secs(5 * 60);
+/// Shortest span the burn rate is taken over, so two updates a moment apart
+/// do not read as a spike.
+const TOKEN_RATE_MIN_SPAN: Duration = Duration::from_secs(10);
+
+/// Rolling tokens per minute, from the totals of successive token updates.
+#[derive(Debug, Default)]
+pub(super) struct TokenRate {
+    /// When each total was seen, oldest first.
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl TokenRate {
+    pub(super) fn record(&mut self, now: Instant, total: u64) {
+        // A smaller total is a new conversation; its rate starts over.
+        if self.samples.back().is_some_and(|&(_, last)| total < last) {
+            self.samples.clear();
+        }
+        if self.samples.len() == TOKEN_RATE_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, total));
+        while self.samples.len() > 1
+            && self
+                .samples
+                .front()
+                .is_some_and(|&(at, _)| now.saturating_duration_since(at) > TOKEN_RATE_WINDOW)
         {
-            elapsed += now.saturating_duration_since(last);
+            self.samples.pop_front();
         }
-        RunTimerSnapshot {
-            elapsed_running: elapsed,
-            last_resume_at: self.last_resume_at,
-            is_paused: self.is_paused,
+    }
+
+    fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Tokens per minute between the oldest and newest totals kept.
+    pub(super) fn per_minute(&self) -> Option<u64> {
+        let &(first_at, first) = self.samples.front()?;
+        let &(last_at, last) = self.samples.back()?;
+        let span = last_at.saturating_duration_since(first_at);
+        if span < TOKEN_RATE_MIN_SPAN {
+            return None;
         }
+        let per_minute = last.saturating_sub(first) as f64 * 60.0 / span.as_secs_f64();
+        Some(per_minute.round() as u64)
+    }
+}
+
+/// Name of the active virtualenv (`VIRTUAL_ENV`) or conda environment
+/// (`CONDA_DEFAULT_ENV`), falling back to a `.venv` in `cwd`, which `uv` and
+/// Poetry use without activating it. `var` looks up environment variables.
+pub(super) fn python_env_name(cwd: &Path, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let var = |key: &str| var(key).filter(|value| !value.trim().is_empty());
+    if let Some(venv) = var("VIRTUAL_ENV") {
+        // Older virtualenvs set the prompt as `(name) `.
+        let prompt = var("VIRTUAL_ENV_PROMPT")
+            .map(|prompt| prompt.trim().trim_matches(['(', ')']).to_string())
+            .filter(|prompt| !prompt.is_empty());
+        return prompt.or_else(|| venv_name(Path::new(&venv)));
+    }
+    if let Some(conda) = var("CONDA_DEFAULT_ENV") {
+        // Environments created with `--prefix` are named by their path.
+        return Path::new(conda.trim())
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+    }
+    let project_venv = cwd.join(".venv");
+    if project_venv.join("pyvenv.cfg").is_file() {
+        return venv_name(&project_venv);
+    }
+    None
+}
+
+/// The Terraform workspace selected in `cwd`, when it has a `.terraform`
+/// directory: `TF_WORKSPACE`, else the one `terraform workspace select` wrote
+/// to `.terraform/environment`, else `default`.
+pub(super) fn terraform_workspace(
+    cwd: &Path,
+    var: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let data_dir = cwd.join(".terraform");
+    if !data_dir.is_dir() {
+        return None;
     }
+    let selected =
+        var("TF_WORKSPACE").or_else(|| std::fs::read_to_string(data_dir.join("environment")).ok());
+    let workspace = selected.as_deref().map(str::trim).unwrap_or_default();
+    Some(if workspace.is_empty() {
+        "default".to_string()
+    } else {
+        workspace.to_string()
+    })
+}
+
+const PROXY_VARS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+];
+
+/// Host and port of the proxy network calls go through, from the first of
+/// `HTTPS_PROXY`, `ALL_PROXY` and `HTTP_PROXY` (either case) that is set.
+/// Credentials in the URL are left out.
+pub(super) fn proxy_host(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let url = PROXY_VARS
+        .iter()
+        .filter_map(|key| var(key))
+        .find(|value| !value.trim().is_empty())?;
+    let url = url.trim();
+    let authority = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or(authority);
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Whether this session was reached over SSH: `SSH_CONNECTION` or `SSH_TTY`
+/// is set.
+pub(super) fn is_ssh_session(var: impl Fn(&str) -> Option<String>) -> bool {
+    ["SSH_CONNECTION", "SSH_TTY"]
+        .into_iter()
+        .filter_map(var)
+        .any(|value| !value.trim().is_empty())
+}
+
+/// The `prompt` from the venv's `pyvenv.cfg`, else its directory name, or the
+/// project's for the conventional `.venv`/`venv`.
+fn venv_name(venv: &Path) -> Option<String> {
+    let prompt = std::fs::read_to_string(venv.join("pyvenv.cfg"))
+        .ok()
+        .and_then(|cfg| {
+            cfg.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                (key.trim() == "prompt").then(|| value.trim().trim_matches(['\'', '"']).to_string())
+            })
+        })
+        .filter(|prompt| !prompt.is_empty());
+    if prompt.is_some() {
+        return prompt;
+    }
+    let name = venv.file_name()?.to_string_lossy();
+    let dir = if matches!(name.as_ref(), ".venv" | "venv") {
+        venv.parent()?.file_name()?
+    } else {
+        venv.file_name()?
+    };
+    Some(dir.to_string_lossy().into_owned())
 }
 
 fn reasoning_detail(effort: Option<ReasoningEffort>) -> Option<String> {
@@ -325,12 +976,14 @@ fn token_snapshot_from_info(
             output_tokens: last.output_tokens,
             reasoning_output_tokens: last.reasoning_output_tokens,
         }),
+        per_minute: None,
     };
 
     let context_snapshot = context_window.map(|window| StatusLineContextSnapshot {
         percent_remaining: total.percent_of_context_window_remaining(window),
         tokens_in_context: total.tokens_in_context_window(),
         window,
+        flash_since: None,
     });
 
     (token_snapshot, context_snapshot)
@@ -0,0 +1,269 @@
+//! Status line colors: a built-in theme picked with `statusline.theme`, with
+//! the model, working directory, git and context bar colors overridable
+//! under `[statusline.colors]` and the context bar's thresholds set by
+//! `statusline.context_warn_at` and `statusline.context_critical_at`.
+
+use codex_core::config_types::StatusLineColors;
+use codex_core::config_types::StatusLineConfig;
+use codex_core::config_types::StatusLineContextLevel;
+use codex_core::config_types::StatusLineContextThresholds;
+use codex_core::config_types::StatusLineThemeName;
+use ratatui::style::Color;
+
+use super::palette;
+
+/// Fill of the context bar and the track behind it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct BarColors {
+    pub fill: Color,
+    pub track: Color,
+}
+
+/// Segment colors by hue, for the segments without a color of their own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Accents {
+    pub lavender: Color,
+    pub sky: Color,
+    pub mauve: Color,
+    pub peach: Color,
+    pub green: Color,
+    pub yellow: Color,
+    pub red: Color,
+    pub rosewater: Color,
+    pub teal: Color,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct StatusLineTheme {
+    /// Text on segment backgrounds.
+    pub text: Color,
+    /// Dim text, e.g. the queued message preview.
+    pub subtext: Color,
+    pub cwd: Color,
+    pub model: Color,
+    pub git_clean: Color,
+    pub git_dirty: Color,
+    pub git_conflict: Color,
+    /// Context bar at each [`StatusLineContextLevel`].
+    pub context: [BarColors; 4],
+    pub context_thresholds: StatusLineContextThresholds,
+    pub accents: Accents,
+}
+
+impl Default for StatusLineTheme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl StatusLineTheme {
+    /// `statusline.theme` with `statusline.colors` applied.
+    pub(crate) fn from_config(config: &StatusLineConfig) -> Self {
+        let mut theme = match config.theme {
+            StatusLineThemeName::Dark => Self::dark(),
+            StatusLineThemeName::Light => Self::light(),
+            StatusLineThemeName::Solarized => Self::solarized(),
+        };
+        theme.apply(&config.colors);
+        theme.context_thresholds = config.context_thresholds;
+        theme
+    }
+
+    pub(crate) fn dark() -> Self {
+        let accents = Accents {
+            lavender: palette::LAVENDER,
+            sky: palette::SKY,
+            mauve: palette::MAUVE,
+            peach: palette::PEACH,
+            green: palette::GREEN,
+            yellow: palette::YELLOW,
+            red: palette::RED,
+            rosewater: palette::ROSEWATER,
+            teal: palette::TEAL,
+        };
+        Self {
+            text: palette::BASE,
+            subtext: palette::SUBTEXT0,
+            cwd: accents.lavender,
+            model: accents.sky,
+            git_clean: accents.sky,
+            git_dirty: accents.sky,
+            git_conflict: accents.red,
+            context: [
+                BarColors {
+                    fill: palette::GREEN,
+                    track: palette::GREEN_LIGHT,
+                },
+                BarColors {
+                    fill: palette::YELLOW,
+                    track: palette::YELLOW_LIGHT,
+                },
+                BarColors {
+                    fill: palette::PEACH,
+                    track: palette::PEACH_LIGHT,
+                },
+                BarColors {
+                    fill: palette::RED,
+                    track: palette::RED_LIGHT,
+                },
+            ],
+            context_thresholds: StatusLineContextThresholds::default(),
+            accents,
+        }
+    }
+
+    #[allow(clippy::disallowed_methods)]
+    pub(crate) fn light() -> Self {
+        Self::from_accents(
+            Color::Rgb(239, 241, 245),
+            Color::Rgb(108, 111, 133),
+            Accents {
+                lavender: Color::Rgb(114, 135, 253),
+                sky: Color::Rgb(4, 165, 229),
+                mauve: Color::Rgb(136, 57, 239),
+                peach: Color::Rgb(254, 100, 11),
+                green: Color::Rgb(64, 160, 43),
+                yellow: Color::Rgb(223, 142, 29),
+                red: Color::Rgb(210, 15, 57),
+                rosewater: Color::Rgb(220, 138, 120),
+                teal: Color::Rgb(23, 146, 153),
+            },
+        )
+    }
+
+    #[allow(clippy::disallowed_methods)]
+    pub(crate) fn solarized() -> Self {
+        Self::from_accents(
+            Color::Rgb(253, 246, 227),
+            Color::Rgb(147, 161, 161),
+            Accents {
+                lavender: Color::Rgb(108, 113, 196),
+                sky: Color::Rgb(38, 139, 210),
+                mauve: Color::Rgb(211, 54, 130),
+                peach: Color::Rgb(203, 75, 22),
+                green: Color::Rgb(133, 153, 0),
+                yellow: Color::Rgb(181, 137, 0),
+                red: Color::Rgb(220, 50, 47),
+                rosewater: Color::Rgb(88, 110, 117),
+                teal: Color::Rgb(42, 161, 152),
+            },
+        )
+    }
+
+    fn from_accents(text: Color, subtext: Color, accents: Accents) -> Self {
+        let bar = |fill| BarColors {
+            fill,
+            track: track_for(fill, text),
+        };
+        Self {
+            text,
+            subtext,
+            cwd: accents.lavender,
+            model: accents.sky,
+            git_clean: accents.sky,
+            git_dirty: accents.sky,
+            git_conflict: accents.red,
+            context: [
+                bar(accents.green),
+                bar(accents.yellow),
+                bar(accents.peach),
+                bar(accents.red),
+            ],
+            context_thresholds: StatusLineContextThresholds::default(),
+            accents,
+        }
+    }
+
+    fn apply(&mut self, colors: &StatusLineColors) {
+        let overrides = [
+            (&mut self.model, colors.model),
+            (&mut self.cwd, colors.cwd),
+            (&mut self.git_clean, colors.git_clean),
+            (&mut self.git_dirty, colors.git_dirty),
+            (&mut self.git_conflict, colors.git_conflict),
+        ];
+        for (color, rgb) in overrides {
+            if let Some(rgb) = rgb {
+                *color = rgb_color(rgb);
+            }
+        }
+        let levels = [
+            colors.context_ok,
+            colors.context_warn,
+            colors.context_high,
+            colors.context_critical,
+        ];
+        for (bar, rgb) in self.context.iter_mut().zip(levels) {
+            if let Some(rgb) = rgb {
+                let fill = rgb_color(rgb);
+                *bar = BarColors {
+                    fill,
+                    track: track_for(fill, self.text),
+                };
+            }
+        }
+    }
+
+    /// The context level with `percent_used` of the window used.
+    pub(crate) fn context_level(&self, percent_used: f64) -> StatusLineContextLevel {
+        self.context_thresholds.level(100.0 - percent_used)
+    }
+
+    /// Context bar colors for `percent_used` of the window.
+    pub(crate) fn context_bar(&self, percent_used: f64) -> BarColors {
+        let [ok, warn, high, critical] = self.context;
+        match self.context_level(percent_used) {
+            StatusLineContextLevel::Ok => ok,
+            StatusLineContextLevel::Warn => warn,
+            StatusLineContextLevel::High => high,
+            StatusLineContextLevel::Critical => critical,
+        }
+    }
+}
+
+#[allow(clippy::disallowed_methods)]
+fn rgb_color([r, g, b]: [u8; 3]) -> Color {
+    Color::Rgb(r, g, b)
+}
+
+/// The bar's track: its fill halfway towards the segment text color.
+fn track_for(fill: Color, text: Color) -> Color {
+    match (fill, text) {
+        (Color::Rgb(r, g, b), Color::Rgb(tr, tg, tb)) => {
+            let mix = |a: u8, b: u8| ((u16::from(a) + u16::from(b)) / 2) as u8;
+            rgb_color([mix(r, tr), mix(g, tg), mix(b, tb)])
+        }
+        _ => fill,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config_types::StatusLineToml;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn colors_override_the_theme_and_derive_the_bar_track() {
+        let mut config = StatusLineConfig::from(StatusLineToml {
+            theme: Some(StatusLineThemeName::Light),
+            ..StatusLineToml::default()
+        });
+        config.colors.git_dirty = Some([250, 179, 135]);
+        config.colors.context_warn = Some([200, 100, 0]);
+        let theme = StatusLineTheme::from_config(&config);
+
+        let light = StatusLineTheme::light();
+        assert_eq!(theme.model, light.model);
+        assert_eq!(theme.git_clean, light.git_clean);
+        assert_eq!(theme.git_dirty, rgb_color([250, 179, 135]));
+        assert_eq!(
+            theme.context_bar(70.0),
+            BarColors {
+                fill: rgb_color([200, 100, 0]),
+                track: rgb_color([219, 170, 122]),
+            }
+        );
+        assert_eq!(theme.context_bar(95.0), light.context[3]);
+    }
+}
@@ -1,6 +1,9 @@
+use std::fs::File;
+use std::io::BufWriter;
 use std::io::IsTerminal;
 use std::io::Result;
 use std::io::Stdout;
+use std::io::Write;
 use std::io::stdout;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -9,6 +12,7 @@ use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU8;
 #[cfg(unix)]
 use std::sync::atomic::AtomicU16;
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 use std::time::Instant;
@@ -19,15 +23,20 @@ use crossterm::SynchronizedUpdate;
 use crossterm::cursor::MoveTo;
 use crossterm::event::DisableBracketedPaste;
 use crossterm::event::DisableFocusChange;
+use crossterm::event::DisableMouseCapture;
 use crossterm::event::EnableBracketedPaste;
 use crossterm::event::EnableFocusChange;
+use crossterm::event::EnableMouseCapture;
 use crossterm::event::Event;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use crossterm::event::PopKeyboardEnhancementFlags;
 use crossterm::event::PushKeyboardEnhancementFlags;
 use crossterm::terminal::EnterAlternateScreen;
 use crossterm::terminal::LeaveAlternateScreen;
+use crossterm::terminal::SetTitle;
 use crossterm::terminal::supports_keyboard_enhancement;
 use ratatui::backend::Backend;
 use ratatui::backend::CrosstermBackend;
@@ -37,13 +46,51 @@ use ratatui::crossterm::terminal::enable_raw_mode;
 use ratatui::layout::Offset;
 use ratatui::text::Line;
 
+use crate::asciicast::AsciicastRecorder;
+use crate::clock::Clock;
+use crate::clock::SystemClock;
 use crate::custom_terminal;
 use crate::custom_terminal::Terminal as CustomTerminal;
+use crate::idle::IdleTracker;
 use tokio::select;
 use tokio_stream::Stream;
 
 /// A type alias for the terminal type used in this application
-pub type Terminal = CustomTerminal<CrosstermBackend<Stdout>>;
+pub type Terminal = CustomTerminal<CrosstermBackend<TerminalOut>>;
+
+/// Stdout, with everything written to it optionally copied into an
+/// asciicast recording (`codex replay --record`).
+pub struct TerminalOut {
+    stdout: Stdout,
+    recorder: Option<AsciicastRecorder<BufWriter<File>>>,
+}
+
+impl TerminalOut {
+    pub(crate) fn record(&mut self, recorder: AsciicastRecorder<BufWriter<File>>) {
+        self.recorder = Some(recorder);
+    }
+}
+
+impl Write for TerminalOut {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let written = self.stdout.write(buf)?;
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(&buf[..written])?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.flush()?;
+        }
+        self.stdout.flush()
+    }
+}
+
+/// `tui.mouse`: set once the mouse is captured, so [`set_modes`] captures it
+/// again after a suspend and [`restore`] releases it.
+static MOUSE_CAPTURE: AtomicBool = AtomicBool::new(false);
 
 pub fn set_modes() -> Result<()> {
     execute!(stdout(), EnableBracketedPaste)?;
@@ -65,6 +112,9 @@ pub fn set_modes() -> Result<()> {
     );
 
     let _ = execute!(stdout(), EnableFocusChange);
+    if MOUSE_CAPTURE.load(Ordering::Relaxed) {
+        let _ = execute!(stdout(), EnableMouseCapture);
+    }
     Ok(())
 }
 
@@ -117,6 +167,9 @@ pub fn restore() -> Result<()> {
     let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
     execute!(stdout(), DisableBracketedPaste)?;
     let _ = execute!(stdout(), DisableFocusChange);
+    if MOUSE_CAPTURE.load(Ordering::Relaxed) {
+        let _ = execute!(stdout(), DisableMouseCapture);
+    }
     disable_raw_mode()?;
     let _ = execute!(stdout(), crossterm::cursor::Show);
     Ok(())
@@ -131,7 +184,10 @@ pub fn init() -> Result<Terminal> {
 
     set_panic_hook();
 
-    let backend = CrosstermBackend::new(stdout());
+    let backend = CrosstermBackend::new(TerminalOut {
+        stdout: stdout(),
+        recorder: None,
+    });
     let tui = CustomTerminal::with_options(backend)?;
     Ok(tui)
 }
@@ -148,11 +204,16 @@ fn set_panic_hook() {
 pub enum TuiEvent {
     Key(KeyEvent),
     Paste(String),
+    /// Only with `tui.mouse` on.
+    Mouse(MouseEvent),
     Draw,
 }
 
 pub struct Tui {
     frame_schedule_tx: tokio::sync::mpsc::UnboundedSender<Instant>,
+    frames_scheduled: Arc<AtomicU64>,
+    clock: Arc<dyn Clock>,
+    idle: Arc<IdleTracker>,
     draw_tx: tokio::sync::broadcast::Sender<()>,
     pub(crate) terminal: Terminal,
     pending_history_lines: Vec<Line<'static>>,
@@ -166,6 +227,8 @@ pub struct Tui {
     // True when terminal/tab is focused; updated internally from crossterm events
     terminal_focused: Arc<AtomicBool>,
     enhanced_keys_supported: bool,
+    /// Last title set with [`Tui::set_title`]; `None` leaves the terminal's own.
+    title: Option<String>,
 }
 
 #[cfg(unix)]
@@ -195,25 +258,65 @@ fn take_resume_action(pending: &AtomicU8) -> ResumeAction {
 #[derive(Clone, Debug)]
 pub struct FrameRequester {
     frame_schedule_tx: tokio::sync::mpsc::UnboundedSender<Instant>,
+    /// Requests made through any requester, for `/debug perf`.
+    frames_scheduled: Arc<AtomicU64>,
+    /// Deadlines are taken from it; widgets that schedule frames share it.
+    clock: Arc<dyn Clock>,
+    idle: Arc<IdleTracker>,
 }
 impl FrameRequester {
     pub fn schedule_frame(&self) {
-        let _ = self.frame_schedule_tx.send(Instant::now());
+        self.frames_scheduled.fetch_add(1, Ordering::Relaxed);
+        let _ = self.frame_schedule_tx.send(self.clock.now());
     }
+    /// A frame after `dur`, unless the TUI is idle; see [`crate::idle`].
     pub fn schedule_frame_in(&self, dur: Duration) {
-        let _ = self.frame_schedule_tx.send(Instant::now() + dur);
+        if self.idle.is_idle() {
+            return;
+        }
+        self.frames_scheduled.fetch_add(1, Ordering::Relaxed);
+        let _ = self.frame_schedule_tx.send(self.clock.now() + dur);
+    }
+
+    /// Keeps delayed frames coming while a task runs.
+    pub(crate) fn set_task_running(&self, running: bool) {
+        self.idle.set_task_running(running);
+    }
+
+    pub(crate) fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
     }
 }
 
-#[cfg(test)]
 impl FrameRequester {
-    /// Create a no-op frame requester for tests.
+    /// Create a no-op frame requester, for tests, benchmarks and headless
+    /// status lines.
     pub(crate) fn test_dummy() -> Self {
         let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         FrameRequester {
             frame_schedule_tx: tx,
+            frames_scheduled: Arc::new(AtomicU64::new(0)),
+            idle: Arc::new(IdleTracker::new(clock.clone())),
+            clock,
         }
     }
+
+    /// A frame requester on `clock` whose frame deadlines arrive on the
+    /// returned receiver.
+    #[cfg(test)]
+    pub(crate) fn test_with_clock(
+        clock: Arc<dyn Clock>,
+    ) -> (Self, tokio::sync::mpsc::UnboundedReceiver<Instant>) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let requester = FrameRequester {
+            frame_schedule_tx: tx,
+            frames_scheduled: Arc::new(AtomicU64::new(0)),
+            idle: Arc::new(IdleTracker::new(clock.clone())),
+            clock,
+        };
+        (requester, rx)
+    }
 }
 
 impl Tui {
@@ -280,8 +383,12 @@ impl Tui {
         let _ = crate::terminal_palette::terminal_palette();
         let _ = crate::terminal_palette::default_colors();
 
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         Self {
             frame_schedule_tx,
+            frames_scheduled: Arc::new(AtomicU64::new(0)),
+            idle: Arc::new(IdleTracker::new(clock.clone())),
+            clock,
             draw_tx,
             terminal,
             pending_history_lines: vec![],
@@ -293,13 +400,54 @@ impl Tui {
             alt_screen_active: Arc::new(AtomicBool::new(false)),
             terminal_focused: Arc::new(AtomicBool::new(true)),
             enhanced_keys_supported,
+            title: None,
         }
     }
 
     pub fn frame_requester(&self) -> FrameRequester {
         FrameRequester {
             frame_schedule_tx: self.frame_schedule_tx.clone(),
+            frames_scheduled: self.frames_scheduled.clone(),
+            clock: self.clock.clone(),
+            idle: self.idle.clone(),
+        }
+    }
+
+    /// `tui.idle_after_secs`; see [`crate::idle`].
+    pub(crate) fn set_idle_after(&self, idle_after: Option<Duration>) {
+        self.idle.set_idle_after(idle_after);
+    }
+
+    /// `tui.mouse`: report clicks and the wheel as [`TuiEvent::Mouse`]
+    /// instead of leaving them to the terminal.
+    pub(crate) fn enable_mouse_capture(&self) {
+        MOUSE_CAPTURE.store(true, Ordering::Relaxed);
+        let _ = execute!(stdout(), EnableMouseCapture);
+    }
+
+    /// Set the terminal title (OSC 0), saving the terminal's own title on
+    /// the first call for [`Self::restore_title`].
+    pub(crate) fn set_title(&mut self, title: &str) {
+        if self.title.as_deref() == Some(title) {
+            return;
+        }
+        if self.title.is_none() {
+            let _ = execute!(stdout(), SaveTitle);
         }
+        let _ = execute!(stdout(), SetTitle(title));
+        self.title = Some(title.to_string());
+    }
+
+    /// Put back the title saved by [`Self::set_title`].
+    pub(crate) fn restore_title(&mut self) {
+        if self.title.take().is_some() {
+            let _ = execute!(stdout(), RestoreTitle);
+        }
+    }
+
+    /// Frames requested so far, whether or not they were coalesced.
+    pub fn frames_scheduled(&self) -> u64 {
+        self.frames_scheduled.load(Ordering::Relaxed)
     }
 
     pub fn enhanced_keys_supported(&self) -> bool {
@@ -317,10 +465,19 @@ impl Tui {
         #[cfg(unix)]
         let suspend_cursor_y = self.suspend_cursor_y.clone();
         let terminal_focused = self.terminal_focused.clone();
+        let idle = self.idle.clone();
         let event_stream = async_stream::stream! {
             loop {
                 select! {
                     Some(Ok(event)) = crossterm_events.next() => {
+                        // The mouse passing over the terminal is not input.
+                        if !matches!(
+                            event,
+                            Event::FocusLost
+                                | Event::Mouse(MouseEvent { kind: MouseEventKind::Moved, .. })
+                        ) {
+                            idle.record_activity();
+                        }
                         match event {
                             crossterm::event::Event::Key(key_event) => {
                                 #[cfg(unix)]
@@ -360,6 +517,9 @@ impl Tui {
                             Event::Paste(pasted) => {
                                 yield TuiEvent::Paste(pasted);
                             }
+                            Event::Mouse(mouse_event) if mouse_event.kind != MouseEventKind::Moved => {
+                                yield TuiEvent::Mouse(mouse_event);
+                            }
                             Event::FocusGained => {
                                 terminal_focused.store(true, Ordering::Relaxed);
                                 crate::terminal_palette::requery_default_colors();
@@ -572,6 +732,50 @@ impl Tui {
     }
 }
 
+/// Pushes the window title onto the terminal's title stack (XTWINOPS 22).
+#[derive(Debug, Clone, Copy)]
+struct SaveTitle;
+
+impl Command for SaveTitle {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(f, "\x1b[22;0t")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute SaveTitle using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+/// Pops the title saved by [`SaveTitle`] (XTWINOPS 23).
+#[derive(Debug, Clone, Copy)]
+struct RestoreTitle;
+
+impl Command for RestoreTitle {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(f, "\x1b[23;0t")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> std::io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute RestoreTitle using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
 /// Command that emits an OSC 9 desktop notification with a message.
 #[derive(Debug, Clone)]
 pub struct PostNotification(pub String);
@@ -57,15 +57,27 @@ export type ItemCompletedEvent = {
   item: ThreadItem;
 };
 
+/** What failed; `codex exec` exits with a status for each. */
+export type ThreadErrorCode =
+  | "auth"
+  | "network"
+  | "provider"
+  | "sandbox"
+  | "patch"
+  | "config"
+  | "internal";
+
 /** Fatal error emitted by the stream. */
 export type ThreadError = {
   message: string;
+  code: ThreadErrorCode;
 };
 
 /** Represents an unrecoverable error emitted directly by the event stream. */
 export type ThreadErrorEvent = {
   type: "error";
   message: string;
+  code: ThreadErrorCode;
 };
 
 /** Top-level JSONL events emitted by codex exec. */
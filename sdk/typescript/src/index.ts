@@ -9,6 +9,7 @@ export type {
   ItemCompletedEvent,
   ThreadError,
   ThreadErrorEvent,
+  ThreadErrorCode,
   Usage,
 } from "./events";
 export type {